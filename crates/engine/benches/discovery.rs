@@ -0,0 +1,20 @@
+//! Criterion benches of the discovery hot paths (`cargo bench -p engine`)
+//!
+//! Cases come from `engine::benchmark`, the same ones the `bench` CLI command times.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use engine::benchmark::{bench_cases, BenchConfig};
+
+fn discovery_benches(c: &mut Criterion) {
+    for mut case in bench_cases(&BenchConfig::default()) {
+        let mut group = c.benchmark_group(case.group);
+        if let Some(bars) = case.bars {
+            group.throughput(Throughput::Elements(bars as u64));
+        }
+        group.bench_function(case.label.clone(), |b| b.iter(|| case.run()));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, discovery_benches);
+criterion_main!(benches);
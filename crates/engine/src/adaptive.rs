@@ -0,0 +1,334 @@
+//! Adaptive continuous cycles planned from knowledge-base coverage
+//!
+//! Continuous cycles 0-2 scan fixed grids, so a server restarted on a populated
+//! knowledge base repeats what it already knows. With `adaptive_cycles` those cycles
+//! are planned instead. The params of the latest `COVERAGE_SAMPLE` stored results give
+//! the coverage of each strategy family (`type_tag()`) and of each numeric parameter of
+//! each strategy (`name()`, so the indicators of a combo are told apart), split into
+//! `REGION_BINS` equal regions of the range the candidates and the stored results span. The candidates (the fixed grids of cycles 0-2 and a
+//! mutation of each) that were never tested are ranked by how little their family and
+//! their parameter regions were explored, and `ADAPTIVE_CYCLE_BUDGET` of them are picked
+//! greedily, each pick counting as coverage for the next, so a cycle spreads over the
+//! gaps instead of piling into one.
+
+use std::collections::{HashMap, HashSet};
+
+use tracing::{info, warn};
+
+use crate::discovery::{
+    generate_exploratory_grid, mutate_strategy, DiscoveryStrategyType, StrategyExclusions,
+};
+use crate::local_search::numeric_leaves;
+use crate::store::ResultStore;
+use crate::strategy_validation::retain_valid;
+
+/// Continuous cycles planned when adaptive (the later ones are ML-guided)
+pub const ADAPTIVE_CYCLES: u32 = 3;
+
+/// Strategies of a planned cycle
+pub const ADAPTIVE_CYCLE_BUDGET: usize = 400;
+
+/// Stored results the coverage is computed from, newest first
+pub const COVERAGE_SAMPLE: i64 = 50_000;
+
+/// Phase label of a planned cycle
+pub const ADAPTIVE_PHASE_NAME: &str = "Adaptive Plan";
+
+/// Regions each parameter range is split into
+const REGION_BINS: usize = 8;
+
+/// What coverage looks at in a strategy
+struct Point {
+    family: &'static str,
+    /// Strategy the parameter regions belong to
+    name: String,
+    /// Serialized params, the identity of a tested strategy
+    key: String,
+    /// (JSON pointer, value) of each numeric parameter
+    coords: Vec<(String, f64)>,
+}
+
+impl Point {
+    fn of(strategy: &DiscoveryStrategyType) -> Option<Self> {
+        let value = serde_json::to_value(strategy).ok()?;
+        let mut leaves = Vec::new();
+        numeric_leaves(&value, String::new(), &mut leaves);
+        Some(Self {
+            family: strategy.type_tag(),
+            name: strategy.name().to_string(),
+            key: value.to_string(),
+            coords: leaves
+                .into_iter()
+                .filter_map(|(pointer, n)| Some((pointer, n.as_f64()?)))
+                .collect(),
+        })
+    }
+}
+
+/// Strategies already tested, by family and parameter
+#[derive(Debug, Default)]
+pub struct ParameterCoverage {
+    tested: HashSet<String>,
+    /// Results per family
+    families: HashMap<&'static str, usize>,
+    /// Tested values of each (strategy name, parameter pointer)
+    values: HashMap<(String, String), Vec<f64>>,
+}
+
+impl ParameterCoverage {
+    pub fn from_strategies<'a>(
+        strategies: impl IntoIterator<Item = &'a DiscoveryStrategyType>,
+    ) -> Self {
+        let mut coverage = Self::default();
+        for point in strategies.into_iter().filter_map(Point::of) {
+            *coverage.families.entry(point.family).or_default() += 1;
+            for (pointer, value) in point.coords {
+                coverage
+                    .values
+                    .entry((point.name.clone(), pointer))
+                    .or_default()
+                    .push(value);
+            }
+            coverage.tested.insert(point.key);
+        }
+        coverage
+    }
+
+    /// Coverage of stored `strategy_params`; params that no longer parse are skipped
+    pub fn from_params(params: &[String]) -> Self {
+        let strategies: Vec<DiscoveryStrategyType> = params
+            .iter()
+            .filter_map(|p| serde_json::from_str(p).ok())
+            .collect();
+        Self::from_strategies(&strategies)
+    }
+
+    /// Distinct params tested
+    pub fn len(&self) -> usize {
+        self.tested.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tested.is_empty()
+    }
+
+    pub fn is_tested(&self, strategy: &DiscoveryStrategyType) -> bool {
+        Point::of(strategy).is_some_and(|p| self.tested.contains(&p.key))
+    }
+
+    /// Results of a family (`DiscoveryStrategyType::type_tag()`)
+    pub fn family_results(&self, family: &str) -> usize {
+        self.families.get(family).copied().unwrap_or(0)
+    }
+}
+
+/// Up to `budget` of the untested `candidates`, least explored family and parameter
+/// regions first
+pub fn plan_adaptive_grid(
+    coverage: &ParameterCoverage,
+    candidates: Vec<DiscoveryStrategyType>,
+    budget: usize,
+) -> Vec<DiscoveryStrategyType> {
+    let mut seen = HashSet::new();
+    let pool: Vec<(DiscoveryStrategyType, Point)> = candidates
+        .into_iter()
+        .filter_map(|strategy| {
+            let point = Point::of(&strategy)?;
+            (!coverage.tested.contains(&point.key) && seen.insert(point.key.clone()))
+                .then_some((strategy, point))
+        })
+        .collect();
+
+    // Range of each parameter, over the candidates and the tested values
+    let mut bounds: HashMap<(String, String), (f64, f64)> = HashMap::new();
+    let mut widen = |name: &str, pointer: &str, value: f64| {
+        let range = bounds
+            .entry((name.to_string(), pointer.to_string()))
+            .or_insert((value, value));
+        *range = (range.0.min(value), range.1.max(value));
+    };
+    for (_, point) in &pool {
+        for (pointer, value) in &point.coords {
+            widen(&point.name, pointer, *value);
+        }
+    }
+    for ((name, pointer), values) in &coverage.values {
+        for value in values {
+            widen(name, pointer, *value);
+        }
+    }
+    let region = |name: &str, pointer: &str, value: f64| {
+        let (low, high) = bounds[&(name.to_string(), pointer.to_string())];
+        let bin = if high > low {
+            ((value - low) / (high - low) * REGION_BINS as f64) as usize
+        } else {
+            0
+        };
+        (
+            name.to_string(),
+            pointer.to_string(),
+            bin.min(REGION_BINS - 1),
+        )
+    };
+
+    // Counters of the families and regions, and the ones of each candidate
+    let mut ids: HashMap<(String, String, usize), usize> = HashMap::new();
+    let mut counts: Vec<usize> = Vec::new();
+    let mut id_of = |key: (String, String, usize), tested: usize| {
+        let id = *ids.entry(key).or_insert_with(|| {
+            counts.push(0);
+            counts.len() - 1
+        });
+        counts[id] += tested;
+        id
+    };
+    for ((name, pointer), values) in &coverage.values {
+        for value in values {
+            id_of(region(name, pointer, *value), 1);
+        }
+    }
+    // Family counters have no pointer, unlike regions
+    for (family, results) in &coverage.families {
+        id_of((family.to_string(), String::new(), 0), *results);
+    }
+    let mut pool: Vec<(DiscoveryStrategyType, usize, Vec<usize>)> = pool
+        .into_iter()
+        .map(|(strategy, point)| {
+            let family = id_of((point.family.to_string(), String::new(), 0), 0);
+            let regions = point
+                .coords
+                .iter()
+                .map(|(pointer, value)| id_of(region(&point.name, pointer, *value), 0))
+                .collect();
+            (strategy, family, regions)
+        })
+        .collect();
+
+    // Novelty: unexplored family + mean unexplored region, each in (0, 1]
+    let novelty = |counts: &[usize], family: usize, regions: &[usize]| {
+        let fresh = |id: usize| 1.0 / (1.0 + counts[id] as f64);
+        let regions = if regions.is_empty() {
+            fresh(family)
+        } else {
+            regions.iter().map(|&id| fresh(id)).sum::<f64>() / regions.len() as f64
+        };
+        fresh(family) + regions
+    };
+    let mut plan = Vec::with_capacity(budget.min(pool.len()));
+    while plan.len() < budget && !pool.is_empty() {
+        let mut best = 0;
+        let mut best_novelty = f64::MIN;
+        for (idx, (_, family, regions)) in pool.iter().enumerate() {
+            let value = novelty(&counts, *family, regions);
+            if value > best_novelty {
+                best = idx;
+                best_novelty = value;
+            }
+        }
+        let (strategy, family, regions) = pool.remove(best);
+        counts[family] += 1;
+        for id in regions {
+            counts[id] += 1;
+        }
+        plan.push(strategy);
+    }
+    plan
+}
+
+/// Fixed grids of the cycles a plan replaces, and a mutation of each
+pub(crate) fn adaptive_candidates() -> Vec<DiscoveryStrategyType> {
+    let mut rng = rand::thread_rng();
+    let mut candidates: Vec<DiscoveryStrategyType> = (0..ADAPTIVE_CYCLES)
+        .flat_map(generate_exploratory_grid)
+        .collect();
+    let mutations: Vec<DiscoveryStrategyType> = candidates
+        .iter()
+        .filter_map(|s| mutate_strategy(s, &mut rng))
+        .collect();
+    candidates.extend(mutations);
+    candidates
+}
+
+/// Grid of a planned cycle, from the coverage of the store's latest results (the plan
+/// of an empty knowledge base on error)
+pub(crate) async fn adaptive_cycle_grid(
+    store: &dyn ResultStore,
+    exclusions: &StrategyExclusions,
+) -> Vec<DiscoveryStrategyType> {
+    let coverage = match store.recent_strategy_params(COVERAGE_SAMPLE).await {
+        Ok(params) => ParameterCoverage::from_params(&params),
+        Err(e) => {
+            warn!(error = %e, "Failed to load knowledge base coverage, planning from scratch");
+            ParameterCoverage::default()
+        }
+    };
+    let mut candidates = adaptive_candidates();
+    retain_valid(&mut candidates);
+    exclusions.retain(&mut candidates);
+    let grid = plan_adaptive_grid(&coverage, candidates, ADAPTIVE_CYCLE_BUDGET);
+    info!(
+        tested = coverage.len(),
+        planned = grid.len(),
+        "Planned adaptive cycle"
+    );
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rsi(period: usize) -> DiscoveryStrategyType {
+        DiscoveryStrategyType::Rsi {
+            period,
+            overbought: 70.0,
+            oversold: 30.0,
+        }
+    }
+
+    fn rsi_period(strategy: &DiscoveryStrategyType) -> usize {
+        match strategy {
+            DiscoveryStrategyType::Rsi { period, .. } => *period,
+            other => panic!("not an RSI: {}", other.name()),
+        }
+    }
+
+    #[test]
+    fn test_plan_targets_unexplored_families_and_regions() {
+        // The knowledge base only knows short RSIs
+        let tested: Vec<String> = (10..=15)
+            .map(|p| serde_json::to_string(&rsi(p)).unwrap())
+            .chain(["{\"type\":\"retired_strategy\"}".to_string()])
+            .collect();
+        let coverage = ParameterCoverage::from_params(&tested);
+        assert_eq!(coverage.len(), 6);
+        assert_eq!(coverage.family_results("rsi"), 6);
+        assert!(coverage.is_tested(&rsi(12)));
+
+        let macd = DiscoveryStrategyType::Macd {
+            fast: 12,
+            slow: 26,
+            signal: 9,
+        };
+        let mut candidates: Vec<_> = (10..=50).step_by(5).map(rsi).collect();
+        candidates.push(macd.clone());
+        candidates.push(rsi(45));
+
+        // Tested and duplicate candidates are dropped
+        let all = plan_adaptive_grid(&coverage, candidates.clone(), 100);
+        assert_eq!(all.len(), 8);
+        assert!(!all.iter().any(|s| coverage.is_tested(s)));
+
+        // A family never tested first, then periods far from the tested ones, each
+        // pick in another region
+        let plan = plan_adaptive_grid(&coverage, candidates, 3);
+        assert_eq!(plan[0].name(), macd.name());
+        let (second, third) = (rsi_period(&plan[1]), rsi_period(&plan[2]));
+        assert!(second >= 20 && third >= 20, "{second} {third}");
+        assert_ne!(second / 5, third / 5);
+
+        // An empty knowledge base plans from the candidates alone
+        let fresh = plan_adaptive_grid(&ParameterCoverage::default(), vec![rsi(14)], 5);
+        assert_eq!(fresh.len(), 1);
+    }
+}
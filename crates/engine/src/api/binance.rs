@@ -4,9 +4,12 @@ use anyhow::Result;
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::{Arc, RwLock};
 use tracing::{debug, info};
 
+use crate::symbols::{SymbolFilters, SymbolMetadata};
 use crate::types::Kline;
 
 const DEFAULT_BASE_URL: &str = "https://api.binance.com";
@@ -17,6 +20,8 @@ const MAX_KLINES_PER_REQUEST: u32 = 1000;
 pub struct BinanceClient {
     client: Client,
     base_url: String,
+    /// exchangeInfo metadata by symbol, shared by the clones of the client
+    symbols: Arc<RwLock<HashMap<String, SymbolMetadata>>>,
 }
 
 /// Raw kline data from Binance API (array of arrays)
@@ -45,6 +50,66 @@ struct TickerPrice {
     price: String,
 }
 
+/// Binance exchangeInfo response (only the fields used)
+#[derive(Debug, Deserialize)]
+struct ExchangeInfo {
+    symbols: Vec<RawSymbol>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSymbol {
+    symbol: String,
+    status: String,
+    base_asset: String,
+    quote_asset: String,
+    filters: Vec<RawFilter>,
+}
+
+/// One entry of a symbol's `filters` (fields depend on `filterType`)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawFilter {
+    filter_type: String,
+    tick_size: Option<String>,
+    step_size: Option<String>,
+    min_qty: Option<String>,
+    min_notional: Option<String>,
+}
+
+impl RawSymbol {
+    fn into_metadata(self) -> SymbolMetadata {
+        let value = |field: &Option<String>| {
+            field
+                .as_deref()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(0.0)
+        };
+        let mut metadata = SymbolMetadata {
+            symbol: self.symbol,
+            status: self.status,
+            base_asset: self.base_asset,
+            quote_asset: self.quote_asset,
+            tick_size: 0.0,
+            step_size: 0.0,
+            min_qty: 0.0,
+            min_notional: 0.0,
+        };
+        for filter in &self.filters {
+            match filter.filter_type.as_str() {
+                "PRICE_FILTER" => metadata.tick_size = value(&filter.tick_size),
+                "LOT_SIZE" => {
+                    metadata.step_size = value(&filter.step_size);
+                    metadata.min_qty = value(&filter.min_qty);
+                }
+                "NOTIONAL" | "MIN_NOTIONAL" => metadata.min_notional = value(&filter.min_notional),
+                _ => {}
+            }
+        }
+        metadata
+    }
+}
+
 /// Binance 24h ticker statistics
 #[derive(Debug, Clone, Deserialize)]
 pub struct TickerStats {
@@ -78,6 +143,7 @@ impl BinanceClient {
                 .build()
                 .expect("Failed to build HTTP client"),
             base_url: DEFAULT_BASE_URL.to_string(),
+            symbols: Arc::default(),
         }
     }
 
@@ -214,4 +280,67 @@ impl BinanceClient {
         let stats: TickerStats = response.json().await?;
         Ok(stats)
     }
+
+    /// Fetch the exchangeInfo metadata of `symbols` (uncached)
+    pub async fn get_exchange_info(&self, symbols: &[String]) -> Result<Vec<SymbolMetadata>> {
+        let url = format!("{}/api/v3/exchangeInfo", self.base_url);
+        let list = serde_json::to_string(symbols)?;
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("symbols", list)])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Binance API error {}: {}", status, body);
+        }
+
+        let info: ExchangeInfo = response.json().await?;
+        Ok(info
+            .symbols
+            .into_iter()
+            .map(RawSymbol::into_metadata)
+            .collect())
+    }
+
+    /// Metadata of `symbols` in the same order, fetched once per symbol and then
+    /// served from the client's cache
+    pub async fn symbol_metadata(&self, symbols: &[String]) -> Result<Vec<SymbolMetadata>> {
+        let missing: Vec<String> = {
+            let cache = self.symbols.read().unwrap();
+            symbols
+                .iter()
+                .filter(|s| !cache.contains_key(s.as_str()))
+                .cloned()
+                .collect()
+        };
+        if !missing.is_empty() {
+            let fetched = self.get_exchange_info(&missing).await?;
+            debug!(count = fetched.len(), "Fetched symbol metadata");
+            let mut cache = self.symbols.write().unwrap();
+            for metadata in fetched {
+                cache.insert(metadata.symbol.clone(), metadata);
+            }
+        }
+        let cache = self.symbols.read().unwrap();
+        symbols
+            .iter()
+            .map(|s| {
+                cache
+                    .get(s)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("Unknown symbol {}", s))
+            })
+            .collect()
+    }
+
+    /// Order filters of one symbol (cached, see `symbol_metadata`)
+    pub async fn symbol_filters(&self, symbol: &str) -> Result<SymbolFilters> {
+        let metadata = self.symbol_metadata(&[symbol.to_string()]).await?;
+        Ok(metadata[0].filters())
+    }
 }
@@ -794,7 +794,7 @@ impl PolymarketDataClient {
             }
 
             // Log progress every 20 pages
-            if (offset / page_limit) % 20 == 0 {
+            if (offset / page_limit).is_multiple_of(20) {
                 info!(
                     offset,
                     total_found = all_markets.len(),
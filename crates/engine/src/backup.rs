@@ -0,0 +1,283 @@
+//! Knowledge base backup & restore
+//!
+//! Backups are taken online with `VACUUM INTO` (a transactionally consistent copy of the
+//! live WAL database, like the SQLite backup API) and zstd-compressed to a single
+//! `.db.zst` file. Restore decompresses, validates and swaps the file in place — it must
+//! run while no server is using the database.
+
+use anyhow::{bail, Context};
+use chrono::{DateTime, Utc};
+use persistence::Database;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// zstd level used for snapshots (3 = zstd default, good speed/ratio trade-off)
+const ZSTD_LEVEL: i32 = 3;
+
+/// First 16 bytes of every SQLite database file
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupReport {
+    pub path: String,
+    /// Size of the uncompressed snapshot
+    pub db_bytes: u64,
+    pub compressed_bytes: u64,
+    pub created_at: i64,
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub db_path: String,
+    /// Where the previous database was moved, if there was one
+    pub previous_db: Option<String>,
+    pub db_bytes: u64,
+}
+
+// ---------------------------------------------------------------------------
+// Backup
+// ---------------------------------------------------------------------------
+
+/// Default snapshot file name, e.g. `discovery-20260301-223000.db.zst`
+pub fn backup_file_name(now: DateTime<Utc>) -> String {
+    format!("discovery-{}.db.zst", now.format("%Y%m%d-%H%M%S"))
+}
+
+/// Snapshot the live database and write it zstd-compressed to `out`.
+///
+/// Refuses to overwrite an existing file.
+pub async fn create_backup(db: &Database, out: &Path) -> anyhow::Result<BackupReport> {
+    let started = Utc::now();
+
+    if out.exists() {
+        bail!("{} already exists", out.display());
+    }
+    if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("cannot create {}", parent.display()))?;
+    }
+
+    // Uncompressed snapshot next to the output, removed once compressed
+    let snapshot = sidecar_path(out, "snapshot.tmp");
+    let _ = std::fs::remove_file(&snapshot);
+
+    info!(out = %out.display(), "Snapshotting knowledge base...");
+    db.snapshot_into(&snapshot)
+        .await
+        .map_err(|e| anyhow::anyhow!("Snapshot failed: {}", e))?;
+
+    let db_bytes = std::fs::metadata(&snapshot)?.len();
+    let (src, dst) = (snapshot.clone(), out.to_path_buf());
+    let compressed = tokio::task::spawn_blocking(move || compress_file(&src, &dst)).await?;
+    let _ = std::fs::remove_file(&snapshot);
+    let compressed_bytes = compressed.context("compression failed")?;
+
+    let finished = Utc::now();
+    let report = BackupReport {
+        path: out.display().to_string(),
+        db_bytes,
+        compressed_bytes,
+        created_at: finished.timestamp(),
+        duration_ms: (finished - started).num_milliseconds(),
+    };
+
+    info!(
+        db_bytes,
+        compressed_bytes,
+        duration_ms = report.duration_ms,
+        "Backup written to {}",
+        report.path
+    );
+    Ok(report)
+}
+
+// ---------------------------------------------------------------------------
+// Restore
+// ---------------------------------------------------------------------------
+
+/// Replace the database at `db_path` with the snapshot at `src` (`.zst` or a plain
+/// SQLite file). The snapshot is validated (header, migrations, `quick_check`) before
+/// anything is touched; the previous database is kept as `<db>.pre-restore-<ts>`.
+pub async fn restore_backup(src: &Path, db_path: &Path) -> anyhow::Result<RestoreReport> {
+    if !src.exists() {
+        bail!("{} does not exist", src.display());
+    }
+    if let Some(parent) = db_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("cannot create {}", parent.display()))?;
+    }
+
+    let staged = sidecar_path(db_path, "restore.tmp");
+    remove_with_sidecars(&staged);
+
+    let is_zst = src.extension().is_some_and(|e| e == "zst");
+    let (from, to) = (src.to_path_buf(), staged.clone());
+    let unpacked = tokio::task::spawn_blocking(move || {
+        if is_zst {
+            decompress_file(&from, &to)
+        } else {
+            std::fs::copy(&from, &to)
+        }
+    })
+    .await?;
+    if let Err(e) = unpacked {
+        remove_with_sidecars(&staged);
+        return Err(e).context("cannot unpack snapshot");
+    }
+
+    if let Err(e) = validate_snapshot(&staged).await {
+        remove_with_sidecars(&staged);
+        return Err(e);
+    }
+
+    let previous_db = if db_path.exists() {
+        let moved = sidecar_path(
+            db_path,
+            &format!("pre-restore-{}", Utc::now().format("%Y%m%d-%H%M%S")),
+        );
+        std::fs::rename(db_path, &moved)
+            .with_context(|| format!("cannot move current database to {}", moved.display()))?;
+        Some(moved.display().to_string())
+    } else {
+        None
+    };
+    // Stale WAL/SHM files belong to the old database and must not be replayed
+    remove_with_sidecars(db_path);
+    std::fs::rename(&staged, db_path)
+        .with_context(|| format!("cannot move snapshot to {}", db_path.display()))?;
+    // Validation opened the staged file in WAL mode; its (checkpointed) sidecars are empty
+    remove_with_sidecars(&staged);
+
+    let report = RestoreReport {
+        db_path: db_path.display().to_string(),
+        previous_db,
+        db_bytes: std::fs::metadata(db_path)?.len(),
+    };
+    info!(
+        db_bytes = report.db_bytes,
+        previous = ?report.previous_db,
+        "Database restored to {}",
+        report.db_path
+    );
+    Ok(report)
+}
+
+/// Open the staged file as a database (runs migrations) and check its integrity
+async fn validate_snapshot(path: &Path) -> anyhow::Result<()> {
+    if !has_sqlite_header(path)? {
+        bail!("{} is not a SQLite database", path.display());
+    }
+
+    let db = Database::new(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Snapshot cannot be opened: {}", e))?;
+    let ok = db
+        .quick_check()
+        .await
+        .map_err(|e| anyhow::anyhow!("Integrity check failed: {}", e))?;
+    db.close().await;
+
+    if !ok {
+        bail!("Snapshot failed PRAGMA quick_check");
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// File helpers
+// ---------------------------------------------------------------------------
+
+/// `<path>.<suffix>` in the same directory (renames stay on one filesystem)
+fn sidecar_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Remove a database file and its `-wal` / `-shm` companions, ignoring missing files
+fn remove_with_sidecars(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    for ext in ["-wal", "-shm"] {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(ext);
+        let _ = std::fs::remove_file(PathBuf::from(name));
+    }
+}
+
+fn has_sqlite_header(path: &Path) -> std::io::Result<bool> {
+    let mut header = [0u8; 16];
+    let mut file = File::open(path)?;
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header == SQLITE_HEADER),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// zstd-compress `src` into `dst`, returning the compressed size
+fn compress_file(src: &Path, dst: &Path) -> std::io::Result<u64> {
+    let reader = BufReader::new(File::open(src)?);
+    let writer = BufWriter::new(File::create(dst)?);
+    let result = zstd::stream::copy_encode(reader, writer, ZSTD_LEVEL);
+    if result.is_err() {
+        let _ = std::fs::remove_file(dst);
+    }
+    result?;
+    Ok(std::fs::metadata(dst)?.len())
+}
+
+/// Decompress a zstd file `src` into `dst`, returning the decompressed size
+fn decompress_file(src: &Path, dst: &Path) -> std::io::Result<u64> {
+    let reader = BufReader::new(File::open(src)?);
+    let writer = BufWriter::new(File::create(dst)?);
+    zstd::stream::copy_decode(reader, writer)?;
+    Ok(std::fs::metadata(dst)?.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_backup_file_name() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 22, 30, 5).unwrap();
+        assert_eq!(backup_file_name(now), "discovery-20260301-223005.db.zst");
+    }
+
+    #[test]
+    fn test_compress_roundtrip_and_header_check() {
+        let dir = std::env::temp_dir().join(format!("poly-backup-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = dir.join("plain.db");
+        let packed = dir.join("plain.db.zst");
+        let unpacked = dir.join("unpacked.db");
+
+        let mut content = SQLITE_HEADER.to_vec();
+        content.extend(std::iter::repeat_n(42u8, 64 * 1024));
+        std::fs::write(&original, &content).unwrap();
+
+        let compressed = compress_file(&original, &packed).unwrap();
+        assert!(compressed < content.len() as u64);
+        assert_eq!(
+            decompress_file(&packed, &unpacked).unwrap(),
+            content.len() as u64
+        );
+        assert_eq!(std::fs::read(&unpacked).unwrap(), content);
+
+        assert!(has_sqlite_header(&unpacked).unwrap());
+        // The compressed file itself is not a database
+        assert!(!has_sqlite_header(&packed).unwrap());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,362 @@
+//! Performance benchmarks of the discovery hot paths
+//!
+//! `bench_cases` builds the timed cases on a seeded synthetic series: the generic
+//! backtest loop, every indicator generator, `score_result` and grid generation. The
+//! criterion benches (`cargo bench -p engine`) and the `bench` CLI command share them;
+//! the command times each case with a fixed iteration count and compares the report
+//! against a saved baseline, so a regression fails CI with numbers attached.
+
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::Instant;
+
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::{
+    generate_legacy_phase1_grid, generate_ml_guided_grid, generate_phase1_grid,
+    generate_refinement_grid, run_backtest, run_generic_backtest, score_result, BacktestLimits,
+    DiscoveryResult, DiscoveryStrategyType, SizingMode,
+};
+use crate::fees::PolymarketFeeConfig;
+use crate::indicators::build_signal_generator;
+use crate::population::Population;
+use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+use crate::types::Kline;
+
+/// `score_result` calls per iteration (a single call is too short to time)
+const SCORE_CALLS: usize = 1_000;
+
+/// Size of the benchmark workload
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BenchConfig {
+    /// 15m bars of the synthetic series (8 640 = 90 days)
+    pub bars: usize,
+    /// Timed iterations per case
+    pub iterations: usize,
+    /// Untimed iterations run first
+    pub warmup: usize,
+    pub seed: u64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            bars: 90 * 96,
+            iterations: 20,
+            warmup: 2,
+            seed: 42,
+        }
+    }
+}
+
+/// One timed unit of work
+pub struct BenchCase {
+    /// `backtest`, `indicators`, `score` or `grid`
+    pub group: &'static str,
+    pub label: String,
+    /// Bars processed per iteration, for throughput
+    pub bars: Option<usize>,
+    work: Box<dyn FnMut()>,
+}
+
+impl BenchCase {
+    fn new(
+        group: &'static str,
+        label: &str,
+        bars: Option<usize>,
+        work: impl FnMut() + 'static,
+    ) -> Self {
+        Self {
+            group,
+            label: label.to_string(),
+            bars,
+            work: Box::new(work),
+        }
+    }
+
+    /// `group/label`
+    pub fn name(&self) -> String {
+        format!("{}/{}", self.group, self.label)
+    }
+
+    /// Run one iteration
+    pub fn run(&mut self) {
+        (self.work)()
+    }
+}
+
+/// Strategies timed through the generic backtest loop
+fn backtest_strategies() -> Vec<DiscoveryStrategyType> {
+    vec![
+        DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        },
+        DiscoveryStrategyType::BollingerBands {
+            period: 20,
+            multiplier: 2.0,
+        },
+        DiscoveryStrategyType::Macd {
+            fast: 12,
+            slow: 26,
+            signal: 9,
+        },
+    ]
+}
+
+/// One variant of each single-symbol generator: the classic strategy types of the
+/// legacy grid, a dynamic combo and every web strategy
+fn indicator_strategies() -> Vec<DiscoveryStrategyType> {
+    let mut seen = HashSet::new();
+    generate_legacy_phase1_grid()
+        .into_iter()
+        .chain(generate_phase1_grid())
+        .filter(|s| !s.is_gabagool() && !s.is_cross_sectional())
+        .filter(|s| {
+            let key = match s {
+                DiscoveryStrategyType::WebStrategy { .. } => s.name().to_string(),
+                _ => format!("{:?}", std::mem::discriminant(s)),
+            };
+            seen.insert(key)
+        })
+        .collect()
+}
+
+/// Every benchmark case, on a synthetic series built from `config`
+pub fn bench_cases(config: &BenchConfig) -> Vec<BenchCase> {
+    let klines: Arc<Vec<Kline>> = Arc::new(generate_klines(&SyntheticConfig {
+        bars: config.bars,
+        seed: config.seed,
+        volatility: 0.005,
+        ..SyntheticConfig::for_model(SyntheticModel::RegimeSwitching)
+    }));
+    let bars = Some(klines.len());
+    let mut cases = Vec::new();
+
+    for strategy in backtest_strategies() {
+        let klines = klines.clone();
+        let mut generator = build_signal_generator(&strategy);
+        let fee_config = PolymarketFeeConfig::default();
+        let limits = BacktestLimits::unlimited();
+        cases.push(BenchCase::new(
+            "backtest",
+            strategy.name(),
+            bars,
+            move || {
+                generator.reset();
+                black_box(run_generic_backtest(
+                    generator.as_mut(),
+                    &klines,
+                    dec!(10000),
+                    dec!(10),
+                    SizingMode::Fixed,
+                    &fee_config,
+                    &limits,
+                ));
+            },
+        ));
+    }
+
+    for strategy in indicator_strategies() {
+        let klines = klines.clone();
+        let mut generator = build_signal_generator(&strategy);
+        cases.push(BenchCase::new(
+            "indicators",
+            strategy.name(),
+            bars,
+            move || {
+                generator.reset();
+                for kline in klines.iter() {
+                    black_box(generator.on_bar(kline));
+                }
+            },
+        ));
+    }
+
+    let scored = run_backtest(
+        &backtest_strategies()[0],
+        &klines,
+        "SYNTH",
+        SizingMode::Fixed,
+        &BacktestLimits::unlimited(),
+    );
+    cases.push(BenchCase::new(
+        "score",
+        "score_result_x1000",
+        None,
+        move || {
+            for _ in 0..SCORE_CALLS {
+                black_box(score_result(black_box(&scored), dec!(10000)));
+            }
+        },
+    ));
+
+    cases.push(BenchCase::new("grid", "phase1", None, || {
+        black_box(generate_phase1_grid());
+    }));
+    let refined = backtest_strategies()[0].clone();
+    cases.push(BenchCase::new("grid", "refinement", None, move || {
+        black_box(generate_refinement_grid(&refined));
+    }));
+    let top: Vec<DiscoveryResult> = generate_phase1_grid()
+        .iter()
+        .filter(|s| !s.is_gabagool())
+        .take(30)
+        .map(|s| {
+            run_backtest(
+                s,
+                &klines,
+                "SYNTH",
+                SizingMode::Fixed,
+                &BacktestLimits::unlimited(),
+            )
+        })
+        .collect();
+    let top = Population::from_results(&top);
+    cases.push(BenchCase::new("grid", "ml_guided", None, move || {
+        black_box(generate_ml_guided_grid(top.individuals(), 5));
+    }));
+
+    cases
+}
+
+/// Timing of one case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchTiming {
+    pub name: String,
+    pub iterations: usize,
+    pub mean_ns: f64,
+    /// Fastest iteration, the figure compared against baselines (least noisy)
+    pub min_ns: f64,
+    pub bars_per_sec: Option<f64>,
+}
+
+/// Timings of every case run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub config: BenchConfig,
+    pub timings: Vec<BenchTiming>,
+}
+
+/// Time the cases whose name contains `filter` (all when `None`). `on_timing` gets each
+/// timing as it completes.
+pub fn run_benchmarks(
+    config: &BenchConfig,
+    filter: Option<&str>,
+    mut on_timing: impl FnMut(&BenchTiming),
+) -> BenchReport {
+    let iterations = config.iterations.max(1);
+    let mut timings = Vec::new();
+    for mut case in bench_cases(config) {
+        let name = case.name();
+        if filter.is_some_and(|f| !name.contains(f)) {
+            continue;
+        }
+        for _ in 0..config.warmup {
+            case.run();
+        }
+        let mut total_ns = 0.0;
+        let mut min_ns = f64::MAX;
+        for _ in 0..iterations {
+            let started = Instant::now();
+            case.run();
+            let elapsed = started.elapsed().as_nanos() as f64;
+            total_ns += elapsed;
+            min_ns = min_ns.min(elapsed);
+        }
+        let mean_ns = total_ns / iterations as f64;
+        let timing = BenchTiming {
+            name,
+            iterations,
+            mean_ns,
+            min_ns,
+            bars_per_sec: case
+                .bars
+                .filter(|_| mean_ns > 0.0)
+                .map(|bars| bars as f64 * 1e9 / mean_ns),
+        };
+        on_timing(&timing);
+        timings.push(timing);
+    }
+    BenchReport {
+        config: *config,
+        timings,
+    }
+}
+
+/// A case slower than its baseline by more than the allowed margin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchRegression {
+    pub name: String,
+    pub baseline_ns: f64,
+    pub current_ns: f64,
+    pub change_pct: f64,
+}
+
+/// Cases of `report` whose fastest iteration is more than `max_regression_pct` slower
+/// than in `baseline`. Cases missing from either side are ignored.
+pub fn find_regressions(
+    report: &BenchReport,
+    baseline: &BenchReport,
+    max_regression_pct: f64,
+) -> Vec<BenchRegression> {
+    report
+        .timings
+        .iter()
+        .filter_map(|timing| {
+            let base = baseline.timings.iter().find(|b| b.name == timing.name)?;
+            if base.min_ns <= 0.0 {
+                return None;
+            }
+            let change_pct = (timing.min_ns / base.min_ns - 1.0) * 100.0;
+            (change_pct > max_regression_pct).then(|| BenchRegression {
+                name: timing.name.clone(),
+                baseline_ns: base.min_ns,
+                current_ns: timing.min_ns,
+                change_pct,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benchmarks_cover_hot_paths_and_flag_regressions() {
+        let config = BenchConfig {
+            bars: 300,
+            iterations: 1,
+            warmup: 0,
+            seed: 7,
+        };
+        let cases = bench_cases(&config);
+        for group in ["backtest", "indicators", "score", "grid"] {
+            assert!(cases.iter().any(|c| c.group == group), "no {} case", group);
+        }
+        let names: HashSet<String> = cases.iter().map(BenchCase::name).collect();
+        assert_eq!(names.len(), cases.len(), "case names must be unique");
+
+        let mut seen = 0;
+        let report = run_benchmarks(&config, Some("backtest/"), |_| seen += 1);
+        assert_eq!(report.timings.len(), 3);
+        assert_eq!(seen, 3);
+        assert!(report
+            .timings
+            .iter()
+            .all(|t| t.min_ns > 0.0 && t.mean_ns >= t.min_ns && t.bars_per_sec.is_some()));
+
+        let mut baseline = report.clone();
+        baseline.timings[0].min_ns = report.timings[0].min_ns / 2.0;
+        baseline.timings.pop();
+        let regressions = find_regressions(&report, &baseline, 25.0);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].name, report.timings[0].name);
+        assert!((regressions[0].change_pct - 100.0).abs() < 1e-6);
+        assert!(find_regressions(&report, &baseline, 150.0).is_empty());
+    }
+}
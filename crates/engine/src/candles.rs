@@ -0,0 +1,349 @@
+//! Candle transforms of the signal bars
+//!
+//! `DiscoveryRequest.candle_transform` feeds the bar-signal strategies smoothed candles
+//! instead of the raw klines:
+//! - `HeikinAshi`: one candle per kline, closing at the mean of its OHLC and opening at
+//!   the middle of the previous Heikin-Ashi body
+//! - `Renko { brick_pct }`: bricks of `brick_pct` % of the first close, drawn on the
+//!   closes. A brick continues the trend once the close moves one size past the last
+//!   brick, and reverses it two sizes past; a kline completes zero, one or several bricks
+//!
+//! Only the signals see the transformed candles: `CandleSignalGenerator` pushes each
+//! kline through a `CandleBuilder`, feeds the strategy every candle it completed and
+//! returns the last non-hold signal (hold when the kline completed none), so fills,
+//! stops and the equity curve stay on the real prices. Gabagool and cross-sectional
+//! strategies read no bars and ignore the transform.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::indicators::{SignalGenerator, SignalWithConfidence};
+use crate::strategy::Signal;
+use crate::types::Kline;
+
+/// Smallest Renko brick, in % of the first close
+pub const MIN_BRICK_PCT: f64 = 0.01;
+
+/// Largest Renko brick, in % of the first close
+pub const MAX_BRICK_PCT: f64 = 50.0;
+
+/// Candles the bar-signal strategies of a run read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CandleTransform {
+    /// The klines as fetched
+    #[default]
+    Raw,
+    HeikinAshi,
+    Renko {
+        /// Brick size, in % of the first close
+        brick_pct: f64,
+    },
+}
+
+impl CandleTransform {
+    pub fn is_raw(&self) -> bool {
+        *self == Self::Raw
+    }
+
+    /// `raw`, `heikin_ashi` or `renko:<brick %>`, as parsed by `parse()`
+    pub fn name(&self) -> String {
+        match self {
+            Self::Raw => "raw".to_string(),
+            Self::HeikinAshi => "heikin_ashi".to_string(),
+            Self::Renko { brick_pct } => format!("renko:{}", brick_pct),
+        }
+    }
+
+    /// `raw`, `heikin_ashi` (or `heikin-ashi`, `ha`) or `renko:<brick %>`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim().to_lowercase().replace('-', "_");
+        let transform = match s.split_once(':') {
+            None if s == "raw" => Self::Raw,
+            None if s == "heikin_ashi" || s == "ha" => Self::HeikinAshi,
+            Some(("renko", pct)) => Self::Renko {
+                brick_pct: pct
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid renko brick size {:?}", pct))?,
+            },
+            _ => {
+                return Err(format!(
+                    "Unknown candle transform {:?}: use raw, heikin_ashi or renko:<brick %>",
+                    s
+                ))
+            }
+        };
+        transform.validate()?;
+        Ok(transform)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Renko { brick_pct } if !(MIN_BRICK_PCT..=MAX_BRICK_PCT).contains(brick_pct) => {
+                Err(format!(
+                    "renko brick_pct must be in [{}, {}], got {}",
+                    MIN_BRICK_PCT, MAX_BRICK_PCT, brick_pct
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Candles of `klines`, built from the first one
+    pub fn apply(&self, klines: &[Kline]) -> Vec<Kline> {
+        let mut builder = CandleBuilder::new(*self);
+        let mut candles = Vec::with_capacity(klines.len());
+        for kline in klines {
+            builder.push(kline, &mut candles);
+        }
+        candles
+    }
+}
+
+/// Builds the candles of a transform one kline at a time
+#[derive(Debug, Clone)]
+pub struct CandleBuilder {
+    transform: CandleTransform,
+    /// Open and close of the previous candle (Heikin-Ashi) or brick (Renko)
+    last: Option<(Decimal, Decimal)>,
+    /// Renko: brick size and first close, set by the first kline
+    renko: Option<(Decimal, Decimal)>,
+}
+
+impl CandleBuilder {
+    pub fn new(transform: CandleTransform) -> Self {
+        Self {
+            transform,
+            last: None,
+            renko: None,
+        }
+    }
+
+    /// Append the candles completed by `kline` to `out`
+    pub fn push(&mut self, kline: &Kline, out: &mut Vec<Kline>) {
+        match self.transform {
+            CandleTransform::Raw => out.push(kline.clone()),
+            CandleTransform::HeikinAshi => out.push(self.heikin_ashi(kline)),
+            CandleTransform::Renko { brick_pct } => self.renko(kline, brick_pct, out),
+        }
+    }
+
+    fn heikin_ashi(&mut self, kline: &Kline) -> Kline {
+        let close = (kline.open + kline.high + kline.low + kline.close) / Decimal::from(4);
+        let open = match self.last {
+            Some((open, close)) => (open + close) / Decimal::TWO,
+            None => (kline.open + kline.close) / Decimal::TWO,
+        };
+        self.last = Some((open, close));
+        Kline {
+            open,
+            high: kline.high.max(open).max(close),
+            low: kline.low.min(open).min(close),
+            close,
+            ..kline.clone()
+        }
+    }
+
+    fn renko(&mut self, kline: &Kline, brick_pct: f64, out: &mut Vec<Kline>) {
+        let (size, anchor) = match self.renko {
+            Some(renko) => renko,
+            None => {
+                let pct = Decimal::try_from(brick_pct).unwrap_or_default();
+                let size = kline.close * pct / Decimal::ONE_HUNDRED;
+                // A zero close has no brick size yet: wait for the next kline
+                if size > Decimal::ZERO {
+                    self.renko = Some((size, kline.close));
+                }
+                return;
+            }
+        };
+        let first = out.len();
+        loop {
+            let (top, bottom) = self.last.map_or((anchor, anchor), |(open, close)| {
+                (open.max(close), open.min(close))
+            });
+            let brick = if kline.close >= top + size {
+                (top, top + size)
+            } else if kline.close <= bottom - size {
+                (bottom, bottom - size)
+            } else {
+                break;
+            };
+            self.last = Some(brick);
+            out.push(Kline {
+                open: brick.0,
+                high: brick.0.max(brick.1),
+                low: brick.0.min(brick.1),
+                close: brick.1,
+                ..kline.clone()
+            });
+        }
+        // The kline's volume is shared by its bricks
+        let bricks = out.len() - first;
+        if bricks > 1 {
+            let volume = kline.volume / Decimal::from(bricks);
+            for brick in &mut out[first..] {
+                brick.volume = volume;
+            }
+        }
+    }
+}
+
+/// A strategy fed the candles of a transform, see the module docs
+pub struct CandleSignalGenerator {
+    inner: Box<dyn SignalGenerator>,
+    builder: CandleBuilder,
+    candles: Vec<Kline>,
+    /// Candles fed to `inner` so far
+    fed: usize,
+}
+
+impl CandleSignalGenerator {
+    pub fn new(inner: Box<dyn SignalGenerator>, transform: CandleTransform) -> Self {
+        Self {
+            inner,
+            builder: CandleBuilder::new(transform),
+            candles: Vec::new(),
+            fed: 0,
+        }
+    }
+}
+
+impl SignalGenerator for CandleSignalGenerator {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    /// The inner warm-up, in klines; a Renko strategy also holds until it has seen that
+    /// many bricks
+    fn warmup_bars(&self) -> usize {
+        self.inner.warmup_bars()
+    }
+
+    fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
+        self.candles.clear();
+        self.builder.push(kline, &mut self.candles);
+        let warmup = self.inner.warmup_bars();
+        let mut signal = SignalWithConfidence::hold();
+        for candle in &self.candles {
+            let candle_signal = self.inner.on_bar(candle);
+            self.fed += 1;
+            if self.fed > warmup && candle_signal.signal != Signal::Hold {
+                signal = candle_signal;
+            }
+        }
+        signal
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.builder = CandleBuilder::new(self.builder.transform);
+        self.fed = 0;
+    }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        self.inner.features()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn kline(minute: i64, open: Decimal, high: Decimal, low: Decimal, close: Decimal) -> Kline {
+        Kline {
+            open_time: minute * 60_000,
+            open,
+            high,
+            low,
+            close,
+            volume: dec!(12),
+            close_time: minute * 60_000 + 59_999,
+        }
+    }
+
+    #[test]
+    fn test_heikin_ashi() {
+        let klines = vec![
+            kline(0, dec!(100), dec!(104), dec!(98), dec!(102)),
+            kline(1, dec!(102), dec!(110), dec!(101), dec!(107)),
+        ];
+        let ha = CandleTransform::HeikinAshi.apply(&klines);
+        assert_eq!(ha.len(), 2);
+        assert_eq!((ha[0].open, ha[0].close), (dec!(101), dec!(101)));
+        assert_eq!((ha[0].high, ha[0].low), (dec!(104), dec!(98)));
+        // Opens at the middle of the previous body, closes at the OHLC mean
+        assert_eq!((ha[1].open, ha[1].close), (dec!(101), dec!(105)));
+        assert_eq!((ha[1].high, ha[1].low), (dec!(110), dec!(101)));
+        assert_eq!(ha[1].open_time, klines[1].open_time);
+    }
+
+    #[test]
+    fn test_renko_bricks() {
+        let renko = CandleTransform::Renko { brick_pct: 1.0 };
+        let closes = [
+            dec!(100), // anchor, brick size 1
+            dec!(100.5),
+            dec!(102.2), // two up bricks
+            dec!(101.4), // a single size back: no reversal
+            dec!(99.9),  // two sizes below the last close: one down brick
+        ];
+        let klines: Vec<Kline> = closes
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| kline(i as i64, c, c, c, c))
+            .collect();
+        let bricks = renko.apply(&klines);
+        let bodies: Vec<_> = bricks.iter().map(|b| (b.open, b.close)).collect();
+        assert_eq!(
+            bodies,
+            vec![
+                (dec!(100), dec!(101)),
+                (dec!(101), dec!(102)),
+                (dec!(101), dec!(100)),
+            ]
+        );
+        assert_eq!(bricks[0].open_time, klines[2].open_time);
+        assert_eq!(bricks[0].volume, dec!(6));
+        assert_eq!(bricks[2].volume, dec!(12));
+
+        // The generator holds on klines without a brick and reads the bricks' closes
+        struct Closes(Vec<Decimal>);
+        impl SignalGenerator for Closes {
+            fn name(&self) -> &str {
+                "closes"
+            }
+            fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
+                self.0.push(kline.close);
+                SignalWithConfidence::buy(1.0)
+            }
+            fn reset(&mut self) {}
+        }
+        let mut generator = CandleSignalGenerator::new(Box::new(Closes(Vec::new())), renko);
+        let signals: Vec<Signal> = klines.iter().map(|k| generator.on_bar(k).signal).collect();
+        assert_eq!(
+            signals,
+            vec![
+                Signal::Hold,
+                Signal::Hold,
+                Signal::Buy,
+                Signal::Hold,
+                Signal::Buy
+            ]
+        );
+
+        assert_eq!(
+            CandleTransform::parse("Renko:0.5").unwrap(),
+            CandleTransform::Renko { brick_pct: 0.5 }
+        );
+        assert_eq!(
+            CandleTransform::parse("heikin-ashi").unwrap(),
+            CandleTransform::HeikinAshi
+        );
+        assert!(CandleTransform::parse("renko:0").is_err());
+        assert!(CandleTransform::parse("kagi").is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! Capital pool shared by concurrent strategies
+//!
+//! A `CapitalAllocator` holds one pool of capital for strategies trading at the same
+//! time (paper trading, and portfolio backtests once they exist): an entry reserves its
+//! notional, its close releases it and books the realized PnL into the pool. An entry
+//! the free capital cannot cover is rejected or queued, per `ExhaustedCapital`; queued
+//! entries are granted first come first served as capital frees up, until they expire
+//! or their strategy cancels them.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// What happens to an entry the free capital cannot cover
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "policy", rename_all = "snake_case")]
+pub enum ExhaustedCapital {
+    /// Dropped
+    #[default]
+    Reject,
+    /// Waits for capital to free up, at most `max_wait_ms`
+    Queue { max_wait_ms: i64 },
+}
+
+/// Capital pool of concurrent strategies
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CapitalConfig {
+    /// Starting capital, in USDC
+    pub capital: f64,
+    #[serde(default)]
+    pub on_exhausted: ExhaustedCapital,
+}
+
+impl Default for CapitalConfig {
+    fn default() -> Self {
+        Self {
+            capital: 10_000.0,
+            on_exhausted: ExhaustedCapital::Reject,
+        }
+    }
+}
+
+impl CapitalConfig {
+    /// Capital must be positive, a queue wait non-negative
+    pub fn validate(&self) -> Result<(), String> {
+        if self.capital.is_nan() || self.capital <= 0.0 {
+            return Err("capital must be positive".to_string());
+        }
+        if let ExhaustedCapital::Queue { max_wait_ms } = self.on_exhausted {
+            if max_wait_ms < 0 {
+                return Err("max_wait_ms must not be negative".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Answer to an entry request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Allocation {
+    /// The notional is reserved, the entry can fill
+    Granted,
+    /// Waiting for capital, see `CapitalAllocator::grant_queued`
+    Queued,
+    Rejected,
+}
+
+/// What the pool did over a session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CapitalStats {
+    /// Entries granted, on request or from the queue
+    pub granted: u32,
+    /// Entries refused for lack of capital
+    pub rejected: u32,
+    /// Entries that had to wait, granted later or not
+    pub queued: u32,
+    /// Queued entries dropped after `max_wait_ms`
+    pub expired: u32,
+    /// Queued entries their strategy withdrew (exit signal, halt)
+    pub cancelled: u32,
+    /// Largest notional reserved at once, in USDC
+    pub peak_reserved: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct QueuedEntry {
+    strategy: usize,
+    notional: f64,
+    time: i64,
+}
+
+/// Capital reserved by the open positions of several strategies, identified by index
+#[derive(Debug, Clone)]
+pub struct CapitalAllocator {
+    config: CapitalConfig,
+    /// Starting capital plus the realized PnL
+    equity: f64,
+    reserved: f64,
+    queue: VecDeque<QueuedEntry>,
+    stats: CapitalStats,
+}
+
+impl CapitalAllocator {
+    pub fn new(config: CapitalConfig) -> Self {
+        Self {
+            config,
+            equity: config.capital,
+            reserved: 0.0,
+            queue: VecDeque::new(),
+            stats: CapitalStats::default(),
+        }
+    }
+
+    pub fn equity(&self) -> f64 {
+        self.equity
+    }
+
+    pub fn reserved(&self) -> f64 {
+        self.reserved
+    }
+
+    /// Capital no open position holds
+    pub fn free(&self) -> f64 {
+        (self.equity - self.reserved).max(0.0)
+    }
+
+    pub fn stats(&self) -> CapitalStats {
+        self.stats
+    }
+
+    pub fn is_queued(&self, strategy: usize) -> bool {
+        self.queue.iter().any(|e| e.strategy == strategy)
+    }
+
+    /// Ask to reserve `notional` for an entry of `strategy`. Waiting entries are served
+    /// first: a request only jumps an empty queue.
+    pub fn request(&mut self, strategy: usize, notional: f64, time: i64) -> Allocation {
+        if self.queue.is_empty() && notional <= self.free() {
+            self.reserve(notional);
+            return Allocation::Granted;
+        }
+        match self.config.on_exhausted {
+            ExhaustedCapital::Reject => {
+                self.stats.rejected += 1;
+                Allocation::Rejected
+            }
+            ExhaustedCapital::Queue { .. } => {
+                self.queue.push_back(QueuedEntry {
+                    strategy,
+                    notional,
+                    time,
+                });
+                self.stats.queued += 1;
+                Allocation::Queued
+            }
+        }
+    }
+
+    /// Withdraw the waiting entry of `strategy`, if any
+    pub fn cancel(&mut self, strategy: usize) -> bool {
+        let before = self.queue.len();
+        self.queue.retain(|e| e.strategy != strategy);
+        let cancelled = before - self.queue.len();
+        self.stats.cancelled += cancelled as u32;
+        cancelled > 0
+    }
+
+    /// Close of a position that reserved `notional`, with its realized `pnl`
+    pub fn release(&mut self, notional: f64, pnl: f64) {
+        self.reserved = (self.reserved - notional).max(0.0);
+        self.equity += pnl;
+    }
+
+    /// Book PnL outside of a close (entry fees)
+    pub fn book(&mut self, pnl: f64) {
+        self.equity += pnl;
+    }
+
+    /// Drop the entries waiting longer than `max_wait_ms` at `time`, then grant the
+    /// oldest ones the free capital covers, in order. Returns `(strategy, notional)`
+    /// of the granted entries.
+    pub fn grant_queued(&mut self, time: i64) -> Vec<(usize, f64)> {
+        if let ExhaustedCapital::Queue { max_wait_ms } = self.config.on_exhausted {
+            let before = self.queue.len();
+            self.queue.retain(|e| time - e.time <= max_wait_ms);
+            self.stats.expired += (before - self.queue.len()) as u32;
+        }
+        let mut granted = Vec::new();
+        while let Some(entry) = self.queue.front().copied() {
+            if entry.notional > self.free() {
+                break;
+            }
+            self.queue.pop_front();
+            self.reserve(entry.notional);
+            granted.push((entry.strategy, entry.notional));
+        }
+        granted
+    }
+
+    fn reserve(&mut self, notional: f64) {
+        self.reserved += notional;
+        self.stats.granted += 1;
+        self.stats.peak_reserved = self.stats.peak_reserved.max(self.reserved);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocator_rejects_or_queues_when_exhausted() {
+        let mut pool = CapitalAllocator::new(CapitalConfig {
+            capital: 1_000.0,
+            on_exhausted: ExhaustedCapital::Reject,
+        });
+        assert_eq!(pool.request(0, 600.0, 0), Allocation::Granted);
+        assert_eq!(pool.request(1, 600.0, 0), Allocation::Rejected);
+        assert_eq!(pool.request(1, 400.0, 0), Allocation::Granted);
+        assert_eq!(pool.free(), 0.0);
+        // A loss shrinks the pool for the next entries
+        pool.release(600.0, -100.0);
+        assert_eq!((pool.equity(), pool.free()), (900.0, 500.0));
+        assert_eq!(pool.request(0, 600.0, 1), Allocation::Rejected);
+        assert_eq!(pool.stats().rejected, 2);
+        assert_eq!(pool.stats().peak_reserved, 1_000.0);
+
+        let mut pool = CapitalAllocator::new(CapitalConfig {
+            capital: 1_000.0,
+            on_exhausted: ExhaustedCapital::Queue { max_wait_ms: 100 },
+        });
+        assert_eq!(pool.request(0, 800.0, 0), Allocation::Granted);
+        assert_eq!(pool.request(1, 500.0, 10), Allocation::Queued);
+        // Waiting entries are served first, even by a request that would fit
+        assert_eq!(pool.request(2, 100.0, 20), Allocation::Queued);
+        assert!(pool.grant_queued(30).is_empty());
+        pool.release(800.0, 50.0);
+        assert_eq!(pool.grant_queued(40), vec![(1, 500.0), (2, 100.0)]);
+
+        // Expired and cancelled entries never fill
+        assert_eq!(pool.request(3, 1_000.0, 50), Allocation::Queued);
+        assert_eq!(pool.request(4, 1_000.0, 60), Allocation::Queued);
+        assert!(pool.cancel(4));
+        assert!(!pool.is_queued(4));
+        pool.release(500.0, 0.0);
+        assert!(pool.grant_queued(200).is_empty());
+        let stats = pool.stats();
+        assert_eq!(
+            (stats.granted, stats.queued, stats.expired, stats.cancelled),
+            (3, 4, 1, 1)
+        );
+
+        assert!(CapitalConfig::default().validate().is_ok());
+        assert!(CapitalConfig {
+            capital: 0.0,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Equity and drawdown charts of stored results
+//!
+//! A stored record keeps its metrics but not its equity curve, so a chart replays the
+//! record's strategy the way re-validation does: same symbol, window ending now, sizing
+//! mode and probability model. The mark-to-market equity of every bar is drawn on top,
+//! the drawdown from the running peak below, as PNG or SVG. plotters is built without
+//! a font backend, so the charts carry no text: the title and figures are the
+//! record's, next to the image in the export or the UI.
+
+use std::path::Path;
+
+use anyhow::{anyhow, bail};
+use persistence::repository::DiscoveryBacktestRecord;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::api::BinanceClient;
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::discovery::{parse_sizing_mode, record_backtest, DiscoveryStrategyType, SizingMode};
+use crate::fees::PolymarketFeeConfig;
+use crate::probability::ProbabilityModelConfig;
+use crate::types::Kline;
+use crate::validation::validation_strategy;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Capital the replay starts from, as in discovery
+const INITIAL_CAPITAL: f64 = 10_000.0;
+
+/// Size of a rendered chart, in pixels
+pub const CHART_SIZE: (u32, u32) = (960, 540);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    Png,
+    Svg,
+}
+
+impl ChartFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// Mark-to-market equity of a replayed backtest, one point per bar
+#[derive(Debug, Clone, Default)]
+pub struct EquityCurve {
+    /// (bar open time in ms, equity)
+    pub points: Vec<(i64, f64)>,
+}
+
+impl EquityCurve {
+    /// Drawdown from the running peak at each point, in %
+    pub fn drawdown_pct(&self) -> Vec<(i64, f64)> {
+        let mut peak = f64::MIN;
+        self.points
+            .iter()
+            .map(|&(time, equity)| {
+                peak = peak.max(equity);
+                let drawdown = if peak > 0.0 {
+                    (peak - equity) / peak * 100.0
+                } else {
+                    0.0
+                };
+                (time, drawdown)
+            })
+            .collect()
+    }
+}
+
+/// Strategy of a stored record, if it has a single-symbol equity curve to draw
+pub fn chart_strategy(record: &DiscoveryBacktestRecord) -> Result<DiscoveryStrategyType, String> {
+    let strategy = validation_strategy(record)?;
+    if strategy.is_gabagool() {
+        return Err(format!(
+            "{} runs its own arbitrage engine and has no bar equity curve",
+            record.strategy_name
+        ));
+    }
+    Ok(strategy)
+}
+
+/// Equity curve of `strategy` backtested on `klines` like discovery does (10 000
+/// capital, 10 % base position, the strategy's trailing stop)
+pub fn equity_curve(
+    strategy: &DiscoveryStrategyType,
+    klines: &[Kline],
+    symbol: &str,
+    sizing_mode: SizingMode,
+    fee_config: &PolymarketFeeConfig,
+) -> EquityCurve {
+    let recording = record_backtest(strategy, klines, symbol, sizing_mode, fee_config);
+    EquityCurve {
+        points: recording.equity,
+    }
+}
+
+/// Replay a stored strategy over its `days` window ending now
+pub async fn record_equity_curve(
+    record: &DiscoveryBacktestRecord,
+    binance: &BinanceClient,
+) -> anyhow::Result<EquityCurve> {
+    let strategy = chart_strategy(record).map_err(anyhow::Error::msg)?;
+
+    let end_time = chrono::Utc::now().timestamp_millis();
+    let (klines, quality) = fetch_checked_klines(
+        binance,
+        &record.symbol,
+        "15m",
+        end_time - record.days * DAY_MS,
+        end_time,
+        &DataQualityConfig::default(),
+    )
+    .await?;
+    quality.warn_if_degraded();
+    if klines.is_empty() {
+        bail!("No klines for {}", record.symbol);
+    }
+
+    let fee_config = PolymarketFeeConfig {
+        probability_model: ProbabilityModelConfig::from_name(record.probability_model.as_deref()),
+        ..PolymarketFeeConfig::default()
+    };
+    Ok(equity_curve(
+        &strategy,
+        &klines,
+        &record.symbol,
+        parse_sizing_mode(&record.sizing_mode),
+        &fee_config,
+    ))
+}
+
+/// Draw `curve` to `path` (created or overwritten) as a `CHART_SIZE` image
+pub fn render_chart(curve: &EquityCurve, format: ChartFormat, path: &Path) -> anyhow::Result<()> {
+    if curve.points.is_empty() {
+        bail!("Empty equity curve");
+    }
+    let drawn = match format {
+        ChartFormat::Png => {
+            let root = BitMapBackend::new(path, CHART_SIZE).into_drawing_area();
+            draw(root, curve).map_err(|e| e.to_string())
+        }
+        ChartFormat::Svg => {
+            let root = SVGBackend::new(path, CHART_SIZE).into_drawing_area();
+            draw(root, curve).map_err(|e| e.to_string())
+        }
+    };
+    drawn.map_err(|e| anyhow!("Chart rendering failed: {}", e))
+}
+
+/// Equity (blue, over the grey starting capital) on the upper two thirds, drawdown
+/// (red, down from 0) below
+fn draw<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    curve: &EquityCurve,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    root.fill(&WHITE)?;
+    let (upper, lower) = root.split_vertically(CHART_SIZE.1 * 2 / 3);
+
+    let first = curve.points[0].0;
+    let last = curve.points[curve.points.len() - 1].0.max(first + 1);
+    let (low, high) = curve
+        .points
+        .iter()
+        .fold((INITIAL_CAPITAL, INITIAL_CAPITAL), |(lo, hi), &(_, e)| {
+            (lo.min(e), hi.max(e))
+        });
+    let pad = ((high - low) * 0.05).max(1.0);
+
+    let mut equity = ChartBuilder::on(&upper)
+        .margin(16)
+        .build_cartesian_2d(first..last, (low - pad)..(high + pad))?;
+    equity.configure_mesh().x_labels(8).y_labels(6).draw()?;
+    equity.draw_series(LineSeries::new(
+        [(first, INITIAL_CAPITAL), (last, INITIAL_CAPITAL)],
+        BLACK.mix(0.3),
+    ))?;
+    equity.draw_series(LineSeries::new(
+        curve.points.iter().copied(),
+        BLUE.stroke_width(2),
+    ))?;
+
+    let drawdown = curve.drawdown_pct();
+    let deepest = drawdown.iter().map(|&(_, d)| d).fold(0.0, f64::max);
+    let mut below = ChartBuilder::on(&lower)
+        .margin(16)
+        .build_cartesian_2d(first..last, -(deepest * 1.05).max(1.0)..0.0)?;
+    below.configure_mesh().x_labels(8).y_labels(4).draw()?;
+    below.draw_series(
+        AreaSeries::new(drawdown.iter().map(|&(t, d)| (t, -d)), 0.0, RED.mix(0.25))
+            .border_style(RED),
+    )?;
+
+    root.present()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{run_backtest, BacktestLimits};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_equity_curve_replays_and_renders() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 2_000,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let fees = PolymarketFeeConfig::default();
+        let curve = equity_curve(&rsi, &klines, "SYNTH", SizingMode::Fixed, &fees);
+        assert_eq!(curve.points.len(), klines.len());
+        assert_eq!(curve.points[0].0, klines[0].open_time);
+
+        // The curve is the backtest's: its deepest drawdown is the reported one, and
+        // it ends on the net PnL less the last exit fee at most
+        let result = run_backtest(
+            &rsi,
+            &klines,
+            "SYNTH",
+            SizingMode::Fixed,
+            &BacktestLimits::default(),
+        );
+        let deepest = curve
+            .drawdown_pct()
+            .iter()
+            .map(|&(_, d)| d)
+            .fold(0.0, f64::max);
+        let reported: f64 = result.max_drawdown_pct.try_into().unwrap();
+        assert!((deepest - reported).abs() < 1e-6, "{deepest} vs {reported}");
+        let net_pnl: f64 = result.net_pnl.try_into().unwrap();
+        let last = curve.points.last().unwrap().1 - INITIAL_CAPITAL;
+        assert!((last - net_pnl).abs() < 5.0, "{last} vs {net_pnl}");
+
+        let dir = std::env::temp_dir().join(format!("poly-chart-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let png = dir.join("chart.png");
+        render_chart(&curve, ChartFormat::Png, &png).unwrap();
+        assert_eq!(&std::fs::read(&png).unwrap()[1..4], b"PNG");
+        let svg = dir.join("chart.svg");
+        render_chart(&curve, ChartFormat::Svg, &svg).unwrap();
+        assert!(std::fs::read_to_string(&svg).unwrap().starts_with("<svg"));
+        assert!(render_chart(&EquityCurve::default(), ChartFormat::Png, &png).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+//! Daily PnL of stored results, for calendar heatmaps
+//!
+//! A stored record keeps its metrics but not its trades, so its daily PnL comes from a
+//! replay, like its chart: the record's strategy on its symbol over its `days` window
+//! ending now, under its sizing mode and probability model. Each trade's PnL counts on
+//! the UTC day it closed on. The server stores the days in `daily_pnl` on the first
+//! request and serves them from there.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context};
+use chrono::DateTime;
+use persistence::repository::{DailyPnlRecord, DiscoveryBacktestRecord};
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::api::BinanceClient;
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::discovery::{parse_sizing_mode, record_backtest, DiscoveryStrategyType};
+use crate::fees::PolymarketFeeConfig;
+use crate::probability::ProbabilityModelConfig;
+use crate::types::BacktestTrade;
+use crate::validation::validation_strategy;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Strategy of a stored record, if it has single-symbol trades to replay
+pub fn daily_pnl_strategy(
+    record: &DiscoveryBacktestRecord,
+) -> Result<DiscoveryStrategyType, String> {
+    let strategy = validation_strategy(record)?;
+    if strategy.is_gabagool() {
+        return Err(format!(
+            "{} runs its own arbitrage engine and has no bar trades",
+            record.strategy_name
+        ));
+    }
+    Ok(strategy)
+}
+
+/// Trades of `backtest_id` summed per UTC day of their exit, oldest first
+pub fn aggregate_daily_pnl(backtest_id: i64, trades: &[BacktestTrade]) -> Vec<DailyPnlRecord> {
+    let mut days: BTreeMap<String, DailyPnlRecord> = BTreeMap::new();
+    for trade in trades {
+        let Some(exit) = DateTime::from_timestamp_millis(trade.exit_time) else {
+            continue;
+        };
+        let day = exit.date_naive().to_string();
+        let entry = days.entry(day.clone()).or_insert(DailyPnlRecord {
+            backtest_id,
+            day,
+            pnl: 0.0,
+            trades: 0,
+            winning_trades: 0,
+        });
+        let pnl = trade.pnl.to_f64().unwrap_or(0.0);
+        entry.pnl += pnl;
+        entry.trades += 1;
+        entry.winning_trades += (pnl > 0.0) as i64;
+    }
+    days.into_values().collect()
+}
+
+/// Replay a stored strategy over its `days` window ending now and sum its trades per day
+pub async fn record_daily_pnl(
+    record: &DiscoveryBacktestRecord,
+    binance: &BinanceClient,
+) -> anyhow::Result<Vec<DailyPnlRecord>> {
+    let backtest_id = record.id.context("record has no id")?;
+    let strategy = daily_pnl_strategy(record).map_err(anyhow::Error::msg)?;
+
+    let end_time = chrono::Utc::now().timestamp_millis();
+    let (klines, quality) = fetch_checked_klines(
+        binance,
+        &record.symbol,
+        "15m",
+        end_time - record.days * DAY_MS,
+        end_time,
+        &DataQualityConfig::default(),
+    )
+    .await?;
+    quality.warn_if_degraded();
+    if klines.is_empty() {
+        bail!("No klines for {}", record.symbol);
+    }
+
+    let fee_config = PolymarketFeeConfig {
+        probability_model: ProbabilityModelConfig::from_name(record.probability_model.as_deref()),
+        ..PolymarketFeeConfig::default()
+    };
+    let recording = record_backtest(
+        &strategy,
+        &klines,
+        &record.symbol,
+        parse_sizing_mode(&record.sizing_mode),
+        &fee_config,
+    );
+    Ok(aggregate_daily_pnl(backtest_id, &recording.trades))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::SizingMode;
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+    use crate::types::{ExitReason, TradeSide};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn trade(exit_time: i64, pnl: Decimal) -> BacktestTrade {
+        BacktestTrade {
+            entry_time: 0,
+            exit_time,
+            side: TradeSide::Buy,
+            entry_price: dec!(100),
+            exit_price: dec!(100),
+            size: dec!(1),
+            pnl,
+            pnl_pct: Decimal::ZERO,
+            exit_reason: ExitReason::Signal,
+        }
+    }
+
+    #[test]
+    fn test_trades_summed_per_exit_day() {
+        // 2026-10-01 00:00 UTC
+        let day = 1_790_812_800_000;
+        let trades = [
+            trade(day + 3_600_000, dec!(12.5)),
+            trade(day + DAY_MS - 1, dec!(-2.5)),
+            trade(day + 2 * DAY_MS, dec!(-4)),
+        ];
+        let days = aggregate_daily_pnl(7, &trades);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].day, "2026-10-01");
+        assert_eq!(
+            (days[0].pnl, days[0].trades, days[0].winning_trades),
+            (10.0, 2, 1)
+        );
+        assert_eq!(days[1].day, "2026-10-03");
+        assert_eq!(
+            (days[1].pnl, days[1].trades, days[1].winning_trades),
+            (-4.0, 1, 0)
+        );
+        assert!(days.iter().all(|d| d.backtest_id == 7));
+
+        // The days of a replay add up to the PnL of its trades
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 2_000,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let recording = record_backtest(
+            &rsi,
+            &klines,
+            "SYNTH",
+            SizingMode::Fixed,
+            &PolymarketFeeConfig::default(),
+        );
+        assert!(!recording.trades.is_empty());
+        let days = aggregate_daily_pnl(1, &recording.trades);
+        let total: f64 = recording
+            .trades
+            .iter()
+            .map(|t| t.pnl.to_f64().unwrap())
+            .sum();
+        let summed: f64 = days.iter().map(|d| d.pnl).sum();
+        assert!((summed - total).abs() < 1e-6);
+        assert_eq!(
+            days.iter().map(|d| d.trades).sum::<i64>(),
+            recording.trades.len() as i64
+        );
+    }
+}
@@ -0,0 +1,524 @@
+//! Data-quality checks of fetched klines
+//!
+//! Binance occasionally returns series with holes (exchange maintenance, pagination
+//! hiccups), repeated candles, long zero-volume stretches on illiquid pairs or a
+//! single absurd print. `check_klines` measures all four and condenses them into a
+//! 0-100 score; `fetch_checked_klines` fetches a symbol, removes duplicates, checks
+//! the series and can re-fetch its gaps once before checking it again. `diff_klines`
+//! compares a cached series with a fresh fetch of the same window.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use persistence::repository::KlineQualityRecord;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::api::BinanceClient;
+use crate::indicators::close_f64;
+use crate::types::Kline;
+
+/// Below this score a series is reported as degraded
+pub const DEGRADED_SCORE: f64 = 95.0;
+
+/// At most this many gaps are re-fetched per series
+const MAX_REPAIRED_GAPS: usize = 50;
+
+/// Thresholds of the kline checks
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DataQualityConfig {
+    /// Move from the previous close (close, high or low), in %, counted as a spike
+    pub spike_pct: f64,
+    /// Consecutive zero-volume bars counted as a stretch (isolated ones are normal)
+    pub min_zero_volume_run: usize,
+    /// Re-fetch missing bars once before the final check
+    pub repair_gaps: bool,
+}
+
+impl Default for DataQualityConfig {
+    fn default() -> Self {
+        Self {
+            spike_pct: 25.0,
+            min_zero_volume_run: 4,
+            repair_gaps: false,
+        }
+    }
+}
+
+/// Bars missing between two consecutive klines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct KlineGap {
+    /// Open time of the first and last missing bar
+    pub from_ms: i64,
+    pub to_ms: i64,
+    pub missing_bars: i64,
+}
+
+/// Quality of one symbol's klines
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DataQualityReport {
+    pub symbol: String,
+    /// 0-100, share of the expected bars that are present and clean
+    pub score: f64,
+    /// Bars after removing duplicates
+    pub bars: usize,
+    /// Bars between the first and last kline at the series interval
+    pub expected_bars: usize,
+    pub missing_bars: usize,
+    pub gaps: usize,
+    pub largest_gap_bars: usize,
+    /// Repeated open times (the last copy is kept)
+    pub duplicates: usize,
+    /// Bars inside zero-volume stretches
+    pub zero_volume_bars: usize,
+    pub zero_volume_stretches: usize,
+    pub spikes: usize,
+    /// Missing bars recovered by re-fetching the gaps
+    pub repaired_bars: usize,
+}
+
+impl DataQualityReport {
+    pub fn is_degraded(&self) -> bool {
+        self.score < DEGRADED_SCORE
+    }
+
+    /// Row stored with the run in `kline_quality`
+    pub fn to_record(&self, run_id: &str) -> KlineQualityRecord {
+        KlineQualityRecord {
+            run_id: run_id.to_string(),
+            symbol: self.symbol.clone(),
+            score: self.score,
+            bars: self.bars as i64,
+            expected_bars: self.expected_bars as i64,
+            missing_bars: self.missing_bars as i64,
+            gaps: self.gaps as i64,
+            duplicates: self.duplicates as i64,
+            zero_volume_bars: self.zero_volume_bars as i64,
+            spikes: self.spikes as i64,
+            repaired_bars: self.repaired_bars as i64,
+            created_at: None,
+        }
+    }
+
+    /// Log a warning when the series is degraded
+    pub fn warn_if_degraded(&self) {
+        if self.is_degraded() {
+            warn!(
+                symbol = %self.symbol,
+                score = format!("{:.1}", self.score),
+                missing = self.missing_bars,
+                duplicates = self.duplicates,
+                zero_volume = self.zero_volume_bars,
+                spikes = self.spikes,
+                "Degraded kline data"
+            );
+        }
+    }
+}
+
+/// Milliseconds of a Binance interval (`15m`, `1h`, `1d`, `1w`)
+pub fn interval_ms(interval: &str) -> Option<i64> {
+    let split = interval.len().checked_sub(1)?;
+    let (count, unit) = interval.split_at(split);
+    let unit_ms = match unit {
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 604_800_000,
+        _ => return None,
+    };
+    count
+        .parse::<i64>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(|n| n * unit_ms)
+}
+
+/// Sort by open time and drop repeated candles, keeping the last copy.
+/// Returns the number of duplicates removed.
+pub fn dedupe_klines(klines: &mut Vec<Kline>) -> usize {
+    let before = klines.len();
+    let by_open_time: BTreeMap<i64, Kline> = klines.drain(..).map(|k| (k.open_time, k)).collect();
+    klines.extend(by_open_time.into_values());
+    before - klines.len()
+}
+
+/// Holes of a sorted, deduplicated series
+pub fn find_gaps(klines: &[Kline], interval_ms: i64) -> Vec<KlineGap> {
+    klines
+        .windows(2)
+        .filter_map(|pair| {
+            let missing = (pair[1].open_time - pair[0].open_time) / interval_ms - 1;
+            (missing > 0).then(|| KlineGap {
+                from_ms: pair[0].open_time + interval_ms,
+                to_ms: pair[0].open_time + missing * interval_ms,
+                missing_bars: missing,
+            })
+        })
+        .collect()
+}
+
+/// Check a sorted, deduplicated series (`duplicates`: copies already removed from it)
+pub fn check_klines(
+    symbol: &str,
+    klines: &[Kline],
+    interval_ms: i64,
+    duplicates: usize,
+    config: &DataQualityConfig,
+) -> DataQualityReport {
+    let gaps = find_gaps(klines, interval_ms);
+    let missing_bars: usize = gaps.iter().map(|g| g.missing_bars as usize).sum();
+    let largest_gap_bars = gaps
+        .iter()
+        .map(|g| g.missing_bars as usize)
+        .max()
+        .unwrap_or(0);
+
+    let mut zero_volume_bars = 0;
+    let mut zero_volume_stretches = 0;
+    let mut run = 0;
+    // Trailing `None` closes a stretch that reaches the last bar
+    for kline in klines.iter().map(Some).chain(std::iter::once(None)) {
+        if kline.is_some_and(|k| k.volume.is_zero()) {
+            run += 1;
+            continue;
+        }
+        if run >= config.min_zero_volume_run.max(1) {
+            zero_volume_bars += run;
+            zero_volume_stretches += 1;
+        }
+        run = 0;
+    }
+
+    let spikes = klines
+        .windows(2)
+        .filter(|pair| is_spike(&pair[0], &pair[1], config.spike_pct))
+        .count();
+
+    let expected_bars = klines.len() + missing_bars;
+    let bad = missing_bars + duplicates + zero_volume_bars + spikes;
+    let score = if expected_bars == 0 {
+        0.0
+    } else {
+        (100.0 * (1.0 - bad as f64 / expected_bars as f64)).clamp(0.0, 100.0)
+    };
+
+    DataQualityReport {
+        symbol: symbol.to_string(),
+        score,
+        bars: klines.len(),
+        expected_bars,
+        missing_bars,
+        gaps: gaps.len(),
+        largest_gap_bars,
+        duplicates,
+        zero_volume_bars,
+        zero_volume_stretches,
+        spikes,
+        repaired_bars: 0,
+    }
+}
+
+fn is_spike(prev: &Kline, kline: &Kline, spike_pct: f64) -> bool {
+    let reference = close_f64(prev);
+    if reference <= 0.0 || kline.close <= Decimal::ZERO || kline.low <= Decimal::ZERO {
+        return true;
+    }
+    let limit = spike_pct / 100.0;
+    [kline.close, kline.high, kline.low]
+        .iter()
+        .filter_map(|p| p.to_f64())
+        .any(|p| (p / reference - 1.0).abs() > limit)
+}
+
+/// Fetch a symbol's klines and check them. With `repair_gaps`, the gaps found are
+/// re-fetched once (the largest `MAX_REPAIRED_GAPS`) and the merged series is
+/// checked again. Returns the sorted, deduplicated klines and their report.
+pub async fn fetch_checked_klines(
+    binance: &BinanceClient,
+    symbol: &str,
+    interval: &str,
+    start_time: i64,
+    end_time: i64,
+    config: &DataQualityConfig,
+) -> Result<(Vec<Kline>, DataQualityReport)> {
+    let step = interval_ms(interval)
+        .ok_or_else(|| anyhow::anyhow!("Unsupported kline interval {interval}"))?;
+    let mut klines = binance
+        .get_klines_paginated(symbol, interval, start_time, end_time)
+        .await?;
+    let duplicates = dedupe_klines(&mut klines);
+    let report = check_klines(symbol, &klines, step, duplicates, config);
+    if !config.repair_gaps || report.missing_bars == 0 {
+        return Ok((klines, report));
+    }
+
+    let mut gaps = find_gaps(&klines, step);
+    gaps.sort_by_key(|g| std::cmp::Reverse(g.missing_bars));
+    gaps.truncate(MAX_REPAIRED_GAPS);
+    for gap in &gaps {
+        match binance
+            .get_klines_paginated(symbol, interval, gap.from_ms, gap.to_ms + step - 1)
+            .await
+        {
+            Ok(fresh) => klines.extend(fresh),
+            Err(e) => warn!(symbol, from = gap.from_ms, error = %e, "Gap re-fetch failed"),
+        }
+    }
+    dedupe_klines(&mut klines);
+
+    let mut repaired = check_klines(symbol, &klines, step, duplicates, config);
+    repaired.repaired_bars = report.missing_bars.saturating_sub(repaired.missing_bars);
+    info!(
+        symbol,
+        repaired = repaired.repaired_bars,
+        still_missing = repaired.missing_bars,
+        "Re-fetched kline gaps"
+    );
+    Ok((klines, repaired))
+}
+
+/// Most revised bars listed in a `KlineDiff` (all are counted)
+pub const MAX_LISTED_REVISIONS: usize = 500;
+
+/// Bar whose values changed between the cached copy and a fresh fetch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RevisedKline {
+    pub open_time: i64,
+    /// `open`, `high`, `low`, `close`, `volume` or `close_time`
+    pub fields: Vec<String>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub cached: Kline,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub live: Kline,
+}
+
+/// Differences between cached klines and a fresh fetch of the same window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct KlineDiff {
+    pub cached_bars: usize,
+    pub live_bars: usize,
+    /// Bars identical in both
+    pub matching_bars: usize,
+    pub revised_bars: usize,
+    /// The first `MAX_LISTED_REVISIONS` revised bars, oldest first
+    pub revised: Vec<RevisedKline>,
+    /// Runs of bars the fresh fetch has and the cache lacks
+    pub missing_in_cache: Vec<KlineGap>,
+    /// Runs of cached bars the fresh fetch no longer returns
+    pub missing_live: Vec<KlineGap>,
+}
+
+impl KlineDiff {
+    /// Both series hold the same bars with the same values
+    pub fn is_identical(&self) -> bool {
+        self.revised_bars == 0 && self.missing_in_cache.is_empty() && self.missing_live.is_empty()
+    }
+}
+
+/// Compare two sorted, deduplicated series bar by bar (matched on open time)
+pub fn diff_klines(cached: &[Kline], live: &[Kline], interval_ms: i64) -> KlineDiff {
+    let mut diff = KlineDiff {
+        cached_bars: cached.len(),
+        live_bars: live.len(),
+        ..Default::default()
+    };
+    let mut not_cached = Vec::new();
+    let mut not_live = Vec::new();
+    let (mut c, mut l) = (0, 0);
+    while c < cached.len() || l < live.len() {
+        match (cached.get(c), live.get(l)) {
+            (Some(old), Some(new)) if old.open_time == new.open_time => {
+                let fields = changed_fields(old, new);
+                if fields.is_empty() {
+                    diff.matching_bars += 1;
+                } else {
+                    diff.revised_bars += 1;
+                    if diff.revised.len() < MAX_LISTED_REVISIONS {
+                        diff.revised.push(RevisedKline {
+                            open_time: old.open_time,
+                            fields,
+                            cached: old.clone(),
+                            live: new.clone(),
+                        });
+                    }
+                }
+                c += 1;
+                l += 1;
+            }
+            (Some(old), Some(new)) if old.open_time < new.open_time => {
+                not_live.push(old.open_time);
+                c += 1;
+            }
+            (Some(old), None) => {
+                not_live.push(old.open_time);
+                c += 1;
+            }
+            (_, Some(new)) => {
+                not_cached.push(new.open_time);
+                l += 1;
+            }
+            (None, None) => break,
+        }
+    }
+    diff.missing_in_cache = group_runs(&not_cached, interval_ms);
+    diff.missing_live = group_runs(&not_live, interval_ms);
+    diff
+}
+
+fn changed_fields(old: &Kline, new: &Kline) -> Vec<String> {
+    [
+        ("open", old.open != new.open),
+        ("high", old.high != new.high),
+        ("low", old.low != new.low),
+        ("close", old.close != new.close),
+        ("volume", old.volume != new.volume),
+        ("close_time", old.close_time != new.close_time),
+    ]
+    .into_iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(field, _)| field.to_string())
+    .collect()
+}
+
+/// Consecutive open times (one interval apart) as gaps
+fn group_runs(open_times: &[i64], interval_ms: i64) -> Vec<KlineGap> {
+    let mut runs: Vec<KlineGap> = Vec::new();
+    for &time in open_times {
+        match runs.last_mut() {
+            Some(run) if time == run.to_ms + interval_ms => {
+                run.to_ms = time;
+                run.missing_bars += 1;
+            }
+            _ => runs.push(KlineGap {
+                from_ms: time,
+                to_ms: time,
+                missing_bars: 1,
+            }),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const STEP: i64 = 900_000;
+
+    fn kline(i: i64, close: Decimal, volume: Decimal) -> Kline {
+        Kline {
+            open_time: i * STEP,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume,
+            close_time: (i + 1) * STEP - 1,
+        }
+    }
+
+    #[test]
+    fn test_interval_ms() {
+        assert_eq!(interval_ms("15m"), Some(STEP));
+        assert_eq!(interval_ms("4h"), Some(4 * 3_600_000));
+        assert_eq!(interval_ms("1d"), Some(86_400_000));
+        assert_eq!(interval_ms("0m"), None);
+        assert_eq!(interval_ms("m"), None);
+        assert_eq!(interval_ms("15x"), None);
+    }
+
+    #[test]
+    fn test_check_klines_flags_every_defect() {
+        let config = DataQualityConfig::default();
+        let clean: Vec<Kline> = (0..100).map(|i| kline(i, dec!(100), dec!(5))).collect();
+        let report = check_klines("BTCUSDT", &clean, STEP, 0, &config);
+        assert_eq!(report.score, 100.0);
+        assert_eq!(report.expected_bars, 100);
+        assert!(!report.is_degraded());
+
+        // Bars 10-12 missing, bar 20 repeated, bars 30-34 without volume,
+        // bar 2 without volume (isolated, fine), bar 50 printing 10x the price
+        let mut klines: Vec<Kline> = (0..100)
+            .filter(|i| !(10..13).contains(i))
+            .map(|i| {
+                let volume = if (30..35).contains(&i) || i == 2 {
+                    dec!(0)
+                } else {
+                    dec!(5)
+                };
+                let close = if i == 50 { dec!(1000) } else { dec!(100) };
+                kline(i, close, volume)
+            })
+            .collect();
+        klines.push(kline(20, dec!(100), dec!(7)));
+
+        let duplicates = dedupe_klines(&mut klines);
+        assert_eq!(duplicates, 1);
+        assert_eq!(klines[17].volume, dec!(7), "last copy kept");
+        assert!(klines.windows(2).all(|w| w[0].open_time < w[1].open_time));
+
+        let report = check_klines("BTCUSDT", &klines, STEP, duplicates, &config);
+        assert_eq!(report.bars, 97);
+        assert_eq!(report.expected_bars, 100);
+        assert_eq!(
+            (report.missing_bars, report.gaps, report.largest_gap_bars),
+            (3, 1, 3)
+        );
+        assert_eq!(
+            (report.zero_volume_bars, report.zero_volume_stretches),
+            (5, 1)
+        );
+        // Into the spike and back out of it
+        assert_eq!(report.spikes, 2);
+        assert_eq!(report.score, 89.0);
+        assert!(report.is_degraded());
+
+        let gaps = find_gaps(&klines, STEP);
+        assert_eq!(
+            gaps,
+            vec![KlineGap {
+                from_ms: 10 * STEP,
+                to_ms: 12 * STEP,
+                missing_bars: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_klines_reports_revisions_and_gaps() {
+        let cached: Vec<Kline> = (0..20)
+            .filter(|i| !(5..8).contains(i))
+            .map(|i| kline(i, dec!(100), dec!(5)))
+            .collect();
+        // Bar 10 revised (volume of a candle cached while still open), bars 5-7 only
+        // live, bars 18-19 not returned any more
+        let live: Vec<Kline> = (0..18)
+            .map(|i| kline(i, dec!(100), if i == 10 { dec!(9) } else { dec!(5) }))
+            .collect();
+        let diff = diff_klines(&cached, &live, STEP);
+        assert_eq!((diff.cached_bars, diff.live_bars), (17, 18));
+        assert_eq!((diff.matching_bars, diff.revised_bars), (14, 1));
+        assert_eq!(diff.revised[0].open_time, 10 * STEP);
+        assert_eq!(diff.revised[0].fields, vec!["volume"]);
+        assert_eq!(
+            diff.missing_in_cache,
+            vec![KlineGap {
+                from_ms: 5 * STEP,
+                to_ms: 7 * STEP,
+                missing_bars: 3
+            }]
+        );
+        assert_eq!(diff.missing_live[0].missing_bars, 2);
+        assert!(!diff.is_identical());
+        assert!(diff_klines(&live, &live, STEP).is_identical());
+    }
+}
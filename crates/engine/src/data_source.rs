@@ -0,0 +1,452 @@
+//! Kline data sources — Binance or local CSV / Parquet files
+//!
+//! `DiscoveryRequest.data_source` is `binance` (default) or `file:<path>`. A file source
+//! is either one file or a directory holding one `<SYMBOL>.csv` / `<SYMBOL>.parquet`
+//! per symbol. Files need the columns `open_time` (Unix ms or s, or RFC 3339 in CSV),
+//! `open`, `high`, `low`, `close` and `volume`; `close_time` and `symbol` are optional.
+//! A file with a `symbol` column may hold several symbols (the feature export can be
+//! loaded back); a file without one can only feed a single-symbol run.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Float32Type, Float64Type, Int32Type, Int64Type, TimestampMillisecondType, TimestampSecondType,
+};
+use arrow_array::{Array, RecordBatch};
+use arrow_schema::{DataType, TimeUnit};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+
+use crate::types::Kline;
+
+/// Interval assumed when a file has fewer than two bars
+const DEFAULT_INTERVAL_MS: i64 = 900_000;
+
+/// Timestamps below this are taken as Unix seconds
+const MIN_MS_TIMESTAMP: i64 = 100_000_000_000;
+
+const REQUIRED_COLUMNS: [&str; 6] = ["open_time", "open", "high", "low", "close", "volume"];
+
+/// Where a run reads its klines from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DataSource {
+    #[default]
+    Binance,
+    /// CSV / Parquet file, or directory of per-symbol files
+    File(PathBuf),
+}
+
+impl DataSource {
+    /// `binance` or `file:<path>`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("binance") {
+            return Ok(Self::Binance);
+        }
+        match s.strip_prefix("file:") {
+            Some(path) if !path.trim().is_empty() => Ok(Self::File(PathBuf::from(path.trim()))),
+            _ => Err(format!(
+                "Invalid data source {:?}: use \"binance\" or \"file:<path>\"",
+                s
+            )),
+        }
+    }
+
+    /// Source of an optional request field (`None` = Binance)
+    pub fn from_option(s: Option<&str>) -> Result<Self, String> {
+        s.map_or(Ok(Self::Binance), Self::parse)
+    }
+
+    pub fn is_file(&self) -> bool {
+        matches!(self, Self::File(_))
+    }
+}
+
+/// Klines of `symbol` from a file source, sorted by open time (duplicates kept for
+/// the quality check). For a directory, `<dir>/<SYMBOL>.csv` or `.parquet` is read.
+pub fn load_klines_file(path: &Path, symbol: &str) -> Result<Vec<Kline>> {
+    let file = if path.is_dir() {
+        ["csv", "parquet"]
+            .iter()
+            .map(|ext| path.join(format!("{}.{}", symbol, ext)))
+            .find(|p| p.is_file())
+            .with_context(|| format!("no {symbol}.csv or {symbol}.parquet in {}", path.display()))?
+    } else {
+        path.to_path_buf()
+    };
+
+    let table = match file
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+    {
+        Some(ext) if ext == "csv" => read_csv(&file)?,
+        Some(ext) if ext == "parquet" => read_parquet(&file)?,
+        _ => bail!("{}: expected a .csv or .parquet file", file.display()),
+    };
+    table
+        .into_klines(symbol)
+        .with_context(|| format!("invalid klines in {}", file.display()))
+}
+
+/// Bar spacing of a series: the most frequent gap between consecutive open times
+pub fn infer_interval_ms(klines: &[Kline]) -> i64 {
+    let mut counts: HashMap<i64, usize> = HashMap::new();
+    for pair in klines.windows(2) {
+        let step = pair[1].open_time - pair[0].open_time;
+        if step > 0 {
+            *counts.entry(step).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(step, count)| (count, std::cmp::Reverse(step)))
+        .map_or(DEFAULT_INTERVAL_MS, |(step, _)| step)
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// One parsed row before validation
+struct RawBar {
+    symbol: Option<String>,
+    open_time: i64,
+    /// open, high, low, close, volume
+    ohlcv: [Decimal; 5],
+    close_time: Option<i64>,
+}
+
+#[derive(Default)]
+struct RawTable {
+    has_symbol: bool,
+    rows: Vec<RawBar>,
+}
+
+impl RawTable {
+    fn into_klines(self, symbol: &str) -> Result<Vec<Kline>> {
+        let mut rows: Vec<(usize, RawBar)> = if self.has_symbol {
+            self.rows
+                .into_iter()
+                .enumerate()
+                .filter(|(_, r)| {
+                    r.symbol
+                        .as_deref()
+                        .is_some_and(|s| s.eq_ignore_ascii_case(symbol))
+                })
+                .collect()
+        } else {
+            self.rows.into_iter().enumerate().collect()
+        };
+        if rows.is_empty() {
+            bail!("no rows for {}", symbol);
+        }
+        rows.sort_by_key(|(_, r)| r.open_time);
+
+        let mut klines = Vec::with_capacity(rows.len());
+        for (row, bar) in &rows {
+            let [open, high, low, close, volume] = bar.ohlcv;
+            // Data rows are numbered from 1
+            let row = row + 1;
+            if low <= Decimal::ZERO {
+                bail!("row {row}: prices must be positive");
+            }
+            if high < open.max(close) || low > open.min(close) {
+                bail!("row {row}: high/low do not bound open and close");
+            }
+            if volume < Decimal::ZERO {
+                bail!("row {row}: negative volume");
+            }
+            if bar.close_time.is_some_and(|t| t < bar.open_time) {
+                bail!("row {row}: close_time before open_time");
+            }
+            klines.push(Kline {
+                open_time: bar.open_time,
+                open,
+                high,
+                low,
+                close,
+                volume,
+                close_time: bar.close_time.unwrap_or_default(),
+            });
+        }
+
+        let interval = infer_interval_ms(&klines);
+        for (kline, (_, bar)) in klines.iter_mut().zip(&rows) {
+            if bar.close_time.is_none() {
+                kline.close_time = kline.open_time + interval - 1;
+            }
+        }
+        Ok(klines)
+    }
+}
+
+fn to_ms(t: i64) -> i64 {
+    if t.abs() < MIN_MS_TIMESTAMP {
+        t * 1000
+    } else {
+        t
+    }
+}
+
+fn read_csv(path: &Path) -> Result<RawTable> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    let (_, header) = lines.next().context("empty CSV file")?;
+    let header: Vec<String> = split_csv_line(header)
+        .iter()
+        .map(|h| h.to_lowercase())
+        .collect();
+    let index = |name: &str| header.iter().position(|h| h == name);
+    let mut required = [0usize; 6];
+    for (slot, name) in required.iter_mut().zip(REQUIRED_COLUMNS) {
+        *slot = index(name).with_context(|| format!("missing column {name}"))?;
+    }
+    let symbol_col = index("symbol");
+    let close_time_col = index("close_time");
+
+    let mut table = RawTable {
+        has_symbol: symbol_col.is_some(),
+        rows: Vec::new(),
+    };
+    for (line_no, line) in lines {
+        let fields = split_csv_line(line);
+        let field = |col: usize| -> Result<&str> {
+            fields
+                .get(col)
+                .map(String::as_str)
+                .with_context(|| format!("line {}: missing {}", line_no + 1, header[col]))
+        };
+        let parse_err = |col: usize| format!("line {}: invalid {}", line_no + 1, header[col]);
+
+        let mut ohlcv = [Decimal::ZERO; 5];
+        for (value, &col) in ohlcv.iter_mut().zip(&required[1..]) {
+            let raw = field(col)?;
+            *value = Decimal::from_str(raw)
+                .or_else(|_| Decimal::from_scientific(raw))
+                .with_context(|| parse_err(col))?;
+        }
+        table.rows.push(RawBar {
+            symbol: symbol_col.map(field).transpose()?.map(str::to_string),
+            open_time: parse_csv_time(field(required[0])?)
+                .with_context(|| parse_err(required[0]))?,
+            ohlcv,
+            close_time: close_time_col
+                .map(|col| parse_csv_time(field(col)?).with_context(|| parse_err(col)))
+                .transpose()?,
+        });
+    }
+    Ok(table)
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(|f| f.trim().trim_matches('"').to_string())
+        .collect()
+}
+
+/// Unix ms / s, or an RFC 3339 date
+fn parse_csv_time(raw: &str) -> Result<i64> {
+    if let Ok(t) = raw.parse::<i64>() {
+        return Ok(to_ms(t));
+    }
+    let date = chrono::DateTime::parse_from_rfc3339(raw)?;
+    Ok(date.timestamp_millis())
+}
+
+fn read_parquet(path: &Path) -> Result<RawTable> {
+    let file = File::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+    let mut table = RawTable::default();
+    for batch in reader {
+        let batch = batch?;
+        let column = |name: &str| batch.column_by_name(name);
+        for name in REQUIRED_COLUMNS {
+            column(name).with_context(|| format!("missing column {name}"))?;
+        }
+        table.has_symbol = column("symbol").is_some();
+        let base = table.rows.len();
+        for i in 0..batch.num_rows() {
+            let row = base + i + 1;
+            let mut ohlcv = [Decimal::ZERO; 5];
+            for (value, name) in ohlcv.iter_mut().zip(&REQUIRED_COLUMNS[1..]) {
+                *value = decimal_at(&batch, name, i)
+                    .with_context(|| format!("row {row}: invalid {name}"))?;
+            }
+            table.rows.push(RawBar {
+                symbol: column("symbol").and_then(|a| string_at(a.as_ref(), i)),
+                open_time: time_at(&batch, "open_time", i)
+                    .with_context(|| format!("row {row}: invalid open_time"))?,
+                ohlcv,
+                close_time: match column("close_time") {
+                    Some(_) => Some(
+                        time_at(&batch, "close_time", i)
+                            .with_context(|| format!("row {row}: invalid close_time"))?,
+                    ),
+                    None => None,
+                },
+            });
+        }
+    }
+    Ok(table)
+}
+
+fn string_at(array: &dyn Array, i: usize) -> Option<String> {
+    if array.is_null(i) {
+        return None;
+    }
+    match array.data_type() {
+        DataType::Utf8 => Some(array.as_string::<i32>().value(i).to_string()),
+        DataType::LargeUtf8 => Some(array.as_string::<i64>().value(i).to_string()),
+        _ => None,
+    }
+}
+
+fn decimal_at(batch: &RecordBatch, name: &str, i: usize) -> Result<Decimal> {
+    let array = batch.column_by_name(name).context("missing column")?;
+    if array.is_null(i) {
+        bail!("null value");
+    }
+    let value = match array.data_type() {
+        DataType::Float64 => Decimal::from_f64(array.as_primitive::<Float64Type>().value(i)),
+        DataType::Float32 => Decimal::from_f32(array.as_primitive::<Float32Type>().value(i)),
+        DataType::Int64 => Some(Decimal::from(array.as_primitive::<Int64Type>().value(i))),
+        DataType::Int32 => Some(Decimal::from(array.as_primitive::<Int32Type>().value(i))),
+        DataType::Utf8 | DataType::LargeUtf8 => {
+            string_at(array.as_ref(), i).and_then(|s| Decimal::from_str(&s).ok())
+        }
+        other => bail!("unsupported type {other}"),
+    };
+    value.context("not a finite number")
+}
+
+fn time_at(batch: &RecordBatch, name: &str, i: usize) -> Result<i64> {
+    let array = batch.column_by_name(name).context("missing column")?;
+    if array.is_null(i) {
+        bail!("null value");
+    }
+    Ok(match array.data_type() {
+        DataType::Int64 => to_ms(array.as_primitive::<Int64Type>().value(i)),
+        DataType::Int32 => to_ms(array.as_primitive::<Int32Type>().value(i) as i64),
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            array.as_primitive::<TimestampMillisecondType>().value(i)
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            array.as_primitive::<TimestampSecondType>().value(i) * 1000
+        }
+        other => bail!("unsupported type {other}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{ArrayRef, Float64Array, Int64Array, StringArray};
+    use arrow_schema::{Field, Schema};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("poly-import-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_data_source_parse() {
+        assert_eq!(DataSource::from_option(None), Ok(DataSource::Binance));
+        assert_eq!(DataSource::parse("Binance"), Ok(DataSource::Binance));
+        assert_eq!(
+            DataSource::parse("file:data/btc.csv"),
+            Ok(DataSource::File(PathBuf::from("data/btc.csv")))
+        );
+        assert!(DataSource::parse("file:").is_err());
+        assert!(DataSource::parse("kraken").is_err());
+    }
+
+    #[test]
+    fn test_load_csv_and_parquet() {
+        // Unsorted, seconds timestamps, an RFC 3339 date, no close_time
+        let csv = temp_path("BTCUSDT.csv");
+        std::fs::write(
+            &csv,
+            "open_time,open,high,low,close,volume\n\
+             1700000100,101,103,100,102,5\n\
+             1699999200,100,102,99,101,4.5\n\
+             2023-11-14T22:30:00Z,102,104,101,103,6\n",
+        )
+        .unwrap();
+        let klines = load_klines_file(&csv, "BTCUSDT").unwrap();
+        assert_eq!(
+            klines.iter().map(|k| k.open_time).collect::<Vec<_>>(),
+            [1_699_999_200_000, 1_700_000_100_000, 1_700_001_000_000]
+        );
+        assert_eq!(klines[0].close_time, 1_700_000_099_999);
+        assert_eq!(klines[1].volume, Decimal::from(5));
+        assert_eq!(infer_interval_ms(&klines), 900_000);
+
+        std::fs::write(&csv, "open_time,open,high,low,close\n1700000000,1,1,1,1\n").unwrap();
+        let err = format!("{:#}", load_klines_file(&csv, "BTCUSDT").unwrap_err());
+        assert!(err.contains("missing column volume"), "{err}");
+        std::fs::write(
+            &csv,
+            "open_time,open,high,low,close,volume\n1700000000,100,99,98,100,1\n",
+        )
+        .unwrap();
+        let err = format!("{:#}", load_klines_file(&csv, "BTCUSDT").unwrap_err());
+        assert!(err.contains("row 1: high/low"), "{err}");
+        std::fs::remove_file(&csv).ok();
+
+        // Multi-symbol Parquet (the feature export layout), hourly bars
+        const T0: i64 = 1_700_000_000_000;
+        let dir = temp_path("parquet");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ETHUSDT.parquet");
+        let schema = Arc::new(Schema::new(
+            std::iter::once(Field::new("symbol", DataType::Utf8, false))
+                .chain(std::iter::once(Field::new(
+                    "open_time",
+                    DataType::Int64,
+                    false,
+                )))
+                .chain(
+                    REQUIRED_COLUMNS[1..]
+                        .iter()
+                        .map(|c| Field::new(*c, DataType::Float64, false)),
+                )
+                .collect::<Vec<_>>(),
+        ));
+        let price = |v: f64| Arc::new(Float64Array::from(vec![v, v, v])) as ArrayRef;
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["ETHUSDT", "SOLUSDT", "ETHUSDT"])),
+                Arc::new(Int64Array::from(vec![T0, T0, T0 + 3_600_000])),
+                price(10.0),
+                price(12.0),
+                price(9.0),
+                price(11.0),
+                price(1.5),
+            ],
+        )
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let klines = load_klines_file(&dir, "ETHUSDT").unwrap();
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[1].close, Decimal::from(11));
+        assert_eq!(klines[1].close_time, T0 + 7_199_999);
+        assert!(load_klines_file(&dir, "BTCUSDT").is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -4,14 +4,20 @@
 //! in 2 phases: Phase 1 broad scan → Phase 2 refinement of top results.
 //! Uses Polymarket fees and composite scoring to rank results.
 
+use std::collections::{HashMap, VecDeque};
 use std::sync::{
     atomic::{AtomicBool, AtomicU32, Ordering},
-    Arc, RwLock,
+    Arc, Mutex, RwLock,
 };
+use std::time::{Duration, Instant};
 
 use chrono::Utc;
-use persistence::repository::discovery::{DiscoveryBacktestRecord, DiscoveryRepository};
+use persistence::repository::discovery::{
+    workspace_key, CycleSummaryRecord, DiscoveryBacktestRecord, DiscoveryRepository,
+    FamilyPerformance, RankKey, DEFAULT_RANK_ORDER,
+};
 use persistence::SqlitePool;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
@@ -19,11 +25,44 @@ use sha2::{Digest, Sha256};
 use rand::Rng;
 use tracing::{info, warn};
 
+use crate::adaptive::{adaptive_cycle_grid, ADAPTIVE_CYCLES, ADAPTIVE_PHASE_NAME};
 use crate::api::BinanceClient;
-use crate::fees::{calculate_taker_fee, PolymarketFeeConfig};
-use crate::gabagool::{GabagoolBacktestConfig, GabagoolBacktestEngine};
-use crate::indicators::{build_signal_generator, SignalGenerator};
-use crate::types::{BacktestTrade, Kline, TradeSide};
+use crate::candles::CandleTransform;
+use crate::data_quality::{
+    check_klines, dedupe_klines, fetch_checked_klines, DataQualityConfig, DataQualityReport,
+};
+use crate::data_source::{infer_interval_ms, load_klines_file, DataSource};
+use crate::distributed::{distribute_phase1, Coordinator};
+use crate::entry_filter::{filter_refinement_grid, REFINEMENT_FILTERS};
+use crate::error::{EngineError, EngineResult};
+use crate::fees::{calculate_taker_fee_f64, FeePreset, PolymarketFeeConfig};
+use crate::fills::{FillModel, ParticipationLimit, UnfilledRemainder, UnfilledStats};
+use crate::gabagool::{GabagoolBacktestConfig, GabagoolBacktestEngine, GabagoolWindow};
+use crate::grid_snapshot::CycleGrid;
+use crate::htf_filter::{htf_refinement_grid, HtfTrend, REFINEMENT_HTF_FILTERS};
+use crate::indicators::{
+    close_f64, IndicatorSeriesCache, SignalGenerator, SignalWithConfidence, SERIES_CACHE_CAPACITY,
+};
+use crate::local_search::{
+    local_search_starts, HillClimber, LOCAL_SEARCH_MAX_EVALS, LOCAL_SEARCH_TOP,
+};
+use crate::pacing::{Pacer, ScanPacing};
+use crate::pairs::{run_pair_spread_backtest, PairSpreadParams};
+use crate::population::{Individual, IslandModel, IslandStats, POPULATION_SIZE};
+use crate::probability::{resolve_probability_model, ProbabilityModel, ProbabilityModelConfig};
+use crate::result_stream::ResultSink;
+use crate::rotation::{run_rotation_backtest, KlineMatrix};
+use crate::sampling::latin_hypercube;
+use crate::sizing::{DrawdownThrottle, PositionSizer};
+use crate::store::{ModelScopedStore, ResultStore};
+use crate::strategy_validation::retain_valid;
+use crate::supervisor::{catch_panic, RestartPolicy, RunCrash};
+use crate::symbols::SymbolFilters;
+use crate::trailing_stop::{stop_refinement_grid, StopTracker, TrailingStop, REFINEMENT_STOPS};
+use crate::types::{
+    BacktestTrade, CostAttribution, ExitBreakdown, ExitReason, Kline, PnlConcentration, TradeSide,
+};
+use crate::zscore_filter::{zscore_refinement_grid, REFINEMENT_ZSCORE_FILTERS};
 
 // ============================================================================
 // Dynamic Combo Types
@@ -31,6 +70,7 @@ use crate::types::{BacktestTrade, Kline, TradeSide};
 
 /// The 10 single indicator types available for dynamic combination
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum SingleIndicatorType {
     Rsi,
@@ -46,7 +86,7 @@ pub enum SingleIndicatorType {
 }
 
 impl SingleIndicatorType {
-    pub fn short_name(&self) -> &str {
+    pub fn short_name(&self) -> &'static str {
         match self {
             Self::Rsi => "RSI",
             Self::BollingerBands => "BB",
@@ -61,6 +101,22 @@ impl SingleIndicatorType {
         }
     }
 
+    /// Tag of the single-indicator strategy of the same indicator
+    pub fn type_tag(&self) -> &'static str {
+        match self {
+            Self::Rsi => "rsi",
+            Self::BollingerBands => "bollinger_bands",
+            Self::Macd => "macd",
+            Self::EmaCrossover => "ema_crossover",
+            Self::Stochastic => "stochastic",
+            Self::AtrMeanReversion => "atr_mean_reversion",
+            Self::Vwap => "vwap",
+            Self::Obv => "obv",
+            Self::WilliamsR => "williams_r",
+            Self::Adx => "adx",
+        }
+    }
+
     pub fn all() -> &'static [SingleIndicatorType] {
         &[
             Self::Rsi,
@@ -84,7 +140,7 @@ impl SingleIndicatorType {
             Self::EmaCrossover => IndicatorParams::EmaCrossover { fast_period: 10, slow_period: 26 },
             Self::Stochastic => IndicatorParams::Stochastic { period: 14, overbought: 80.0, oversold: 20.0 },
             Self::AtrMeanReversion => IndicatorParams::AtrMeanReversion { atr_period: 14, sma_period: 20, multiplier: 2.0 },
-            Self::Vwap => IndicatorParams::Vwap { period: 20 },
+            Self::Vwap => IndicatorParams::Vwap { period: 20, anchor: VwapAnchor::Rolling },
             Self::Obv => IndicatorParams::Obv { sma_period: 14 },
             Self::WilliamsR => IndicatorParams::WilliamsR { period: 14, overbought: -20.0, oversold: -80.0 },
             Self::Adx => IndicatorParams::Adx { period: 14, adx_threshold: 25.0 },
@@ -99,7 +155,7 @@ impl SingleIndicatorType {
             Self::EmaCrossover => IndicatorParams::EmaCrossover { fast_period: 5, slow_period: 15 },
             Self::Stochastic => IndicatorParams::Stochastic { period: 7, overbought: 75.0, oversold: 25.0 },
             Self::AtrMeanReversion => IndicatorParams::AtrMeanReversion { atr_period: 7, sma_period: 10, multiplier: 1.5 },
-            Self::Vwap => IndicatorParams::Vwap { period: 10 },
+            Self::Vwap => IndicatorParams::Vwap { period: 10, anchor: VwapAnchor::Rolling },
             Self::Obv => IndicatorParams::Obv { sma_period: 7 },
             Self::WilliamsR => IndicatorParams::WilliamsR { period: 7, overbought: -15.0, oversold: -85.0 },
             Self::Adx => IndicatorParams::Adx { period: 7, adx_threshold: 20.0 },
@@ -114,7 +170,7 @@ impl SingleIndicatorType {
             Self::EmaCrossover => IndicatorParams::EmaCrossover { fast_period: 15, slow_period: 50 },
             Self::Stochastic => IndicatorParams::Stochastic { period: 21, overbought: 85.0, oversold: 15.0 },
             Self::AtrMeanReversion => IndicatorParams::AtrMeanReversion { atr_period: 21, sma_period: 40, multiplier: 2.5 },
-            Self::Vwap => IndicatorParams::Vwap { period: 40 },
+            Self::Vwap => IndicatorParams::Vwap { period: 40, anchor: VwapAnchor::Rolling },
             Self::Obv => IndicatorParams::Obv { sma_period: 25 },
             Self::WilliamsR => IndicatorParams::WilliamsR { period: 21, overbought: -25.0, oversold: -75.0 },
             Self::Adx => IndicatorParams::Adx { period: 21, adx_threshold: 30.0 },
@@ -128,47 +184,84 @@ impl SingleIndicatorType {
         (ind, params)
     }
 
+    /// Parameters drawn by `random_params_for()`, the coordinates of `params_at()`
+    pub fn param_dims(&self) -> usize {
+        match self {
+            Self::Rsi
+            | Self::Macd
+            | Self::Stochastic
+            | Self::AtrMeanReversion
+            | Self::WilliamsR => 3,
+            Self::BollingerBands | Self::EmaCrossover | Self::Adx => 2,
+            Self::Vwap | Self::Obv => 1,
+        }
+    }
+
     pub fn random_params_for(&self, rng: &mut impl rand::Rng) -> IndicatorParams {
+        let point: Vec<f64> = (0..self.param_dims()).map(|_| rng.gen()).collect();
+        self.params_at(&point)
+    }
+
+    /// Parameters at `point` of the unit cube (`param_dims()` coordinates in [0, 1)) over
+    /// the ranges random exploration draws from; equal slices of a coordinate map to
+    /// equally many integer values
+    pub fn params_at(&self, point: &[f64]) -> IndicatorParams {
+        let int = |i: usize, low: usize, high: usize| {
+            low + ((point[i] * (high - low + 1) as f64) as usize).min(high - low)
+        };
+        let float = |i: usize, low: f64, high: f64| low + point[i] * (high - low);
         match self {
             Self::Rsi => IndicatorParams::Rsi {
-                period: rng.gen_range(5..=35),
-                overbought: rng.gen_range(60.0..=85.0),
-                oversold: rng.gen_range(15.0..=40.0),
+                period: int(0, 5, 35),
+                overbought: float(1, 60.0, 85.0),
+                oversold: float(2, 15.0, 40.0),
             },
             Self::BollingerBands => IndicatorParams::BollingerBands {
-                period: rng.gen_range(7..=40),
-                multiplier: rng.gen_range(1.0..=3.5),
+                period: int(0, 7, 40),
+                multiplier: float(1, 1.0, 3.5),
             },
             Self::Macd => {
-                let fast = rng.gen_range(4..=15);
-                let slow = rng.gen_range((fast + 3)..=40);
-                IndicatorParams::Macd { fast, slow, signal: rng.gen_range(3..=12) }
+                let fast = int(0, 4, 15);
+                let slow = int(1, fast + 3, 40);
+                IndicatorParams::Macd {
+                    fast,
+                    slow,
+                    signal: int(2, 3, 12),
+                }
             }
             Self::EmaCrossover => {
-                let fast = rng.gen_range(4..=18);
-                let slow = rng.gen_range((fast + 3)..=60);
-                IndicatorParams::EmaCrossover { fast_period: fast, slow_period: slow }
+                let fast = int(0, 4, 18);
+                let slow = int(1, fast + 3, 60);
+                IndicatorParams::EmaCrossover {
+                    fast_period: fast,
+                    slow_period: slow,
+                }
             }
             Self::Stochastic => IndicatorParams::Stochastic {
-                period: rng.gen_range(5..=25),
-                overbought: rng.gen_range(70.0..=90.0),
-                oversold: rng.gen_range(10.0..=30.0),
+                period: int(0, 5, 25),
+                overbought: float(1, 70.0, 90.0),
+                oversold: float(2, 10.0, 30.0),
             },
             Self::AtrMeanReversion => IndicatorParams::AtrMeanReversion {
-                atr_period: rng.gen_range(5..=30),
-                sma_period: rng.gen_range(8..=60),
-                multiplier: rng.gen_range(0.75..=3.0),
+                atr_period: int(0, 5, 30),
+                sma_period: int(1, 8, 60),
+                multiplier: float(2, 0.75, 3.0),
+            },
+            Self::Vwap => IndicatorParams::Vwap {
+                period: int(0, 7, 60),
+                anchor: VwapAnchor::Rolling,
+            },
+            Self::Obv => IndicatorParams::Obv {
+                sma_period: int(0, 7, 40),
             },
-            Self::Vwap => IndicatorParams::Vwap { period: rng.gen_range(7..=60) },
-            Self::Obv => IndicatorParams::Obv { sma_period: rng.gen_range(7..=40) },
             Self::WilliamsR => IndicatorParams::WilliamsR {
-                period: rng.gen_range(5..=30),
-                overbought: rng.gen_range(-30.0..=-10.0),
-                oversold: rng.gen_range(-90.0..=-70.0),
+                period: int(0, 5, 30),
+                overbought: float(1, -30.0, -10.0),
+                oversold: float(2, -90.0, -70.0),
             },
             Self::Adx => IndicatorParams::Adx {
-                period: rng.gen_range(7..=30),
-                adx_threshold: rng.gen_range(15.0..=40.0),
+                period: int(0, 7, 30),
+                adx_threshold: float(1, 15.0, 40.0),
             },
         }
     }
@@ -176,6 +269,7 @@ impl SingleIndicatorType {
 
 /// Parameters for each indicator type
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(tag = "indicator", rename_all = "snake_case")]
 pub enum IndicatorParams {
     Rsi { period: usize, overbought: f64, oversold: f64 },
@@ -184,14 +278,56 @@ pub enum IndicatorParams {
     EmaCrossover { fast_period: usize, slow_period: usize },
     Stochastic { period: usize, overbought: f64, oversold: f64 },
     AtrMeanReversion { atr_period: usize, sma_period: usize, multiplier: f64 },
-    Vwap { period: usize },
+    Vwap {
+        period: usize,
+        #[serde(default, skip_serializing_if = "VwapAnchor::is_rolling")]
+        anchor: VwapAnchor,
+    },
     Obv { sma_period: usize },
     WilliamsR { period: usize, overbought: f64, oversold: f64 },
     Adx { period: usize, adx_threshold: f64 },
 }
 
+/// Where the VWAP starts accumulating. `Rolling` is the classic window of the last
+/// `period` bars; the anchored kinds sum every bar since their anchor and only signal
+/// once `period` bars have accumulated.
+///
+/// Rolling is left out of the serialized params, so the params and hashes of VWAP
+/// strategies stored before anchors existed are unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VwapAnchor {
+    #[default]
+    Rolling,
+    /// Start of each UTC day (the first bar of the series anchors the first session)
+    Session,
+    /// Every `days` UTC days, in blocks counted from the Unix epoch
+    Days { days: u32 },
+    /// Latest confirmed swing low: a low under the `lookback` bars on each side of it,
+    /// confirmed (and anchored back to) `lookback` bars later
+    SwingLow { lookback: usize },
+}
+
+impl VwapAnchor {
+    pub fn is_rolling(&self) -> bool {
+        *self == Self::Rolling
+    }
+
+    /// Short label for names and explanations (`rolling`, `session`, `7d`, `swing low 12`)
+    pub fn label(&self) -> String {
+        match self {
+            Self::Rolling => "rolling".to_string(),
+            Self::Session => "session".to_string(),
+            Self::Days { days } => format!("{}d", days),
+            Self::SwingLow { lookback } => format!("swing low {}", lookback),
+        }
+    }
+}
+
 /// How to combine signals in a dynamic combo
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum DynCombineMode {
     Unanimous,
@@ -219,6 +355,7 @@ impl DynCombineMode {
 
 /// All strategy types the discovery agent can explore
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum DiscoveryStrategyType {
     // === Singles (6) ===
@@ -311,6 +448,9 @@ pub enum DiscoveryStrategyType {
     // === New Singles (4) ===
     Vwap {
         period: usize,
+        /// Omitted = rolling window
+        #[serde(default, skip_serializing_if = "VwapAnchor::is_rolling")]
+        anchor: VwapAnchor,
     },
     Obv {
         sma_period: usize,
@@ -368,6 +508,77 @@ pub enum DiscoveryStrategyType {
         bid_offset: Decimal,
         spread_multiplier: Decimal,
     },
+    // === Cross-sectional (2) — runs on all requested symbols at once ===
+    RelativeStrength {
+        /// Momentum lookback in bars
+        lookback: usize,
+        /// Number of symbols held
+        top_k: usize,
+    },
+    PairSpread {
+        leg_a: String,
+        leg_b: String,
+        /// z-score window of the log price ratio, in bars
+        lookback: usize,
+        entry_z: f64,
+        exit_z: f64,
+    },
+    // === ML (1) — user-supplied ONNX classifier, see onnx.rs ===
+    OnnxModel {
+        model_path: String,
+        /// Minimum class probability to act on
+        threshold: f64,
+    },
+    // === Ensemble — vote of knowledge base strategies, see ensemble.rs ===
+    Ensemble {
+        members: Vec<DiscoveryStrategyType>,
+        /// Voting weight of each member
+        weights: Vec<f64>,
+        vote: crate::ensemble::EnsembleVote,
+    },
+    // === Filtered — a strategy whose entries are gated by market conditions, see
+    // entry_filter.rs ===
+    Filtered {
+        inner: Box<DiscoveryStrategyType>,
+        filter: crate::entry_filter::EntryFilter,
+    },
+    // === Trailing — a strategy whose positions are closed by a trailing stop, see
+    // trailing_stop.rs ===
+    Trailing {
+        inner: Box<DiscoveryStrategyType>,
+        stop: TrailingStop,
+    },
+    // === HtfFiltered — a strategy whose entries are gated by a higher-timeframe trend,
+    // see htf_filter.rs ===
+    HtfFiltered {
+        inner: Box<DiscoveryStrategyType>,
+        htf_filter: crate::htf_filter::HtfFilter,
+    },
+    // === ZScoreFiltered — a strategy whose signals are kept only when its metric's
+    // rolling z-score is large enough, see zscore_filter.rs ===
+    ZScoreFiltered {
+        inner: Box<DiscoveryStrategyType>,
+        zscore_filter: crate::zscore_filter::ZScoreFilter,
+    },
+}
+
+/// Names computed at runtime (dynamic combos, ensembles), leaked once per distinct name
+/// so `name()` can return `&str`; there are finitely many of them
+fn interned_name(key: String) -> &'static str {
+    use std::collections::HashMap;
+    use std::sync::{Mutex, OnceLock};
+    static NAMES: OnceLock<Mutex<HashMap<String, &'static str>>> = OnceLock::new();
+
+    let mut names = NAMES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap();
+    if let Some(name) = names.get(&key) {
+        return name;
+    }
+    let leaked: &'static str = Box::leak(key.clone().into_boxed_str());
+    names.insert(key, leaked);
+    leaked
 }
 
 impl DiscoveryStrategyType {
@@ -386,6 +597,7 @@ impl DiscoveryStrategyType {
             Self::MacdBollinger { .. } => "MACD+Bollinger",
             Self::TripleRsiMacdBb { .. } => "Triple:RSI+MACD+BB",
             Self::TripleEmaRsiStoch { .. } => "Triple:EMA+RSI+Stoch",
+            Self::Vwap { anchor, .. } if !anchor.is_rolling() => "Anchored VWAP",
             Self::Vwap { .. } => "VWAP",
             Self::Obv { .. } => "OBV",
             Self::WilliamsR { .. } => "Williams %R",
@@ -394,50 +606,160 @@ impl DiscoveryStrategyType {
             Self::ObvMacd { .. } => "OBV+MACD",
             Self::AdxEma { .. } => "ADX+EMA",
             Self::WilliamsRStoch { .. } => "Williams%R+Stoch",
-            Self::DynamicCombo { .. } => return self.dynamic_combo_name(),
+            Self::DynamicCombo { .. } => self.dynamic_combo_name(),
             Self::WebStrategy { id, .. } => id.display_name(),
             Self::Gabagool { .. } => "Gabagool",
+            Self::RelativeStrength { .. } => "Relative Strength",
+            Self::PairSpread { .. } => "Pair Spread",
+            Self::OnnxModel { .. } => "ONNX Model",
+            Self::Ensemble { .. } => self.ensemble_name(),
+            Self::Filtered { inner, filter } => {
+                interned_name(format!("{}|{}", inner.name(), filter.label()))
+            }
+            Self::Trailing { inner, stop } => {
+                interned_name(format!("{}|{}", inner.name(), stop.label()))
+            }
+            Self::HtfFiltered { inner, htf_filter } => {
+                interned_name(format!("{}|{}", inner.name(), htf_filter.label()))
+            }
+            Self::ZScoreFiltered {
+                inner,
+                zscore_filter,
+            } => interned_name(format!("{}|{}", inner.name(), zscore_filter.label())),
+        }
+    }
+
+    /// Every `type_tag()`
+    pub const TYPE_TAGS: [&'static str; 32] = [
+        "rsi",
+        "bollinger_bands",
+        "macd",
+        "ema_crossover",
+        "stochastic",
+        "atr_mean_reversion",
+        "rsi_bollinger",
+        "macd_rsi",
+        "ema_rsi",
+        "stoch_rsi",
+        "macd_bollinger",
+        "triple_rsi_macd_bb",
+        "triple_ema_rsi_stoch",
+        "vwap",
+        "obv",
+        "williams_r",
+        "adx",
+        "vwap_rsi",
+        "obv_macd",
+        "adx_ema",
+        "williams_r_stoch",
+        "dynamic_combo",
+        "web_strategy",
+        "gabagool",
+        "relative_strength",
+        "pair_spread",
+        "onnx_model",
+        "ensemble",
+        "filtered",
+        "trailing",
+        "htf_filtered",
+        "zscore_filtered",
+    ];
+
+    /// `strategy_type` column of stored records (the serde tag)
+    pub fn type_tag(&self) -> &'static str {
+        match self {
+            Self::Rsi { .. } => "rsi",
+            Self::BollingerBands { .. } => "bollinger_bands",
+            Self::Macd { .. } => "macd",
+            Self::EmaCrossover { .. } => "ema_crossover",
+            Self::Stochastic { .. } => "stochastic",
+            Self::AtrMeanReversion { .. } => "atr_mean_reversion",
+            Self::RsiBollinger { .. } => "rsi_bollinger",
+            Self::MacdRsi { .. } => "macd_rsi",
+            Self::EmaRsi { .. } => "ema_rsi",
+            Self::StochRsi { .. } => "stoch_rsi",
+            Self::MacdBollinger { .. } => "macd_bollinger",
+            Self::TripleRsiMacdBb { .. } => "triple_rsi_macd_bb",
+            Self::TripleEmaRsiStoch { .. } => "triple_ema_rsi_stoch",
+            Self::Vwap { .. } => "vwap",
+            Self::Obv { .. } => "obv",
+            Self::WilliamsR { .. } => "williams_r",
+            Self::Adx { .. } => "adx",
+            Self::VwapRsi { .. } => "vwap_rsi",
+            Self::ObvMacd { .. } => "obv_macd",
+            Self::AdxEma { .. } => "adx_ema",
+            Self::WilliamsRStoch { .. } => "williams_r_stoch",
+            Self::DynamicCombo { .. } => "dynamic_combo",
+            Self::WebStrategy { .. } => "web_strategy",
+            Self::Gabagool { .. } => "gabagool",
+            Self::RelativeStrength { .. } => "relative_strength",
+            Self::PairSpread { .. } => "pair_spread",
+            Self::OnnxModel { .. } => "onnx_model",
+            Self::Ensemble { .. } => "ensemble",
+            Self::Filtered { .. } => "filtered",
+            Self::Trailing { .. } => "trailing",
+            Self::HtfFiltered { .. } => "htf_filtered",
+            Self::ZScoreFiltered { .. } => "zscore_filtered",
         }
     }
 
     fn dynamic_combo_name(&self) -> &str {
-        // We use a thread-local cache for the computed name since we return &str
-        // For dynamic combos, we leak the string to get a static ref
-        // This is acceptable because there are a finite number of combo names
         match self {
-            Self::DynamicCombo { indicators, combine_mode, .. } => {
-                use std::sync::OnceLock;
-                use std::collections::HashMap;
-                use std::sync::Mutex;
-                static NAMES: OnceLock<Mutex<HashMap<String, &'static str>>> = OnceLock::new();
-                let names = NAMES.get_or_init(|| Mutex::new(HashMap::new()));
-
-                let key = format!(
-                    "{}({})",
-                    indicators.iter().map(|i| i.short_name()).collect::<Vec<_>>().join("+"),
-                    combine_mode.short_suffix(),
-                );
+            Self::DynamicCombo { indicators, combine_mode, .. } => interned_name(format!(
+                "{}({})",
+                indicators.iter().map(|i| i.short_name()).collect::<Vec<_>>().join("+"),
+                combine_mode.short_suffix(),
+            )),
+            _ => unreachable!(),
+        }
+    }
 
-                let mut map = names.lock().unwrap();
-                if let Some(s) = map.get(&key) {
-                    return s;
-                }
-                let leaked: &'static str = Box::leak(key.clone().into_boxed_str());
-                map.insert(key, leaked);
-                leaked
-            }
+    fn ensemble_name(&self) -> &str {
+        match self {
+            Self::Ensemble { members, vote, .. } => interned_name(format!(
+                "Ensemble:{}({})",
+                members
+                    .iter()
+                    .map(|m| m.name())
+                    .collect::<Vec<_>>()
+                    .join("+"),
+                vote.short_suffix(),
+            )),
             _ => unreachable!(),
         }
     }
 
-    fn is_gabagool(&self) -> bool {
+    pub(crate) fn is_ensemble(&self) -> bool {
+        matches!(self, Self::Ensemble { .. })
+    }
+
+    /// Trailing stop of the strategy's positions, through any entry filter
+    pub fn trailing_stop(&self) -> Option<TrailingStop> {
+        match self {
+            Self::Trailing { stop, .. } => Some(*stop),
+            Self::Filtered { inner, .. }
+            | Self::HtfFiltered { inner, .. }
+            | Self::ZScoreFiltered { inner, .. } => inner.trailing_stop(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_gabagool(&self) -> bool {
         matches!(self, Self::Gabagool { .. })
     }
 
+    /// Strategies that rank several symbols and need an aligned `KlineMatrix`
+    pub fn is_cross_sectional(&self) -> bool {
+        matches!(
+            self,
+            Self::RelativeStrength { .. } | Self::PairSpread { .. }
+        )
+    }
 }
 
 /// Position sizing mode
 #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum SizingMode {
     #[default]
@@ -448,24 +770,405 @@ pub enum SizingMode {
 
 /// Request to start a discovery scan
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DiscoveryRequest {
     pub symbols: Vec<String>,
     #[serde(default = "default_days")]
     pub days: u32,
     pub top_n: Option<usize>,
+    /// Results kept for each requested symbol in the final ranking, even below the
+    /// global `top_n` (default: none, one global top-N)
+    #[serde(default)]
+    pub per_symbol_top_n: Option<usize>,
     pub sizing_mode: Option<SizingMode>,
     #[serde(default)]
     pub continuous: Option<bool>,
+    /// ONNX classifier files to backtest alongside the rule-based strategies
+    #[serde(default)]
+    pub onnx_models: Vec<String>,
+    /// Wall-clock budget of one backtest in ms (default 10 000, 0 = unlimited)
+    #[serde(default)]
+    pub time_budget_ms: Option<u64>,
+    /// Abort once equity falls below this % of initial capital (default 50, 0 = never)
+    #[serde(default)]
+    pub ruin_threshold_pct: Option<Decimal>,
+    /// Re-fetch the gaps found by the kline quality check once (default false)
+    #[serde(default)]
+    pub repair_gaps: Option<bool>,
+    /// `binance` (default) or `file:<path>` — a CSV / Parquet file or a directory of
+    /// per-symbol files, read instead of Binance (the last `days` of the file are used)
+    #[serde(default)]
+    pub data_source: Option<String>,
+    /// Fee model the backtests are priced under (default Polymarket). Runs under another
+    /// preset are what-if scans: their results are neither read from nor written to the KB.
+    #[serde(default)]
+    pub fee_preset: Option<FeePreset>,
+    /// How fees estimate the Polymarket share price of a trade (default linear). Results
+    /// of other models are stored under their own hash.
+    #[serde(default)]
+    pub probability_model: Option<ProbabilityModelConfig>,
+    /// Price signals fill at (default: the signal bar's close). Runs under another model
+    /// are what-if scans: their results are neither read from nor written to the KB.
+    #[serde(default)]
+    pub fill_model: Option<FillModel>,
+    /// Entries fill at most this share of the volume of their bar (default: in full).
+    /// Capped runs are what-if scans too: their results are neither read from nor
+    /// written to the KB.
+    #[serde(default)]
+    pub participation: Option<ParticipationLimit>,
+    /// Re-run the best single-symbol results under each drawdown throttle preset after
+    /// refinement, to measure whether throttling improves their score (default false)
+    #[serde(default)]
+    pub throttle_search: Option<bool>,
+    /// Re-run the best single-symbol results on the run's other symbols to score how
+    /// well they generalize (default false; needs at least two symbols)
+    #[serde(default)]
+    pub cross_symbol: Option<bool>,
+    /// Weight (0-1) of the cross-symbol score in the final ranking: up to 200 composite
+    /// points for params profitable on every other symbol (default 0 = reported only)
+    #[serde(default)]
+    pub cross_symbol_weight: Option<Decimal>,
+    /// Best results refined per Phase 2 round (default 20)
+    #[serde(default)]
+    pub refinement_top_k: Option<usize>,
+    /// Phase 2 rounds (default 1): each later round refines the best new results of
+    /// the previous one
+    #[serde(default)]
+    pub refinement_rounds: Option<u32>,
+    /// Multiplier of the parameter steps of the refinement grids (default 1)
+    #[serde(default)]
+    pub refinement_delta_scale: Option<f64>,
+    /// Hill-climb the parameters of the best result of each strategy family after
+    /// refinement (default false)
+    #[serde(default)]
+    pub local_search: Option<bool>,
+    /// Plan continuous cycles 0-2 from the knowledge base coverage instead of the fixed
+    /// grids, targeting the least explored families and parameter regions (default
+    /// false; needs a store)
+    #[serde(default)]
+    pub adaptive_cycles: Option<bool>,
+    /// Half-life in days of the time-decayed score: each trade's PnL and return count
+    /// `0.5^(age / half-life)`, its age running to the end of the data, and the decayed
+    /// composite score is stored next to the plain one (default: not computed)
+    #[serde(default)]
+    pub decay_half_life_days: Option<f64>,
+    /// Composite points taken off per day of a result's longest drawdown in the final
+    /// ranking (default 0 = reported only)
+    #[serde(default)]
+    pub underwater_penalty: Option<Decimal>,
+    /// Largest position of the generic backtester, in % of equity at entry: caps every
+    /// sizing mode, Kelly included (default: the sizing mode's own size)
+    #[serde(default)]
+    pub max_exposure_pct: Option<f64>,
+    /// Strategy families (`type_tag()`s) left out of every grid, e.g. `gabagool`; an
+    /// excluded indicator also removes the combos and wrapped strategies using it
+    #[serde(default)]
+    pub exclude_strategy_types: Vec<String>,
+    /// Speed limits of the scan loops (default: unpaced), adjustable while the run
+    /// goes on through `DiscoveryProgress::set_pacing()`
+    #[serde(default)]
+    pub pacing: Option<ScanPacing>,
+    /// Candles the bar-signal strategies read (default: the raw klines): Heikin-Ashi
+    /// candles or Renko bricks, trades still filling at the kline prices. Transformed
+    /// runs are what-if scans: their results are neither read from nor written to the KB.
+    #[serde(default)]
+    pub candle_transform: Option<CandleTransform>,
+    /// Order of the ranked results (`best_so_far`, final results): the first key, then
+    /// each next one on ties (default: score, sharpe, trades). Results still tied are
+    /// ordered by strategy name and symbol, so the ranking is the same on every run.
+    #[serde(default)]
+    pub rank_by: Option<Vec<RankKey>>,
+    /// Restart the run when its task panics, after a backoff doubling with each crash
+    /// (default: the crash is recorded and the run stays stopped)
+    #[serde(default)]
+    pub restart_on_crash: Option<RestartPolicy>,
+}
+
+impl DiscoveryRequest {
+    /// `rank_by`, or `DEFAULT_RANK_ORDER` when not set or empty
+    pub fn rank_order(&self) -> Vec<RankKey> {
+        match &self.rank_by {
+            Some(keys) if !keys.is_empty() => keys.clone(),
+            _ => DEFAULT_RANK_ORDER.to_vec(),
+        }
+    }
+}
+
+/// Strategy families a run leaves out (`DiscoveryRequest.exclude_strategy_types`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StrategyExclusions {
+    tags: Vec<String>,
+}
+
+impl StrategyExclusions {
+    pub fn new<I, S>(tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut tags: Vec<String> = tags.into_iter().map(Into::into).collect();
+        tags.sort();
+        tags.dedup();
+        Self { tags }
+    }
+
+    pub fn from_request(request: &DiscoveryRequest) -> Self {
+        Self::new(request.exclude_strategy_types.iter().cloned())
+    }
+
+    /// Every tag is a `DiscoveryStrategyType::TYPE_TAGS` one
+    pub fn validate(&self) -> Result<(), String> {
+        match self
+            .tags
+            .iter()
+            .find(|t| !DiscoveryStrategyType::TYPE_TAGS.contains(&t.as_str()))
+        {
+            Some(unknown) => Err(format!(
+                "Unknown strategy type '{}' in exclude_strategy_types (expected one of: {})",
+                unknown,
+                DiscoveryStrategyType::TYPE_TAGS.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// `strategy` is an excluded family, or wraps, votes with or combines one
+    pub fn excludes(&self, strategy: &DiscoveryStrategyType) -> bool {
+        let excluded = |tag: &str| self.tags.iter().any(|t| t == tag);
+        if excluded(strategy.type_tag()) {
+            return true;
+        }
+        match strategy {
+            DiscoveryStrategyType::Filtered { inner, .. }
+            | DiscoveryStrategyType::Trailing { inner, .. }
+            | DiscoveryStrategyType::HtfFiltered { inner, .. }
+            | DiscoveryStrategyType::ZScoreFiltered { inner, .. } => self.excludes(inner),
+            DiscoveryStrategyType::Ensemble { members, .. } => {
+                members.iter().any(|m| self.excludes(m))
+            }
+            DiscoveryStrategyType::DynamicCombo { indicators, .. } => {
+                indicators.iter().any(|i| excluded(i.type_tag()))
+            }
+            // Fixed combos: their indicators
+            DiscoveryStrategyType::RsiBollinger { .. } => {
+                excluded("rsi") || excluded("bollinger_bands")
+            }
+            DiscoveryStrategyType::MacdRsi { .. } => excluded("macd") || excluded("rsi"),
+            DiscoveryStrategyType::EmaRsi { .. } => excluded("ema_crossover") || excluded("rsi"),
+            DiscoveryStrategyType::StochRsi { .. } => excluded("stochastic") || excluded("rsi"),
+            DiscoveryStrategyType::MacdBollinger { .. } => {
+                excluded("macd") || excluded("bollinger_bands")
+            }
+            DiscoveryStrategyType::TripleRsiMacdBb { .. } => {
+                excluded("rsi") || excluded("macd") || excluded("bollinger_bands")
+            }
+            DiscoveryStrategyType::TripleEmaRsiStoch { .. } => {
+                excluded("ema_crossover") || excluded("rsi") || excluded("stochastic")
+            }
+            DiscoveryStrategyType::VwapRsi { .. } => excluded("vwap") || excluded("rsi"),
+            DiscoveryStrategyType::ObvMacd { .. } => excluded("obv") || excluded("macd"),
+            DiscoveryStrategyType::AdxEma { .. } => excluded("adx") || excluded("ema_crossover"),
+            DiscoveryStrategyType::WilliamsRStoch { .. } => {
+                excluded("williams_r") || excluded("stochastic")
+            }
+            _ => false,
+        }
+    }
+
+    /// Drop the excluded strategies of `grid`
+    pub fn retain(&self, grid: &mut Vec<DiscoveryStrategyType>) {
+        if !self.is_empty() {
+            grid.retain(|s| !self.excludes(s));
+        }
+    }
+}
+
+/// Why a backtest stopped before the last bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AbortReason {
+    /// Equity fell below the ruin threshold
+    Ruin,
+    /// The per-backtest time budget ran out
+    TimeBudget,
+}
+
+impl AbortReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ruin => "ruin",
+            Self::TimeBudget => "time_budget",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ruin" => Some(Self::Ruin),
+            "time_budget" => Some(Self::TimeBudget),
+            _ => None,
+        }
+    }
+}
+
+/// Early-termination, equity-protection and execution rules of the generic backtester
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BacktestLimits {
+    pub time_budget: Option<Duration>,
+    /// Ruin level in % of initial capital
+    pub ruin_threshold_pct: Option<Decimal>,
+    /// Scale entries down (then pause) while the strategy is in drawdown
+    pub drawdown_throttle: Option<DrawdownThrottle>,
+    /// Price signals are filled at (default: the signal bar's close)
+    pub fill_model: FillModel,
+    /// Tick / lot rounding of the symbol's fills (none: unrounded)
+    pub symbol_filters: Option<SymbolFilters>,
+    /// Trailing stop of every position (none: positions close on signals only)
+    pub trailing_stop: Option<TrailingStop>,
+    /// Half-life in days of the time-decayed score (none: not computed)
+    pub decay_half_life_days: Option<f64>,
+    /// Largest entry, in % of equity (none: the sizing mode's own size)
+    pub max_exposure_pct: Option<f64>,
+    /// Volume cap of the entries (none: they fill in full)
+    pub participation: Option<ParticipationLimit>,
+}
+
+impl Default for BacktestLimits {
+    fn default() -> Self {
+        Self {
+            time_budget: Some(Duration::from_secs(10)),
+            ruin_threshold_pct: Some(dec!(50)),
+            drawdown_throttle: None,
+            fill_model: FillModel::Close,
+            symbol_filters: None,
+            trailing_stop: None,
+            decay_half_life_days: None,
+            max_exposure_pct: None,
+            participation: None,
+        }
+    }
+}
+
+impl BacktestLimits {
+    /// No time budget, no ruin check
+    pub fn unlimited() -> Self {
+        Self {
+            time_budget: None,
+            ruin_threshold_pct: None,
+            drawdown_throttle: None,
+            fill_model: FillModel::Close,
+            symbol_filters: None,
+            trailing_stop: None,
+            decay_half_life_days: None,
+            max_exposure_pct: None,
+            participation: None,
+        }
+    }
+
+    pub fn with_drawdown_throttle(self, throttle: Option<DrawdownThrottle>) -> Self {
+        Self {
+            drawdown_throttle: throttle,
+            ..self
+        }
+    }
+
+    pub fn from_request(request: &DiscoveryRequest) -> Self {
+        Self {
+            fill_model: request.fill_model.unwrap_or_default(),
+            decay_half_life_days: request.decay_half_life_days.filter(|days| *days > 0.0),
+            max_exposure_pct: request.max_exposure_pct.filter(|pct| *pct > 0.0),
+            participation: request.participation.filter(|limit| limit.max_pct > 0.0),
+            ..Self::new(request.time_budget_ms, request.ruin_threshold_pct)
+        }
+    }
+
+    pub fn with_fill_model(self, fill_model: FillModel) -> Self {
+        Self { fill_model, ..self }
+    }
+
+    pub fn with_symbol_filters(self, symbol_filters: Option<SymbolFilters>) -> Self {
+        Self {
+            symbol_filters,
+            ..self
+        }
+    }
+
+    pub fn with_trailing_stop(self, trailing_stop: Option<TrailingStop>) -> Self {
+        Self {
+            trailing_stop,
+            ..self
+        }
+    }
+
+    pub fn with_decay_half_life(self, decay_half_life_days: Option<f64>) -> Self {
+        Self {
+            decay_half_life_days,
+            ..self
+        }
+    }
+
+    pub fn with_max_exposure(self, max_exposure_pct: Option<f64>) -> Self {
+        Self {
+            max_exposure_pct,
+            ..self
+        }
+    }
+
+    pub fn with_participation(self, participation: Option<ParticipationLimit>) -> Self {
+        Self {
+            participation,
+            ..self
+        }
+    }
+
+    /// `None` keeps the default of a limit, `0` disables it
+    pub fn new(time_budget_ms: Option<u64>, ruin_threshold_pct: Option<Decimal>) -> Self {
+        let defaults = Self::default();
+        Self {
+            time_budget: match time_budget_ms {
+                Some(0) => None,
+                Some(ms) => Some(Duration::from_millis(ms)),
+                None => defaults.time_budget,
+            },
+            ruin_threshold_pct: match ruin_threshold_pct {
+                Some(pct) if pct <= Decimal::ZERO => None,
+                Some(pct) => Some(pct),
+                None => defaults.ruin_threshold_pct,
+            },
+            drawdown_throttle: None,
+            fill_model: FillModel::Close,
+            symbol_filters: None,
+            trailing_stop: None,
+            decay_half_life_days: None,
+            max_exposure_pct: None,
+            participation: None,
+        }
+    }
 }
 
 fn default_days() -> u32 {
     365
 }
 
+fn default_probability_model() -> String {
+    ProbabilityModelConfig::Linear.name()
+}
+
 /// A single scored discovery result
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DiscoveryResult {
     pub rank: usize,
+    /// Strategy and parameters, tagged by `type`
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub strategy_type: DiscoveryStrategyType,
     pub strategy_name: String,
     pub symbol: String,
@@ -492,10 +1195,87 @@ pub struct DiscoveryResult {
     // Gabagool-specific
     pub hit_rate: Option<Decimal>,
     pub avg_locked_profit: Option<Decimal>,
+    /// Set when the backtest stopped early; such results are never ranked
+    #[serde(default)]
+    pub abort_reason: Option<AbortReason>,
+    /// Probability model the fees were estimated with, e.g. `logistic(slope=0.2)`
+    #[serde(default = "default_probability_model")]
+    pub probability_model: String,
+    /// Drawdown throttle the backtest ran under (throttle search), none by default
+    #[serde(default)]
+    pub drawdown_throttle: Option<DrawdownThrottle>,
+    /// Share (0-1) of the run's other symbols the same params are profitable on, set on
+    /// the best results by the cross-symbol validation
+    #[serde(default)]
+    pub cross_symbol_score: Option<Decimal>,
+    /// Trades and PnL by exit reason (signal, stop, trailing, timeout), set by the
+    /// generic backtester
+    #[serde(default)]
+    pub exit_breakdown: Option<ExitBreakdown>,
+    /// Composite score with PnL and Sharpe from time-decayed trades, set by the generic
+    /// backtester when the run has a decay half-life
+    #[serde(default)]
+    pub decayed_score: Option<Decimal>,
+    /// Metrics of the trades closed in the last 30 days of the data, set by the generic
+    /// backtester
+    #[serde(default)]
+    pub recent_30d: Option<RecentPerformance>,
+    /// Same over the last 90 days
+    #[serde(default)]
+    pub recent_90d: Option<RecentPerformance>,
+    /// How the PnL spreads over the weeks of the backtest; results carried by a week
+    /// or two are usually luck (none without trades)
+    #[serde(default)]
+    pub pnl_concentration: Option<PnlConcentration>,
+    /// Longest time spent under a previous equity peak, recovered or not, in days (set
+    /// by the generic backtester)
+    #[serde(default)]
+    pub longest_drawdown_days: Option<Decimal>,
+    /// Mean time from a peak back to it over the drawdowns that recovered, in days
+    /// (none without a recovery)
+    #[serde(default)]
+    pub avg_recovery_days: Option<Decimal>,
+    /// Largest position value over mark-to-market equity of any bar, in % (measured
+    /// by the generic backtester)
+    #[serde(default)]
+    pub max_exposure_pct: Option<Decimal>,
+    /// Mean of that exposure over every bar run, flat bars counting 0
+    #[serde(default)]
+    pub avg_exposure_pct: Option<Decimal>,
+    /// Leading bars the generic backtester held through while the strategy's
+    /// indicators warmed up (`SignalGenerator::warmup_bars()`)
+    #[serde(default)]
+    pub warmup_bars: Option<u32>,
+    /// Days actually tested, the warm-up excluded; a short window of a slow strategy
+    /// tests much less than `days`
+    #[serde(default)]
+    pub effective_days: Option<Decimal>,
+    /// Entries the volume cap cut short (runs with a participation limit only; not
+    /// stored, as such runs are what-if scans)
+    #[serde(default)]
+    pub unfilled: Option<UnfilledStats>,
+    /// PnL at the signal prices, lost to the fills and to fees, down to the net PnL
+    /// (set by the generic backtester)
+    #[serde(default)]
+    pub cost_attribution: Option<CostAttribution>,
+}
+
+/// Score and metrics of the trades closed in a trailing window of the backtest, which
+/// tell a strategy still performing from one that only performed early in the period
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecentPerformance {
+    /// Composite score of the window's trades (-9999 under 5 trades, as any result)
+    pub score: Decimal,
+    pub net_pnl: Decimal,
+    /// Win rate in %, 0 without trades
+    pub win_rate: Decimal,
+    pub total_trades: u32,
 }
 
 /// Discovery scan status
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum DiscoveryStatus {
     Idle,
@@ -505,6 +1285,69 @@ pub enum DiscoveryStatus {
     Phase3Exploration,
     Complete,
     Error,
+    /// The run's task panicked and is restarted after a backoff (see `crashes`)
+    Restarting,
+}
+
+/// Rolling window of the throughput estimate
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Polling interval of a paused run
+const PAUSE_POLL: Duration = Duration::from_millis(200);
+
+/// Throughput and ETA of the running phase, see `DiscoveryProgress::estimate()`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProgressEstimate {
+    /// Combinations (tested + cached) per second over the last minute
+    pub throughput_per_sec: f64,
+    /// Seconds left at that rate, `None` until a rate is known
+    pub eta_secs: Option<u64>,
+    /// Estimated finish timestamp (RFC 3339)
+    pub eta_at: Option<String>,
+}
+
+/// Finished and planned combinations of one symbol or strategy family
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WorkProgress {
+    /// Symbol (basket or pair label for cross-sectional strategies), or strategy family
+    /// (`type_tag()`)
+    pub name: String,
+    pub completed: u32,
+    pub total: u32,
+}
+
+/// Progress of the running phase per symbol and per strategy family, sorted by name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProgressBreakdown {
+    pub by_symbol: Vec<WorkProgress>,
+    pub by_family: Vec<WorkProgress>,
+}
+
+/// Failures listed on the tracker, later ones are only counted
+pub const MAX_LISTED_FAILURES: usize = 100;
+
+/// Combination skipped because its backtest panicked (e.g. parameters a signal
+/// generator rejects)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FailedCombo {
+    pub symbol: String,
+    pub strategy_name: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub strategy_type: DiscoveryStrategyType,
+    /// Phase label of the backtest (`phase1`, `phase2`, `throttle`, ...)
+    pub phase: String,
+    /// Panic message
+    pub reason: String,
+}
+
+/// `(completed, total)` per symbol and per family
+#[derive(Default)]
+struct WorkCounts {
+    by_symbol: HashMap<String, (u32, u32)>,
+    by_family: HashMap<&'static str, (u32, u32)>,
 }
 
 /// Shared progress tracker for the discovery agent
@@ -517,6 +1360,12 @@ pub struct DiscoveryProgress {
     pub completed: AtomicU32,
     pub skipped: AtomicU32,
     pub cancelled: AtomicBool,
+    /// Set to hold the scan loops between two backtests (the run keeps its state)
+    pub paused: AtomicBool,
+    /// Speed limits of the scan loops, read after every backtest
+    pacing: RwLock<ScanPacing>,
+    /// Rank order of `best_so_far` and the final results (request's `rank_by`)
+    rank_order: RwLock<Vec<RankKey>>,
     pub best_so_far: RwLock<Vec<DiscoveryResult>>,
     pub final_results: RwLock<Vec<DiscoveryResult>>,
     pub error_message: RwLock<Option<String>>,
@@ -525,6 +1374,26 @@ pub struct DiscoveryProgress {
     pub total_tested_all_cycles: AtomicU32,
     pub total_new_this_cycle: AtomicU32,
     pub is_continuous: AtomicBool,
+    /// Kline quality of each symbol of the run
+    pub data_quality: RwLock<Vec<DataQualityReport>>,
+    /// Island of each symbol of the evolutionary search (continuous cycles 3+)
+    pub islands: RwLock<Vec<IslandStats>>,
+    /// `discovery_run_id` of the running scan, set once the runner has started
+    pub run_id: RwLock<Option<String>>,
+    /// Panics of the run's task, kept across the restarts of a supervised run
+    pub crashes: RwLock<Vec<RunCrash>>,
+    /// Combinations whose backtest panicked, counted in `completed`
+    pub failed: AtomicU32,
+    /// The first `MAX_LISTED_FAILURES` of them
+    pub failed_combos: RwLock<Vec<FailedCombo>>,
+    /// Binance klines each symbol of the run backtests on, as last fetched or refreshed
+    kline_cache: RwLock<HashMap<String, Arc<Vec<Kline>>>>,
+    /// `(instant, completed)` samples taken by `estimate()`, oldest first
+    rate_samples: Mutex<VecDeque<(Instant, u32)>>,
+    /// Per-symbol / per-family counts behind `breakdown()`
+    work_counts: Mutex<WorkCounts>,
+    /// Receives every finished result (kept across `reset()`)
+    result_sink: RwLock<Option<Arc<dyn ResultSink>>>,
 }
 
 impl DiscoveryProgress {
@@ -538,6 +1407,9 @@ impl DiscoveryProgress {
             completed: AtomicU32::new(0),
             skipped: AtomicU32::new(0),
             cancelled: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            pacing: RwLock::new(ScanPacing::default()),
+            rank_order: RwLock::new(DEFAULT_RANK_ORDER.to_vec()),
             best_so_far: RwLock::new(Vec::new()),
             final_results: RwLock::new(Vec::new()),
             error_message: RwLock::new(None),
@@ -546,6 +1418,16 @@ impl DiscoveryProgress {
             total_tested_all_cycles: AtomicU32::new(0),
             total_new_this_cycle: AtomicU32::new(0),
             is_continuous: AtomicBool::new(false),
+            data_quality: RwLock::new(Vec::new()),
+            islands: RwLock::new(Vec::new()),
+            run_id: RwLock::new(None),
+            crashes: RwLock::new(Vec::new()),
+            failed: AtomicU32::new(0),
+            failed_combos: RwLock::new(Vec::new()),
+            kline_cache: RwLock::new(HashMap::new()),
+            rate_samples: Mutex::new(VecDeque::new()),
+            work_counts: Mutex::new(WorkCounts::default()),
+            result_sink: RwLock::new(None),
         }
     }
 
@@ -558,6 +1440,9 @@ impl DiscoveryProgress {
         self.completed.store(0, Ordering::Relaxed);
         self.skipped.store(0, Ordering::Relaxed);
         self.cancelled.store(false, Ordering::Relaxed);
+        self.paused.store(false, Ordering::Relaxed);
+        *self.pacing.write().unwrap() = ScanPacing::default();
+        *self.rank_order.write().unwrap() = DEFAULT_RANK_ORDER.to_vec();
         *self.best_so_far.write().unwrap() = Vec::new();
         *self.final_results.write().unwrap() = Vec::new();
         *self.error_message.write().unwrap() = None;
@@ -566,6 +1451,75 @@ impl DiscoveryProgress {
         self.total_tested_all_cycles.store(0, Ordering::Relaxed);
         self.total_new_this_cycle.store(0, Ordering::Relaxed);
         self.is_continuous.store(false, Ordering::Relaxed);
+        *self.data_quality.write().unwrap() = Vec::new();
+        *self.islands.write().unwrap() = Vec::new();
+        *self.run_id.write().unwrap() = None;
+        self.failed.store(0, Ordering::Relaxed);
+        *self.failed_combos.write().unwrap() = Vec::new();
+        self.kline_cache.write().unwrap().clear();
+        self.rate_samples.lock().unwrap().clear();
+        self.clear_breakdown();
+    }
+
+    /// Tracker side of a failed run: `Error` status and message, or back to `Idle` for
+    /// a cancelled one. Hands the error back for the runner to return.
+    pub(crate) fn fail(&self, error: EngineError) -> EngineError {
+        if error == EngineError::Cancelled {
+            info!("Discovery cancelled by user");
+            *self.status.write().unwrap() = DiscoveryStatus::Idle;
+        } else {
+            *self.error_message.write().unwrap() = Some(error.to_string());
+            *self.status.write().unwrap() = DiscoveryStatus::Error;
+        }
+        error
+    }
+
+    /// Tracker side of a panicked run: `Restarting` while a restart is planned,
+    /// `Error` otherwise, with the panic message
+    pub(crate) fn record_crash(&self, crash: RunCrash) {
+        *self.error_message.write().unwrap() =
+            Some(format!("Discovery task panicked: {}", crash.message));
+        *self.status.write().unwrap() = if crash.restart_in_secs.is_some() {
+            DiscoveryStatus::Restarting
+        } else {
+            DiscoveryStatus::Error
+        };
+        self.crashes.write().unwrap().push(crash);
+    }
+
+    /// Run one backtest of `strategy` on `symbol`, isolating a panic: the combination
+    /// is recorded as failed with the panic message and `None` returned, so the scan
+    /// goes on with the next one
+    pub(crate) fn isolate<T>(
+        &self,
+        symbol: &str,
+        strategy: &DiscoveryStrategyType,
+        phase: &str,
+        run: impl FnOnce() -> T,
+    ) -> Option<T> {
+        let reason = match catch_panic(run) {
+            Ok(result) => return Some(result),
+            Err(reason) => reason,
+        };
+        warn!(
+            symbol,
+            strategy = strategy.name(),
+            phase,
+            "Backtest panicked, combination skipped: {}",
+            reason
+        );
+        self.failed.fetch_add(1, Ordering::Relaxed);
+        let mut failed = self.failed_combos.write().unwrap();
+        if failed.len() < MAX_LISTED_FAILURES {
+            failed.push(FailedCombo {
+                symbol: symbol.to_string(),
+                strategy_name: strategy.name().to_string(),
+                strategy_type: strategy.clone(),
+                phase: phase.to_string(),
+                reason,
+            });
+        }
+        None
     }
 
     pub fn progress_pct(&self) -> f32 {
@@ -578,29 +1532,211 @@ impl DiscoveryProgress {
         }
     }
 
-    pub fn is_running(&self) -> bool {
-        let s = self.status.read().unwrap();
-        matches!(
-            *s,
-            DiscoveryStatus::FetchingData
-                | DiscoveryStatus::Phase1BroadScan
-                | DiscoveryStatus::Phase2Refinement
-                | DiscoveryStatus::Phase3Exploration
-        )
+    /// Rolling throughput and ETA. Each call records a progress sample, so the rate
+    /// follows the polling of the status endpoint / CLI progress bar.
+    pub fn estimate(&self) -> ProgressEstimate {
+        self.estimate_at(Instant::now())
     }
-}
 
-impl Default for DiscoveryProgress {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+    fn estimate_at(&self, now: Instant) -> ProgressEstimate {
+        let total = self.total_combinations.load(Ordering::Relaxed);
+        let done = self.completed.load(Ordering::Relaxed);
 
-// ============================================================================
+        let mut samples = self.rate_samples.lock().unwrap();
+        // `completed` restarts at 0 on each continuous cycle
+        if samples.back().is_some_and(|&(_, d)| d > done) {
+            samples.clear();
+        }
+        samples.push_back((now, done));
+        while samples.len() > 2
+            && samples
+                .front()
+                .is_some_and(|&(t, _)| now.duration_since(t) > THROUGHPUT_WINDOW)
+        {
+            samples.pop_front();
+        }
+
+        let (t0, d0) = samples[0];
+        let elapsed = now.duration_since(t0).as_secs_f64();
+        if elapsed <= 0.0 {
+            return ProgressEstimate::default();
+        }
+        let throughput_per_sec = (done - d0) as f64 / elapsed;
+        let remaining = total.saturating_sub(done);
+        let eta_secs = if throughput_per_sec > 0.0 && total > 0 {
+            Some((remaining as f64 / throughput_per_sec).ceil() as u64)
+        } else {
+            None
+        };
+        ProgressEstimate {
+            throughput_per_sec,
+            eta_secs,
+            eta_at: eta_secs
+                .map(|secs| (Utc::now() + chrono::Duration::seconds(secs as i64)).to_rfc3339()),
+        }
+    }
+
+    /// Add `n` planned combinations of `strategy` on `symbol` to the breakdown
+    pub(crate) fn plan_work(&self, symbol: &str, strategy: &DiscoveryStrategyType, n: u32) {
+        self.count_work(symbol, strategy, 0, n);
+    }
+
+    /// Count `n` finished (tested or cached) combinations of `strategy` on `symbol`
+    pub(crate) fn complete_work(&self, symbol: &str, strategy: &DiscoveryStrategyType, n: u32) {
+        self.count_work(symbol, strategy, n, 0);
+    }
+
+    fn count_work(
+        &self,
+        symbol: &str,
+        strategy: &DiscoveryStrategyType,
+        completed: u32,
+        total: u32,
+    ) {
+        let add = |counts: &mut (u32, u32)| {
+            counts.0 += completed;
+            counts.1 += total;
+        };
+        let mut counts = self.work_counts.lock().unwrap();
+        let WorkCounts {
+            by_symbol,
+            by_family,
+        } = &mut *counts;
+        match by_symbol.get_mut(symbol) {
+            Some(c) => add(c),
+            None => {
+                by_symbol.insert(symbol.to_string(), (completed, total));
+            }
+        }
+        add(by_family.entry(strategy.type_tag()).or_default());
+    }
+
+    /// Start a new breakdown (each continuous cycle has its own)
+    pub(crate) fn clear_breakdown(&self) {
+        *self.work_counts.lock().unwrap() = WorkCounts::default();
+    }
+
+    /// Completed / planned combinations per symbol and per strategy family. Totals grow
+    /// as refinement grids are built.
+    pub fn breakdown(&self) -> ProgressBreakdown {
+        fn sorted<K: ToString>(counts: &HashMap<K, (u32, u32)>) -> Vec<WorkProgress> {
+            let mut rows: Vec<WorkProgress> = counts
+                .iter()
+                .map(|(name, &(completed, total))| WorkProgress {
+                    name: name.to_string(),
+                    completed,
+                    total,
+                })
+                .collect();
+            rows.sort_by(|a, b| a.name.cmp(&b.name));
+            rows
+        }
+        let counts = self.work_counts.lock().unwrap();
+        ProgressBreakdown {
+            by_symbol: sorted(&counts.by_symbol),
+            by_family: sorted(&counts.by_family),
+        }
+    }
+
+    /// Stream every result the runners finish to `sink` (`None` stops streaming)
+    pub fn set_result_sink(&self, sink: Option<Arc<dyn ResultSink>>) {
+        *self.result_sink.write().unwrap() = sink;
+    }
+
+    pub(crate) fn emit_result(&self, result: &DiscoveryResult) {
+        if let Some(sink) = self.result_sink.read().unwrap().as_ref() {
+            sink.on_result(result);
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        let s = self.status.read().unwrap();
+        matches!(
+            *s,
+            DiscoveryStatus::FetchingData
+                | DiscoveryStatus::Phase1BroadScan
+                | DiscoveryStatus::Phase2Refinement
+                | DiscoveryStatus::Phase3Exploration
+                | DiscoveryStatus::Restarting
+        )
+    }
+
+    pub fn pacing(&self) -> ScanPacing {
+        *self.pacing.read().unwrap()
+    }
+
+    pub fn rank_order(&self) -> Vec<RankKey> {
+        self.rank_order.read().unwrap().clone()
+    }
+
+    pub fn set_rank_order(&self, order: Vec<RankKey>) {
+        *self.rank_order.write().unwrap() = order;
+    }
+
+    /// Takes effect from the next backtest of the running scan
+    pub fn set_pacing(&self, pacing: ScanPacing) {
+        if !pacing.is_unlimited() {
+            info!(?pacing, "Discovery paced");
+        }
+        *self.pacing.write().unwrap() = pacing;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// `KLINE_INTERVAL` klines of `symbol` the run backtests on, if fetched from Binance
+    pub fn cached_klines(&self, symbol: &str) -> Option<Arc<Vec<Kline>>> {
+        self.kline_cache.read().unwrap().get(symbol).cloned()
+    }
+
+    fn cache_klines(&self, symbol: &str, klines: &[Kline]) {
+        self.kline_cache
+            .write()
+            .unwrap()
+            .insert(symbol.to_string(), Arc::new(klines.to_vec()));
+    }
+
+    /// Merge a fresh fetch into the cached klines of `symbol` (see `merge_new_klines`),
+    /// an empty series if none is cached yet. Returns the bars appended and the merged
+    /// series.
+    fn merge_klines(
+        &self,
+        symbol: &str,
+        fresh: Vec<Kline>,
+        max_days: u32,
+    ) -> (usize, Arc<Vec<Kline>>) {
+        let mut cache = self.kline_cache.write().unwrap();
+        let cached = cache.entry(symbol.to_string()).or_default();
+        let added = merge_new_klines(Arc::make_mut(cached), fresh, max_days);
+        (added, Arc::clone(cached))
+    }
+
+    /// Hold the calling scan loop while `paused` is set; a cancel ends the wait so the
+    /// loop's cancel check runs right after
+    pub async fn wait_while_paused(&self) {
+        if !self.is_paused() {
+            return;
+        }
+        info!("Discovery paused");
+        while self.is_paused() && !self.cancelled.load(Ordering::Relaxed) {
+            tokio::time::sleep(PAUSE_POLL).await;
+        }
+        info!("Discovery resumed");
+    }
+}
+
+impl Default for DiscoveryProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
 // Parameter Grids — Phase 1 (Broad Scan)
 // ============================================================================
 
-fn generate_phase1_grid() -> Vec<DiscoveryStrategyType> {
+pub(crate) fn generate_phase1_grid() -> Vec<DiscoveryStrategyType> {
     let mut grid = Vec::with_capacity(4000);
     let all_indicators = SingleIndicatorType::all();
     let all_modes = DynCombineMode::all();
@@ -702,9 +1838,69 @@ fn generate_phase1_grid() -> Vec<DiscoveryStrategyType> {
     grid
 }
 
+/// Cross-sectional grid: lookbacks from 1h to 1 week of 15m bars × every top-K
+/// smaller than the basket (holding all symbols would just be buy & hold)
+pub(crate) fn generate_rotation_grid(n_symbols: usize) -> Vec<DiscoveryStrategyType> {
+    let mut grid = Vec::new();
+    if n_symbols < 2 {
+        return grid;
+    }
+    for lookback in [4usize, 16, 48, 96, 192, 384, 672] {
+        for top_k in 1..n_symbols {
+            grid.push(DiscoveryStrategyType::RelativeStrength { lookback, top_k });
+        }
+    }
+    grid
+}
+
+/// Pairs grid: every pair of requested symbols × z-score window (12h to 4 days of
+/// 15m bars) × entry threshold × exit threshold
+pub(crate) fn generate_pair_spread_grid(symbols: &[String]) -> Vec<DiscoveryStrategyType> {
+    let mut grid = Vec::new();
+    for (i, leg_a) in symbols.iter().enumerate() {
+        for leg_b in &symbols[i + 1..] {
+            for lookback in [48usize, 96, 192, 384] {
+                for entry_z in [1.5f64, 2.0, 2.5] {
+                    for exit_z in [0.0f64, 0.5] {
+                        grid.push(DiscoveryStrategyType::PairSpread {
+                            leg_a: leg_a.clone(),
+                            leg_b: leg_b.clone(),
+                            lookback,
+                            entry_z,
+                            exit_z,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    grid
+}
+
+/// ONNX grid: every requested model × probability threshold
+pub(crate) fn generate_onnx_grid(model_paths: &[String]) -> Vec<DiscoveryStrategyType> {
+    let mut grid = Vec::new();
+    for model_path in model_paths {
+        for threshold in [0.5f64, 0.55, 0.6, 0.7] {
+            grid.push(DiscoveryStrategyType::OnnxModel {
+                model_path: model_path.clone(),
+                threshold,
+            });
+        }
+    }
+    grid
+}
+
+/// Every cross-sectional strategy for a basket: rotation + all symbol pairs
+fn generate_cross_sectional_grid(matrix: &KlineMatrix) -> Vec<DiscoveryStrategyType> {
+    let mut grid = generate_rotation_grid(matrix.symbols.len());
+    grid.extend(generate_pair_spread_grid(&matrix.symbols));
+    grid
+}
+
 // Keep the old grid for legacy strategies that may still be in DB
 #[allow(dead_code)]
-fn generate_legacy_phase1_grid() -> Vec<DiscoveryStrategyType> {
+pub(crate) fn generate_legacy_phase1_grid() -> Vec<DiscoveryStrategyType> {
     let mut grid = Vec::with_capacity(500);
 
     // 1. RSI: 5 periods × 4 ob × 4 os = 80
@@ -914,9 +2110,21 @@ fn generate_legacy_phase1_grid() -> Vec<DiscoveryStrategyType> {
         }
     }
 
-    // 14. VWAP: 4 periods = 4
+    // 14. VWAP: 4 rolling periods + 2 periods × 3 anchors = 10
     for &period in &[10usize, 20, 30, 50] {
-        grid.push(DiscoveryStrategyType::Vwap { period });
+        grid.push(DiscoveryStrategyType::Vwap {
+            period,
+            anchor: VwapAnchor::Rolling,
+        });
+    }
+    for &period in &[10usize, 20] {
+        for anchor in [
+            VwapAnchor::Session,
+            VwapAnchor::Days { days: 7 },
+            VwapAnchor::SwingLow { lookback: 12 },
+        ] {
+            grid.push(DiscoveryStrategyType::Vwap { period, anchor });
+        }
     }
 
     // 15. OBV: 4 sma_periods = 4
@@ -1042,7 +2250,7 @@ fn generate_legacy_phase1_grid() -> Vec<DiscoveryStrategyType> {
 // ============================================================================
 
 #[allow(dead_code)]
-struct GenericBacktestResult {
+pub(crate) struct GenericBacktestResult {
     total_pnl: Decimal,
     total_fees: Decimal,
     total_trades: u32,
@@ -1060,14 +2268,93 @@ struct GenericBacktestResult {
     total_volume: Decimal,
     annualized_return_pct: Decimal,
     annualized_sharpe: Decimal,
+    aborted: Option<AbortReason>,
+    exit_breakdown: ExitBreakdown,
+    /// PnL and Sharpe of the time-decayed trades, with a decay half-life
+    decayed: Option<DecayedPerformance>,
+    /// Trades closed in the last 30 and 90 days of the bars run
+    recent_30d: Option<RecentTrades>,
+    recent_90d: Option<RecentTrades>,
+    pnl_concentration: Option<PnlConcentration>,
+    /// Longest drawdown and mean recovery time, in days
+    longest_drawdown_days: Option<Decimal>,
+    avg_recovery_days: Option<Decimal>,
+    /// Largest and mean position value over equity across the bars run, in %
+    max_exposure_pct: Option<Decimal>,
+    avg_exposure_pct: Option<Decimal>,
+    /// Leading bars whose signals were ignored, and the days tested after them
+    warmup_bars: Option<u32>,
+    effective_days: Option<Decimal>,
+    /// Entries the volume cap cut short (runs with a participation limit only)
+    unfilled: Option<UnfilledStats>,
+    /// Signal PnL, timing cost and fees of the trades
+    cost_attribution: Option<CostAttribution>,
+}
+
+/// Bars spent under the running equity peak
+#[derive(Debug, Default)]
+struct UnderwaterTracker {
+    /// Last bar the equity stood at its peak
+    peak_idx: usize,
+    longest: usize,
+    /// Bars from the peak back to it, of each drawdown that recovered
+    recoveries: Vec<usize>,
+}
+
+impl UnderwaterTracker {
+    fn on_bar(&mut self, idx: usize, at_peak: bool) {
+        let under = idx - self.peak_idx;
+        self.longest = self.longest.max(under);
+        if at_peak {
+            if under > 1 {
+                self.recoveries.push(under);
+            }
+            self.peak_idx = idx;
+        }
+    }
+
+    /// (longest drawdown, mean recovery) in days, for bars `bar_ms` apart
+    fn days(&self, bar_ms: i64) -> (Decimal, Option<Decimal>) {
+        let to_days = |bars: f64| f64_to_dec(bars * bar_ms as f64 / 86_400_000.0);
+        let recovery = (!self.recoveries.is_empty()).then(|| {
+            to_days(self.recoveries.iter().sum::<usize>() as f64 / self.recoveries.len() as f64)
+        });
+        (to_days(self.longest as f64), recovery)
+    }
+}
+
+/// Metrics of the trades closed in a trailing window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RecentTrades {
+    pub net_pnl: Decimal,
+    pub win_rate: Decimal,
+    pub total_trades: u32,
+    pub sharpe_ratio: Decimal,
+}
+
+/// Net PnL and Sharpe ratio of trades weighted by recency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct DecayedPerformance {
+    pub net_pnl: Decimal,
+    pub sharpe_ratio: Decimal,
 }
 
 struct OpenPosition {
-    entry_price: Decimal,
-    size: Decimal,
+    entry_idx: usize,
+    entry_price: f64,
+    size: f64,
+    /// Highest price since entry and the trailing stop level under it
+    high: f64,
+    stop_level: Option<f64>,
+    /// Quantity of a volume-capped entry still to fill
+    unfilled: f64,
+    /// Entry fees paid, and PnL the entry fills lost against their signal prices
+    fees: f64,
+    timing_cost: f64,
 }
 
-/// Estimate Polymarket probability from price change percentage.
+/// Estimate Polymarket probability from price change percentage (the default
+/// `LinearModel`, see [`crate::probability`]).
 /// Maps price movement to a probability in [0.05, 0.95].
 /// At 0% change → p=0.50 (max fees). Large moves push p toward extremes (lower fees).
 pub fn estimate_poly_probability(entry_price: Decimal, current_price: Decimal) -> Decimal {
@@ -1082,170 +2369,455 @@ pub fn estimate_poly_probability(entry_price: Decimal, current_price: Decimal) -
     Decimal::from_str_exact(&format!("{:.4}", p_f64)).unwrap_or(dec!(0.50))
 }
 
-fn run_generic_backtest(
+/// f64 version of [`estimate_poly_probability`] (same 4-decimal rounding) for per-bar use
+pub fn estimate_poly_probability_f64(entry_price: f64, current_price: f64) -> f64 {
+    if entry_price <= 0.0 {
+        return 0.50;
+    }
+    let change_pct = (current_price - entry_price) / entry_price * 100.0;
+    let p = (0.5 + change_pct * 0.05).clamp(0.05, 0.95);
+    (p * 10_000.0).round() / 10_000.0
+}
+
+/// Bars between two checks of the time budget
+const TIME_BUDGET_CHECK_BARS: usize = 1024;
+
+pub(crate) fn run_generic_backtest(
     generator: &mut dyn SignalGenerator,
     klines: &[Kline],
     initial_capital: Decimal,
     base_position_pct: Decimal,
     sizing_mode: SizingMode,
     fee_config: &PolymarketFeeConfig,
+    limits: &BacktestLimits,
 ) -> GenericBacktestResult {
-    let hundred = dec!(100);
+    run_generic_backtest_recording(
+        generator,
+        klines,
+        initial_capital,
+        base_position_pct,
+        sizing_mode,
+        fee_config,
+        limits,
+        None,
+    )
+}
+
+/// Bars and trades of a recorded backtest
+#[derive(Debug, Default)]
+pub(crate) struct BacktestRecording {
+    /// (open time, mark-to-market equity) of every bar run
+    pub equity: Vec<(i64, f64)>,
+    /// Closed trades in exit order, a position still open at the end included
+    pub trades: Vec<BacktestTrade>,
+}
+
+/// Replay `strategy` on `klines` like discovery does (10 000 capital, 10 % base
+/// position, the strategy's trailing stop), recording every bar and trade
+pub(crate) fn record_backtest(
+    strategy: &DiscoveryStrategyType,
+    klines: &[Kline],
+    symbol: &str,
+    sizing_mode: SizingMode,
+    fee_config: &PolymarketFeeConfig,
+) -> BacktestRecording {
+    let mut generator = IndicatorSeriesCache::new(1).build_generator(strategy, symbol, klines);
+    let limits = BacktestLimits::default().with_trailing_stop(strategy.trailing_stop());
+    let mut recording = BacktestRecording {
+        equity: Vec::with_capacity(klines.len()),
+        trades: Vec::new(),
+    };
+    run_generic_backtest_recording(
+        generator.as_mut(),
+        klines,
+        dec!(10000),
+        dec!(10),
+        sizing_mode,
+        fee_config,
+        &limits,
+        Some(&mut recording),
+    );
+    recording
+}
+
+/// [`run_generic_backtest`], recording the (open time, mark-to-market equity) of every
+/// bar run and the trades to `recording`
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_generic_backtest_recording(
+    generator: &mut dyn SignalGenerator,
+    klines: &[Kline],
+    initial_capital: Decimal,
+    base_position_pct: Decimal,
+    sizing_mode: SizingMode,
+    fee_config: &PolymarketFeeConfig,
+    limits: &BacktestLimits,
+    mut recording: Option<&mut BacktestRecording>,
+) -> GenericBacktestResult {
+    let started = Instant::now();
+    // f64 fast path: prices are extracted once and the loop never touches Decimal;
+    // Decimal only comes back for the trade log and the final report
+    let closes: Vec<f64> = klines.iter().map(close_f64).collect();
+    let initial = initial_capital.to_f64().unwrap_or(0.0);
+    let base_pct = base_position_pct.to_f64().unwrap_or(0.0);
+    let ruin_equity = limits
+        .ruin_threshold_pct
+        .map(|pct| initial * pct.to_f64().unwrap_or(0.0) / 100.0);
+    let mut aborted = None;
+    let mut bars_run = 0usize;
     // Use first kline close as baseline for probability estimation
-    let baseline_price = klines.first().map(|k| k.close).unwrap_or(dec!(1));
-    let mut equity = initial_capital;
+    let baseline_price = closes.first().copied().unwrap_or(1.0);
+    let probability_model = &fee_config.probability_model;
+    let mut equity = initial;
     let mut peak_equity = equity;
-    let mut max_drawdown_pct = Decimal::ZERO;
+    let mut max_drawdown_pct = 0.0f64;
+    let mut underwater = UnderwaterTracker::default();
+    let mut max_exposure_pct = 0.0f64;
+    let mut exposure_sum = 0.0f64;
+    let mut total_fees = 0.0f64;
     let mut position: Option<OpenPosition> = None;
     let mut trades: Vec<BacktestTrade> = Vec::new();
 
-    // Sliding window for Kelly
-    let mut recent_wins = 0u32;
-    let mut recent_total = 0u32;
-    let mut recent_avg_win = Decimal::ZERO;
-    let mut recent_avg_loss = Decimal::ZERO;
+    let mut sizer = PositionSizer::new(sizing_mode, base_pct)
+        .with_throttle(limits.drawdown_throttle)
+        .with_max_exposure(limits.max_exposure_pct);
+    // Entry of a signal skipped at zero size: its round trip still feeds the Kelly
+    // window and the throttle, so a sizer that stopped trading can pick up again
+    let mut shadow_entry: Option<f64> = None;
+    let fill_model = limits.fill_model;
+    let filters = limits.symbol_filters;
+    let fill = |idx: usize, close: f64, side: TradeSide| {
+        let price = match fill_model {
+            FillModel::Close => close,
+            _ => fill_model.fill_price(klines, idx, side),
+        };
+        filters.map_or(price, |f| f.round_price(price, side))
+    };
+    let stops = limits
+        .trailing_stop
+        .map(|stop| StopTracker::new(stop, klines));
+    let participation = limits.participation;
+    // Quantity a volume-capped order signalled on bar `idx` can fill
+    let capacity = |limit: ParticipationLimit, idx: usize| {
+        let volume = fill_model.fill_bar(klines, idx).volume;
+        limit.capacity(volume.to_f64().unwrap_or(0.0))
+    };
+    let mut unfilled = UnfilledStats::default();
+    let (mut ordered, mut filled) = (0.0f64, 0.0f64);
+    let mut costs = CostAttribution::default();
+    let warmup = generator.warmup_bars();
 
-    for kline in klines {
+    for (idx, (kline, &close)) in klines.iter().zip(&closes).enumerate() {
+        bars_run += 1;
         let sig = generator.on_bar(kline);
+        // Signals of the warm-up bars come from indicators still converging
+        let sig = if idx < warmup {
+            SignalWithConfidence::hold()
+        } else {
+            sig
+        };
+
+        // Trailing stop: hit when the bar trades through the level set by the previous
+        // bars, filled at the level (at the open when the bar gaps below it)
+        let stop_hit = position
+            .as_ref()
+            .filter(|pos| idx > pos.entry_idx)
+            .and_then(|pos| pos.stop_level)
+            .filter(|&level| kline.low.to_f64().is_some_and(|low| low <= level));
+        if let Some(level) = stop_hit {
+            let pos = position
+                .take()
+                .expect("only an open position hits its stop");
+            let open = kline.open.to_f64().unwrap_or(close);
+            let price = level.min(open);
+            let price = filters.map_or(price, |f| f.round_price(price, TradeSide::Sell));
+            let pnl = (price - pos.entry_price) * pos.size;
+            let p_exit = probability_model.probability(baseline_price, price);
+            let exit_fee = calculate_taker_fee_f64(pos.size, p_exit, fee_config);
+
+            let reason = if level > pos.entry_price {
+                ExitReason::Trailing
+            } else {
+                ExitReason::Stop
+            };
+
+            equity += pnl - exit_fee;
+            total_fees += exit_fee;
+            // A stop decides at its level: a gap through it is the fill's cost
+            let timing = pos.timing_cost + (level - price) * pos.size;
+            costs.record_trade(pnl, pos.fees + exit_fee, timing);
+            trades.push(close_trade(&pos, klines, kline, price, pnl, reason));
+            sizer.record_trade(trade_return(pos.entry_price, price));
+        }
 
         match sig.signal {
+            // A stopped-out position is not re-entered on the same bar
+            _ if stop_hit.is_some() => {}
             crate::strategy::Signal::Buy => {
                 if position.is_none() {
-                    // Calculate position size
-                    let size_pct = match sizing_mode {
-                        SizingMode::Fixed => base_position_pct,
-                        SizingMode::Kelly => {
-                            if recent_total >= 10 && recent_avg_loss > Decimal::ZERO {
-                                let p = Decimal::from(recent_wins) / Decimal::from(recent_total);
-                                let b = recent_avg_win / recent_avg_loss;
-                                let q = Decimal::ONE - p;
-                                let kelly = if b > Decimal::ZERO {
-                                    ((p * b - q) / b) * hundred
-                                } else {
-                                    Decimal::ZERO
-                                };
-                                kelly.max(Decimal::ZERO).min(dec!(25)) // Cap at 25%
-                            } else {
-                                base_position_pct
-                            }
-                        }
-                        SizingMode::ConfidenceWeighted => {
-                            let conf = Decimal::from_str_exact(&format!("{:.4}", sig.confidence))
-                                .unwrap_or(Decimal::ONE);
-                            base_position_pct * conf
-                        }
-                    };
-
-                    if size_pct <= Decimal::ZERO {
+                    let price = fill(idx, close, TradeSide::Buy);
+                    let size_pct = sizer.entry_pct(sig.confidence);
+                    let shares = equity * size_pct / 100.0 / price;
+                    // Below the symbol's minimum order rounds to zero: nothing is placed
+                    let shares = filters.map_or(shares, |f| f.round_quantity(shares, price));
+                    // Volume cap: what the bar cannot fill carries or is dropped, and
+                    // an entry it cannot fill at all is not placed
+                    let wanted = shares;
+                    let shares = participation.map_or(shares, |limit| {
+                        let cap = shares.min(capacity(limit, idx));
+                        filters.map_or(cap, |f| f.round_quantity(cap, price))
+                    });
+                    if participation.is_some() && size_pct > 0.0 && wanted > 0.0 {
+                        unfilled.entries += 1;
+                        unfilled.partial_entries += u32::from(shares < wanted);
+                        ordered += wanted;
+                        filled += shares;
+                    }
+                    if size_pct <= 0.0 || shares == 0.0 {
+                        shadow_entry.get_or_insert(price);
                         continue;
                     }
+                    shadow_entry = None;
+                    let carried = match participation.map(|limit| limit.remainder) {
+                        Some(UnfilledRemainder::Carry) => wanted - shares,
+                        _ => 0.0,
+                    };
 
-                    let position_value = equity * size_pct / hundred;
-                    let shares = position_value / kline.close;
-
-                    // Entry fee — estimate probability from current price vs baseline
-                    let p_entry = estimate_poly_probability(baseline_price, kline.close);
-                    let entry_fee = calculate_taker_fee(shares, p_entry, fee_config);
+                    // Entry fee — probability model on the fill price vs baseline
+                    let p_entry = probability_model.probability(baseline_price, price);
+                    let entry_fee = calculate_taker_fee_f64(shares, p_entry, fee_config);
                     equity -= entry_fee;
+                    total_fees += entry_fee;
 
                     position = Some(OpenPosition {
-                        entry_price: kline.close,
+                        entry_idx: idx,
+                        entry_price: price,
                         size: shares,
+                        high: price,
+                        stop_level: stops.as_ref().map(|s| s.level(price, idx)),
+                        unfilled: carried,
+                        fees: entry_fee,
+                        timing_cost: (price - close) * shares,
                     });
                 }
             }
             crate::strategy::Signal::Sell => {
                 if let Some(pos) = position.take() {
-                    let pnl = (kline.close - pos.entry_price) * pos.size;
-                    let p_exit = estimate_poly_probability(baseline_price, kline.close);
-                    let exit_fee = calculate_taker_fee(pos.size, p_exit, fee_config);
-
-                    let pnl_pct = if pos.entry_price > Decimal::ZERO {
-                        (kline.close - pos.entry_price) / pos.entry_price * hundred
-                    } else {
-                        Decimal::ZERO
-                    };
+                    let price = fill(idx, close, TradeSide::Sell);
+                    let pnl = (price - pos.entry_price) * pos.size;
+                    let p_exit = probability_model.probability(baseline_price, price);
+                    let exit_fee = calculate_taker_fee_f64(pos.size, p_exit, fee_config);
 
                     equity += pnl - exit_fee;
-
-                    trades.push(BacktestTrade {
-                        entry_time: 0,
-                        exit_time: kline.open_time,
-                        side: TradeSide::Buy,
-                        entry_price: pos.entry_price,
-                        exit_price: kline.close,
-                        size: pos.size,
+                    total_fees += exit_fee;
+                    let timing = pos.timing_cost + (close - price) * pos.size;
+                    costs.record_trade(pnl, pos.fees + exit_fee, timing);
+                    trades.push(close_trade(
+                        &pos,
+                        klines,
+                        kline,
+                        price,
                         pnl,
-                        pnl_pct,
-                    });
-
-                    // Update Kelly stats
-                    recent_total += 1;
-                    if pnl > Decimal::ZERO {
-                        recent_wins += 1;
-                        recent_avg_win = if recent_wins > 0 {
-                            (recent_avg_win * Decimal::from(recent_wins - 1) + pnl)
-                                / Decimal::from(recent_wins)
-                        } else {
-                            pnl
-                        };
-                    } else {
-                        let losses = recent_total - recent_wins;
-                        recent_avg_loss = if losses > 0 {
-                            (recent_avg_loss * Decimal::from(losses - 1) + pnl.abs())
-                                / Decimal::from(losses)
-                        } else {
-                            pnl.abs()
-                        };
-                    }
+                        ExitReason::Signal,
+                    ));
+                    sizer.record_trade(trade_return(pos.entry_price, price));
+                } else if let Some(entry) = shadow_entry.take() {
+                    sizer.record_trade(trade_return(entry, fill(idx, close, TradeSide::Sell)));
                 }
             }
             crate::strategy::Signal::Hold => {}
         }
 
+        // The carried remainder of a capped entry fills on the next bars
+        if let (Some(limit), Some(pos)) = (participation, position.as_mut()) {
+            if pos.unfilled > 0.0 && idx > pos.entry_idx {
+                let price = fill(idx, close, TradeSide::Buy);
+                let add = pos.unfilled.min(capacity(limit, idx));
+                let add = filters.map_or(add, |f| f.round_quantity(add, price));
+                if add > 0.0 {
+                    let p_entry = probability_model.probability(baseline_price, price);
+                    let entry_fee = calculate_taker_fee_f64(add, p_entry, fee_config);
+                    equity -= entry_fee;
+                    total_fees += entry_fee;
+                    pos.fees += entry_fee;
+                    pos.timing_cost += (price - close) * add;
+                    pos.entry_price = (pos.entry_price * pos.size + price * add) / (pos.size + add);
+                    pos.size += add;
+                    pos.unfilled = (pos.unfilled - add).max(0.0);
+                    filled += add;
+                }
+            }
+        }
+
+        // Ratchet the stop up under the new high
+        if let (Some(stops), Some(pos)) = (&stops, position.as_mut()) {
+            if idx > pos.entry_idx {
+                pos.high = pos.high.max(kline.high.to_f64().unwrap_or(close));
+                let level = stops.level(pos.high, idx);
+                pos.stop_level = pos.stop_level.map(|current| current.max(level));
+            }
+        }
+
         // Track drawdown
         let unrealized = position
             .as_ref()
-            .map(|pos| (kline.close - pos.entry_price) * pos.size)
-            .unwrap_or(Decimal::ZERO);
+            .map(|pos| (close - pos.entry_price) * pos.size)
+            .unwrap_or(0.0);
         let current_equity = equity + unrealized;
+        if let Some(recording) = recording.as_deref_mut() {
+            recording.equity.push((kline.open_time, current_equity));
+        }
 
         if current_equity > peak_equity {
             peak_equity = current_equity;
         }
-        if peak_equity > Decimal::ZERO {
-            let dd_pct = (peak_equity - current_equity) / peak_equity * hundred;
+        underwater.on_bar(idx, current_equity >= peak_equity);
+        if let Some(pos) = position.as_ref().filter(|_| current_equity > 0.0) {
+            let exposure_pct = close * pos.size / current_equity * 100.0;
+            max_exposure_pct = max_exposure_pct.max(exposure_pct);
+            exposure_sum += exposure_pct;
+        }
+        if peak_equity > 0.0 {
+            let dd_pct = (peak_equity - current_equity) / peak_equity * 100.0;
             if dd_pct > max_drawdown_pct {
                 max_drawdown_pct = dd_pct;
             }
         }
+
+        // Early termination: the parameter set is ruined or too slow to be worth finishing
+        if ruin_equity.is_some_and(|ruin| current_equity < ruin) {
+            aborted = Some(AbortReason::Ruin);
+            break;
+        }
+        if bars_run.is_multiple_of(TIME_BUDGET_CHECK_BARS)
+            && limits
+                .time_budget
+                .is_some_and(|budget| started.elapsed() > budget)
+        {
+            aborted = Some(AbortReason::TimeBudget);
+            break;
+        }
     }
 
-    // Close any remaining position at last bar price
+    // Close any remaining position at the last bar run
     if let Some(pos) = position.take() {
-        if let Some(last) = klines.last() {
-            let pnl = (last.close - pos.entry_price) * pos.size;
-            let p_exit = estimate_poly_probability(baseline_price, last.close);
-            let exit_fee = calculate_taker_fee(pos.size, p_exit, fee_config);
+        if let Some(last) = klines[..bars_run].last() {
+            let close = closes[bars_run - 1];
+            let pnl = (close - pos.entry_price) * pos.size;
+            let p_exit = probability_model.probability(baseline_price, close);
+            let exit_fee = calculate_taker_fee_f64(pos.size, p_exit, fee_config);
             equity += pnl - exit_fee;
-            trades.push(BacktestTrade {
-                entry_time: 0,
-                exit_time: last.open_time,
-                side: TradeSide::Buy,
-                entry_price: pos.entry_price,
-                exit_price: last.close,
-                size: pos.size,
+            total_fees += exit_fee;
+            costs.record_trade(pnl, pos.fees + exit_fee, pos.timing_cost);
+            trades.push(close_trade(
+                &pos,
+                klines,
+                last,
+                close,
                 pnl,
-                pnl_pct: if pos.entry_price > Decimal::ZERO {
-                    (last.close - pos.entry_price) / pos.entry_price * hundred
-                } else {
-                    Decimal::ZERO
-                },
-            });
+                ExitReason::Timeout,
+            ));
+        }
+    }
+
+    // The test period starts once the warm-up is over
+    let tested_bars = bars_run.saturating_sub(warmup);
+    let mut result = summarize_trades(
+        &trades,
+        initial_capital,
+        f64_to_dec(equity),
+        f64_to_dec(total_fees),
+        f64_to_dec(max_drawdown_pct),
+        tested_bars,
+    );
+    result.aborted = aborted;
+    if let Some(end) = klines[..bars_run].last() {
+        result.decayed = limits
+            .decay_half_life_days
+            .map(|half_life| decayed_performance(&trades, total_fees, end.open_time, half_life));
+        result.recent_30d = Some(recent_trades(&trades, total_fees, end.open_time, 30));
+        result.recent_90d = Some(recent_trades(&trades, total_fees, end.open_time, 90));
+    }
+    let bar_ms = match klines {
+        [first, second, ..] => second.open_time - first.open_time,
+        _ => 0,
+    };
+    result.warmup_bars = Some(warmup.min(bars_run) as u32);
+    result.effective_days = Some(f64_to_dec(
+        tested_bars as f64 * bar_ms as f64 / 86_400_000.0,
+    ));
+    let (longest, recovery) = underwater.days(bar_ms);
+    result.longest_drawdown_days = Some(longest);
+    result.avg_recovery_days = recovery;
+    result.max_exposure_pct = Some(f64_to_dec(max_exposure_pct));
+    result.avg_exposure_pct = Some(f64_to_dec(exposure_sum / bars_run.max(1) as f64));
+    if participation.is_some() {
+        if ordered > 0.0 {
+            unfilled.unfilled_pct = (ordered - filled) / ordered * 100.0;
+        }
+        result.unfilled = Some(unfilled);
+    }
+    result.cost_attribution = Some(costs);
+    if let Some(recording) = recording {
+        recording.trades = trades;
+    }
+    result
+}
+
+/// Price return of a round trip, the unit of the Kelly window
+fn trade_return(entry_price: f64, exit_price: f64) -> f64 {
+    if entry_price > 0.0 {
+        (exit_price - entry_price) / entry_price
+    } else {
+        0.0
+    }
+}
+
+/// Decimal trade record for a position closed on `exit` at `exit_price` (fills at a
+/// bar's close keep the kline's own Decimal)
+fn close_trade(
+    pos: &OpenPosition,
+    klines: &[Kline],
+    exit: &Kline,
+    exit_price: f64,
+    pnl: f64,
+    exit_reason: ExitReason,
+) -> BacktestTrade {
+    let decimal_price = |kline: &Kline, price: f64| {
+        if close_f64(kline) == price {
+            kline.close
+        } else {
+            f64_to_dec(price)
         }
+    };
+    let pnl_pct = if pos.entry_price > 0.0 {
+        f64_to_dec((exit_price - pos.entry_price) / pos.entry_price * 100.0)
+    } else {
+        Decimal::ZERO
+    };
+    BacktestTrade {
+        entry_time: 0,
+        exit_time: exit.open_time,
+        side: TradeSide::Buy,
+        entry_price: decimal_price(&klines[pos.entry_idx], pos.entry_price),
+        exit_price: decimal_price(exit, exit_price),
+        size: f64_to_dec(pos.size),
+        pnl: f64_to_dec(pnl),
+        pnl_pct,
+        exit_reason,
     }
+}
 
-    // Calculate metrics
+/// Trade statistics shared by the single-symbol and cross-sectional backtests.
+/// `period_bars` is the number of 15m bars covered (for annualization).
+fn summarize_trades(
+    trades: &[BacktestTrade],
+    initial_capital: Decimal,
+    final_equity: Decimal,
+    total_fees: Decimal,
+    max_drawdown_pct: Decimal,
+    period_bars: usize,
+) -> GenericBacktestResult {
+    let hundred = dec!(100);
     let total_trades = trades.len() as u32;
     let winning_trades = trades.iter().filter(|t| t.pnl > Decimal::ZERO).count() as u32;
     let losing_trades = total_trades - winning_trades;
@@ -1256,19 +2828,7 @@ fn run_generic_backtest(
         Decimal::ZERO
     };
 
-    let total_pnl = equity - initial_capital;
-
-    // Calculate total fees from entry+exit on each trade
-    let total_fees = {
-        let mut fees = Decimal::ZERO;
-        for trade in &trades {
-            let p_entry = estimate_poly_probability(baseline_price, trade.entry_price);
-            let p_exit = estimate_poly_probability(baseline_price, trade.exit_price);
-            fees += calculate_taker_fee(trade.size, p_entry, fee_config)
-                + calculate_taker_fee(trade.size, p_exit, fee_config);
-        }
-        fees
-    };
+    let total_pnl = final_equity - initial_capital;
 
     let gross_profits: Decimal = trades
         .iter()
@@ -1288,7 +2848,7 @@ fn run_generic_backtest(
         Decimal::ZERO
     };
 
-    let sharpe_ratio = calculate_sharpe(&trades);
+    let sharpe_ratio = calculate_sharpe(trades);
 
     let avg_trade_pnl = if total_trades > 0 {
         total_pnl / Decimal::from(total_trades)
@@ -1299,13 +2859,13 @@ fn run_generic_backtest(
     // --- Advanced metrics ---
 
     // Sortino ratio: mean(returns) / std_dev(negative_returns_only)
-    let sortino_ratio = calculate_sortino(&trades);
+    let sortino_ratio = calculate_sortino(trades);
 
     // Max consecutive losses
     let max_consecutive_losses = {
         let mut max_streak = 0u32;
         let mut current_streak = 0u32;
-        for trade in &trades {
+        for trade in trades {
             if trade.pnl < Decimal::ZERO {
                 current_streak += 1;
                 if current_streak > max_streak {
@@ -1338,7 +2898,7 @@ fn run_generic_backtest(
 
     // Annualized return: ((1 + total_return)^(365/period_days) - 1) * 100
     let annualized_return_pct = {
-        let period_bars = klines.len() as f64;
+        let period_bars = period_bars as f64;
         let period_days = period_bars / 96.0; // 96 bars per day (15min)
         if period_days > 0.0 && initial_capital > Decimal::ZERO {
             let total_return_f: f64 = (total_pnl / initial_capital)
@@ -1357,7 +2917,7 @@ fn run_generic_backtest(
 
     // Annualized Sharpe: sharpe * sqrt(365 / period_days)
     let annualized_sharpe = {
-        let period_bars = klines.len() as f64;
+        let period_bars = period_bars as f64;
         let period_days = period_bars / 96.0;
         if period_days > 0.0 {
             let sharpe_f: f64 = sharpe_ratio.to_string().parse().unwrap_or(0.0);
@@ -1386,6 +2946,20 @@ fn run_generic_backtest(
         total_volume,
         annualized_return_pct,
         annualized_sharpe,
+        aborted: None,
+        exit_breakdown: ExitBreakdown::from_trades(trades),
+        decayed: None,
+        recent_30d: None,
+        recent_90d: None,
+        pnl_concentration: PnlConcentration::from_trades(trades),
+        longest_drawdown_days: None,
+        avg_recovery_days: None,
+        max_exposure_pct: None,
+        avg_exposure_pct: None,
+        warmup_bars: None,
+        effective_days: None,
+        unfilled: None,
+        cost_attribution: None,
     }
 }
 
@@ -1412,20 +2986,102 @@ fn calculate_sharpe(trades: &[BacktestTrade]) -> Decimal {
     Decimal::from_str_exact(&format!("{:.2}", sharpe)).unwrap_or(Decimal::ZERO)
 }
 
-fn calculate_sortino(trades: &[BacktestTrade]) -> Decimal {
-    if trades.len() < 2 {
-        return Decimal::ZERO;
-    }
-
-    let returns: Vec<f64> = trades
+/// PnL and Sharpe of `trades` each weighted by `0.5^(age / half_life)`, its age running
+/// from its exit to `end_time` (ms). Fees are spread evenly over the trades.
+pub(crate) fn decayed_performance(
+    trades: &[BacktestTrade],
+    total_fees: f64,
+    end_time: i64,
+    half_life_days: f64,
+) -> DecayedPerformance {
+    const DAY_MS: f64 = 86_400_000.0;
+    let fee_share = total_fees / trades.len().max(1) as f64;
+    let weighted: Vec<(f64, f64, f64)> = trades
         .iter()
-        .map(|t| t.pnl_pct.to_string().parse::<f64>().unwrap_or(0.0))
+        .map(|t| {
+            let age_days = (end_time - t.exit_time).max(0) as f64 / DAY_MS;
+            let weight = 0.5f64.powf(age_days / half_life_days);
+            let pnl = t.pnl.to_f64().unwrap_or(0.0) - fee_share;
+            (weight, pnl, t.pnl_pct.to_f64().unwrap_or(0.0))
+        })
         .collect();
+    let net_pnl = weighted.iter().map(|(w, pnl, _)| w * pnl).sum::<f64>();
 
-    let n = returns.len() as f64;
-    let mean = returns.iter().sum::<f64>() / n;
-
-    // Downside deviation: std_dev of negative returns only
+    // Weighted mean and (reliability-weighted, unbiased) std dev of the returns
+    let weights = weighted.iter().map(|(w, _, _)| w).sum::<f64>();
+    let squares = weighted.iter().map(|(w, _, _)| w * w).sum::<f64>();
+    let sharpe_ratio = if weighted.len() < 2 || weights <= 0.0 {
+        Decimal::ZERO
+    } else {
+        let mean = weighted.iter().map(|(w, _, r)| w * r).sum::<f64>() / weights;
+        let spread = weighted
+            .iter()
+            .map(|(w, _, r)| w * (r - mean).powi(2))
+            .sum::<f64>();
+        let denominator = weights - squares / weights;
+        let std_dev = if denominator > 0.0 {
+            (spread / denominator).sqrt()
+        } else {
+            0.0
+        };
+        if std_dev < 1e-10 {
+            Decimal::ZERO
+        } else {
+            Decimal::from_str_exact(&format!("{:.2}", mean / std_dev)).unwrap_or(Decimal::ZERO)
+        }
+    };
+
+    DecayedPerformance {
+        net_pnl: f64_to_dec(net_pnl),
+        sharpe_ratio,
+    }
+}
+
+/// Metrics of the trades that closed in the last `days` before `end_time` (ms), with
+/// their even share of the backtest's fees
+pub(crate) fn recent_trades(
+    trades: &[BacktestTrade],
+    total_fees: f64,
+    end_time: i64,
+    days: u32,
+) -> RecentTrades {
+    let start = end_time - i64::from(days) * 86_400_000;
+    let fee_share = total_fees / trades.len().max(1) as f64;
+    let recent: Vec<BacktestTrade> = trades
+        .iter()
+        .filter(|t| t.exit_time > start)
+        .cloned()
+        .collect();
+    let total_trades = recent.len() as u32;
+    let pnl: Decimal = recent.iter().map(|t| t.pnl).sum();
+    let winning = recent.iter().filter(|t| t.pnl > Decimal::ZERO).count();
+    let win_rate = if total_trades > 0 {
+        Decimal::from(winning as u32) / Decimal::from(total_trades) * dec!(100)
+    } else {
+        Decimal::ZERO
+    };
+    RecentTrades {
+        net_pnl: pnl - f64_to_dec(fee_share * total_trades as f64),
+        win_rate,
+        total_trades,
+        sharpe_ratio: calculate_sharpe(&recent),
+    }
+}
+
+fn calculate_sortino(trades: &[BacktestTrade]) -> Decimal {
+    if trades.len() < 2 {
+        return Decimal::ZERO;
+    }
+
+    let returns: Vec<f64> = trades
+        .iter()
+        .map(|t| t.pnl_pct.to_string().parse::<f64>().unwrap_or(0.0))
+        .collect();
+
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+
+    // Downside deviation: std_dev of negative returns only
     let negative_returns: Vec<f64> = returns.iter().filter(|&&r| r < 0.0).copied().collect();
     if negative_returns.is_empty() {
         // No negative returns — perfect Sortino
@@ -1452,9 +3108,9 @@ fn calculate_sortino(trades: &[BacktestTrade]) -> Decimal {
 // Scoring
 // ============================================================================
 
-fn score_result(result: &DiscoveryResult, initial_capital: Decimal) -> Decimal {
-    // Minimum 5 trades for statistical significance
-    if result.total_trades < 5 {
+pub(crate) fn score_result(result: &DiscoveryResult, initial_capital: Decimal) -> Decimal {
+    // Minimum 5 trades for statistical significance; aborted runs are partial
+    if result.total_trades < 5 || result.abort_reason.is_some() {
         return dec!(-9999);
     }
 
@@ -1514,7 +3170,20 @@ fn score_result(result: &DiscoveryResult, initial_capital: Decimal) -> Decimal {
 // Phase 2 Refinement
 // ============================================================================
 
-fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoveryStrategyType> {
+pub(crate) fn generate_refinement_grid(
+    strategy: &DiscoveryStrategyType,
+) -> Vec<DiscoveryStrategyType> {
+    generate_scaled_refinement_grid(strategy, 1.0)
+}
+
+/// Neighbours of `strategy` with every parameter step multiplied by `delta_scale`
+/// (`RefinementDepth::delta_scale`); scaled integer steps stay at least 1
+pub(crate) fn generate_scaled_refinement_grid(
+    strategy: &DiscoveryStrategyType,
+    delta_scale: f64,
+) -> Vec<DiscoveryStrategyType> {
+    let step = |deltas: &[i32]| scale_steps(deltas, delta_scale);
+    let scale = |d: f64| d * delta_scale;
     let mut variants = Vec::new();
 
     match strategy {
@@ -1523,9 +3192,9 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
             overbought,
             oversold,
         } => {
-            for dp in [-2i32, -1, 0, 1, 2] {
-                for dob in [-2.5f64, 0.0, 2.5] {
-                    for dos in [-2.5f64, 0.0, 2.5] {
+            for dp in step(&[-2, -1, 0, 1, 2]) {
+                for dob in [-2.5f64, 0.0, 2.5].map(scale) {
+                    for dos in [-2.5f64, 0.0, 2.5].map(scale) {
                         let p = (*period as i32 + dp).max(3) as usize;
                         let ob = overbought + dob;
                         let os = oversold + dos;
@@ -1541,8 +3210,8 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
             }
         }
         DiscoveryStrategyType::BollingerBands { period, multiplier } => {
-            for dp in [-2i32, 0, 2] {
-                for dm in [-0.25f64, 0.0, 0.25] {
+            for dp in step(&[-2, 0, 2]) {
+                for dm in [-0.25f64, 0.0, 0.25].map(scale) {
                     let p = (*period as i32 + dp).max(5) as usize;
                     let m = multiplier + dm;
                     if m > 0.5 {
@@ -1555,9 +3224,9 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
             }
         }
         DiscoveryStrategyType::Macd { fast, slow, signal } => {
-            for df in [-1i32, 0, 1] {
-                for ds in [-2i32, 0, 2] {
-                    for dsig in [-1i32, 0, 1] {
+            for df in step(&[-1, 0, 1]) {
+                for ds in step(&[-2, 0, 2]) {
+                    for dsig in step(&[-1, 0, 1]) {
                         let f = (*fast as i32 + df).max(3) as usize;
                         let s = (*slow as i32 + ds).max(5) as usize;
                         let sig = (*signal as i32 + dsig).max(2) as usize;
@@ -1576,8 +3245,8 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
             fast_period,
             slow_period,
         } => {
-            for df in [-2i32, 0, 2] {
-                for ds in [-3i32, 0, 3] {
+            for df in step(&[-2, 0, 2]) {
+                for ds in step(&[-3, 0, 3]) {
                     let f = (*fast_period as i32 + df).max(3) as usize;
                     let s = (*slow_period as i32 + ds).max(5) as usize;
                     if f < s {
@@ -1594,9 +3263,10 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
             bid_offset,
             spread_multiplier,
         } => {
-            for dmpc in &[dec!(-0.01), dec!(0), dec!(0.01)] {
-                for dbo in &[dec!(-0.005), dec!(0), dec!(0.005)] {
-                    for dsm in &[dec!(-0.5), dec!(0), dec!(0.5)] {
+            let decimal_scale = Decimal::try_from(delta_scale).unwrap_or(Decimal::ONE);
+            for dmpc in [dec!(-0.01), dec!(0), dec!(0.01)].map(|d| d * decimal_scale) {
+                for dbo in [dec!(-0.005), dec!(0), dec!(0.005)].map(|d| d * decimal_scale) {
+                    for dsm in [dec!(-0.5), dec!(0), dec!(0.5)].map(|d| d * decimal_scale) {
                         let mpc = (*max_pair_cost + dmpc).max(dec!(0.85)).min(dec!(0.99));
                         let bo = (*bid_offset + dbo).max(dec!(0.001));
                         let sm = (*spread_multiplier + dsm).max(dec!(1));
@@ -1609,14 +3279,63 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
                 }
             }
         }
+        DiscoveryStrategyType::RelativeStrength { lookback, top_k } => {
+            for dl in [-0.5f64, -0.25, 0.0, 0.25, 0.5].map(scale) {
+                for dk in step(&[-1, 0, 1]) {
+                    let l = ((*lookback as f64) * (1.0 + dl)).round().max(2.0) as usize;
+                    let k = (*top_k as i32 + dk).max(1) as usize;
+                    variants.push(DiscoveryStrategyType::RelativeStrength {
+                        lookback: l,
+                        top_k: k,
+                    });
+                }
+            }
+        }
+        DiscoveryStrategyType::OnnxModel {
+            model_path,
+            threshold,
+        } => {
+            for dt in [-0.1f64, -0.05, 0.0, 0.05, 0.1].map(scale) {
+                variants.push(DiscoveryStrategyType::OnnxModel {
+                    model_path: model_path.clone(),
+                    threshold: (threshold + dt).clamp(0.34, 0.95),
+                });
+            }
+        }
+        DiscoveryStrategyType::PairSpread {
+            leg_a,
+            leg_b,
+            lookback,
+            entry_z,
+            exit_z,
+        } => {
+            for dl in [-0.25f64, 0.0, 0.25].map(scale) {
+                for de in [-0.25f64, 0.0, 0.25].map(scale) {
+                    for dx in [-0.25f64, 0.0, 0.25].map(scale) {
+                        let l = ((*lookback as f64) * (1.0 + dl)).round().max(10.0) as usize;
+                        let entry = entry_z + de;
+                        let exit = (exit_z + dx).max(0.0);
+                        if exit < entry {
+                            variants.push(DiscoveryStrategyType::PairSpread {
+                                leg_a: leg_a.clone(),
+                                leg_b: leg_b.clone(),
+                                lookback: l,
+                                entry_z: entry,
+                                exit_z: exit,
+                            });
+                        }
+                    }
+                }
+            }
+        }
         DiscoveryStrategyType::Stochastic {
             period,
             overbought,
             oversold,
         } => {
-            for dp in [-2i32, -1, 0, 1, 2] {
-                for dob in [-2.5f64, 0.0, 2.5] {
-                    for dos in [-2.5f64, 0.0, 2.5] {
+            for dp in step(&[-2, -1, 0, 1, 2]) {
+                for dob in [-2.5f64, 0.0, 2.5].map(scale) {
+                    for dos in [-2.5f64, 0.0, 2.5].map(scale) {
                         let p = (*period as i32 + dp).max(3) as usize;
                         let ob = overbought + dob;
                         let os = oversold + dos;
@@ -1636,9 +3355,9 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
             sma_period,
             multiplier,
         } => {
-            for da in [-2i32, 0, 2] {
-                for ds in [-3i32, 0, 3] {
-                    for dm in [-0.25f64, 0.0, 0.25] {
+            for da in step(&[-2, 0, 2]) {
+                for ds in step(&[-3, 0, 3]) {
+                    for dm in [-0.25f64, 0.0, 0.25].map(scale) {
                         let a = (*atr_period as i32 + da).max(3) as usize;
                         let s = (*sma_period as i32 + ds).max(5) as usize;
                         let m = multiplier + dm;
@@ -1653,14 +3372,17 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
                 }
             }
         }
-        DiscoveryStrategyType::Vwap { period } => {
-            for dp in [-3i32, -1, 0, 1, 3] {
+        DiscoveryStrategyType::Vwap { period, anchor } => {
+            for dp in step(&[-3, -1, 0, 1, 3]) {
                 let p = (*period as i32 + dp).max(5) as usize;
-                variants.push(DiscoveryStrategyType::Vwap { period: p });
+                variants.push(DiscoveryStrategyType::Vwap {
+                    period: p,
+                    anchor: *anchor,
+                });
             }
         }
         DiscoveryStrategyType::Obv { sma_period } => {
-            for dp in [-2i32, -1, 0, 1, 2] {
+            for dp in step(&[-2, -1, 0, 1, 2]) {
                 let p = (*sma_period as i32 + dp).max(5) as usize;
                 variants.push(DiscoveryStrategyType::Obv { sma_period: p });
             }
@@ -1670,9 +3392,9 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
             overbought,
             oversold,
         } => {
-            for dp in [-2i32, 0, 2] {
-                for dob in [-2.5f64, 0.0, 2.5] {
-                    for dos in [-2.5f64, 0.0, 2.5] {
+            for dp in step(&[-2, 0, 2]) {
+                for dob in [-2.5f64, 0.0, 2.5].map(scale) {
+                    for dos in [-2.5f64, 0.0, 2.5].map(scale) {
                         let p = (*period as i32 + dp).max(3) as usize;
                         let ob = overbought + dob;
                         let os = oversold + dos;
@@ -1691,8 +3413,8 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
             period,
             adx_threshold,
         } => {
-            for dp in [-2i32, 0, 2] {
-                for dt in [-2.5f64, 0.0, 2.5] {
+            for dp in step(&[-2, 0, 2]) {
+                for dt in [-2.5f64, 0.0, 2.5].map(scale) {
                     let p = (*period as i32 + dp).max(3) as usize;
                     let t = (adx_threshold + dt).max(10.0);
                     variants.push(DiscoveryStrategyType::Adx {
@@ -1742,6 +3464,49 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
                 }
             }
         }
+        // Filtered strategies: refine the inner strategy under the same filter (other
+        // filters are tried by `filter_refinement_grid`)
+        DiscoveryStrategyType::Filtered { inner, filter } => {
+            for variant in generate_scaled_refinement_grid(inner, delta_scale) {
+                variants.push(DiscoveryStrategyType::Filtered {
+                    inner: Box::new(variant),
+                    filter: *filter,
+                });
+            }
+        }
+        // Trailing strategies: refine the inner strategy under the same stop (other
+        // stops are tried by `stop_refinement_grid`)
+        DiscoveryStrategyType::Trailing { inner, stop } => {
+            for variant in generate_scaled_refinement_grid(inner, delta_scale) {
+                variants.push(DiscoveryStrategyType::Trailing {
+                    inner: Box::new(variant),
+                    stop: *stop,
+                });
+            }
+        }
+        // HTF-filtered strategies: refine the inner strategy under the same filter (other
+        // filters are tried by `htf_refinement_grid`)
+        DiscoveryStrategyType::HtfFiltered { inner, htf_filter } => {
+            for variant in generate_scaled_refinement_grid(inner, delta_scale) {
+                variants.push(DiscoveryStrategyType::HtfFiltered {
+                    inner: Box::new(variant),
+                    htf_filter: *htf_filter,
+                });
+            }
+        }
+        // Z-score-filtered strategies: refine the inner strategy under the same filter
+        // (other filters are tried by `zscore_refinement_grid`)
+        DiscoveryStrategyType::ZScoreFiltered {
+            inner,
+            zscore_filter,
+        } => {
+            for variant in generate_scaled_refinement_grid(inner, delta_scale) {
+                variants.push(DiscoveryStrategyType::ZScoreFiltered {
+                    inner: Box::new(variant),
+                    zscore_filter: *zscore_filter,
+                });
+            }
+        }
         // For legacy combos, return the original (no refinement — too many params)
         other => {
             variants.push(other.clone());
@@ -1751,6 +3516,20 @@ fn generate_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoverySt
     variants
 }
 
+/// Increasing integer refinement steps scaled by `delta_scale`: a step other than 0 stays
+/// at least 1, and steps rounded onto each other are kept once
+fn scale_steps(deltas: &[i32], delta_scale: f64) -> Vec<i32> {
+    let mut steps: Vec<i32> = deltas
+        .iter()
+        .map(|&d| match (d as f64 * delta_scale).round() as i32 {
+            0 => d.signum(),
+            scaled => scaled,
+        })
+        .collect();
+    steps.dedup();
+    steps
+}
+
 /// Slightly mutate indicator params (deterministic small deltas for refinement)
 fn mutate_indicator_params(params: &IndicatorParams) -> IndicatorParams {
     match params {
@@ -1782,7 +3561,7 @@ fn mutate_indicator_params(params: &IndicatorParams) -> IndicatorParams {
             sma_period: sma_period + 3,
             multiplier: multiplier + 0.25,
         },
-        IndicatorParams::Vwap { period } => IndicatorParams::Vwap { period: period + 3 },
+        IndicatorParams::Vwap { period, anchor } => IndicatorParams::Vwap { period: period + 3, anchor: *anchor },
         IndicatorParams::Obv { sma_period } => IndicatorParams::Obv { sma_period: sma_period + 2 },
         IndicatorParams::WilliamsR { period, overbought, oversold } => IndicatorParams::WilliamsR {
             period: (*period).max(3).wrapping_add(1),
@@ -1801,7 +3580,7 @@ fn mutate_indicator_params(params: &IndicatorParams) -> IndicatorParams {
 // ============================================================================
 
 /// Compute a deterministic hash for deduplication of backtest params
-fn compute_params_hash(
+pub(crate) fn compute_params_hash(
     strategy: &DiscoveryStrategyType,
     symbol: &str,
     days: u32,
@@ -1813,299 +3592,1031 @@ fn compute_params_hash(
     format!("{:x}", hash)
 }
 
+/// Top KB results per symbol loaded into memory when its grid units are skipped, so
+/// refinement and ML-guided cycles still have parents to work from
+pub(crate) const GRID_SEED_RESULTS: i64 = 50;
+
+/// Hash of `params_hash` run under a drawdown throttle (unthrottled runs keep the plain hash)
+pub(crate) fn compute_throttled_hash(params_hash: &str, throttle: &DrawdownThrottle) -> String {
+    let input = format!("{}:{}", params_hash, throttle.label());
+    format!("{:x}", Sha256::digest(input.as_bytes()))
+}
+
+/// Content hash of a grid: changes whenever its generator yields other combinations
+pub(crate) fn compute_grid_id(grid: &[DiscoveryStrategyType]) -> String {
+    let mut hasher = Sha256::new();
+    for strategy in grid {
+        let json = serde_json::to_string(strategy).unwrap_or_default();
+        hasher.update(json.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Key of a grid unit (grid × symbol × days × sizing) in `tested_grids`, per backtest
+/// logic version: a grid tested by an earlier logic is run again
+pub(crate) fn compute_grid_hash(
+    grid_id: &str,
+    symbol: &str,
+    days: u32,
+    sizing: SizingMode,
+) -> String {
+    let input = format!(
+        "{}:{}:{}:{:?}:logic{}",
+        grid_id, symbol, days, sizing, BACKTEST_LOGIC_VERSION
+    );
+    let hash = Sha256::digest(input.as_bytes());
+    format!("{:x}", hash)
+}
+
+/// Convert a Decimal metric to the f64 stored in REAL columns
+pub(crate) fn dec_to_f64(d: Decimal) -> f64 {
+    d.to_string().parse::<f64>().unwrap_or(0.0)
+}
+
+/// Convert a stored REAL metric back to Decimal (shortest round-trip repr, so 55.1 stays 55.1)
+pub(crate) fn f64_to_dec(x: f64) -> Decimal {
+    x.to_string().parse::<Decimal>().unwrap_or(Decimal::ZERO)
+}
+
 /// Convert a DiscoveryResult to a DiscoveryBacktestRecord for DB storage
-fn result_to_record(
+pub(crate) fn result_to_record(
     result: &DiscoveryResult,
     hash: &str,
     run_id: &str,
     phase: &str,
     days: u32,
 ) -> DiscoveryBacktestRecord {
-    let strategy_type_tag = match &result.strategy_type {
-        DiscoveryStrategyType::Rsi { .. } => "rsi",
-        DiscoveryStrategyType::BollingerBands { .. } => "bollinger_bands",
-        DiscoveryStrategyType::Macd { .. } => "macd",
-        DiscoveryStrategyType::EmaCrossover { .. } => "ema_crossover",
-        DiscoveryStrategyType::Stochastic { .. } => "stochastic",
-        DiscoveryStrategyType::AtrMeanReversion { .. } => "atr_mean_reversion",
-        DiscoveryStrategyType::RsiBollinger { .. } => "rsi_bollinger",
-        DiscoveryStrategyType::MacdRsi { .. } => "macd_rsi",
-        DiscoveryStrategyType::EmaRsi { .. } => "ema_rsi",
-        DiscoveryStrategyType::StochRsi { .. } => "stoch_rsi",
-        DiscoveryStrategyType::MacdBollinger { .. } => "macd_bollinger",
-        DiscoveryStrategyType::TripleRsiMacdBb { .. } => "triple_rsi_macd_bb",
-        DiscoveryStrategyType::TripleEmaRsiStoch { .. } => "triple_ema_rsi_stoch",
-        DiscoveryStrategyType::Vwap { .. } => "vwap",
-        DiscoveryStrategyType::Obv { .. } => "obv",
-        DiscoveryStrategyType::WilliamsR { .. } => "williams_r",
-        DiscoveryStrategyType::Adx { .. } => "adx",
-        DiscoveryStrategyType::VwapRsi { .. } => "vwap_rsi",
-        DiscoveryStrategyType::ObvMacd { .. } => "obv_macd",
-        DiscoveryStrategyType::AdxEma { .. } => "adx_ema",
-        DiscoveryStrategyType::WilliamsRStoch { .. } => "williams_r_stoch",
-        DiscoveryStrategyType::DynamicCombo { .. } => "dynamic_combo",
-        DiscoveryStrategyType::WebStrategy { .. } => "web_strategy",
-        DiscoveryStrategyType::Gabagool { .. } => "gabagool",
-    };
-
     DiscoveryBacktestRecord {
         id: None,
         params_hash: hash.to_string(),
-        strategy_type: strategy_type_tag.to_string(),
+        strategy_type: result.strategy_type.type_tag().to_string(),
         strategy_name: result.strategy_name.clone(),
         strategy_params: serde_json::to_string(&result.strategy_type).unwrap_or_default(),
         symbol: result.symbol.clone(),
         days: days as i64,
         sizing_mode: format!("{:?}", result.sizing_mode),
-        composite_score: result.composite_score.to_string(),
-        net_pnl: result.net_pnl.to_string(),
-        gross_pnl: result.gross_pnl.to_string(),
-        total_fees: result.total_fees.to_string(),
-        win_rate: result.win_rate.to_string(),
+        composite_score: dec_to_f64(result.composite_score),
+        net_pnl: dec_to_f64(result.net_pnl),
+        gross_pnl: dec_to_f64(result.gross_pnl),
+        total_fees: dec_to_f64(result.total_fees),
+        win_rate: dec_to_f64(result.win_rate),
         total_trades: result.total_trades as i64,
-        sharpe_ratio: result.sharpe_ratio.to_string(),
-        max_drawdown_pct: result.max_drawdown_pct.to_string(),
-        profit_factor: result.profit_factor.to_string(),
-        avg_trade_pnl: result.avg_trade_pnl.to_string(),
-        hit_rate: result.hit_rate.map(|d| d.to_string()),
-        avg_locked_profit: result.avg_locked_profit.map(|d| d.to_string()),
+        sharpe_ratio: dec_to_f64(result.sharpe_ratio),
+        max_drawdown_pct: dec_to_f64(result.max_drawdown_pct),
+        profit_factor: dec_to_f64(result.profit_factor),
+        avg_trade_pnl: dec_to_f64(result.avg_trade_pnl),
+        hit_rate: result.hit_rate.map(dec_to_f64),
+        avg_locked_profit: result.avg_locked_profit.map(dec_to_f64),
         discovery_run_id: Some(run_id.to_string()),
         phase: Some(phase.to_string()),
-        sortino_ratio: Some(result.sortino_ratio.to_string()),
+        sortino_ratio: Some(dec_to_f64(result.sortino_ratio)),
         max_consecutive_losses: Some(result.max_consecutive_losses as i64),
-        avg_win_pnl: Some(result.avg_win_pnl.to_string()),
-        avg_loss_pnl: Some(result.avg_loss_pnl.to_string()),
-        total_volume: Some(result.total_volume.to_string()),
-        annualized_return_pct: Some(result.annualized_return_pct.to_string()),
-        annualized_sharpe: Some(result.annualized_sharpe.to_string()),
-        strategy_confidence: Some(result.strategy_confidence.to_string()),
+        avg_win_pnl: Some(dec_to_f64(result.avg_win_pnl)),
+        avg_loss_pnl: Some(dec_to_f64(result.avg_loss_pnl)),
+        total_volume: Some(dec_to_f64(result.total_volume)),
+        annualized_return_pct: Some(dec_to_f64(result.annualized_return_pct)),
+        annualized_sharpe: Some(dec_to_f64(result.annualized_sharpe)),
+        strategy_confidence: Some(dec_to_f64(result.strategy_confidence)),
+        schema_version: Some(STRATEGY_PARAMS_VERSION),
+        abort_reason: result.abort_reason.map(|r| r.as_str().to_string()),
+        probability_model: Some(result.probability_model.clone()),
+        drawdown_throttle: result
+            .drawdown_throttle
+            .and_then(|t| serde_json::to_string(&t).ok()),
+        cross_symbol_score: result.cross_symbol_score.map(dec_to_f64),
+        exit_breakdown: result
+            .exit_breakdown
+            .and_then(|b| serde_json::to_string(&b).ok()),
+        decayed_score: result.decayed_score.map(dec_to_f64),
+        score_30d: result.recent_30d.map(|r| dec_to_f64(r.score)),
+        net_pnl_30d: result.recent_30d.map(|r| dec_to_f64(r.net_pnl)),
+        win_rate_30d: result.recent_30d.map(|r| dec_to_f64(r.win_rate)),
+        trades_30d: result.recent_30d.map(|r| r.total_trades as i64),
+        score_90d: result.recent_90d.map(|r| dec_to_f64(r.score)),
+        net_pnl_90d: result.recent_90d.map(|r| dec_to_f64(r.net_pnl)),
+        win_rate_90d: result.recent_90d.map(|r| dec_to_f64(r.win_rate)),
+        trades_90d: result.recent_90d.map(|r| r.total_trades as i64),
+        pnl_hhi: result.pnl_concentration.map(|c| c.hhi),
+        top_week_pnl_share: result.pnl_concentration.map(|c| c.top_week_share),
+        active_weeks: result.pnl_concentration.map(|c| c.active_weeks as i64),
+        longest_drawdown_days: result.longest_drawdown_days.map(dec_to_f64),
+        avg_recovery_days: result.avg_recovery_days.map(dec_to_f64),
+        max_exposure_pct: result.max_exposure_pct.map(dec_to_f64),
+        avg_exposure_pct: result.avg_exposure_pct.map(dec_to_f64),
+        warmup_bars: result.warmup_bars.map(i64::from),
+        effective_days: result.effective_days.map(dec_to_f64),
+        engine_version: Some(ENGINE_VERSION.to_string()),
+        logic_version: Some(BACKTEST_LOGIC_VERSION),
+        cost_attribution: result
+            .cost_attribution
+            .and_then(|c| serde_json::to_string(&c).ok()),
     }
 }
 
-/// Convert a DB record back to a DiscoveryResult
-fn record_to_result(record: DiscoveryBacktestRecord) -> DiscoveryResult {
-    let strategy_type: DiscoveryStrategyType = serde_json::from_str(&record.strategy_params)
-        .unwrap_or(DiscoveryStrategyType::Rsi {
-            period: 14,
-            overbought: 70.0,
-            oversold: 30.0,
-        });
-
-    let parse_dec = |s: &str| -> Decimal { Decimal::from_str_exact(s).unwrap_or(Decimal::ZERO) };
-
-    let sizing_mode = match record.sizing_mode.to_lowercase().as_str() {
+/// Parse the `sizing_mode` column (stored as `{:?}` of SizingMode)
+pub(crate) fn parse_sizing_mode(s: &str) -> SizingMode {
+    match s.to_lowercase().as_str() {
         "kelly" => SizingMode::Kelly,
         "confidenceweighted" => SizingMode::ConfidenceWeighted,
         _ => SizingMode::Fixed,
+    }
+}
+
+/// Convert a DB record back to a DiscoveryResult.
+///
+/// Returns None when `strategy_params` cannot be decoded for its schema_version, so the
+/// caller re-runs the backtest instead of reusing a result under the wrong strategy.
+pub(crate) fn record_to_result(record: DiscoveryBacktestRecord) -> Option<DiscoveryResult> {
+    let version = record.schema_version.unwrap_or(0);
+    let strategy_type = match decode_strategy_params(&record.strategy_params, version) {
+        Ok(st) => st,
+        Err(e) => {
+            warn!(
+                params_hash = %record.params_hash,
+                schema_version = version,
+                error = %e,
+                "Ignoring cached result with undecodable strategy_params"
+            );
+            return None;
+        }
     };
 
-    DiscoveryResult {
+    let sizing_mode = parse_sizing_mode(&record.sizing_mode);
+
+    Some(DiscoveryResult {
         rank: 0,
         strategy_type,
         strategy_name: record.strategy_name,
         symbol: record.symbol,
         sizing_mode,
-        composite_score: parse_dec(&record.composite_score),
-        net_pnl: parse_dec(&record.net_pnl),
-        gross_pnl: parse_dec(&record.gross_pnl),
-        total_fees: parse_dec(&record.total_fees),
-        win_rate: parse_dec(&record.win_rate),
+        composite_score: f64_to_dec(record.composite_score),
+        net_pnl: f64_to_dec(record.net_pnl),
+        gross_pnl: f64_to_dec(record.gross_pnl),
+        total_fees: f64_to_dec(record.total_fees),
+        win_rate: f64_to_dec(record.win_rate),
         total_trades: record.total_trades as u32,
-        sharpe_ratio: parse_dec(&record.sharpe_ratio),
-        max_drawdown_pct: parse_dec(&record.max_drawdown_pct),
-        profit_factor: parse_dec(&record.profit_factor),
-        avg_trade_pnl: parse_dec(&record.avg_trade_pnl),
-        sortino_ratio: record.sortino_ratio.as_deref().map(parse_dec).unwrap_or(Decimal::ZERO),
+        sharpe_ratio: f64_to_dec(record.sharpe_ratio),
+        max_drawdown_pct: f64_to_dec(record.max_drawdown_pct),
+        profit_factor: f64_to_dec(record.profit_factor),
+        avg_trade_pnl: f64_to_dec(record.avg_trade_pnl),
+        sortino_ratio: record.sortino_ratio.map(f64_to_dec).unwrap_or(Decimal::ZERO),
         max_consecutive_losses: record.max_consecutive_losses.unwrap_or(0) as u32,
-        avg_win_pnl: record.avg_win_pnl.as_deref().map(parse_dec).unwrap_or(Decimal::ZERO),
-        avg_loss_pnl: record.avg_loss_pnl.as_deref().map(parse_dec).unwrap_or(Decimal::ZERO),
-        total_volume: record.total_volume.as_deref().map(parse_dec).unwrap_or(Decimal::ZERO),
-        annualized_return_pct: record.annualized_return_pct.as_deref().map(parse_dec).unwrap_or(Decimal::ZERO),
-        annualized_sharpe: record.annualized_sharpe.as_deref().map(parse_dec).unwrap_or(Decimal::ZERO),
-        strategy_confidence: record.strategy_confidence.as_deref().map(parse_dec).unwrap_or(Decimal::ZERO),
-        hit_rate: record.hit_rate.as_deref().map(parse_dec),
-        avg_locked_profit: record.avg_locked_profit.as_deref().map(parse_dec),
-    }
+        avg_win_pnl: record.avg_win_pnl.map(f64_to_dec).unwrap_or(Decimal::ZERO),
+        avg_loss_pnl: record.avg_loss_pnl.map(f64_to_dec).unwrap_or(Decimal::ZERO),
+        total_volume: record.total_volume.map(f64_to_dec).unwrap_or(Decimal::ZERO),
+        annualized_return_pct: record.annualized_return_pct.map(f64_to_dec).unwrap_or(Decimal::ZERO),
+        annualized_sharpe: record.annualized_sharpe.map(f64_to_dec).unwrap_or(Decimal::ZERO),
+        strategy_confidence: record.strategy_confidence.map(f64_to_dec).unwrap_or(Decimal::ZERO),
+        hit_rate: record.hit_rate.map(f64_to_dec),
+        avg_locked_profit: record.avg_locked_profit.map(f64_to_dec),
+        abort_reason: record.abort_reason.as_deref().and_then(AbortReason::parse),
+        probability_model: record
+            .probability_model
+            .unwrap_or_else(default_probability_model),
+        drawdown_throttle: record
+            .drawdown_throttle
+            .as_deref()
+            .and_then(|t| serde_json::from_str(t).ok()),
+        cross_symbol_score: record.cross_symbol_score.map(f64_to_dec),
+        exit_breakdown: record
+            .exit_breakdown
+            .as_deref()
+            .and_then(|b| serde_json::from_str(b).ok()),
+        decayed_score: record.decayed_score.map(f64_to_dec),
+        recent_30d: recent_performance(
+            record.score_30d,
+            record.net_pnl_30d,
+            record.win_rate_30d,
+            record.trades_30d,
+        ),
+        recent_90d: recent_performance(
+            record.score_90d,
+            record.net_pnl_90d,
+            record.win_rate_90d,
+            record.trades_90d,
+        ),
+        pnl_concentration: match (
+            record.pnl_hhi,
+            record.top_week_pnl_share,
+            record.active_weeks,
+        ) {
+            (Some(hhi), Some(top_week_share), Some(weeks)) => Some(PnlConcentration {
+                hhi,
+                top_week_share,
+                active_weeks: weeks as u32,
+            }),
+            _ => None,
+        },
+        longest_drawdown_days: record.longest_drawdown_days.map(f64_to_dec),
+        avg_recovery_days: record.avg_recovery_days.map(f64_to_dec),
+        max_exposure_pct: record.max_exposure_pct.map(f64_to_dec),
+        avg_exposure_pct: record.avg_exposure_pct.map(f64_to_dec),
+        warmup_bars: record.warmup_bars.map(|w| w as u32),
+        effective_days: record.effective_days.map(f64_to_dec),
+        unfilled: None,
+        cost_attribution: record
+            .cost_attribution
+            .as_deref()
+            .and_then(|c| serde_json::from_str(c).ok()),
+    })
+}
+
+/// Recent window of a stored record, if all its columns were recorded
+fn recent_performance(
+    score: Option<f64>,
+    net_pnl: Option<f64>,
+    win_rate: Option<f64>,
+    trades: Option<i64>,
+) -> Option<RecentPerformance> {
+    Some(RecentPerformance {
+        score: f64_to_dec(score?),
+        net_pnl: f64_to_dec(net_pnl?),
+        win_rate: f64_to_dec(win_rate?),
+        total_trades: trades? as u32,
+    })
 }
 
 // ============================================================================
-// Main Discovery Runner
+// Backtest Logic Versioning
 // ============================================================================
 
-pub async fn run_discovery(
-    request: DiscoveryRequest,
-    binance: Arc<BinanceClient>,
-    progress: Arc<DiscoveryProgress>,
-    db_pool: Option<SqlitePool>,
-) {
-    let top_n = request.top_n.unwrap_or(10);
-    let sizing_mode = request.sizing_mode.unwrap_or_default();
-    let initial_capital = dec!(10000);
-    let base_position_pct = dec!(10);
-    let fee_config = PolymarketFeeConfig::default();
-
-    let run_id = Utc::now().timestamp_millis().to_string();
-
-    info!(
-        symbols = ?request.symbols,
-        days = request.days,
-        sizing = ?sizing_mode,
-        run_id = %run_id,
-        "Starting discovery agent"
-    );
+/// Version of the backtest and scoring logic written by `result_to_record`.
+///
+/// Bump this whenever a change alters the result of an existing strategy on the same
+/// klines (fills, fees, metrics, composite score): stored results of an earlier version
+/// are then backtested again, and replaced, instead of being reused from the cache. A
+/// bump that leaves the earlier results valid lists their version in
+/// `COMPATIBLE_LOGIC_VERSIONS`.
+pub const BACKTEST_LOGIC_VERSION: i64 = 1;
+
+/// Earlier logic versions whose results are reused as if the current logic computed them.
+/// Rows written before logic versioning count as version 0.
+pub const COMPATIBLE_LOGIC_VERSIONS: &[i64] = &[];
+
+/// Engine crate version stored next to the logic version of each result
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Whether a stored result can be reused by the current backtest logic
+pub fn is_current_logic(record: &DiscoveryBacktestRecord) -> bool {
+    let version = record.logic_version.unwrap_or(0);
+    version == BACKTEST_LOGIC_VERSION || COMPATIBLE_LOGIC_VERSIONS.contains(&version)
+}
 
-    // ── Phase 0: Fetch klines ───────────────────────────────────────────
-    let end_time = chrono::Utc::now().timestamp_millis();
-    let start_time = end_time - (request.days as i64 * 24 * 60 * 60 * 1000);
+// ============================================================================
+// Strategy Params Versioning
+// ============================================================================
 
-    let mut symbol_klines: Vec<(String, Vec<Kline>)> = Vec::new();
+/// Current format version of `strategy_params` JSON written by `result_to_record`.
+///
+/// Bump this whenever a `DiscoveryStrategyType` / `IndicatorParams` variant changes shape,
+/// and add a matching arm to `decode_strategy_params`.
+pub const STRATEGY_PARAMS_VERSION: i64 = 1;
+
+/// Decode stored `strategy_params` according to the version it was written with
+pub fn decode_strategy_params(json: &str, version: i64) -> Result<DiscoveryStrategyType, String> {
+    match version {
+        0 => decode_strategy_params_v0(json),
+        1 => serde_json::from_str(json).map_err(|e| format!("invalid v1 strategy_params: {e}")),
+        v => Err(format!("unknown strategy_params schema_version {v}")),
+    }
+}
 
-    for symbol in &request.symbols {
-        if progress.cancelled.load(Ordering::Relaxed) {
-            return;
-        }
-        *progress.current_symbol.write().unwrap() = symbol.clone();
+/// v0: rows written before versioning. DynamicCombo params may lack fields added later;
+/// missing fields are filled from the indicator's default params.
+fn decode_strategy_params_v0(json: &str) -> Result<DiscoveryStrategyType, String> {
+    if let Ok(st) = serde_json::from_str::<DiscoveryStrategyType>(json) {
+        return Ok(st);
+    }
 
-        match binance
-            .get_klines_paginated(symbol, "15m", start_time, end_time)
-            .await
-        {
-            Ok(klines) => {
-                info!(symbol = %symbol, bars = klines.len(), "Fetched klines");
-                symbol_klines.push((symbol.clone(), klines));
-            }
-            Err(e) => {
-                warn!(symbol = %symbol, error = %e, "Failed to fetch klines, skipping");
+    let mut value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("invalid JSON: {e}"))?;
+
+    if value.get("type").and_then(|t| t.as_str()) == Some("dynamic_combo") {
+        let indicators: Vec<SingleIndicatorType> = value
+            .get("indicators")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| format!("invalid dynamic_combo indicators: {e}"))?
+            .unwrap_or_default();
+
+        let mut params: Vec<serde_json::Value> = value
+            .get("params")
+            .and_then(|p| p.as_array())
+            .cloned()
+            .unwrap_or_default();
+        params.resize(indicators.len(), serde_json::Value::Null);
+
+        for (ind, p) in indicators.iter().zip(params.iter_mut()) {
+            let mut merged = serde_json::to_value(ind.default_params())
+                .map_err(|e| format!("failed to serialize default params: {e}"))?;
+            if let (Some(target), Some(stored)) = (merged.as_object_mut(), p.as_object()) {
+                for (k, v) in stored {
+                    target.insert(k.clone(), v.clone());
+                }
             }
+            *p = merged;
         }
+        value["params"] = serde_json::Value::Array(params);
     }
 
-    if symbol_klines.is_empty() {
-        *progress.error_message.write().unwrap() =
-            Some("Failed to fetch klines for any symbol".to_string());
-        *progress.status.write().unwrap() = DiscoveryStatus::Error;
-        return;
-    }
-
-    // ── Phase 1: Broad Scan ─────────────────────────────────────────────
-    *progress.status.write().unwrap() = DiscoveryStatus::Phase1BroadScan;
-    *progress.phase.write().unwrap() = "Phase 1: Broad Scan".to_string();
-
-    let grid = generate_phase1_grid();
-    let total_phase1 = grid.len() as u32 * symbol_klines.len() as u32;
-
-    // Estimate phase 2 — top 20 × ~27 variants = ~540
-    let estimated_phase2 = 20u32 * 27;
-    let total_all = total_phase1 + estimated_phase2;
-    progress
-        .total_combinations
-        .store(total_all, Ordering::Relaxed);
-
-    info!(
-        grid_size = grid.len(),
-        symbols = symbol_klines.len(),
-        total_combos = total_phase1,
-        "Phase 1 starting"
-    );
-
-    let mut all_results: Vec<DiscoveryResult> = Vec::new();
-    let mut global_idx = 0u32;
+    serde_json::from_value(value).map_err(|e| format!("incompatible v0 strategy_params: {e}"))
+}
 
-    for (symbol, klines) in &symbol_klines {
-        for strategy_type in &grid {
-            if progress.cancelled.load(Ordering::Relaxed) {
-                info!("Discovery cancelled by user");
-                *progress.status.write().unwrap() = DiscoveryStatus::Idle;
-                return;
-            }
+/// Outcome of a `migrate_strategy_params` run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParamsMigrationReport {
+    /// Rows with schema_version below `STRATEGY_PARAMS_VERSION`
+    pub scanned: usize,
+    /// Rows rewritten in the current format
+    pub upgraded: usize,
+    /// Rows whose upgraded params duplicated an existing row (deleted)
+    pub duplicates_removed: usize,
+    /// Rows moved to `discovery_quarantine`
+    pub quarantined: usize,
+}
 
-            // Update progress
-            if global_idx.is_multiple_of(50) {
-                *progress.current_strategy.write().unwrap() = strategy_type.name().to_string();
-                *progress.current_symbol.write().unwrap() = symbol.clone();
-            }
+/// Upgrade every outdated `strategy_params` row to `STRATEGY_PARAMS_VERSION`.
+///
+/// Rows that cannot be decoded are moved to `discovery_quarantine` instead of being
+/// reinterpreted. With `dry_run`, the report is computed without touching the DB.
+pub async fn migrate_strategy_params(
+    pool: &SqlitePool,
+    dry_run: bool,
+) -> anyhow::Result<ParamsMigrationReport> {
+    let repo = DiscoveryRepository::new(pool);
+    let rows = repo
+        .get_outdated_params(STRATEGY_PARAMS_VERSION)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load outdated rows: {e}"))?;
+
+    let mut report = ParamsMigrationReport {
+        scanned: rows.len(),
+        ..Default::default()
+    };
 
-            // Check DB cache before running backtest
-            let hash = compute_params_hash(strategy_type, symbol, request.days, sizing_mode);
-            if let Some(pool) = &db_pool {
-                let repo = DiscoveryRepository::new(pool);
-                if let Ok(Some(existing)) = repo.get_by_hash(&hash).await {
-                    all_results.push(record_to_result(existing));
-                    global_idx += 1;
-                    progress.completed.store(global_idx, Ordering::Relaxed);
-                    progress.skipped.fetch_add(1, Ordering::Relaxed);
-                    if global_idx.is_multiple_of(50) {
-                        update_best_so_far(&all_results, initial_capital, top_n, &progress);
-                        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
-                    }
+    for row in rows {
+        let version = row.schema_version.unwrap_or(0);
+        match decode_strategy_params(&row.strategy_params, version) {
+            Ok(strategy) => {
+                report.upgraded += 1;
+                if dry_run {
                     continue;
                 }
+                let params = serde_json::to_string(&strategy)?;
+                let hash = compute_params_hash(
+                    &strategy,
+                    &row.symbol,
+                    row.days as u32,
+                    parse_sizing_mode(&row.sizing_mode),
+                );
+                let hash = workspace_key(&row.workspace, &hash);
+                let updated = repo
+                    .update_strategy_params(row.id, &hash, &params, STRATEGY_PARAMS_VERSION)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to update row {}: {e}", row.id))?;
+                if !updated {
+                    // Upgraded params now match an existing row — keep that one
+                    repo.delete_by_id(row.id)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to delete row {}: {e}", row.id))?;
+                    report.upgraded -= 1;
+                    report.duplicates_removed += 1;
+                }
             }
+            Err(reason) => {
+                warn!(id = row.id, strategy_type = %row.strategy_type, %reason, "Quarantining row");
+                report.quarantined += 1;
+                if !dry_run {
+                    repo.quarantine(row.id, &reason)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to quarantine row {}: {e}", row.id))?;
+                }
+            }
+        }
+    }
 
-            let result = run_single_backtest(
-                strategy_type,
-                klines,
-                symbol,
-                initial_capital,
-                base_position_pct,
-                sizing_mode,
-                &fee_config,
-            );
+    info!(
+        scanned = report.scanned,
+        upgraded = report.upgraded,
+        duplicates_removed = report.duplicates_removed,
+        quarantined = report.quarantined,
+        dry_run,
+        "Strategy params migration complete"
+    );
 
-            // Save to DB
-            if let Some(pool) = &db_pool {
-                let record = result_to_record(&result, &hash, &run_id, "phase1", request.days);
-                let repo = DiscoveryRepository::new(pool);
-                let _ = repo.save(&record).await;
-            }
+    Ok(report)
+}
 
-            all_results.push(result);
+// ============================================================================
+// Work Queue Priority
+// ============================================================================
 
-            global_idx += 1;
-            progress.completed.store(global_idx, Ordering::Relaxed);
+/// Expected composite score of each strategy family (`name()`) per symbol, from the
+/// knowledge base. Families never tested get the mean of the known ones.
+#[derive(Debug, Default)]
+struct StrategyPriors {
+    by_family_symbol: HashMap<String, HashMap<String, f64>>,
+    by_family: HashMap<String, f64>,
+    default: f64,
+}
 
-            // Sleep every 50 iterations to let trading strategies breathe
-            if global_idx.is_multiple_of(50) {
-                update_best_so_far(&all_results, initial_capital, top_n, &progress);
-                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+impl StrategyPriors {
+    fn from_performance(rows: &[FamilyPerformance]) -> Self {
+        let mut priors = Self::default();
+        // family → (Σ prior × backtests, Σ backtests)
+        let mut weighted: HashMap<String, (f64, f64)> = HashMap::new();
+        for row in rows {
+            // Mean and best both matter: a family with one great config is worth scanning
+            let prior = (row.avg_score + row.best_score) / 2.0;
+            priors
+                .by_family_symbol
+                .entry(row.strategy_name.clone())
+                .or_default()
+                .insert(row.symbol.clone(), prior);
+            let entry = weighted.entry(row.strategy_name.clone()).or_default();
+            entry.0 += prior * row.backtests as f64;
+            entry.1 += row.backtests as f64;
+        }
+        for (family, (sum, n)) in weighted {
+            if n > 0.0 {
+                priors.by_family.insert(family, sum / n);
             }
         }
+        if !priors.by_family.is_empty() {
+            priors.default = priors.by_family.values().sum::<f64>() / priors.by_family.len() as f64;
+        }
+        priors
     }
 
-    // Final update of best_so_far after phase 1
-    update_best_so_far(&all_results, initial_capital, top_n, &progress);
-
-    info!(results = all_results.len(), "Phase 1 complete");
-
-    // ── Phase 2: Refinement ─────────────────────────────────────────────
-    *progress.status.write().unwrap() = DiscoveryStatus::Phase2Refinement;
-    *progress.phase.write().unwrap() = "Phase 2: Refinement".to_string();
-
-    // Take top 20 from phase 1
-    let mut phase1_scored = all_results.clone();
-    phase1_scored.sort_by(|a, b| {
-        let sa = score_result(a, initial_capital);
-        let sb = score_result(b, initial_capital);
-        sb.cmp(&sa)
-    });
-    let top_for_refinement: Vec<DiscoveryResult> = phase1_scored.into_iter().take(20).collect();
-
-    info!(
-        top_count = top_for_refinement.len(),
-        "Phase 2: refining top results"
-    );
+    fn score(&self, strategy: &DiscoveryStrategyType, symbol: &str) -> f64 {
+        let family = strategy.name();
+        self.by_family_symbol
+            .get(family)
+            .and_then(|by_symbol| by_symbol.get(symbol))
+            .or_else(|| self.by_family.get(family))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
 
-    for top_result in &top_for_refinement {
-        if progress.cancelled.load(Ordering::Relaxed) {
-            *progress.status.write().unwrap() = DiscoveryStatus::Idle;
-            return;
+/// Priors from the knowledge base (neutral without a DB or on error)
+async fn load_strategy_priors(store: Option<&dyn ResultStore>) -> StrategyPriors {
+    let Some(store) = store else {
+        return StrategyPriors::default();
+    };
+    match store.family_performance().await {
+        Ok(rows) => {
+            info!(
+                families = rows.len(),
+                "Loaded strategy priors from knowledge base"
+            );
+            StrategyPriors::from_performance(&rows)
         }
+        Err(e) => {
+            warn!(error = %e, "Failed to load strategy priors, keeping generation order");
+            StrategyPriors::default()
+        }
+    }
+}
 
-        let refinement_grid = generate_refinement_grid(&top_result.strategy_type);
+/// Sort work items by descending prior so likely winners run first and `best_so_far`
+/// is meaningful early. Stable: equally-rated items keep generation order.
+fn prioritize_work<'a, T>(
+    items: Vec<T>,
+    priors: &StrategyPriors,
+    key: impl Fn(&T) -> (&'a DiscoveryStrategyType, &'a str),
+) -> Vec<T> {
+    let mut scored: Vec<(f64, T)> = items
+        .into_iter()
+        .map(|item| {
+            let (strategy, symbol) = key(&item);
+            (priors.score(strategy, symbol), item)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
 
-        // Find klines for this symbol
-        let klines_opt = symbol_klines
-            .iter()
+// ============================================================================
+// Main Discovery Runner
+// ============================================================================
+
+/// Fee config of a run; the store is dropped when the run is priced under another
+/// preset than Polymarket's, filled under another model than the close or capped by
+/// volume, so what-if results never mix with the knowledge base, and scoped to the
+/// probability model otherwise (a logistic model without a slope is calibrated here,
+/// before any backtest)
+pub(crate) async fn run_fees(
+    request: &DiscoveryRequest,
+    binance: &BinanceClient,
+    store: Option<Arc<dyn ResultStore>>,
+) -> (PolymarketFeeConfig, Option<Arc<dyn ResultStore>>) {
+    let preset = request.fee_preset.clone().unwrap_or_default();
+    let probability_model = resolve_probability_model(
+        request.probability_model.clone().unwrap_or_default(),
+        &request.symbols,
+        binance,
+    )
+    .await;
+    if probability_model != ProbabilityModelConfig::Linear {
+        info!(
+            model = %probability_model.name(),
+            "Fees estimated with a non-linear probability model"
+        );
+    }
+    let fee_config = PolymarketFeeConfig {
+        probability_model,
+        ..preset.config()
+    };
+    let fill_model = request.fill_model.unwrap_or_default();
+    if fill_model != FillModel::Close {
+        info!(
+            fills = fill_model.name(),
+            "What-if fill model: results are not stored"
+        );
+        return (fee_config, None);
+    }
+    if let Some(limit) = &request.participation {
+        info!(
+            participation = %limit.label(),
+            "What-if volume cap: results are not stored"
+        );
+        return (fee_config, None);
+    }
+    let candles = request.candle_transform.unwrap_or_default();
+    if !candles.is_raw() {
+        info!(
+            candles = %candles.name(),
+            "What-if candle transform: results are not stored"
+        );
+        return (fee_config, None);
+    }
+    if preset == FeePreset::Polymarket {
+        let store = store.map(|s| ModelScopedStore::scope(s, &fee_config.probability_model));
+        return (fee_config, store);
+    }
+    info!(fees = %preset.name(), "What-if fee preset: results are not stored");
+    (fee_config, None)
+}
+
+/// Backtests of one refined result, before Phase 1 picks it: ~27 variants + the entry
+/// filters + the trailing stops + the higher-timeframe filters + the z-score filters
+pub(crate) const REFINEMENT_COMBOS_PER_PARENT: u32 = 27
+    + REFINEMENT_FILTERS as u32
+    + REFINEMENT_STOPS as u32
+    + REFINEMENT_HTF_FILTERS as u32
+    + REFINEMENT_ZSCORE_FILTERS as u32;
+
+/// Largest `refinement_top_k` of a request
+pub const MAX_REFINEMENT_TOP_K: usize = 200;
+
+/// Most `refinement_rounds` of a request
+pub const MAX_REFINEMENT_ROUNDS: u32 = 5;
+
+/// Largest `refinement_delta_scale` of a request
+pub const MAX_REFINEMENT_DELTA_SCALE: f64 = 5.0;
+
+/// How wide and how deep Phase 2 refines the Phase 1 winners
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefinementDepth {
+    /// Best results refined per round
+    pub top_k: usize,
+    /// Rounds; each one after the first refines the best new results of the previous
+    pub rounds: u32,
+    /// Multiplier of the parameter steps of `generate_refinement_grid`
+    pub delta_scale: f64,
+}
+
+impl Default for RefinementDepth {
+    fn default() -> Self {
+        Self {
+            top_k: 20,
+            rounds: 1,
+            delta_scale: 1.0,
+        }
+    }
+}
+
+impl RefinementDepth {
+    pub fn from_request(request: &DiscoveryRequest) -> Self {
+        let default = Self::default();
+        Self {
+            top_k: request.refinement_top_k.unwrap_or(default.top_k),
+            rounds: request.refinement_rounds.unwrap_or(default.rounds),
+            delta_scale: request
+                .refinement_delta_scale
+                .unwrap_or(default.delta_scale),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_REFINEMENT_TOP_K).contains(&self.top_k) {
+            return Err(format!(
+                "refinement_top_k must be between 1 and {}, got {}",
+                MAX_REFINEMENT_TOP_K, self.top_k
+            ));
+        }
+        if !(1..=MAX_REFINEMENT_ROUNDS).contains(&self.rounds) {
+            return Err(format!(
+                "refinement_rounds must be between 1 and {}, got {}",
+                MAX_REFINEMENT_ROUNDS, self.rounds
+            ));
+        }
+        if !(self.delta_scale > 0.0 && self.delta_scale <= MAX_REFINEMENT_DELTA_SCALE) {
+            return Err(format!(
+                "refinement_delta_scale must be above 0 and at most {}, got {}",
+                MAX_REFINEMENT_DELTA_SCALE, self.delta_scale
+            ));
+        }
+        Ok(())
+    }
+
+    /// Phase 2 size, before Phase 1 picks the parents: every round refines `top_k`
+    pub fn estimated_combos(&self) -> u32 {
+        self.top_k as u32 * self.rounds * REFINEMENT_COMBOS_PER_PARENT
+    }
+}
+
+/// Parents of Phase 2, round after round: the best `top_k` results of Phase 1, then the
+/// best `top_k` results of each round, each parent refined once
+pub(crate) struct RefinementRounds {
+    depth: RefinementDepth,
+    initial_capital: Decimal,
+    round: u32,
+    /// Results from this index on were found by the current round
+    round_start: usize,
+    parents: std::vec::IntoIter<DiscoveryResult>,
+    /// (symbol, params) of the results refined so far
+    refined: std::collections::HashSet<(String, String)>,
+}
+
+impl RefinementRounds {
+    pub fn new(depth: RefinementDepth, initial_capital: Decimal) -> Self {
+        Self {
+            depth,
+            initial_capital,
+            round: 0,
+            round_start: 0,
+            parents: Vec::new().into_iter(),
+            refined: std::collections::HashSet::new(),
+        }
+    }
+
+    /// Next result to refine, `results` being every result of the run so far (Phase 1
+    /// first, then what the rounds appended). `None` once the last round is done, or a
+    /// round found nothing new to refine.
+    pub fn next_parent(&mut self, results: &[DiscoveryResult]) -> Option<DiscoveryResult> {
+        loop {
+            if let Some(parent) = self.parents.next() {
+                return Some(parent);
+            }
+            if self.round >= self.depth.rounds {
+                return None;
+            }
+            self.round += 1;
+            let mut candidates = results[self.round_start.min(results.len())..].to_vec();
+            self.round_start = results.len();
+            candidates
+                .sort_by_cached_key(|r| std::cmp::Reverse(score_result(r, self.initial_capital)));
+            let parents: Vec<DiscoveryResult> = candidates
+                .into_iter()
+                .filter(|r| {
+                    let params = serde_json::to_string(&r.strategy_type).unwrap_or_default();
+                    self.refined.insert((r.symbol.clone(), params))
+                })
+                .take(self.depth.top_k)
+                .collect();
+            if parents.is_empty() {
+                return None;
+            }
+            info!(
+                round = self.round,
+                top_count = parents.len(),
+                "Phase 2: refining top results"
+            );
+            self.parents = parents.into_iter();
+        }
+    }
+}
+
+/// Best results re-run under each throttle preset by the throttle search
+pub(crate) const THROTTLE_SEARCH_TOP: usize = 10;
+
+/// Backtests of the throttle search
+pub(crate) const THROTTLE_SEARCH_COMBOS: u32 =
+    (THROTTLE_SEARCH_TOP * DrawdownThrottle::SEARCH_PRESETS.len()) as u32;
+
+/// Best results re-run on every other symbol by the cross-symbol validation
+pub(crate) const CROSS_SYMBOL_TOP: usize = 10;
+
+/// Composite points of a result profitable on every other symbol, at weight 1
+const CROSS_SYMBOL_MAX_BONUS: Decimal = dec!(200);
+
+/// Store writes of a one-shot run. A failed write only costs a recomputation next
+/// time, but a store that rejected every write fails the run.
+#[derive(Debug, Default)]
+struct SaveTally {
+    saved: u32,
+    failed: u32,
+    last_error: Option<String>,
+}
+
+impl SaveTally {
+    fn record(&mut self, outcome: anyhow::Result<()>) {
+        match outcome {
+            Ok(()) => self.saved += 1,
+            Err(e) => {
+                self.failed += 1;
+                self.last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    fn check(&self) -> EngineResult<()> {
+        match &self.last_error {
+            Some(e) if self.saved == 0 => Err(EngineError::Storage(format!(
+                "The result store rejected all {} results: {}",
+                self.failed, e
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// One-shot runner behind `DiscoveryEngine::run()` — embed through the engine instead
+#[doc(hidden)]
+pub async fn run_discovery(
+    request: DiscoveryRequest,
+    binance: Arc<BinanceClient>,
+    progress: Arc<DiscoveryProgress>,
+    store: Option<Arc<dyn ResultStore>>,
+    coordinator: Option<Arc<Coordinator>>,
+) -> EngineResult<()> {
+    let top_n = request.top_n.unwrap_or(10);
+    let sizing_mode = request.sizing_mode.unwrap_or_default();
+    let initial_capital = dec!(10000);
+    let base_position_pct = dec!(10);
+    let (fee_config, store) = run_fees(&request, &binance, store).await;
+    let limits = BacktestLimits::from_request(&request);
+    let series_cache = IndicatorSeriesCache::new(SERIES_CACHE_CAPACITY)
+        .with_candles(request.candle_transform.unwrap_or_default());
+    let mut saves = SaveTally::default();
+    progress.set_pacing(request.pacing.unwrap_or_default());
+    progress.set_rank_order(request.rank_order());
+    let mut pacer = Pacer::new();
+
+    let run_id = Utc::now().timestamp_millis().to_string();
+    *progress.run_id.write().unwrap() = Some(run_id.clone());
+
+    info!(
+        symbols = ?request.symbols,
+        days = request.days,
+        sizing = ?sizing_mode,
+        run_id = %run_id,
+        "Starting discovery agent"
+    );
+
+    // ── Phase 0: Fetch klines ───────────────────────────────────────────
+    let end_time = chrono::Utc::now().timestamp_millis();
+    let start_time = end_time - (request.days as i64 * 24 * 60 * 60 * 1000);
+
+    let symbol_klines = fetch_run_klines(
+        &request,
+        &binance,
+        (start_time, end_time),
+        &progress,
+        store.as_deref(),
+        &run_id,
+    )
+    .await
+    .map_err(|e| progress.fail(e))?;
+    let symbol_filters = fetch_run_symbol_filters(&request, &binance, &symbol_klines).await;
+    let symbol_limits =
+        |symbol: &str| limits.with_symbol_filters(symbol_filters.get(symbol).copied());
+
+    // ── Phase 1: Broad Scan ─────────────────────────────────────────────
+    *progress.status.write().unwrap() = DiscoveryStatus::Phase1BroadScan;
+    *progress.phase.write().unwrap() = "Phase 1: Broad Scan".to_string();
+
+    let exclusions = StrategyExclusions::from_request(&request);
+    if !exclusions.is_empty() {
+        info!(excluded = ?exclusions.tags(), "Strategy types excluded from the grids");
+    }
+    let mut grid = generate_phase1_grid();
+    grid.extend(generate_onnx_grid(&request.onnx_models));
+    retain_valid(&mut grid);
+    exclusions.retain(&mut grid);
+    // Cross-sectional strategies run once on the aligned basket of all symbols
+    let basket = KlineMatrix::align(&symbol_klines);
+    let mut cross_grid = basket
+        .as_ref()
+        .map(generate_cross_sectional_grid)
+        .unwrap_or_default();
+    retain_valid(&mut cross_grid);
+    exclusions.retain(&mut cross_grid);
+    let total_phase1 = grid.len() as u32 * symbol_klines.len() as u32 + cross_grid.len() as u32;
+
+    let refinement = RefinementDepth::from_request(&request);
+    let estimated_phase2 = refinement.estimated_combos();
+    let total_all = total_phase1 + estimated_phase2;
+    progress
+        .total_combinations
+        .store(total_all, Ordering::Relaxed);
+
+    info!(
+        grid_size = grid.len(),
+        symbols = symbol_klines.len(),
+        total_combos = total_phase1,
+        "Phase 1 starting"
+    );
+
+    let mut all_results: Vec<DiscoveryResult> = Vec::new();
+    let mut global_idx = 0u32;
+
+    let priors = load_strategy_priors(store.as_deref()).await;
+    let work = prioritize_work(
+        symbol_klines
+            .iter()
+            .flat_map(|(symbol, klines)| grid.iter().map(move |s| (symbol, klines, s)))
+            .collect(),
+        &priors,
+        |&(symbol, _, strategy_type)| (strategy_type, symbol.as_str()),
+    );
+    for &(symbol, _, strategy_type) in &work {
+        progress.plan_work(symbol, strategy_type, 1);
+    }
+
+    // Uncached combinations backtested by the remote workers, when some are attached
+    let mut remote = match &coordinator {
+        Some(coordinator) => {
+            let chunk_request = DiscoveryRequest {
+                probability_model: Some(fee_config.probability_model.clone()),
+                ..request.clone()
+            };
+            distribute_phase1(
+                coordinator,
+                &chunk_request,
+                (start_time, end_time),
+                &run_id,
+                &work,
+                store.as_deref(),
+                &progress,
+            )
+            .await?
+        }
+        None => HashMap::new(),
+    };
+
+    for (symbol, klines, strategy_type) in work {
+        progress.wait_while_paused().await;
+        if progress.cancelled.load(Ordering::Relaxed) {
+            return Err(progress.fail(EngineError::Cancelled));
+        }
+
+        // Update progress
+        if global_idx.is_multiple_of(50) {
+            *progress.current_strategy.write().unwrap() = strategy_type.name().to_string();
+            *progress.current_symbol.write().unwrap() = symbol.clone();
+        }
+
+        // Check DB cache before running backtest
+        let hash = compute_params_hash(strategy_type, symbol, request.days, sizing_mode);
+        if let Some(store) = &store {
+            if let Some(cached) =
+                store.get_by_hash(&hash).await.ok().flatten().and_then(record_to_result)
+            {
+                progress.emit_result(&cached);
+                all_results.push(cached);
+                global_idx += 1;
+                progress.completed.store(global_idx, Ordering::Relaxed);
+                progress.skipped.fetch_add(1, Ordering::Relaxed);
+                progress.complete_work(symbol, strategy_type, 1);
+                if global_idx.is_multiple_of(50) {
+                    update_best_so_far(&all_results, initial_capital, top_n, &progress);
+                }
+                pacer.cached().await;
+                continue;
+            }
+        }
+
+        let remote_result = remote.remove(&hash);
+        let backtested = remote_result.is_none();
+        let Some(result) = remote_result.or_else(|| {
+            progress.isolate(symbol, strategy_type, "phase1", || {
+                run_single_backtest(
+                    strategy_type,
+                    klines,
+                    symbol,
+                    initial_capital,
+                    base_position_pct,
+                    sizing_mode,
+                    &fee_config,
+                    &symbol_limits(symbol),
+                    &series_cache,
+                )
+            })
+        }) else {
+            global_idx += 1;
+            progress.completed.store(global_idx, Ordering::Relaxed);
+            progress.complete_work(symbol, strategy_type, 1);
+            continue;
+        };
+
+        // Save to DB
+        if let Some(store) = &store {
+            let record = result_to_record(&result, &hash, &run_id, "phase1", request.days);
+            saves.record(store.save(&record).await);
+        }
+
+        progress.emit_result(&result);
+        all_results.push(result);
+
+        global_idx += 1;
+        progress.completed.store(global_idx, Ordering::Relaxed);
+        progress.complete_work(symbol, strategy_type, 1);
+
+        if global_idx.is_multiple_of(50) {
+            update_best_so_far(&all_results, initial_capital, top_n, &progress);
+        }
+        if backtested {
+            pacer.backtested(progress.pacing()).await;
+        } else {
+            pacer.cached().await;
+        }
+    }
+
+    if let Some(matrix) = &basket {
+        let (results, cached) = run_cross_sectional_batch(
+            &cross_grid,
+            matrix,
+            request.days,
+            initial_capital,
+            &fee_config,
+            store.as_deref(),
+            &run_id,
+            "phase1",
+            &progress,
+        )
+        .await;
+        global_idx += results.len() as u32;
+        progress.completed.store(global_idx, Ordering::Relaxed);
+        progress.skipped.fetch_add(cached, Ordering::Relaxed);
+        all_results.extend(results);
+
+        progress.wait_while_paused().await;
+        if progress.cancelled.load(Ordering::Relaxed) {
+            return Err(progress.fail(EngineError::Cancelled));
+        }
+    }
+
+    // Final update of best_so_far after phase 1
+    update_best_so_far(&all_results, initial_capital, top_n, &progress);
+
+    info!(results = all_results.len(), "Phase 1 complete");
+
+    // ── Phase 2: Refinement ─────────────────────────────────────────────
+    *progress.status.write().unwrap() = DiscoveryStatus::Phase2Refinement;
+    *progress.phase.write().unwrap() = "Phase 2: Refinement".to_string();
+
+    // Top K of phase 1, then top K of each further round
+    let mut rounds = RefinementRounds::new(refinement, initial_capital);
+    while let Some(top_result) = rounds.next_parent(&all_results) {
+        let top_result = &top_result;
+        progress.wait_while_paused().await;
+        if progress.cancelled.load(Ordering::Relaxed) {
+            return Err(progress.fail(EngineError::Cancelled));
+        }
+
+        let mut refinement_grid =
+            generate_scaled_refinement_grid(&top_result.strategy_type, refinement.delta_scale);
+
+        if top_result.strategy_type.is_cross_sectional() {
+            if let Some(matrix) = &basket {
+                let (results, cached) = run_cross_sectional_batch(
+                    &refinement_grid,
+                    matrix,
+                    request.days,
+                    initial_capital,
+                    &fee_config,
+                    store.as_deref(),
+                    &run_id,
+                    "phase2",
+                    &progress,
+                )
+                .await;
+                global_idx += results.len() as u32;
+                progress.completed.store(global_idx, Ordering::Relaxed);
+                progress.skipped.fetch_add(cached, Ordering::Relaxed);
+                all_results.extend(results);
+            }
+            continue;
+        }
+
+        // Find klines for this symbol
+        let klines_opt = symbol_klines
+            .iter()
             .find(|(s, _)| *s == top_result.symbol)
             .map(|(_, k)| k);
 
@@ -2113,89 +4624,439 @@ pub async fn run_discovery(
             Some(k) => k,
             None => continue,
         };
+        refinement_grid.extend(filter_refinement_grid(&top_result.strategy_type, klines));
+        refinement_grid.extend(stop_refinement_grid(&top_result.strategy_type));
+        refinement_grid.extend(htf_refinement_grid(&top_result.strategy_type));
+        refinement_grid.extend(zscore_refinement_grid(&top_result.strategy_type));
+        retain_valid(&mut refinement_grid);
+        exclusions.retain(&mut refinement_grid);
 
         *progress.current_strategy.write().unwrap() =
             format!("{} (refine)", top_result.strategy_name);
         *progress.current_symbol.write().unwrap() = top_result.symbol.clone();
+        for variant in &refinement_grid {
+            progress.plan_work(&top_result.symbol, variant, 1);
+        }
 
         for variant in &refinement_grid {
             // Check DB cache before running backtest
             let hash = compute_params_hash(variant, &top_result.symbol, request.days, sizing_mode);
-            if let Some(pool) = &db_pool {
-                let repo = DiscoveryRepository::new(pool);
-                if let Ok(Some(existing)) = repo.get_by_hash(&hash).await {
-                    all_results.push(record_to_result(existing));
+            if let Some(store) = &store {
+                if let Some(cached) =
+                    store.get_by_hash(&hash).await.ok().flatten().and_then(record_to_result)
+                {
+                    progress.emit_result(&cached);
+                    all_results.push(cached);
                     global_idx += 1;
                     progress.completed.store(global_idx, Ordering::Relaxed);
                     progress.skipped.fetch_add(1, Ordering::Relaxed);
+                    progress.complete_work(&top_result.symbol, variant, 1);
                     if global_idx.is_multiple_of(50) {
                         update_best_so_far(&all_results, initial_capital, top_n, &progress);
-                        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
                     }
+                    pacer.cached().await;
                     continue;
                 }
             }
 
-            let result = run_single_backtest(
-                variant,
-                klines,
-                &top_result.symbol,
-                initial_capital,
-                base_position_pct,
-                sizing_mode,
-                &fee_config,
-            );
+            let Some(result) = progress.isolate(&top_result.symbol, variant, "phase2", || {
+                run_single_backtest(
+                    variant,
+                    klines,
+                    &top_result.symbol,
+                    initial_capital,
+                    base_position_pct,
+                    sizing_mode,
+                    &fee_config,
+                    &symbol_limits(&top_result.symbol),
+                    &series_cache,
+                )
+            }) else {
+                global_idx += 1;
+                progress.completed.store(global_idx, Ordering::Relaxed);
+                progress.complete_work(&top_result.symbol, variant, 1);
+                continue;
+            };
 
             // Save to DB
-            if let Some(pool) = &db_pool {
+            if let Some(store) = &store {
                 let record = result_to_record(&result, &hash, &run_id, "phase2", request.days);
-                let repo = DiscoveryRepository::new(pool);
-                let _ = repo.save(&record).await;
+                saves.record(store.save(&record).await);
             }
 
+            progress.emit_result(&result);
             all_results.push(result);
 
             global_idx += 1;
             progress.completed.store(global_idx, Ordering::Relaxed);
+            progress.complete_work(&top_result.symbol, variant, 1);
 
             if global_idx.is_multiple_of(50) {
                 update_best_so_far(&all_results, initial_capital, top_n, &progress);
-                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
             }
+            pacer.backtested(progress.pacing()).await;
+        }
+    }
+
+    // ── Phase 2b: Local search ──────────────────────────────────────────
+    if request.local_search.unwrap_or(false) {
+        *progress.phase.write().unwrap() = "Phase 2b: Local Search".to_string();
+        let starts = local_search_starts(&all_results, initial_capital, LOCAL_SEARCH_TOP);
+        let budget = (starts.len() * LOCAL_SEARCH_MAX_EVALS) as u32;
+        progress
+            .total_combinations
+            .fetch_add(budget, Ordering::Relaxed);
+
+        for start in &starts {
+            let Some((_, klines)) = symbol_klines.iter().find(|(s, _)| *s == start.symbol) else {
+                continue;
+            };
+            let symbol = &start.symbol;
+            *progress.current_strategy.write().unwrap() =
+                format!("{} (local search)", start.strategy_name);
+            *progress.current_symbol.write().unwrap() = symbol.clone();
+
+            let mut climber = HillClimber::new(start, initial_capital, LOCAL_SEARCH_MAX_EVALS);
+            while let Some(variant) = climber.next_candidate() {
+                progress.wait_while_paused().await;
+                if progress.cancelled.load(Ordering::Relaxed) {
+                    return Err(progress.fail(EngineError::Cancelled));
+                }
+                progress.plan_work(symbol, &variant, 1);
+                let hash = compute_params_hash(&variant, symbol, request.days, sizing_mode);
+                let cached = match &store {
+                    Some(store) => store
+                        .get_by_hash(&hash)
+                        .await
+                        .ok()
+                        .flatten()
+                        .and_then(record_to_result),
+                    None => None,
+                };
+                let result = match cached {
+                    Some(cached) => {
+                        progress.skipped.fetch_add(1, Ordering::Relaxed);
+                        cached
+                    }
+                    None => {
+                        let run = || {
+                            run_single_backtest(
+                                &variant,
+                                klines,
+                                symbol,
+                                initial_capital,
+                                base_position_pct,
+                                sizing_mode,
+                                &fee_config,
+                                &symbol_limits(symbol),
+                                &series_cache,
+                            )
+                        };
+                        let Some(result) = progress.isolate(symbol, &variant, "local_search", run)
+                        else {
+                            climber.report_failure();
+                            global_idx += 1;
+                            progress.completed.store(global_idx, Ordering::Relaxed);
+                            progress.complete_work(symbol, &variant, 1);
+                            continue;
+                        };
+                        if let Some(store) = &store {
+                            let record = result_to_record(
+                                &result,
+                                &hash,
+                                &run_id,
+                                "local_search",
+                                request.days,
+                            );
+                            saves.record(store.save(&record).await);
+                        }
+                        pacer.backtested(progress.pacing()).await;
+                        result
+                    }
+                };
+                climber.report(&result);
+                progress.emit_result(&result);
+                all_results.push(result);
+                global_idx += 1;
+                progress.completed.store(global_idx, Ordering::Relaxed);
+                progress.complete_work(symbol, &variant, 1);
+            }
+
+            // The budget a climb settled before using is no longer expected
+            let unused = (LOCAL_SEARCH_MAX_EVALS - climber.evaluations()) as u32;
+            progress
+                .total_combinations
+                .fetch_sub(unused, Ordering::Relaxed);
+            info!(
+                family = start.strategy_type.type_tag(),
+                symbol = %symbol,
+                evaluations = climber.evaluations(),
+                improvement = %climber.improvement(),
+                best = %climber.best().strategy_name,
+                "Local search"
+            );
+        }
+        update_best_so_far(&all_results, initial_capital, top_n, &progress);
+    }
+
+    // ── Phase 3: Drawdown throttle search ──────────────────────────────
+    if request.throttle_search.unwrap_or(false) {
+        *progress.phase.write().unwrap() = "Phase 3: Drawdown Throttle".to_string();
+        let candidates = refit_candidates(&all_results, initial_capital, THROTTLE_SEARCH_TOP);
+        progress
+            .total_combinations
+            .fetch_add(THROTTLE_SEARCH_COMBOS, Ordering::Relaxed);
+        for candidate in &candidates {
+            let presets = DrawdownThrottle::SEARCH_PRESETS.len() as u32;
+            progress.plan_work(&candidate.symbol, &candidate.strategy_type, presets);
+        }
+        let mut throttled = Vec::new();
+
+        for candidate in &candidates {
+            let Some((_, klines)) = symbol_klines.iter().find(|(s, _)| *s == candidate.symbol)
+            else {
+                continue;
+            };
+            *progress.current_strategy.write().unwrap() =
+                format!("{} (throttle)", candidate.strategy_name);
+            *progress.current_symbol.write().unwrap() = candidate.symbol.clone();
+
+            for throttle in DrawdownThrottle::SEARCH_PRESETS {
+                progress.wait_while_paused().await;
+                if progress.cancelled.load(Ordering::Relaxed) {
+                    return Err(progress.fail(EngineError::Cancelled));
+                }
+                let strategy_type = &candidate.strategy_type;
+                let symbol = &candidate.symbol;
+                let base_hash =
+                    compute_params_hash(strategy_type, symbol, request.days, sizing_mode);
+                let hash = compute_throttled_hash(&base_hash, &throttle);
+                let cached = match &store {
+                    Some(store) => store
+                        .get_by_hash(&hash)
+                        .await
+                        .ok()
+                        .flatten()
+                        .and_then(record_to_result),
+                    None => None,
+                };
+                let result = match cached {
+                    Some(cached) => {
+                        progress.skipped.fetch_add(1, Ordering::Relaxed);
+                        cached
+                    }
+                    None => {
+                        let limits = symbol_limits(symbol).with_drawdown_throttle(Some(throttle));
+                        let run = || {
+                            run_single_backtest(
+                                strategy_type,
+                                klines,
+                                symbol,
+                                initial_capital,
+                                base_position_pct,
+                                sizing_mode,
+                                &fee_config,
+                                &limits,
+                                &series_cache,
+                            )
+                        };
+                        let Some(result) = progress.isolate(symbol, strategy_type, "throttle", run)
+                        else {
+                            global_idx += 1;
+                            progress.completed.store(global_idx, Ordering::Relaxed);
+                            progress.complete_work(symbol, strategy_type, 1);
+                            continue;
+                        };
+                        if let Some(store) = &store {
+                            let record =
+                                result_to_record(&result, &hash, &run_id, "throttle", request.days);
+                            saves.record(store.save(&record).await);
+                        }
+                        pacer.backtested(progress.pacing()).await;
+                        result
+                    }
+                };
+
+                progress.emit_result(&result);
+                throttled.push(result.clone());
+                all_results.push(result);
+                global_idx += 1;
+                progress.completed.store(global_idx, Ordering::Relaxed);
+                progress.complete_work(symbol, strategy_type, 1);
+            }
+        }
+
+        for comparison in compare_throttles(&candidates, &throttled, initial_capital) {
+            info!(
+                throttle = %comparison.throttle.label(),
+                tested = comparison.tested,
+                improved = comparison.improved,
+                mean_score_delta = comparison.mean_score_delta,
+                mean_drawdown_delta = comparison.mean_drawdown_delta,
+                "Throttle search"
+            );
+        }
+        update_best_so_far(&all_results, initial_capital, top_n, &progress);
+    }
+
+    // ── Phase 4: Cross-symbol validation ───────────────────────────────
+    if request.cross_symbol.unwrap_or(false) && symbol_klines.len() > 1 {
+        *progress.phase.write().unwrap() = "Phase 4: Cross-Symbol Validation".to_string();
+        let candidates = refit_candidates(&all_results, initial_capital, CROSS_SYMBOL_TOP);
+        let others = symbol_klines.len() as u32 - 1;
+        progress
+            .total_combinations
+            .fetch_add(candidates.len() as u32 * others, Ordering::Relaxed);
+        for candidate in &candidates {
+            for (symbol, _) in symbol_klines.iter().filter(|(s, _)| *s != candidate.symbol) {
+                progress.plan_work(symbol, &candidate.strategy_type, 1);
+            }
+        }
+
+        for candidate in &candidates {
+            *progress.current_strategy.write().unwrap() =
+                format!("{} (cross-symbol)", candidate.strategy_name);
+            let params = serde_json::to_string(&candidate.strategy_type).ok();
+            let mut transfers = Vec::new();
+
+            for (symbol, klines) in symbol_klines.iter().filter(|(s, _)| *s != candidate.symbol) {
+                progress.wait_while_paused().await;
+                if progress.cancelled.load(Ordering::Relaxed) {
+                    return Err(progress.fail(EngineError::Cancelled));
+                }
+                *progress.current_symbol.write().unwrap() = symbol.clone();
+                let strategy_type = &candidate.strategy_type;
+                let known = all_results.iter().find(|r| {
+                    r.symbol == *symbol
+                        && r.drawdown_throttle.is_none()
+                        && serde_json::to_string(&r.strategy_type).ok() == params
+                });
+                let result = match known {
+                    Some(known) => {
+                        progress.skipped.fetch_add(1, Ordering::Relaxed);
+                        known.clone()
+                    }
+                    None => {
+                        let hash =
+                            compute_params_hash(strategy_type, symbol, request.days, sizing_mode);
+                        let cached = match &store {
+                            Some(store) => store
+                                .get_by_hash(&hash)
+                                .await
+                                .ok()
+                                .flatten()
+                                .and_then(record_to_result),
+                            None => None,
+                        };
+                        let result = match cached {
+                            Some(cached) => {
+                                progress.skipped.fetch_add(1, Ordering::Relaxed);
+                                cached
+                            }
+                            None => {
+                                let run = || {
+                                    run_single_backtest(
+                                        strategy_type,
+                                        klines,
+                                        symbol,
+                                        initial_capital,
+                                        base_position_pct,
+                                        sizing_mode,
+                                        &fee_config,
+                                        &symbol_limits(symbol),
+                                        &series_cache,
+                                    )
+                                };
+                                let Some(result) =
+                                    progress.isolate(symbol, strategy_type, "cross_symbol", run)
+                                else {
+                                    global_idx += 1;
+                                    progress.completed.store(global_idx, Ordering::Relaxed);
+                                    progress.complete_work(symbol, strategy_type, 1);
+                                    continue;
+                                };
+                                if let Some(store) = &store {
+                                    let record = result_to_record(
+                                        &result,
+                                        &hash,
+                                        &run_id,
+                                        "cross_symbol",
+                                        request.days,
+                                    );
+                                    saves.record(store.save(&record).await);
+                                }
+                                pacer.backtested(progress.pacing()).await;
+                                result
+                            }
+                        };
+                        progress.emit_result(&result);
+                        all_results.push(result.clone());
+                        result
+                    }
+                };
+                transfers.push(result);
+                global_idx += 1;
+                progress.completed.store(global_idx, Ordering::Relaxed);
+                progress.complete_work(symbol, strategy_type, 1);
+            }
+
+            let Some(score) = cross_symbol_score(&transfers) else {
+                continue;
+            };
+            for result in all_results.iter_mut().filter(|r| {
+                r.symbol == candidate.symbol
+                    && r.drawdown_throttle.is_none()
+                    && serde_json::to_string(&r.strategy_type).ok() == params
+            }) {
+                result.cross_symbol_score = Some(score);
+            }
+            if let Some(store) = &store {
+                let hash = compute_params_hash(
+                    &candidate.strategy_type,
+                    &candidate.symbol,
+                    request.days,
+                    sizing_mode,
+                );
+                let _ = store.set_cross_symbol_score(&hash, dec_to_f64(score)).await;
+            }
+            info!(
+                strategy = %candidate.strategy_name,
+                symbol = %candidate.symbol,
+                cross_symbol_score = %score,
+                "Cross-symbol validation"
+            );
         }
+        update_best_so_far(&all_results, initial_capital, top_n, &progress);
     }
 
     // ── Finalize ────────────────────────────────────────────────────────
     // Score all, sort, rank
+    let cross_symbol_weight = request
+        .cross_symbol_weight
+        .unwrap_or_default()
+        .clamp(Decimal::ZERO, Decimal::ONE);
+    let per_underwater_day = request
+        .underwater_penalty
+        .unwrap_or_default()
+        .max(Decimal::ZERO);
     let mut scored_results: Vec<(Decimal, DiscoveryResult)> = all_results
         .into_iter()
         .map(|r| {
-            let score = score_result(&r, initial_capital);
+            let score = score_result(&r, initial_capital)
+                + cross_symbol_bonus(&r, cross_symbol_weight)
+                - underwater_penalty(&r, per_underwater_day);
             (score, r)
         })
         .collect();
 
-    scored_results.sort_by(|a, b| b.0.cmp(&a.0));
-
-    // Deduplicate: keep best score per (strategy_name, symbol) to avoid near-identical results
-    let mut seen = std::collections::HashSet::new();
-    let mut final_results = Vec::new();
-    for (score, mut result) in scored_results {
-        let key = format!(
-            "{}:{}:{}",
-            result.strategy_name, result.symbol, result.total_trades
-        );
-        if seen.contains(&key) {
-            continue;
-        }
-        seen.insert(key);
-        result.composite_score = score;
-        result.rank = final_results.len() + 1;
-        final_results.push(result);
-        if final_results.len() >= top_n {
-            break;
-        }
-    }
+    let order = request.rank_order();
+    scored_results.sort_by(|a, b| compare_ranked(&order, (a.0, &a.1), (b.0, &b.1)));
+    let final_results = select_final_results(
+        scored_results,
+        top_n,
+        request.per_symbol_top_n.unwrap_or(0),
+        &request.symbols,
+    );
 
     // Update total to actual completed count
     progress
@@ -2204,23 +5065,212 @@ pub async fn run_discovery(
     progress.completed.store(global_idx, Ordering::Relaxed);
 
     let skipped_count = progress.skipped.load(Ordering::Relaxed);
+    let (series_hits, series_misses, _) = series_cache.stats();
     info!(
         final_count = final_results.len(),
         total_tested = global_idx,
         skipped = skipped_count,
+        series_hits,
+        series_misses,
         best_score = %final_results.first().map(|r| r.composite_score).unwrap_or_default(),
         "Discovery complete"
     );
+    apply_retention(store.as_deref()).await;
 
     *progress.final_results.write().unwrap() = final_results;
+    saves.check().map_err(|e| progress.fail(e))?;
     *progress.status.write().unwrap() = DiscoveryStatus::Complete;
+    Ok(())
 }
 
 // ============================================================================
 // Helpers
 // ============================================================================
 
-fn run_single_backtest(
+/// Best unthrottled results the throttle search and the cross-symbol validation re-run:
+/// single-symbol strategies of the generic backtester that ran to the end, one per
+/// strategy and symbol
+fn refit_candidates(
+    results: &[DiscoveryResult],
+    initial_capital: Decimal,
+    limit: usize,
+) -> Vec<DiscoveryResult> {
+    let mut scored: Vec<(Decimal, &DiscoveryResult)> = results
+        .iter()
+        .filter(|r| {
+            r.drawdown_throttle.is_none()
+                && r.abort_reason.is_none()
+                && !r.strategy_type.is_gabagool()
+                && !r.strategy_type.is_cross_sectional()
+        })
+        .map(|r| (score_result(r, initial_capital), r))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    let mut seen = std::collections::HashSet::new();
+    scored
+        .into_iter()
+        .filter(|(_, r)| seen.insert((r.strategy_name.clone(), r.symbol.clone())))
+        .take(limit)
+        .map(|(_, r)| r.clone())
+        .collect()
+}
+
+/// Share of `transfers` (the same params backtested on other symbols) that ended with a
+/// positive net PnL; `None` when there is none. Aborted runs count as unprofitable.
+pub fn cross_symbol_score(transfers: &[DiscoveryResult]) -> Option<Decimal> {
+    if transfers.is_empty() {
+        return None;
+    }
+    let profitable = transfers
+        .iter()
+        .filter(|r| r.abort_reason.is_none() && r.net_pnl > Decimal::ZERO)
+        .count();
+    Some(Decimal::from(profitable) / Decimal::from(transfers.len()))
+}
+
+/// Composite points a cross-validated result gains in the final ranking, at `weight`
+/// (0-1): `CROSS_SYMBOL_MAX_BONUS` when profitable on every other symbol, none when
+/// profitable on none of them or not cross-validated
+pub(crate) fn cross_symbol_bonus(result: &DiscoveryResult, weight: Decimal) -> Decimal {
+    result.cross_symbol_score.map_or(Decimal::ZERO, |score| {
+        score * CROSS_SYMBOL_MAX_BONUS * weight
+    })
+}
+
+/// Composite points a result loses in the final ranking for its longest drawdown, at
+/// `per_day` points per day under water (none without a measured drawdown)
+pub(crate) fn underwater_penalty(result: &DiscoveryResult, per_day: Decimal) -> Decimal {
+    result
+        .longest_drawdown_days
+        .map_or(Decimal::ZERO, |days| days * per_day)
+}
+
+/// How one throttle preset did against the unthrottled runs of the same strategies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThrottleComparison {
+    pub throttle: DrawdownThrottle,
+    pub tested: u32,
+    /// Strategies whose score is higher throttled
+    pub improved: u32,
+    /// Mean of throttled minus unthrottled score
+    pub mean_score_delta: f64,
+    /// Mean of throttled minus unthrottled max drawdown (%), negative = shallower
+    pub mean_drawdown_delta: f64,
+}
+
+/// Compare each throttled result with the unthrottled `baselines` run of its strategy
+/// and symbol, per `DrawdownThrottle::SEARCH_PRESETS` preset
+pub fn compare_throttles(
+    baselines: &[DiscoveryResult],
+    throttled: &[DiscoveryResult],
+    initial_capital: Decimal,
+) -> Vec<ThrottleComparison> {
+    DrawdownThrottle::SEARCH_PRESETS
+        .iter()
+        .map(|preset| {
+            let mut comparison = ThrottleComparison {
+                throttle: *preset,
+                tested: 0,
+                improved: 0,
+                mean_score_delta: 0.0,
+                mean_drawdown_delta: 0.0,
+            };
+            let runs = throttled
+                .iter()
+                .filter(|r| r.drawdown_throttle == Some(*preset));
+            for result in runs {
+                let params = serde_json::to_string(&result.strategy_type).ok();
+                let Some(base) = baselines.iter().find(|b| {
+                    b.symbol == result.symbol
+                        && serde_json::to_string(&b.strategy_type).ok() == params
+                }) else {
+                    continue;
+                };
+                let score = score_result(result, initial_capital);
+                let base_score = score_result(base, initial_capital);
+                comparison.tested += 1;
+                if score > base_score {
+                    comparison.improved += 1;
+                }
+                comparison.mean_score_delta += dec_to_f64(score - base_score);
+                comparison.mean_drawdown_delta +=
+                    dec_to_f64(result.max_drawdown_pct - base.max_drawdown_pct);
+            }
+            if comparison.tested > 0 {
+                comparison.mean_score_delta /= comparison.tested as f64;
+                comparison.mean_drawdown_delta /= comparison.tested as f64;
+            }
+            comparison
+        })
+        .collect()
+}
+
+/// Apply the store's retention policy once a run or cycle has stored its results
+async fn apply_retention(store: Option<&dyn ResultStore>) {
+    let Some(store) = store else {
+        return;
+    };
+    match store.apply_retention().await {
+        Ok(Some(report)) if report.deleted() > 0 => info!(
+            below_min_score = report.below_min_score,
+            stale_negative = report.stale_negative,
+            beyond_top_n = report.beyond_top_n,
+            remaining = report.remaining,
+            "Retention policy applied"
+        ),
+        Ok(_) => {}
+        Err(e) => warn!(error = %e, "Retention policy failed"),
+    }
+}
+
+/// Backtest one single-symbol strategy the way discovery does (10 000 capital, 10 %
+/// base position, default Polymarket fees, confidence quartiles) and score it.
+/// Entry point of the Python bindings; cross-sectional strategies need a `KlineMatrix`.
+pub fn run_backtest(
+    strategy_type: &DiscoveryStrategyType,
+    klines: &[Kline],
+    symbol: &str,
+    sizing_mode: SizingMode,
+    limits: &BacktestLimits,
+) -> DiscoveryResult {
+    run_backtest_with_fees(
+        strategy_type,
+        klines,
+        symbol,
+        sizing_mode,
+        limits,
+        &PolymarketFeeConfig::default(),
+    )
+}
+
+/// [`run_backtest`] priced under another fee model
+pub fn run_backtest_with_fees(
+    strategy_type: &DiscoveryStrategyType,
+    klines: &[Kline],
+    symbol: &str,
+    sizing_mode: SizingMode,
+    limits: &BacktestLimits,
+    fee_config: &PolymarketFeeConfig,
+) -> DiscoveryResult {
+    let initial_capital = dec!(10000);
+    let series_cache = IndicatorSeriesCache::new(SERIES_CACHE_CAPACITY);
+    let mut result = run_single_backtest(
+        strategy_type,
+        klines,
+        symbol,
+        initial_capital,
+        dec!(10),
+        sizing_mode,
+        fee_config,
+        limits,
+        &series_cache,
+    );
+    result.composite_score = score_result(&result, initial_capital);
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_single_backtest(
     strategy_type: &DiscoveryStrategyType,
     klines: &[Kline],
     symbol: &str,
@@ -2228,6 +5278,8 @@ fn run_single_backtest(
     base_position_pct: Decimal,
     sizing_mode: SizingMode,
     fee_config: &PolymarketFeeConfig,
+    limits: &BacktestLimits,
+    series_cache: &IndicatorSeriesCache,
 ) -> DiscoveryResult {
     if strategy_type.is_gabagool() {
         run_gabagool_backtest_for_discovery(strategy_type, klines, symbol, fee_config, sizing_mode)
@@ -2240,19 +5292,25 @@ fn run_single_backtest(
             base_position_pct,
             sizing_mode,
             fee_config,
+            limits,
+            series_cache,
         )
     }
 }
 
 /// Calculate strategy confidence by running backtests on 4 quartiles of the data.
 /// Returns a score from 0 to 100 based on consistency across time periods.
+#[allow(clippy::too_many_arguments)]
 fn calculate_strategy_confidence(
     strategy_type: &DiscoveryStrategyType,
     klines: &[Kline],
+    symbol: &str,
     initial_capital: Decimal,
     base_position_pct: Decimal,
     sizing_mode: SizingMode,
     fee_config: &PolymarketFeeConfig,
+    limits: &BacktestLimits,
+    series_cache: &IndicatorSeriesCache,
 ) -> Decimal {
     if klines.len() < 200 {
         // Not enough data for meaningful quartile analysis
@@ -2271,7 +5329,7 @@ fn calculate_strategy_confidence(
     let mut profitable_count = 0u32;
 
     for q_klines in &quartiles {
-        let mut gen = build_signal_generator(strategy_type);
+        let mut gen = series_cache.build_generator(strategy_type, symbol, q_klines);
         let bt = run_generic_backtest(
             gen.as_mut(),
             q_klines,
@@ -2279,6 +5337,7 @@ fn calculate_strategy_confidence(
             base_position_pct,
             sizing_mode,
             fee_config,
+            limits,
         );
         if bt.total_pnl > Decimal::ZERO {
             profitable_count += 1;
@@ -2318,6 +5377,7 @@ fn calculate_strategy_confidence(
         .unwrap_or(Decimal::ZERO)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_indicator_backtest_for_discovery(
     strategy_type: &DiscoveryStrategyType,
     klines: &[Kline],
@@ -2326,8 +5386,11 @@ fn run_indicator_backtest_for_discovery(
     base_position_pct: Decimal,
     sizing_mode: SizingMode,
     fee_config: &PolymarketFeeConfig,
+    limits: &BacktestLimits,
+    series_cache: &IndicatorSeriesCache,
 ) -> DiscoveryResult {
-    let mut generator = build_signal_generator(strategy_type);
+    let mut generator = series_cache.build_generator(strategy_type, symbol, klines);
+    let limits = &limits.with_trailing_stop(strategy_type.trailing_stop().or(limits.trailing_stop));
 
     let bt = run_generic_backtest(
         generator.as_mut(),
@@ -2336,23 +5399,28 @@ fn run_indicator_backtest_for_discovery(
         base_position_pct,
         sizing_mode,
         fee_config,
+        limits,
     );
 
     // Calculate confidence only for promising strategies (net_pnl > 0 AND win_rate > 50)
-    let strategy_confidence = if bt.total_pnl > Decimal::ZERO && bt.win_rate > dec!(50) {
+    let promising = bt.total_pnl > Decimal::ZERO && bt.win_rate > dec!(50);
+    let strategy_confidence = if promising && bt.aborted.is_none() {
         calculate_strategy_confidence(
             strategy_type,
             klines,
+            symbol,
             initial_capital,
             base_position_pct,
             sizing_mode,
             fee_config,
+            limits,
+            series_cache,
         )
     } else {
         Decimal::ZERO
     };
 
-    DiscoveryResult {
+    let mut result = DiscoveryResult {
         rank: 0,
         strategy_type: strategy_type.clone(),
         strategy_name: strategy_type.name().to_string(),
@@ -2378,6 +5446,52 @@ fn run_indicator_backtest_for_discovery(
         strategy_confidence,
         hit_rate: None,
         avg_locked_profit: None,
+        abort_reason: bt.aborted,
+        probability_model: fee_config.probability_model.name(),
+        drawdown_throttle: limits.drawdown_throttle,
+        cross_symbol_score: None,
+        exit_breakdown: Some(bt.exit_breakdown),
+        decayed_score: None,
+        recent_30d: None,
+        recent_90d: None,
+        pnl_concentration: bt.pnl_concentration,
+        longest_drawdown_days: bt.longest_drawdown_days,
+        avg_recovery_days: bt.avg_recovery_days,
+        max_exposure_pct: bt.max_exposure_pct,
+        avg_exposure_pct: bt.avg_exposure_pct,
+        warmup_bars: bt.warmup_bars,
+        effective_days: bt.effective_days,
+        unfilled: bt.unfilled,
+        cost_attribution: bt.cost_attribution,
+    };
+    result.decayed_score = bt.decayed.map(|decayed| {
+        let decayed = DiscoveryResult {
+            net_pnl: decayed.net_pnl,
+            sharpe_ratio: decayed.sharpe_ratio,
+            ..result.clone()
+        };
+        score_result(&decayed, initial_capital)
+    });
+    let recent = |window: RecentTrades| {
+        let windowed = DiscoveryResult {
+            net_pnl: window.net_pnl,
+            win_rate: window.win_rate,
+            total_trades: window.total_trades,
+            sharpe_ratio: window.sharpe_ratio,
+            ..result.clone()
+        };
+        RecentPerformance {
+            score: score_result(&windowed, initial_capital),
+            net_pnl: window.net_pnl,
+            win_rate: window.win_rate,
+            total_trades: window.total_trades,
+        }
+    };
+    let (recent_30d, recent_90d) = (bt.recent_30d.map(recent), bt.recent_90d.map(recent));
+    DiscoveryResult {
+        recent_30d,
+        recent_90d,
+        ..result
     }
 }
 
@@ -2397,27 +5511,29 @@ fn run_gabagool_backtest_for_discovery(
         _ => unreachable!(),
     };
 
+    // Cover exactly the period the klines were fetched for
+    let days = klines
+        .first()
+        .zip(klines.last())
+        .map(|(first, last)| {
+            ((last.close_time + 1 - first.open_time) as f64 / 86_400_000.0).ceil() as u32
+        })
+        .unwrap_or(0);
+
     let config = GabagoolBacktestConfig {
         symbol: symbol.to_string(),
-        days: 90,
+        days,
         size_per_side: dec!(10),
         max_pair_cost,
         bid_offset,
         spread_multiplier,
+        window: GabagoolWindow::default(),
+        fee_config: fee_config.clone(),
     };
 
     let result = GabagoolBacktestEngine::run(&config, klines);
-
-    // Calculate gabagool fees
-    let mut total_fees = Decimal::ZERO;
-    for window in &result.windows {
-        if window.traded {
-            total_fees += calculate_taker_fee(config.size_per_side, window.yes_fill, fee_config);
-            total_fees += calculate_taker_fee(config.size_per_side, window.no_fill, fee_config);
-        }
-    }
-
-    let net_pnl = result.total_locked_profit - total_fees;
+    let total_fees = result.total_fees;
+    let net_pnl = result.net_profit;
 
     DiscoveryResult {
         rank: 0,
@@ -2431,8 +5547,8 @@ fn run_gabagool_backtest_for_discovery(
         total_fees,
         win_rate: result.hit_rate,
         total_trades: result.traded_windows,
-        sharpe_ratio: Decimal::ZERO,
-        max_drawdown_pct: Decimal::ZERO,
+        sharpe_ratio: result.sharpe_ratio,
+        max_drawdown_pct: result.max_drawdown_pct,
         profit_factor: if total_fees > Decimal::ZERO {
             result.total_locked_profit / total_fees
         } else {
@@ -2449,9 +5565,381 @@ fn run_gabagool_backtest_for_discovery(
         strategy_confidence: Decimal::ZERO,
         hit_rate: Some(result.hit_rate),
         avg_locked_profit: Some(result.avg_locked_profit),
+        abort_reason: None,
+        probability_model: fee_config.probability_model.name(),
+        drawdown_throttle: None,
+        cross_symbol_score: None,
+        exit_breakdown: None,
+        decayed_score: None,
+        recent_30d: None,
+        recent_90d: None,
+        pnl_concentration: None,
+        longest_drawdown_days: None,
+        avg_recovery_days: None,
+        max_exposure_pct: None,
+        avg_exposure_pct: None,
+        warmup_bars: None,
+        effective_days: None,
+        unfilled: None,
+        cost_attribution: None,
     }
 }
 
+fn run_rotation_backtest_for_discovery(
+    strategy_type: &DiscoveryStrategyType,
+    matrix: &KlineMatrix,
+    initial_capital: Decimal,
+    fee_config: &PolymarketFeeConfig,
+) -> DiscoveryResult {
+    let (lookback, top_k) = match strategy_type {
+        DiscoveryStrategyType::RelativeStrength { lookback, top_k } => (*lookback, *top_k),
+        _ => unreachable!(),
+    };
+
+    let bt = run_rotation_backtest(matrix, lookback, top_k, initial_capital, fee_config);
+    let stats = summarize_trades(
+        &bt.trades,
+        initial_capital,
+        bt.final_equity,
+        bt.total_fees,
+        bt.max_drawdown_pct,
+        matrix.len(),
+    );
+
+    DiscoveryResult {
+        rank: 0,
+        strategy_type: strategy_type.clone(),
+        strategy_name: strategy_type.name().to_string(),
+        symbol: matrix.label(),
+        // Rotation always splits equity equally between the top-K
+        sizing_mode: SizingMode::Fixed,
+        composite_score: Decimal::ZERO,
+        net_pnl: stats.total_pnl,
+        gross_pnl: stats.total_pnl + stats.total_fees,
+        total_fees: stats.total_fees,
+        win_rate: stats.win_rate,
+        total_trades: stats.total_trades,
+        sharpe_ratio: stats.sharpe_ratio,
+        max_drawdown_pct: stats.max_drawdown_pct,
+        profit_factor: stats.profit_factor,
+        avg_trade_pnl: stats.avg_trade_pnl,
+        sortino_ratio: stats.sortino_ratio,
+        max_consecutive_losses: stats.max_consecutive_losses,
+        avg_win_pnl: stats.avg_win_pnl,
+        avg_loss_pnl: stats.avg_loss_pnl,
+        total_volume: stats.total_volume,
+        annualized_return_pct: stats.annualized_return_pct,
+        annualized_sharpe: stats.annualized_sharpe,
+        strategy_confidence: Decimal::ZERO,
+        hit_rate: None,
+        avg_locked_profit: None,
+        abort_reason: None,
+        probability_model: fee_config.probability_model.name(),
+        drawdown_throttle: None,
+        cross_symbol_score: None,
+        exit_breakdown: Some(stats.exit_breakdown),
+        decayed_score: None,
+        recent_30d: None,
+        recent_90d: None,
+        pnl_concentration: stats.pnl_concentration,
+        longest_drawdown_days: None,
+        avg_recovery_days: None,
+        max_exposure_pct: None,
+        avg_exposure_pct: None,
+        warmup_bars: None,
+        effective_days: None,
+        unfilled: None,
+        cost_attribution: None,
+    }
+}
+
+/// Notional of each pair leg in % of equity (both legs together = full equity)
+const PAIR_LEG_PCT: Decimal = dec!(50);
+
+fn run_pair_spread_backtest_for_discovery(
+    strategy_type: &DiscoveryStrategyType,
+    matrix: &KlineMatrix,
+    initial_capital: Decimal,
+    fee_config: &PolymarketFeeConfig,
+) -> Option<DiscoveryResult> {
+    let DiscoveryStrategyType::PairSpread {
+        leg_a,
+        leg_b,
+        lookback,
+        entry_z,
+        exit_z,
+    } = strategy_type
+    else {
+        unreachable!()
+    };
+    let ia = matrix.symbols.iter().position(|s| s == leg_a)?;
+    let ib = matrix.symbols.iter().position(|s| s == leg_b)?;
+    if ia == ib {
+        return None;
+    }
+
+    let closes_a: Vec<Decimal> = matrix.closes.iter().map(|row| row[ia]).collect();
+    let closes_b: Vec<Decimal> = matrix.closes.iter().map(|row| row[ib]).collect();
+    let params = PairSpreadParams {
+        lookback: *lookback,
+        entry_z: *entry_z,
+        exit_z: *exit_z,
+        leg_pct: PAIR_LEG_PCT,
+    };
+    let bt = run_pair_spread_backtest(
+        &matrix.open_times,
+        &closes_a,
+        &closes_b,
+        params,
+        initial_capital,
+        fee_config,
+    );
+    let stats = summarize_trades(
+        &bt.trades,
+        initial_capital,
+        bt.final_equity,
+        bt.total_fees,
+        bt.max_drawdown_pct,
+        matrix.len(),
+    );
+
+    Some(DiscoveryResult {
+        rank: 0,
+        strategy_type: strategy_type.clone(),
+        strategy_name: strategy_type.name().to_string(),
+        symbol: format!("{}/{}", leg_a, leg_b),
+        sizing_mode: SizingMode::Fixed,
+        composite_score: Decimal::ZERO,
+        net_pnl: stats.total_pnl,
+        gross_pnl: stats.total_pnl + stats.total_fees,
+        total_fees: stats.total_fees,
+        win_rate: stats.win_rate,
+        total_trades: stats.total_trades,
+        sharpe_ratio: stats.sharpe_ratio,
+        max_drawdown_pct: stats.max_drawdown_pct,
+        profit_factor: stats.profit_factor,
+        avg_trade_pnl: stats.avg_trade_pnl,
+        sortino_ratio: stats.sortino_ratio,
+        max_consecutive_losses: stats.max_consecutive_losses,
+        avg_win_pnl: stats.avg_win_pnl,
+        avg_loss_pnl: stats.avg_loss_pnl,
+        total_volume: stats.total_volume,
+        annualized_return_pct: stats.annualized_return_pct,
+        annualized_sharpe: stats.annualized_sharpe,
+        strategy_confidence: Decimal::ZERO,
+        hit_rate: None,
+        avg_locked_profit: None,
+        abort_reason: None,
+        probability_model: fee_config.probability_model.name(),
+        drawdown_throttle: None,
+        cross_symbol_score: None,
+        exit_breakdown: Some(stats.exit_breakdown),
+        decayed_score: None,
+        recent_30d: None,
+        recent_90d: None,
+        pnl_concentration: stats.pnl_concentration,
+        longest_drawdown_days: None,
+        avg_recovery_days: None,
+        max_exposure_pct: None,
+        avg_exposure_pct: None,
+        warmup_bars: None,
+        effective_days: None,
+        unfilled: None,
+        cost_attribution: None,
+    })
+}
+
+/// Result symbol of a cross-sectional strategy: `A/B` for pairs, the basket label otherwise
+fn cross_sectional_label(strategy_type: &DiscoveryStrategyType, matrix: &KlineMatrix) -> String {
+    match strategy_type {
+        DiscoveryStrategyType::PairSpread { leg_a, leg_b, .. } => format!("{}/{}", leg_a, leg_b),
+        _ => matrix.label(),
+    }
+}
+
+/// Whether `strategy_type` can run on `matrix` (enough symbols, both legs present)
+fn cross_sectional_runnable(strategy_type: &DiscoveryStrategyType, matrix: &KlineMatrix) -> bool {
+    match strategy_type {
+        DiscoveryStrategyType::RelativeStrength { top_k, .. } => *top_k < matrix.symbols.len(),
+        DiscoveryStrategyType::PairSpread { leg_a, leg_b, .. } => {
+            leg_a != leg_b && matrix.symbols.contains(leg_a) && matrix.symbols.contains(leg_b)
+        }
+        _ => false,
+    }
+}
+
+/// Backtest cross-sectional strategies on the aligned basket, reusing DB-cached
+/// results. Returns the results and how many came from the cache; stops early
+/// (partial results) when the run is cancelled.
+#[allow(clippy::too_many_arguments)]
+async fn run_cross_sectional_batch(
+    strategies: &[DiscoveryStrategyType],
+    matrix: &KlineMatrix,
+    days: u32,
+    initial_capital: Decimal,
+    fee_config: &PolymarketFeeConfig,
+    store: Option<&dyn ResultStore>,
+    run_id: &str,
+    phase: &str,
+    progress: &DiscoveryProgress,
+) -> (Vec<DiscoveryResult>, u32) {
+    let mut results = Vec::new();
+    let mut cached_count = 0u32;
+    let mut pacer = Pacer::new();
+    let runnable: Vec<&DiscoveryStrategyType> = strategies
+        .iter()
+        .filter(|s| cross_sectional_runnable(s, matrix))
+        .collect();
+    for strategy_type in &runnable {
+        let label = cross_sectional_label(strategy_type, matrix);
+        progress.plan_work(&label, strategy_type, 1);
+    }
+
+    for strategy_type in runnable {
+        progress.wait_while_paused().await;
+        if progress.cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        let label = cross_sectional_label(strategy_type, matrix);
+        *progress.current_strategy.write().unwrap() = strategy_type.name().to_string();
+        *progress.current_symbol.write().unwrap() = label.clone();
+
+        let hash = compute_params_hash(strategy_type, &label, days, SizingMode::Fixed);
+        if let Some(store) = store {
+            if let Some(cached) = store
+                .get_by_hash(&hash)
+                .await
+                .ok()
+                .flatten()
+                .and_then(record_to_result)
+            {
+                progress.emit_result(&cached);
+                results.push(cached);
+                cached_count += 1;
+                progress.complete_work(&label, strategy_type, 1);
+                continue;
+            }
+        }
+
+        let result = match strategy_type {
+            DiscoveryStrategyType::PairSpread { .. } => run_pair_spread_backtest_for_discovery(
+                strategy_type,
+                matrix,
+                initial_capital,
+                fee_config,
+            ),
+            _ => Some(run_rotation_backtest_for_discovery(
+                strategy_type,
+                matrix,
+                initial_capital,
+                fee_config,
+            )),
+        };
+        progress.complete_work(&label, strategy_type, 1);
+        let Some(result) = result else {
+            continue;
+        };
+
+        if let Some(store) = store {
+            let record = result_to_record(&result, &hash, run_id, phase, days);
+            let _ = store.save(&record).await;
+        }
+
+        progress.emit_result(&result);
+        results.push(result);
+        pacer.backtested(progress.pacing()).await;
+    }
+
+    (results, cached_count)
+}
+
+/// Order of two scored results in rank order `order` (first ranked first), the score
+/// being the one the ranking recomputed; results tied on every key are ordered by
+/// strategy name, then symbol
+pub(crate) fn compare_ranked(
+    order: &[RankKey],
+    a: (Decimal, &DiscoveryResult),
+    b: (Decimal, &DiscoveryResult),
+) -> std::cmp::Ordering {
+    for key in order {
+        let ascending = match key {
+            RankKey::Score => a.0.cmp(&b.0),
+            RankKey::Sharpe => a.1.sharpe_ratio.cmp(&b.1.sharpe_ratio),
+            RankKey::Trades => a.1.total_trades.cmp(&b.1.total_trades),
+            RankKey::NetPnl => a.1.net_pnl.cmp(&b.1.net_pnl),
+            RankKey::WinRate => a.1.win_rate.cmp(&b.1.win_rate),
+            RankKey::ProfitFactor => a.1.profit_factor.cmp(&b.1.profit_factor),
+            RankKey::Drawdown => a.1.max_drawdown_pct.cmp(&b.1.max_drawdown_pct),
+        };
+        let ordering = if key.descending() {
+            ascending.reverse()
+        } else {
+            ascending
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    a.1.strategy_name
+        .cmp(&b.1.strategy_name)
+        .then_with(|| a.1.symbol.cmp(&b.1.symbol))
+}
+
+/// Final ranking of the ranked `scored` results: near-identical results (same strategy
+/// name, symbol and trade count) are dropped, then the best `per_symbol` of each of
+/// `symbols` are kept first and the best of the rest fill up to `top_n`. The output can
+/// exceed `top_n` when the per-symbol quotas alone do.
+fn select_final_results(
+    scored: Vec<(Decimal, DiscoveryResult)>,
+    top_n: usize,
+    per_symbol: usize,
+    symbols: &[String],
+) -> Vec<DiscoveryResult> {
+    let mut seen = std::collections::HashSet::new();
+    let unique: Vec<(Decimal, DiscoveryResult)> = scored
+        .into_iter()
+        .filter(|(_, result)| {
+            seen.insert(format!(
+                "{}:{}:{}",
+                result.strategy_name, result.symbol, result.total_trades
+            ))
+        })
+        .collect();
+
+    let mut kept = vec![false; unique.len()];
+    let mut per_symbol_kept: HashMap<&str, usize> = HashMap::new();
+    for (i, (_, result)) in unique.iter().enumerate() {
+        if !symbols.contains(&result.symbol) {
+            continue;
+        }
+        let count = per_symbol_kept.entry(result.symbol.as_str()).or_default();
+        if *count < per_symbol {
+            *count += 1;
+            kept[i] = true;
+        }
+    }
+    let mut remaining = top_n.saturating_sub(kept.iter().filter(|k| **k).count());
+    for keep in kept.iter_mut().filter(|k| !**k) {
+        if remaining == 0 {
+            break;
+        }
+        *keep = true;
+        remaining -= 1;
+    }
+
+    unique
+        .into_iter()
+        .zip(kept)
+        .filter_map(|(entry, keep)| keep.then_some(entry))
+        .enumerate()
+        .map(|(i, (score, mut result))| {
+            result.composite_score = score;
+            result.rank = i + 1;
+            result
+        })
+        .collect()
+}
+
 fn update_best_so_far(
     results: &[DiscoveryResult],
     initial_capital: Decimal,
@@ -2463,7 +5951,8 @@ fn update_best_so_far(
         .map(|r| (score_result(r, initial_capital), r))
         .collect();
 
-    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    let order = progress.rank_order();
+    scored.sort_by(|a, b| compare_ranked(&order, (a.0, a.1), (b.0, b.1)));
 
     let best: Vec<DiscoveryResult> = scored
         .into_iter()
@@ -2489,7 +5978,7 @@ fn update_best_so_far(
 /// - Cycle 1: Quadruples with all 3 modes (Unanimous + PrimaryConfirmed)
 /// - Cycle 2: Mixed param variants on pairs/triples
 /// - Cycle 3+: ML-guided (evolutionary algorithm)
-fn generate_exploratory_grid(cycle: u32) -> Vec<DiscoveryStrategyType> {
+pub(crate) fn generate_exploratory_grid(cycle: u32) -> Vec<DiscoveryStrategyType> {
     let mut grid = Vec::new();
     let all_indicators = SingleIndicatorType::all();
     let all_modes = DynCombineMode::all();
@@ -2624,8 +6113,8 @@ fn generate_exploratory_grid(cycle: u32) -> Vec<DiscoveryStrategyType> {
     grid
 }
 
-/// Generate a random DynamicCombo with n indicators (2-4)
-fn generate_random_dynamic_combo(n: usize, rng: &mut impl rand::Rng) -> DiscoveryStrategyType {
+/// n distinct random indicators
+fn random_indicators(n: usize, rng: &mut impl rand::Rng) -> Vec<SingleIndicatorType> {
     let all = SingleIndicatorType::all();
     let mut indices: Vec<usize> = (0..all.len()).collect();
     // Fisher-Yates partial shuffle
@@ -2633,7 +6122,12 @@ fn generate_random_dynamic_combo(n: usize, rng: &mut impl rand::Rng) -> Discover
         let j = rng.gen_range(i..indices.len());
         indices.swap(i, j);
     }
-    let indicators: Vec<SingleIndicatorType> = indices[..n].iter().map(|&i| all[i]).collect();
+    indices[..n].iter().map(|&i| all[i]).collect()
+}
+
+/// Generate a random DynamicCombo with n indicators (2-4)
+fn generate_random_dynamic_combo(n: usize, rng: &mut impl rand::Rng) -> DiscoveryStrategyType {
+    let indicators = random_indicators(n, rng);
     let params: Vec<IndicatorParams> = indicators.iter().map(|ind| ind.random_params_for(rng)).collect();
     let modes = DynCombineMode::all();
     let combine_mode = modes[rng.gen_range(0..modes.len())];
@@ -2722,7 +6216,10 @@ fn generate_legacy_exploratory_grid(cycle: u32) -> Vec<DiscoveryStrategyType> {
             }
             // VWAP
             for &period in &[15usize, 25, 40] {
-                grid.push(DiscoveryStrategyType::Vwap { period });
+                grid.push(DiscoveryStrategyType::Vwap {
+                    period,
+                    anchor: VwapAnchor::Rolling,
+                });
             }
             // OBV
             for &sma in &[12usize, 17, 25] {
@@ -2754,15 +6251,13 @@ fn generate_legacy_exploratory_grid(cycle: u32) -> Vec<DiscoveryStrategyType> {
                 for &rob in &[72.5, 77.5] {
                     for &ros in &[22.5, 27.5] {
                         for &bp in &[17usize, 22] {
-                            for &bm in &[2.25] {
-                                grid.push(DiscoveryStrategyType::RsiBollinger {
-                                    rsi_period: rp,
-                                    rsi_ob: rob,
-                                    rsi_os: ros,
-                                    bb_period: bp,
-                                    bb_mult: bm,
-                                });
-                            }
+                            grid.push(DiscoveryStrategyType::RsiBollinger {
+                                rsi_period: rp,
+                                rsi_ob: rob,
+                                rsi_os: ros,
+                                bb_period: bp,
+                                bb_mult: 2.25,
+                            });
                         }
                     }
                 }
@@ -2834,23 +6329,15 @@ fn generate_legacy_exploratory_grid(cycle: u32) -> Vec<DiscoveryStrategyType> {
             }
             // WilliamsR+Stoch
             for &wp in &[10usize, 17] {
-                for &wob in &[-17.5f64] {
-                    for &wos in &[-82.5f64] {
-                        for &sp in &[7usize, 11] {
-                            for &sob in &[82.5] {
-                                for &sos in &[17.5] {
-                                    grid.push(DiscoveryStrategyType::WilliamsRStoch {
-                                        wr_period: wp,
-                                        wr_overbought: wob,
-                                        wr_oversold: wos,
-                                        stoch_period: sp,
-                                        stoch_overbought: sob,
-                                        stoch_oversold: sos,
-                                    });
-                                }
-                            }
-                        }
-                    }
+                for &sp in &[7usize, 11] {
+                    grid.push(DiscoveryStrategyType::WilliamsRStoch {
+                        wr_period: wp,
+                        wr_overbought: -17.5,
+                        wr_oversold: -82.5,
+                        stoch_period: sp,
+                        stoch_overbought: 82.5,
+                        stoch_oversold: 17.5,
+                    });
                 }
             }
             // Gabagool fine
@@ -2938,7 +6425,10 @@ fn generate_legacy_exploratory_grid(cycle: u32) -> Vec<DiscoveryStrategyType> {
             }
             // VWAP extended
             for &period in &[5usize, 7, 60, 80, 100] {
-                grid.push(DiscoveryStrategyType::Vwap { period });
+                grid.push(DiscoveryStrategyType::Vwap {
+                    period,
+                    anchor: VwapAnchor::Rolling,
+                });
             }
             // OBV extended
             for &sma in &[5usize, 7, 35, 40, 50] {
@@ -3024,6 +6514,7 @@ fn generate_legacy_exploratory_grid(cycle: u32) -> Vec<DiscoveryStrategyType> {
                     }),
                     6 => grid.push(DiscoveryStrategyType::Vwap {
                         period: rng.gen_range(5..=100),
+                        anchor: VwapAnchor::Rolling,
                     }),
                     7 => grid.push(DiscoveryStrategyType::Obv {
                         sma_period: rng.gen_range(5..=50),
@@ -3177,8 +6668,8 @@ fn generate_legacy_exploratory_grid(cycle: u32) -> Vec<DiscoveryStrategyType> {
 /// - 60% exploitation: mutations around top performers
 /// - 20% crossover: parameter mixing between good results
 /// - 20% exploration: pure random for diversity
-fn generate_ml_guided_grid(
-    top_results: &[DiscoveryResult],
+pub(crate) fn generate_ml_guided_grid(
+    population: &[Individual],
     cycle: u32,
 ) -> Vec<DiscoveryStrategyType> {
     let total_budget = (300 + cycle as usize * 50).min(1000);
@@ -3190,19 +6681,19 @@ fn generate_ml_guided_grid(
     let mut rng = rand::thread_rng();
 
     // --- 1. Exploitation: mutate top performers ---
-    // Sort by composite_score descending, take top 30
-    let mut sorted = top_results.to_vec();
-    sorted.sort_by(|a, b| b.composite_score.cmp(&a.composite_score));
-    let top_n = sorted.iter().take(30).collect::<Vec<_>>();
+    // Sort by fitness descending, take the population size
+    let mut sorted = population.to_vec();
+    sorted.sort_by_key(|i| std::cmp::Reverse(i.fitness));
+    let top_n = sorted.iter().take(POPULATION_SIZE).collect::<Vec<_>>();
 
     if !top_n.is_empty() {
         let mutations_per = (exploit_budget / top_n.len()).max(1);
-        for result in &top_n {
+        for individual in &top_n {
             for _ in 0..mutations_per {
                 if grid.len() >= exploit_budget {
                     break;
                 }
-                if let Some(mutated) = mutate_strategy(&result.strategy_type, &mut rng) {
+                if let Some(mutated) = mutate_strategy(&individual.genome, &mut rng) {
                     grid.push(mutated);
                 }
             }
@@ -3212,7 +6703,7 @@ fn generate_ml_guided_grid(
     // Pad exploitation budget if not enough top results
     while grid.len() < exploit_budget {
         if let Some(parent) = top_n.first() {
-            if let Some(mutated) = mutate_strategy(&parent.strategy_type, &mut rng) {
+            if let Some(mutated) = mutate_strategy(&parent.genome, &mut rng) {
                 grid.push(mutated);
             } else {
                 break;
@@ -3231,11 +6722,9 @@ fn generate_ml_guided_grid(
             if idx_a == idx_b {
                 continue;
             }
-            if let Some(child) = crossover_strategies(
-                &top_n[idx_a].strategy_type,
-                &top_n[idx_b].strategy_type,
-                &mut rng,
-            ) {
+            if let Some(child) =
+                crossover_strategies(&top_n[idx_a].genome, &top_n[idx_b].genome, &mut rng)
+            {
                 grid.push(child);
             }
         }
@@ -3277,7 +6766,7 @@ fn perturb_decimal(val: Decimal, rng: &mut impl rand::Rng) -> Decimal {
 }
 
 /// Mutate a strategy by perturbing each numeric parameter by ±5-15%
-fn mutate_strategy(
+pub(crate) fn mutate_strategy(
     strategy: &DiscoveryStrategyType,
     rng: &mut impl rand::Rng,
 ) -> Option<DiscoveryStrategyType> {
@@ -3332,9 +6821,10 @@ fn mutate_strategy(
                 multiplier: perturb_f64(*multiplier, rng).clamp(0.5, 3.5),
             }
         }
-        DiscoveryStrategyType::Vwap { period } => {
+        DiscoveryStrategyType::Vwap { period, anchor } => {
             DiscoveryStrategyType::Vwap {
                 period: perturb_usize(*period, rng),
+                anchor: *anchor,
             }
         }
         DiscoveryStrategyType::Obv { sma_period } => {
@@ -3590,6 +7080,77 @@ fn mutate_strategy(
                 spread_multiplier: perturb_decimal(*spread_multiplier, rng).max(dec!(1)),
             }
         }
+        DiscoveryStrategyType::RelativeStrength { lookback, top_k } => {
+            DiscoveryStrategyType::RelativeStrength {
+                lookback: perturb_usize(*lookback, rng),
+                top_k: if rng.gen_bool(0.3) {
+                    (*top_k as i32 + if rng.gen_bool(0.5) { 1 } else { -1 }).max(1) as usize
+                } else {
+                    *top_k
+                },
+            }
+        }
+        DiscoveryStrategyType::OnnxModel {
+            model_path,
+            threshold,
+        } => DiscoveryStrategyType::OnnxModel {
+            model_path: model_path.clone(),
+            threshold: perturb_f64(*threshold, rng).clamp(0.34, 0.95),
+        },
+        DiscoveryStrategyType::PairSpread { leg_a, leg_b, lookback, entry_z, exit_z } => {
+            let entry = perturb_f64(*entry_z, rng).clamp(1.0, 3.5);
+            DiscoveryStrategyType::PairSpread {
+                leg_a: leg_a.clone(),
+                leg_b: leg_b.clone(),
+                lookback: perturb_usize(*lookback, rng).max(10),
+                entry_z: entry,
+                exit_z: perturb_f64(*exit_z, rng).clamp(0.0, entry - 0.25),
+            }
+        }
+        // Ensembles are built from the knowledge base, not explored
+        DiscoveryStrategyType::Ensemble { .. } => return None,
+        DiscoveryStrategyType::Filtered { inner, filter } => DiscoveryStrategyType::Filtered {
+            inner: Box::new(mutate_strategy(inner, rng)?),
+            filter: *filter,
+        },
+        DiscoveryStrategyType::Trailing { inner, stop } => DiscoveryStrategyType::Trailing {
+            inner: Box::new(mutate_strategy(inner, rng)?),
+            stop: match *stop {
+                TrailingStop::Percent { pct } => TrailingStop::Percent {
+                    pct: perturb_f64(pct, rng).clamp(0.25, 20.0),
+                },
+                TrailingStop::Atr { period, multiplier } => TrailingStop::Atr {
+                    period: perturb_usize(period, rng),
+                    multiplier: perturb_f64(multiplier, rng).clamp(0.5, 8.0),
+                },
+            },
+        },
+        DiscoveryStrategyType::HtfFiltered { inner, htf_filter } => {
+            let mut htf_filter = *htf_filter;
+            htf_filter.trend = match htf_filter.trend {
+                HtfTrend::EmaSlope { period } => HtfTrend::EmaSlope {
+                    period: perturb_usize(period, rng).max(2),
+                },
+                HtfTrend::Adx { period, min_adx } => HtfTrend::Adx {
+                    period: perturb_usize(period, rng).max(2),
+                    min_adx: perturb_f64(min_adx, rng).clamp(10.0, 50.0),
+                },
+            };
+            DiscoveryStrategyType::HtfFiltered {
+                inner: Box::new(mutate_strategy(inner, rng)?),
+                htf_filter,
+            }
+        }
+        DiscoveryStrategyType::ZScoreFiltered {
+            inner,
+            zscore_filter,
+        } => DiscoveryStrategyType::ZScoreFiltered {
+            inner: Box::new(mutate_strategy(inner, rng)?),
+            zscore_filter: crate::zscore_filter::ZScoreFilter {
+                window: perturb_usize(zscore_filter.window, rng).max(10),
+                threshold: perturb_f64(zscore_filter.threshold, rng).clamp(0.5, 3.0),
+            },
+        },
     })
 }
 
@@ -3629,8 +7190,9 @@ fn perturb_indicator_params(params: &IndicatorParams, rng: &mut impl rand::Rng)
             sma_period: perturb_usize(*sma_period, rng).max(5),
             multiplier: perturb_f64(*multiplier, rng).clamp(0.5, 3.5),
         },
-        IndicatorParams::Vwap { period } => IndicatorParams::Vwap {
+        IndicatorParams::Vwap { period, anchor } => IndicatorParams::Vwap {
             period: perturb_usize(*period, rng).max(5),
+            anchor: *anchor,
         },
         IndicatorParams::Obv { sma_period } => IndicatorParams::Obv {
             sma_period: perturb_usize(*sma_period, rng).max(5),
@@ -3780,61 +7342,159 @@ fn crossover_strategies(
     }
 }
 
-/// Generate pure random strategy combinations (DynamicCombo only + some Gabagool)
-fn generate_random_strategies(count: usize, rng: &mut impl rand::Rng) -> Vec<DiscoveryStrategyType> {
-    let mut grid = Vec::with_capacity(count);
+/// Generate pure random strategy combinations (DynamicCombo only + some Gabagool).
+///
+/// The strategies are drawn first and their parameters after: every indicator's
+/// params, and the Gabagool ones, come from one Latin hypercube over all of its draws,
+/// so the exploration covers each parameter range evenly instead of clustering.
+fn generate_random_strategies(
+    count: usize,
+    rng: &mut impl rand::Rng,
+) -> Vec<DiscoveryStrategyType> {
+    enum Draw {
+        Combo(Vec<SingleIndicatorType>, DynCombineMode),
+        Ready(DiscoveryStrategyType),
+        Gabagool,
+    }
+    let mut draws = Vec::with_capacity(count);
 
     for _ in 0..count {
         // 85% dynamic combos, 10% web strategies, 5% gabagool
         let roll: f64 = rng.gen();
         if roll < 0.85 {
             let n = rng.gen_range(2..=4usize);
-            grid.push(generate_random_dynamic_combo(n, rng));
+            let modes = DynCombineMode::all();
+            draws.push(Draw::Combo(
+                random_indicators(n, rng),
+                modes[rng.gen_range(0..modes.len())],
+            ));
         } else if roll < 0.95 {
             use crate::web_strategies::{WebStrategyId, WebStrategyParams};
             let ids = WebStrategyId::all_backtestable();
             let id = ids[rng.gen_range(0..ids.len())];
-            grid.push(DiscoveryStrategyType::WebStrategy {
+            draws.push(Draw::Ready(DiscoveryStrategyType::WebStrategy {
                 id,
                 params: WebStrategyParams::random_for(&id, rng),
-            });
+            }));
         } else {
-            let mpc_f = rng.gen_range(0.85..=0.99);
-            let bo_f = rng.gen_range(0.001..=0.05);
-            let sm_f = rng.gen_range(1.0..=8.0);
-            grid.push(DiscoveryStrategyType::Gabagool {
-                max_pair_cost: Decimal::from_str_exact(&format!("{:.3}", mpc_f)).unwrap_or(dec!(0.95)),
-                bid_offset: Decimal::from_str_exact(&format!("{:.4}", bo_f)).unwrap_or(dec!(0.01)),
-                spread_multiplier: Decimal::from_str_exact(&format!("{:.1}", sm_f)).unwrap_or(dec!(3)),
-            });
+            draws.push(Draw::Gabagool);
         }
     }
 
-    grid
+    let mut samples: HashMap<SingleIndicatorType, usize> = HashMap::new();
+    let mut gabagools = 0;
+    for draw in &draws {
+        match draw {
+            Draw::Combo(indicators, _) => {
+                for indicator in indicators {
+                    *samples.entry(*indicator).or_default() += 1;
+                }
+            }
+            Draw::Gabagool => gabagools += 1,
+            Draw::Ready(_) => {}
+        }
+    }
+    let mut points: HashMap<SingleIndicatorType, std::vec::IntoIter<Vec<f64>>> = samples
+        .into_iter()
+        .map(|(indicator, n)| {
+            (
+                indicator,
+                latin_hypercube(n, indicator.param_dims(), rng).into_iter(),
+            )
+        })
+        .collect();
+    let mut gabagool_points = latin_hypercube(gabagools, 3, rng).into_iter();
+
+    draws
+        .into_iter()
+        .filter_map(|draw| {
+            Some(match draw {
+                Draw::Combo(indicators, combine_mode) => {
+                    let params = indicators
+                        .iter()
+                        .map(|indicator| {
+                            Some(indicator.params_at(&points.get_mut(indicator)?.next()?))
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+                    DiscoveryStrategyType::DynamicCombo {
+                        indicators,
+                        params,
+                        combine_mode,
+                    }
+                }
+                Draw::Ready(strategy) => strategy,
+                Draw::Gabagool => {
+                    let point = gabagool_points.next()?;
+                    let mpc_f = 0.85 + point[0] * 0.14;
+                    let bo_f = 0.001 + point[1] * 0.049;
+                    let sm_f = 1.0 + point[2] * 7.0;
+                    DiscoveryStrategyType::Gabagool {
+                        max_pair_cost: Decimal::from_str_exact(&format!("{:.3}", mpc_f))
+                            .unwrap_or(dec!(0.95)),
+                        bid_offset: Decimal::from_str_exact(&format!("{:.4}", bo_f))
+                            .unwrap_or(dec!(0.01)),
+                        spread_multiplier: Decimal::from_str_exact(&format!("{:.1}", sm_f))
+                            .unwrap_or(dec!(3)),
+                    }
+                }
+            })
+        })
+        .collect()
 }
 
 // ============================================================================
 // Continuous Discovery Runner
 // ============================================================================
 
+/// Sizing modes tested by continuous cycles after the first
+pub(crate) const CONTINUOUS_SIZING_MODES: [SizingMode; 3] =
+    [SizingMode::Fixed, SizingMode::Kelly, SizingMode::ConfidenceWeighted];
+/// Periods (days) tested by continuous cycles after the first
+pub(crate) const CONTINUOUS_DAYS: [u32; 5] = [30, 60, 90, 180, 365];
+
+/// Phase label of a continuous cycle
+pub(crate) fn cycle_phase_name(cycle: u32) -> &'static str {
+    match cycle {
+        0 => "Phase 1: Broad Scan",
+        1 => "Phase 2: Fine Interpolation",
+        2 => "Phase 3: Extended Ranges",
+        _ => "ML-Guided Exploration",
+    }
+}
+
 /// Run discovery continuously in an infinite loop, expanding the search space
-/// each cycle. Stops only when `progress.cancelled` is set to true.
+/// each cycle. Stops only when `progress.cancelled` is set to true, which ends
+/// the run with `Ok` once the klines are fetched.
+/// Implementation detail of `DiscoveryEngine::continuous(true)`.
+#[doc(hidden)]
 pub async fn run_continuous_discovery(
     request: DiscoveryRequest,
     binance: Arc<BinanceClient>,
     progress: Arc<DiscoveryProgress>,
-    db_pool: Option<SqlitePool>,
-) {
+    store: Option<Arc<dyn ResultStore>>,
+) -> EngineResult<()> {
     let top_n = request.top_n.unwrap_or(10);
     let initial_capital = dec!(10000);
     let base_position_pct = dec!(10);
-    let fee_config = PolymarketFeeConfig::default();
+    let (fee_config, store) = run_fees(&request, &binance, store).await;
+    let limits = BacktestLimits::from_request(&request);
+    let exclusions = StrategyExclusions::from_request(&request);
+    if !exclusions.is_empty() {
+        info!(excluded = ?exclusions.tags(), "Strategy types excluded from the grids");
+    }
+    if request.adaptive_cycles.unwrap_or(false) && store.is_none() {
+        warn!("Adaptive cycles need a result store, scanning the fixed grids");
+    }
+    let series_cache = IndicatorSeriesCache::new(SERIES_CACHE_CAPACITY)
+        .with_candles(request.candle_transform.unwrap_or_default());
     let run_id = Utc::now().timestamp_millis().to_string();
+    *progress.run_id.write().unwrap() = Some(run_id.clone());
+    progress.set_pacing(request.pacing.unwrap_or_default());
+    progress.set_rank_order(request.rank_order());
+    let mut pacer = Pacer::new();
 
-    // Multi-sizing modes to test across cycles
-    let sizing_modes = [SizingMode::Fixed, SizingMode::Kelly, SizingMode::ConfidenceWeighted];
-    // Multi-days periods to test
-    let days_variants: Vec<u32> = vec![30, 60, 90, 180, 365];
+    let sizing_modes = CONTINUOUS_SIZING_MODES;
+    let days_variants: Vec<u32> = CONTINUOUS_DAYS.to_vec();
 
     progress.is_continuous.store(true, Ordering::Relaxed);
 
@@ -3848,92 +7508,111 @@ pub async fn run_continuous_discovery(
     let end_time = chrono::Utc::now().timestamp_millis();
     let start_time = end_time - (max_days as i64 * 24 * 60 * 60 * 1000);
     let mut last_fetch_time = std::time::Instant::now();
-
-    let mut symbol_klines: Vec<(String, Vec<Kline>)> = Vec::new();
-
-    for symbol in &request.symbols {
-        if progress.cancelled.load(Ordering::Relaxed) {
-            return;
-        }
-        *progress.current_symbol.write().unwrap() = symbol.clone();
-
-        match binance
-            .get_klines_paginated(symbol, "15m", start_time, end_time)
-            .await
-        {
-            Ok(klines) => {
-                info!(symbol = %symbol, bars = klines.len(), "Fetched klines");
-                symbol_klines.push((symbol.clone(), klines));
-            }
-            Err(e) => {
-                warn!(symbol = %symbol, error = %e, "Failed to fetch klines, skipping");
-            }
-        }
-    }
-
-    if symbol_klines.is_empty() {
-        *progress.error_message.write().unwrap() =
-            Some("Failed to fetch klines for any symbol".to_string());
-        *progress.status.write().unwrap() = DiscoveryStatus::Error;
-        return;
-    }
+    let file_source = DataSource::from_option(request.data_source.as_deref())
+        .is_ok_and(|source| source.is_file());
+
+    let mut symbol_klines = fetch_run_klines(
+        &request,
+        &binance,
+        (start_time, end_time),
+        &progress,
+        store.as_deref(),
+        &run_id,
+    )
+    .await
+    .map_err(|e| progress.fail(e))?;
+    let symbol_filters = fetch_run_symbol_filters(&request, &binance, &symbol_klines).await;
+    let symbol_limits =
+        |symbol: &str| limits.with_symbol_filters(symbol_filters.get(symbol).copied());
 
     // ── Main loop ──────────────────────────────────────────────────────
     let mut all_results: Vec<DiscoveryResult> = Vec::new();
+    // Symbols whose top KB results were loaded in place of skipped grid units
+    let mut seeded_symbols: Vec<String> = Vec::new();
+    // Parents of the ML-guided cycles, one island per symbol, resumed from the ones
+    // saved by earlier runs
+    let symbols: Vec<String> = symbol_klines.iter().map(|(s, _)| s.clone()).collect();
+    let mut islands = match store.as_deref() {
+        Some(store) => IslandModel::load(store, &symbols).await,
+        None => IslandModel::new(symbols),
+    };
     let mut cycle = 0u32;
 
     loop {
+        progress.wait_while_paused().await;
         if progress.cancelled.load(Ordering::Relaxed) {
             info!("Continuous discovery cancelled by user");
             break;
         }
 
-        // Re-fetch klines every 6 hours
-        if last_fetch_time.elapsed() > std::time::Duration::from_secs(6 * 3600) {
-            info!("Re-fetching klines (6h refresh)");
+        // Incremental kline refresh every 6 hours: only fetch bars newer than the cache
+        // (file sources are static)
+        if !file_source && last_fetch_time.elapsed() > std::time::Duration::from_secs(6 * 3600) {
+            info!("Refreshing klines incrementally (6h refresh)");
             *progress.status.write().unwrap() = DiscoveryStatus::FetchingData;
             *progress.phase.write().unwrap() = "Refreshing market data...".to_string();
 
             let new_end = chrono::Utc::now().timestamp_millis();
-            let new_start = new_end - (max_days as i64 * 24 * 60 * 60 * 1000);
-            let mut new_klines = Vec::new();
+            let window_start = new_end - (max_days as i64 * 24 * 60 * 60 * 1000);
 
             for symbol in &request.symbols {
+                progress.wait_while_paused().await;
                 if progress.cancelled.load(Ordering::Relaxed) {
                     break;
                 }
+                // Restart from the last cached bar so a candle that was still open
+                // at the previous fetch gets replaced by its final values; a symbol
+                // with nothing cached (its fetch failed so far) gets the full window
+                let fetch_start = progress
+                    .cached_klines(symbol)
+                    .and_then(|klines| klines.last().map(|last| last.open_time))
+                    .unwrap_or(window_start);
                 match binance
-                    .get_klines_paginated(symbol, "15m", new_start, new_end)
+                    .get_klines_paginated(symbol, KLINE_INTERVAL, fetch_start, new_end)
                     .await
                 {
-                    Ok(klines) => {
-                        info!(symbol = %symbol, bars = klines.len(), "Refreshed klines");
-                        new_klines.push((symbol.clone(), klines));
+                    Ok(fresh) => {
+                        let (added, klines) = progress.merge_klines(symbol, fresh, max_days);
+                        if klines.is_empty() {
+                            continue;
+                        }
+                        info!(symbol = %symbol, added, bars = klines.len(), "Refreshed klines");
+                        let report = check_klines(
+                            symbol,
+                            &klines,
+                            KLINE_INTERVAL_MS,
+                            0,
+                            &DataQualityConfig::default(),
+                        );
+                        record_data_quality(&progress, store.as_deref(), &run_id, report).await;
+                        match symbol_klines.iter_mut().find(|(s, _)| s == symbol) {
+                            Some((_, cached)) => *cached = klines.to_vec(),
+                            None => {
+                                islands.add_island(symbol);
+                                symbol_klines.push((symbol.clone(), klines.to_vec()));
+                            }
+                        }
                     }
                     Err(e) => {
-                        warn!(symbol = %symbol, error = %e, "Failed to refresh klines");
+                        warn!(symbol = %symbol, error = %e, "Failed to refresh klines, keeping cached bars");
                     }
                 }
             }
 
-            if !new_klines.is_empty() {
-                symbol_klines = new_klines;
-            }
             last_fetch_time = std::time::Instant::now();
         }
 
         // Set cycle info
+        let cycle_started = Instant::now();
         progress.current_cycle.store(cycle, Ordering::Relaxed);
         progress.total_new_this_cycle.store(0, Ordering::Relaxed);
 
-        let phase_name = if cycle == 0 {
-            "Phase 1: Broad Scan"
-        } else if cycle == 1 {
-            "Phase 2: Fine Interpolation"
-        } else if cycle == 2 {
-            "Phase 3: Extended Ranges"
+        let adaptive =
+            request.adaptive_cycles.unwrap_or(false) && cycle < ADAPTIVE_CYCLES && store.is_some();
+        let phase_name = if adaptive {
+            ADAPTIVE_PHASE_NAME
         } else {
-            "ML-Guided Exploration"
+            cycle_phase_name(cycle)
         };
 
         let status = if cycle < 2 {
@@ -3949,11 +7628,53 @@ pub async fn run_continuous_discovery(
         *progress.status.write().unwrap() = status;
         *progress.phase.write().unwrap() = format!("Cycle {} — {}", cycle, phase_name);
 
-        let grid = if cycle >= 3 {
-            generate_ml_guided_grid(&all_results, cycle)
-        } else {
-            generate_exploratory_grid(cycle)
+        // ML-guided cycles breed one grid per symbol, from the symbol's island
+        let mut island_grids: HashMap<String, Vec<DiscoveryStrategyType>> = HashMap::new();
+        let mut grid = match store.as_deref() {
+            Some(store) if adaptive => adaptive_cycle_grid(store, &exclusions).await,
+            _ if cycle >= 3 => {
+                islands.evolve(&all_results);
+                if let Some(store) = store.as_deref() {
+                    islands.save(store).await;
+                }
+                *progress.islands.write().unwrap() = islands.stats();
+                island_grids = islands.breed(cycle);
+                Vec::new()
+            }
+            _ => generate_exploratory_grid(cycle),
         };
+        if cycle < 2 {
+            grid.extend(generate_onnx_grid(&request.onnx_models));
+        }
+
+        // Cross-sectional strategies (incl. ML-guided mutations of them) run on the basket
+        let basket = KlineMatrix::align(&symbol_klines);
+        let (mut cross_grid, mut grid): (Vec<_>, Vec<_>) =
+            grid.into_iter().partition(|s| s.is_cross_sectional());
+        if cycle < 2 {
+            if let Some(matrix) = &basket {
+                cross_grid.extend(generate_cross_sectional_grid(matrix));
+            }
+        }
+        for island_grid in island_grids.values_mut() {
+            let (cross, single): (Vec<_>, Vec<_>) = std::mem::take(island_grid)
+                .into_iter()
+                .partition(|s| s.is_cross_sectional());
+            cross_grid.extend(cross);
+            *island_grid = single;
+            retain_valid(island_grid);
+            exclusions.retain(island_grid);
+        }
+        if basket.is_none() {
+            cross_grid.clear();
+        }
+        retain_valid(&mut grid);
+        exclusions.retain(&mut grid);
+        retain_valid(&mut cross_grid);
+        exclusions.retain(&mut cross_grid);
+        let grid_size =
+            grid.len() + island_grids.values().map(Vec::len).sum::<usize>() + cross_grid.len();
+        let symbol_grid = |symbol: &str| island_grids.get(symbol).unwrap_or(&grid);
 
         // For cycle 0, also do Phase 2 refinement after the grid
         let do_refinement = cycle == 0;
@@ -3973,109 +7694,235 @@ pub async fn run_continuous_discovery(
             days_variants.clone()
         };
 
-        let total_combos = grid.len() as u32
-            * symbol_klines.len() as u32
+        let total_combos = symbol_klines
+            .iter()
+            .map(|(symbol, _)| symbol_grid(symbol).len() as u32)
+            .sum::<u32>()
             * days_list.len() as u32
-            * sizing_list.len() as u32;
+            * sizing_list.len() as u32
+            + cross_grid.len() as u32 * days_list.len() as u32;
 
         progress
             .total_combinations
             .store(total_combos, Ordering::Relaxed);
         progress.completed.store(0, Ordering::Relaxed);
         progress.skipped.store(0, Ordering::Relaxed);
+        progress.clear_breakdown();
 
         info!(
             cycle = cycle,
-            grid_size = grid.len(),
+            grid_size = grid_size,
             total_combos = total_combos,
             "Cycle starting"
         );
 
         let mut cycle_idx = 0u32;
 
-        for (symbol, full_klines) in &symbol_klines {
-            for &days in &days_list {
-                // Slice klines to the requested days period
-                let klines = slice_klines_to_days(full_klines, days);
-
+        // Klines sliced once per (symbol, days) period
+        let sliced: Vec<Vec<Vec<Kline>>> = symbol_klines
+            .iter()
+            .map(|(_, full_klines)| {
+                days_list
+                    .iter()
+                    .map(|&days| slice_klines_to_days(full_klines, days))
+                    .collect()
+            })
+            .collect();
+        // Cycles 0-1 are deterministic: units fully tested by a previous run are skipped
+        let tracks_grids = store.as_ref().is_some_and(|s| s.tracks_grids());
+        let grid_id = (cycle < 2 && tracks_grids).then(|| compute_grid_id(&grid));
+        let mut pending_units = Vec::new();
+        let mut skipped_units = 0u32;
+        let mut work = Vec::with_capacity(total_combos as usize);
+        for (sym_idx, (symbol, _)) in symbol_klines.iter().enumerate() {
+            for (days_idx, &days) in days_list.iter().enumerate() {
                 for sizing_mode in &sizing_list {
-                    for strategy_type in &grid {
-                        if progress.cancelled.load(Ordering::Relaxed) {
-                            info!("Continuous discovery cancelled by user");
-                            *progress.status.write().unwrap() = DiscoveryStatus::Complete;
-                            update_best_so_far(&all_results, initial_capital, top_n, &progress);
-                            *progress.final_results.write().unwrap() =
-                                progress.best_so_far.read().unwrap().clone();
-                            return;
-                        }
-
-                        if cycle_idx % 50 == 0 {
-                            *progress.current_strategy.write().unwrap() =
-                                strategy_type.name().to_string();
-                            *progress.current_symbol.write().unwrap() = symbol.clone();
-                        }
-
-                        // Check DB cache
-                        let hash =
-                            compute_params_hash(strategy_type, symbol, days, *sizing_mode);
-                        if let Some(pool) = &db_pool {
-                            let repo = DiscoveryRepository::new(pool);
-                            if let Ok(Some(existing)) = repo.get_by_hash(&hash).await {
-                                all_results.push(record_to_result(existing));
-                                cycle_idx += 1;
-                                progress.completed.store(cycle_idx, Ordering::Relaxed);
-                                progress.skipped.fetch_add(1, Ordering::Relaxed);
-                                progress
-                                    .total_tested_all_cycles
-                                    .fetch_add(1, Ordering::Relaxed);
-                                if cycle_idx % 50 == 0 {
-                                    update_best_so_far(
-                                        &all_results,
-                                        initial_capital,
-                                        top_n,
-                                        &progress,
-                                    );
-                                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
-                                }
-                                continue;
+                    if let (Some(grid_id), Some(store)) = (&grid_id, &store) {
+                        let unit_hash = compute_grid_hash(grid_id, symbol, days, *sizing_mode);
+                        if store.is_grid_tested(&unit_hash).await.unwrap_or(false) {
+                            skipped_units += 1;
+                            for strategy_type in &grid {
+                                progress.plan_work(symbol, strategy_type, 1);
+                                progress.complete_work(symbol, strategy_type, 1);
+                            }
+                            if !seeded_symbols.contains(symbol) {
+                                seeded_symbols.push(symbol.clone());
+                                let top = store
+                                    .top_results(GRID_SEED_RESULTS, symbol)
+                                    .await
+                                    .unwrap_or_default();
+                                all_results.extend(top.into_iter().filter_map(record_to_result));
                             }
+                            continue;
                         }
-
-                        let result = run_single_backtest(
-                            strategy_type,
-                            &klines,
+                        pending_units.push((unit_hash, symbol, days, *sizing_mode));
+                    }
+                    for strategy_type in symbol_grid(symbol) {
+                        progress.plan_work(symbol, strategy_type, 1);
+                        work.push((
                             symbol,
-                            initial_capital,
-                            base_position_pct,
-                            *sizing_mode,
-                            &fee_config,
-                        );
+                            &sliced[sym_idx][days_idx],
+                            days,
+                            sizing_mode,
+                            strategy_type,
+                        ));
+                    }
+                }
+            }
+        }
+        if skipped_units > 0 {
+            let skipped_combos = skipped_units * grid.len() as u32;
+            info!(
+                cycle = cycle,
+                units = skipped_units,
+                combos = skipped_combos,
+                "Skipping grid units already fully tested"
+            );
+            cycle_idx += skipped_combos;
+            progress.completed.store(cycle_idx, Ordering::Relaxed);
+            progress
+                .skipped
+                .fetch_add(skipped_combos, Ordering::Relaxed);
+            progress
+                .total_tested_all_cycles
+                .fetch_add(skipped_combos, Ordering::Relaxed);
+        }
 
-                        // Save to DB
-                        if let Some(pool) = &db_pool {
-                            let phase_label = format!("cycle{}", cycle);
-                            let record =
-                                result_to_record(&result, &hash, &run_id, &phase_label, days);
-                            let repo = DiscoveryRepository::new(pool);
-                            let _ = repo.save(&record).await;
-                        }
+        let priors = load_strategy_priors(store.as_deref()).await;
+        let work = prioritize_work(work, &priors, |&(symbol, _, _, _, strategy_type)| {
+            (strategy_type, symbol.as_str())
+        });
+        let mut unsaved = false;
 
-                        all_results.push(result);
+        for (symbol, klines, days, sizing_mode, strategy_type) in work {
+            progress.wait_while_paused().await;
+            if progress.cancelled.load(Ordering::Relaxed) {
+                info!("Continuous discovery cancelled by user");
+                *progress.status.write().unwrap() = DiscoveryStatus::Complete;
+                update_best_so_far(&all_results, initial_capital, top_n, &progress);
+                *progress.final_results.write().unwrap() =
+                    progress.best_so_far.read().unwrap().clone();
+                return Ok(());
+            }
 
-                        cycle_idx += 1;
-                        progress.completed.store(cycle_idx, Ordering::Relaxed);
-                        progress.total_new_this_cycle.fetch_add(1, Ordering::Relaxed);
-                        progress
-                            .total_tested_all_cycles
-                            .fetch_add(1, Ordering::Relaxed);
+            if cycle_idx.is_multiple_of(50) {
+                *progress.current_strategy.write().unwrap() = strategy_type.name().to_string();
+                *progress.current_symbol.write().unwrap() = symbol.clone();
+            }
 
-                        if cycle_idx % 50 == 0 {
-                            update_best_so_far(&all_results, initial_capital, top_n, &progress);
-                            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
-                        }
+            // Check DB cache
+            let hash = compute_params_hash(strategy_type, symbol, days, *sizing_mode);
+            if let Some(store) = &store {
+                if let Some(cached) =
+                    store.get_by_hash(&hash).await.ok().flatten().and_then(record_to_result)
+                {
+                    progress.emit_result(&cached);
+                    all_results.push(cached);
+                    cycle_idx += 1;
+                    progress.completed.store(cycle_idx, Ordering::Relaxed);
+                    progress.skipped.fetch_add(1, Ordering::Relaxed);
+                    progress.complete_work(symbol, strategy_type, 1);
+                    progress
+                        .total_tested_all_cycles
+                        .fetch_add(1, Ordering::Relaxed);
+                    if cycle_idx.is_multiple_of(50) {
+                        update_best_so_far(&all_results, initial_capital, top_n, &progress);
                     }
+                    pacer.cached().await;
+                    continue;
                 }
             }
+
+            let phase_label = format!("cycle{}", cycle);
+            let Some(result) = progress.isolate(symbol, strategy_type, &phase_label, || {
+                run_single_backtest(
+                    strategy_type,
+                    klines,
+                    symbol,
+                    initial_capital,
+                    base_position_pct,
+                    *sizing_mode,
+                    &fee_config,
+                    &symbol_limits(symbol),
+                    &series_cache,
+                )
+            }) else {
+                cycle_idx += 1;
+                progress.completed.store(cycle_idx, Ordering::Relaxed);
+                progress.complete_work(symbol, strategy_type, 1);
+                progress
+                    .total_tested_all_cycles
+                    .fetch_add(1, Ordering::Relaxed);
+                continue;
+            };
+
+            // Save to DB
+            if let Some(store) = &store {
+                let record = result_to_record(&result, &hash, &run_id, &phase_label, days);
+                unsaved |= store.save(&record).await.is_err();
+            }
+
+            progress.emit_result(&result);
+            all_results.push(result);
+
+            cycle_idx += 1;
+            progress.completed.store(cycle_idx, Ordering::Relaxed);
+            progress.complete_work(symbol, strategy_type, 1);
+            progress.total_new_this_cycle.fetch_add(1, Ordering::Relaxed);
+            progress
+                .total_tested_all_cycles
+                .fetch_add(1, Ordering::Relaxed);
+
+            if cycle_idx.is_multiple_of(50) {
+                update_best_so_far(&all_results, initial_capital, top_n, &progress);
+            }
+            pacer.backtested(progress.pacing()).await;
+        }
+
+        // Every combination of the pending units is now in the KB
+        if let (Some(grid_id), Some(store), false) = (&grid_id, &store, unsaved) {
+            for (unit_hash, symbol, days, sizing_mode) in &pending_units {
+                let _ = store
+                    .mark_grid_tested(
+                        unit_hash,
+                        grid_id,
+                        symbol,
+                        *days as i64,
+                        &format!("{:?}", sizing_mode),
+                        grid.len() as i64,
+                    )
+                    .await;
+            }
+        }
+
+        if let Some(matrix) = &basket {
+            let phase_label = format!("cycle{}", cycle);
+            for &days in &days_list {
+                let (results, cached) = run_cross_sectional_batch(
+                    &cross_grid,
+                    &matrix.last_days(days),
+                    days,
+                    initial_capital,
+                    &fee_config,
+                    store.as_deref(),
+                    &run_id,
+                    &phase_label,
+                    &progress,
+                )
+                .await;
+                let tested = results.len() as u32;
+                cycle_idx += tested;
+                progress.completed.store(cycle_idx, Ordering::Relaxed);
+                progress.skipped.fetch_add(cached, Ordering::Relaxed);
+                progress
+                    .total_new_this_cycle
+                    .fetch_add(tested - cached, Ordering::Relaxed);
+                progress
+                    .total_tested_all_cycles
+                    .fetch_add(tested, Ordering::Relaxed);
+                all_results.extend(results);
+            }
+            update_best_so_far(&all_results, initial_capital, top_n, &progress);
         }
 
         // Phase 2 refinement for cycle 0
@@ -4083,19 +7930,46 @@ pub async fn run_continuous_discovery(
             *progress.status.write().unwrap() = DiscoveryStatus::Phase2Refinement;
             *progress.phase.write().unwrap() = "Cycle 0 — Phase 2: Refinement".to_string();
 
-            let mut scored = all_results.clone();
-            scored.sort_by(|a, b| {
-                let sa = score_result(a, initial_capital);
-                let sb = score_result(b, initial_capital);
-                sb.cmp(&sa)
-            });
-            let top_for_refinement: Vec<DiscoveryResult> = scored.into_iter().take(20).collect();
-
-            for top_result in &top_for_refinement {
+            let refinement = RefinementDepth::from_request(&request);
+            let mut rounds = RefinementRounds::new(refinement, initial_capital);
+            while let Some(top_result) = rounds.next_parent(&all_results) {
+                let top_result = &top_result;
+                progress.wait_while_paused().await;
                 if progress.cancelled.load(Ordering::Relaxed) {
                     break;
                 }
-                let refinement_grid = generate_refinement_grid(&top_result.strategy_type);
+                let mut refinement_grid = generate_scaled_refinement_grid(
+                    &top_result.strategy_type,
+                    refinement.delta_scale,
+                );
+
+                if top_result.strategy_type.is_cross_sectional() {
+                    if let Some(matrix) = &basket {
+                        let (results, cached) = run_cross_sectional_batch(
+                            &refinement_grid,
+                            &matrix.last_days(request.days),
+                            request.days,
+                            initial_capital,
+                            &fee_config,
+                            store.as_deref(),
+                            &run_id,
+                            "phase2",
+                            &progress,
+                        )
+                        .await;
+                        let tested = results.len() as u32;
+                        progress.skipped.fetch_add(cached, Ordering::Relaxed);
+                        progress
+                            .total_new_this_cycle
+                            .fetch_add(tested - cached, Ordering::Relaxed);
+                        progress
+                            .total_tested_all_cycles
+                            .fetch_add(tested, Ordering::Relaxed);
+                        all_results.extend(results);
+                    }
+                    continue;
+                }
+
                 let klines_opt = symbol_klines
                     .iter()
                     .find(|(s, _)| *s == top_result.symbol)
@@ -4106,9 +7980,18 @@ pub async fn run_continuous_discovery(
                 };
                 let klines = slice_klines_to_days(full_kl, request.days);
                 let sizing_mode = request.sizing_mode.unwrap_or_default();
+                refinement_grid.extend(filter_refinement_grid(&top_result.strategy_type, &klines));
+                refinement_grid.extend(stop_refinement_grid(&top_result.strategy_type));
+                refinement_grid.extend(htf_refinement_grid(&top_result.strategy_type));
+                refinement_grid.extend(zscore_refinement_grid(&top_result.strategy_type));
+                retain_valid(&mut refinement_grid);
+                exclusions.retain(&mut refinement_grid);
 
                 *progress.current_strategy.write().unwrap() =
                     format!("{} (refine)", top_result.strategy_name);
+                for variant in &refinement_grid {
+                    progress.plan_work(&top_result.symbol, variant, 1);
+                }
 
                 for variant in &refinement_grid {
                     let hash = compute_params_hash(
@@ -4117,11 +8000,14 @@ pub async fn run_continuous_discovery(
                         request.days,
                         sizing_mode,
                     );
-                    if let Some(pool) = &db_pool {
-                        let repo = DiscoveryRepository::new(pool);
-                        if let Ok(Some(existing)) = repo.get_by_hash(&hash).await {
-                            all_results.push(record_to_result(existing));
+                    if let Some(store) = &store {
+                        if let Some(cached) =
+                            store.get_by_hash(&hash).await.ok().flatten().and_then(record_to_result)
+                        {
+                            progress.emit_result(&cached);
+                            all_results.push(cached);
                             progress.skipped.fetch_add(1, Ordering::Relaxed);
+                            progress.complete_work(&top_result.symbol, variant, 1);
                             progress
                                 .total_tested_all_cycles
                                 .fetch_add(1, Ordering::Relaxed);
@@ -4129,17 +8015,29 @@ pub async fn run_continuous_discovery(
                         }
                     }
 
-                    let result = run_single_backtest(
-                        variant,
-                        &klines,
-                        &top_result.symbol,
-                        initial_capital,
-                        base_position_pct,
-                        sizing_mode,
-                        &fee_config,
-                    );
+                    let run = || {
+                        run_single_backtest(
+                            variant,
+                            &klines,
+                            &top_result.symbol,
+                            initial_capital,
+                            base_position_pct,
+                            sizing_mode,
+                            &fee_config,
+                            &symbol_limits(&top_result.symbol),
+                            &series_cache,
+                        )
+                    };
+                    let Some(result) = progress.isolate(&top_result.symbol, variant, "phase2", run)
+                    else {
+                        progress.complete_work(&top_result.symbol, variant, 1);
+                        progress
+                            .total_tested_all_cycles
+                            .fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    };
 
-                    if let Some(pool) = &db_pool {
+                    if let Some(store) = &store {
                         let record = result_to_record(
                             &result,
                             &hash,
@@ -4147,37 +8045,80 @@ pub async fn run_continuous_discovery(
                             "phase2",
                             request.days,
                         );
-                        let repo = DiscoveryRepository::new(pool);
-                        let _ = repo.save(&record).await;
+                        let _ = store.save(&record).await;
                     }
 
+                    progress.emit_result(&result);
                     all_results.push(result);
+                    progress.complete_work(&top_result.symbol, variant, 1);
                     progress.total_new_this_cycle.fetch_add(1, Ordering::Relaxed);
                     progress
                         .total_tested_all_cycles
                         .fetch_add(1, Ordering::Relaxed);
+                    pacer.backtested(progress.pacing()).await;
                 }
             }
         }
 
         // Update best at end of cycle
         update_best_so_far(&all_results, initial_capital, top_n, &progress);
+        apply_retention(store.as_deref()).await;
 
         let new_count = progress.total_new_this_cycle.load(Ordering::Relaxed);
         let total_all = progress.total_tested_all_cycles.load(Ordering::Relaxed);
+        let (series_hits, series_misses, series_cached) = series_cache.stats();
+        let best_score = progress
+            .best_so_far
+            .read()
+            .unwrap()
+            .first()
+            .map(|r| r.composite_score);
         info!(
             cycle = cycle,
             new_this_cycle = new_count,
             total_all_cycles = total_all,
-            best_score = %progress
-                .best_so_far
-                .read()
-                .unwrap()
-                .first()
-                .map(|r| r.composite_score)
-                .unwrap_or_default(),
+            series_hits,
+            series_misses,
+            series_cached,
+            best_score = %best_score.unwrap_or_default(),
             "Cycle complete"
         );
+        if let Some(store) = store.as_deref() {
+            let elapsed = cycle_started.elapsed();
+            let cycle_grid = CycleGrid {
+                shared: grid.clone(),
+                islands: island_grids.clone().into_iter().collect(),
+                cross_sectional: cross_grid.clone(),
+            };
+            let grid_snapshot_id = match cycle_grid.to_record() {
+                Ok(snapshot) => match store.save_grid_snapshot(&snapshot).await {
+                    Ok(()) => Some(snapshot.snapshot_id),
+                    Err(e) => {
+                        warn!(cycle, error = %e, "Failed to save grid snapshot");
+                        None
+                    }
+                },
+                Err(e) => {
+                    warn!(cycle, error = %e, "Failed to encode grid snapshot");
+                    None
+                }
+            };
+            let summary = CycleSummaryRecord {
+                run_id: run_id.clone(),
+                cycle: cycle as i64,
+                grid_size: grid_size as i64,
+                combinations: total_combos as i64,
+                new_results: new_count as i64,
+                best_score: best_score.and_then(|s| s.to_f64()),
+                duration_ms: elapsed.as_millis() as i64,
+                throughput_per_sec: new_count as f64 / elapsed.as_secs_f64().max(1e-3),
+                grid_snapshot_id,
+                created_at: None,
+            };
+            if let Err(e) = store.save_cycle_summary(&summary).await {
+                warn!(cycle, error = %e, "Failed to save cycle summary");
+            }
+        }
 
         cycle += 1;
 
@@ -4195,25 +8136,221 @@ pub async fn run_continuous_discovery(
         total_tested = progress.total_tested_all_cycles.load(Ordering::Relaxed),
         "Continuous discovery finished"
     );
+    Ok(())
+}
+
+/// Slice klines to only include the last N days of data
+fn slice_klines_to_days(klines: &[Kline], days: u32) -> Vec<Kline> {
+    if klines.is_empty() {
+        return Vec::new();
+    }
+    let last_time = klines.last().unwrap().close_time;
+    let cutoff = last_time - (days as i64 * 24 * 60 * 60 * 1000);
+    klines
+        .iter()
+        .filter(|k| k.open_time >= cutoff)
+        .cloned()
+        .collect()
+}
+
+/// Interval of the klines discovery backtests on
+pub const KLINE_INTERVAL: &str = "15m";
+pub const KLINE_INTERVAL_MS: i64 = 15 * 60 * 1000;
+
+/// Phase 0: fetch and quality-check the klines of every requested symbol over
+/// `(start, end)`. Symbols that fail to fetch or have no bars in the window are
+/// skipped; an error if no symbol is left.
+pub(crate) async fn fetch_run_klines(
+    request: &DiscoveryRequest,
+    binance: &BinanceClient,
+    (start_time, end_time): (i64, i64),
+    progress: &DiscoveryProgress,
+    store: Option<&dyn ResultStore>,
+    run_id: &str,
+) -> EngineResult<Vec<(String, Vec<Kline>)>> {
+    let config = DataQualityConfig {
+        repair_gaps: request.repair_gaps.unwrap_or(false),
+        ..DataQualityConfig::default()
+    };
+    let source = DataSource::from_option(request.data_source.as_deref())
+        .map_err(EngineError::InvalidParams)?;
+    let mut symbol_klines = Vec::new();
+
+    for symbol in &request.symbols {
+        progress.wait_while_paused().await;
+        if progress.cancelled.load(Ordering::Relaxed) {
+            return Err(EngineError::Cancelled);
+        }
+        *progress.current_symbol.write().unwrap() = symbol.clone();
+
+        if let DataSource::File(path) = &source {
+            match load_file_klines(path, symbol, end_time - start_time, &config) {
+                Ok((klines, report)) => {
+                    info!(symbol = %symbol, bars = klines.len(), path = %path.display(), "Loaded klines");
+                    record_data_quality(progress, store, run_id, report).await;
+                    symbol_klines.push((symbol.clone(), klines));
+                }
+                Err(e) => {
+                    warn!(symbol = %symbol, error = %format!("{:#}", e), "Failed to load klines, skipping");
+                }
+            }
+            continue;
+        }
+
+        match fetch_checked_klines(
+            binance,
+            symbol,
+            KLINE_INTERVAL,
+            start_time,
+            end_time,
+            &config,
+        )
+        .await
+        {
+            Ok((klines, report)) => {
+                info!(
+                    symbol = %symbol,
+                    bars = klines.len(),
+                    quality = format!("{:.1}", report.score),
+                    "Fetched klines"
+                );
+                record_data_quality(progress, store, run_id, report).await;
+                progress.cache_klines(symbol, &klines);
+                symbol_klines.push((symbol.clone(), klines));
+            }
+            Err(e) => {
+                warn!(symbol = %symbol, error = %e, "Failed to fetch klines, skipping");
+            }
+        }
+    }
+
+    let (symbol_klines, empty): (Vec<_>, Vec<_>) = symbol_klines
+        .into_iter()
+        .partition(|(_, klines)| !klines.is_empty());
+    let empty: Vec<String> = empty.into_iter().map(|(symbol, _)| symbol).collect();
+    if !empty.is_empty() {
+        warn!(symbols = ?empty, "No klines in the window, skipping");
+    }
+    if symbol_klines.is_empty() {
+        return Err(if empty.is_empty() {
+            EngineError::DataFetch("Failed to fetch klines for any symbol".to_string())
+        } else {
+            EngineError::InsufficientData(format!(
+                "No klines in the requested window for {}",
+                empty.join(", ")
+            ))
+        });
+    }
+    Ok(symbol_klines)
+}
+
+/// Tick / lot filters of the run's symbols from the exchange. File klines and
+/// symbols the exchange does not know are backtested unrounded.
+pub(crate) async fn fetch_run_symbol_filters(
+    request: &DiscoveryRequest,
+    binance: &BinanceClient,
+    symbol_klines: &[(String, Vec<Kline>)],
+) -> HashMap<String, SymbolFilters> {
+    let file_source = DataSource::from_option(request.data_source.as_deref())
+        .is_ok_and(|source| source.is_file());
+    if file_source || symbol_klines.is_empty() {
+        return HashMap::new();
+    }
+    let symbols: Vec<String> = symbol_klines.iter().map(|(s, _)| s.clone()).collect();
+    match binance.symbol_metadata(&symbols).await {
+        Ok(metadata) => metadata
+            .iter()
+            .map(|m| (m.symbol.clone(), m.filters()))
+            .collect(),
+        Err(e) => {
+            warn!(error = %e, "Failed to fetch symbol filters, fills are not rounded");
+            HashMap::new()
+        }
+    }
+}
+
+/// Last `span_ms` of a symbol's file klines (ending at the file's last bar), checked
+/// at the file's own bar spacing
+fn load_file_klines(
+    path: &std::path::Path,
+    symbol: &str,
+    span_ms: i64,
+    config: &DataQualityConfig,
+) -> anyhow::Result<(Vec<Kline>, DataQualityReport)> {
+    let mut klines = load_klines_file(path, symbol)?;
+    let duplicates = dedupe_klines(&mut klines);
+    if let Some(last) = klines.last() {
+        let window_start = last.close_time - span_ms;
+        klines.retain(|k| k.open_time >= window_start);
+    }
+    let interval_ms = infer_interval_ms(&klines);
+    let report = check_klines(symbol, &klines, interval_ms, duplicates, config);
+    Ok((klines, report))
 }
 
-/// Slice klines to only include the last N days of data
-fn slice_klines_to_days(klines: &[Kline], days: u32) -> Vec<Kline> {
-    if klines.is_empty() {
-        return Vec::new();
+/// Publish a symbol's kline quality on the progress tracker and store it with the run
+async fn record_data_quality(
+    progress: &DiscoveryProgress,
+    store: Option<&dyn ResultStore>,
+    run_id: &str,
+    report: DataQualityReport,
+) {
+    report.warn_if_degraded();
+    if let Some(store) = store {
+        if let Err(e) = store.save_data_quality(&report.to_record(run_id)).await {
+            warn!(symbol = %report.symbol, error = %e, "Failed to save kline quality");
+        }
+    }
+    let mut reports = progress.data_quality.write().unwrap();
+    match reports.iter_mut().find(|r| r.symbol == report.symbol) {
+        Some(existing) => *existing = report,
+        None => reports.push(report),
     }
-    let last_time = klines.last().unwrap().close_time;
-    let cutoff = last_time - (days as i64 * 24 * 60 * 60 * 1000);
-    klines
-        .iter()
-        .filter(|k| k.open_time >= cutoff)
-        .cloned()
-        .collect()
+}
+
+/// Merge freshly fetched klines into a cached series (sorted by open_time).
+///
+/// Bars whose open_time matches a cached bar replace it (the last cached candle may
+/// have been fetched while still open), newer bars are appended, and bars older than
+/// `max_days` before the newest close are dropped. Returns the number of bars appended.
+fn merge_new_klines(cached: &mut Vec<Kline>, fresh: Vec<Kline>, max_days: u32) -> usize {
+    let mut added = 0;
+    for kline in fresh {
+        match cached.last() {
+            Some(last) if kline.open_time < last.open_time => {
+                // Older than the cache tail: replace in place if present, otherwise ignore
+                if let Ok(idx) = cached.binary_search_by_key(&kline.open_time, |k| k.open_time) {
+                    cached[idx] = kline;
+                }
+            }
+            Some(last) if kline.open_time == last.open_time => {
+                let idx = cached.len() - 1;
+                cached[idx] = kline;
+            }
+            _ => {
+                cached.push(kline);
+                added += 1;
+            }
+        }
+    }
+
+    if let Some(last) = cached.last() {
+        let cutoff = last.close_time - (max_days as i64 * 24 * 60 * 60 * 1000);
+        let stale = cached.partition_point(|k| k.open_time < cutoff);
+        if stale > 0 {
+            cached.drain(..stale);
+        }
+    }
+
+    added
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::indicators::build_signal_generator;
+    use crate::population::Population;
+    use crate::store::{MemoryResultStore, NoopResultStore};
     use rust_decimal_macros::dec;
 
     fn make_klines(prices: &[f64]) -> Vec<Kline> {
@@ -4312,6 +8449,7 @@ mod tests {
             dec!(10),
             SizingMode::Fixed,
             &fee_config,
+            &BacktestLimits::unlimited(),
         );
 
         assert!(
@@ -4320,6 +8458,727 @@ mod tests {
         );
     }
 
+    /// Decimal reference of `run_generic_backtest()` (the pre-f64 implementation, no limits)
+    fn run_generic_backtest_decimal(
+        generator: &mut dyn SignalGenerator,
+        klines: &[Kline],
+        initial_capital: Decimal,
+        base_position_pct: Decimal,
+        sizing_mode: SizingMode,
+        fee_config: &PolymarketFeeConfig,
+    ) -> GenericBacktestResult {
+        let hundred = dec!(100);
+        // Use first kline close as baseline for probability estimation
+        let baseline_price = klines.first().map(|k| k.close).unwrap_or(dec!(1));
+        let mut equity = initial_capital;
+        let mut peak_equity = equity;
+        let mut max_drawdown_pct = Decimal::ZERO;
+        let mut position: Option<(Decimal, Decimal)> = None;
+        let mut trades: Vec<BacktestTrade> = Vec::new();
+
+        // Kelly window kept by the shared sizer, fed the Decimal round trips
+        let mut kelly = crate::sizing::KellySizer::new(Default::default());
+        let mut shadow_entry: Option<Decimal> = None;
+        let decimal_return = |entry: Decimal, exit: Decimal| {
+            if entry > Decimal::ZERO {
+                dec_to_f64((exit - entry) / entry)
+            } else {
+                0.0
+            }
+        };
+
+        let warmup = generator.warmup_bars();
+        for (idx, kline) in klines.iter().enumerate() {
+            let sig = generator.on_bar(kline);
+            let sig = if idx < warmup {
+                SignalWithConfidence::hold()
+            } else {
+                sig
+            };
+
+            match sig.signal {
+                crate::strategy::Signal::Buy => {
+                    if position.is_none() {
+                        // Calculate position size
+                        let size_pct = match sizing_mode {
+                            SizingMode::Fixed => base_position_pct,
+                            SizingMode::Kelly => {
+                                f64_to_dec(kelly.size_pct(dec_to_f64(base_position_pct)))
+                            }
+                            SizingMode::ConfidenceWeighted => {
+                                let conf =
+                                    Decimal::from_str_exact(&format!("{:.4}", sig.confidence))
+                                        .unwrap_or(Decimal::ONE);
+                                base_position_pct * conf
+                            }
+                        };
+
+                        if size_pct <= Decimal::ZERO {
+                            shadow_entry.get_or_insert(kline.close);
+                            continue;
+                        }
+                        shadow_entry = None;
+
+                        let position_value = equity * size_pct / hundred;
+                        let shares = position_value / kline.close;
+
+                        // Entry fee — estimate probability from current price vs baseline
+                        let p_entry = estimate_poly_probability(baseline_price, kline.close);
+                        let entry_fee =
+                            crate::fees::calculate_taker_fee(shares, p_entry, fee_config);
+                        equity -= entry_fee;
+
+                        position = Some((kline.close, shares));
+                    }
+                }
+                crate::strategy::Signal::Sell => {
+                    if let Some((entry_price, size)) = position.take() {
+                        let pnl = (kline.close - entry_price) * size;
+                        let p_exit = estimate_poly_probability(baseline_price, kline.close);
+                        let exit_fee = crate::fees::calculate_taker_fee(size, p_exit, fee_config);
+
+                        let pnl_pct = if entry_price > Decimal::ZERO {
+                            (kline.close - entry_price) / entry_price * hundred
+                        } else {
+                            Decimal::ZERO
+                        };
+
+                        equity += pnl - exit_fee;
+
+                        trades.push(BacktestTrade {
+                            entry_time: 0,
+                            exit_time: kline.open_time,
+                            side: TradeSide::Buy,
+                            entry_price,
+                            exit_price: kline.close,
+                            size,
+                            pnl,
+                            pnl_pct,
+                            exit_reason: ExitReason::Signal,
+                        });
+
+                        kelly.record(decimal_return(entry_price, kline.close));
+                    } else if let Some(entry) = shadow_entry.take() {
+                        kelly.record(decimal_return(entry, kline.close));
+                    }
+                }
+                crate::strategy::Signal::Hold => {}
+            }
+
+            // Track drawdown
+            let unrealized = position
+                .as_ref()
+                .map(|(entry_price, size)| (kline.close - entry_price) * size)
+                .unwrap_or(Decimal::ZERO);
+            let current_equity = equity + unrealized;
+
+            if current_equity > peak_equity {
+                peak_equity = current_equity;
+            }
+            if peak_equity > Decimal::ZERO {
+                let dd_pct = (peak_equity - current_equity) / peak_equity * hundred;
+                if dd_pct > max_drawdown_pct {
+                    max_drawdown_pct = dd_pct;
+                }
+            }
+        }
+
+        // Close any remaining position at the last bar
+        if let Some((entry_price, size)) = position.take() {
+            if let Some(last) = klines.last() {
+                let pnl = (last.close - entry_price) * size;
+                let p_exit = estimate_poly_probability(baseline_price, last.close);
+                let exit_fee = crate::fees::calculate_taker_fee(size, p_exit, fee_config);
+                equity += pnl - exit_fee;
+                trades.push(BacktestTrade {
+                    entry_time: 0,
+                    exit_time: last.open_time,
+                    side: TradeSide::Buy,
+                    entry_price,
+                    exit_price: last.close,
+                    size,
+                    pnl,
+                    pnl_pct: if entry_price > Decimal::ZERO {
+                        (last.close - entry_price) / entry_price * hundred
+                    } else {
+                        Decimal::ZERO
+                    },
+                    exit_reason: ExitReason::Timeout,
+                });
+            }
+        }
+
+        // Calculate total fees from entry+exit on each trade
+        let total_fees = {
+            let mut fees = Decimal::ZERO;
+            for trade in &trades {
+                let p_entry = estimate_poly_probability(baseline_price, trade.entry_price);
+                let p_exit = estimate_poly_probability(baseline_price, trade.exit_price);
+                fees += crate::fees::calculate_taker_fee(trade.size, p_entry, fee_config)
+                    + crate::fees::calculate_taker_fee(trade.size, p_exit, fee_config);
+            }
+            fees
+        };
+
+        summarize_trades(
+            &trades,
+            initial_capital,
+            equity,
+            total_fees,
+            max_drawdown_pct,
+            klines.len().saturating_sub(warmup),
+        )
+    }
+
+    #[test]
+    fn test_generic_backtest_f64_matches_decimal() {
+        // Noisy oscillation over ~30 days of 15m bars: plenty of round trips
+        let prices: Vec<f64> = (0..3000)
+            .map(|i| {
+                let t = i as f64;
+                100.0 + 8.0 * (t / 40.0).sin() + 3.0 * (t / 7.0).cos() + t * 0.002
+            })
+            .collect();
+        let klines = make_klines(&prices);
+        let fee_config = PolymarketFeeConfig::default();
+        let strategies = [
+            DiscoveryStrategyType::Rsi {
+                period: 14,
+                overbought: 70.0,
+                oversold: 30.0,
+            },
+            DiscoveryStrategyType::BollingerBands {
+                period: 20,
+                multiplier: 2.0,
+            },
+        ];
+        let sizings = [
+            SizingMode::Fixed,
+            SizingMode::Kelly,
+            SizingMode::ConfidenceWeighted,
+        ];
+
+        for strategy_type in &strategies {
+            for &sizing in &sizings {
+                let mut gen = build_signal_generator(strategy_type);
+                let exact = run_generic_backtest_decimal(
+                    gen.as_mut(),
+                    &klines,
+                    dec!(10000),
+                    dec!(10),
+                    sizing,
+                    &fee_config,
+                );
+                let mut gen = build_signal_generator(strategy_type);
+                let fast = run_generic_backtest(
+                    gen.as_mut(),
+                    &klines,
+                    dec!(10000),
+                    dec!(10),
+                    sizing,
+                    &fee_config,
+                    &BacktestLimits::unlimited(),
+                );
+
+                let label = format!("{} / {:?}", strategy_type.name(), sizing);
+                assert!(exact.total_trades >= 10, "{}: too few trades", label);
+                assert_eq!(fast.total_trades, exact.total_trades, "{}", label);
+                assert_eq!(fast.winning_trades, exact.winning_trades, "{}", label);
+                let close = |a: Decimal, b: Decimal, tol: Decimal| (a - b).abs() <= tol;
+                assert!(
+                    close(fast.total_pnl, exact.total_pnl, dec!(0.01)),
+                    "{}: pnl {} vs {}",
+                    label,
+                    fast.total_pnl,
+                    exact.total_pnl
+                );
+                assert!(
+                    close(fast.total_fees, exact.total_fees, dec!(0.01)),
+                    "{}",
+                    label
+                );
+                assert!(
+                    close(fast.max_drawdown_pct, exact.max_drawdown_pct, dec!(0.0001)),
+                    "{}",
+                    label
+                );
+                assert!(
+                    close(fast.sharpe_ratio, exact.sharpe_ratio, dec!(0.01)),
+                    "{}",
+                    label
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_backtest_is_scored() {
+        let prices: Vec<f64> = (0..600)
+            .map(|i| 100.0 + 10.0 * (i as f64 * 0.2).sin())
+            .collect();
+        let klines = make_klines(&prices);
+        let strategy_type = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+
+        let result = run_backtest(
+            &strategy_type,
+            &klines,
+            "BTCUSDT",
+            SizingMode::Fixed,
+            &BacktestLimits::unlimited(),
+        );
+        assert_eq!(result.symbol, "BTCUSDT");
+        assert!(result.total_trades > 0);
+        assert_eq!(result.composite_score, score_result(&result, dec!(10000)));
+    }
+
+    #[test]
+    fn test_generic_backtest_early_termination() {
+        // Steady 1%/bar decline: the RSI buys the dip and keeps losing
+        let prices: Vec<f64> = (0..2000).map(|i| 100.0 * 0.99f64.powi(i)).collect();
+        let klines = make_klines(&prices);
+        let strategy_type = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let run = |limits: BacktestLimits| {
+            let mut gen = build_signal_generator(&strategy_type);
+            run_generic_backtest(
+                gen.as_mut(),
+                &klines,
+                dec!(10000),
+                dec!(100),
+                SizingMode::Fixed,
+                &PolymarketFeeConfig::default(),
+                &limits,
+            )
+        };
+
+        let full = run(BacktestLimits::unlimited());
+        assert_eq!(full.aborted, None);
+
+        let ruined = run(BacktestLimits {
+            time_budget: None,
+            ruin_threshold_pct: Some(dec!(90)),
+            drawdown_throttle: None,
+            fill_model: FillModel::Close,
+            symbol_filters: None,
+            trailing_stop: None,
+            decay_half_life_days: None,
+            max_exposure_pct: None,
+            participation: None,
+        });
+        assert_eq!(ruined.aborted, Some(AbortReason::Ruin));
+        assert!(ruined.total_pnl > full.total_pnl);
+        assert!(ruined.total_pnl < dec!(-1000));
+
+        let timed_out = run(BacktestLimits {
+            time_budget: Some(Duration::ZERO),
+            ruin_threshold_pct: None,
+            drawdown_throttle: None,
+            fill_model: FillModel::Close,
+            symbol_filters: None,
+            trailing_stop: None,
+            decay_half_life_days: None,
+            max_exposure_pct: None,
+            participation: None,
+        });
+        assert_eq!(timed_out.aborted, Some(AbortReason::TimeBudget));
+    }
+
+    #[test]
+    fn test_backtest_limits_from_request() {
+        let mut request: DiscoveryRequest =
+            serde_json::from_str(r#"{"symbols": ["BTCUSDT"]}"#).unwrap();
+        assert_eq!(
+            BacktestLimits::from_request(&request),
+            BacktestLimits::default()
+        );
+
+        request.time_budget_ms = Some(0);
+        request.ruin_threshold_pct = Some(dec!(0));
+        assert_eq!(
+            BacktestLimits::from_request(&request),
+            BacktestLimits::unlimited()
+        );
+
+        request.time_budget_ms = Some(250);
+        request.ruin_threshold_pct = Some(dec!(30));
+        let limits = BacktestLimits::from_request(&request);
+        assert_eq!(limits.time_budget, Some(Duration::from_millis(250)));
+        assert_eq!(limits.ruin_threshold_pct, Some(dec!(30)));
+    }
+
+    #[test]
+    fn test_cross_symbol_score_and_ranking_bonus() {
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let prices: Vec<f64> = (0..3000)
+            .map(|i| 200.0 + 6.0 * (i as f64 / 30.0).sin())
+            .collect();
+        let klines = make_klines(&prices);
+        let limits = BacktestLimits::unlimited();
+        let home = run_backtest(&strategy, &klines, "AAA", SizingMode::Fixed, &limits);
+        let transfer = |symbol: &str, net_pnl: Decimal, abort_reason| DiscoveryResult {
+            symbol: symbol.to_string(),
+            net_pnl,
+            abort_reason,
+            ..home.clone()
+        };
+        // An aborted run counts as unprofitable whatever its partial PnL
+        let transfers = [
+            transfer("BBB", dec!(120), None),
+            transfer("CCC", dec!(-40), None),
+            transfer("DDD", dec!(15), Some(AbortReason::TimeBudget)),
+            transfer("EEE", dec!(3), None),
+        ];
+        assert_eq!(cross_symbol_score(&transfers), Some(dec!(0.5)));
+        assert_eq!(cross_symbol_score(&transfers[..1]), Some(Decimal::ONE));
+        assert_eq!(cross_symbol_score(&[]), None);
+
+        let mut scored = home.clone();
+        assert_eq!(cross_symbol_bonus(&scored, Decimal::ONE), Decimal::ZERO);
+        scored.cross_symbol_score = Some(dec!(0.5));
+        assert_eq!(cross_symbol_bonus(&scored, Decimal::ZERO), Decimal::ZERO);
+        assert_eq!(cross_symbol_bonus(&scored, dec!(0.5)), dec!(50));
+
+        // Stored and read back with its score
+        let hash = compute_params_hash(&strategy, "AAA", 30, SizingMode::Fixed);
+        let record = result_to_record(&scored, &hash, "run", "phase1", 30);
+        assert_eq!(record.cross_symbol_score, Some(0.5));
+        let back = record_to_result(record).unwrap();
+        assert_eq!(back.cross_symbol_score, Some(dec!(0.5)));
+
+        let candidates = refit_candidates(&transfers, dec!(10000), 2);
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.iter().all(|r| r.abort_reason.is_none()));
+    }
+
+    #[test]
+    fn test_ranking_breaks_ties_in_order() {
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let prices: Vec<f64> = (0..3000)
+            .map(|i| 200.0 + 6.0 * (i as f64 / 30.0).sin())
+            .collect();
+        let klines = make_klines(&prices);
+        let limits = BacktestLimits::unlimited();
+        let base = run_backtest(&strategy, &klines, "AAA", SizingMode::Fixed, &limits);
+        let variant = |name: &str, sharpe: Decimal, trades: u32| DiscoveryResult {
+            strategy_name: name.to_string(),
+            sharpe_ratio: sharpe,
+            total_trades: trades,
+            ..base.clone()
+        };
+        let a = variant("a", dec!(1), 30);
+        let b = variant("b", dec!(2), 10);
+        let c = variant("c", dec!(1), 10);
+        let mut ranked = [
+            (dec!(50), &c),
+            (dec!(50), &a),
+            (dec!(50), &b),
+            (dec!(60), &c),
+        ];
+        ranked.sort_by(|x, y| compare_ranked(DEFAULT_RANK_ORDER, *x, *y));
+        let names: Vec<_> = ranked
+            .iter()
+            .map(|(s, r)| format!("{}{}", r.strategy_name, s))
+            .collect();
+        assert_eq!(names, ["c60", "b50", "a50", "c50"]);
+
+        // Trades first, then the name settles a full tie
+        ranked.sort_by(|x, y| compare_ranked(&[RankKey::Trades], *x, *y));
+        let names: Vec<_> = ranked
+            .iter()
+            .map(|(_, r)| r.strategy_name.as_str())
+            .collect();
+        assert_eq!(names, ["a", "b", "c", "c"]);
+
+        // The request's order reaches the live ranking
+        let progress = Arc::new(DiscoveryProgress::new());
+        assert_eq!(progress.rank_order(), DEFAULT_RANK_ORDER);
+        progress.set_rank_order(vec![RankKey::Trades, RankKey::Sharpe]);
+        update_best_so_far(
+            &[c.clone(), b.clone(), a.clone()],
+            dec!(10000),
+            2,
+            &progress,
+        );
+        let best = progress.best_so_far.read().unwrap();
+        let names: Vec<_> = best
+            .iter()
+            .map(|r| (r.rank, r.strategy_name.as_str()))
+            .collect();
+        assert_eq!(names, [(1, "a"), (2, "b")]);
+    }
+
+    #[test]
+    fn test_final_results_keep_per_symbol_quota() {
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let prices: Vec<f64> = (0..3000)
+            .map(|i| 200.0 + 6.0 * (i as f64 / 30.0).sin())
+            .collect();
+        let klines = make_klines(&prices);
+        let limits = BacktestLimits::unlimited();
+        let base = run_backtest(&strategy, &klines, "AAA", SizingMode::Fixed, &limits);
+        let result = |name: &str, symbol: &str, score: Decimal| {
+            let result = DiscoveryResult {
+                strategy_name: name.to_string(),
+                symbol: symbol.to_string(),
+                ..base.clone()
+            };
+            (score, result)
+        };
+        // AAA holds the 4 best scores, one of them twice (a near-duplicate)
+        let ranked = || {
+            vec![
+                result("a1", "AAA", dec!(90)),
+                result("a1", "AAA", dec!(89)),
+                result("a2", "AAA", dec!(88)),
+                result("a3", "AAA", dec!(87)),
+                result("a4", "AAA", dec!(86)),
+                result("b1", "BBB", dec!(50)),
+                result("b2", "BBB", dec!(40)),
+                result("c1", "CCC", dec!(30)),
+            ]
+        };
+        let symbols = ["AAA", "BBB", "CCC"].map(String::from);
+        let names = |results: &[DiscoveryResult]| -> Vec<String> {
+            results
+                .iter()
+                .map(|r| format!("{}#{}", r.strategy_name, r.rank))
+                .collect()
+        };
+
+        // Global top 3: only AAA
+        let global = select_final_results(ranked(), 3, 0, &symbols);
+        assert_eq!(names(&global), ["a1#1", "a2#2", "a3#3"]);
+        assert_eq!(global[0].composite_score, dec!(90));
+
+        // One per symbol, the best of the rest filling up to 4, in rank order
+        let quota = select_final_results(ranked(), 4, 1, &symbols);
+        assert_eq!(names(&quota), ["a1#1", "a2#2", "b1#3", "c1#4"]);
+
+        // Quotas beyond top N are kept; a symbol short of results keeps what it has
+        let over = select_final_results(ranked(), 3, 2, &symbols);
+        assert_eq!(names(&over), ["a1#1", "a2#2", "b1#3", "b2#4", "c1#5"]);
+    }
+
+    #[test]
+    fn test_save_tally_fails_only_when_nothing_was_stored() {
+        let mut saves = SaveTally::default();
+        assert!(saves.check().is_ok());
+        saves.record(Err(anyhow::anyhow!("disk full")));
+        saves.record(Err(anyhow::anyhow!("disk full")));
+        assert_eq!(
+            saves.check(),
+            Err(EngineError::Storage(
+                "The result store rejected all 2 results: disk full".to_string()
+            ))
+        );
+        saves.record(Ok(()));
+        assert!(saves.check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_exit_breakdown_is_persisted() {
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let prices: Vec<f64> = (0..3000)
+            .map(|i| 200.0 + 6.0 * (i as f64 / 30.0).sin() + i as f64 * 0.01)
+            .collect();
+        let klines = make_klines(&prices);
+        let limits = BacktestLimits::unlimited();
+        let result = run_backtest(&strategy, &klines, "AAA", SizingMode::Fixed, &limits);
+        let exits = result.exit_breakdown.unwrap();
+        assert!(exits.signal.trades > 0);
+        assert!(exits.timeout.trades <= 1);
+        assert_eq!(exits.total_trades(), result.total_trades);
+
+        // Stored as JSON and read back, through the database
+        let hash = compute_params_hash(&strategy, "AAA", 30, SizingMode::Fixed);
+        let record = result_to_record(&result, &hash, "run", "phase1", 30);
+        let db = persistence::Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool());
+        repo.save(&record).await.unwrap();
+        let stored = repo.get_by_hash(&hash).await.unwrap().unwrap();
+        assert!(stored
+            .exit_breakdown
+            .as_deref()
+            .unwrap()
+            .contains("\"timeout\""));
+        let back = record_to_result(stored).unwrap();
+        assert_eq!(back.exit_breakdown, Some(exits));
+
+        // Records written before exits were recorded have none
+        let legacy = DiscoveryBacktestRecord {
+            exit_breakdown: None,
+            decayed_score: None,
+            score_30d: None,
+            net_pnl_30d: None,
+            win_rate_30d: None,
+            trades_30d: None,
+            score_90d: None,
+            net_pnl_90d: None,
+            win_rate_90d: None,
+            trades_90d: None,
+            pnl_hhi: None,
+            top_week_pnl_share: None,
+            active_weeks: None,
+            longest_drawdown_days: None,
+            avg_recovery_days: None,
+            max_exposure_pct: None,
+            avg_exposure_pct: None,
+            warmup_bars: None,
+            effective_days: None,
+            ..record
+        };
+        assert_eq!(record_to_result(legacy).unwrap().exit_breakdown, None);
+    }
+
+    #[tokio::test]
+    async fn test_cost_attribution_adds_up_and_is_persisted() {
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let prices: Vec<f64> = (0..3000)
+            .map(|i| 200.0 + 6.0 * (i as f64 / 30.0).sin() + i as f64 * 0.01)
+            .collect();
+        let klines = make_klines(&prices);
+        let run = |fill_model| {
+            let limits = BacktestLimits::unlimited().with_fill_model(fill_model);
+            run_backtest(&strategy, &klines, "AAA", SizingMode::Fixed, &limits)
+        };
+        let costs_of = |result: &DiscoveryResult| result.cost_attribution.unwrap();
+        let near = |a: f64, b: f64| (a - b).abs() < 1e-6 * b.abs().max(1.0);
+
+        // Filled at the signal closes: no timing cost, fees alone separate gross and net
+        let at_close = run(FillModel::Close);
+        let costs = costs_of(&at_close);
+        assert_eq!(costs.trades, at_close.total_trades);
+        assert_eq!(costs.timing_cost, 0.0);
+        assert_eq!(costs.slippage_drag, 0.0);
+        assert!(near(costs.signal_pnl, costs.gross_pnl));
+        assert!(near(costs.fee_drag, dec_to_f64(at_close.total_fees)));
+        assert!(near(costs.net_pnl, dec_to_f64(at_close.net_pnl)));
+        assert!(near(costs.gross_pnl, dec_to_f64(at_close.gross_pnl)));
+
+        // Worst-of-bar fills pay for their timing: the signals are the same, the fills
+        // lose what separates their prices from the closes
+        let worst = run(FillModel::WorstOfBar);
+        let costs = costs_of(&worst);
+        assert!(costs.timing_cost > 0.0);
+        assert!(near(
+            costs.signal_pnl - costs.timing_cost - costs.slippage_drag,
+            costs.gross_pnl
+        ));
+        assert!(near(costs.gross_pnl - costs.fee_drag, costs.net_pnl));
+        assert!(near(costs.net_pnl, dec_to_f64(worst.net_pnl)));
+        assert!(costs.net_win_rate() <= costs.win_rate());
+
+        // Stored as JSON and read back, through the database
+        let hash = compute_params_hash(&strategy, "AAA", 30, SizingMode::Fixed);
+        let record = result_to_record(&worst, &hash, "run", "phase1", 30);
+        let db = persistence::Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool());
+        repo.save(&record).await.unwrap();
+        let stored = repo.get_by_hash(&hash).await.unwrap().unwrap();
+        assert!(stored
+            .cost_attribution
+            .as_deref()
+            .unwrap()
+            .contains("\"timing_cost\""));
+        assert_eq!(
+            record_to_result(stored).unwrap().cost_attribution,
+            Some(costs)
+        );
+
+        // A winner its fees turn into a loss
+        let mut flipped = CostAttribution::default();
+        flipped.record_trade(1.0, 1.5, 0.0);
+        flipped.record_trade(4.0, 1.5, 0.5);
+        assert_eq!((flipped.winning_trades, flipped.fee_flipped_trades), (2, 1));
+        assert_eq!((flipped.win_rate(), flipped.net_win_rate()), (100.0, 50.0));
+        assert_eq!((flipped.signal_pnl, flipped.net_pnl), (5.5, 2.0));
+        assert_eq!(flipped.fee_per_trade(), 1.5);
+    }
+
+    #[test]
+    fn test_drawdown_throttle_backtest_and_search() {
+        // Slow downtrend with swings: RSI dip buying keeps losing
+        let prices: Vec<f64> = (0..3000)
+            .map(|i| {
+                let t = i as f64;
+                200.0 - t * 0.03 + 6.0 * (t / 30.0).sin()
+            })
+            .collect();
+        let klines = make_klines(&prices);
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let throttle = DrawdownThrottle::SEARCH_PRESETS[2];
+        let run = |throttle| {
+            let limits = BacktestLimits::unlimited().with_drawdown_throttle(throttle);
+            run_backtest(&strategy, &klines, "SYNTH", SizingMode::Fixed, &limits)
+        };
+        let base = run(None);
+        let throttled = run(Some(throttle));
+        assert!(base.max_drawdown_pct > dec!(3), "{}", base.max_drawdown_pct);
+        assert_eq!(throttled.drawdown_throttle, Some(throttle));
+        assert!(throttled.total_volume < base.total_volume);
+        assert!(throttled.max_drawdown_pct < base.max_drawdown_pct);
+
+        // Stored and read back with its throttle, under its own hash
+        let base_hash = compute_params_hash(&strategy, "SYNTH", 30, SizingMode::Fixed);
+        let hash = compute_throttled_hash(&base_hash, &throttle);
+        assert_ne!(hash, base_hash);
+        assert_ne!(
+            hash,
+            compute_throttled_hash(&base_hash, &DrawdownThrottle::SEARCH_PRESETS[0])
+        );
+        let record = result_to_record(&throttled, &hash, "run", "throttle", 30);
+        let back = record_to_result(record).unwrap();
+        assert_eq!(back.drawdown_throttle, Some(throttle));
+
+        // One candidate per strategy and symbol, throttled runs excluded
+        let results = [base.clone(), base.clone(), throttled.clone()];
+        let candidates = refit_candidates(&results, dec!(10000), THROTTLE_SEARCH_TOP);
+        assert_eq!(candidates.len(), 1);
+        assert!(candidates[0].drawdown_throttle.is_none());
+
+        let comparisons =
+            compare_throttles(&candidates, std::slice::from_ref(&throttled), dec!(10000));
+        assert_eq!(comparisons.len(), DrawdownThrottle::SEARCH_PRESETS.len());
+        assert_eq!((comparisons[0].tested, comparisons[1].tested), (0, 0));
+        let compared = &comparisons[2];
+        assert_eq!(compared.tested, 1);
+        let score_delta = score_result(&throttled, dec!(10000)) - score_result(&base, dec!(10000));
+        assert_eq!(compared.improved, u32::from(score_delta > Decimal::ZERO));
+        assert!((compared.mean_score_delta - dec_to_f64(score_delta)).abs() < 1e-9);
+        assert!(compared.mean_drawdown_delta < 0.0);
+    }
+
     #[test]
     fn test_scoring_penalizes_few_trades() {
         let result = DiscoveryResult {
@@ -4352,6 +9211,23 @@ mod tests {
             strategy_confidence: Decimal::ZERO,
             hit_rate: None,
             avg_locked_profit: None,
+            abort_reason: None,
+            probability_model: default_probability_model(),
+            drawdown_throttle: None,
+            cross_symbol_score: None,
+            exit_breakdown: None,
+            decayed_score: None,
+            recent_30d: None,
+            recent_90d: None,
+            pnl_concentration: None,
+            longest_drawdown_days: None,
+            avg_recovery_days: None,
+            max_exposure_pct: None,
+            avg_exposure_pct: None,
+            warmup_bars: None,
+            effective_days: None,
+            unfilled: None,
+            cost_attribution: None,
         };
 
         let score = score_result(&result, dec!(10000));
@@ -4390,6 +9266,23 @@ mod tests {
             strategy_confidence: Decimal::ZERO,
             hit_rate: None,
             avg_locked_profit: None,
+            abort_reason: None,
+            probability_model: default_probability_model(),
+            drawdown_throttle: None,
+            cross_symbol_score: None,
+            exit_breakdown: None,
+            decayed_score: None,
+            recent_30d: None,
+            recent_90d: None,
+            pnl_concentration: None,
+            longest_drawdown_days: None,
+            avg_recovery_days: None,
+            max_exposure_pct: None,
+            avg_exposure_pct: None,
+            warmup_bars: None,
+            effective_days: None,
+            unfilled: None,
+            cost_attribution: None,
         };
 
         let low_wr = DiscoveryResult {
@@ -4398,34 +9291,276 @@ mod tests {
             ..high_wr.clone()
         };
 
-        let score_high = score_result(&high_wr, dec!(10000));
-        let score_low = score_result(&low_wr, dec!(10000));
+        let score_high = score_result(&high_wr, dec!(10000));
+        let score_low = score_result(&low_wr, dec!(10000));
+
+        assert!(
+            score_high > score_low,
+            "High WR ({}) should score higher than low WR ({})",
+            score_high,
+            score_low
+        );
+    }
+
+    #[test]
+    fn test_refinement_grid_produces_variants() {
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let variants = generate_refinement_grid(&strategy);
+        assert!(variants.len() > 10, "Should produce multiple variants");
+        assert!(variants.len() < 200, "Should not explode in size");
+    }
+
+    #[test]
+    fn test_refinement_depth_scales_grid_and_chains_rounds() {
+        let rsi = |period: usize| DiscoveryStrategyType::Rsi {
+            period,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let json = |grid: &[DiscoveryStrategyType]| serde_json::to_string(grid).unwrap();
+        let default = generate_refinement_grid(&rsi(14));
+        assert_eq!(
+            json(&generate_scaled_refinement_grid(&rsi(14), 1.0)),
+            json(&default)
+        );
+        let periods = |grid: &[DiscoveryStrategyType]| {
+            let mut periods: Vec<usize> = grid
+                .iter()
+                .map(|s| match s {
+                    DiscoveryStrategyType::Rsi { period, .. } => *period,
+                    _ => unreachable!(),
+                })
+                .collect();
+            periods.dedup();
+            periods
+        };
+        assert_eq!(periods(&default), [12, 13, 14, 15, 16]);
+        assert_eq!(
+            periods(&generate_scaled_refinement_grid(&rsi(14), 2.0)),
+            [10, 12, 14, 16, 18]
+        );
+        // Steps rounded onto each other are tried once
+        let fine = generate_scaled_refinement_grid(&rsi(14), 0.2);
+        assert_eq!(periods(&fine), [13, 14, 15]);
+        assert_eq!(fine.len(), 3 * 9);
+
+        let request: DiscoveryRequest = serde_json::from_value(serde_json::json!({
+            "symbols": ["BTCUSDT"],
+            "refinement_top_k": 2,
+            "refinement_rounds": 2,
+        }))
+        .unwrap();
+        let depth = RefinementDepth::from_request(&request);
+        assert_eq!(
+            depth,
+            RefinementDepth {
+                top_k: 2,
+                rounds: 2,
+                delta_scale: 1.0,
+            }
+        );
+        assert!(depth.validate().is_ok());
+        assert_eq!(
+            depth.estimated_combos(),
+            RefinementDepth::default().estimated_combos() / 5
+        );
+        for invalid in [
+            RefinementDepth { top_k: 0, ..depth },
+            RefinementDepth { rounds: 6, ..depth },
+            RefinementDepth {
+                delta_scale: 0.0,
+                ..depth
+            },
+        ] {
+            assert!(invalid.validate().is_err(), "{:?}", invalid);
+        }
+
+        let klines = make_klines(&(0..500).map(|i| 100.0 + i as f64 * 0.1).collect::<Vec<_>>());
+        let base = run_backtest(
+            &rsi(14),
+            &klines,
+            "AAA",
+            SizingMode::Fixed,
+            &BacktestLimits::unlimited(),
+        );
+        let result = |symbol: &str, net_pnl: i64| DiscoveryResult {
+            symbol: symbol.to_string(),
+            net_pnl: Decimal::from(net_pnl),
+            total_trades: 20,
+            ..base.clone()
+        };
+        let symbol_of = |r: Option<DiscoveryResult>| r.map(|r| r.symbol);
+        let some = |symbol: &str| Some(symbol.to_string());
+
+        // Round 1: the best 2 of phase 1
+        let mut results = vec![result("A", 100), result("B", 300), result("C", 200)];
+        let mut rounds = RefinementRounds::new(depth, dec!(10000));
+        assert_eq!(symbol_of(rounds.next_parent(&results)), some("B"));
+        assert_eq!(symbol_of(rounds.next_parent(&results)), some("C"));
+        // Round 2: the best 2 new results, not refined yet
+        results.extend([
+            result("B", 900),
+            result("D", 400),
+            result("E", 50),
+            result("F", 500),
+        ]);
+        assert_eq!(symbol_of(rounds.next_parent(&results)), some("F"));
+        assert_eq!(symbol_of(rounds.next_parent(&results)), some("D"));
+        assert!(rounds.next_parent(&results).is_none());
+    }
+
+    #[test]
+    fn test_discovery_progress_new() {
+        let progress = DiscoveryProgress::new();
+        assert!(!progress.is_running());
+        assert_eq!(progress.progress_pct(), 0.0);
+    }
 
-        assert!(
-            score_high > score_low,
-            "High WR ({}) should score higher than low WR ({})",
-            score_high,
-            score_low
+    #[test]
+    fn test_discovery_progress_estimate() {
+        let progress = DiscoveryProgress::new();
+        progress.total_combinations.store(1000, Ordering::Relaxed);
+        let t0 = Instant::now();
+        assert_eq!(progress.estimate_at(t0).eta_secs, None);
+
+        // 100 combinations in 10 s → 10/s, 900 left → 90 s
+        progress.completed.store(100, Ordering::Relaxed);
+        let est = progress.estimate_at(t0 + Duration::from_secs(10));
+        assert!((est.throughput_per_sec - 10.0).abs() < 1e-9);
+        assert_eq!(est.eta_secs, Some(90));
+        assert!(est.eta_at.is_some());
+
+        // Only the last minute counts: 600 more in the next 60 s → 10/s still,
+        // then a slowdown to 1/s over the following minute
+        progress.completed.store(700, Ordering::Relaxed);
+        progress.estimate_at(t0 + Duration::from_secs(70));
+        progress.completed.store(760, Ordering::Relaxed);
+        let est = progress.estimate_at(t0 + Duration::from_secs(130));
+        assert!((est.throughput_per_sec - 1.0).abs() < 1e-9);
+        assert_eq!(est.eta_secs, Some(240));
+
+        // New continuous cycle: counter restarts, samples are dropped
+        progress.completed.store(0, Ordering::Relaxed);
+        let est = progress.estimate_at(t0 + Duration::from_secs(131));
+        assert_eq!(est, ProgressEstimate::default());
+    }
+
+    #[test]
+    fn test_discovery_progress_breakdown() {
+        let progress = DiscoveryProgress::new();
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let macd = DiscoveryStrategyType::Macd {
+            fast: 12,
+            slow: 26,
+            signal: 9,
+        };
+        for symbol in ["ETHUSDT", "BTCUSDT"] {
+            progress.plan_work(symbol, &rsi, 2);
+            progress.plan_work(symbol, &macd, 1);
+        }
+        progress.complete_work("BTCUSDT", &rsi, 2);
+        progress.complete_work("BTCUSDT", &macd, 1);
+        progress.complete_work("ETHUSDT", &rsi, 1);
+
+        let row = |name: &str, completed, total| WorkProgress {
+            name: name.to_string(),
+            completed,
+            total,
+        };
+        let breakdown = progress.breakdown();
+        // ETH is the one dragging
+        assert_eq!(
+            breakdown.by_symbol,
+            vec![row("BTCUSDT", 3, 3), row("ETHUSDT", 1, 3)]
+        );
+        assert_eq!(
+            breakdown.by_family,
+            vec![row("macd", 1, 2), row("rsi", 3, 4)]
         );
+
+        progress.reset();
+        assert_eq!(progress.breakdown(), ProgressBreakdown::default());
     }
 
     #[test]
-    fn test_refinement_grid_produces_variants() {
-        let strategy = DiscoveryStrategyType::Rsi {
+    fn test_prioritize_work_by_family_track_record() {
+        let row = |name: &str, symbol: &str, backtests, avg_score, best_score| FamilyPerformance {
+            strategy_name: name.to_string(),
+            symbol: symbol.to_string(),
+            backtests,
+            avg_score,
+            best_score,
+        };
+        let priors = StrategyPriors::from_performance(&[
+            row("RSI", "BTCUSDT", 10, 20.0, 40.0),
+            row("RSI", "ETHUSDT", 30, 50.0, 70.0),
+            row("MACD", "BTCUSDT", 10, 40.0, 60.0),
+        ]);
+        let rsi = DiscoveryStrategyType::Rsi {
             period: 14,
             overbought: 70.0,
             oversold: 30.0,
         };
-        let variants = generate_refinement_grid(&strategy);
-        assert!(variants.len() > 10, "Should produce multiple variants");
-        assert!(variants.len() < 200, "Should not explode in size");
+        let macd = DiscoveryStrategyType::Macd {
+            fast: 12,
+            slow: 26,
+            signal: 9,
+        };
+        let obv = DiscoveryStrategyType::Obv { sma_period: 20 };
+
+        assert_eq!(priors.score(&rsi, "BTCUSDT"), 30.0);
+        // Unknown symbol → family mean weighted by backtests: (30×10 + 60×30) / 40
+        assert_eq!(priors.score(&rsi, "SOLUSDT"), 52.5);
+        // Unknown family → mean of the known families
+        assert_eq!(priors.score(&obv, "BTCUSDT"), (52.5 + 50.0) / 2.0);
+
+        let work = vec![
+            (&rsi, "BTCUSDT"),
+            (&obv, "BTCUSDT"),
+            (&macd, "BTCUSDT"),
+            (&rsi, "ETHUSDT"),
+        ];
+        let labels = |items: Vec<(&DiscoveryStrategyType, &str)>| -> Vec<String> {
+            items
+                .iter()
+                .map(|(s, sym)| format!("{}/{}", s.name(), sym))
+                .collect()
+        };
+        let ordered = prioritize_work(work.clone(), &priors, |&(s, sym)| (s, sym));
+        assert_eq!(
+            labels(ordered),
+            ["RSI/ETHUSDT", "OBV/BTCUSDT", "MACD/BTCUSDT", "RSI/BTCUSDT"]
+        );
+
+        // Empty knowledge base: generation order is kept
+        let neutral = StrategyPriors::default();
+        let kept = prioritize_work(work.clone(), &neutral, |&(s, sym)| (s, sym));
+        assert_eq!(labels(kept), labels(work));
     }
 
     #[test]
-    fn test_discovery_progress_new() {
-        let progress = DiscoveryProgress::new();
-        assert!(!progress.is_running());
-        assert_eq!(progress.progress_pct(), 0.0);
+    fn test_grid_id_and_unit_hash() {
+        let cycle0 = generate_exploratory_grid(0);
+        let id = compute_grid_id(&cycle0);
+        // Regenerating the deterministic grid gives the same id; any other content does not
+        assert_eq!(id, compute_grid_id(&generate_exploratory_grid(0)));
+        assert_ne!(id, compute_grid_id(&cycle0[1..]));
+        assert_ne!(id, compute_grid_id(&generate_exploratory_grid(1)));
+
+        let unit = |symbol, days, sizing| compute_grid_hash(&id, symbol, days, sizing);
+        let btc = unit("BTCUSDT", 90, SizingMode::Fixed);
+        assert_eq!(btc, unit("BTCUSDT", 90, SizingMode::Fixed));
+        assert_ne!(btc, unit("BTCUSDT", 90, SizingMode::Kelly));
+        assert_ne!(btc, unit("BTCUSDT", 180, SizingMode::Fixed));
+        assert_ne!(btc, unit("ETHUSDT", 90, SizingMode::Fixed));
     }
 
     #[test]
@@ -4436,6 +9571,31 @@ mod tests {
         assert!(progress.started_at.read().unwrap().is_some());
     }
 
+    #[tokio::test]
+    async fn test_discovery_progress_pause() {
+        let progress = Arc::new(DiscoveryProgress::new());
+        progress.reset();
+        progress.paused.store(true, Ordering::Relaxed);
+
+        let waiter = tokio::spawn({
+            let progress = progress.clone();
+            async move { progress.wait_while_paused().await }
+        });
+        tokio::time::sleep(PAUSE_POLL * 2).await;
+        assert!(!waiter.is_finished());
+        assert!(progress.is_running());
+
+        progress.paused.store(false, Ordering::Relaxed);
+        waiter.await.unwrap();
+
+        // A cancel ends the wait; a reset clears the flag
+        progress.paused.store(true, Ordering::Relaxed);
+        progress.cancelled.store(true, Ordering::Relaxed);
+        progress.wait_while_paused().await;
+        progress.reset();
+        assert!(!progress.is_paused());
+    }
+
     #[test]
     fn test_gabagool_backtest_for_discovery() {
         let klines = make_klines(&[50000.0; 50]);
@@ -4538,6 +9698,23 @@ mod tests {
                 strategy_confidence: Decimal::ZERO,
                 hit_rate: None,
                 avg_locked_profit: None,
+                abort_reason: None,
+                probability_model: default_probability_model(),
+                drawdown_throttle: None,
+                cross_symbol_score: None,
+                exit_breakdown: None,
+                decayed_score: None,
+                recent_30d: None,
+                recent_90d: None,
+                pnl_concentration: None,
+                longest_drawdown_days: None,
+                avg_recovery_days: None,
+                max_exposure_pct: None,
+                avg_exposure_pct: None,
+                warmup_bars: None,
+                effective_days: None,
+                unfilled: None,
+                cost_attribution: None,
             },
             DiscoveryResult {
                 rank: 2,
@@ -4568,10 +9745,28 @@ mod tests {
                 strategy_confidence: Decimal::ZERO,
                 hit_rate: None,
                 avg_locked_profit: None,
+                abort_reason: None,
+                probability_model: default_probability_model(),
+                drawdown_throttle: None,
+                cross_symbol_score: None,
+                exit_breakdown: None,
+                decayed_score: None,
+                recent_30d: None,
+                recent_90d: None,
+                pnl_concentration: None,
+                longest_drawdown_days: None,
+                avg_recovery_days: None,
+                max_exposure_pct: None,
+                avg_exposure_pct: None,
+                warmup_bars: None,
+                effective_days: None,
+                unfilled: None,
+                cost_attribution: None,
             },
         ];
 
-        let grid = generate_ml_guided_grid(&results, 3);
+        let population = Population::from_results(&results);
+        let grid = generate_ml_guided_grid(population.individuals(), 3);
         // Budget = 300 + 3*50 = 450
         assert!(grid.len() > 100, "Should produce a substantial grid: {}", grid.len());
         assert!(grid.len() <= 1000, "Grid too large: {}", grid.len());
@@ -4609,10 +9804,28 @@ mod tests {
             strategy_confidence: Decimal::ZERO,
             hit_rate: None,
             avg_locked_profit: None,
+            abort_reason: None,
+            probability_model: default_probability_model(),
+            drawdown_throttle: None,
+            cross_symbol_score: None,
+            exit_breakdown: None,
+            decayed_score: None,
+            recent_30d: None,
+            recent_90d: None,
+            pnl_concentration: None,
+            longest_drawdown_days: None,
+            avg_recovery_days: None,
+            max_exposure_pct: None,
+            avg_exposure_pct: None,
+            warmup_bars: None,
+            effective_days: None,
+            unfilled: None,
+            cost_attribution: None,
         }];
 
-        let grid3 = generate_ml_guided_grid(&results, 3);
-        let grid6 = generate_ml_guided_grid(&results, 6);
+        let population = Population::from_results(&results);
+        let grid3 = generate_ml_guided_grid(population.individuals(), 3);
+        let grid6 = generate_ml_guided_grid(population.individuals(), 6);
         assert!(
             grid6.len() > grid3.len(),
             "Later cycles should produce more combos ({} vs {})",
@@ -4633,6 +9846,480 @@ mod tests {
         assert!(sliced.len() > 80 && sliced.len() < 120, "Got {} bars for 1 day", sliced.len());
     }
 
+    #[test]
+    fn test_merge_new_klines_appends_and_replaces_tail() {
+        let all = make_klines(&[100.0, 101.0, 102.0, 103.0, 104.0]);
+        let mut cached = all[..3].to_vec();
+
+        // Fresh fetch starts at the last cached bar (now closed at a different price)
+        let mut fresh = all[2..].to_vec();
+        fresh[0].close = dec!(150);
+        let added = merge_new_klines(&mut cached, fresh, 365);
+
+        assert_eq!(added, 2);
+        assert_eq!(cached.len(), 5);
+        assert_eq!(cached[2].close, dec!(150));
+        assert!(cached.windows(2).all(|w| w[0].open_time < w[1].open_time));
+    }
+
+    #[test]
+    fn test_merge_new_klines_trims_to_max_days() {
+        // 200 bars of 15m ≈ 2 days; keeping 1 day drops the oldest half
+        let all = make_klines(&vec![100.0; 200]);
+        let mut cached = all[..100].to_vec();
+        merge_new_klines(&mut cached, all[100..].to_vec(), 1);
+
+        assert_eq!(cached.last().unwrap().open_time, all[199].open_time);
+        assert!(cached.len() > 80 && cached.len() < 120, "Got {} bars", cached.len());
+    }
+
+    #[test]
+    fn test_merge_klines_into_progress_cache() {
+        let all = make_klines(&[100.0, 101.0, 102.0, 103.0]);
+        let progress = DiscoveryProgress::new();
+
+        // Nothing cached (first fetch failed): the fresh series is cached as is
+        let (added, klines) = progress.merge_klines("BTCUSDT", all[..2].to_vec(), 365);
+        assert_eq!(added, 2);
+        assert_eq!(klines.len(), 2);
+
+        let (added, klines) = progress.merge_klines("BTCUSDT", all[1..].to_vec(), 365);
+        assert_eq!(added, 2);
+        assert_eq!(klines.len(), 4);
+        assert_eq!(progress.cached_klines("BTCUSDT").unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_decode_strategy_params_v0_fills_missing_indicator_fields() {
+        // Pre-versioning row: the ADX params lack adx_threshold
+        let json = r#"{"type":"dynamic_combo","indicators":["rsi","adx"],"params":[{"indicator":"rsi","period":10,"overbought":75.0,"oversold":25.0},{"indicator":"adx","period":21}],"combine_mode":"majority"}"#;
+        assert!(decode_strategy_params(json, 1).is_err());
+
+        match decode_strategy_params(json, 0).unwrap() {
+            DiscoveryStrategyType::DynamicCombo { params, .. } => {
+                assert!(matches!(params[0], IndicatorParams::Rsi { period: 10, .. }));
+                match &params[1] {
+                    IndicatorParams::Adx { period, adx_threshold } => {
+                        assert_eq!(*period, 21);
+                        assert!(*adx_threshold > 0.0);
+                    }
+                    other => panic!("Expected ADX params, got {:?}", other),
+                }
+            }
+            other => panic!("Expected DynamicCombo, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_strategy_params_rejects_incompatible() {
+        // Legacy variants with missing fields have no defaults to fall back on; unknown versions are refused
+        assert!(decode_strategy_params(r#"{"type":"rsi","period":14}"#, 0).is_err());
+        assert!(decode_strategy_params(r#"{"type":"not_a_strategy"}"#, 0).is_err());
+        assert!(decode_strategy_params(r#"{"type":"vwap","period":20}"#, 99).is_err());
+    }
+
+    #[test]
+    fn test_record_roundtrip_uses_current_schema_version() {
+        let strategy = DiscoveryStrategyType::Vwap {
+            period: 20,
+            anchor: VwapAnchor::Rolling,
+        };
+        let hash = compute_params_hash(&strategy, "BTCUSDT", 30, SizingMode::Kelly);
+        let result = DiscoveryResult {
+            rank: 1,
+            strategy_name: strategy.name().to_string(),
+            strategy_type: strategy,
+            symbol: "BTCUSDT".to_string(),
+            sizing_mode: SizingMode::Kelly,
+            composite_score: dec!(10),
+            net_pnl: dec!(5),
+            gross_pnl: dec!(6),
+            total_fees: dec!(1),
+            win_rate: dec!(55),
+            total_trades: 12,
+            sharpe_ratio: dec!(1.2),
+            max_drawdown_pct: dec!(3),
+            profit_factor: dec!(1.5),
+            avg_trade_pnl: dec!(0.4),
+            sortino_ratio: Decimal::ZERO,
+            max_consecutive_losses: 2,
+            avg_win_pnl: Decimal::ZERO,
+            avg_loss_pnl: Decimal::ZERO,
+            total_volume: Decimal::ZERO,
+            annualized_return_pct: Decimal::ZERO,
+            annualized_sharpe: Decimal::ZERO,
+            strategy_confidence: Decimal::ZERO,
+            hit_rate: None,
+            avg_locked_profit: None,
+            abort_reason: None,
+            probability_model: default_probability_model(),
+            drawdown_throttle: None,
+            cross_symbol_score: None,
+            exit_breakdown: None,
+            decayed_score: None,
+            recent_30d: None,
+            recent_90d: None,
+            pnl_concentration: None,
+            longest_drawdown_days: None,
+            avg_recovery_days: None,
+            max_exposure_pct: None,
+            avg_exposure_pct: None,
+            warmup_bars: None,
+            effective_days: None,
+            unfilled: None,
+            cost_attribution: None,
+        };
+
+        let record = result_to_record(&result, &hash, "run", "phase1", 30);
+        assert_eq!(record.schema_version, Some(STRATEGY_PARAMS_VERSION));
+
+        let back = record_to_result(record).expect("current-version record should decode");
+        assert!(matches!(back.strategy_type, DiscoveryStrategyType::Vwap { period: 20, .. }));
+        assert!(matches!(back.sizing_mode, SizingMode::Kelly));
+        assert_eq!(back.sharpe_ratio, dec!(1.2));
+        assert_eq!(back.avg_trade_pnl, dec!(0.4));
+
+        let mut corrupt = result_to_record(&result, &hash, "run", "phase1", 30);
+        corrupt.strategy_params = r#"{"type":"vwap"}"#.to_string();
+        assert!(record_to_result(corrupt).is_none());
+    }
+
+    #[test]
+    fn test_decayed_performance_weights_recent_trades() {
+        use crate::types::{BacktestTrade, ExitReason, TradeSide};
+        const DAY: i64 = 86_400_000;
+        let trade = |exit_time: i64, pnl: Decimal| BacktestTrade {
+            entry_time: exit_time - 1,
+            exit_time,
+            side: TradeSide::Buy,
+            entry_price: dec!(0.5),
+            exit_price: dec!(0.5),
+            size: dec!(100),
+            pnl,
+            pnl_pct: pnl,
+            exit_reason: ExitReason::Signal,
+        };
+        // An old win and a recent loss of the same size: the loss weighs twice as much
+        // one half-life later
+        let end = 100 * DAY;
+        let trades = [trade(end - 10 * DAY, dec!(10)), trade(end, dec!(-10))];
+        let decayed = decayed_performance(&trades, 0.0, end, 10.0);
+        assert_eq!(decayed.net_pnl, dec!(-5));
+        assert!(decayed.sharpe_ratio < Decimal::ZERO);
+        let reversed = [trade(end - 10 * DAY, dec!(-10)), trade(end, dec!(10))];
+        let decayed = decayed_performance(&reversed, 2.0, end, 10.0);
+        assert_eq!(decayed.net_pnl, dec!(3.5));
+
+        // Only runs with a half-life get a decayed score, and it is stored
+        let klines = crate::synthetic::generate_klines(&crate::synthetic::SyntheticConfig {
+            bars: 2_000,
+            ..Default::default()
+        });
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let limits = BacktestLimits::default();
+        let plain = run_backtest(&rsi, &klines, "SYNTH", SizingMode::Fixed, &limits);
+        assert!(plain.decayed_score.is_none());
+        let limits = limits.with_decay_half_life(Some(3.0));
+        let result = run_backtest(&rsi, &klines, "SYNTH", SizingMode::Fixed, &limits);
+        assert!(result.total_trades > 1);
+        let score = result.decayed_score.expect("decayed score");
+        let hash = compute_params_hash(&rsi, "SYNTH", 30, SizingMode::Fixed);
+        let record = result_to_record(&result, &hash, "run", "phase1", 30);
+        assert_eq!(record.decayed_score, Some(dec_to_f64(score)));
+        let stored = record_to_result(record).unwrap().decayed_score.unwrap();
+        assert!((stored - score).abs() < dec!(0.000001));
+    }
+
+    #[test]
+    fn test_recent_windows_screen_stale_strategies() {
+        use crate::types::{BacktestTrade, ExitReason, TradeSide};
+        const DAY: i64 = 86_400_000;
+        let trade = |exit_time: i64, pnl: Decimal| BacktestTrade {
+            entry_time: exit_time - 1,
+            exit_time,
+            side: TradeSide::Buy,
+            entry_price: dec!(0.5),
+            exit_price: dec!(0.5),
+            size: dec!(100),
+            pnl,
+            pnl_pct: pnl,
+            exit_reason: ExitReason::Signal,
+        };
+        // Won early in the period, lost over the last month
+        let end = 180 * DAY;
+        let mut trades: Vec<_> = (0..10).map(|d| trade(d * DAY, dec!(20))).collect();
+        trades.extend((0..4).map(|d| trade(end - 40 * DAY + d * DAY, dec!(5))));
+        trades.extend((0..6).map(|d| trade(end - d * DAY, dec!(-10))));
+
+        let last_30d = recent_trades(&trades, 40.0, end, 30);
+        assert_eq!(last_30d.total_trades, 6);
+        assert_eq!(last_30d.win_rate, Decimal::ZERO);
+        assert_eq!(last_30d.net_pnl, dec!(-72));
+        let last_90d = recent_trades(&trades, 40.0, end, 90);
+        assert_eq!(last_90d.total_trades, 10);
+        assert_eq!(last_90d.win_rate, dec!(40));
+        let later = recent_trades(&trades, 0.0, end + 60 * DAY, 30);
+        assert_eq!(later.total_trades, 0);
+
+        // Generic backtests score both windows, and the record keeps them
+        let klines = crate::synthetic::generate_klines(&crate::synthetic::SyntheticConfig {
+            bars: 2_000,
+            ..Default::default()
+        });
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let limits = BacktestLimits::default();
+        let result = run_backtest(&rsi, &klines, "SYNTH", SizingMode::Fixed, &limits);
+        let (recent_30d, recent_90d) = (result.recent_30d.unwrap(), result.recent_90d.unwrap());
+        assert!(recent_30d.total_trades <= recent_90d.total_trades);
+        assert!(recent_90d.total_trades <= result.total_trades);
+        let hash = compute_params_hash(&rsi, "SYNTH", 30, SizingMode::Fixed);
+        let record = result_to_record(&result, &hash, "run", "phase1", 30);
+        assert_eq!(record.trades_30d, Some(recent_30d.total_trades as i64));
+        let back = record_to_result(record).unwrap().recent_30d.unwrap();
+        assert_eq!(back.total_trades, recent_30d.total_trades);
+        assert!((back.score - recent_30d.score).abs() < dec!(0.000001));
+    }
+
+    #[test]
+    fn test_pnl_concentration_flags_lucky_weeks() {
+        use crate::types::{BacktestTrade, ExitReason, TradeSide, CONCENTRATED_WEEK_SHARE};
+        const WEEK: i64 = 7 * 86_400_000;
+        let trade = |exit_time: i64, pnl: Decimal| BacktestTrade {
+            entry_time: exit_time - 1,
+            exit_time,
+            side: TradeSide::Buy,
+            entry_price: dec!(0.5),
+            exit_price: dec!(0.5),
+            size: dec!(100),
+            pnl,
+            pnl_pct: pnl,
+            exit_reason: ExitReason::Signal,
+        };
+        // The same PnL every week: 1/n each
+        let steady: Vec<_> = (0..8).map(|w| trade(w * WEEK + 1, dec!(10))).collect();
+        let spread = PnlConcentration::from_trades(&steady).unwrap();
+        assert!((spread.hhi - 0.125).abs() < 1e-12);
+        assert_eq!(spread.active_weeks, 8);
+        assert!(!spread.is_concentrated());
+
+        // One lucky week carries the result; losses count towards the spread too
+        let mut lucky = steady.clone();
+        lucky.extend((0..5).map(|_| trade(3 * WEEK + 2, dec!(30))));
+        lucky.push(trade(9 * WEEK, dec!(-10)));
+        let lucky = PnlConcentration::from_trades(&lucky).unwrap();
+        assert_eq!(lucky.active_weeks, 9);
+        assert!(lucky.top_week_share > CONCENTRATED_WEEK_SHARE);
+        assert!(lucky.hhi > spread.hhi);
+        assert!(lucky.is_concentrated());
+        assert_eq!(PnlConcentration::from_trades(&[]), None);
+        let even = [trade(0, Decimal::ZERO)];
+        assert_eq!(PnlConcentration::from_trades(&even), None);
+
+        // Backtests measure it and the record keeps it
+        let klines = crate::synthetic::generate_klines(&crate::synthetic::SyntheticConfig {
+            bars: 2_000,
+            ..Default::default()
+        });
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let limits = BacktestLimits::default();
+        let result = run_backtest(&rsi, &klines, "SYNTH", SizingMode::Fixed, &limits);
+        let concentration = result.pnl_concentration.unwrap();
+        assert!(concentration.hhi > 0.0 && concentration.hhi <= 1.0);
+        let hash = compute_params_hash(&rsi, "SYNTH", 30, SizingMode::Fixed);
+        let record = result_to_record(&result, &hash, "run", "phase1", 30);
+        assert_eq!(record.pnl_hhi, Some(concentration.hhi));
+        let back = record_to_result(record).unwrap();
+        assert_eq!(back.pnl_concentration, Some(concentration));
+    }
+
+    #[test]
+    fn test_drawdown_duration_and_recovery() {
+        // Peak at 0, under water 1-4, back at 5; new peaks in a row are no drawdown, a
+        // dip at 7 is back at 8; under water again from 9 to the end
+        let mut tracker = UnderwaterTracker::default();
+        let peaks = [
+            true, false, false, false, false, true, true, false, true, false, false,
+        ];
+        for (idx, at_peak) in peaks.into_iter().enumerate() {
+            tracker.on_bar(idx, at_peak);
+        }
+        assert_eq!(tracker.longest, 5);
+        assert_eq!(tracker.recoveries, vec![5, 2]);
+        // 15-minute bars
+        let (longest, recovery) = tracker.days(900_000);
+        assert_eq!(longest, f64_to_dec(5.0 / 96.0));
+        assert_eq!(recovery, Some(f64_to_dec(3.5 / 96.0)));
+        let (_, none) = UnderwaterTracker::default().days(900_000);
+        assert_eq!(none, None);
+
+        let klines = crate::synthetic::generate_klines(&crate::synthetic::SyntheticConfig {
+            bars: 2_000,
+            ..Default::default()
+        });
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let limits = BacktestLimits::default();
+        let result = run_backtest(&rsi, &klines, "SYNTH", SizingMode::Fixed, &limits);
+        let longest = result.longest_drawdown_days.unwrap();
+        assert!(longest > Decimal::ZERO && longest <= dec!(2000) / dec!(96));
+        if let Some(recovery) = result.avg_recovery_days {
+            assert!(recovery <= longest);
+        }
+
+        // The penalty scales with the days under water
+        assert_eq!(underwater_penalty(&result, Decimal::ZERO), Decimal::ZERO);
+        assert_eq!(underwater_penalty(&result, dec!(2)), longest * dec!(2));
+        let unmeasured = DiscoveryResult {
+            longest_drawdown_days: None,
+            ..result.clone()
+        };
+        assert_eq!(underwater_penalty(&unmeasured, dec!(2)), Decimal::ZERO);
+
+        let hash = compute_params_hash(&rsi, "SYNTH", 30, SizingMode::Fixed);
+        let record = result_to_record(&result, &hash, "run", "phase1", 30);
+        let back = record_to_result(record).unwrap();
+        let tolerance = dec!(0.000001);
+        assert!((back.longest_drawdown_days.unwrap() - longest).abs() < tolerance);
+        assert_eq!(
+            back.avg_recovery_days.is_some(),
+            result.avg_recovery_days.is_some()
+        );
+    }
+
+    #[test]
+    fn test_exposure_tracked_and_capped() {
+        let klines = crate::synthetic::generate_klines(&crate::synthetic::SyntheticConfig {
+            bars: 2_000,
+            ..Default::default()
+        });
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let run = |limits: &BacktestLimits| {
+            let mut generator =
+                IndicatorSeriesCache::new(1).build_generator(&rsi, "SYNTH", &klines);
+            run_generic_backtest(
+                generator.as_mut(),
+                &klines,
+                dec!(10000),
+                dec!(40),
+                SizingMode::Fixed,
+                &PolymarketFeeConfig::default(),
+                limits,
+            )
+        };
+
+        // 40% entries: about 40% of equity at entry, drifting with the price after
+        let full = run(&BacktestLimits::unlimited());
+        let max = full.max_exposure_pct.unwrap();
+        let avg = full.avg_exposure_pct.unwrap();
+        assert!(max > dec!(30) && max < dec!(80), "{max}");
+        assert!(avg > Decimal::ZERO && avg < max);
+
+        // The cap shrinks every entry, hence the exposure
+        let capped = run(&BacktestLimits::unlimited().with_max_exposure(Some(10.0)));
+        assert!(capped.max_exposure_pct.unwrap() < max / dec!(2));
+        assert!(capped.avg_exposure_pct.unwrap() < avg);
+
+        // The request sets the cap; a non-positive one is ignored
+        let mut request: DiscoveryRequest =
+            serde_json::from_str(r#"{"symbols": ["BTCUSDT"], "max_exposure_pct": 10}"#).unwrap();
+        let cap = |r: &DiscoveryRequest| BacktestLimits::from_request(r).max_exposure_pct;
+        assert_eq!(cap(&request), Some(10.0));
+        request.max_exposure_pct = Some(0.0);
+        assert_eq!(cap(&request), None);
+
+        let result = run_backtest(
+            &rsi,
+            &klines,
+            "SYNTH",
+            SizingMode::Fixed,
+            &BacktestLimits::default(),
+        );
+        let hash = compute_params_hash(&rsi, "SYNTH", 30, SizingMode::Fixed);
+        let record = result_to_record(&result, &hash, "run", "phase1", 30);
+        let back = record_to_result(record).unwrap();
+        let tolerance = dec!(0.000001);
+        let stored = back.max_exposure_pct.unwrap() - result.max_exposure_pct.unwrap();
+        assert!(stored.abs() < tolerance);
+        let stored = back.avg_exposure_pct.unwrap() - result.avg_exposure_pct.unwrap();
+        assert!(stored.abs() < tolerance);
+    }
+
+    #[test]
+    fn test_participation_limit_fills_part_of_the_entries() {
+        let klines = crate::synthetic::generate_klines(&crate::synthetic::SyntheticConfig {
+            bars: 2_000,
+            ..Default::default()
+        });
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let run = |klines: &[Kline], participation: Option<ParticipationLimit>| {
+            let mut generator = IndicatorSeriesCache::new(1).build_generator(&rsi, "SYNTH", klines);
+            let limits = BacktestLimits::unlimited().with_participation(participation);
+            run_generic_backtest(
+                generator.as_mut(),
+                klines,
+                dec!(10000),
+                dec!(40),
+                SizingMode::Fixed,
+                &PolymarketFeeConfig::default(),
+                &limits,
+            )
+        };
+        let limit = |max_pct: f64, remainder| Some(ParticipationLimit { max_pct, remainder });
+        let full = run(&klines, None);
+        assert_eq!(full.unfilled, None);
+
+        // Volume far above the orders: nothing changes
+        let deep = run(&klines, limit(50.0, UnfilledRemainder::Carry));
+        let stats = deep.unfilled.unwrap();
+        assert!(stats.entries > 0);
+        assert_eq!((stats.partial_entries, stats.unfilled_pct), (0, 0.0));
+        assert_eq!(deep.total_pnl, full.total_pnl);
+
+        // A thin market: 1% of 0.4 units a bar, where an entry wants ~0.08 (4000 at
+        // ~50 000), so every entry fills over several bars or is cut to 0.004
+        let thin: Vec<Kline> = klines
+            .iter()
+            .map(|k| Kline {
+                volume: dec!(0.4),
+                ..k.clone()
+            })
+            .collect();
+        let carried = run(&thin, limit(1.0, UnfilledRemainder::Carry));
+        let cancelled = run(&thin, limit(1.0, UnfilledRemainder::Cancel));
+        let (carry, cancel) = (carried.unfilled.unwrap(), cancelled.unfilled.unwrap());
+        assert!(carry.entries > 0 && carry.partial_entries == carry.entries);
+        assert!(carry.unfilled_pct > 0.0 && carry.unfilled_pct < 100.0);
+        assert!(cancel.unfilled_pct > carry.unfilled_pct);
+        // About 2% of equity in each cancelled entry, more once carried fills add up
+        let cancelled_max = cancelled.max_exposure_pct.unwrap();
+        assert!(cancelled_max < dec!(3), "{cancelled_max}");
+        assert!(carried.max_exposure_pct.unwrap() > cancelled_max);
+        assert!(carried.max_exposure_pct.unwrap() < full.max_exposure_pct.unwrap());
+    }
+
     #[test]
     fn test_continuous_progress_fields() {
         let progress = DiscoveryProgress::new();
@@ -4704,6 +10391,7 @@ mod tests {
             dec!(10),
             SizingMode::Fixed,
             &fee_config,
+            &BacktestLimits::unlimited(),
         );
 
         // Should run without panicking; just verify it completed
@@ -4771,4 +10459,387 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_relative_strength_grid_and_basket_result() {
+        assert!(generate_rotation_grid(1).is_empty());
+        let grid = generate_rotation_grid(3);
+        assert_eq!(grid.len(), 7 * 2);
+        assert!(grid.iter().all(|s| s.is_cross_sectional()
+            && matches!(s, DiscoveryStrategyType::RelativeStrength { top_k, .. } if *top_k < 3)));
+
+        let up: Vec<f64> = (0..300).map(|i| 100.0 + i as f64 * 0.5).collect();
+        let down: Vec<f64> = (0..300).map(|i| 200.0 - i as f64 * 0.3).collect();
+        let wave: Vec<f64> = (0..300)
+            .map(|i| 150.0 + (i as f64 / 10.0).sin() * 5.0)
+            .collect();
+        let series = vec![
+            ("BTCUSDT".to_string(), make_klines(&up)),
+            ("ETHUSDT".to_string(), make_klines(&down)),
+            ("SOLUSDT".to_string(), make_klines(&wave)),
+        ];
+        let matrix = KlineMatrix::align(&series).unwrap();
+
+        let strategy = DiscoveryStrategyType::RelativeStrength {
+            lookback: 16,
+            top_k: 1,
+        };
+        let result = run_rotation_backtest_for_discovery(
+            &strategy,
+            &matrix,
+            dec!(10000),
+            &PolymarketFeeConfig::default(),
+        );
+        assert_eq!(result.symbol, "BTCUSDT+ETHUSDT+SOLUSDT");
+        assert_eq!(result.strategy_name, "Relative Strength");
+        assert!(result.total_trades >= 1);
+        assert!(
+            result.net_pnl > Decimal::ZERO,
+            "holding the trending symbol should pay"
+        );
+        assert_eq!(result.gross_pnl - result.total_fees, result.net_pnl);
+
+        let record = result_to_record(&result, "h", "run", "phase1", 30);
+        assert_eq!(record.strategy_type, "relative_strength");
+        let back = record_to_result(record).unwrap();
+        assert!(matches!(
+            back.strategy_type,
+            DiscoveryStrategyType::RelativeStrength {
+                lookback: 16,
+                top_k: 1
+            }
+        ));
+        assert!(generate_refinement_grid(&strategy)
+            .iter()
+            .all(|s| s.is_cross_sectional()));
+    }
+
+    #[tokio::test]
+    async fn test_cross_sectional_batch_reuses_store() {
+        let up: Vec<f64> = (0..300).map(|i| 100.0 + i as f64 * 0.5).collect();
+        let down: Vec<f64> = (0..300).map(|i| 200.0 - i as f64 * 0.3).collect();
+        let series = vec![
+            ("BTCUSDT".to_string(), make_klines(&up)),
+            ("ETHUSDT".to_string(), make_klines(&down)),
+            ("SOLUSDT".to_string(), make_klines(&down)),
+        ];
+        let matrix = KlineMatrix::align(&series).unwrap();
+        let grid = generate_rotation_grid(3);
+        let store: &'static MemoryResultStore = Box::leak(Box::default());
+        let progress = DiscoveryProgress::new();
+        let streamed = Arc::new(AtomicU32::new(0));
+        let counter = streamed.clone();
+        progress.set_result_sink(Some(Arc::new(move |_: &DiscoveryResult| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        })));
+        let fees = PolymarketFeeConfig::default();
+        let run = |store: &'static dyn ResultStore| {
+            run_cross_sectional_batch(
+                &grid,
+                &matrix,
+                30,
+                dec!(10000),
+                &fees,
+                Some(store),
+                "run",
+                "phase1",
+                &progress,
+            )
+        };
+
+        let (first, cached) = run(store).await;
+        assert_eq!((first.len(), cached), (grid.len(), 0));
+        assert_eq!(store.len(), grid.len());
+        let basket = &progress.breakdown().by_symbol[0];
+        assert_eq!(basket.name, matrix.label());
+        assert_eq!(basket.total, grid.len() as u32);
+        assert_eq!(basket.completed, basket.total);
+
+        // Second pass is served entirely from the store, and streamed all the same
+        let (second, cached) = run(store).await;
+        assert_eq!(cached as usize, grid.len());
+        assert_eq!(streamed.load(Ordering::Relaxed) as usize, 2 * grid.len());
+        assert_eq!(second[0].total_trades, first[0].total_trades);
+
+        // A no-op store never caches anything
+        let (_, cached) = run(&NoopResultStore).await;
+        assert_eq!(cached, 0);
+    }
+
+    #[test]
+    fn test_pair_spread_grid_and_pair_result() {
+        let symbols: Vec<String> = ["BTCUSDT", "ETHUSDT", "SOLUSDT"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let grid = generate_pair_spread_grid(&symbols);
+        // 3 pairs × 4 lookbacks × 3 entries × 2 exits
+        assert_eq!(grid.len(), 3 * 4 * 3 * 2);
+        assert!(grid.iter().all(|s| s.is_cross_sectional()
+            && matches!(s, DiscoveryStrategyType::PairSpread { leg_a, leg_b, .. } if leg_a < leg_b)));
+
+        // ETH tracks BTC at a 0.5 ratio with noise, and dislocates by 3% every 50 bars
+        let btc: Vec<f64> = (0..400).map(|i| 100.0 + i as f64 * 0.2).collect();
+        let eth: Vec<f64> = btc
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let wobble = match i % 50 {
+                    45..=47 => 0.03,
+                    _ if i % 2 == 0 => 0.002,
+                    _ => -0.002,
+                };
+                p * 0.5 * (1.0 + wobble)
+            })
+            .collect();
+        let series = vec![
+            ("BTCUSDT".to_string(), make_klines(&btc)),
+            ("ETHUSDT".to_string(), make_klines(&eth)),
+        ];
+        let matrix = KlineMatrix::align(&series).unwrap();
+        assert_eq!(generate_cross_sectional_grid(&matrix).len(), 7 + 24);
+
+        let strategy = DiscoveryStrategyType::PairSpread {
+            leg_a: "ETHUSDT".to_string(),
+            leg_b: "BTCUSDT".to_string(),
+            lookback: 48,
+            entry_z: 1.5,
+            exit_z: 0.0,
+        };
+        let result = run_pair_spread_backtest_for_discovery(
+            &strategy,
+            &matrix,
+            dec!(10000),
+            &PolymarketFeeConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(result.symbol, "ETHUSDT/BTCUSDT");
+        assert_eq!(result.strategy_name, "Pair Spread");
+        assert!(result.total_trades >= 6, "one round trip per dislocation");
+        assert!(result.win_rate > dec!(50));
+        assert!(result.total_fees > Decimal::ZERO);
+        assert_eq!(result.gross_pnl - result.total_fees, result.net_pnl);
+
+        let record = result_to_record(&result, "h", "run", "phase1", 30);
+        assert_eq!(record.strategy_type, "pair_spread");
+        assert!(matches!(
+            record_to_result(record).unwrap().strategy_type,
+            DiscoveryStrategyType::PairSpread { lookback: 48, .. }
+        ));
+
+        // Unknown leg: nothing to run
+        let missing = DiscoveryStrategyType::PairSpread {
+            leg_a: "XRPUSDT".to_string(),
+            leg_b: "BTCUSDT".to_string(),
+            lookback: 48,
+            entry_z: 2.0,
+            exit_z: 0.5,
+        };
+        assert!(run_pair_spread_backtest_for_discovery(
+            &missing,
+            &matrix,
+            dec!(10000),
+            &PolymarketFeeConfig::default()
+        )
+        .is_none());
+        assert!(generate_refinement_grid(&strategy).iter().all(|s| matches!(
+            s,
+            DiscoveryStrategyType::PairSpread { entry_z, exit_z, .. } if exit_z < entry_z
+        )));
+    }
+
+    #[test]
+    fn test_onnx_grid_refinement_and_record() {
+        assert!(generate_onnx_grid(&[]).is_empty());
+        let models = vec!["models/a.onnx".to_string(), "models/b.onnx".to_string()];
+        let grid = generate_onnx_grid(&models);
+        assert_eq!(grid.len(), 2 * 4);
+        assert!(grid
+            .iter()
+            .all(|s| !s.is_cross_sectional() && s.name() == "ONNX Model"));
+
+        let strategy = DiscoveryStrategyType::OnnxModel {
+            model_path: "models/a.onnx".to_string(),
+            threshold: 0.9,
+        };
+        let refined = generate_refinement_grid(&strategy);
+        assert_eq!(refined.len(), 5);
+        assert!(refined.iter().all(|s| matches!(
+            s,
+            DiscoveryStrategyType::OnnxModel { model_path, threshold }
+                if model_path == "models/a.onnx" && (0.34..=0.95).contains(threshold)
+        )));
+
+        // The model path is part of the cache hash
+        let other = DiscoveryStrategyType::OnnxModel {
+            model_path: "models/b.onnx".to_string(),
+            threshold: 0.9,
+        };
+        assert_ne!(
+            compute_params_hash(&strategy, "BTCUSDT", 30, SizingMode::Fixed),
+            compute_params_hash(&other, "BTCUSDT", 30, SizingMode::Fixed)
+        );
+
+        // A missing model backtests as a strategy that never trades
+        let closes: Vec<f64> = (0..200)
+            .map(|i| 100.0 + (i as f64 / 7.0).sin() * 4.0)
+            .collect();
+        let result = run_indicator_backtest_for_discovery(
+            &strategy,
+            &make_klines(&closes),
+            "BTCUSDT",
+            dec!(10000),
+            dec!(10),
+            SizingMode::Fixed,
+            &PolymarketFeeConfig::default(),
+            &BacktestLimits::default(),
+            &IndicatorSeriesCache::new(1),
+        );
+        assert_eq!(result.total_trades, 0);
+        let record = result_to_record(&result, "h", "run", "phase1", 30);
+        assert_eq!(record.strategy_type, "onnx_model");
+        assert!(matches!(
+            record_to_result(record).unwrap().strategy_type,
+            DiscoveryStrategyType::OnnxModel { .. }
+        ));
+    }
+
+    #[test]
+    fn test_warmup_held_and_left_out_of_the_test_period() {
+        // Buys on odd bars and sells on even ones, after a declared warm-up
+        struct Flip(usize, usize);
+        impl SignalGenerator for Flip {
+            fn name(&self) -> &str {
+                "Flip"
+            }
+
+            fn warmup_bars(&self) -> usize {
+                self.1
+            }
+
+            fn on_bar(&mut self, _kline: &Kline) -> SignalWithConfidence {
+                self.0 += 1;
+                if self.0 % 2 == 1 {
+                    SignalWithConfidence::buy(1.0)
+                } else {
+                    SignalWithConfidence::sell(1.0)
+                }
+            }
+
+            fn reset(&mut self) {
+                self.0 = 0;
+            }
+        }
+        // 10 days of 15m bars
+        let klines = make_klines(&[100.0; 960]);
+        let run = |warmup: usize| {
+            run_generic_backtest(
+                &mut Flip(0, warmup),
+                &klines,
+                dec!(10000),
+                dec!(10),
+                SizingMode::Fixed,
+                &PolymarketFeeConfig::default(),
+                &BacktestLimits::unlimited(),
+            )
+        };
+        let cold = run(0);
+        assert_eq!(cold.total_trades, 480);
+        assert_eq!(cold.warmup_bars, Some(0));
+        assert_eq!(cold.effective_days, Some(dec!(10)));
+
+        // Two days of warm-up: no trade in them, eight days tested
+        let warm = run(192);
+        assert_eq!(warm.total_trades, 384);
+        assert_eq!(warm.warmup_bars, Some(192));
+        assert_eq!(warm.effective_days, Some(dec!(8)));
+
+        // A warm-up longer than the klines tests nothing
+        let never = run(5_000);
+        assert_eq!(never.total_trades, 0);
+        assert_eq!(never.warmup_bars, Some(960));
+        assert_eq!(never.effective_days, Some(Decimal::ZERO));
+
+        // Each generator declares its warm-up; filters and combos the longest of theirs
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let macd = DiscoveryStrategyType::Macd {
+            fast: 12,
+            slow: 26,
+            signal: 9,
+        };
+        assert_eq!(build_signal_generator(&rsi).warmup_bars(), 15);
+        assert_eq!(build_signal_generator(&macd).warmup_bars(), 35);
+        let filtered = DiscoveryStrategyType::Filtered {
+            inner: Box::new(rsi.clone()),
+            filter: Default::default(),
+        };
+        assert_eq!(build_signal_generator(&filtered).warmup_bars(), 95);
+        let ensemble = DiscoveryStrategyType::Ensemble {
+            members: vec![rsi.clone(), macd.clone()],
+            weights: vec![1.0, 1.0],
+            vote: crate::ensemble::EnsembleVote::Majority,
+        };
+        assert_eq!(build_signal_generator(&ensemble).warmup_bars(), 35);
+
+        // Stored with the result
+        let klines = crate::synthetic::generate_klines(&crate::synthetic::SyntheticConfig {
+            bars: 2_000,
+            ..Default::default()
+        });
+        let result = run_backtest(
+            &macd,
+            &klines,
+            "SYNTH",
+            SizingMode::Fixed,
+            &BacktestLimits::default(),
+        );
+        assert_eq!(result.warmup_bars, Some(35));
+        let hash = compute_params_hash(&macd, "SYNTH", 30, SizingMode::Fixed);
+        let record = result_to_record(&result, &hash, "run", "phase1", 30);
+        let back = record_to_result(record).unwrap();
+        assert_eq!(back.warmup_bars, Some(35));
+        let stored = back.effective_days.unwrap() - result.effective_days.unwrap();
+        assert!(stored.abs() < dec!(0.000001));
+    }
+
+    #[test]
+    fn test_panicking_backtest_is_recorded_and_skipped() {
+        let klines = crate::synthetic::generate_klines(&crate::synthetic::SyntheticConfig {
+            bars: 500,
+            ..Default::default()
+        });
+        let limits = BacktestLimits::default();
+        let backtest = |strategy: &DiscoveryStrategyType| {
+            run_backtest(strategy, &klines, "SYNTH", SizingMode::Fixed, &limits)
+        };
+        let progress = DiscoveryProgress::new();
+        // `ta` rejects a zero period and the generator's constructor panics
+        let broken = DiscoveryStrategyType::Rsi {
+            period: 0,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        assert!(progress
+            .isolate("SYNTH", &broken, "phase1", || backtest(&broken))
+            .is_none());
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let result = progress.isolate("SYNTH", &rsi, "phase1", || backtest(&rsi));
+        assert_eq!(result.unwrap().strategy_name, rsi.name());
+
+        assert_eq!(progress.failed.load(Ordering::Relaxed), 1);
+        let failed = progress.failed_combos.read().unwrap().clone();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].phase, "phase1");
+        assert!(failed[0].reason.contains("Invalid RSI period"));
+        progress.reset();
+        assert!(progress.failed_combos.read().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,410 @@
+//! `DiscoveryEngine` — embeddable entry point of the discovery agent
+//!
+//! Wraps the one-shot and continuous runners behind a builder, so a Rust program can
+//! run discovery without the server's spawning and progress plumbing:
+//!
+//! ```no_run
+//! # async fn demo() -> anyhow::Result<()> {
+//! use std::sync::Arc;
+//! use engine::{DiscoveryEngine, MemoryResultStore};
+//!
+//! let store = Arc::new(MemoryResultStore::new());
+//! let results = DiscoveryEngine::new()
+//!     .with_symbols(["BTCUSDT", "ETHUSDT"])
+//!     .with_days(90)
+//!     .with_store(store.clone())
+//!     .with_progress_sink(|p| println!("{:.0}%", p.progress_pct()))
+//!     .run()
+//!     .await?;
+//! println!("{} strategies, {} stored", results.len(), store.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use persistence::repository::RankKey;
+use rust_decimal::Decimal;
+
+use crate::api::BinanceClient;
+use crate::candles::CandleTransform;
+use crate::discovery::{
+    run_continuous_discovery, run_discovery, DiscoveryProgress, DiscoveryRequest, DiscoveryResult,
+    RefinementDepth, SizingMode,
+};
+use crate::distributed::Coordinator;
+use crate::error::EngineResult;
+use crate::pacing::ScanPacing;
+use crate::result_stream::ResultSink;
+use crate::store::ResultStore;
+
+/// Symbols scanned when none are given (same as the CLI)
+pub const DEFAULT_SYMBOLS: [&str; 4] = ["BTCUSDT", "ETHUSDT", "SOLUSDT", "XRPUSDT"];
+
+/// Default delay between two progress sink calls
+const DEFAULT_SINK_INTERVAL: Duration = Duration::from_millis(500);
+
+type ProgressSink = Box<dyn Fn(&DiscoveryProgress) + Send + Sync>;
+
+/// Builder and runner of a discovery scan
+pub struct DiscoveryEngine {
+    request: DiscoveryRequest,
+    binance: Option<Arc<BinanceClient>>,
+    store: Option<Arc<dyn ResultStore>>,
+    progress: Arc<DiscoveryProgress>,
+    sink: Option<ProgressSink>,
+    sink_interval: Duration,
+    result_sink: Option<Arc<dyn ResultSink>>,
+    coordinator: Option<Arc<Coordinator>>,
+}
+
+impl DiscoveryEngine {
+    /// One-shot scan of `DEFAULT_SYMBOLS` over 365 days, nothing stored
+    pub fn new() -> Self {
+        Self::from_request(DiscoveryRequest {
+            symbols: DEFAULT_SYMBOLS.iter().map(|s| s.to_string()).collect(),
+            days: 365,
+            top_n: None,
+            per_symbol_top_n: None,
+            sizing_mode: None,
+            continuous: None,
+            onnx_models: Vec::new(),
+            time_budget_ms: None,
+            ruin_threshold_pct: None,
+            repair_gaps: None,
+            data_source: None,
+            fee_preset: None,
+            probability_model: None,
+            fill_model: None,
+            participation: None,
+            throttle_search: None,
+            cross_symbol: None,
+            cross_symbol_weight: None,
+            refinement_top_k: None,
+            refinement_rounds: None,
+            refinement_delta_scale: None,
+            local_search: None,
+            adaptive_cycles: None,
+            decay_half_life_days: None,
+            underwater_penalty: None,
+            max_exposure_pct: None,
+            exclude_strategy_types: Vec::new(),
+            pacing: None,
+            candle_transform: None,
+            rank_by: None,
+            restart_on_crash: None,
+        })
+    }
+
+    /// Start from a full request (HTTP body, CLI flags)
+    pub fn from_request(request: DiscoveryRequest) -> Self {
+        Self {
+            request,
+            binance: None,
+            store: None,
+            progress: Arc::new(DiscoveryProgress::new()),
+            sink: None,
+            sink_interval: DEFAULT_SINK_INTERVAL,
+            result_sink: None,
+            coordinator: None,
+        }
+    }
+
+    pub fn with_symbols<I, S>(mut self, symbols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.request.symbols = symbols.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_days(mut self, days: u32) -> Self {
+        self.request.days = days;
+        self
+    }
+
+    pub fn with_top_n(mut self, top_n: usize) -> Self {
+        self.request.top_n = Some(top_n);
+        self
+    }
+
+    /// Keep the best `count` results of each symbol in the final ranking, on top of the
+    /// global top-N
+    pub fn with_per_symbol_top_n(mut self, count: usize) -> Self {
+        self.request.per_symbol_top_n = Some(count);
+        self
+    }
+
+    pub fn with_sizing_mode(mut self, sizing_mode: SizingMode) -> Self {
+        self.request.sizing_mode = Some(sizing_mode);
+        self
+    }
+
+    /// Leave these strategy families (`DiscoveryStrategyType::type_tag()`) out of the grids
+    pub fn excluding<I, S>(mut self, strategy_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.request.exclude_strategy_types = strategy_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Hill-climb the best result of each strategy family after refinement (one-shot
+    /// scans)
+    pub fn with_local_search(mut self, local_search: bool) -> Self {
+        self.request.local_search = Some(local_search);
+        self
+    }
+
+    /// Plan continuous cycles 0-2 from the knowledge base coverage (continuous runs
+    /// with a store)
+    pub fn with_adaptive_cycles(mut self, adaptive_cycles: bool) -> Self {
+        self.request.adaptive_cycles = Some(adaptive_cycles);
+        self
+    }
+
+    /// Also score each result with its trades decayed by recency (half-life in days)
+    pub fn with_decay_half_life(mut self, half_life_days: f64) -> Self {
+        self.request.decay_half_life_days = Some(half_life_days);
+        self
+    }
+
+    /// Take `per_day` composite points off each result per day of its longest drawdown
+    /// in the final ranking (one-shot scans)
+    pub fn with_underwater_penalty(mut self, per_day: Decimal) -> Self {
+        self.request.underwater_penalty = Some(per_day);
+        self
+    }
+
+    /// Cap every position at `max_pct` of equity, whatever the sizing mode
+    pub fn with_max_exposure(mut self, max_pct: f64) -> Self {
+        self.request.max_exposure_pct = Some(max_pct);
+        self
+    }
+
+    /// Limit the scan's backtest rate or CPU share; `progress().set_pacing()` changes it
+    /// while the scan runs
+    pub fn with_pacing(mut self, pacing: ScanPacing) -> Self {
+        self.request.pacing = Some(pacing);
+        self
+    }
+
+    /// Rank the results by `order`'s first key, then each next one on ties
+    pub fn with_rank_order(mut self, order: Vec<RankKey>) -> Self {
+        self.request.rank_by = Some(order);
+        self
+    }
+
+    /// Feed the strategies Heikin-Ashi candles or Renko bricks (a what-if scan, nothing
+    /// stored)
+    pub fn with_candle_transform(mut self, candles: CandleTransform) -> Self {
+        self.request.candle_transform = Some(candles);
+        self
+    }
+
+    /// Re-run the best results under drawdown throttle presets (one-shot scans)
+    pub fn with_throttle_search(mut self, throttle_search: bool) -> Self {
+        self.request.throttle_search = Some(throttle_search);
+        self
+    }
+
+    /// Score how the best results generalize to the other symbols (one-shot scans), with
+    /// `weight` (0-1) of that score in the final ranking
+    pub fn with_cross_symbol(mut self, weight: Decimal) -> Self {
+        self.request.cross_symbol = Some(true);
+        self.request.cross_symbol_weight = Some(weight);
+        self
+    }
+
+    /// How many Phase 2 parents, rounds and how wide their parameter steps
+    pub fn with_refinement(mut self, depth: RefinementDepth) -> Self {
+        self.request.refinement_top_k = Some(depth.top_k);
+        self.request.refinement_rounds = Some(depth.rounds);
+        self.request.refinement_delta_scale = Some(depth.delta_scale);
+        self
+    }
+
+    /// Run cycle after cycle until `progress().cancelled` is set
+    pub fn continuous(mut self, continuous: bool) -> Self {
+        self.request.continuous = Some(continuous);
+        self
+    }
+
+    /// Where cached results are read and new ones written (default: none)
+    pub fn with_store(mut self, store: Arc<dyn ResultStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Share a Binance client (default: a new one)
+    pub fn with_binance(mut self, binance: Arc<BinanceClient>) -> Self {
+        self.binance = Some(binance);
+        self
+    }
+
+    /// Report into an existing tracker, e.g. one polled by a status endpoint
+    pub fn with_progress(mut self, progress: Arc<DiscoveryProgress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Called every `interval` while the scan runs, and once at the end
+    pub fn with_progress_sink<F>(mut self, sink: F) -> Self
+    where
+        F: Fn(&DiscoveryProgress) + Send + Sync + 'static,
+    {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    pub fn with_progress_interval(mut self, interval: Duration) -> Self {
+        self.sink_interval = interval;
+        self
+    }
+
+    /// Receives every result as soon as it is finished, e.g. an `NdjsonResultSink`
+    pub fn with_result_sink(mut self, sink: Arc<dyn ResultSink>) -> Self {
+        self.result_sink = Some(sink);
+        self
+    }
+
+    /// Shard the Phase 1 grid to the workers attached to `coordinator` (one-shot scans;
+    /// nothing changes while no worker is attached)
+    pub fn with_coordinator(mut self, coordinator: Arc<Coordinator>) -> Self {
+        self.coordinator = Some(coordinator);
+        self
+    }
+
+    pub fn request(&self) -> &DiscoveryRequest {
+        &self.request
+    }
+
+    /// Tracker of this engine — set `cancelled` on it to stop the scan
+    pub fn progress(&self) -> Arc<DiscoveryProgress> {
+        self.progress.clone()
+    }
+
+    /// Store the engine records its results in, if any
+    pub fn store(&self) -> Option<Arc<dyn ResultStore>> {
+        self.store.clone()
+    }
+
+    /// Run the scan to the end and return the final ranking. The tracker is reset
+    /// first. A cancelled one-shot scan is `Err(Cancelled)`; a continuous one returns
+    /// its best results so far.
+    pub async fn run(self) -> EngineResult<Vec<DiscoveryResult>> {
+        let Self {
+            request,
+            binance,
+            store,
+            progress,
+            sink,
+            sink_interval,
+            result_sink,
+            coordinator,
+        } = self;
+        let binance = binance.unwrap_or_else(|| Arc::new(BinanceClient::new()));
+
+        progress.reset();
+        let streaming = result_sink.is_some();
+        if streaming {
+            progress.set_result_sink(result_sink);
+        }
+        let continuous = request.continuous.unwrap_or(false);
+        let runner = {
+            let progress = progress.clone();
+            async move {
+                if continuous {
+                    run_continuous_discovery(request, binance, progress, store).await
+                } else {
+                    run_discovery(request, binance, progress, store, coordinator).await
+                }
+            }
+        };
+
+        let outcome = match &sink {
+            Some(sink) => {
+                let mut runner = std::pin::pin!(runner);
+                let mut ticker = tokio::time::interval(sink_interval);
+                let outcome = loop {
+                    tokio::select! {
+                        outcome = &mut runner => break outcome,
+                        _ = ticker.tick() => sink(&progress),
+                    }
+                };
+                sink(&progress);
+                outcome
+            }
+            None => runner.await,
+        };
+        if streaming {
+            progress.set_result_sink(None);
+        }
+
+        outcome?;
+        let results = progress.final_results.read().unwrap().clone();
+        Ok(results)
+    }
+}
+
+impl Default for DiscoveryEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::DiscoveryStatus;
+    use crate::error::EngineError;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_engine_reports_errors_through_sink() {
+        let engine = DiscoveryEngine::new()
+            .with_symbols(Vec::<String>::new())
+            .with_days(30)
+            .with_top_n(5);
+        assert_eq!(engine.request().days, 30);
+        assert_eq!(engine.request().top_n, Some(5));
+        assert!(!engine.request().continuous.unwrap_or(false));
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let seen = calls.clone();
+        let progress = engine.progress();
+        let err = engine
+            .with_progress_sink(move |_| {
+                seen.fetch_add(1, Ordering::Relaxed);
+            })
+            .run()
+            .await
+            .unwrap_err();
+
+        // No symbol → no klines → Error status, surfaced as Err without touching the network
+        assert_eq!(
+            err,
+            EngineError::DataFetch("Failed to fetch klines for any symbol".to_string())
+        );
+        assert!(matches!(
+            *progress.status.read().unwrap(),
+            DiscoveryStatus::Error
+        ));
+        assert!(calls.load(Ordering::Relaxed) >= 1);
+
+        // A request the runner cannot start is not a data failure
+        let mut request = DiscoveryEngine::new().request().clone();
+        request.data_source = Some("ftp://klines".to_string());
+        let engine = DiscoveryEngine::from_request(request);
+        let progress = engine.progress();
+        let err = engine.run().await.unwrap_err();
+        assert!(matches!(err, EngineError::InvalidParams(_)));
+        assert_eq!(
+            progress.error_message.read().unwrap().as_deref(),
+            Some(err.to_string().as_str())
+        );
+    }
+}
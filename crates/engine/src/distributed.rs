@@ -0,0 +1,699 @@
+//! Distributed Phase 1: a coordinator and remote backtest workers
+//!
+//! A server that workers are attached to (`poly-discover worker --coordinator URL`,
+//! or the in-process `run_local_worker()` tasks of `serve --local-workers N`) shards
+//! the uncached Phase 1 combinations of a one-shot scan into `GridChunk`s of one
+//! symbol each. Workers lease a chunk, fetch its klines themselves, backtest it with
+//! the run's request and post the results back. A lease that is not completed within
+//! the lease timeout (the worker died or hung) goes back to the queue for the next
+//! worker, and so does a chunk a worker reports as failed, up to `MAX_ATTEMPTS` times.
+//!
+//! The queue is the `work_queue` table: chunks, leases and results outlive a restart
+//! of the server. Workers keep draining the chunks of an interrupted scan, and the
+//! same scan started again adopts them by key instead of queueing new ones.
+//!
+//! The scan keeps a single Phase 1 loop: `distribute_phase1()` waits for the chunks,
+//! then the loop walks its work as usual and takes the remote result of a combination
+//! instead of backtesting it. Chunks still missing when no worker is left are
+//! backtested locally. Continuous runs are not distributed.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use persistence::repository::WorkQueueRepository;
+use persistence::SqlitePool;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::api::BinanceClient;
+use crate::discovery::{
+    compute_params_hash, fetch_run_klines, fetch_run_symbol_filters, run_fees, run_single_backtest,
+    BacktestLimits, DiscoveryProgress, DiscoveryRequest, DiscoveryResult, DiscoveryStrategyType,
+};
+use crate::error::{EngineError, EngineResult};
+use crate::fees::PolymarketFeeConfig;
+use crate::indicators::{IndicatorSeriesCache, SERIES_CACHE_CAPACITY};
+use crate::store::ResultStore;
+use crate::supervisor::catch_panic;
+use crate::types::Kline;
+
+/// Combinations of one chunk
+pub const CHUNK_SIZE: usize = 25;
+
+/// Leases of a chunk before it is left to the local loop
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// Time a worker has to post the results of a chunk before it is reassigned
+pub const DEFAULT_LEASE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Done and failed chunks are kept this long for a restarted scan to adopt
+const FINISHED_RETENTION_SECS: i64 = 24 * 3600;
+
+/// A worker without a lease is no longer counted once silent for this long
+const WORKER_TTL: Duration = Duration::from_secs(30);
+
+/// Polling interval of the scan while it waits for the chunks
+const WAIT_POLL: Duration = Duration::from_millis(500);
+
+/// Capital and base position of the discovery backtests, as in `run_discovery()`
+const INITIAL_CAPITAL: Decimal = dec!(10000);
+const BASE_POSITION_PCT: Decimal = dec!(10);
+
+/// Phase 1 combinations of one symbol, leased to a worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GridChunk {
+    pub id: u64,
+    pub run_id: String,
+    /// Request of the run, its probability model already calibrated
+    pub request: DiscoveryRequest,
+    /// Kline window of the run (ms)
+    pub start_time: i64,
+    pub end_time: i64,
+    pub symbol: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Object>))]
+    pub strategies: Vec<DiscoveryStrategyType>,
+}
+
+/// A worker as seen by the coordinator
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WorkerInfo {
+    pub id: String,
+    /// RFC 3339
+    pub last_seen: String,
+    /// Chunk it holds a lease on
+    pub chunk: Option<u64>,
+    pub chunks_done: u32,
+    pub chunks_failed: u32,
+    /// Counted by the scans: holds a lease or polled in the last 30 s
+    pub active: bool,
+}
+
+/// Queue of the coordinator and its workers
+#[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CoordinatorStatus {
+    /// Run whose chunks are queued, if any
+    pub run_id: Option<String>,
+    pub pending: usize,
+    pub leased: usize,
+    /// Chunks done, kept a day for a restarted scan to reuse
+    pub completed: usize,
+    /// Leases of the current run that expired and went back to the queue
+    pub reassigned: u32,
+    /// Chunks failed `MAX_ATTEMPTS` times, left to the local loop
+    pub abandoned: usize,
+    pub workers: Vec<WorkerInfo>,
+}
+
+struct WorkerState {
+    last_seen: Instant,
+    last_seen_at: DateTime<Utc>,
+    chunks_done: u32,
+    chunks_failed: u32,
+}
+
+#[derive(Default)]
+struct CoordinatorState {
+    run_id: Option<String>,
+    reassigned: u32,
+    workers: HashMap<String, WorkerState>,
+}
+
+impl CoordinatorState {
+    fn touch(&mut self, worker: &str) -> &mut WorkerState {
+        let state = self
+            .workers
+            .entry(worker.to_string())
+            .or_insert(WorkerState {
+                last_seen: Instant::now(),
+                last_seen_at: Utc::now(),
+                chunks_done: 0,
+                chunks_failed: 0,
+            });
+        state.last_seen = Instant::now();
+        state.last_seen_at = Utc::now();
+        state
+    }
+}
+
+/// Work queue of the distributed Phase 1, shared by the scan and the workers
+///
+/// The chunks and their leases live in the `work_queue` table, so they survive a
+/// restart of the coordinator; only the worker registry is kept in memory.
+pub struct Coordinator {
+    pool: SqlitePool,
+    state: Mutex<CoordinatorState>,
+    lease_timeout: Duration,
+}
+
+impl Coordinator {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            state: Mutex::new(CoordinatorState::default()),
+            lease_timeout: DEFAULT_LEASE_TIMEOUT,
+        }
+    }
+
+    pub fn with_lease_timeout(mut self, lease_timeout: Duration) -> Self {
+        self.lease_timeout = lease_timeout;
+        self
+    }
+
+    fn queue(&self) -> WorkQueueRepository<'_> {
+        WorkQueueRepository::new(&self.pool)
+    }
+
+    /// Send the chunks of expired leases back to the queue
+    async fn release_expired(&self) -> EngineResult<()> {
+        let now = Utc::now().timestamp_millis();
+        let released = self
+            .queue()
+            .release_expired(now, MAX_ATTEMPTS as i64)
+            .await?;
+        if released > 0 {
+            warn!(chunks = released, "Chunk leases expired, reassigning");
+            self.state.lock().unwrap().reassigned += released as u32;
+        }
+        Ok(())
+    }
+
+    /// Workers holding a lease or heard from in the last 30 s
+    pub async fn active_workers(&self) -> EngineResult<usize> {
+        self.release_expired().await?;
+        let holders: HashSet<String> = self
+            .queue()
+            .claimed()
+            .await?
+            .into_iter()
+            .filter_map(|item| item.worker_id)
+            .collect();
+        let state = self.state.lock().unwrap();
+        let mut active: HashSet<&str> = holders.iter().map(String::as_str).collect();
+        active.extend(
+            state
+                .workers
+                .iter()
+                .filter(|(_, worker)| worker.last_seen.elapsed() < WORKER_TTL)
+                .map(|(id, _)| id.as_str()),
+        );
+        Ok(active.len())
+    }
+
+    /// Next queued chunk for `worker`, if any
+    pub async fn lease(&self, worker: &str) -> EngineResult<Option<GridChunk>> {
+        self.state.lock().unwrap().touch(worker);
+        self.release_expired().await?;
+        let deadline = Utc::now().timestamp_millis() + self.lease_timeout.as_millis() as i64;
+        let Some(item) = self.queue().claim(worker, deadline).await? else {
+            return Ok(None);
+        };
+        let mut chunk: GridChunk = serde_json::from_str(&item.payload).map_err(|e| {
+            EngineError::Storage(format!("Invalid work queue chunk {}: {}", item.id, e))
+        })?;
+        chunk.id = item.id as u64;
+        chunk.run_id = item.run_id;
+        Ok(Some(chunk))
+    }
+
+    /// Results of a chunk. False when the chunk is already done (by the worker it was
+    /// reassigned to) or no longer queued because its run is over.
+    pub async fn complete(
+        &self,
+        chunk_id: u64,
+        worker: &str,
+        results: Vec<DiscoveryResult>,
+    ) -> EngineResult<bool> {
+        self.state.lock().unwrap().touch(worker);
+        let results = serde_json::to_string(&results)
+            .map_err(|e| EngineError::Storage(format!("Unserializable results: {}", e)))?;
+        // A late worker still wins over a queued copy of its chunk
+        let done = self
+            .queue()
+            .complete(chunk_id as i64, worker, &results)
+            .await?;
+        if done {
+            self.state.lock().unwrap().touch(worker).chunks_done += 1;
+        }
+        Ok(done)
+    }
+
+    /// `worker` could not run the chunk: queue it again, or leave it to the local loop
+    /// after `MAX_ATTEMPTS` leases
+    pub async fn fail(&self, chunk_id: u64, worker: &str, error: &str) -> EngineResult<()> {
+        self.state.lock().unwrap().touch(worker).chunks_failed += 1;
+        let released = self
+            .queue()
+            .fail(chunk_id as i64, worker, error, MAX_ATTEMPTS as i64)
+            .await?;
+        if released {
+            warn!(chunk = chunk_id, worker, error, "Worker failed a chunk");
+        }
+        Ok(())
+    }
+
+    pub async fn status(&self) -> EngineResult<CoordinatorStatus> {
+        self.release_expired().await?;
+        let counts = self.queue().counts().await?;
+        let claimed = self.queue().claimed().await?;
+        let state = self.state.lock().unwrap();
+        let mut workers: Vec<WorkerInfo> = state
+            .workers
+            .iter()
+            .map(|(id, worker)| {
+                let chunk = claimed
+                    .iter()
+                    .find(|item| item.worker_id.as_deref() == Some(id.as_str()))
+                    .map(|item| item.id as u64);
+                WorkerInfo {
+                    id: id.clone(),
+                    last_seen: worker.last_seen_at.to_rfc3339(),
+                    chunk,
+                    chunks_done: worker.chunks_done,
+                    chunks_failed: worker.chunks_failed,
+                    active: chunk.is_some() || worker.last_seen.elapsed() < WORKER_TTL,
+                }
+            })
+            .collect();
+        workers.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(CoordinatorStatus {
+            run_id: state.run_id.clone(),
+            pending: counts.pending as usize,
+            leased: counts.claimed as usize,
+            completed: counts.done as usize,
+            reassigned: state.reassigned,
+            abandoned: counts.failed as usize,
+            workers,
+        })
+    }
+
+    /// Queue the chunks of `run_id`; chunks already queued by an earlier run (or
+    /// before a restart) keep their row and, once done, their results
+    async fn submit(&self, run_id: &str, chunks: &[GridChunk]) -> EngineResult<Vec<i64>> {
+        self.queue().purge_finished(FINISHED_RETENTION_SECS).await?;
+        let items = chunks
+            .iter()
+            .map(|chunk| {
+                let payload = serde_json::to_string(chunk)
+                    .map_err(|e| EngineError::Storage(format!("Unserializable chunk: {}", e)))?;
+                Ok((chunk_key(chunk), payload))
+            })
+            .collect::<EngineResult<Vec<_>>>()?;
+        let rows = self.queue().enqueue(run_id, &items).await?;
+        let mut state = self.state.lock().unwrap();
+        state.run_id = Some(run_id.to_string());
+        state.reassigned = 0;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    /// End the run: drop its chunks that are not done; later results for them are
+    /// refused
+    async fn finish(&self, ids: &[i64]) -> EngineResult<()> {
+        self.queue().cancel(ids).await?;
+        self.state.lock().unwrap().run_id = None;
+        Ok(())
+    }
+}
+
+/// Identity of a chunk across runs: its request, symbol and combinations
+fn chunk_key(chunk: &GridChunk) -> String {
+    let request = serde_json::to_string(&chunk.request).unwrap_or_default();
+    let sizing_mode = chunk.request.sizing_mode.unwrap_or_default();
+    let combinations: Vec<String> = chunk
+        .strategies
+        .iter()
+        .map(|s| compute_params_hash(s, &chunk.symbol, chunk.request.days, sizing_mode))
+        .collect();
+    let input = format!("{}:{}:{}", request, chunk.symbol, combinations.join(","));
+    format!("{:x}", Sha256::digest(input.as_bytes()))
+}
+
+/// Shard the uncached combinations of `work` to the workers of `coordinator` and wait
+/// for them. Returns the remote results by params hash, empty when no worker is
+/// attached.
+pub(crate) async fn distribute_phase1(
+    coordinator: &Coordinator,
+    request: &DiscoveryRequest,
+    (start_time, end_time): (i64, i64),
+    run_id: &str,
+    work: &[(&String, &Vec<Kline>, &DiscoveryStrategyType)],
+    store: Option<&dyn ResultStore>,
+    progress: &DiscoveryProgress,
+) -> EngineResult<HashMap<String, DiscoveryResult>> {
+    let mut remote = HashMap::new();
+    let workers = coordinator.active_workers().await?;
+    if workers == 0 {
+        return Ok(remote);
+    }
+    let sizing_mode = request.sizing_mode.unwrap_or_default();
+    let hash = |strategy: &DiscoveryStrategyType, symbol: &str| {
+        compute_params_hash(strategy, symbol, request.days, sizing_mode)
+    };
+
+    // Uncached combinations of each symbol, in work order
+    let mut by_symbol: Vec<(&String, Vec<DiscoveryStrategyType>)> = Vec::new();
+    for &(symbol, _, strategy) in work {
+        if let Some(store) = store {
+            let cached = store.get_by_hash(&hash(strategy, symbol)).await;
+            if cached.ok().flatten().is_some() {
+                continue;
+            }
+        }
+        match by_symbol.iter_mut().find(|(s, _)| *s == symbol) {
+            Some((_, strategies)) => strategies.push(strategy.clone()),
+            None => by_symbol.push((symbol, vec![strategy.clone()])),
+        }
+    }
+    let chunks: Vec<GridChunk> = by_symbol
+        .iter()
+        .flat_map(|(symbol, strategies)| {
+            strategies.chunks(CHUNK_SIZE).map(|strategies| GridChunk {
+                id: 0,
+                run_id: run_id.to_string(),
+                request: request.clone(),
+                start_time,
+                end_time,
+                symbol: symbol.to_string(),
+                strategies: strategies.to_vec(),
+            })
+        })
+        .collect();
+    if chunks.is_empty() {
+        return Ok(remote);
+    }
+    let total = chunks.len();
+    info!(
+        chunks = total,
+        workers, "Phase 1 sharded to the remote workers"
+    );
+    let ids = coordinator.submit(run_id, &chunks).await?;
+
+    let mut collected = HashSet::new();
+    let outcome = loop {
+        coordinator.release_expired().await?;
+        let mut left = 0;
+        for item in coordinator.queue().get_many(&ids).await? {
+            match item.status.as_str() {
+                "done" if collected.insert(item.id) => {
+                    let results: Vec<DiscoveryResult> = item
+                        .result
+                        .as_deref()
+                        .and_then(|json| serde_json::from_str(json).ok())
+                        .unwrap_or_default();
+                    for result in results {
+                        remote.insert(hash(&result.strategy_type, &result.symbol), result);
+                    }
+                }
+                "pending" | "claimed" => left += 1,
+                _ => {}
+            }
+        }
+        *progress.phase.write().unwrap() = format!(
+            "Phase 1: Broad Scan ({}/{} chunks on remote workers)",
+            total - left,
+            total
+        );
+        if left == 0 {
+            break Ok(());
+        }
+        if progress.cancelled.load(Ordering::Relaxed) {
+            break Err(progress.fail(EngineError::Cancelled));
+        }
+        if coordinator.active_workers().await? == 0 {
+            warn!(
+                chunks = left,
+                "No remote worker left, backtesting the rest locally"
+            );
+            break Ok(());
+        }
+        tokio::time::sleep(WAIT_POLL).await;
+    };
+    coordinator.finish(&ids).await?;
+    *progress.phase.write().unwrap() = "Phase 1: Broad Scan".to_string();
+    outcome?;
+    info!(results = remote.len(), "Remote Phase 1 chunks done");
+    Ok(remote)
+}
+
+/// Lease, run and complete chunks of `coordinator` in-process until the task is
+/// dropped: the local parallelism of a server, on the same queue as remote workers
+pub async fn run_local_worker(
+    coordinator: Arc<Coordinator>,
+    binance: Arc<BinanceClient>,
+    worker_id: String,
+    poll: Duration,
+) {
+    let mut runner = ChunkRunner::new(binance);
+    loop {
+        let chunk = match coordinator.lease(&worker_id).await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => {
+                tokio::time::sleep(poll).await;
+                continue;
+            }
+            Err(e) => {
+                warn!(worker = %worker_id, error = %e, "Work queue unavailable");
+                tokio::time::sleep(poll).await;
+                continue;
+            }
+        };
+        let reply = match runner.run(&chunk).await {
+            Ok(results) => coordinator
+                .complete(chunk.id, &worker_id, results)
+                .await
+                .map(|_| ()),
+            Err(e) => coordinator.fail(chunk.id, &worker_id, &e.to_string()).await,
+        };
+        // The lease expires on its own if the queue missed the reply
+        if let Err(e) = reply {
+            warn!(chunk = chunk.id, worker = %worker_id, error = %e, "Failed to report chunk");
+        }
+    }
+}
+
+/// Klines, limits and fees of the run a worker is on
+struct RunData {
+    run_id: String,
+    fee_config: PolymarketFeeConfig,
+    series_cache: IndicatorSeriesCache,
+    symbols: HashMap<String, (Vec<Kline>, BacktestLimits)>,
+}
+
+/// Backtests the chunks leased by a worker, keeping the klines of the current run
+pub struct ChunkRunner {
+    binance: Arc<BinanceClient>,
+    run: Option<RunData>,
+}
+
+impl ChunkRunner {
+    pub fn new(binance: Arc<BinanceClient>) -> Self {
+        Self { binance, run: None }
+    }
+
+    /// Results of `chunk`, as the coordinator's own Phase 1 loop would compute them
+    pub async fn run(&mut self, chunk: &GridChunk) -> EngineResult<Vec<DiscoveryResult>> {
+        let request = &chunk.request;
+        if self.run.as_ref().map(|run| run.run_id.as_str()) != Some(chunk.run_id.as_str()) {
+            let (fee_config, _) = run_fees(request, &self.binance, None).await;
+            self.run = Some(RunData {
+                run_id: chunk.run_id.clone(),
+                fee_config,
+                series_cache: IndicatorSeriesCache::new(SERIES_CACHE_CAPACITY)
+                    .with_candles(request.candle_transform.unwrap_or_default()),
+                symbols: HashMap::new(),
+            });
+        }
+        let run = self.run.as_mut().unwrap();
+
+        if !run.symbols.contains_key(&chunk.symbol) {
+            let request = DiscoveryRequest {
+                symbols: vec![chunk.symbol.clone()],
+                ..request.clone()
+            };
+            let symbol_klines = fetch_run_klines(
+                &request,
+                &self.binance,
+                (chunk.start_time, chunk.end_time),
+                &DiscoveryProgress::new(),
+                None,
+                &chunk.run_id,
+            )
+            .await?;
+            let filters = fetch_run_symbol_filters(&request, &self.binance, &symbol_klines).await;
+            let limits = BacktestLimits::from_request(&request)
+                .with_symbol_filters(filters.get(&chunk.symbol).copied());
+            let klines = symbol_klines
+                .into_iter()
+                .next()
+                .map(|(_, klines)| klines)
+                .unwrap_or_default();
+            run.symbols.insert(chunk.symbol.clone(), (klines, limits));
+        }
+        let (klines, limits) = &run.symbols[&chunk.symbol];
+
+        let sizing_mode = request.sizing_mode.unwrap_or_default();
+        // A combination whose backtest panics is left out: the coordinator's loop runs
+        // the missing ones itself and records the failure
+        Ok(chunk
+            .strategies
+            .iter()
+            .filter_map(|strategy| {
+                catch_panic(|| {
+                    run_single_backtest(
+                        strategy,
+                        klines,
+                        &chunk.symbol,
+                        INITIAL_CAPITAL,
+                        BASE_POSITION_PCT,
+                        sizing_mode,
+                        &run.fee_config,
+                        limits,
+                        &run.series_cache,
+                    )
+                })
+                .inspect_err(|reason| {
+                    warn!(strategy = strategy.name(), "Backtest panicked: {}", reason)
+                })
+                .ok()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{generate_phase1_grid, SizingMode};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[tokio::test]
+    async fn test_chunks_reassigned_and_collected() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 1_000,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let symbol = "SYNTH".to_string();
+        let grid: Vec<DiscoveryStrategyType> = generate_phase1_grid()
+            .into_iter()
+            .filter(|s| !s.is_gabagool())
+            .take(CHUNK_SIZE + 5)
+            .collect();
+        let work: Vec<_> = grid.iter().map(|s| (&symbol, &klines, s)).collect();
+        let request: DiscoveryRequest = serde_json::from_value(serde_json::json!({
+            "symbols": ["SYNTH"],
+            "days": 30,
+        }))
+        .unwrap();
+
+        let db = persistence::Database::in_memory().await.unwrap();
+        let coordinator = Arc::new(
+            Coordinator::new(db.pool_clone()).with_lease_timeout(Duration::from_millis(200)),
+        );
+        // Without workers the scan runs everything locally
+        let progress = DiscoveryProgress::new();
+        let remote = distribute_phase1(&coordinator, &request, (0, 1), "1", &work, None, &progress)
+            .await
+            .unwrap();
+        assert!(remote.is_empty());
+
+        // "dead" leases the first chunk and never answers; "live" gets it back once
+        // the lease expires
+        assert!(coordinator.lease("dead").await.unwrap().is_none());
+        let worker = tokio::spawn({
+            let coordinator = coordinator.clone();
+            let klines = klines.clone();
+            async move {
+                while coordinator.status().await.unwrap().run_id.is_none() {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                let lost = coordinator.lease("dead").await.unwrap().unwrap();
+                let mut done = Vec::new();
+                while coordinator.status().await.unwrap().run_id.is_some() {
+                    let Some(chunk) = coordinator.lease("live").await.unwrap() else {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        continue;
+                    };
+                    let results = chunk
+                        .strategies
+                        .iter()
+                        .map(|s| {
+                            run_single_backtest(
+                                s,
+                                &klines,
+                                &chunk.symbol,
+                                INITIAL_CAPITAL,
+                                BASE_POSITION_PCT,
+                                SizingMode::Fixed,
+                                &PolymarketFeeConfig::default(),
+                                &BacktestLimits::default(),
+                                &IndicatorSeriesCache::new(8),
+                            )
+                        })
+                        .collect();
+                    let accepted = coordinator.complete(chunk.id, "live", results);
+                    assert!(accepted.await.unwrap());
+                    done.push(chunk.id);
+                }
+                (lost.id, done)
+            }
+        });
+        let remote = distribute_phase1(&coordinator, &request, (0, 1), "2", &work, None, &progress)
+            .await
+            .unwrap();
+        let (lost, done) = worker.await.unwrap();
+
+        assert_eq!(remote.len(), grid.len());
+        for strategy in &grid {
+            let hash = compute_params_hash(strategy, &symbol, 30, SizingMode::Fixed);
+            assert_eq!(remote[&hash].strategy_name, strategy.name());
+        }
+        assert!(done.contains(&lost));
+        // The chunk is done: the late answer of the dead worker is refused
+        let late = coordinator.complete(lost, "dead", Vec::new()).await;
+        assert!(!late.unwrap());
+        let status = coordinator.status().await.unwrap();
+        assert_eq!((status.pending, status.leased, status.completed), (0, 0, 2));
+        assert_eq!(status.workers.len(), 2);
+        assert_eq!(status.workers[1].chunks_done, done.len() as u32);
+
+        // After a restart, the same scan adopts the done chunks of the queue table
+        let restarted = Coordinator::new(db.pool_clone());
+        assert!(restarted.lease("live").await.unwrap().is_none());
+        let again = distribute_phase1(&restarted, &request, (0, 1), "3", &work, None, &progress)
+            .await
+            .unwrap();
+        assert_eq!(again.len(), grid.len());
+        assert_eq!(restarted.status().await.unwrap().workers[0].chunks_done, 0);
+
+        // A chunk failing MAX_ATTEMPTS times is left to the local loop
+        let chunk = GridChunk {
+            id: 0,
+            run_id: "4".into(),
+            request,
+            start_time: 0,
+            end_time: 1,
+            symbol,
+            strategies: grid,
+        };
+        let ids = coordinator.submit("4", &[chunk]).await.unwrap();
+        for _ in 0..MAX_ATTEMPTS {
+            let chunk = coordinator.lease("live").await.unwrap().unwrap();
+            coordinator
+                .fail(chunk.id, "live", "no klines")
+                .await
+                .unwrap();
+        }
+        let status = coordinator.status().await.unwrap();
+        assert_eq!((status.pending, status.abandoned), (0, 1));
+        coordinator.finish(&ids).await.unwrap();
+        assert_eq!(coordinator.status().await.unwrap().abandoned, 0);
+    }
+}
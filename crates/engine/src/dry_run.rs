@@ -0,0 +1,463 @@
+//! Discovery dry run — the size of a request without running it
+//!
+//! `estimate_discovery` builds the grid a run would test (a one-shot scan, or one cycle
+//! of continuous mode), counts the combinations per strategy type and looks each one up
+//! in the knowledge base to split cached results from new backtests. The new ones are
+//! priced at the store's recent throughput. No klines are fetched: the cross-sectional
+//! grid assumes every symbol aligns, and the probability model is taken as requested
+//! (an uncalibrated logistic model is looked up under the default slope).
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::adaptive::{adaptive_cycle_grid, ADAPTIVE_CYCLES, ADAPTIVE_PHASE_NAME};
+use crate::discovery::{
+    compute_grid_hash, compute_grid_id, compute_params_hash, cycle_phase_name,
+    generate_exploratory_grid, generate_ml_guided_grid, generate_onnx_grid,
+    generate_pair_spread_grid, generate_phase1_grid, generate_rotation_grid, record_to_result,
+    DiscoveryRequest, DiscoveryStrategyType, RefinementDepth, SizingMode, StrategyExclusions,
+    CONTINUOUS_DAYS, CONTINUOUS_SIZING_MODES, CROSS_SYMBOL_TOP, GRID_SEED_RESULTS,
+    THROTTLE_SEARCH_COMBOS,
+};
+use crate::fees::FeePreset;
+use crate::fills::FillModel;
+use crate::local_search::LOCAL_SEARCH_COMBOS;
+use crate::population::Population;
+use crate::store::{ModelScopedStore, ResultStore};
+use crate::strategy_validation::retain_valid;
+
+/// Combinations of one strategy type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StrategyTypeCount {
+    pub strategy_type: String,
+    pub combinations: u64,
+    pub cached: u64,
+}
+
+/// What a discovery request would run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DiscoveryEstimate {
+    /// Continuous cycle estimated, `None` for a one-shot scan
+    pub cycle: Option<u32>,
+    pub phase: String,
+    pub symbols: usize,
+    pub days: Vec<u32>,
+    pub sizing_modes: Vec<SizingMode>,
+    /// Single-symbol strategies, run for every symbol × days × sizing mode
+    pub grid_size: usize,
+    /// Cross-sectional strategies, run once per days on the basket
+    pub cross_sectional: usize,
+    pub total_combinations: u64,
+    /// Served from the knowledge base
+    pub cached: u64,
+    /// Backtests the run would execute
+    pub new: u64,
+    /// Cached combinations of grid units already fully tested (skipped without lookups)
+    pub skipped_units: u64,
+    /// Phase 2 refinement (and the throttle search) added after the grid (rough: their
+    /// parents are not known yet)
+    pub refinement_estimate: u64,
+    /// Largest first
+    pub by_strategy_type: Vec<StrategyTypeCount>,
+    /// Executed backtests per second of the latest recorded run
+    pub throughput_per_sec: Option<f64>,
+    /// New backtests and refinement at that throughput
+    pub estimated_runtime_secs: Option<u64>,
+    /// False without a store or under a what-if fee preset or fill model: every
+    /// combination is new
+    pub uses_cache: bool,
+}
+
+/// The store the run would use: none under a what-if fee preset, fill model or volume
+/// cap, scoped to the model otherwise
+fn run_store(
+    request: &DiscoveryRequest,
+    store: Option<Arc<dyn ResultStore>>,
+) -> Option<Arc<dyn ResultStore>> {
+    if request.fee_preset.clone().unwrap_or_default() != FeePreset::Polymarket
+        || request.fill_model.unwrap_or_default() != FillModel::Close
+        || request.participation.is_some()
+    {
+        return None;
+    }
+    let model = request.probability_model.clone().unwrap_or_default();
+    store.map(|s| ModelScopedStore::scope(s, &model))
+}
+
+/// Rotation and pair strategies of a basket of `symbols`
+fn basket_grid(symbols: &[String]) -> Vec<DiscoveryStrategyType> {
+    let mut grid = generate_rotation_grid(symbols.len());
+    grid.extend(generate_pair_spread_grid(symbols));
+    grid
+}
+
+/// `symbol` of a cross-sectional result (see the runners' basket label)
+fn basket_label(strategy: &DiscoveryStrategyType, symbols: &[String]) -> String {
+    match strategy {
+        DiscoveryStrategyType::PairSpread { leg_a, leg_b, .. } => format!("{}/{}", leg_a, leg_b),
+        _ => symbols.join("+"),
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    by_type: BTreeMap<&'static str, (u64, u64)>,
+    cached: u64,
+}
+
+impl Tally {
+    fn add(&mut self, strategy: &DiscoveryStrategyType, cached: bool) {
+        let entry = self.by_type.entry(strategy.type_tag()).or_default();
+        entry.0 += 1;
+        if cached {
+            entry.1 += 1;
+            self.cached += 1;
+        }
+    }
+}
+
+async fn is_cached(store: Option<&dyn ResultStore>, hash: &str) -> bool {
+    match store {
+        Some(store) => store.get_by_hash(hash).await.ok().flatten().is_some(),
+        None => false,
+    }
+}
+
+/// Size of `request` — the one-shot scan, or `cycle` (default 0) of a continuous run.
+/// ML-guided cycles (3+) mutate the best stored results and adaptive cycles mutate the
+/// fixed grids, so their grid is one sample.
+pub async fn estimate_discovery(
+    request: &DiscoveryRequest,
+    cycle: Option<u32>,
+    store: Option<Arc<dyn ResultStore>>,
+) -> DiscoveryEstimate {
+    let store = run_store(request, store);
+    let store = store.as_deref();
+    let symbols = &request.symbols;
+    let basket = symbols.len() >= 2;
+    let cycle = request
+        .continuous
+        .unwrap_or(false)
+        .then(|| cycle.unwrap_or(0));
+    let request_sizing = request.sizing_mode.unwrap_or_default();
+
+    let exclusions = StrategyExclusions::from_request(request);
+    let adaptive = request.adaptive_cycles.unwrap_or(false)
+        && cycle.is_some_and(|cycle| cycle < ADAPTIVE_CYCLES)
+        && store.is_some();
+    let (mut grid, mut cross_grid) = match cycle {
+        None => {
+            let mut grid = generate_phase1_grid();
+            grid.extend(generate_onnx_grid(&request.onnx_models));
+            let cross_grid = if basket {
+                basket_grid(symbols)
+            } else {
+                Vec::new()
+            };
+            (grid, cross_grid)
+        }
+        Some(cycle) => {
+            let mut grid = match store {
+                Some(store) if adaptive => adaptive_cycle_grid(store, &exclusions).await,
+                _ if cycle >= 3 => {
+                    let mut parents = Vec::new();
+                    if let Some(store) = store {
+                        for symbol in symbols {
+                            let top = store
+                                .top_results(GRID_SEED_RESULTS, symbol)
+                                .await
+                                .unwrap_or_default();
+                            parents.extend(top.into_iter().filter_map(record_to_result));
+                        }
+                    }
+                    // The saved population the continuous run would resume from
+                    let mut population = match store {
+                        Some(store) => Population::load(store).await,
+                        None => Population::default(),
+                    };
+                    population.merge(&parents);
+                    generate_ml_guided_grid(population.individuals(), cycle)
+                }
+                _ => generate_exploratory_grid(cycle),
+            };
+            if cycle < 2 {
+                grid.extend(generate_onnx_grid(&request.onnx_models));
+            }
+            let (mut cross_grid, grid): (Vec<_>, Vec<_>) =
+                grid.into_iter().partition(|s| s.is_cross_sectional());
+            if cycle < 2 && basket {
+                cross_grid.extend(basket_grid(symbols));
+            }
+            if !basket {
+                cross_grid.clear();
+            }
+            (grid, cross_grid)
+        }
+    };
+    retain_valid(&mut grid);
+    exclusions.retain(&mut grid);
+    retain_valid(&mut cross_grid);
+    exclusions.retain(&mut cross_grid);
+    let (days_list, sizing_list) = match cycle {
+        Some(cycle) if cycle > 0 => (CONTINUOUS_DAYS.to_vec(), CONTINUOUS_SIZING_MODES.to_vec()),
+        _ => (vec![request.days], vec![request_sizing]),
+    };
+
+    let mut tally = Tally::default();
+    let mut skipped_units = 0u64;
+    // Continuous cycles 0-1 skip grid units a previous run fully tested
+    let grid_id = (cycle.is_some_and(|c| c < 2) && store.is_some_and(|s| s.tracks_grids()))
+        .then(|| compute_grid_id(&grid));
+    for symbol in symbols {
+        for &days in &days_list {
+            for &sizing_mode in &sizing_list {
+                if let (Some(grid_id), Some(store)) = (&grid_id, store) {
+                    let unit_hash = compute_grid_hash(grid_id, symbol, days, sizing_mode);
+                    if store.is_grid_tested(&unit_hash).await.unwrap_or(false) {
+                        skipped_units += grid.len() as u64;
+                        for strategy in &grid {
+                            tally.add(strategy, true);
+                        }
+                        continue;
+                    }
+                }
+                for strategy in &grid {
+                    let hash = compute_params_hash(strategy, symbol, days, sizing_mode);
+                    tally.add(strategy, is_cached(store, &hash).await);
+                }
+            }
+        }
+    }
+    for &days in &days_list {
+        for strategy in &cross_grid {
+            let label = basket_label(strategy, symbols);
+            let hash = compute_params_hash(strategy, &label, days, SizingMode::Fixed);
+            tally.add(strategy, is_cached(store, &hash).await);
+        }
+    }
+
+    let total_combinations: u64 = tally.by_type.values().map(|(n, _)| n).sum();
+    let new = total_combinations - tally.cached;
+    let refinement = RefinementDepth::from_request(request).estimated_combos();
+    let refinement_estimate = match cycle {
+        None => {
+            let mut combos = refinement;
+            if request.local_search.unwrap_or(false) {
+                combos += LOCAL_SEARCH_COMBOS;
+            }
+            if request.throttle_search.unwrap_or(false) {
+                combos += THROTTLE_SEARCH_COMBOS;
+            }
+            if request.cross_symbol.unwrap_or(false) {
+                let others = (symbols.len() as u32).saturating_sub(1);
+                combos += CROSS_SYMBOL_TOP as u32 * others;
+            }
+            combos as u64
+        }
+        Some(0) => refinement as u64,
+        Some(_) => 0,
+    };
+    let throughput_per_sec = match store {
+        Some(store) => store.recent_throughput().await.ok().flatten(),
+        None => None,
+    };
+    let mut by_strategy_type: Vec<StrategyTypeCount> = tally
+        .by_type
+        .into_iter()
+        .map(
+            |(strategy_type, (combinations, cached))| StrategyTypeCount {
+                strategy_type: strategy_type.to_string(),
+                combinations,
+                cached,
+            },
+        )
+        .collect();
+    by_strategy_type.sort_by_key(|c| std::cmp::Reverse(c.combinations));
+
+    DiscoveryEstimate {
+        cycle,
+        phase: match cycle {
+            Some(cycle) if adaptive => format!("Cycle {} — {}", cycle, ADAPTIVE_PHASE_NAME),
+            Some(cycle) => format!("Cycle {} — {}", cycle, cycle_phase_name(cycle)),
+            None => "Phase 1: Broad Scan + Phase 2: Refinement".to_string(),
+        },
+        symbols: symbols.len(),
+        days: days_list,
+        sizing_modes: sizing_list,
+        grid_size: grid.len(),
+        cross_sectional: cross_grid.len(),
+        total_combinations,
+        cached: tally.cached,
+        new,
+        skipped_units,
+        refinement_estimate,
+        by_strategy_type,
+        throughput_per_sec,
+        estimated_runtime_secs: throughput_per_sec
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| ((new + refinement_estimate) as f64 / rate).ceil() as u64),
+        uses_cache: store.is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{result_to_record, run_backtest, BacktestLimits};
+    use crate::store::MemoryResultStore;
+    use crate::synthetic::{generate_klines, SyntheticConfig};
+
+    fn request(symbols: &[&str], continuous: bool) -> DiscoveryRequest {
+        DiscoveryRequest {
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            days: 90,
+            top_n: None,
+            per_symbol_top_n: None,
+            sizing_mode: None,
+            continuous: Some(continuous),
+            onnx_models: Vec::new(),
+            time_budget_ms: None,
+            ruin_threshold_pct: None,
+            repair_gaps: None,
+            data_source: None,
+            fee_preset: None,
+            probability_model: None,
+            fill_model: None,
+            participation: None,
+            throttle_search: None,
+            cross_symbol: None,
+            cross_symbol_weight: None,
+            refinement_top_k: None,
+            refinement_rounds: None,
+            refinement_delta_scale: None,
+            local_search: None,
+            adaptive_cycles: None,
+            decay_half_life_days: None,
+            underwater_penalty: None,
+            max_exposure_pct: None,
+            exclude_strategy_types: Vec::new(),
+            pacing: None,
+            rank_by: None,
+            restart_on_crash: None,
+            candle_transform: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_estimate_counts_grid_and_cache() {
+        let store = Arc::new(MemoryResultStore::new());
+        let one_shot = request(&["BTCUSDT", "ETHUSDT"], false);
+        let empty = estimate_discovery(&one_shot, None, Some(store.clone())).await;
+        let grid = generate_phase1_grid().len();
+        // 2 symbols: 7 rotation lookbacks (top-1) + 1 pair × 4 windows × 3 entries × 2 exits
+        assert_eq!(empty.cross_sectional, 7 + 24);
+        assert_eq!(
+            empty.total_combinations,
+            (grid * 2 + empty.cross_sectional) as u64
+        );
+        assert_eq!((empty.cached, empty.new), (0, empty.total_combinations));
+        let refinement = RefinementDepth::default().estimated_combos();
+        assert_eq!(empty.refinement_estimate, refinement as u64);
+        let cross_symbol = DiscoveryRequest {
+            cross_symbol: Some(true),
+            ..one_shot.clone()
+        };
+        let estimate = estimate_discovery(&cross_symbol, None, None).await;
+        assert_eq!(
+            estimate.refinement_estimate,
+            (refinement as usize + CROSS_SYMBOL_TOP) as u64
+        );
+        assert!(empty.uses_cache && empty.throughput_per_sec.is_none());
+        assert_eq!(
+            empty
+                .by_strategy_type
+                .iter()
+                .map(|c| c.combinations)
+                .sum::<u64>(),
+            empty.total_combinations
+        );
+
+        // A stored result of the grid is counted as cached
+        let strategy = generate_phase1_grid().remove(0);
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 200,
+            ..SyntheticConfig::default()
+        });
+        let result = run_backtest(
+            &strategy,
+            &klines,
+            "BTCUSDT",
+            SizingMode::Fixed,
+            &BacktestLimits::default(),
+        );
+        let hash = compute_params_hash(&strategy, "BTCUSDT", 90, SizingMode::Fixed);
+        let record = result_to_record(&result, &hash, "run", "phase1", 90);
+        store.save(&record).await.unwrap();
+        let estimate = estimate_discovery(&one_shot, None, Some(store.clone())).await;
+        assert_eq!(estimate.cached, 1);
+        assert_eq!(estimate.new, empty.total_combinations - 1);
+        assert_eq!(estimate.by_strategy_type[0].cached, 1);
+
+        // What-if fees never read the KB
+        let what_if = DiscoveryRequest {
+            fee_preset: Some(FeePreset::ZeroFee),
+            ..one_shot.clone()
+        };
+        let estimate = estimate_discovery(&what_if, None, Some(store.clone())).await;
+        assert!(!estimate.uses_cache);
+        assert_eq!(estimate.cached, 0);
+
+        // Continuous cycle 1: every days variant × sizing mode, no refinement
+        let continuous = request(&["BTCUSDT"], true);
+        let estimate = estimate_discovery(&continuous, Some(1), Some(store)).await;
+        assert_eq!(estimate.cycle, Some(1));
+        assert_eq!(estimate.cross_sectional, 0);
+        assert_eq!(
+            estimate.total_combinations,
+            (estimate.grid_size * CONTINUOUS_DAYS.len() * CONTINUOUS_SIZING_MODES.len()) as u64
+        );
+        assert_eq!(estimate.refinement_estimate, 0);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_honors_excluded_strategy_types() {
+        let all = request(&["BTCUSDT", "ETHUSDT"], false);
+        let full = estimate_discovery(&all, None, None).await;
+        let excluding = DiscoveryRequest {
+            exclude_strategy_types: vec!["gabagool".to_string(), "rsi".to_string()],
+            ..all.clone()
+        };
+        let exclusions = StrategyExclusions::from_request(&excluding);
+        assert!(exclusions.validate().is_ok());
+        let estimate = estimate_discovery(&excluding, None, None).await;
+
+        // RSI goes with the combos and dynamic combos using it
+        let mut grid = generate_phase1_grid();
+        retain_valid(&mut grid);
+        exclusions.retain(&mut grid);
+        assert!(grid.iter().all(|s| !s.name().contains("RSI")));
+        assert_eq!(estimate.grid_size, grid.len());
+        assert!(estimate.grid_size < full.grid_size);
+        assert_eq!(estimate.cross_sectional, full.cross_sectional);
+        assert!(estimate
+            .by_strategy_type
+            .iter()
+            .all(|c| c.strategy_type != "gabagool" && c.strategy_type != "rsi"));
+        assert!(estimate.total_combinations < full.total_combinations);
+
+        // Every family of the grids can be excluded, and only those
+        let mut every_family = generate_phase1_grid();
+        every_family.extend(basket_grid(&all.symbols));
+        for strategy in &every_family {
+            assert!(DiscoveryStrategyType::TYPE_TAGS.contains(&strategy.type_tag()));
+        }
+        assert!(StrategyExclusions::new(["rsi", "bollinger"])
+            .validate()
+            .unwrap_err()
+            .contains("'bollinger'"));
+    }
+}
@@ -96,6 +96,7 @@ impl BacktestEngine {
                             size: pos.size,
                             pnl,
                             pnl_pct,
+                            exit_reason: ExitReason::Signal,
                         });
 
                         debug!(
@@ -162,6 +163,7 @@ impl BacktestEngine {
                     size: pos.size,
                     pnl,
                     pnl_pct,
+                    exit_reason: ExitReason::Timeout,
                 });
             }
         }
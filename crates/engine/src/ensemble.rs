@@ -0,0 +1,356 @@
+//! Ensembles of knowledge base strategies
+//!
+//! An ensemble is a `DiscoveryStrategyType::Ensemble` whose members are stored
+//! strategies: on each bar every member's signal generator votes, and the ensemble
+//! trades when the members holding more than half of the voting weight agree.
+//! `EnsembleVote` picks the weights — one vote per member, or the strategy confidence
+//! of each member's stored result. The ensemble is backtested and stored like any
+//! other result, so it can be ranked, re-validated and promoted.
+
+use anyhow::bail;
+use persistence::repository::DiscoveryBacktestRecord;
+use serde::{Deserialize, Serialize};
+
+use crate::api::BinanceClient;
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::discovery::{
+    compute_params_hash, parse_sizing_mode, result_to_record, run_backtest_with_fees,
+    BacktestLimits, DiscoveryStrategyType, SizingMode,
+};
+use crate::fees::PolymarketFeeConfig;
+use crate::indicators::{SignalGenerator, SignalWithConfidence};
+use crate::probability::ProbabilityModelConfig;
+use crate::store::ResultStore;
+use crate::strategy::Signal;
+use crate::types::Kline;
+use crate::validation::validation_strategy;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Largest number of members of an ensemble
+pub const MAX_MEMBERS: usize = 8;
+
+/// How the members of an ensemble are weighted
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum EnsembleVote {
+    /// One vote per member
+    #[default]
+    Majority,
+    /// Each member weighs its stored strategy confidence (0-100, at least 1)
+    ConfidenceWeighted,
+}
+
+impl EnsembleVote {
+    pub fn short_suffix(&self) -> &'static str {
+        match self {
+            Self::Majority => "M",
+            Self::ConfidenceWeighted => "W",
+        }
+    }
+
+    /// Voting weight of the member stored as `record`
+    fn weight(&self, record: &DiscoveryBacktestRecord) -> f64 {
+        match self {
+            Self::Majority => 1.0,
+            Self::ConfidenceWeighted => record.strategy_confidence.unwrap_or(0.0).max(1.0),
+        }
+    }
+}
+
+/// Weighted vote of member generators: buys (sells) when the members signalling a buy
+/// (sell) hold more than half of the total weight, at their mean confidence
+pub struct EnsembleSignalGenerator {
+    members: Vec<Box<dyn SignalGenerator>>,
+    weights: Vec<f64>,
+}
+
+impl EnsembleSignalGenerator {
+    pub fn new(members: Vec<Box<dyn SignalGenerator>>, weights: Vec<f64>) -> Self {
+        Self { members, weights }
+    }
+}
+
+impl SignalGenerator for EnsembleSignalGenerator {
+    fn name(&self) -> &str {
+        "Ensemble"
+    }
+
+    fn warmup_bars(&self) -> usize {
+        self.members
+            .iter()
+            .map(|m| m.warmup_bars())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
+        let votes: Vec<(SignalWithConfidence, f64)> = self
+            .members
+            .iter_mut()
+            .zip(self.weights.iter())
+            .map(|(member, &weight)| (member.on_bar(kline), weight))
+            .collect();
+        let half = self.weights.iter().sum::<f64>() / 2.0;
+
+        for side in [Signal::Buy, Signal::Sell] {
+            let voters: Vec<&(SignalWithConfidence, f64)> =
+                votes.iter().filter(|(s, _)| s.signal == side).collect();
+            if voters.iter().map(|(_, w)| w).sum::<f64>() > half {
+                let confidence =
+                    voters.iter().map(|(s, _)| s.confidence).sum::<f64>() / voters.len() as f64;
+                return match side {
+                    Signal::Buy => SignalWithConfidence::buy(confidence),
+                    _ => SignalWithConfidence::sell(confidence),
+                };
+            }
+        }
+        SignalWithConfidence::hold()
+    }
+
+    fn reset(&mut self) {
+        for member in &mut self.members {
+            member.reset();
+        }
+    }
+
+    /// Values of every member, in member order
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        self.members.iter().flat_map(|m| m.features()).collect()
+    }
+}
+
+/// Ensemble of the strategies stored as `records` (2 to `MAX_MEMBERS`, in order)
+pub fn ensemble_strategy(
+    records: &[DiscoveryBacktestRecord],
+    vote: EnsembleVote,
+) -> Result<DiscoveryStrategyType, String> {
+    if !(2..=MAX_MEMBERS).contains(&records.len()) {
+        return Err(format!(
+            "An ensemble needs 2 to {} strategies, got {}",
+            MAX_MEMBERS,
+            records.len()
+        ));
+    }
+    let mut members = Vec::with_capacity(records.len());
+    for record in records {
+        let strategy = validation_strategy(record)?;
+        if strategy.is_gabagool() || strategy.is_ensemble() {
+            return Err(format!(
+                "{} has no bar signals to vote with",
+                record.strategy_name
+            ));
+        }
+        members.push(strategy);
+    }
+    Ok(DiscoveryStrategyType::Ensemble {
+        members,
+        weights: records.iter().map(|r| vote.weight(r)).collect(),
+        vote,
+    })
+}
+
+/// A stored strategy voting in an ensemble
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EnsembleMember {
+    pub backtest_id: Option<i64>,
+    pub strategy_name: String,
+    pub symbol: String,
+    pub weight: f64,
+    pub net_pnl: f64,
+    pub composite_score: f64,
+}
+
+/// A backtested ensemble and the strategies it was built from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EnsembleBuild {
+    /// The ensemble's knowledge base record
+    pub record: DiscoveryBacktestRecord,
+    pub members: Vec<EnsembleMember>,
+    /// False when the same ensemble was already stored (the stored record is returned)
+    pub created: bool,
+}
+
+/// Backtest `strategy` like discovery does; the record is not stored and belongs to
+/// no discovery run
+pub fn backtest_ensemble(
+    strategy: &DiscoveryStrategyType,
+    klines: &[Kline],
+    symbol: &str,
+    days: u32,
+    sizing_mode: SizingMode,
+    fee_config: &PolymarketFeeConfig,
+) -> DiscoveryBacktestRecord {
+    let result = run_backtest_with_fees(
+        strategy,
+        klines,
+        symbol,
+        sizing_mode,
+        &BacktestLimits::default(),
+        fee_config,
+    );
+    let hash = compute_params_hash(strategy, symbol, days, sizing_mode);
+    DiscoveryBacktestRecord {
+        discovery_run_id: None,
+        ..result_to_record(&result, &hash, "", "ensemble", days)
+    }
+}
+
+/// Build the ensemble of `records`, backtest it on `symbol` (default: the first
+/// record's) over the first record's window ending now, with its sizing mode and
+/// probability model, and store it
+pub async fn build_ensemble(
+    records: &[DiscoveryBacktestRecord],
+    vote: EnsembleVote,
+    symbol: Option<&str>,
+    binance: &BinanceClient,
+    store: &dyn ResultStore,
+) -> anyhow::Result<EnsembleBuild> {
+    let strategy = ensemble_strategy(records, vote).map_err(anyhow::Error::msg)?;
+    let base = &records[0];
+    let symbol = symbol.unwrap_or(&base.symbol);
+
+    let end_time = chrono::Utc::now().timestamp_millis();
+    let (klines, quality) = fetch_checked_klines(
+        binance,
+        symbol,
+        "15m",
+        end_time - base.days * DAY_MS,
+        end_time,
+        &DataQualityConfig::default(),
+    )
+    .await?;
+    quality.warn_if_degraded();
+    if klines.is_empty() {
+        bail!("No klines for {}", symbol);
+    }
+
+    let fee_config = PolymarketFeeConfig {
+        probability_model: ProbabilityModelConfig::from_name(base.probability_model.as_deref()),
+        ..PolymarketFeeConfig::default()
+    };
+    let record = backtest_ensemble(
+        &strategy,
+        &klines,
+        symbol,
+        base.days as u32,
+        parse_sizing_mode(&base.sizing_mode),
+        &fee_config,
+    );
+    let (record, created) = store.save_new(record).await?;
+    let members = records
+        .iter()
+        .map(|r| EnsembleMember {
+            backtest_id: r.id,
+            strategy_name: r.strategy_name.clone(),
+            symbol: r.symbol.clone(),
+            weight: vote.weight(r),
+            net_pnl: r.net_pnl,
+            composite_score: r.composite_score,
+        })
+        .collect();
+    Ok(EnsembleBuild {
+        record,
+        members,
+        created,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{record_to_result, run_backtest};
+    use crate::store::MemoryResultStore;
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    /// Generator replaying fixed signals
+    struct Fixed(Vec<SignalWithConfidence>, usize);
+
+    impl SignalGenerator for Fixed {
+        fn name(&self) -> &str {
+            "Fixed"
+        }
+
+        fn on_bar(&mut self, _kline: &Kline) -> SignalWithConfidence {
+            self.1 += 1;
+            self.0[self.1 - 1]
+        }
+
+        fn reset(&mut self) {
+            self.1 = 0;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ensemble_votes_backtests_and_stores() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 3_000,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+
+        // Weights 1, 1, 3: the third member alone holds more than half
+        let buy = SignalWithConfidence::buy(0.5);
+        let sell = SignalWithConfidence::sell(0.9);
+        let hold = SignalWithConfidence::hold();
+        let mut generator = EnsembleSignalGenerator::new(
+            vec![
+                Box::new(Fixed(vec![buy, buy, sell], 0)),
+                Box::new(Fixed(vec![buy, hold, sell], 0)),
+                Box::new(Fixed(vec![hold, buy, hold], 0)),
+            ],
+            vec![1.0, 1.0, 3.0],
+        );
+        let votes: Vec<Signal> = (0..3)
+            .map(|i| generator.on_bar(&klines[i]).signal)
+            .collect();
+        assert_eq!(votes, [Signal::Hold, Signal::Buy, Signal::Hold]);
+
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let ema = DiscoveryStrategyType::EmaCrossover {
+            fast_period: 9,
+            slow_period: 21,
+        };
+        let limits = BacktestLimits::default();
+        let stored = |strategy: &DiscoveryStrategyType, confidence: f64| {
+            let result = run_backtest(strategy, &klines, "SYNTH", SizingMode::Fixed, &limits);
+            let hash = compute_params_hash(strategy, "SYNTH", 30, SizingMode::Fixed);
+            DiscoveryBacktestRecord {
+                strategy_confidence: Some(confidence),
+                ..result_to_record(&result, &hash, "run", "phase1", 30)
+            }
+        };
+        let records = [stored(&rsi, 80.0), stored(&ema, 0.0)];
+
+        assert!(ensemble_strategy(&records[..1], EnsembleVote::Majority).is_err());
+        let weighted = ensemble_strategy(&records, EnsembleVote::ConfidenceWeighted).unwrap();
+        let DiscoveryStrategyType::Ensemble { weights, .. } = &weighted else {
+            panic!("not an ensemble");
+        };
+        assert_eq!(weights, &[80.0, 1.0]);
+        assert_eq!(weighted.name(), "Ensemble:RSI+EMA Crossover(W)");
+
+        let majority = ensemble_strategy(&records, EnsembleVote::Majority).unwrap();
+        let fees = PolymarketFeeConfig::default();
+        let record = backtest_ensemble(&majority, &klines, "SYNTH", 30, SizingMode::Fixed, &fees);
+        assert_eq!(record.strategy_type, "ensemble");
+        assert_eq!(record.discovery_run_id, None);
+        let back = record_to_result(record.clone()).unwrap();
+        assert_eq!(back.strategy_name, majority.name());
+
+        let store = MemoryResultStore::new();
+        let (_, created) = store.save_new(record.clone()).await.unwrap();
+        assert!(created);
+        let (again, created) = store.save_new(record).await.unwrap();
+        assert!(!created);
+        assert_eq!(again.phase.as_deref(), Some("ensemble"));
+        assert_eq!(store.len(), 1);
+    }
+}
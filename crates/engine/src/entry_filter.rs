@@ -0,0 +1,342 @@
+//! Volume and volatility filters on a strategy's entries
+//!
+//! `DiscoveryStrategyType::Filtered` wraps a bar-signal strategy with an `EntryFilter`:
+//! its buys are only kept while the trailing 24h quote volume and realized volatility
+//! are inside the filter's bounds; exits always pass. Refinement re-runs each top result
+//! under a few filters placed at quantiles of its own symbol's history, so discovery can
+//! learn that a combo only works in active or volatile markets.
+
+use std::collections::VecDeque;
+
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::DiscoveryStrategyType;
+use crate::indicators::{SignalGenerator, SignalWithConfidence};
+use crate::strategy::Signal;
+use crate::types::Kline;
+
+/// Trailing window of the filters: 24h of 15m klines
+pub const WINDOW_BARS: usize = 96;
+
+/// Filters tried on each top result by refinement
+pub const REFINEMENT_FILTERS: usize = 6;
+
+/// Market conditions required to open a position; `None` bounds are not checked
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct EntryFilter {
+    /// Minimum quote-asset volume (close × volume) traded over the last 24h
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_volume_24h: Option<f64>,
+    /// Minimum realized volatility of the last 24h, in %
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_volatility_pct: Option<f64>,
+    /// Maximum realized volatility of the last 24h, in %
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_volatility_pct: Option<f64>,
+}
+
+impl EntryFilter {
+    /// An entry is allowed under these 24h conditions
+    pub fn allows(&self, conditions: MarketConditions) -> bool {
+        self.min_volume_24h
+            .is_none_or(|min| conditions.volume_24h >= min)
+            && self
+                .min_volatility_pct
+                .is_none_or(|min| conditions.volatility_pct >= min)
+            && self
+                .max_volatility_pct
+                .is_none_or(|max| conditions.volatility_pct <= max)
+    }
+
+    /// Short suffix of the wrapped strategy's name, e.g. `vol>=12M,rv<=3.1%`
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(min) = self.min_volume_24h {
+            parts.push(format!("vol>={}", compact_amount(min)));
+        }
+        match (self.min_volatility_pct, self.max_volatility_pct) {
+            (Some(min), Some(max)) => parts.push(format!("rv {}-{}%", min, max)),
+            (Some(min), None) => parts.push(format!("rv>={}%", min)),
+            (None, Some(max)) => parts.push(format!("rv<={}%", max)),
+            (None, None) => {}
+        }
+        if parts.is_empty() {
+            "unfiltered".to_string()
+        } else {
+            parts.join(",")
+        }
+    }
+}
+
+/// Trailing 24h volume and realized volatility at one bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketConditions {
+    pub volume_24h: f64,
+    /// Standard deviation of the bars' log returns, scaled to a day, in %
+    pub volatility_pct: f64,
+}
+
+/// Rolling `MarketConditions` over the last `WINDOW_BARS` bars
+#[derive(Debug, Default, Clone)]
+pub struct RollingConditions {
+    /// (quote volume, log return) of each bar of the window
+    window: VecDeque<(f64, f64)>,
+    last_close: Option<f64>,
+    volume_sum: f64,
+    return_sum: f64,
+    return_sq_sum: f64,
+}
+
+impl RollingConditions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a bar; the conditions once the window is full
+    pub fn push(&mut self, kline: &Kline) -> Option<MarketConditions> {
+        let close = kline.close.to_f64().unwrap_or(0.0);
+        let volume = close * kline.volume.to_f64().unwrap_or(0.0);
+        let log_return = match self.last_close {
+            Some(prev) if prev > 0.0 && close > 0.0 => (close / prev).ln(),
+            _ => 0.0,
+        };
+        self.last_close = Some(close);
+
+        self.window.push_back((volume, log_return));
+        self.volume_sum += volume;
+        self.return_sum += log_return;
+        self.return_sq_sum += log_return * log_return;
+        if self.window.len() > WINDOW_BARS {
+            let (old_volume, old_return) = self.window.pop_front().unwrap();
+            self.volume_sum -= old_volume;
+            self.return_sum -= old_return;
+            self.return_sq_sum -= old_return * old_return;
+        }
+        if self.window.len() < WINDOW_BARS {
+            return None;
+        }
+
+        let n = WINDOW_BARS as f64;
+        let mean = self.return_sum / n;
+        let variance = (self.return_sq_sum / n - mean * mean).max(0.0);
+        Some(MarketConditions {
+            volume_24h: self.volume_sum.max(0.0),
+            volatility_pct: variance.sqrt() * n.sqrt() * 100.0,
+        })
+    }
+}
+
+/// Conditions at every bar of `klines` once the first window is full
+pub fn market_conditions(klines: &[Kline]) -> Vec<MarketConditions> {
+    let mut rolling = RollingConditions::new();
+    klines.iter().filter_map(|k| rolling.push(k)).collect()
+}
+
+/// Holds the inner generator's buys while the market is outside the filter (and during
+/// the first `WINDOW_BARS` bars); sells and holds pass through
+pub struct FilteredSignalGenerator {
+    inner: Box<dyn SignalGenerator>,
+    filter: EntryFilter,
+    conditions: RollingConditions,
+}
+
+impl FilteredSignalGenerator {
+    pub fn new(inner: Box<dyn SignalGenerator>, filter: EntryFilter) -> Self {
+        Self {
+            inner,
+            filter,
+            conditions: RollingConditions::new(),
+        }
+    }
+}
+
+impl SignalGenerator for FilteredSignalGenerator {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn warmup_bars(&self) -> usize {
+        self.inner.warmup_bars().max(WINDOW_BARS - 1)
+    }
+
+    fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
+        let signal = self.inner.on_bar(kline);
+        let conditions = self.conditions.push(kline);
+        if signal.signal == Signal::Buy && !conditions.is_some_and(|c| self.filter.allows(c)) {
+            return SignalWithConfidence::hold();
+        }
+        signal
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.conditions = RollingConditions::new();
+    }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        self.inner.features()
+    }
+}
+
+/// `strategy` (unwrapped if already filtered) under `REFINEMENT_FILTERS` filters set at
+/// the 25th / 50th / 75th percentiles of the conditions of `klines`. Empty for strategies
+/// without bar signals, or when the klines are shorter than a window.
+pub fn filter_refinement_grid(
+    strategy: &DiscoveryStrategyType,
+    klines: &[Kline],
+) -> Vec<DiscoveryStrategyType> {
+    let (inner, current) = match strategy {
+        DiscoveryStrategyType::Filtered { inner, filter } => (inner.as_ref(), Some(*filter)),
+        other => (other, None),
+    };
+    if inner.is_gabagool() || inner.is_cross_sectional() {
+        return Vec::new();
+    }
+    let conditions = market_conditions(klines);
+    if conditions.is_empty() {
+        return Vec::new();
+    }
+    let volume = quantiles(conditions.iter().map(|c| c.volume_24h).collect());
+    let volatility = quantiles(conditions.iter().map(|c| c.volatility_pct).collect());
+
+    let filters = [
+        EntryFilter {
+            min_volume_24h: Some(volume[0]),
+            ..Default::default()
+        },
+        EntryFilter {
+            min_volume_24h: Some(volume[1]),
+            ..Default::default()
+        },
+        EntryFilter {
+            min_volatility_pct: Some(volatility[0]),
+            ..Default::default()
+        },
+        EntryFilter {
+            min_volatility_pct: Some(volatility[1]),
+            ..Default::default()
+        },
+        EntryFilter {
+            max_volatility_pct: Some(volatility[2]),
+            ..Default::default()
+        },
+        EntryFilter {
+            min_volume_24h: None,
+            min_volatility_pct: Some(volatility[0]),
+            max_volatility_pct: Some(volatility[2]),
+        },
+    ];
+    filters
+        .into_iter()
+        .filter(|f| Some(*f) != current)
+        .map(|filter| DiscoveryStrategyType::Filtered {
+            inner: Box::new(inner.clone()),
+            filter,
+        })
+        .collect()
+}
+
+/// 25th, 50th and 75th percentiles, rounded to 2 significant digits so the thresholds
+/// (and the params hashes) stay put while the klines move
+fn quantiles(mut values: Vec<f64>) -> [f64; 3] {
+    values.sort_by(f64::total_cmp);
+    let at = |q: f64| round_significant(values[((values.len() - 1) as f64 * q).round() as usize]);
+    [at(0.25), at(0.5), at(0.75)]
+}
+
+fn round_significant(x: f64) -> f64 {
+    if x <= 0.0 || !x.is_finite() {
+        return 0.0;
+    }
+    let decimals = 1 - x.log10().floor() as i32;
+    let scale = 10f64.powi(decimals.abs());
+    if decimals >= 0 {
+        (x * scale).round() / scale
+    } else {
+        (x / scale).round() * scale
+    }
+}
+
+pub(crate) fn compact_amount(x: f64) -> String {
+    if x >= 1e9 {
+        format!("{}B", x / 1e9)
+    } else if x >= 1e6 {
+        format!("{}M", x / 1e6)
+    } else if x >= 1e3 {
+        format!("{}K", x / 1e3)
+    } else {
+        format!("{}", x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{generate_refinement_grid, run_backtest, BacktestLimits, SizingMode};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_filter_gates_entries_and_refines() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 3_000,
+            ..SyntheticConfig::for_model(SyntheticModel::RegimeSwitching)
+        });
+        let conditions = market_conditions(&klines);
+        assert_eq!(conditions.len(), klines.len() - WINDOW_BARS + 1);
+        assert!(conditions
+            .iter()
+            .all(|c| c.volume_24h > 0.0 && c.volatility_pct > 0.0));
+
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 65.0,
+            oversold: 35.0,
+        };
+        let grid = filter_refinement_grid(&rsi, &klines);
+        assert_eq!(grid.len(), REFINEMENT_FILTERS);
+        let DiscoveryStrategyType::Filtered { filter, .. } = &grid[2] else {
+            panic!("not filtered");
+        };
+        let threshold = filter.min_volatility_pct.unwrap();
+        assert_eq!(threshold, round_significant(threshold));
+        assert!(grid[2].name().starts_with("RSI|rv>="));
+
+        // A filtered strategy is refined as its inner strategy, under the same filter,
+        // and re-tried under the other filters
+        let inner_grid = generate_refinement_grid(&rsi);
+        let refined = generate_refinement_grid(&grid[2]);
+        assert_eq!(refined.len(), inner_grid.len());
+        assert!(refined.iter().all(
+            |s| matches!(s, DiscoveryStrategyType::Filtered { filter: f, .. } if f == filter)
+        ));
+        assert_eq!(
+            filter_refinement_grid(&grid[2], &klines).len(),
+            REFINEMENT_FILTERS - 1
+        );
+
+        // Round trip through the stored params
+        let json = serde_json::to_string(&grid[2]).unwrap();
+        let back: DiscoveryStrategyType = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.name(), grid[2].name());
+
+        // Filters only remove entries: an impossible one removes them all
+        let limits = BacktestLimits::unlimited();
+        let run = |s: &DiscoveryStrategyType| {
+            run_backtest(s, &klines, "SYNTH", SizingMode::Fixed, &limits).total_trades
+        };
+        let unfiltered = run(&rsi);
+        assert!(unfiltered > 0);
+        assert!(run(&grid[3]) <= unfiltered);
+        let blocked = DiscoveryStrategyType::Filtered {
+            inner: Box::new(rsi.clone()),
+            filter: EntryFilter {
+                min_volume_24h: Some(f64::MAX),
+                ..Default::default()
+            },
+        };
+        assert_eq!(run(&blocked), 0);
+    }
+}
@@ -0,0 +1,49 @@
+//! Failures of the engine's runners
+//!
+//! `run_discovery()`, `run_continuous_discovery()` and `run_optimization()` still put the
+//! message in their tracker's `error_message` for the pollers, and return the
+//! `EngineError` so callers (the server, `DiscoveryEngine::run()`) can tell a bad
+//! request from an exchange outage without parsing it.
+
+/// Why a run failed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EngineError {
+    /// Market data could not be fetched or loaded
+    #[error("{0}")]
+    DataFetch(String),
+    /// Data was fetched, but too little to backtest on
+    #[error("{0}")]
+    InsufficientData(String),
+    /// The request is invalid
+    #[error("{0}")]
+    InvalidParams(String),
+    /// The result store failed
+    #[error("{0}")]
+    Storage(String),
+    /// Stopped through the tracker's `cancelled` flag before the end
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+pub type EngineResult<T> = Result<T, EngineError>;
+
+impl From<persistence::DbError> for EngineError {
+    fn from(e: persistence::DbError) -> Self {
+        EngineError::Storage(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_is_the_payload() {
+        let err = EngineError::InsufficientData("Only 12 klines".to_string());
+        assert_eq!(err.to_string(), "Only 12 klines");
+        assert_eq!(EngineError::Cancelled.to_string(), "Cancelled");
+
+        let db = persistence::DbError::Query("database is locked".to_string());
+        assert!(matches!(EngineError::from(db), EngineError::Storage(_)));
+    }
+}
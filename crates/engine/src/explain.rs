@@ -0,0 +1,974 @@
+//! Human-readable rules of a strategy
+//!
+//! `explain_strategy()` renders a `DiscoveryStrategyType` into its entry and exit rules,
+//! e.g. `Buy when RSI(14) < 30 AND price < lower BB(20,2)`, with how a combo combines its
+//! indicators and the conditions added by its wrappers (entry filter, higher-timeframe
+//! trend, trailing stop). `explain_record()` adds the sizing and fee assumptions a
+//! knowledge base result was backtested under, for exports and notifications.
+
+use persistence::repository::DiscoveryBacktestRecord;
+use rust_decimal::prelude::ToPrimitive;
+use serde::Serialize;
+
+use crate::discovery::{
+    decode_strategy_params, parse_sizing_mode, DiscoveryStrategyType, DynCombineMode,
+    IndicatorParams, SizingMode, VwapAnchor,
+};
+use crate::ensemble::EnsembleVote;
+use crate::entry_filter::{compact_amount, EntryFilter};
+use crate::fees::PolymarketFeeConfig;
+use crate::htf_filter::{HtfFilter, HtfTrend};
+use crate::sizing::{DrawdownThrottle, KellyConfig};
+use crate::trailing_stop::TrailingStop;
+use crate::web_strategies::WebStrategyParams;
+use crate::zscore_filter::ZScoreFilter;
+
+/// Capital and base position of the discovery backtests, see `run_discovery()`
+const INITIAL_CAPITAL: f64 = 10_000.0;
+const BASE_POSITION_PCT: f64 = 10.0;
+
+/// Entry and exit rules of a strategy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StrategyRules {
+    /// When a position is opened, e.g. `Buy when RSI(14) < 30`
+    pub entry: String,
+    /// When it is closed
+    pub exit: String,
+    /// How a combo or an ensemble combines its members' signals
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combine: Option<String>,
+    /// Conditions added by the strategy's wrappers (filters, trailing stop)
+    pub conditions: Vec<String>,
+}
+
+/// A knowledge base result explained: rules, sizing and fees
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StrategyExplanation {
+    pub strategy_name: String,
+    pub symbol: String,
+    pub rules: StrategyRules,
+    pub sizing: String,
+    pub fees: String,
+    /// Everything above as one line per rule
+    pub text: String,
+}
+
+/// Rules of `strategy`
+pub fn explain_strategy(strategy: &DiscoveryStrategyType) -> StrategyRules {
+    use DiscoveryStrategyType as S;
+    if let Some((params, mode)) = strategy_indicators(strategy) {
+        return match mode {
+            Some(mode) => combo_rules(&params, mode),
+            None => single_rules(&params[0]),
+        };
+    }
+    match strategy {
+        S::WebStrategy { params, .. } => web_rules(params),
+        S::Gabagool {
+            max_pair_cost,
+            bid_offset,
+            spread_multiplier,
+        } => StrategyRules {
+            entry: format!(
+                "Bid YES and NO at mid - spread/2 - {} (spread = {} x volatility) while YES + NO cost <= {}",
+                bid_offset, spread_multiplier, max_pair_cost
+            ),
+            exit: "Hold both sides to resolution: one of them pays 1 USDC".to_string(),
+            combine: None,
+            conditions: Vec::new(),
+        },
+        S::RelativeStrength { lookback, top_k } => StrategyRules {
+            entry: format!(
+                "Hold the top {} symbols by {}-bar return, in equal parts",
+                top_k, lookback
+            ),
+            exit: format!("Sell a symbol once it drops out of the top {}", top_k),
+            combine: None,
+            conditions: Vec::new(),
+        },
+        S::PairSpread {
+            leg_a,
+            leg_b,
+            lookback,
+            entry_z,
+            exit_z,
+        } => StrategyRules {
+            entry: format!(
+                "Buy {a} / sell {b} when the z-score of ln({a}/{b}) over {lookback} bars < -{z}; \
+                 sell {a} / buy {b} when it is > {z}",
+                a = leg_a,
+                b = leg_b,
+                lookback = lookback,
+                z = entry_z
+            ),
+            exit: format!("Close both legs once the z-score is back within ±{}", exit_z),
+            combine: None,
+            conditions: Vec::new(),
+        },
+        S::OnnxModel {
+            model_path,
+            threshold,
+        } => StrategyRules {
+            entry: format!(
+                "Buy when the ONNX classifier {} gives buy the highest probability, at least {}",
+                model_path, threshold
+            ),
+            exit: format!(
+                "Sell when it gives sell the highest probability, at least {}",
+                threshold
+            ),
+            combine: None,
+            conditions: Vec::new(),
+        },
+        S::Ensemble {
+            members,
+            weights,
+            vote,
+        } => {
+            let voters = members
+                .iter()
+                .zip(weights)
+                .map(|(m, w)| format!("{} ({})", m.name(), w))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let total: f64 = weights.iter().sum();
+            let vote = match vote {
+                EnsembleVote::Majority => "one vote per member",
+                EnsembleVote::ConfidenceWeighted => "members weighted by strategy confidence",
+            };
+            StrategyRules {
+                entry: format!("Buy when members weighing more than {} buy: {}", total / 2.0, voters),
+                exit: format!("Sell when members weighing more than {} sell", total / 2.0),
+                combine: Some(format!("vote of {} members, {}", members.len(), vote)),
+                conditions: Vec::new(),
+            }
+        }
+        S::Filtered { inner, filter } => {
+            let mut rules = explain_strategy(inner);
+            rules.conditions.push(filter_condition(filter));
+            rules
+        }
+        S::HtfFiltered { inner, htf_filter } => {
+            let mut rules = explain_strategy(inner);
+            rules.conditions.push(htf_condition(htf_filter));
+            rules
+        }
+        S::ZScoreFiltered {
+            inner,
+            zscore_filter,
+        } => {
+            let mut rules = explain_strategy(inner);
+            rules.conditions.push(zscore_condition(zscore_filter));
+            rules
+        }
+        S::Trailing { inner, stop } => {
+            let mut rules = explain_strategy(inner);
+            rules.conditions.push(trailing_condition(stop));
+            rules
+        }
+        _ => unreachable!("single-indicator and combo strategies are explained above"),
+    }
+}
+
+/// Indicators of a single-indicator or combo strategy, with how a combo combines them
+/// (`None` for a single indicator); `None` for the other strategies
+pub fn strategy_indicators(
+    strategy: &DiscoveryStrategyType,
+) -> Option<(Vec<IndicatorParams>, Option<DynCombineMode>)> {
+    use DiscoveryStrategyType as S;
+    let single = |params| Some((vec![params], None));
+    let combo = |params, mode| Some((params, Some(mode)));
+    match strategy {
+        S::Rsi {
+            period,
+            overbought,
+            oversold,
+        } => single(IndicatorParams::Rsi {
+            period: *period,
+            overbought: *overbought,
+            oversold: *oversold,
+        }),
+        S::BollingerBands { period, multiplier } => single(IndicatorParams::BollingerBands {
+            period: *period,
+            multiplier: *multiplier,
+        }),
+        S::Macd { fast, slow, signal } => single(IndicatorParams::Macd {
+            fast: *fast,
+            slow: *slow,
+            signal: *signal,
+        }),
+        S::EmaCrossover {
+            fast_period,
+            slow_period,
+        } => single(IndicatorParams::EmaCrossover {
+            fast_period: *fast_period,
+            slow_period: *slow_period,
+        }),
+        S::Stochastic {
+            period,
+            overbought,
+            oversold,
+        } => single(IndicatorParams::Stochastic {
+            period: *period,
+            overbought: *overbought,
+            oversold: *oversold,
+        }),
+        S::AtrMeanReversion {
+            atr_period,
+            sma_period,
+            multiplier,
+        } => single(IndicatorParams::AtrMeanReversion {
+            atr_period: *atr_period,
+            sma_period: *sma_period,
+            multiplier: *multiplier,
+        }),
+        S::Vwap { period, anchor } => single(IndicatorParams::Vwap {
+            period: *period,
+            anchor: *anchor,
+        }),
+        S::Obv { sma_period } => single(IndicatorParams::Obv {
+            sma_period: *sma_period,
+        }),
+        S::WilliamsR {
+            period,
+            overbought,
+            oversold,
+        } => single(IndicatorParams::WilliamsR {
+            period: *period,
+            overbought: *overbought,
+            oversold: *oversold,
+        }),
+        S::Adx {
+            period,
+            adx_threshold,
+        } => single(IndicatorParams::Adx {
+            period: *period,
+            adx_threshold: *adx_threshold,
+        }),
+        S::RsiBollinger {
+            rsi_period,
+            rsi_ob,
+            rsi_os,
+            bb_period,
+            bb_mult,
+        } => combo(
+            vec![
+                rsi(*rsi_period, *rsi_ob, *rsi_os),
+                bollinger(*bb_period, *bb_mult),
+            ],
+            DynCombineMode::Unanimous,
+        ),
+        S::MacdRsi {
+            macd_fast,
+            macd_slow,
+            macd_signal,
+            rsi_period,
+            rsi_ob,
+            rsi_os,
+        } => combo(
+            vec![
+                macd(*macd_fast, *macd_slow, *macd_signal),
+                rsi(*rsi_period, *rsi_ob, *rsi_os),
+            ],
+            DynCombineMode::PrimaryConfirmed,
+        ),
+        S::EmaRsi {
+            ema_fast,
+            ema_slow,
+            rsi_period,
+            rsi_ob,
+            rsi_os,
+        } => combo(
+            vec![
+                ema(*ema_fast, *ema_slow),
+                rsi(*rsi_period, *rsi_ob, *rsi_os),
+            ],
+            DynCombineMode::PrimaryConfirmed,
+        ),
+        S::StochRsi {
+            stoch_period,
+            stoch_ob,
+            stoch_os,
+            rsi_period,
+            rsi_ob,
+            rsi_os,
+        } => combo(
+            vec![
+                stochastic(*stoch_period, *stoch_ob, *stoch_os),
+                rsi(*rsi_period, *rsi_ob, *rsi_os),
+            ],
+            DynCombineMode::Unanimous,
+        ),
+        S::MacdBollinger {
+            macd_fast,
+            macd_slow,
+            macd_signal,
+            bb_period,
+            bb_mult,
+        } => combo(
+            vec![
+                macd(*macd_fast, *macd_slow, *macd_signal),
+                bollinger(*bb_period, *bb_mult),
+            ],
+            DynCombineMode::PrimaryConfirmed,
+        ),
+        S::TripleRsiMacdBb {
+            rsi_period,
+            rsi_ob,
+            rsi_os,
+            macd_fast,
+            macd_slow,
+            macd_signal,
+            bb_period,
+            bb_mult,
+        } => combo(
+            vec![
+                rsi(*rsi_period, *rsi_ob, *rsi_os),
+                macd(*macd_fast, *macd_slow, *macd_signal),
+                bollinger(*bb_period, *bb_mult),
+            ],
+            DynCombineMode::Majority,
+        ),
+        S::TripleEmaRsiStoch {
+            ema_fast,
+            ema_slow,
+            rsi_period,
+            rsi_ob,
+            rsi_os,
+            stoch_period,
+            stoch_ob,
+            stoch_os,
+        } => combo(
+            vec![
+                ema(*ema_fast, *ema_slow),
+                rsi(*rsi_period, *rsi_ob, *rsi_os),
+                stochastic(*stoch_period, *stoch_ob, *stoch_os),
+            ],
+            DynCombineMode::Majority,
+        ),
+        S::VwapRsi {
+            vwap_period,
+            rsi_period,
+            rsi_overbought,
+            rsi_oversold,
+        } => combo(
+            vec![
+                IndicatorParams::Vwap {
+                    period: *vwap_period,
+                    anchor: VwapAnchor::Rolling,
+                },
+                rsi(*rsi_period, *rsi_overbought, *rsi_oversold),
+            ],
+            DynCombineMode::PrimaryConfirmed,
+        ),
+        S::ObvMacd {
+            obv_sma_period,
+            macd_fast,
+            macd_slow,
+            macd_signal,
+        } => combo(
+            vec![
+                macd(*macd_fast, *macd_slow, *macd_signal),
+                IndicatorParams::Obv {
+                    sma_period: *obv_sma_period,
+                },
+            ],
+            DynCombineMode::PrimaryConfirmed,
+        ),
+        S::AdxEma {
+            adx_period,
+            adx_threshold,
+            ema_fast,
+            ema_slow,
+        } => combo(
+            vec![
+                ema(*ema_fast, *ema_slow),
+                IndicatorParams::Adx {
+                    period: *adx_period,
+                    adx_threshold: *adx_threshold,
+                },
+            ],
+            DynCombineMode::PrimaryConfirmed,
+        ),
+        S::WilliamsRStoch {
+            wr_period,
+            wr_overbought,
+            wr_oversold,
+            stoch_period,
+            stoch_overbought,
+            stoch_oversold,
+        } => combo(
+            vec![
+                IndicatorParams::WilliamsR {
+                    period: *wr_period,
+                    overbought: *wr_overbought,
+                    oversold: *wr_oversold,
+                },
+                stochastic(*stoch_period, *stoch_overbought, *stoch_oversold),
+            ],
+            DynCombineMode::Unanimous,
+        ),
+        S::DynamicCombo {
+            params,
+            combine_mode,
+            ..
+        } => Some((params.clone(), Some(*combine_mode))),
+        S::WebStrategy { .. }
+        | S::Gabagool { .. }
+        | S::RelativeStrength { .. }
+        | S::PairSpread { .. }
+        | S::OnnxModel { .. }
+        | S::Ensemble { .. }
+        | S::Filtered { .. }
+        | S::HtfFiltered { .. }
+        | S::ZScoreFiltered { .. }
+        | S::Trailing { .. } => None,
+    }
+}
+
+/// Rules, sizing and fees of a stored result
+pub fn explain_record(record: &DiscoveryBacktestRecord) -> Result<StrategyExplanation, String> {
+    let strategy =
+        decode_strategy_params(&record.strategy_params, record.schema_version.unwrap_or(0))?;
+    let rules = explain_strategy(&strategy);
+
+    let mut sizing = sizing_description(parse_sizing_mode(&record.sizing_mode));
+    if let Some(json) = &record.drawdown_throttle {
+        let throttle: DrawdownThrottle = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid drawdown throttle {}: {}", json, e))?;
+        sizing.push_str(&format!(
+            "; entries shrink from {}% drawdown down to {}x their size and pause from {}%",
+            throttle.start_pct, throttle.min_scale, throttle.pause_pct
+        ));
+    }
+    let fees = fee_description(record.probability_model.as_deref().unwrap_or("linear"));
+
+    let mut lines = vec![
+        format!("{} on {}", record.strategy_name, record.symbol),
+        rules.entry.clone(),
+        rules.exit.clone(),
+    ];
+    lines.extend(rules.combine.iter().map(|c| format!("Combine: {}", c)));
+    lines.extend(rules.conditions.iter().cloned());
+    lines.push(format!("Sizing: {}", sizing));
+    lines.push(format!("Fees: {}", fees));
+
+    Ok(StrategyExplanation {
+        strategy_name: record.strategy_name.clone(),
+        symbol: record.symbol.clone(),
+        rules,
+        sizing,
+        fees,
+        text: lines.join("\n"),
+    })
+}
+
+fn rsi(period: usize, overbought: f64, oversold: f64) -> IndicatorParams {
+    IndicatorParams::Rsi {
+        period,
+        overbought,
+        oversold,
+    }
+}
+
+fn bollinger(period: usize, multiplier: f64) -> IndicatorParams {
+    IndicatorParams::BollingerBands { period, multiplier }
+}
+
+fn macd(fast: usize, slow: usize, signal: usize) -> IndicatorParams {
+    IndicatorParams::Macd { fast, slow, signal }
+}
+
+fn ema(fast_period: usize, slow_period: usize) -> IndicatorParams {
+    IndicatorParams::EmaCrossover {
+        fast_period,
+        slow_period,
+    }
+}
+
+fn stochastic(period: usize, overbought: f64, oversold: f64) -> IndicatorParams {
+    IndicatorParams::Stochastic {
+        period,
+        overbought,
+        oversold,
+    }
+}
+
+/// (buy, sell) conditions of one indicator's signal generator
+fn indicator_conditions(params: &IndicatorParams) -> (String, String) {
+    match *params {
+        IndicatorParams::Rsi {
+            period,
+            overbought,
+            oversold,
+        } => (
+            format!("RSI({}) < {}", period, oversold),
+            format!("RSI({}) > {}", period, overbought),
+        ),
+        IndicatorParams::BollingerBands { period, multiplier } => (
+            format!("price < lower BB({},{})", period, multiplier),
+            format!("price > upper BB({},{})", period, multiplier),
+        ),
+        IndicatorParams::Macd { fast, slow, signal } => (
+            format!(
+                "MACD({},{},{}) histogram crosses above 0",
+                fast, slow, signal
+            ),
+            format!(
+                "MACD({},{},{}) histogram crosses below 0",
+                fast, slow, signal
+            ),
+        ),
+        IndicatorParams::EmaCrossover {
+            fast_period,
+            slow_period,
+        } => (
+            format!("EMA({}) crosses above EMA({})", fast_period, slow_period),
+            format!("EMA({}) crosses below EMA({})", fast_period, slow_period),
+        ),
+        IndicatorParams::Stochastic {
+            period,
+            overbought,
+            oversold,
+        } => (
+            format!("Stoch %K({}) crosses above %D under {}", period, oversold),
+            format!("Stoch %K({}) crosses below %D over {}", period, overbought),
+        ),
+        IndicatorParams::AtrMeanReversion {
+            atr_period,
+            sma_period,
+            multiplier,
+        } => (
+            format!(
+                "price < SMA({}) - {} x ATR({})",
+                sma_period, multiplier, atr_period
+            ),
+            format!(
+                "price > SMA({}) + {} x ATR({})",
+                sma_period, multiplier, atr_period
+            ),
+        ),
+        IndicatorParams::Vwap { period, anchor } => {
+            let vwap = match anchor {
+                VwapAnchor::Rolling => format!("VWAP({})", period),
+                VwapAnchor::Session => format!("VWAP since the UTC day start ({}+ bars)", period),
+                VwapAnchor::Days { days } => {
+                    format!(
+                        "VWAP since the last {}-day boundary ({}+ bars)",
+                        days, period
+                    )
+                }
+                VwapAnchor::SwingLow { lookback } => {
+                    format!(
+                        "VWAP since the last {}-bar swing low ({}+ bars)",
+                        lookback, period
+                    )
+                }
+            };
+            (format!("price < {}", vwap), format!("price > {}", vwap))
+        }
+        IndicatorParams::Obv { sma_period } => (
+            format!("OBV > its SMA({})", sma_period),
+            format!("OBV < its SMA({})", sma_period),
+        ),
+        IndicatorParams::WilliamsR {
+            period,
+            overbought,
+            oversold,
+        } => (
+            format!("Williams %R({}) < {}", period, oversold),
+            format!("Williams %R({}) > {}", period, overbought),
+        ),
+        IndicatorParams::Adx {
+            period,
+            adx_threshold,
+        } => (
+            format!("ADX({}) >= {} AND +DI > -DI", period, adx_threshold),
+            format!("ADX({}) >= {} AND -DI > +DI", period, adx_threshold),
+        ),
+    }
+}
+
+fn single_rules(params: &IndicatorParams) -> StrategyRules {
+    let (buy, sell) = indicator_conditions(params);
+    StrategyRules {
+        entry: format!("Buy when {}", buy),
+        exit: format!("Sell when {}", sell),
+        combine: None,
+        conditions: Vec::new(),
+    }
+}
+
+/// Rules of a combo, following `CombineMode` of the combo signal generator
+fn combo_rules(params: &[IndicatorParams], mode: DynCombineMode) -> StrategyRules {
+    let (buys, sells): (Vec<String>, Vec<String>) = params.iter().map(indicator_conditions).unzip();
+    let n = params.len();
+    let (entry, exit, combine) = match mode {
+        DynCombineMode::Unanimous => (
+            buys.join(" AND "),
+            sells.join(" AND "),
+            format!("all {} indicators agree", n),
+        ),
+        DynCombineMode::Majority => {
+            let threshold = n.div_ceil(2);
+            (
+                format!("at least {} of [{}]", threshold, buys.join(", ")),
+                format!("at least {} of [{}]", threshold, sells.join(", ")),
+                format!("majority of {} (buys checked first)", n),
+            )
+        }
+        DynCombineMode::PrimaryConfirmed => (
+            format!("{}, unless {}", buys[0], sells[1..].join(" AND ")),
+            format!("{}, unless {}", sells[0], buys[1..].join(" AND ")),
+            format!(
+                "the first of {} indicators, unless all the others disagree",
+                n
+            ),
+        ),
+    };
+    StrategyRules {
+        entry: format!("Buy when {}", entry),
+        exit: format!("Sell when {}", exit),
+        combine: Some(combine),
+        conditions: Vec::new(),
+    }
+}
+
+fn web_rules(params: &WebStrategyParams) -> StrategyRules {
+    let (entry, exit, conditions) = match *params {
+        WebStrategyParams::ProbabilityEdge {
+            edge_threshold,
+            rsi_period,
+            momentum_period,
+            vol_period,
+        } => (
+            format!(
+                "Buy when the estimated probability exceeds the market's by more than {} \
+                 (estimate from RSI({}), momentum against SMA({}) and {}-bar volatility)",
+                edge_threshold, rsi_period, momentum_period, vol_period
+            ),
+            format!(
+                "Sell when it is below the market's by more than {}",
+                edge_threshold
+            ),
+            Vec::new(),
+        ),
+        WebStrategyParams::CatalystMomentum {
+            spike_threshold,
+            trailing_stop_pct,
+            lookback,
+        } => (
+            format!(
+                "Buy when price > SMA({}) + {}",
+                lookback,
+                percent(spike_threshold)
+            ),
+            format!(
+                "Sell when price falls more than {} below its high since entry",
+                percent(trailing_stop_pct)
+            ),
+            Vec::new(),
+        ),
+        WebStrategyParams::FavoriteCompounder {
+            min_probability,
+            take_profit,
+            sma_period,
+        } => (
+            format!(
+                "Buy when the market probability >= {} AND price > SMA({})",
+                min_probability, sma_period
+            ),
+            format!("Sell when the gain since entry >= {}", percent(take_profit)),
+            Vec::new(),
+        ),
+        WebStrategyParams::MarketMakingSim {
+            spread,
+            sma_period,
+            inventory_limit,
+        } => (
+            format!(
+                "Buy when price < SMA({}) - {} (half the spread)",
+                sma_period,
+                percent(spread / 2.0)
+            ),
+            format!(
+                "Sell when price > SMA({}) + {}",
+                sma_period,
+                percent(spread / 2.0)
+            ),
+            vec![format!(
+                "Inventory kept within ±{} positions",
+                inventory_limit
+            )],
+        ),
+        WebStrategyParams::MeanReversionPoly {
+            sma_period,
+            entry_dev,
+            exit_dev,
+        } => (
+            format!(
+                "Buy when price is more than {} below SMA({})",
+                percent(entry_dev),
+                sma_period
+            ),
+            format!(
+                "Sell once it is back within {} of SMA({})",
+                percent(exit_dev),
+                sma_period
+            ),
+            vec![format!(
+                "Mirrored when price is more than {} above SMA({})",
+                percent(entry_dev),
+                sma_period
+            )],
+        ),
+    };
+    StrategyRules {
+        entry,
+        exit,
+        combine: None,
+        conditions,
+    }
+}
+
+fn filter_condition(filter: &EntryFilter) -> String {
+    let mut parts = Vec::new();
+    if let Some(min) = filter.min_volume_24h {
+        parts.push(format!("24h volume >= {}", compact_amount(min)));
+    }
+    match (filter.min_volatility_pct, filter.max_volatility_pct) {
+        (Some(min), Some(max)) => {
+            parts.push(format!("24h volatility between {}% and {}%", min, max))
+        }
+        (Some(min), None) => parts.push(format!("24h volatility >= {}%", min)),
+        (None, Some(max)) => parts.push(format!("24h volatility <= {}%", max)),
+        (None, None) => {}
+    }
+    if parts.is_empty() {
+        "Entries unfiltered".to_string()
+    } else {
+        format!("Entries only while {}", parts.join(" AND "))
+    }
+}
+
+fn htf_condition(filter: &HtfFilter) -> String {
+    let interval = filter.interval.label();
+    match filter.trend {
+        HtfTrend::EmaSlope { period } => {
+            format!(
+                "Entries only while the {} EMA({}) is rising",
+                interval, period
+            )
+        }
+        HtfTrend::Adx { period, min_adx } => format!(
+            "Entries only while the {} ADX({}) >= {} AND +DI > -DI",
+            interval, period, min_adx
+        ),
+    }
+}
+
+fn zscore_condition(filter: &ZScoreFilter) -> String {
+    format!(
+        "Signals only when the indicator's {}-bar z-score is beyond ±{}",
+        filter.window, filter.threshold
+    )
+}
+
+fn trailing_condition(stop: &TrailingStop) -> String {
+    let distance = match *stop {
+        TrailingStop::Percent { pct } => format!("{}%", pct),
+        TrailingStop::Atr { period, multiplier } => format!("{} x ATR({})", multiplier, period),
+    };
+    format!(
+        "Positions also close when price falls {} below its high since entry",
+        distance
+    )
+}
+
+fn sizing_description(mode: SizingMode) -> String {
+    let base = format!(
+        "{}% of equity per trade ({} USDC initial capital)",
+        BASE_POSITION_PCT, INITIAL_CAPITAL
+    );
+    match mode {
+        SizingMode::Fixed => base,
+        SizingMode::ConfidenceWeighted => format!("{}, scaled by signal confidence", base),
+        SizingMode::Kelly => {
+            let kelly = KellyConfig::default();
+            format!(
+                "{}x Kelly on the last {} trades, at most {}% of equity ({} until {} trades)",
+                kelly.fraction, kelly.window, kelly.max_pct, base, kelly.min_trades
+            )
+        }
+    }
+}
+
+/// Polymarket taker fee as priced by the discovery backtests (the KB only stores
+/// Polymarket-preset runs)
+fn fee_description(probability_model: &str) -> String {
+    let config = PolymarketFeeConfig::default();
+    format!(
+        "Polymarket taker fee of shares x {} x (p(1-p))^{} per fill, p estimated from the \
+         price move by the {} probability model",
+        config.fee_rate.to_f64().unwrap_or(0.0),
+        config.exponent,
+        probability_model
+    )
+}
+
+/// 0.025 → `2.5%`
+fn percent(fraction: f64) -> String {
+    format!("{}%", (fraction * 10_000.0).round() / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{result_to_record, run_backtest, BacktestLimits, SingleIndicatorType};
+    use crate::htf_filter::HtfInterval;
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_explain_combos_and_wrappers() {
+        let rules = explain_strategy(&DiscoveryStrategyType::RsiBollinger {
+            rsi_period: 14,
+            rsi_ob: 70.0,
+            rsi_os: 30.0,
+            bb_period: 20,
+            bb_mult: 2.0,
+        });
+        assert_eq!(
+            rules.entry,
+            "Buy when RSI(14) < 30 AND price < lower BB(20,2)"
+        );
+        assert_eq!(
+            rules.exit,
+            "Sell when RSI(14) > 70 AND price > upper BB(20,2)"
+        );
+        assert_eq!(rules.combine.as_deref(), Some("all 2 indicators agree"));
+
+        let triple = DiscoveryStrategyType::DynamicCombo {
+            indicators: vec![
+                SingleIndicatorType::Rsi,
+                SingleIndicatorType::Vwap,
+                SingleIndicatorType::Obv,
+            ],
+            params: vec![
+                rsi(14, 70.0, 30.0),
+                IndicatorParams::Vwap {
+                    period: 20,
+                    anchor: VwapAnchor::Rolling,
+                },
+                IndicatorParams::Obv { sma_period: 10 },
+            ],
+            combine_mode: DynCombineMode::Majority,
+        };
+        let rules = explain_strategy(&triple);
+        assert_eq!(
+            rules.entry,
+            "Buy when at least 2 of [RSI(14) < 30, price < VWAP(20), OBV > its SMA(10)]"
+        );
+        assert_eq!(
+            rules.combine.as_deref(),
+            Some("majority of 3 (buys checked first)")
+        );
+
+        // An anchored VWAP says where it is summed from
+        let anchored = DiscoveryStrategyType::Vwap {
+            period: 10,
+            anchor: VwapAnchor::SwingLow { lookback: 12 },
+        };
+        assert_eq!(
+            explain_strategy(&anchored).entry,
+            "Buy when price < VWAP since the last 12-bar swing low (10+ bars)"
+        );
+
+        // Wrappers add their conditions to the inner strategy's rules, outermost last
+        let wrapped = DiscoveryStrategyType::Trailing {
+            inner: Box::new(DiscoveryStrategyType::HtfFiltered {
+                inner: Box::new(DiscoveryStrategyType::Filtered {
+                    inner: Box::new(DiscoveryStrategyType::AdxEma {
+                        adx_period: 14,
+                        adx_threshold: 25.0,
+                        ema_fast: 9,
+                        ema_slow: 21,
+                    }),
+                    filter: EntryFilter {
+                        min_volume_24h: Some(12e6),
+                        ..Default::default()
+                    },
+                }),
+                htf_filter: HtfFilter {
+                    interval: HtfInterval::H4,
+                    trend: HtfTrend::EmaSlope { period: 20 },
+                },
+            }),
+            stop: TrailingStop::Percent { pct: 2.0 },
+        };
+        let rules = explain_strategy(&wrapped);
+        assert_eq!(
+            rules.entry,
+            "Buy when EMA(9) crosses above EMA(21), unless ADX(14) >= 25 AND -DI > +DI"
+        );
+        assert_eq!(
+            rules.conditions,
+            vec![
+                "Entries only while 24h volume >= 12M",
+                "Entries only while the 4h EMA(20) is rising",
+                "Positions also close when price falls 2% below its high since entry",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explain_record_adds_sizing_and_fees() {
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 500,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let result = run_backtest(
+            &strategy,
+            &klines,
+            "BTCUSDT",
+            SizingMode::Kelly,
+            &BacktestLimits::unlimited(),
+        );
+        let mut record = result_to_record(&result, "hash", "run", "phase1", 30);
+        record.drawdown_throttle =
+            Some(serde_json::to_string(&DrawdownThrottle::SEARCH_PRESETS[0]).unwrap());
+        let explanation = explain_record(&record).unwrap();
+        assert!(explanation
+            .sizing
+            .starts_with("0.5x Kelly on the last 20 trades"));
+        assert!(explanation.sizing.ends_with("pause from 15%"));
+        assert!(explanation.fees.contains("shares x 0.25 x (p(1-p))^2"));
+        assert!(explanation.fees.contains("linear probability model"));
+        assert_eq!(
+            explanation.text.lines().take(3).collect::<Vec<_>>(),
+            vec![
+                "RSI on BTCUSDT",
+                "Buy when RSI(14) < 30",
+                "Sell when RSI(14) > 70"
+            ]
+        );
+        assert!(explanation
+            .text
+            .lines()
+            .last()
+            .unwrap()
+            .starts_with("Fees: "));
+
+        let broken = DiscoveryBacktestRecord {
+            strategy_params: "{}".to_string(),
+            ..record
+        };
+        assert!(explain_record(&broken).is_err());
+    }
+}
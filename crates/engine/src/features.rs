@@ -0,0 +1,338 @@
+//! ML feature export
+//!
+//! Runs the single-indicator signal generators (default params) over the klines of
+//! each symbol and collects, per bar, the indicator values they computed plus forward
+//! returns as labels. The table is written as Parquet so external models train on
+//! exactly the features the discovery strategies trade on.
+
+use anyhow::Context;
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use rust_decimal::prelude::ToPrimitive;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::discovery::SingleIndicatorType;
+use crate::indicators::{build_single_generator, SignalGenerator};
+use crate::types::Kline;
+
+/// Leading bars dropped per symbol: the slowest default indicators (MACD 26+9,
+/// ADX 2×14) are not warmed up before that
+pub const FEATURE_WARMUP_BARS: usize = 50;
+
+/// Default label horizons in 15m bars: 15m, 1h, 4h, 1d
+pub const DEFAULT_HORIZONS: &[usize] = &[1, 4, 16, 96];
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct FeatureRow {
+    pub symbol: String,
+    pub open_time: i64,
+    /// open, high, low, close, volume
+    pub ohlcv: [f64; 5],
+    /// Same order as `FeatureTable::columns`
+    pub features: Vec<f64>,
+    /// `close[t + h] / close[t] - 1` for each horizon, `None` past the last bar
+    pub forward_returns: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FeatureTable {
+    /// Indicator column names (`rsi`, `bb_upper`, `macd`, ...)
+    pub columns: Vec<String>,
+    pub horizons: Vec<usize>,
+    pub rows: Vec<FeatureRow>,
+}
+
+impl FeatureTable {
+    /// Append the rows of another symbol computed with the same columns/horizons
+    pub fn append(&mut self, other: FeatureTable) {
+        if self.columns.is_empty() {
+            self.columns = other.columns;
+            self.horizons = other.horizons;
+        }
+        self.rows.extend(other.rows);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Computation
+// ---------------------------------------------------------------------------
+
+/// The 10 single-indicator generators with default params, fed bar by bar. This is
+/// the feature vector of the export and the input of ONNX models.
+pub struct IndicatorSnapshot {
+    generators: Vec<Box<dyn SignalGenerator>>,
+    bars_seen: usize,
+}
+
+impl Default for IndicatorSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IndicatorSnapshot {
+    pub fn new() -> Self {
+        Self {
+            generators: SingleIndicatorType::all()
+                .iter()
+                .map(|ind| build_single_generator(ind, &ind.default_params()))
+                .collect(),
+            bars_seen: 0,
+        }
+    }
+
+    pub fn on_bar(&mut self, kline: &Kline) {
+        for generator in self.generators.iter_mut() {
+            generator.on_bar(kline);
+        }
+        self.bars_seen += 1;
+    }
+
+    /// False during the first `FEATURE_WARMUP_BARS` bars
+    pub fn is_warm(&self) -> bool {
+        self.bars_seen > FEATURE_WARMUP_BARS
+    }
+
+    pub fn columns(&self) -> Vec<String> {
+        self.generators
+            .iter()
+            .flat_map(|g| g.features())
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Values after the last bar, in `columns()` order
+    pub fn values(&self) -> Vec<f64> {
+        self.generators
+            .iter()
+            .flat_map(|g| g.features())
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    pub fn reset(&mut self) {
+        for generator in self.generators.iter_mut() {
+            generator.reset();
+        }
+        self.bars_seen = 0;
+    }
+}
+
+/// Feature rows of one symbol (klines in chronological order)
+pub fn compute_features(symbol: &str, klines: &[Kline], horizons: &[usize]) -> FeatureTable {
+    let mut snapshot = IndicatorSnapshot::new();
+    let columns = snapshot.columns();
+
+    let closes: Vec<f64> = klines
+        .iter()
+        .map(|k| k.close.to_f64().unwrap_or(0.0))
+        .collect();
+
+    let mut rows = Vec::with_capacity(klines.len().saturating_sub(FEATURE_WARMUP_BARS));
+    for (i, kline) in klines.iter().enumerate() {
+        snapshot.on_bar(kline);
+        if !snapshot.is_warm() {
+            continue;
+        }
+
+        let forward_returns = horizons
+            .iter()
+            .map(|&h| match closes.get(i + h) {
+                Some(&future) if closes[i] > 0.0 => Some(future / closes[i] - 1.0),
+                _ => None,
+            })
+            .collect();
+
+        rows.push(FeatureRow {
+            symbol: symbol.to_string(),
+            open_time: kline.open_time,
+            ohlcv: [
+                kline.open.to_f64().unwrap_or(0.0),
+                kline.high.to_f64().unwrap_or(0.0),
+                kline.low.to_f64().unwrap_or(0.0),
+                closes[i],
+                kline.volume.to_f64().unwrap_or(0.0),
+            ],
+            features: snapshot.values(),
+            forward_returns,
+        });
+    }
+
+    FeatureTable {
+        columns,
+        horizons: horizons.to_vec(),
+        rows,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parquet
+// ---------------------------------------------------------------------------
+
+/// Column order: symbol, open_time, OHLCV, indicator columns, `fwd_return_<h>` labels
+fn to_record_batch(table: &FeatureTable) -> anyhow::Result<RecordBatch> {
+    let mut fields = vec![
+        Field::new("symbol", DataType::Utf8, false),
+        Field::new("open_time", DataType::Int64, false),
+    ];
+    let mut arrays: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            table.rows.iter().map(|r| r.symbol.as_str()),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            table.rows.iter().map(|r| r.open_time),
+        )),
+    ];
+
+    for (i, name) in ["open", "high", "low", "close", "volume"]
+        .iter()
+        .enumerate()
+    {
+        fields.push(Field::new(*name, DataType::Float64, false));
+        arrays.push(Arc::new(Float64Array::from_iter_values(
+            table.rows.iter().map(|r| r.ohlcv[i]),
+        )));
+    }
+    for (i, name) in table.columns.iter().enumerate() {
+        fields.push(Field::new(name, DataType::Float64, false));
+        arrays.push(Arc::new(Float64Array::from_iter_values(
+            table.rows.iter().map(|r| r.features[i]),
+        )));
+    }
+    for (i, h) in table.horizons.iter().enumerate() {
+        fields.push(Field::new(
+            format!("fwd_return_{}", h),
+            DataType::Float64,
+            true,
+        ));
+        arrays.push(Arc::new(Float64Array::from_iter(
+            table.rows.iter().map(|r| r.forward_returns[i]),
+        )));
+    }
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+        .context("cannot build feature batch")
+}
+
+/// Write the table as a Snappy-compressed Parquet file, returning the row count
+pub fn write_features_parquet(table: &FeatureTable, out: &Path) -> anyhow::Result<usize> {
+    if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("cannot create {}", parent.display()))?;
+    }
+
+    let batch = to_record_batch(table)?;
+    let file = File::create(out).with_context(|| format!("cannot create {}", out.display()))?;
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(batch.num_rows())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn make_klines(closes: &[f64]) -> Vec<Kline> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let close = Decimal::from_f64_retain(c).unwrap();
+                let open_time = i as i64 * 900_000;
+                Kline {
+                    open_time,
+                    open: close,
+                    high: close + Decimal::ONE,
+                    low: close - Decimal::ONE,
+                    close,
+                    volume: Decimal::from(10 + i as i64 % 7),
+                    close_time: open_time + 899_999,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compute_features_columns_and_labels() {
+        let closes: Vec<f64> = (0..120)
+            .map(|i| 100.0 + (i as f64 / 5.0).sin() * 3.0)
+            .collect();
+        let table = compute_features("BTCUSDT", &make_klines(&closes), &[1, 4]);
+
+        for col in [
+            "rsi", "bb_upper", "macd", "stoch_k", "atr", "vwap", "obv", "adx",
+        ] {
+            assert!(table.columns.iter().any(|c| c == col), "missing {}", col);
+        }
+        assert_eq!(table.rows.len(), 120 - FEATURE_WARMUP_BARS);
+
+        let first = &table.rows[0];
+        assert_eq!(first.open_time, FEATURE_WARMUP_BARS as i64 * 900_000);
+        assert_eq!(first.features.len(), table.columns.len());
+        let expected = closes[FEATURE_WARMUP_BARS + 4] / closes[FEATURE_WARMUP_BARS] - 1.0;
+        assert!((first.forward_returns[1].unwrap() - expected).abs() < 1e-12);
+
+        // Labels past the end are missing
+        let last = table.rows.last().unwrap();
+        assert_eq!(last.forward_returns, vec![None, None]);
+        assert!(table.rows[table.rows.len() - 2].forward_returns[0].is_some());
+
+        // Too short: warmup only
+        assert!(compute_features("X", &make_klines(&closes[..30]), &[1])
+            .rows
+            .is_empty());
+    }
+
+    #[test]
+    fn test_write_features_parquet_roundtrip() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let closes: Vec<f64> = (0..80).map(|i| 50.0 + i as f64 * 0.1).collect();
+        let mut table = compute_features("BTCUSDT", &make_klines(&closes), DEFAULT_HORIZONS);
+        table.append(compute_features(
+            "ETHUSDT",
+            &make_klines(&closes),
+            DEFAULT_HORIZONS,
+        ));
+
+        let path =
+            std::env::temp_dir().join(format!("poly-features-test-{}.parquet", std::process::id()));
+        let written = write_features_parquet(&table, &path).unwrap();
+        assert_eq!(written, 2 * (80 - FEATURE_WARMUP_BARS));
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, written);
+
+        let schema = batches[0].schema();
+        assert_eq!(schema.field(0).name(), "symbol");
+        assert_eq!(
+            schema.fields().len(),
+            2 + 5 + table.columns.len() + DEFAULT_HORIZONS.len()
+        );
+        assert!(schema
+            .field_with_name("fwd_return_96")
+            .unwrap()
+            .is_nullable());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
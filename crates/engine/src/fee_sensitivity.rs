@@ -0,0 +1,269 @@
+//! Fee sensitivity of stored strategies
+//!
+//! Re-runs knowledge base strategies on the same klines under each `FeePreset`, so the
+//! edges that only exist before fees stand out: a strategy profitable without fees but
+//! losing under the Polymarket schedule has its edge eaten by fees. Fees are estimated
+//! with the probability model each record was stored with.
+
+use std::collections::HashMap;
+
+use persistence::repository::DiscoveryBacktestRecord;
+use serde::{Deserialize, Serialize};
+
+use crate::api::BinanceClient;
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::discovery::{
+    dec_to_f64, parse_sizing_mode, run_backtest_with_fees, BacktestLimits, DiscoveryStrategyType,
+    SizingMode,
+};
+use crate::fees::{FeePreset, PolymarketFeeConfig};
+use crate::probability::ProbabilityModelConfig;
+use crate::types::Kline;
+use crate::validation::validation_strategy;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// One strategy backtested under one preset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeeScenario {
+    pub preset: String,
+    pub total_trades: u32,
+    pub win_rate: f64,
+    pub net_pnl: f64,
+    pub total_fees: f64,
+    pub composite_score: f64,
+}
+
+/// A stored strategy under every preset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeeSensitivity {
+    pub backtest_id: Option<i64>,
+    pub strategy_name: String,
+    pub symbol: String,
+    pub days: i64,
+    /// Same order as `FeeSensitivityReport.presets`
+    pub scenarios: Vec<FeeScenario>,
+    /// Presets the strategy stays profitable under
+    pub profitable_under: Vec<String>,
+    /// Profitable without fees, not under the Polymarket fees
+    pub edge_vanishes: bool,
+}
+
+/// Fee sensitivity of a set of stored strategies
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeeSensitivityReport {
+    pub presets: Vec<String>,
+    pub strategies: Vec<FeeSensitivity>,
+    /// Strategies whose edge disappears under the Polymarket fees
+    pub vanished: usize,
+    /// Records that could not be re-run, with the reason
+    pub skipped: Vec<String>,
+}
+
+/// `presets` with the zero-fee and Polymarket baselines in front (duplicates dropped)
+pub fn with_baselines(presets: &[FeePreset]) -> Vec<FeePreset> {
+    let mut all = vec![FeePreset::ZeroFee, FeePreset::Polymarket];
+    for preset in presets {
+        if !all.contains(preset) {
+            all.push(preset.clone());
+        }
+    }
+    all
+}
+
+/// Backtest `strategy` on `klines` under each preset, fees estimated with `probability_model`
+pub fn fee_scenarios(
+    strategy: &DiscoveryStrategyType,
+    klines: &[Kline],
+    symbol: &str,
+    sizing_mode: SizingMode,
+    presets: &[FeePreset],
+    probability_model: &ProbabilityModelConfig,
+) -> Vec<FeeScenario> {
+    let limits = BacktestLimits::default();
+    presets
+        .iter()
+        .map(|preset| {
+            let fee_config = PolymarketFeeConfig {
+                probability_model: probability_model.clone(),
+                ..preset.config()
+            };
+            let result =
+                run_backtest_with_fees(strategy, klines, symbol, sizing_mode, &limits, &fee_config);
+            FeeScenario {
+                preset: preset.name(),
+                total_trades: result.total_trades,
+                win_rate: dec_to_f64(result.win_rate),
+                net_pnl: dec_to_f64(result.net_pnl),
+                total_fees: dec_to_f64(result.total_fees),
+                composite_score: dec_to_f64(result.composite_score),
+            }
+        })
+        .collect()
+}
+
+/// Profitable without fees, not under the Polymarket fees (`scenarios` in `presets` order)
+fn edge_vanishes(scenarios: &[FeeScenario], presets: &[FeePreset]) -> bool {
+    let pnl_under = |target: &FeePreset| {
+        presets
+            .iter()
+            .position(|p| p == target)
+            .map(|i| scenarios[i].net_pnl)
+    };
+    pnl_under(&FeePreset::ZeroFee).is_some_and(|pnl| pnl > 0.0)
+        && pnl_under(&FeePreset::Polymarket).is_some_and(|pnl| pnl <= 0.0)
+}
+
+fn sensitivity(
+    record: &DiscoveryBacktestRecord,
+    scenarios: Vec<FeeScenario>,
+    presets: &[FeePreset],
+) -> FeeSensitivity {
+    FeeSensitivity {
+        backtest_id: record.id,
+        strategy_name: record.strategy_name.clone(),
+        symbol: record.symbol.clone(),
+        days: record.days,
+        profitable_under: scenarios
+            .iter()
+            .filter(|s| s.net_pnl > 0.0)
+            .map(|s| s.preset.clone())
+            .collect(),
+        edge_vanishes: edge_vanishes(&scenarios, presets),
+        scenarios,
+    }
+}
+
+/// Re-run `records` over their `days` window ending now under the zero-fee and
+/// Polymarket baselines plus `presets`. Klines are fetched once per symbol and window.
+pub async fn fee_sensitivity(
+    records: &[DiscoveryBacktestRecord],
+    binance: &BinanceClient,
+    presets: &[FeePreset],
+) -> FeeSensitivityReport {
+    let presets = with_baselines(presets);
+    let mut report = FeeSensitivityReport {
+        presets: presets.iter().map(FeePreset::name).collect(),
+        ..Default::default()
+    };
+    let mut klines_cache: HashMap<(String, i64), Vec<Kline>> = HashMap::new();
+    let end_time = chrono::Utc::now().timestamp_millis();
+
+    for record in records {
+        let strategy = match validation_strategy(record) {
+            Ok(strategy) => strategy,
+            Err(e) => {
+                report.skipped.push(e);
+                continue;
+            }
+        };
+        let key = (record.symbol.clone(), record.days);
+        if !klines_cache.contains_key(&key) {
+            match fetch_checked_klines(
+                binance,
+                &record.symbol,
+                "15m",
+                end_time - record.days * DAY_MS,
+                end_time,
+                &DataQualityConfig::default(),
+            )
+            .await
+            {
+                Ok((klines, quality)) => {
+                    quality.warn_if_degraded();
+                    klines_cache.insert(key.clone(), klines);
+                }
+                Err(e) => {
+                    report.skipped.push(format!(
+                        "{}: no klines for {} ({})",
+                        record.strategy_name, record.symbol, e
+                    ));
+                    continue;
+                }
+            }
+        }
+        let klines = &klines_cache[&key];
+        let sizing_mode = parse_sizing_mode(&record.sizing_mode);
+        let model = ProbabilityModelConfig::from_name(record.probability_model.as_deref());
+        let scenarios = fee_scenarios(
+            &strategy,
+            klines,
+            &record.symbol,
+            sizing_mode,
+            &presets,
+            &model,
+        );
+        report
+            .strategies
+            .push(sensitivity(record, scenarios, &presets));
+    }
+    report.vanished = report.strategies.iter().filter(|s| s.edge_vanishes).count();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_fee_scenarios_order_by_fee_level() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 3_000,
+            volatility: 0.01,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 65.0,
+            oversold: 35.0,
+        };
+        let double = FeePreset::Custom {
+            taker_rate: dec!(0.5),
+            maker_rate: Decimal::ZERO,
+            maker_share: Decimal::ZERO,
+        };
+        let presets = with_baselines(&[double.clone(), FeePreset::Polymarket]);
+        assert_eq!(
+            presets,
+            vec![FeePreset::ZeroFee, FeePreset::Polymarket, double]
+        );
+
+        let scenarios = fee_scenarios(
+            &strategy,
+            &klines,
+            "SYNTH",
+            SizingMode::Fixed,
+            &presets,
+            &ProbabilityModelConfig::Linear,
+        );
+        let [zero, polymarket, doubled] = &scenarios[..] else {
+            panic!("one scenario per preset");
+        };
+        assert!(zero.total_trades > 0);
+        assert_eq!(zero.total_fees, 0.0);
+        assert!(polymarket.total_fees > 0.0);
+        assert!(doubled.total_fees > polymarket.total_fees);
+        assert!(zero.net_pnl > polymarket.net_pnl && polymarket.net_pnl > doubled.net_pnl);
+        assert_eq!(
+            scenarios[2].preset,
+            "custom(taker=0.5,maker=0,maker_share=0)"
+        );
+
+        // Gross edge smaller than the Polymarket fees
+        let vanishes = |zero_pnl: f64, poly_pnl: f64| {
+            let mut scenarios = scenarios.clone();
+            scenarios[0].net_pnl = zero_pnl;
+            scenarios[1].net_pnl = poly_pnl;
+            edge_vanishes(&scenarios, &presets)
+        };
+        assert!(vanishes(120.0, -30.0));
+        assert!(!vanishes(120.0, 15.0));
+        assert!(!vanishes(-5.0, -30.0));
+    }
+}
@@ -3,18 +3,28 @@
 //! Implements the exact fee formula from Polymarket's 15-min crypto markets.
 //! Formula: fee = C × feeRate × (p × (1 - p))^exponent
 //! Where C = shares, p = price, feeRate = 0.25, exponent = 2
+//!
+//! `FeePreset` names the fee models a run can be priced under: the current Polymarket
+//! schedule, no fees at all, or custom maker / taker rates.
 
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 
+use crate::probability::ProbabilityModelConfig;
+
 /// Polymarket taker fee parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PolymarketFeeConfig {
     /// Fee rate (default 0.25)
     pub fee_rate: Decimal,
     /// Exponent for the price factor (default 2)
     pub exponent: u32,
+    /// How backtests estimate the share price p of a trade (default linear)
+    #[serde(default)]
+    pub probability_model: ProbabilityModelConfig,
 }
 
 impl Default for PolymarketFeeConfig {
@@ -22,6 +32,120 @@ impl Default for PolymarketFeeConfig {
         Self {
             fee_rate: dec!(0.25),
             exponent: 2,
+            probability_model: ProbabilityModelConfig::Linear,
+        }
+    }
+}
+
+/// Named fee model, selectable per request
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "preset", rename_all = "snake_case")]
+pub enum FeePreset {
+    /// Current Polymarket 15-min crypto taker fee (rate 0.25, exponent 2)
+    #[default]
+    Polymarket,
+    /// No fees: the gross edge
+    ZeroFee,
+    /// `maker_share` of the fills (0-1) pay `maker_rate`, the others `taker_rate`
+    Custom {
+        taker_rate: Decimal,
+        maker_rate: Decimal,
+        #[serde(default)]
+        maker_share: Decimal,
+    },
+}
+
+impl FeePreset {
+    /// `polymarket`, `zero_fee`, `custom(taker=…,maker=…,maker_share=…)`
+    pub fn name(&self) -> String {
+        match self {
+            Self::Polymarket => "polymarket".to_string(),
+            Self::ZeroFee => "zero_fee".to_string(),
+            Self::Custom {
+                taker_rate,
+                maker_rate,
+                maker_share,
+            } => format!(
+                "custom(taker={},maker={},maker_share={})",
+                taker_rate, maker_rate, maker_share
+            ),
+        }
+    }
+
+    /// `polymarket`, `zero` / `zero_fee`, or `custom:taker=0.1,maker=0,maker_share=0.5`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim().to_lowercase();
+        let preset = match s.as_str() {
+            "polymarket" => Self::Polymarket,
+            "zero" | "zero_fee" | "zero-fee" => Self::ZeroFee,
+            _ => {
+                let params = s.strip_prefix("custom:").ok_or_else(|| {
+                    format!(
+                        "Unknown fee preset {:?}: use polymarket, zero or custom:taker=…,maker=…",
+                        s
+                    )
+                })?;
+                let mut rates = [None; 3];
+                for pair in params.split(',') {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    let slot = match key.trim() {
+                        "taker" => 0,
+                        "maker" => 1,
+                        "maker_share" => 2,
+                        other => return Err(format!("Unknown custom fee parameter {:?}", other)),
+                    };
+                    let value = value
+                        .trim()
+                        .parse::<Decimal>()
+                        .map_err(|_| format!("Invalid {} rate {:?}", key.trim(), value))?;
+                    rates[slot] = Some(value);
+                }
+                Self::Custom {
+                    taker_rate: rates[0].ok_or("custom fees need taker=<rate>")?,
+                    maker_rate: rates[1].unwrap_or(Decimal::ZERO),
+                    maker_share: rates[2].unwrap_or(Decimal::ZERO),
+                }
+            }
+        };
+        preset.validate()?;
+        Ok(preset)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Self::Custom {
+            taker_rate,
+            maker_rate,
+            maker_share,
+        } = self
+        {
+            if taker_rate.is_sign_negative() || maker_rate.is_sign_negative() {
+                return Err("fee rates cannot be negative".to_string());
+            }
+            if *maker_share < Decimal::ZERO || *maker_share > Decimal::ONE {
+                return Err("maker_share must be between 0 and 1".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Fee parameters of the preset. The formula is linear in the rate, so a maker /
+    /// taker mix is priced at the fill-weighted rate.
+    pub fn config(&self) -> PolymarketFeeConfig {
+        match self {
+            Self::Polymarket => PolymarketFeeConfig::default(),
+            Self::ZeroFee => PolymarketFeeConfig {
+                fee_rate: Decimal::ZERO,
+                ..PolymarketFeeConfig::default()
+            },
+            Self::Custom {
+                taker_rate,
+                maker_rate,
+                maker_share,
+            } => PolymarketFeeConfig {
+                fee_rate: taker_rate * (Decimal::ONE - maker_share) + maker_rate * maker_share,
+                ..PolymarketFeeConfig::default()
+            },
         }
     }
 }
@@ -62,11 +186,55 @@ pub fn calculate_taker_fee(
     }
 }
 
+/// f64 version of [`calculate_taker_fee`] for the backtest hot loop.
+///
+/// Same formula and 4-decimal floor; the tiny epsilon keeps exact multiples
+/// of 0.0001 (e.g. 1.5625) from flooring one tick down after f64 rounding.
+pub fn calculate_taker_fee_f64(shares: f64, price: f64, config: &PolymarketFeeConfig) -> f64 {
+    if shares <= 0.0 || price <= 0.0 || price >= 1.0 {
+        return 0.0;
+    }
+
+    let base = price * (1.0 - price);
+    let factor = base.powi(config.exponent as i32);
+    let raw_fee = shares * config.fee_rate.to_f64().unwrap_or(0.0) * factor;
+
+    let rounded = (raw_fee * 10_000.0 + 1e-6).floor() / 10_000.0;
+    if rounded < 0.0001 {
+        0.0
+    } else {
+        rounded
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rust_decimal_macros::dec;
 
+    #[test]
+    fn test_fee_presets() {
+        assert_eq!(FeePreset::parse("polymarket"), Ok(FeePreset::Polymarket));
+        assert_eq!(FeePreset::parse("Zero"), Ok(FeePreset::ZeroFee));
+        let custom = FeePreset::parse("custom:taker=0.2,maker=0,maker_share=0.5").unwrap();
+        assert_eq!(custom.name(), "custom(taker=0.2,maker=0,maker_share=0.5)");
+        assert_eq!(custom.config().fee_rate, dec!(0.1));
+        assert!(FeePreset::parse("custom:maker=0.1").is_err());
+        assert!(FeePreset::parse("custom:taker=0.2,maker_share=2").is_err());
+        assert!(FeePreset::parse("binance").is_err());
+
+        // Same 100 shares at 0.50: 1.5625 under Polymarket, half with a 50 % maker share
+        let fee = |preset: &FeePreset| calculate_taker_fee(dec!(100), dec!(0.50), &preset.config());
+        assert_eq!(fee(&FeePreset::Polymarket), dec!(1.5625));
+        assert_eq!(fee(&FeePreset::ZeroFee), Decimal::ZERO);
+        let half = FeePreset::parse("custom:taker=0.25,maker=0,maker_share=0.5").unwrap();
+        assert_eq!(fee(&half), dec!(0.7812));
+
+        let json = serde_json::to_value(&custom).unwrap();
+        assert_eq!(json["preset"], "custom");
+        assert_eq!(serde_json::from_value::<FeePreset>(json).unwrap(), custom);
+    }
+
     #[test]
     fn test_fee_at_50_50() {
         let config = PolymarketFeeConfig::default();
@@ -130,4 +298,28 @@ mod tests {
         let fee = calculate_taker_fee(Decimal::ONE, dec!(0.50), &config);
         assert_eq!(fee, dec!(0.0156));
     }
+
+    #[test]
+    fn test_fee_f64_matches_decimal() {
+        let config = PolymarketFeeConfig::default();
+        let prices = [dec!(0), dec!(0.05), dec!(0.3), dec!(0.5), dec!(1)];
+        for shares in [dec!(1), dec!(7.5), dec!(100), dec!(1234.5678)] {
+            for price in prices {
+                let exact = calculate_taker_fee(shares, price, &config);
+                let fast = calculate_taker_fee_f64(
+                    shares.to_f64().unwrap(),
+                    price.to_f64().unwrap(),
+                    &config,
+                );
+                assert!(
+                    (exact.to_f64().unwrap() - fast).abs() < 1e-9,
+                    "{} shares @ {}: {} vs {}",
+                    shares,
+                    price,
+                    exact,
+                    fast
+                );
+            }
+        }
+    }
 }
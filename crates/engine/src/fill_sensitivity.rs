@@ -0,0 +1,252 @@
+//! Fill model sensitivity of stored strategies
+//!
+//! Re-runs knowledge base strategies on the same klines under each `FillModel`, so the
+//! edges that only exist when signals fill at their own close stand out. Each scenario
+//! reports its net PnL and the difference with the close fills of the same strategy.
+
+use std::collections::HashMap;
+
+use persistence::repository::DiscoveryBacktestRecord;
+use serde::{Deserialize, Serialize};
+
+use crate::api::BinanceClient;
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::discovery::{
+    dec_to_f64, parse_sizing_mode, run_backtest_with_fees, BacktestLimits, DiscoveryStrategyType,
+    SizingMode,
+};
+use crate::fees::PolymarketFeeConfig;
+use crate::fills::FillModel;
+use crate::probability::ProbabilityModelConfig;
+use crate::types::Kline;
+use crate::validation::validation_strategy;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// One strategy backtested under one fill model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FillScenario {
+    pub fill_model: FillModel,
+    pub total_trades: u32,
+    pub win_rate: f64,
+    pub net_pnl: f64,
+    /// `net_pnl` minus the net PnL of close fills
+    pub pnl_delta: f64,
+    pub composite_score: f64,
+}
+
+/// A stored strategy under every fill model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FillSensitivity {
+    pub backtest_id: Option<i64>,
+    pub strategy_name: String,
+    pub symbol: String,
+    pub days: i64,
+    /// Same order as `FillSensitivityReport.fill_models`
+    pub scenarios: Vec<FillScenario>,
+    /// Profitable with close fills, not under every other model
+    pub edge_vanishes: bool,
+}
+
+/// Fill model sensitivity of a set of stored strategies
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FillSensitivityReport {
+    pub fill_models: Vec<FillModel>,
+    pub strategies: Vec<FillSensitivity>,
+    /// Mean `pnl_delta` of each model over the strategies (same order as `fill_models`)
+    pub mean_pnl_delta: Vec<f64>,
+    /// Strategies whose edge disappears under a realistic fill model
+    pub vanished: usize,
+    /// Records that could not be re-run, with the reason
+    pub skipped: Vec<String>,
+}
+
+/// `models` with the close fills in front (duplicates dropped); every model when empty
+pub fn with_close_baseline(models: &[FillModel]) -> Vec<FillModel> {
+    if models.is_empty() {
+        return FillModel::ALL.to_vec();
+    }
+    let mut all = vec![FillModel::Close];
+    for model in models {
+        if !all.contains(model) {
+            all.push(*model);
+        }
+    }
+    all
+}
+
+/// Backtest `strategy` on `klines` under each model of `models`, which starts with
+/// `FillModel::Close` (see `with_close_baseline`)
+pub fn fill_scenarios(
+    strategy: &DiscoveryStrategyType,
+    klines: &[Kline],
+    symbol: &str,
+    sizing_mode: SizingMode,
+    models: &[FillModel],
+    fee_config: &PolymarketFeeConfig,
+) -> Vec<FillScenario> {
+    let mut close_pnl = 0.0;
+    models
+        .iter()
+        .map(|&fill_model| {
+            let limits = BacktestLimits::default().with_fill_model(fill_model);
+            let result =
+                run_backtest_with_fees(strategy, klines, symbol, sizing_mode, &limits, fee_config);
+            let net_pnl = dec_to_f64(result.net_pnl);
+            if fill_model == FillModel::Close {
+                close_pnl = net_pnl;
+            }
+            FillScenario {
+                fill_model,
+                total_trades: result.total_trades,
+                win_rate: dec_to_f64(result.win_rate),
+                net_pnl,
+                pnl_delta: net_pnl - close_pnl,
+                composite_score: dec_to_f64(result.composite_score),
+            }
+        })
+        .collect()
+}
+
+fn sensitivity(record: &DiscoveryBacktestRecord, scenarios: Vec<FillScenario>) -> FillSensitivity {
+    let (close, others) = scenarios.split_first().expect("close baseline first");
+    FillSensitivity {
+        backtest_id: record.id,
+        strategy_name: record.strategy_name.clone(),
+        symbol: record.symbol.clone(),
+        days: record.days,
+        edge_vanishes: close.net_pnl > 0.0
+            && !others.is_empty()
+            && others.iter().any(|s| s.net_pnl <= 0.0),
+        scenarios,
+    }
+}
+
+/// Re-run `records` over their `days` window ending now under the close fills plus
+/// `models` (every model when empty). Klines are fetched once per symbol and window.
+pub async fn fill_sensitivity(
+    records: &[DiscoveryBacktestRecord],
+    binance: &BinanceClient,
+    models: &[FillModel],
+) -> FillSensitivityReport {
+    let models = with_close_baseline(models);
+    let mut report = FillSensitivityReport {
+        fill_models: models.clone(),
+        ..Default::default()
+    };
+    let mut klines_cache: HashMap<(String, i64), Vec<Kline>> = HashMap::new();
+    let end_time = chrono::Utc::now().timestamp_millis();
+
+    for record in records {
+        let strategy = match validation_strategy(record) {
+            Ok(strategy) => strategy,
+            Err(e) => {
+                report.skipped.push(e);
+                continue;
+            }
+        };
+        let key = (record.symbol.clone(), record.days);
+        if !klines_cache.contains_key(&key) {
+            match fetch_checked_klines(
+                binance,
+                &record.symbol,
+                "15m",
+                end_time - record.days * DAY_MS,
+                end_time,
+                &DataQualityConfig::default(),
+            )
+            .await
+            {
+                Ok((klines, quality)) => {
+                    quality.warn_if_degraded();
+                    klines_cache.insert(key.clone(), klines);
+                }
+                Err(e) => {
+                    report.skipped.push(format!(
+                        "{}: no klines for {} ({})",
+                        record.strategy_name, record.symbol, e
+                    ));
+                    continue;
+                }
+            }
+        }
+        let fee_config = PolymarketFeeConfig {
+            probability_model: ProbabilityModelConfig::from_name(
+                record.probability_model.as_deref(),
+            ),
+            ..Default::default()
+        };
+        let scenarios = fill_scenarios(
+            &strategy,
+            &klines_cache[&key],
+            &record.symbol,
+            parse_sizing_mode(&record.sizing_mode),
+            &models,
+            &fee_config,
+        );
+        report.strategies.push(sensitivity(record, scenarios));
+    }
+    report.mean_pnl_delta = (0..models.len())
+        .map(|i| {
+            let deltas: Vec<f64> = report
+                .strategies
+                .iter()
+                .map(|s| s.scenarios[i].pnl_delta)
+                .collect();
+            if deltas.is_empty() {
+                0.0
+            } else {
+                deltas.iter().sum::<f64>() / deltas.len() as f64
+            }
+        })
+        .collect();
+    report.vanished = report.strategies.iter().filter(|s| s.edge_vanishes).count();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_fill_scenarios_against_close_baseline() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 3_000,
+            volatility: 0.01,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 65.0,
+            oversold: 35.0,
+        };
+        let models = with_close_baseline(&[FillModel::WorstOfBar, FillModel::Close]);
+        assert_eq!(models, vec![FillModel::Close, FillModel::WorstOfBar]);
+        assert_eq!(with_close_baseline(&[]), FillModel::ALL.to_vec());
+
+        let scenarios = fill_scenarios(
+            &strategy,
+            &klines,
+            "SYNTH",
+            SizingMode::Fixed,
+            &FillModel::ALL,
+            &PolymarketFeeConfig::default(),
+        );
+        let [close, next_open, worst, path] = &scenarios[..] else {
+            panic!("one scenario per model");
+        };
+        assert!(close.total_trades > 0);
+        assert_eq!(close.pnl_delta, 0.0);
+        for scenario in [next_open, worst, path] {
+            assert_eq!(scenario.total_trades, close.total_trades);
+            assert!((scenario.pnl_delta - (scenario.net_pnl - close.net_pnl)).abs() < 1e-9);
+        }
+        // Buying every high and selling every low is the worst any fill can do
+        assert!(worst.net_pnl < next_open.net_pnl && worst.net_pnl < path.net_pnl);
+        assert!(worst.pnl_delta < 0.0);
+    }
+}
@@ -0,0 +1,246 @@
+//! Fill models of the generic backtest
+//!
+//! Signals are computed on the close of a bar. `FillModel::Close` fills them at that
+//! same close, the historical and optimistic assumption. The other models fill on the
+//! next bar, when an order sent after the close would actually execute:
+//! - `NextOpen`: at its open
+//! - `WorstOfBar`: at its high when buying, at its low when selling
+//! - `OhlcPath`: at the mean price of its path, O→L→H→C for a rising bar and O→H→L→C
+//!   for a falling one, walked at constant speed
+//!
+//! Every fill of a backtest goes through the model: entries, signal exits, and the
+//! stops and take-profits of the strategies that signal them. The last bar has no next
+//! bar, so its signals fill at its close under every model.
+//!
+//! A `ParticipationLimit` also caps how much of an entry fills: at most `max_pct` of the
+//! volume of the bar the order executes on. The rest is carried to the following bars
+//! (until filled or the position closes) or cancelled. Exits always fill in full, so a
+//! position is never left half-closed. `UnfilledStats` counts what the cap cut.
+
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Kline, TradeSide};
+
+/// Price a signal computed on a bar's close is filled at
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FillModel {
+    #[default]
+    Close,
+    NextOpen,
+    WorstOfBar,
+    OhlcPath,
+}
+
+impl FillModel {
+    pub const ALL: [FillModel; 4] = [
+        FillModel::Close,
+        FillModel::NextOpen,
+        FillModel::WorstOfBar,
+        FillModel::OhlcPath,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Close => "close",
+            Self::NextOpen => "next_open",
+            Self::WorstOfBar => "worst_of_bar",
+            Self::OhlcPath => "ohlc_path",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let name = s.trim().to_lowercase().replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|model| model.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown fill model {:?}: use close, next_open, worst_of_bar or ohlc_path",
+                    s
+                )
+            })
+    }
+
+    /// Bar an order signalled on the close of `klines[idx]` executes on
+    pub fn fill_bar<'a>(&self, klines: &'a [Kline], idx: usize) -> &'a Kline {
+        match (self, klines.get(idx + 1)) {
+            (Self::Close, _) | (_, None) => &klines[idx],
+            (_, Some(next)) => next,
+        }
+    }
+
+    /// Fill price of a `side` order signalled on the close of `klines[idx]`
+    pub fn fill_price(&self, klines: &[Kline], idx: usize, side: TradeSide) -> f64 {
+        let price = |value: rust_decimal::Decimal| value.to_f64().unwrap_or(0.0);
+        let next = match (self, klines.get(idx + 1)) {
+            (Self::Close, _) | (_, None) => return price(klines[idx].close),
+            (_, Some(next)) => next,
+        };
+        match (self, side) {
+            (Self::NextOpen, _) => price(next.open),
+            (Self::WorstOfBar, TradeSide::Buy) => price(next.high),
+            (Self::WorstOfBar, TradeSide::Sell) => price(next.low),
+            _ => ohlc_path_mean(
+                price(next.open),
+                price(next.high),
+                price(next.low),
+                price(next.close),
+            ),
+        }
+    }
+}
+
+/// What becomes of the part of an entry the bar's volume could not fill
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum UnfilledRemainder {
+    /// Keeps filling on the next bars, at their fill price
+    #[default]
+    Carry,
+    /// Dropped: the position keeps the size filled on the first bar
+    Cancel,
+}
+
+/// Volume cap of the entries: an order takes at most `max_pct` of a bar's volume
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ParticipationLimit {
+    /// Largest share of a bar's volume an order fills, in %
+    pub max_pct: f64,
+    #[serde(default)]
+    pub remainder: UnfilledRemainder,
+}
+
+impl ParticipationLimit {
+    /// Quantity an order can fill on a bar that traded `volume`
+    pub fn capacity(&self, volume: f64) -> f64 {
+        (volume * self.max_pct / 100.0).max(0.0)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.max_pct.is_finite() && self.max_pct > 0.0 && self.max_pct <= 100.0) {
+            return Err(format!(
+                "participation max_pct must be between 0 (excluded) and 100, got {}",
+                self.max_pct
+            ));
+        }
+        Ok(())
+    }
+
+    /// Short label, e.g. `5% of volume, carried`
+    pub fn label(&self) -> String {
+        let remainder = match self.remainder {
+            UnfilledRemainder::Carry => "carried",
+            UnfilledRemainder::Cancel => "cancelled",
+        };
+        format!("{}% of volume, {}", self.max_pct, remainder)
+    }
+}
+
+/// Entries a `ParticipationLimit` cut short in a backtest
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UnfilledStats {
+    /// Entries placed
+    pub entries: u32,
+    /// Entries the volume of their bar did not fill entirely
+    pub partial_entries: u32,
+    /// Share of the quantity ordered that never filled, in %
+    pub unfilled_pct: f64,
+}
+
+/// Mean price along a bar's path: O→L→H→C when it closes at or above its open,
+/// O→H→L→C otherwise, each segment weighted by its length (constant speed)
+pub fn ohlc_path_mean(open: f64, high: f64, low: f64, close: f64) -> f64 {
+    let path = if close >= open {
+        [open, low, high, close]
+    } else {
+        [open, high, low, close]
+    };
+    let (mut length, mut weighted) = (0.0, 0.0);
+    for segment in path.windows(2) {
+        let d = (segment[1] - segment[0]).abs();
+        length += d;
+        weighted += d * (segment[0] + segment[1]) / 2.0;
+    }
+    if length > 0.0 {
+        weighted / length
+    } else {
+        open
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn bar(open: i64, high: i64, low: i64, close: i64) -> Kline {
+        Kline {
+            open_time: 0,
+            open: Decimal::from(open),
+            high: Decimal::from(high),
+            low: Decimal::from(low),
+            close: Decimal::from(close),
+            volume: Decimal::ONE,
+            close_time: 0,
+        }
+    }
+
+    #[test]
+    fn test_fill_prices_by_model() {
+        let klines = [bar(99, 101, 98, 100), bar(100, 110, 95, 106)];
+        let fill = |model: FillModel, side| model.fill_price(&klines, 0, side);
+
+        assert_eq!(fill(FillModel::Close, TradeSide::Buy), 100.0);
+        assert_eq!(fill(FillModel::NextOpen, TradeSide::Sell), 100.0);
+        assert_eq!(fill(FillModel::WorstOfBar, TradeSide::Buy), 110.0);
+        assert_eq!(fill(FillModel::WorstOfBar, TradeSide::Sell), 95.0);
+        // Rising bar 100→95→110→106: segments 5, 15, 4 with midpoints 97.5, 102.5, 108
+        // → (487.5 + 1537.5 + 432) / 24 = 102.375
+        assert!((fill(FillModel::OhlcPath, TradeSide::Buy) - 102.375).abs() < 1e-12);
+        // Falling bar 105→110→95→100: segments 5, 15, 5 → 102.5
+        assert!((ohlc_path_mean(105.0, 110.0, 95.0, 100.0) - 102.5).abs() < 1e-12);
+        assert_eq!(ohlc_path_mean(100.0, 100.0, 100.0, 100.0), 100.0);
+
+        // No next bar: the signal bar's close under every model
+        for model in FillModel::ALL {
+            assert_eq!(model.fill_price(&klines, 1, TradeSide::Buy), 106.0);
+        }
+
+        assert_eq!(FillModel::parse(" Next-Open "), Ok(FillModel::NextOpen));
+        assert_eq!(FillModel::parse("ohlc_path"), Ok(FillModel::OhlcPath));
+        assert!(FillModel::parse("vwap").is_err());
+    }
+
+    #[test]
+    fn test_participation_capacity() {
+        let limit = ParticipationLimit {
+            max_pct: 5.0,
+            remainder: UnfilledRemainder::Carry,
+        };
+        assert_eq!(limit.capacity(200.0), 10.0);
+        assert_eq!(limit.capacity(-1.0), 0.0);
+        assert_eq!(limit.label(), "5% of volume, carried");
+        assert!(limit.validate().is_ok());
+        let empty = ParticipationLimit {
+            max_pct: 0.0,
+            ..limit
+        };
+        assert!(empty.validate().is_err());
+
+        // The volume of the bar the order executes on
+        let klines = [bar(99, 101, 98, 100), bar(100, 110, 95, 106)];
+        let bar_of = |model: FillModel, idx| model.fill_bar(&klines, idx).open;
+        assert_eq!(bar_of(FillModel::Close, 0), Decimal::from(99));
+        assert_eq!(bar_of(FillModel::NextOpen, 0), Decimal::from(100));
+        assert_eq!(bar_of(FillModel::OhlcPath, 1), Decimal::from(100));
+
+        let parsed: ParticipationLimit = serde_json::from_str(r#"{"max_pct": 2}"#).unwrap();
+        assert_eq!(parsed.remainder, UnfilledRemainder::Carry);
+    }
+}
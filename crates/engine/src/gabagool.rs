@@ -1,17 +1,44 @@
 //! Gabagool binary arbitrage backtest engine
 //!
-//! Simulates 1 month of Gabagool strategy using Binance BTC 15m klines.
-//! Each 15m candle becomes a synthetic Polymarket binary market "BTC up or down?".
+//! Simulates the Gabagool strategy over the last `days` of Binance klines.
+//! Klines are grouped into market windows (15m / 1h / 4h); each window becomes a
+//! synthetic Polymarket binary market "BTC up or down?".
 //! We model YES/NO prices with realistic spreads, then simulate maker buys on both
-//! sides to calculate pair cost and locked profit.
+//! sides to calculate pair cost, locked profit and Polymarket fees per window.
 
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use tracing::info;
 
+use crate::fees::{calculate_taker_fee, PolymarketFeeConfig};
 use crate::types::{EquityPoint, Kline};
 use serde::{Deserialize, Serialize};
 
+const DAY_MS: i64 = 86_400_000;
+
+/// Duration of one synthetic binary market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum GabagoolWindow {
+    #[default]
+    #[serde(rename = "15m")]
+    M15,
+    #[serde(rename = "1h")]
+    H1,
+    #[serde(rename = "4h")]
+    H4,
+}
+
+impl GabagoolWindow {
+    pub fn duration_ms(self) -> i64 {
+        match self {
+            Self::M15 => 15 * 60_000,
+            Self::H1 => 60 * 60_000,
+            Self::H4 => 4 * 60 * 60_000,
+        }
+    }
+}
+
 /// Configuration for a Gabagool backtest run
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GabagoolBacktestConfig {
@@ -27,6 +54,12 @@ pub struct GabagoolBacktestConfig {
     pub bid_offset: Decimal,
     /// Spread multiplier: spread = volatility * multiplier
     pub spread_multiplier: Decimal,
+    /// Market window length; klines are aggregated up to it
+    #[serde(default)]
+    pub window: GabagoolWindow,
+    /// Taker fee parameters applied to both legs of each traded window
+    #[serde(default)]
+    pub fee_config: PolymarketFeeConfig,
 }
 
 impl Default for GabagoolBacktestConfig {
@@ -38,11 +71,13 @@ impl Default for GabagoolBacktestConfig {
             max_pair_cost: dec!(0.98),
             bid_offset: dec!(0.01),
             spread_multiplier: dec!(3),
+            window: GabagoolWindow::default(),
+            fee_config: PolymarketFeeConfig::default(),
         }
     }
 }
 
-/// Result for a single 15m window
+/// Result for a single market window
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GabagoolWindowResult {
     /// Timestamp of the window
@@ -55,6 +90,10 @@ pub struct GabagoolWindowResult {
     pub pair_cost: Decimal,
     /// Locked profit = size * (1.00 - pair_cost), or 0 if skipped
     pub locked_profit: Decimal,
+    /// Taker fees on both legs, or 0 if skipped
+    pub fees: Decimal,
+    /// locked_profit - fees
+    pub net_pnl: Decimal,
     /// Whether a trade was executed in this window
     pub traded: bool,
     /// BTC open price
@@ -74,7 +113,7 @@ pub struct GabagoolBacktestResult {
     pub start_time: i64,
     /// End timestamp
     pub end_time: i64,
-    /// Total number of 15m windows analyzed
+    /// Total number of windows analyzed
     pub total_windows: u32,
     /// Windows where a trade was executed
     pub traded_windows: u32,
@@ -84,8 +123,12 @@ pub struct GabagoolBacktestResult {
     pub hit_rate: Decimal,
     /// Total capital deployed (sum of size_per_side * 2 per trade)
     pub total_capital_used: Decimal,
-    /// Total locked profit across all trades
+    /// Total locked profit across all trades (before fees)
     pub total_locked_profit: Decimal,
+    /// Total taker fees across all trades
+    pub total_fees: Decimal,
+    /// total_locked_profit - total_fees
+    pub net_profit: Decimal,
     /// Average pair cost on executed trades
     pub avg_pair_cost: Decimal,
     /// Average locked profit per traded window
@@ -96,7 +139,13 @@ pub struct GabagoolBacktestResult {
     pub worst_pair_cost: Decimal,
     /// Average spread across all windows
     pub avg_spread: Decimal,
-    /// Cumulative profit curve
+    /// Net PnL of every window (0 for skipped ones), for drawdown/Sharpe computation
+    pub pnl_series: Vec<Decimal>,
+    /// Max drawdown of the net equity curve, % of peak equity (capital = one pair)
+    pub max_drawdown_pct: Decimal,
+    /// Sharpe ratio of per-trade net returns (net_pnl / capital per pair)
+    pub sharpe_ratio: Decimal,
+    /// Cumulative locked profit curve (before fees)
     pub profit_curve: Vec<EquityPoint>,
     /// Per-window detail
     pub windows: Vec<GabagoolWindowResult>,
@@ -108,10 +157,18 @@ pub struct GabagoolBacktestEngine;
 impl GabagoolBacktestEngine {
     /// Run the Gabagool backtest on the provided klines
     pub fn run(config: &GabagoolBacktestConfig, klines: &[Kline]) -> GabagoolBacktestResult {
+        let klines = aggregate_windows(
+            trim_to_days(klines, config.days),
+            config.window.duration_ms(),
+        );
+        let klines = klines.as_slice();
+
         let mut windows = Vec::with_capacity(klines.len());
         let mut profit_curve = Vec::with_capacity(klines.len());
         let mut cumulative_profit = Decimal::ZERO;
         let mut total_capital_used = Decimal::ZERO;
+        let mut total_fees = Decimal::ZERO;
+        let mut pnl_series = Vec::with_capacity(klines.len());
 
         let mut traded_count: u32 = 0;
         let mut pair_cost_sum = Decimal::ZERO;
@@ -135,7 +192,8 @@ impl GabagoolBacktestEngine {
 
         info!(
             symbol = %config.symbol,
-            bars = klines.len(),
+            windows = klines.len(),
+            window = ?config.window,
             max_pair_cost = %config.max_pair_cost,
             "Starting Gabagool backtest"
         );
@@ -201,12 +259,23 @@ impl GabagoolBacktestEngine {
                 Decimal::ZERO
             };
 
+            let fees = if traded {
+                calculate_taker_fee(config.size_per_side, yes_fill, &config.fee_config)
+                    + calculate_taker_fee(config.size_per_side, no_fill, &config.fee_config)
+            } else {
+                Decimal::ZERO
+            };
+            total_fees += fees;
+            pnl_series.push(locked_profit - fees);
+
             windows.push(GabagoolWindowResult {
                 time: kline.open_time,
                 yes_fill,
                 no_fill,
                 pair_cost,
                 locked_profit,
+                fees,
+                net_pnl: locked_profit - fees,
                 traded,
                 btc_open: kline.open,
                 btc_close: kline.close,
@@ -251,6 +320,10 @@ impl GabagoolBacktestEngine {
             best_pair_cost = Decimal::ZERO;
         }
 
+        let pair_capital = config.size_per_side * two;
+        let max_drawdown_pct = net_max_drawdown_pct(&pnl_series, pair_capital);
+        let sharpe_ratio = trade_sharpe(&windows, pair_capital);
+
         let start_time = klines.first().map(|k| k.open_time).unwrap_or(0);
         let end_time = klines.last().map(|k| k.close_time).unwrap_or(0);
 
@@ -259,6 +332,7 @@ impl GabagoolBacktestEngine {
             traded_count,
             hit_rate = %hit_rate,
             total_locked_profit = %cumulative_profit,
+            total_fees = %total_fees,
             avg_pair_cost = %avg_pair_cost,
             "Gabagool backtest complete"
         );
@@ -273,17 +347,123 @@ impl GabagoolBacktestEngine {
             hit_rate,
             total_capital_used,
             total_locked_profit: cumulative_profit,
+            total_fees,
+            net_profit: cumulative_profit - total_fees,
             avg_pair_cost,
             avg_locked_profit,
             best_pair_cost,
             worst_pair_cost: worst_traded_pair_cost,
             avg_spread,
+            pnl_series,
+            max_drawdown_pct,
+            sharpe_ratio,
             profit_curve,
             windows,
         }
     }
 }
 
+/// Keep only the klines of the last `days` days (0 = keep everything)
+fn trim_to_days(klines: &[Kline], days: u32) -> &[Kline] {
+    let Some(last) = klines.last() else {
+        return klines;
+    };
+    if days == 0 {
+        return klines;
+    }
+    let cutoff = last.close_time + 1 - i64::from(days) * DAY_MS;
+    let start = klines.partition_point(|k| k.open_time < cutoff);
+    &klines[start..]
+}
+
+/// Merge consecutive klines into one candle per `window_ms` bucket (aligned on epoch).
+///
+/// Buckets that don't cover the full window (gaps, partial first/last window) are
+/// dropped. Klines already as long as the window are kept as-is.
+fn aggregate_windows(klines: &[Kline], window_ms: i64) -> Vec<Kline> {
+    let mut out: Vec<Kline> = Vec::new();
+    let mut current: Option<Kline> = None;
+
+    let flush = |candle: Kline, out: &mut Vec<Kline>| {
+        if candle.close_time + 1 - candle.open_time >= window_ms {
+            out.push(candle);
+        }
+    };
+
+    for k in klines {
+        match current.as_mut() {
+            Some(c) if c.open_time.div_euclid(window_ms) == k.open_time.div_euclid(window_ms) => {
+                c.high = c.high.max(k.high);
+                c.low = c.low.min(k.low);
+                c.close = k.close;
+                c.volume += k.volume;
+                c.close_time = k.close_time;
+            }
+            _ => {
+                if let Some(done) = current.take() {
+                    flush(done, &mut out);
+                }
+                current = Some(k.clone());
+            }
+        }
+    }
+    if let Some(done) = current {
+        flush(done, &mut out);
+    }
+    out
+}
+
+/// Max drawdown (%) of `capital + cumulative net PnL`
+fn net_max_drawdown_pct(pnl_series: &[Decimal], capital: Decimal) -> Decimal {
+    let mut equity = capital;
+    let mut peak = capital;
+    let mut max_dd_pct = Decimal::ZERO;
+
+    for pnl in pnl_series {
+        equity += *pnl;
+        if equity > peak {
+            peak = equity;
+        }
+        if peak > Decimal::ZERO {
+            let dd_pct = (peak - equity) / peak * dec!(100);
+            if dd_pct > max_dd_pct {
+                max_dd_pct = dd_pct;
+            }
+        }
+    }
+    max_dd_pct.round_dp(2)
+}
+
+/// Sharpe ratio (mean / sample std-dev) of net returns on traded windows
+fn trade_sharpe(windows: &[GabagoolWindowResult], capital: Decimal) -> Decimal {
+    if capital <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    let returns: Vec<f64> = windows
+        .iter()
+        .filter(|w| w.traded)
+        .map(|w| {
+            (w.net_pnl / capital)
+                .to_string()
+                .parse::<f64>()
+                .unwrap_or(0.0)
+        })
+        .collect();
+    if returns.len() < 2 {
+        return Decimal::ZERO;
+    }
+
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let std_dev = variance.sqrt();
+    if std_dev < 1e-10 {
+        return Decimal::ZERO;
+    }
+
+    Decimal::from_str_exact(&format!("{:.2}", mean / std_dev)).unwrap_or(Decimal::ZERO)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -424,4 +604,79 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_hourly_windows_aggregate_klines() {
+        // 9 x 15m klines = 2 full hours + 1 partial (dropped)
+        let klines: Vec<Kline> = (0..9)
+            .map(|i| {
+                make_kline(
+                    50000.0 + i as f64,
+                    50010.0 + i as f64,
+                    50100.0 + i as f64,
+                    49900.0,
+                    i,
+                )
+            })
+            .collect();
+        let config = GabagoolBacktestConfig {
+            window: GabagoolWindow::H1,
+            ..Default::default()
+        };
+        let result = GabagoolBacktestEngine::run(&config, &klines);
+
+        assert_eq!(result.total_windows, 2);
+        let first = &result.windows[0];
+        assert_eq!(first.time, 0);
+        assert_eq!(first.btc_open, dec!(50000));
+        assert_eq!(first.btc_close, dec!(50013));
+        assert_eq!(result.windows[1].time, 3_600_000);
+    }
+
+    #[test]
+    fn test_days_trims_to_requested_period() {
+        // 3 days of 15m klines, backtest only the last day
+        let klines: Vec<Kline> = (0..288)
+            .map(|i| make_kline(50000.0, 50010.0, 50100.0, 49900.0, i))
+            .collect();
+        let config = GabagoolBacktestConfig {
+            days: 1,
+            ..Default::default()
+        };
+        let result = GabagoolBacktestEngine::run(&config, &klines);
+
+        assert_eq!(result.total_windows, 96);
+        assert_eq!(result.start_time, 192 * 900_000);
+    }
+
+    #[test]
+    fn test_fees_and_pnl_series() {
+        let klines = vec![
+            make_kline(50000.0, 50000.0, 50010.0, 49990.0, 0),
+            make_kline(50000.0, 50100.0, 50200.0, 49900.0, 1),
+            make_kline(50000.0, 49900.0, 50200.0, 49800.0, 2),
+        ];
+        let config = GabagoolBacktestConfig::default();
+        let result = GabagoolBacktestEngine::run(&config, &klines);
+
+        assert!(result.total_fees > Decimal::ZERO);
+        assert_eq!(
+            result.net_profit,
+            result.total_locked_profit - result.total_fees
+        );
+        assert_eq!(result.pnl_series.len(), result.windows.len());
+        let series_sum: Decimal = result.pnl_series.iter().sum();
+        assert_eq!(series_sum, result.net_profit);
+        for w in &result.windows {
+            assert_eq!(w.net_pnl, w.locked_profit - w.fees);
+        }
+
+        // A losing stretch shows up as drawdown on the net equity curve
+        let dd = net_max_drawdown_pct(&[dec!(2), dec!(-4), dec!(1)], dec!(20));
+        assert_eq!(dd, dec!(18.18));
+        assert_eq!(
+            net_max_drawdown_pct(&[dec!(1), dec!(1)], dec!(20)),
+            Decimal::ZERO
+        );
+    }
 }
@@ -0,0 +1,476 @@
+//! Gabagool live opportunity scanner
+//!
+//! Polls the currently open Polymarket hourly crypto "Up or Down" markets and reads the
+//! CLOB orderbook of both outcome tokens. Whenever buying one share of each side at the
+//! best asks costs less than `max_pair_cost`, the pair is a Gabagool opportunity: the
+//! payout is locked at $1 per pair whatever the outcome, minus taker fees.
+
+use crate::api::polymarket::{GammaMarket, OrderbookSnapshot, PolymarketDataClient};
+use crate::fees::{calculate_taker_fee, PolymarketFeeConfig};
+use crate::orderbook_backtest::parse_clob_token_ids;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::RwLock;
+use tracing::{debug, info, warn};
+
+const MAX_EVENTS: usize = 100;
+const MARKET_PAGE_SIZE: u32 = 100;
+const MAX_MARKET_PAGES: u32 = 5;
+const RATE_LIMIT_MS: u64 = 100;
+const HOUR_SECS: i64 = 3600;
+
+const CRYPTO_KEYWORDS: [&str; 8] = [
+    "bitcoin", "btc", "ethereum", "eth", "solana", "sol", "xrp", "ripple",
+];
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct GabagoolScannerConfig {
+    /// Emit an opportunity when YES ask + NO ask is below this
+    pub max_pair_cost: Decimal,
+    /// Shares bought on each side (capped by the depth at the best asks)
+    pub size_per_side: Decimal,
+    /// Seconds between two scans
+    pub poll_interval_secs: u64,
+    pub fee_config: PolymarketFeeConfig,
+}
+
+impl Default for GabagoolScannerConfig {
+    fn default() -> Self {
+        Self {
+            max_pair_cost: dec!(0.98),
+            size_per_side: dec!(100),
+            poll_interval_secs: 10,
+            fee_config: PolymarketFeeConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ScannerStatus {
+    Idle,
+    Scanning,
+    Error,
+}
+
+/// A YES+NO pair that can currently be bought below `max_pair_cost`
+#[derive(Debug, Clone, Serialize)]
+pub struct GabagoolOpportunity {
+    pub condition_id: String,
+    pub question: String,
+    pub slug: Option<String>,
+    pub end_date: Option<String>,
+    pub yes_token_id: String,
+    pub no_token_id: String,
+    pub yes_ask: Decimal,
+    pub no_ask: Decimal,
+    pub pair_cost: Decimal,
+    /// Shares per side, limited by the size resting at both best asks
+    pub shares: Decimal,
+    /// shares × (1 − pair_cost)
+    pub gross_profit: Decimal,
+    /// Taker fees for buying both sides
+    pub fees: Decimal,
+    /// Locked profit after fees
+    pub expected_profit: Decimal,
+    pub detected_at: i64,
+}
+
+/// Shared progress/state for the scanner (same pattern as WatcherProgress)
+pub struct GabagoolScannerProgress {
+    pub status: RwLock<ScannerStatus>,
+    pub cancelled: AtomicBool,
+    /// Opportunities found by the latest scan
+    pub active: RwLock<Vec<GabagoolOpportunity>>,
+    /// Opportunity events, newest first: one per market each time it drops below the threshold
+    pub events: RwLock<Vec<GabagoolOpportunity>>,
+    pub scans: AtomicU32,
+    pub markets_scanned: AtomicU32,
+    pub last_scan_at: RwLock<Option<i64>>,
+    pub config: RwLock<GabagoolScannerConfig>,
+    pub error_message: RwLock<Option<String>>,
+}
+
+impl GabagoolScannerProgress {
+    pub fn new() -> Self {
+        Self {
+            status: RwLock::new(ScannerStatus::Idle),
+            cancelled: AtomicBool::new(false),
+            active: RwLock::new(Vec::new()),
+            events: RwLock::new(Vec::new()),
+            scans: AtomicU32::new(0),
+            markets_scanned: AtomicU32::new(0),
+            last_scan_at: RwLock::new(None),
+            config: RwLock::new(GabagoolScannerConfig::default()),
+            error_message: RwLock::new(None),
+        }
+    }
+
+    pub fn reset(&self, config: GabagoolScannerConfig) {
+        *self.status.write().unwrap() = ScannerStatus::Scanning;
+        self.cancelled.store(false, Ordering::Relaxed);
+        *self.active.write().unwrap() = Vec::new();
+        *self.events.write().unwrap() = Vec::new();
+        self.scans.store(0, Ordering::Relaxed);
+        self.markets_scanned.store(0, Ordering::Relaxed);
+        *self.last_scan_at.write().unwrap() = None;
+        *self.config.write().unwrap() = config;
+        *self.error_message.write().unwrap() = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(*self.status.read().unwrap(), ScannerStatus::Scanning)
+    }
+
+    /// Replace the active set and emit an event for every market that wasn't in it
+    fn publish(&self, found: Vec<GabagoolOpportunity>) {
+        let previous: HashSet<String> = self
+            .active
+            .read()
+            .unwrap()
+            .iter()
+            .map(|o| o.condition_id.clone())
+            .collect();
+
+        {
+            let mut events = self.events.write().unwrap();
+            for opp in found.iter().filter(|o| !previous.contains(&o.condition_id)) {
+                info!(
+                    question = %opp.question,
+                    pair_cost = %opp.pair_cost,
+                    expected_profit = %opp.expected_profit,
+                    "Gabagool opportunity"
+                );
+                events.insert(0, opp.clone());
+            }
+            events.truncate(MAX_EVENTS);
+        }
+
+        *self.active.write().unwrap() = found;
+    }
+}
+
+impl Default for GabagoolScannerProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Market selection & pricing
+// ---------------------------------------------------------------------------
+
+/// Hourly crypto markets are titled like "Bitcoin Up or Down - October 16, 3PM ET";
+/// the 15-minute ones carry a "3:00PM-3:15PM" range, hence the colon check.
+pub fn is_hourly_crypto_question(question: &str) -> bool {
+    let q = question.to_lowercase();
+    let is_crypto = q
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| CRYPTO_KEYWORDS.contains(&word));
+    is_crypto && q.contains("up or down") && !q.contains(':') && !q.contains("15 min")
+}
+
+/// Open hourly crypto market whose hour is in progress at `now`
+fn is_current_hourly_market(m: &GammaMarket, now: DateTime<Utc>) -> bool {
+    if m.closed == Some(true) || !m.question.as_deref().is_some_and(is_hourly_crypto_question) {
+        return false;
+    }
+    m.end_date
+        .as_deref()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|end| {
+            let remaining = end.timestamp() - now.timestamp();
+            remaining > 0 && remaining <= HOUR_SECS
+        })
+        .unwrap_or(false)
+}
+
+/// Lowest ask and the size resting at it
+fn best_ask(book: &OrderbookSnapshot) -> Option<(Decimal, Decimal)> {
+    book.asks
+        .as_ref()?
+        .iter()
+        .filter_map(|l| {
+            let price = Decimal::from_str(&l.price).ok()?;
+            let size = Decimal::from_str(&l.size).ok()?;
+            (price > Decimal::ZERO && size > Decimal::ZERO).then_some((price, size))
+        })
+        .min_by_key(|(price, _)| *price)
+}
+
+/// Price a YES+NO pair at the best asks; `None` unless it's below `max_pair_cost`
+pub fn evaluate_pair(
+    market: &GammaMarket,
+    yes_book: &OrderbookSnapshot,
+    no_book: &OrderbookSnapshot,
+    config: &GabagoolScannerConfig,
+    now: DateTime<Utc>,
+) -> Option<GabagoolOpportunity> {
+    let (yes_ask, yes_size) = best_ask(yes_book)?;
+    let (no_ask, no_size) = best_ask(no_book)?;
+
+    let pair_cost = yes_ask + no_ask;
+    if pair_cost >= config.max_pair_cost {
+        return None;
+    }
+
+    let shares = config.size_per_side.min(yes_size).min(no_size);
+    let gross_profit = shares * (Decimal::ONE - pair_cost);
+    let fees = calculate_taker_fee(shares, yes_ask, &config.fee_config)
+        + calculate_taker_fee(shares, no_ask, &config.fee_config);
+
+    let (yes_token_id, no_token_id) = parse_clob_token_ids(market.clob_token_ids.as_deref());
+
+    Some(GabagoolOpportunity {
+        condition_id: market.condition_id.clone()?,
+        question: market.question.clone().unwrap_or_default(),
+        slug: market.slug.clone(),
+        end_date: market.end_date.clone(),
+        yes_token_id: yes_token_id?,
+        no_token_id: no_token_id?,
+        yes_ask,
+        no_ask,
+        pair_cost,
+        shares,
+        gross_profit: gross_profit.round_dp(4),
+        fees,
+        expected_profit: (gross_profit - fees).round_dp(4),
+        detected_at: now.timestamp(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Scanner loop
+// ---------------------------------------------------------------------------
+
+/// Run the scanner until cancelled: one pass over the current hourly markets every
+/// `poll_interval_secs`.
+pub async fn run_gabagool_scanner(
+    client: &PolymarketDataClient,
+    progress: &GabagoolScannerProgress,
+) {
+    let config = progress.config.read().unwrap().clone();
+    info!(
+        max_pair_cost = %config.max_pair_cost,
+        interval = config.poll_interval_secs,
+        "Gabagool scanner starting"
+    );
+    *progress.status.write().unwrap() = ScannerStatus::Scanning;
+
+    loop {
+        if progress.cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+
+        match scan_once(client, progress, &config).await {
+            Ok(found) => {
+                progress.publish(found);
+                progress.scans.fetch_add(1, Ordering::Relaxed);
+                *progress.last_scan_at.write().unwrap() = Some(Utc::now().timestamp());
+                *progress.error_message.write().unwrap() = None;
+            }
+            Err(e) => {
+                warn!(error = %e, "Gabagool scan failed");
+                *progress.error_message.write().unwrap() = Some(e.to_string());
+            }
+        }
+
+        // Wait before next scan
+        for _ in 0..(config.poll_interval_secs.max(1) * 2) {
+            if progress.cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+    }
+
+    *progress.status.write().unwrap() = ScannerStatus::Idle;
+    info!("Gabagool scanner stopped");
+}
+
+async fn scan_once(
+    client: &PolymarketDataClient,
+    progress: &GabagoolScannerProgress,
+    config: &GabagoolScannerConfig,
+) -> anyhow::Result<Vec<GabagoolOpportunity>> {
+    let now = Utc::now();
+
+    let mut markets = Vec::new();
+    for page in 0..MAX_MARKET_PAGES {
+        let batch = client
+            .search_markets(page * MARKET_PAGE_SIZE, MARKET_PAGE_SIZE, Some(false), true)
+            .await?;
+        let exhausted = (batch.len() as u32) < MARKET_PAGE_SIZE;
+        markets.extend(
+            batch
+                .into_iter()
+                .filter(|m| is_current_hourly_market(m, now)),
+        );
+        if exhausted {
+            break;
+        }
+    }
+    progress
+        .markets_scanned
+        .store(markets.len() as u32, Ordering::Relaxed);
+    debug!(count = markets.len(), "Current hourly crypto markets");
+
+    let mut found = Vec::new();
+    for market in &markets {
+        if progress.cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        let (Some(yes_token), Some(no_token)) =
+            parse_clob_token_ids(market.clob_token_ids.as_deref())
+        else {
+            continue;
+        };
+
+        let books = async {
+            let yes = client.get_orderbook(&yes_token).await?;
+            tokio::time::sleep(std::time::Duration::from_millis(RATE_LIMIT_MS)).await;
+            let no = client.get_orderbook(&no_token).await?;
+            anyhow::Ok((yes, no))
+        }
+        .await;
+
+        match books {
+            Ok((yes_book, no_book)) => {
+                if let Some(opp) = evaluate_pair(market, &yes_book, &no_book, config, Utc::now()) {
+                    found.push(opp);
+                }
+            }
+            Err(e) => {
+                debug!(market = ?market.question, error = %e, "Orderbook fetch failed");
+            }
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(RATE_LIMIT_MS)).await;
+    }
+
+    found.sort_by_key(|o| std::cmp::Reverse(o.expected_profit));
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::polymarket::OrderbookLevel;
+
+    fn book(asks: &[(&str, &str)]) -> OrderbookSnapshot {
+        OrderbookSnapshot {
+            market: None,
+            asset_id: None,
+            bids: Some(Vec::new()),
+            asks: Some(
+                asks.iter()
+                    .map(|(price, size)| OrderbookLevel {
+                        price: price.to_string(),
+                        size: size.to_string(),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn market(question: &str, end_date: &str) -> GammaMarket {
+        GammaMarket {
+            id: None,
+            condition_id: Some("0xabc".into()),
+            question: Some(question.into()),
+            slug: None,
+            end_date: Some(end_date.into()),
+            category: None,
+            closed: Some(false),
+            active: Some(true),
+            liquidity: None,
+            volume: None,
+            outcomes: Some("[\"Up\",\"Down\"]".into()),
+            outcome_prices: None,
+            event_slug: None,
+            description: None,
+            clob_token_ids: Some("[\"yes-token\",\"no-token\"]".into()),
+        }
+    }
+
+    #[test]
+    fn test_hourly_question_filter() {
+        assert!(is_hourly_crypto_question(
+            "Bitcoin Up or Down - October 16, 3PM ET"
+        ));
+        assert!(is_hourly_crypto_question(
+            "XRP Up or Down - October 16, 10AM ET"
+        ));
+        // 15-minute market
+        assert!(!is_hourly_crypto_question(
+            "Bitcoin Up or Down - October 16, 3:00PM-3:15PM ET"
+        ));
+        assert!(!is_hourly_crypto_question(
+            "Will it rain in Paris? Up or down"
+        ));
+
+        let now = DateTime::parse_from_rfc3339("2026-10-16T19:20:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let q = "Ethereum Up or Down - October 16, 3PM ET";
+        assert!(is_current_hourly_market(
+            &market(q, "2026-10-16T20:00:00Z"),
+            now
+        ));
+        // Next hour hasn't started yet, previous one is over
+        assert!(!is_current_hourly_market(
+            &market(q, "2026-10-16T21:00:00Z"),
+            now
+        ));
+        assert!(!is_current_hourly_market(
+            &market(q, "2026-10-16T19:00:00Z"),
+            now
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_pair_below_threshold() {
+        let config = GabagoolScannerConfig::default();
+        let m = market(
+            "Bitcoin Up or Down - October 16, 3PM ET",
+            "2026-10-16T20:00:00Z",
+        );
+        // Best asks: YES 0.45 (40 shares), NO 0.50 (500 shares) → pair cost 0.95
+        let yes = book(&[("0.48", "1000"), ("0.45", "40")]);
+        let no = book(&[("0.50", "500"), ("0.55", "10")]);
+
+        let opp = evaluate_pair(&m, &yes, &no, &config, Utc::now()).unwrap();
+        assert_eq!(opp.pair_cost, dec!(0.95));
+        assert_eq!(opp.shares, dec!(40));
+        assert_eq!(opp.gross_profit, dec!(2.00));
+        // 40 × 0.25 × (0.45 × 0.55)² + 40 × 0.25 × (0.5 × 0.5)²
+        assert_eq!(opp.fees, dec!(0.6125) + dec!(0.625));
+        assert_eq!(opp.expected_profit, opp.gross_profit - opp.fees);
+        assert_eq!(opp.yes_token_id, "yes-token");
+        assert_eq!(opp.no_token_id, "no-token");
+    }
+
+    #[test]
+    fn test_evaluate_pair_at_or_above_threshold_is_ignored() {
+        let config = GabagoolScannerConfig::default();
+        let m = market(
+            "Bitcoin Up or Down - October 16, 3PM ET",
+            "2026-10-16T20:00:00Z",
+        );
+        let yes = book(&[("0.49", "100")]);
+        let no = book(&[("0.49", "100")]);
+        assert!(evaluate_pair(&m, &yes, &no, &config, Utc::now()).is_none());
+        // One-sided book
+        assert!(evaluate_pair(&m, &yes, &book(&[]), &config, Utc::now()).is_none());
+    }
+}
@@ -0,0 +1,141 @@
+//! Snapshots of the grids run by continuous discovery
+//!
+//! Exploratory and ML-guided grids are rebuilt every cycle, so the strategies a cycle
+//! attempted cannot be recovered from the code afterwards. Each cycle's `CycleGrid` is
+//! hashed (SHA-256 of its JSON) into `CycleSummaryRecord.grid_snapshot_id` and stored
+//! zstd-compressed in `grid_snapshots`, once per distinct grid: the deterministic cycles
+//! of successive runs share a snapshot. Refinement variants are not part of the grid.
+
+use std::collections::BTreeMap;
+
+use persistence::repository::GridSnapshotRecord;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::discovery::{decode_strategy_params, DiscoveryStrategyType, STRATEGY_PARAMS_VERSION};
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// Strategies scheduled by one cycle, before the per-symbol cache lookups
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CycleGrid {
+    /// Single-symbol grid, run on every symbol without an island grid
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Object>))]
+    pub shared: Vec<DiscoveryStrategyType>,
+    /// Grids bred per symbol by the ML-guided cycles
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub islands: BTreeMap<String, Vec<DiscoveryStrategyType>>,
+    /// Strategies run on the whole basket
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Object>))]
+    pub cross_sectional: Vec<DiscoveryStrategyType>,
+}
+
+impl CycleGrid {
+    /// Strategies in the grid (the cycle's `grid_size`)
+    pub fn len(&self) -> usize {
+        self.shared.len()
+            + self.islands.values().map(Vec::len).sum::<usize>()
+            + self.cross_sectional.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Record to store, keyed by the hash of the grid's JSON
+    pub fn to_record(&self) -> Result<GridSnapshotRecord, String> {
+        let json = serde_json::to_vec(self).map_err(|e| format!("Failed to encode grid: {}", e))?;
+        let grid = zstd::encode_all(json.as_slice(), ZSTD_LEVEL)
+            .map_err(|e| format!("Failed to compress grid: {}", e))?;
+        Ok(GridSnapshotRecord {
+            snapshot_id: format!("{:x}", Sha256::digest(&json)),
+            strategy_count: self.len() as i64,
+            schema_version: STRATEGY_PARAMS_VERSION,
+            grid,
+            created_at: None,
+        })
+    }
+
+    /// Grid of a stored snapshot, each strategy decoded for the snapshot's params version
+    pub fn from_record(record: &GridSnapshotRecord) -> Result<Self, String> {
+        let json = zstd::decode_all(record.grid.as_slice())
+            .map_err(|e| format!("Failed to decompress grid: {}", e))?;
+        let raw: CycleGridJson =
+            serde_json::from_slice(&json).map_err(|e| format!("Invalid grid JSON: {}", e))?;
+        let decode = |values: Vec<serde_json::Value>| {
+            values
+                .iter()
+                .map(|v| decode_strategy_params(&v.to_string(), record.schema_version))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            shared: decode(raw.shared)?,
+            islands: raw
+                .islands
+                .into_iter()
+                .map(|(symbol, grid)| Ok((symbol, decode(grid)?)))
+                .collect::<Result<_, String>>()?,
+            cross_sectional: decode(raw.cross_sectional)?,
+        })
+    }
+}
+
+/// `CycleGrid` with the strategies left undecoded
+#[derive(Deserialize)]
+struct CycleGridJson {
+    shared: Vec<serde_json::Value>,
+    islands: BTreeMap<String, Vec<serde_json::Value>>,
+    cross_sectional: Vec<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip_and_id() {
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let grid = CycleGrid {
+            shared: vec![
+                rsi.clone(),
+                DiscoveryStrategyType::Vwap {
+                    period: 20,
+                    anchor: crate::discovery::VwapAnchor::Rolling,
+                },
+            ],
+            islands: BTreeMap::from([("ETHUSDT".to_string(), vec![rsi.clone()])]),
+            cross_sectional: vec![DiscoveryStrategyType::RelativeStrength {
+                lookback: 96,
+                top_k: 1,
+            }],
+        };
+        let record = grid.to_record().unwrap();
+        assert_eq!(record.strategy_count, 4);
+        assert_eq!(record.snapshot_id.len(), 64);
+
+        let back = CycleGrid::from_record(&record).unwrap();
+        assert_eq!(back.len(), 4);
+        assert_eq!(back.shared[1].name(), "VWAP");
+        assert_eq!(back.islands["ETHUSDT"][0].name(), "RSI");
+        assert!(back.cross_sectional[0].is_cross_sectional());
+
+        // Same grid, same id; another grid, another id
+        assert_eq!(back.to_record().unwrap().snapshot_id, record.snapshot_id);
+        let other = CycleGrid {
+            shared: vec![rsi],
+            ..Default::default()
+        };
+        assert_ne!(other.to_record().unwrap().snapshot_id, record.snapshot_id);
+
+        let corrupt = GridSnapshotRecord {
+            grid: b"not zstd".to_vec(),
+            ..record
+        };
+        assert!(CycleGrid::from_record(&corrupt).is_err());
+    }
+}
@@ -0,0 +1,409 @@
+//! Higher-timeframe trend filters on a strategy's entries
+//!
+//! `DiscoveryStrategyType::HtfFiltered` wraps a bar-signal strategy with an `HtfFilter`:
+//! its buys are only kept while a 1h or 4h trend indicator (EMA slope, or ADX with +DI
+//! above -DI) says the market is trending up; exits always pass. The higher-timeframe
+//! bars are aggregated from the strategy's own 15m klines, aligned on the interval's
+//! boundaries like exchange bars, and only completed bars are read, so a 15m entry never
+//! sees the rest of its hour. Refinement re-runs each top result under a few filters.
+
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::DiscoveryStrategyType;
+use crate::indicators::{close_f64, SignalGenerator, SignalWithConfidence};
+use crate::strategy::Signal;
+use crate::types::Kline;
+
+/// Filters tried on each top result by refinement
+pub const REFINEMENT_HTF_FILTERS: usize = 4;
+
+/// Higher timeframe the trend is read on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum HtfInterval {
+    #[serde(rename = "1h")]
+    H1,
+    #[serde(rename = "4h")]
+    H4,
+}
+
+impl HtfInterval {
+    pub fn ms(&self) -> i64 {
+        match self {
+            Self::H1 => 3_600_000,
+            Self::H4 => 14_400_000,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::H1 => "1h",
+            Self::H4 => "4h",
+        }
+    }
+}
+
+/// Trend indicator of the higher-timeframe bars
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "indicator", rename_all = "snake_case")]
+pub enum HtfTrend {
+    /// EMA of the closes over `period` bars, rising since the previous bar
+    EmaSlope { period: usize },
+    /// ADX over `period` bars of at least `min_adx`, with +DI above -DI
+    Adx { period: usize, min_adx: f64 },
+}
+
+/// Entries allowed only while the higher timeframe trends up
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct HtfFilter {
+    pub interval: HtfInterval,
+    pub trend: HtfTrend,
+}
+
+impl HtfFilter {
+    /// Short suffix of the wrapped strategy's name, e.g. `4h EMA20 up` or `1h ADX14>25`
+    pub fn label(&self) -> String {
+        match self.trend {
+            HtfTrend::EmaSlope { period } => format!("{} EMA{} up", self.interval.label(), period),
+            HtfTrend::Adx { period, min_adx } => {
+                format!("{} ADX{}>{}", self.interval.label(), period, min_adx)
+            }
+        }
+    }
+}
+
+/// Higher-timeframe bar being aggregated
+#[derive(Debug, Clone, Copy)]
+struct PartialBar {
+    bucket: i64,
+    high: f64,
+    low: f64,
+    close: f64,
+    /// Already handed to the trend (its last 15m bar closed the interval)
+    completed: bool,
+}
+
+/// Streaming trend of the completed higher-timeframe bars
+#[derive(Debug, Clone, Default)]
+struct TrendState {
+    bars: usize,
+    ema: f64,
+    prev_ema: f64,
+    prev: Option<(f64, f64, f64)>,
+    tr_avg: f64,
+    plus_dm_avg: f64,
+    minus_dm_avg: f64,
+    adx: f64,
+    up: bool,
+}
+
+impl TrendState {
+    fn push(&mut self, trend: HtfTrend, high: f64, low: f64, close: f64) {
+        self.bars += 1;
+        match trend {
+            HtfTrend::EmaSlope { period } => {
+                let alpha = 2.0 / (period as f64 + 1.0);
+                self.prev_ema = self.ema;
+                self.ema = if self.bars == 1 {
+                    close
+                } else {
+                    self.ema + alpha * (close - self.ema)
+                };
+                self.up = self.bars > period && self.ema > self.prev_ema;
+            }
+            HtfTrend::Adx { period, min_adx } => {
+                let Some((prev_high, prev_low, prev_close)) = self.prev.replace((high, low, close))
+                else {
+                    return;
+                };
+                let tr = (high - low)
+                    .max((high - prev_close).abs())
+                    .max((low - prev_close).abs());
+                let up_move = high - prev_high;
+                let down_move = prev_low - low;
+                let plus_dm = if up_move > down_move {
+                    up_move.max(0.0)
+                } else {
+                    0.0
+                };
+                let minus_dm = if down_move > up_move {
+                    down_move.max(0.0)
+                } else {
+                    0.0
+                };
+
+                // Wilder smoothing, as `AdxSignalGenerator`
+                let alpha = 1.0 / period as f64;
+                let smooth = |avg: f64, x: f64| avg * (1.0 - alpha) + x * alpha;
+                if self.bars == 2 {
+                    (self.tr_avg, self.plus_dm_avg, self.minus_dm_avg) = (tr, plus_dm, minus_dm);
+                } else {
+                    self.tr_avg = smooth(self.tr_avg, tr);
+                    self.plus_dm_avg = smooth(self.plus_dm_avg, plus_dm);
+                    self.minus_dm_avg = smooth(self.minus_dm_avg, minus_dm);
+                }
+                if self.tr_avg <= 0.0 {
+                    self.up = false;
+                    return;
+                }
+                let plus_di = self.plus_dm_avg / self.tr_avg * 100.0;
+                let minus_di = self.minus_dm_avg / self.tr_avg * 100.0;
+                let di_sum = plus_di + minus_di;
+                let dx = if di_sum > 0.0 {
+                    (plus_di - minus_di).abs() / di_sum * 100.0
+                } else {
+                    0.0
+                };
+                self.adx = if self.bars == 2 {
+                    dx
+                } else {
+                    smooth(self.adx, dx)
+                };
+                self.up = self.bars >= period * 2 && self.adx >= min_adx && plus_di > minus_di;
+            }
+        }
+    }
+}
+
+/// Higher-timeframe bars aggregated from lower-timeframe klines, and their trend
+#[derive(Debug, Clone)]
+pub struct HtfTrendTracker {
+    filter: HtfFilter,
+    current: Option<PartialBar>,
+    state: TrendState,
+}
+
+impl HtfTrendTracker {
+    pub fn new(filter: HtfFilter) -> Self {
+        Self {
+            filter,
+            current: None,
+            state: TrendState::default(),
+        }
+    }
+
+    /// Add a kline; whether the last completed higher-timeframe bar trends up
+    pub fn push(&mut self, kline: &Kline) -> bool {
+        let ms = self.filter.interval.ms();
+        let bucket = kline.open_time.div_euclid(ms);
+        let high = kline.high.to_f64().unwrap_or(0.0);
+        let low = kline.low.to_f64().unwrap_or(0.0);
+        let close = close_f64(kline);
+
+        match &mut self.current {
+            Some(bar) if bar.bucket == bucket => {
+                bar.high = bar.high.max(high);
+                bar.low = bar.low.min(low);
+                bar.close = close;
+            }
+            current => {
+                // A gap in the klines completes the previous bar too
+                if let Some(bar) = (*current).filter(|b| !b.completed) {
+                    self.state
+                        .push(self.filter.trend, bar.high, bar.low, bar.close);
+                }
+                *current = Some(PartialBar {
+                    bucket,
+                    high,
+                    low,
+                    close,
+                    completed: false,
+                });
+            }
+        }
+
+        let bar = self.current.as_mut().unwrap();
+        if kline.close_time + 1 >= (bucket + 1) * ms && !bar.completed {
+            bar.completed = true;
+            self.state
+                .push(self.filter.trend, bar.high, bar.low, bar.close);
+        }
+        self.state.up
+    }
+}
+
+/// Holds the inner generator's buys unless the higher timeframe trends up (and until
+/// the trend's first bars are complete); sells and holds pass through
+pub struct HtfFilteredSignalGenerator {
+    inner: Box<dyn SignalGenerator>,
+    tracker: HtfTrendTracker,
+}
+
+impl HtfFilteredSignalGenerator {
+    pub fn new(inner: Box<dyn SignalGenerator>, filter: HtfFilter) -> Self {
+        Self {
+            inner,
+            tracker: HtfTrendTracker::new(filter),
+        }
+    }
+}
+
+impl SignalGenerator for HtfFilteredSignalGenerator {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn warmup_bars(&self) -> usize {
+        self.inner.warmup_bars()
+    }
+
+    fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
+        let signal = self.inner.on_bar(kline);
+        let trending_up = self.tracker.push(kline);
+        if signal.signal == Signal::Buy && !trending_up {
+            return SignalWithConfidence::hold();
+        }
+        signal
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.tracker = HtfTrendTracker::new(self.tracker.filter);
+    }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        self.inner.features()
+    }
+}
+
+/// `strategy` (unwrapped if already HTF-filtered) under `REFINEMENT_HTF_FILTERS` filters:
+/// a 20-bar EMA slope and a 14-bar ADX, on 1h and 4h. Empty for strategies without bar
+/// signals.
+pub fn htf_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoveryStrategyType> {
+    let (inner, current) = match strategy {
+        DiscoveryStrategyType::HtfFiltered { inner, htf_filter } => {
+            (inner.as_ref(), Some(*htf_filter))
+        }
+        other => (other, None),
+    };
+    if inner.is_gabagool() || inner.is_cross_sectional() {
+        return Vec::new();
+    }
+    let filters = [
+        HtfFilter {
+            interval: HtfInterval::H1,
+            trend: HtfTrend::EmaSlope { period: 20 },
+        },
+        HtfFilter {
+            interval: HtfInterval::H4,
+            trend: HtfTrend::EmaSlope { period: 20 },
+        },
+        HtfFilter {
+            interval: HtfInterval::H1,
+            trend: HtfTrend::Adx {
+                period: 14,
+                min_adx: 25.0,
+            },
+        },
+        HtfFilter {
+            interval: HtfInterval::H4,
+            trend: HtfTrend::Adx {
+                period: 14,
+                min_adx: 20.0,
+            },
+        },
+    ];
+    filters
+        .into_iter()
+        .filter(|f| Some(*f) != current)
+        .map(|htf_filter| DiscoveryStrategyType::HtfFiltered {
+            inner: Box::new(inner.clone()),
+            htf_filter,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{generate_refinement_grid, run_backtest, BacktestLimits, SizingMode};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_htf_filter_uses_completed_bars_and_refines() {
+        // 15m klines on a staircase: each hour 1 higher for 30 hours, then 3 lower
+        let kline = |i: i64| {
+            let hour = i / 4;
+            let level = if hour < 30 {
+                100 + hour
+            } else {
+                216 - 3 * hour
+            };
+            let price = Decimal::from(level);
+            Kline {
+                open_time: i * 900_000,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: Decimal::ONE,
+                close_time: (i + 1) * 900_000 - 1,
+            }
+        };
+        let ema = HtfFilter {
+            interval: HtfInterval::H1,
+            trend: HtfTrend::EmaSlope { period: 5 },
+        };
+        let mut tracker = HtfTrendTracker::new(ema);
+        let up: Vec<bool> = (0..240).map(|i| tracker.push(&kline(i))).collect();
+        // The 6th hourly bar completes on the 24th 15m bar, not before
+        assert!(!up[22]);
+        assert!(up[23]);
+        assert!(up[100]);
+        // The first falling hour only counts once it has closed
+        assert!(up[122]);
+        assert!(!up[123]);
+        assert!(!up[200]);
+
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 65.0,
+            oversold: 35.0,
+        };
+        let grid = htf_refinement_grid(&rsi);
+        assert_eq!(grid.len(), REFINEMENT_HTF_FILTERS);
+        assert_eq!(grid[1].name(), "RSI|4h EMA20 up");
+        assert_eq!(grid[1].type_tag(), "htf_filtered");
+        let DiscoveryStrategyType::HtfFiltered { htf_filter, .. } = &grid[1] else {
+            panic!("not HTF-filtered");
+        };
+
+        // Refined as the inner strategy under the same filter, re-tried under the others
+        let refined = generate_refinement_grid(&grid[1]);
+        assert_eq!(refined.len(), generate_refinement_grid(&rsi).len());
+        assert!(refined.iter().all(|s| matches!(
+            s,
+            DiscoveryStrategyType::HtfFiltered { htf_filter: f, .. } if f == htf_filter
+        )));
+        assert_eq!(
+            htf_refinement_grid(&grid[1]).len(),
+            REFINEMENT_HTF_FILTERS - 1
+        );
+
+        // Round trip through the stored params
+        let json = serde_json::to_string(&grid[3]).unwrap();
+        assert!(json.contains("\"interval\":\"4h\""));
+        let back: DiscoveryStrategyType = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.name(), grid[3].name());
+
+        // Filters only remove entries
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 3_000,
+            ..SyntheticConfig::for_model(SyntheticModel::RegimeSwitching)
+        });
+        let limits = BacktestLimits::unlimited();
+        let run = |s: &DiscoveryStrategyType| {
+            run_backtest(s, &klines, "SYNTH", SizingMode::Fixed, &limits).total_trades
+        };
+        let unfiltered = run(&rsi);
+        assert!(unfiltered > 0);
+        assert!(grid.iter().all(|s| run(s) <= unfiltered));
+    }
+}
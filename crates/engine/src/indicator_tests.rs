@@ -0,0 +1,684 @@
+//! Reference-value and reset tests for the signal generators
+//!
+//! VWAP, OBV, Williams %R and ADX are hand-rolled rather than taken from `ta`: their
+//! features and signals are checked bar by bar against naive reference implementations
+//! over a fixed fixture, and a few values are pinned from an independent computation of
+//! the same series. The `ta`-backed generators are checked against the plain SMA / EMA
+//! they are built on. Every generator must replay identically after `reset()`.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::discovery::{
+    generate_legacy_phase1_grid, DiscoveryStrategyType, DynCombineMode, SingleIndicatorType,
+    VwapAnchor,
+};
+use crate::indicators::*;
+use crate::strategy::Signal;
+use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+use crate::types::Kline;
+
+/// (open, high, low, close, volume) of 30 consecutive 15m bars: a rally, a slide and a
+/// recovery, with one unchanged close (bar 16) for OBV
+const FIXTURE: [(f64, f64, f64, f64, f64); 30] = [
+    (100.00, 100.40, 99.70, 100.00, 120.0),
+    (100.00, 102.00, 99.50, 101.20, 95.0),
+    (101.20, 102.80, 100.60, 102.50, 140.0),
+    (102.50, 103.40, 101.40, 101.80, 80.0),
+    (101.80, 103.90, 101.10, 103.40, 160.0),
+    (103.40, 105.60, 103.20, 104.90, 210.0),
+    (104.90, 105.10, 103.20, 104.10, 75.0),
+    (104.10, 106.60, 103.80, 106.00, 130.0),
+    (106.00, 108.40, 105.50, 107.30, 190.0),
+    (107.30, 107.60, 105.70, 106.50, 100.0),
+    (106.50, 107.00, 104.80, 105.20, 85.0),
+    (105.20, 105.60, 103.20, 103.80, 150.0),
+    (103.80, 105.30, 103.50, 104.40, 90.0),
+    (104.40, 104.60, 102.20, 102.90, 170.0),
+    (102.90, 103.50, 101.00, 101.50, 220.0),
+    (101.50, 102.30, 100.00, 100.20, 110.0),
+    (100.20, 100.50, 99.60, 100.20, 60.0),
+    (100.20, 100.70, 99.00, 99.40, 140.0),
+    (99.40, 99.80, 97.90, 98.70, 180.0),
+    (98.70, 99.40, 97.60, 97.90, 200.0),
+    (97.90, 99.80, 97.40, 99.20, 95.0),
+    (99.20, 101.50, 98.80, 100.60, 160.0),
+    (100.60, 100.90, 99.40, 100.10, 70.0),
+    (100.10, 102.40, 99.80, 101.90, 125.0),
+    (101.90, 104.00, 101.30, 103.20, 175.0),
+    (103.20, 103.60, 101.90, 102.40, 90.0),
+    (102.40, 104.60, 102.20, 104.00, 150.0),
+    (104.00, 106.30, 103.60, 105.60, 200.0),
+    (105.60, 105.90, 103.90, 104.80, 80.0),
+    (104.80, 106.80, 104.50, 106.30, 165.0),
+];
+
+const TOLERANCE: f64 = 1e-9;
+
+fn fixture_klines() -> Vec<Kline> {
+    let dec = |x: f64| Decimal::from_str_exact(&format!("{:.2}", x)).unwrap();
+    FIXTURE
+        .iter()
+        .enumerate()
+        .map(|(i, &(open, high, low, close, volume))| Kline {
+            open_time: i as i64 * 900_000,
+            open: dec(open),
+            high: dec(high),
+            low: dec(low),
+            close: dec(close),
+            volume: dec(volume),
+            close_time: (i as i64 + 1) * 900_000 - 1,
+        })
+        .collect()
+}
+
+/// (close, high, low, volume) of each bar
+fn columns(klines: &[Kline]) -> Vec<(f64, f64, f64, f64)> {
+    klines
+        .iter()
+        .map(|k| {
+            (
+                k.close.to_f64().unwrap(),
+                k.high.to_f64().unwrap(),
+                k.low.to_f64().unwrap(),
+                k.volume.to_f64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+/// Output of a generator at one bar
+#[derive(Debug, Clone, PartialEq)]
+struct BarOutput {
+    signal: Signal,
+    confidence: f64,
+    features: Vec<(&'static str, f64)>,
+}
+
+fn run(gen: &mut dyn SignalGenerator, klines: &[Kline]) -> Vec<BarOutput> {
+    klines
+        .iter()
+        .map(|k| {
+            let s = gen.on_bar(k);
+            BarOutput {
+                signal: s.signal,
+                confidence: s.confidence,
+                features: gen.features(),
+            }
+        })
+        .collect()
+}
+
+/// One letter per bar: `B`uy, `S`ell or `H`old
+fn signal_string(outputs: &[BarOutput]) -> String {
+    outputs
+        .iter()
+        .map(|o| match o.signal {
+            Signal::Buy => 'B',
+            Signal::Sell => 'S',
+            Signal::Hold => 'H',
+        })
+        .collect()
+}
+
+fn feature(output: &BarOutput, name: &str) -> f64 {
+    output
+        .features
+        .iter()
+        .find(|(n, _)| *n == name)
+        .unwrap_or_else(|| panic!("no feature {}", name))
+        .1
+}
+
+fn assert_close(actual: f64, expected: f64, what: &str) {
+    assert!(
+        (actual - expected).abs() < TOLERANCE * expected.abs().max(1.0),
+        "{}: {} != {}",
+        what,
+        actual,
+        expected
+    );
+}
+
+fn sma(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// EMA seeded with the first value
+fn ema(values: &[f64], period: usize) -> Vec<f64> {
+    let k = 2.0 / (period as f64 + 1.0);
+    let mut out = Vec::with_capacity(values.len());
+    for &v in values {
+        let next = match out.last() {
+            Some(prev) => k * v + (1.0 - k) * prev,
+            None => v,
+        };
+        out.push(next);
+    }
+    out
+}
+
+// ============================================================================
+// Reference implementations of the hand-rolled generators
+// ============================================================================
+
+/// Volume-weighted typical price of the last `period` bars, once there are that many
+fn vwap_reference(bars: &[(f64, f64, f64, f64)], period: usize) -> Vec<Option<f64>> {
+    (0..bars.len())
+        .map(|i| {
+            let window = &bars[(i + 1).checked_sub(period)?..=i];
+            let pv: f64 = window
+                .iter()
+                .map(|(c, h, l, v)| (c + h + l) / 3.0 * v)
+                .sum();
+            let v: f64 = window.iter().map(|b| b.3).sum();
+            Some(pv / v)
+        })
+        .collect()
+}
+
+/// Volume-weighted typical price since the anchor of each bar, once `period` bars have
+/// accumulated
+fn anchored_vwap_reference(
+    bars: &[(f64, f64, f64, f64)],
+    period: usize,
+    anchor_of: impl Fn(usize) -> usize,
+) -> Vec<Option<f64>> {
+    (0..bars.len())
+        .map(|i| {
+            let window = &bars[anchor_of(i)..=i];
+            if window.len() < period {
+                return None;
+            }
+            let pv: f64 = window
+                .iter()
+                .map(|(c, h, l, v)| (c + h + l) / 3.0 * v)
+                .sum();
+            let v: f64 = window.iter().map(|b| b.3).sum();
+            Some(pv / v)
+        })
+        .collect()
+}
+
+/// OBV and its average: the generator averages the current OBV with the `sma_period`
+/// before it, or all of them while fewer are available
+fn obv_reference(bars: &[(f64, f64, f64, f64)], sma_period: usize) -> Vec<(f64, Option<f64>)> {
+    let mut obv = vec![0.0; bars.len()];
+    for i in 1..bars.len() {
+        let (close, prev) = (bars[i].0, bars[i - 1].0);
+        obv[i] = obv[i - 1]
+            + if close > prev {
+                bars[i].3
+            } else if close < prev {
+                -bars[i].3
+            } else {
+                0.0
+            };
+    }
+    (0..bars.len())
+        .map(|i| {
+            let average =
+                (i + 1 >= sma_period).then(|| sma(&obv[i.saturating_sub(sma_period)..=i]));
+            (obv[i], average)
+        })
+        .collect()
+}
+
+/// Williams %R over the highs and lows of the last `period` bars
+fn williams_r_reference(bars: &[(f64, f64, f64, f64)], period: usize) -> Vec<Option<f64>> {
+    (0..bars.len())
+        .map(|i| {
+            let window = &bars[(i + 1).checked_sub(period)?..=i];
+            let highest = window.iter().map(|b| b.1).fold(f64::MIN, f64::max);
+            let lowest = window.iter().map(|b| b.2).fold(f64::MAX, f64::min);
+            Some((highest - bars[i].0) / (highest - lowest) * -100.0)
+        })
+        .collect()
+}
+
+/// (ADX, +DI, -DI): true range and directional movement smoothed with `1 / period` from
+/// the second bar, and the ADX an average of DX (from 0) from bar `2 × period`
+fn adx_reference(bars: &[(f64, f64, f64, f64)], period: usize) -> Vec<Option<(f64, f64, f64)>> {
+    let alpha = 1.0 / period as f64;
+    let smooth = |prev: Option<f64>, x: f64| prev.map_or(x, |p| p * (1.0 - alpha) + x * alpha);
+    let (mut tr, mut plus, mut minus) = (None, None, None);
+    let mut adx = 0.0;
+    let mut out = vec![None];
+    for i in 1..bars.len() {
+        let ((_, high, low, _), (prev_close, prev_high, prev_low, _)) = (bars[i], bars[i - 1]);
+        let range = (high - low)
+            .max((high - prev_close).abs())
+            .max((low - prev_close).abs());
+        let (up, down) = (high - prev_high, prev_low - low);
+        tr = Some(smooth(tr, range));
+        plus = Some(smooth(plus, if up > down && up > 0.0 { up } else { 0.0 }));
+        minus = Some(smooth(
+            minus,
+            if down > up && down > 0.0 { down } else { 0.0 },
+        ));
+        if i + 1 < 2 * period {
+            out.push(None);
+            continue;
+        }
+        let plus_di = plus.unwrap() / tr.unwrap() * 100.0;
+        let minus_di = minus.unwrap() / tr.unwrap() * 100.0;
+        let dx = (plus_di - minus_di).abs() / (plus_di + minus_di) * 100.0;
+        adx = adx * (1.0 - alpha) + dx * alpha;
+        out.push(Some((adx, plus_di, minus_di)));
+    }
+    out
+}
+
+// ============================================================================
+// Hand-rolled generators against the references
+// ============================================================================
+
+#[test]
+fn test_vwap_matches_reference() {
+    let klines = fixture_klines();
+    let outputs = run(&mut VwapSignalGenerator::new(5), &klines);
+    let bars = columns(&klines);
+    for (i, (reference, out)) in vwap_reference(&bars, 5).iter().zip(&outputs).enumerate() {
+        if let Some(vwap) = *reference {
+            assert_close(feature(out, "vwap"), vwap, &format!("vwap at bar {}", i));
+        }
+    }
+    assert_eq!(signal_string(&outputs), "HHHHSSSSSSBBBBBBBBBBSSSSSSSSSS");
+    assert_close(feature(&outputs[9], "vwap"), 105.6486997636, "vwap[9]");
+    assert_close(feature(&outputs[19], "vwap"), 99.2748792271, "vwap[19]");
+    assert_close(feature(&outputs[29], "vwap"), 104.6243309002, "vwap[29]");
+}
+
+#[test]
+fn test_anchored_vwap_matches_reference() {
+    let check = |anchor: VwapAnchor, klines: &[Kline], anchor_of: &dyn Fn(usize) -> usize| {
+        let outputs = run(&mut VwapSignalGenerator::anchored(3, anchor), klines);
+        let reference = anchored_vwap_reference(&columns(klines), 3, anchor_of);
+        for (i, (reference, out)) in reference.iter().zip(&outputs).enumerate() {
+            match *reference {
+                Some(vwap) => assert_close(
+                    feature(out, "vwap"),
+                    vwap,
+                    &format!("{:?} at bar {}", anchor, i),
+                ),
+                None => assert_eq!(
+                    out.signal,
+                    Signal::Hold,
+                    "{:?} warm-up at bar {}",
+                    anchor,
+                    i
+                ),
+            }
+        }
+        outputs
+    };
+
+    // The UTC day turns at bar 10: a session VWAP starts over and warms up again, a
+    // 2-day block spans both days
+    let mut klines = fixture_klines();
+    let shift = 86_400_000 - 10 * 900_000;
+    for k in &mut klines {
+        k.open_time += shift;
+        k.close_time += shift;
+    }
+    let session = check(VwapAnchor::Session, &klines, &|i| {
+        if i < 10 {
+            0
+        } else {
+            10
+        }
+    });
+    assert_eq!(&signal_string(&session)[8..13], "SSHHB");
+    check(VwapAnchor::Days { days: 2 }, &klines, &|_| 0);
+
+    // Swing lows confirmed 2 bars later re-anchor the VWAP at the low itself
+    let klines = fixture_klines();
+    let lows: Vec<f64> = columns(&klines).iter().map(|b| b.2).collect();
+    let swing_anchor = |i: usize| {
+        (2..=i.saturating_sub(2))
+            .rev()
+            .find(|&j| (j - 2..=j + 2).all(|k| k == j || lows[k] > lows[j]))
+            .unwrap_or(0)
+    };
+    assert_eq!(swing_anchor(21), 0);
+    assert_eq!(swing_anchor(22), 20);
+    check(VwapAnchor::SwingLow { lookback: 2 }, &klines, &swing_anchor);
+
+    // Rolling stays the classic window
+    assert_eq!(
+        run(
+            &mut VwapSignalGenerator::anchored(5, VwapAnchor::Rolling),
+            &klines
+        ),
+        run(&mut VwapSignalGenerator::new(5), &klines)
+    );
+}
+
+#[test]
+fn test_obv_matches_reference() {
+    let klines = fixture_klines();
+    let outputs = run(&mut ObvSignalGenerator::new(5), &klines);
+    let bars = columns(&klines);
+    for (i, (&(obv, average), out)) in obv_reference(&bars, 5).iter().zip(&outputs).enumerate() {
+        assert_close(feature(out, "obv"), obv, &format!("obv at bar {}", i));
+        if let Some(average) = average {
+            assert_close(
+                feature(out, "obv_sma"),
+                average,
+                &format!("obv_sma at bar {}", i),
+            );
+        }
+    }
+    // The unchanged close of bar 16 leaves OBV as it was
+    assert_eq!(feature(&outputs[16], "obv"), feature(&outputs[15], "obv"));
+    assert_eq!(signal_string(&outputs), "HHHHBBBBBBSSSSSSSSSSSBSBBBBBBB");
+    assert_eq!(feature(&outputs[9], "obv"), 670.0);
+    assert_eq!(feature(&outputs[19], "obv"), -495.0);
+    assert_eq!(feature(&outputs[29], "obv"), 335.0);
+    assert_close(
+        feature(&outputs[9], "obv_sma"),
+        551.6666666667,
+        "obv_sma[9]",
+    );
+    assert_close(
+        feature(&outputs[29], "obv_sma"),
+        115.8333333333,
+        "obv_sma[29]",
+    );
+}
+
+#[test]
+fn test_williams_r_matches_reference() {
+    let klines = fixture_klines();
+    let outputs = run(&mut WilliamsRSignalGenerator::new(5, -20.0, -80.0), &klines);
+    let bars = columns(&klines);
+    for (i, (reference, out)) in williams_r_reference(&bars, 5)
+        .iter()
+        .zip(&outputs)
+        .enumerate()
+    {
+        let wr = feature(out, "williams_r");
+        match *reference {
+            Some(expected) => assert_close(wr, expected, &format!("williams_r at bar {}", i)),
+            None => assert_eq!(wr, -50.0),
+        }
+        assert!((-100.0..=0.0).contains(&wr));
+    }
+    assert_eq!(signal_string(&outputs), "HHHHSSHSSHHBHBBBBBBBHHHSSHSSHS");
+    assert_close(
+        feature(&outputs[9], "williams_r"),
+        -36.5384615385,
+        "williams_r[9]",
+    );
+    assert_close(
+        feature(&outputs[19], "williams_r"),
+        -93.6170212766,
+        "williams_r[19]",
+    );
+    assert_close(
+        feature(&outputs[29], "williams_r"),
+        -10.2040816327,
+        "williams_r[29]",
+    );
+}
+
+#[test]
+fn test_adx_matches_reference() {
+    let klines = fixture_klines();
+    let outputs = run(&mut AdxSignalGenerator::new(5, 25.0), &klines);
+    let bars = columns(&klines);
+    for (i, (reference, out)) in adx_reference(&bars, 5).iter().zip(&outputs).enumerate() {
+        if let Some((adx, plus_di, minus_di)) = *reference {
+            assert_close(feature(out, "adx"), adx, &format!("adx at bar {}", i));
+            assert_close(
+                feature(out, "plus_di"),
+                plus_di,
+                &format!("plus_di at bar {}", i),
+            );
+            assert_close(
+                feature(out, "minus_di"),
+                minus_di,
+                &format!("minus_di at bar {}", i),
+            );
+        } else {
+            assert_eq!(out.signal, Signal::Hold);
+        }
+    }
+    assert_eq!(signal_string(&outputs), "HHHHHHHHHHBBHHHSSSSSSBBBBBBBBB");
+    // First ADX value: a DX of 100 (no downward movement yet) weighted by 1/5
+    assert_close(feature(&outputs[9], "adx"), 20.0, "adx[9]");
+    assert_close(feature(&outputs[9], "plus_di"), 41.1708808537, "plus_di[9]");
+    assert_close(feature(&outputs[19], "adx"), 50.9952380772, "adx[19]");
+    assert_close(
+        feature(&outputs[19], "minus_di"),
+        34.3215383911,
+        "minus_di[19]",
+    );
+    assert_close(feature(&outputs[29], "adx"), 63.8250442006, "adx[29]");
+    assert_close(
+        feature(&outputs[29], "plus_di"),
+        33.9192854566,
+        "plus_di[29]",
+    );
+}
+
+// ============================================================================
+// `ta`-backed generators against the averages they are built on
+// ============================================================================
+
+#[test]
+fn test_ta_backed_generators_follow_their_averages() {
+    let klines = generate_klines(&SyntheticConfig {
+        bars: 400,
+        ..SyntheticConfig::for_model(SyntheticModel::RegimeSwitching)
+    });
+    let bars = columns(&klines);
+    let closes: Vec<f64> = bars.iter().map(|b| b.0).collect();
+
+    // Bollinger: the middle band is the SMA, the bands `multiplier` population deviations
+    // away from it
+    let bb = run(&mut BollingerSignalGenerator::new(20, 2.0), &klines);
+    for (i, out) in bb.iter().enumerate().skip(19) {
+        let window = &closes[i - 19..=i];
+        let mean = sma(window);
+        let sd = (window.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / 20.0).sqrt();
+        assert_close(feature(out, "bb_middle"), mean, "bb_middle");
+        assert_close(feature(out, "bb_upper"), mean + 2.0 * sd, "bb_upper");
+        assert_close(feature(out, "bb_lower"), mean - 2.0 * sd, "bb_lower");
+        let expected = if closes[i] < mean - 2.0 * sd {
+            Signal::Buy
+        } else if closes[i] > mean + 2.0 * sd {
+            Signal::Sell
+        } else {
+            Signal::Hold
+        };
+        assert_eq!(out.signal, expected, "bollinger signal at bar {}", i);
+    }
+
+    // EMA crossover and MACD: differences of EMAs seeded with the first close
+    let (fast, slow, signal) = (ema(&closes, 12), ema(&closes, 26), 9);
+    let macd_line: Vec<f64> = fast.iter().zip(&slow).map(|(f, s)| f - s).collect();
+    let signal_line = ema(&macd_line, signal);
+    let macd = run(&mut MacdSignalGenerator::new(12, 26, 9), &klines);
+    let cross = run(&mut EmaCrossoverSignalGenerator::new(12, 26), &klines);
+    for i in 0..klines.len() {
+        assert_close(feature(&macd[i], "macd"), macd_line[i], "macd");
+        assert_close(
+            feature(&macd[i], "macd_signal"),
+            signal_line[i],
+            "macd_signal",
+        );
+        assert_close(
+            feature(&macd[i], "macd_histogram"),
+            macd_line[i] - signal_line[i],
+            "macd_histogram",
+        );
+        assert_close(feature(&cross[i], "ema_fast"), fast[i], "ema_fast");
+        assert_close(feature(&cross[i], "ema_slow"), slow[i], "ema_slow");
+        let crossed_up = i >= 26 && fast[i - 1] <= slow[i - 1] && fast[i] > slow[i];
+        let crossed_down = i >= 26 && fast[i - 1] >= slow[i - 1] && fast[i] < slow[i];
+        assert_eq!(
+            cross[i].signal == Signal::Buy,
+            crossed_up,
+            "EMA buy at bar {}",
+            i
+        );
+        assert_eq!(
+            cross[i].signal == Signal::Sell,
+            crossed_down,
+            "EMA sell at bar {}",
+            i
+        );
+    }
+
+    // ATR mean reversion: the mean is the SMA of the closes
+    let atr = run(
+        &mut AtrMeanReversionSignalGenerator::new(14, 20, 2.0),
+        &klines,
+    );
+    for (i, out) in atr.iter().enumerate() {
+        assert_close(
+            feature(out, "atr_sma"),
+            sma(&closes[i.saturating_sub(19)..=i]),
+            "atr_sma",
+        );
+        assert!(feature(out, "atr") >= 0.0);
+    }
+
+    // Oscillators stay in range, and RSI signals on its thresholds
+    let rsi = run(&mut RsiSignalGenerator::new(14, 70.0, 30.0), &klines);
+    let stoch = run(&mut StochasticSignalGenerator::new(14, 80.0, 20.0), &klines);
+    for i in 0..klines.len() {
+        let value = feature(&rsi[i], "rsi");
+        assert!((0.0..=100.0).contains(&value));
+        assert_eq!(rsi[i].signal == Signal::Buy, value < 30.0);
+        assert_eq!(rsi[i].signal == Signal::Sell, value > 70.0);
+        let k: Vec<f64> = stoch[i.saturating_sub(2)..=i]
+            .iter()
+            .map(|o| feature(o, "stoch_k"))
+            .collect();
+        assert!(k.iter().all(|k| (0.0..=100.0).contains(k)));
+        assert_close(feature(&stoch[i], "stoch_d"), sma(&k), "stoch_d");
+    }
+}
+
+// ============================================================================
+// reset()
+// ============================================================================
+
+/// One generator of every kind discovery builds from bar signals: each single indicator
+/// under two parameter sets, the fixed and dynamic combos, web strategies, and the
+/// ensemble / filter wrappers
+fn every_generator() -> Vec<DiscoveryStrategyType> {
+    let mut strategies: Vec<DiscoveryStrategyType> = Vec::new();
+    let mut push = |s: DiscoveryStrategyType| {
+        if !strategies.iter().any(|t| t.name() == s.name()) {
+            strategies.push(s);
+        }
+    };
+    generate_legacy_phase1_grid()
+        .into_iter()
+        .filter(|s| !s.is_gabagool() && !s.is_cross_sectional())
+        .for_each(&mut push);
+    for &mode in DynCombineMode::all() {
+        let indicators = vec![SingleIndicatorType::Vwap, SingleIndicatorType::WilliamsR];
+        push(DiscoveryStrategyType::DynamicCombo {
+            params: indicators.iter().map(|i| i.aggressive_params()).collect(),
+            indicators,
+            combine_mode: mode,
+        });
+    }
+    for id in crate::web_strategies::WebStrategyId::all_backtestable() {
+        push(DiscoveryStrategyType::WebStrategy {
+            id: *id,
+            params: crate::web_strategies::WebStrategyParams::aggressive_for(id),
+        });
+    }
+    push(DiscoveryStrategyType::Vwap {
+        period: 5,
+        anchor: VwapAnchor::SwingLow { lookback: 4 },
+    });
+    let adx = DiscoveryStrategyType::Adx {
+        period: 7,
+        adx_threshold: 20.0,
+    };
+    push(DiscoveryStrategyType::Ensemble {
+        members: vec![
+            adx.clone(),
+            DiscoveryStrategyType::Vwap {
+                period: 10,
+                anchor: VwapAnchor::Rolling,
+            },
+            DiscoveryStrategyType::Obv { sma_period: 7 },
+        ],
+        weights: vec![1.0, 2.0, 1.0],
+        vote: crate::ensemble::EnsembleVote::ConfidenceWeighted,
+    });
+    push(DiscoveryStrategyType::Filtered {
+        inner: Box::new(adx.clone()),
+        filter: crate::entry_filter::EntryFilter {
+            min_volatility_pct: Some(0.5),
+            ..Default::default()
+        },
+    });
+    push(DiscoveryStrategyType::ZScoreFiltered {
+        inner: Box::new(adx.clone()),
+        zscore_filter: crate::zscore_filter::ZScoreFilter {
+            window: 30,
+            threshold: 1.0,
+        },
+    });
+    push(DiscoveryStrategyType::HtfFiltered {
+        inner: Box::new(adx),
+        htf_filter: crate::htf_filter::HtfFilter {
+            interval: crate::htf_filter::HtfInterval::H1,
+            trend: crate::htf_filter::HtfTrend::Adx {
+                period: 14,
+                min_adx: 20.0,
+            },
+        },
+    });
+    strategies
+}
+
+#[test]
+fn test_reset_restores_initial_behavior() {
+    let klines = generate_klines(&SyntheticConfig {
+        bars: 600,
+        ..SyntheticConfig::for_model(SyntheticModel::RegimeSwitching)
+    });
+    let strategies = every_generator();
+    assert!(strategies.len() >= 30);
+
+    let mut singles: Vec<Box<dyn SignalGenerator>> = Vec::new();
+    for ind in SingleIndicatorType::all() {
+        singles.push(build_single_generator(ind, &ind.default_params()));
+        singles.push(build_single_generator(ind, &ind.aggressive_params()));
+    }
+    let generators = singles
+        .into_iter()
+        .chain(strategies.iter().map(build_signal_generator));
+
+    let mut silent = Vec::new();
+    for mut gen in generators {
+        let name = gen.name().to_string();
+        let first = run(gen.as_mut(), &klines);
+        if first.iter().all(|o| o.signal == Signal::Hold) {
+            silent.push(name.clone());
+        }
+
+        // After a full run, and after a reset partway through the next one
+        gen.reset();
+        assert_eq!(run(gen.as_mut(), &klines), first, "{} after reset", name);
+        run(gen.as_mut(), &klines[..klines.len() / 3]);
+        gen.reset();
+        assert_eq!(
+            run(gen.as_mut(), &klines),
+            first,
+            "{} after a partial run",
+            name
+        );
+    }
+    // Every generator trades on these klines, so the replays compared actual signals
+    assert_eq!(silent, Vec::<String>::new());
+}
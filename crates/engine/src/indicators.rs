@@ -4,14 +4,21 @@
 //! technical indicators plus 7 combo strategies. Each generator processes
 //! klines bar-by-bar and emits Buy/Sell/Hold signals with a confidence score.
 
+use crate::candles::{CandleSignalGenerator, CandleTransform};
+use crate::discovery::VwapAnchor;
 use crate::strategy::Signal;
 use crate::types::Kline;
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use ta::indicators::{
     AverageTrueRange, BollingerBands, ExponentialMovingAverage, MovingAverageConvergenceDivergence,
     RelativeStrengthIndex, SimpleMovingAverage, SlowStochastic,
 };
 use ta::Next;
 
+pub mod stream;
+
 // ============================================================================
 // Core trait
 // ============================================================================
@@ -51,14 +58,27 @@ pub trait SignalGenerator: Send {
     fn name(&self) -> &str;
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence;
     fn reset(&mut self);
+
+    /// Indicator values computed on the last bar, as `(column, value)` — used by the
+    /// ML feature export so features match exactly what the signals were built from
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        Vec::new()
+    }
+
+    /// Leading bars whose signals are not trusted: the indicator is still converging.
+    /// The backtester holds through them and leaves them out of the test period
+    fn warmup_bars(&self) -> usize {
+        0
+    }
 }
 
 // ============================================================================
 // Helper: convert Decimal close price to f64
 // ============================================================================
 
+/// Direct mantissa/scale conversion — no String round-trip, this runs on every bar
 pub fn close_f64(kline: &Kline) -> f64 {
-    kline.close.to_string().parse::<f64>().unwrap_or(0.0)
+    kline.close.to_f64().unwrap_or(0.0)
 }
 
 // ============================================================================
@@ -70,6 +90,7 @@ pub struct RsiSignalGenerator {
     overbought: f64,
     oversold: f64,
     period: usize,
+    last_rsi: f64,
 }
 
 impl RsiSignalGenerator {
@@ -79,6 +100,7 @@ impl RsiSignalGenerator {
             overbought,
             oversold,
             period,
+            last_rsi: 50.0,
         }
     }
 }
@@ -88,8 +110,13 @@ impl SignalGenerator for RsiSignalGenerator {
         "RSI"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.period + 1
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let val = self.rsi.next(close_f64(kline));
+        self.last_rsi = val;
         if val < self.oversold {
             let distance = (self.oversold - val) / self.oversold;
             SignalWithConfidence::buy(distance)
@@ -103,6 +130,11 @@ impl SignalGenerator for RsiSignalGenerator {
 
     fn reset(&mut self) {
         self.rsi = RelativeStrengthIndex::new(self.period).expect("Invalid RSI period");
+        self.last_rsi = 50.0;
+    }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        vec![("rsi", self.last_rsi)]
     }
 }
 
@@ -137,6 +169,10 @@ impl SignalGenerator for BollingerSignalGenerator {
         "BollingerBands"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.period
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
         let bb_out = self.bb.next(close);
@@ -166,6 +202,14 @@ impl SignalGenerator for BollingerSignalGenerator {
         self.last_lower = 0.0;
         self.last_middle = 0.0;
     }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("bb_upper", self.last_upper),
+            ("bb_middle", self.last_middle),
+            ("bb_lower", self.last_lower),
+        ]
+    }
 }
 
 // ============================================================================
@@ -178,6 +222,8 @@ pub struct MacdSignalGenerator {
     slow: usize,
     signal_period: usize,
     prev_histogram: f64,
+    last_macd: f64,
+    last_signal: f64,
     bars_seen: usize,
 }
 
@@ -190,6 +236,8 @@ impl MacdSignalGenerator {
             slow,
             signal_period,
             prev_histogram: 0.0,
+            last_macd: 0.0,
+            last_signal: 0.0,
             bars_seen: 0,
         }
     }
@@ -200,10 +248,16 @@ impl SignalGenerator for MacdSignalGenerator {
         "MACD"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.slow + self.signal_period
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
         let out = self.macd.next(close);
         let histogram = out.histogram;
+        self.last_macd = out.macd;
+        self.last_signal = out.signal;
         self.bars_seen += 1;
 
         let result = if self.bars_seen > self.slow && self.prev_histogram <= 0.0 && histogram > 0.0
@@ -228,8 +282,18 @@ impl SignalGenerator for MacdSignalGenerator {
             MovingAverageConvergenceDivergence::new(self.fast, self.slow, self.signal_period)
                 .expect("Invalid MACD params");
         self.prev_histogram = 0.0;
+        self.last_macd = 0.0;
+        self.last_signal = 0.0;
         self.bars_seen = 0;
     }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("macd", self.last_macd),
+            ("macd_signal", self.last_signal),
+            ("macd_histogram", self.prev_histogram),
+        ]
+    }
 }
 
 // ============================================================================
@@ -265,6 +329,10 @@ impl SignalGenerator for EmaCrossoverSignalGenerator {
         "EMACrossover"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.slow_period
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
         let fast_val = self.ema_fast.next(close);
@@ -301,6 +369,11 @@ impl SignalGenerator for EmaCrossoverSignalGenerator {
         self.prev_slow = 0.0;
         self.bars_seen = 0;
     }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        // prev_* hold the values of the bar just processed
+        vec![("ema_fast", self.prev_fast), ("ema_slow", self.prev_slow)]
+    }
 }
 
 // ============================================================================
@@ -339,6 +412,10 @@ impl SignalGenerator for StochasticSignalGenerator {
         "Stochastic"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.period + 2
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
 
@@ -385,6 +462,10 @@ impl SignalGenerator for StochasticSignalGenerator {
         self.prev_d = 50.0;
         self.bars_seen = 0;
     }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        vec![("stoch_k", self.prev_k), ("stoch_d", self.prev_d)]
+    }
 }
 
 // ============================================================================
@@ -422,10 +503,14 @@ impl SignalGenerator for AtrMeanReversionSignalGenerator {
         "ATRMeanReversion"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.atr_period.max(self.sma_period)
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
-        let high = kline.high.to_string().parse::<f64>().unwrap_or(close);
-        let low = kline.low.to_string().parse::<f64>().unwrap_or(close);
+        let high = kline.high.to_f64().unwrap_or(close);
+        let low = kline.low.to_f64().unwrap_or(close);
 
         // ATR needs DataItem
         let bar = ta::DataItem::builder()
@@ -478,24 +563,102 @@ impl SignalGenerator for AtrMeanReversionSignalGenerator {
         self.last_sma = 0.0;
         self.bars_seen = 0;
     }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        vec![("atr", self.last_atr), ("atr_sma", self.last_sma)]
+    }
 }
 
 // ============================================================================
 // 7. VWAP (Volume Weighted Average Price) Signal Generator
 // ============================================================================
 
+const DAY_MS: i64 = 86_400_000;
+
 pub struct VwapSignalGenerator {
     period: usize,
+    anchor: VwapAnchor,
     price_volume_sum: Vec<f64>,
     volume_sum: Vec<f64>,
+    /// Anchored: sums and bar count since the anchor
+    anchored_pv: f64,
+    anchored_volume: f64,
+    bars_since_anchor: usize,
+    /// Session / N-day anchors: block of the current anchor
+    anchor_block: Option<i64>,
+    /// Swing-low anchor: (price × volume, volume, low) of the last 2 × lookback + 1 bars
+    recent: VecDeque<(f64, f64, f64)>,
+    last_vwap: f64,
 }
 
 impl VwapSignalGenerator {
     pub fn new(period: usize) -> Self {
+        Self::anchored(period, VwapAnchor::Rolling)
+    }
+
+    /// VWAP summed from `anchor`, signalling once `period` bars have accumulated
+    pub fn anchored(period: usize, anchor: VwapAnchor) -> Self {
         Self {
             period,
+            anchor,
             price_volume_sum: Vec::with_capacity(period),
             volume_sum: Vec::with_capacity(period),
+            anchored_pv: 0.0,
+            anchored_volume: 0.0,
+            bars_since_anchor: 0,
+            anchor_block: None,
+            recent: VecDeque::new(),
+            last_vwap: 0.0,
+        }
+    }
+
+    fn restart_anchor(&mut self) {
+        self.anchored_pv = 0.0;
+        self.anchored_volume = 0.0;
+        self.bars_since_anchor = 0;
+    }
+
+    /// Add a bar to the anchored sums, moving the anchor first when a new one starts
+    fn accumulate_anchored(&mut self, kline: &Kline, price_volume: f64, volume: f64) {
+        let block_ms = match self.anchor {
+            VwapAnchor::Session => Some(DAY_MS),
+            VwapAnchor::Days { days } => Some(DAY_MS * days.max(1) as i64),
+            VwapAnchor::Rolling | VwapAnchor::SwingLow { .. } => None,
+        };
+        if let Some(block_ms) = block_ms {
+            let block = kline.open_time.div_euclid(block_ms);
+            if self.anchor_block != Some(block) {
+                self.anchor_block = Some(block);
+                self.restart_anchor();
+            }
+        }
+
+        self.anchored_pv += price_volume;
+        self.anchored_volume += volume;
+        self.bars_since_anchor += 1;
+
+        if let VwapAnchor::SwingLow { lookback } = self.anchor {
+            let low = kline.low.to_f64().unwrap_or_else(|| close_f64(kline));
+            self.recent.push_back((price_volume, volume, low));
+            if self.recent.len() > 2 * lookback + 1 {
+                self.recent.pop_front();
+            }
+            // The middle bar is a swing low once `lookback` bars on each side are higher:
+            // the VWAP restarts from it
+            if self.recent.len() == 2 * lookback + 1 {
+                let pivot = self.recent[lookback].2;
+                let is_swing_low = self
+                    .recent
+                    .iter()
+                    .enumerate()
+                    .all(|(i, bar)| i == lookback || bar.2 > pivot);
+                if is_swing_low {
+                    let since_pivot = self.recent.iter().skip(lookback);
+                    self.anchored_pv = since_pivot.clone().map(|bar| bar.0).sum();
+                    self.anchored_volume = since_pivot.map(|bar| bar.1).sum();
+                    self.bars_since_anchor = lookback + 1;
+                }
+            }
         }
     }
 }
@@ -505,33 +668,46 @@ impl SignalGenerator for VwapSignalGenerator {
         "VWAP"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.period
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
-        let volume = kline.volume.to_string().parse::<f64>().unwrap_or(1.0);
-        let typical_price = (close
-            + kline.high.to_string().parse::<f64>().unwrap_or(close)
-            + kline.low.to_string().parse::<f64>().unwrap_or(close))
-            / 3.0;
-
-        self.price_volume_sum.push(typical_price * volume);
-        self.volume_sum.push(volume);
-        if self.price_volume_sum.len() > self.period {
-            self.price_volume_sum.remove(0);
-            self.volume_sum.remove(0);
-        }
+        let volume = kline.volume.to_f64().unwrap_or(1.0);
+        let typical_price =
+            (close + kline.high.to_f64().unwrap_or(close) + kline.low.to_f64().unwrap_or(close))
+                / 3.0;
+
+        let (total_pv, total_v) = if self.anchor.is_rolling() {
+            self.price_volume_sum.push(typical_price * volume);
+            self.volume_sum.push(volume);
+            if self.price_volume_sum.len() > self.period {
+                self.price_volume_sum.remove(0);
+                self.volume_sum.remove(0);
+            }
 
-        if self.price_volume_sum.len() < self.period {
-            return SignalWithConfidence::hold();
-        }
+            if self.price_volume_sum.len() < self.period {
+                return SignalWithConfidence::hold();
+            }
 
-        let total_pv: f64 = self.price_volume_sum.iter().sum();
-        let total_v: f64 = self.volume_sum.iter().sum();
+            let total_pv: f64 = self.price_volume_sum.iter().sum();
+            let total_v: f64 = self.volume_sum.iter().sum();
+            (total_pv, total_v)
+        } else {
+            self.accumulate_anchored(kline, typical_price * volume, volume);
+            if self.bars_since_anchor < self.period {
+                return SignalWithConfidence::hold();
+            }
+            (self.anchored_pv, self.anchored_volume)
+        };
 
         if total_v <= 0.0 {
             return SignalWithConfidence::hold();
         }
 
         let vwap = total_pv / total_v;
+        self.last_vwap = vwap;
 
         if vwap <= 0.0 {
             return SignalWithConfidence::hold();
@@ -553,6 +729,14 @@ impl SignalGenerator for VwapSignalGenerator {
     fn reset(&mut self) {
         self.price_volume_sum.clear();
         self.volume_sum.clear();
+        self.restart_anchor();
+        self.anchor_block = None;
+        self.recent.clear();
+        self.last_vwap = 0.0;
+    }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        vec![("vwap", self.last_vwap)]
     }
 }
 
@@ -564,6 +748,7 @@ pub struct ObvSignalGenerator {
     sma_period: usize,
     obv: f64,
     obv_history: Vec<f64>,
+    last_obv_sma: f64,
     prev_close: f64,
     bars_seen: usize,
 }
@@ -574,6 +759,7 @@ impl ObvSignalGenerator {
             sma_period,
             obv: 0.0,
             obv_history: Vec::with_capacity(sma_period + 1),
+            last_obv_sma: 0.0,
             prev_close: 0.0,
             bars_seen: 0,
         }
@@ -585,9 +771,13 @@ impl SignalGenerator for ObvSignalGenerator {
         "OBV"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.sma_period + 1
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
-        let volume = kline.volume.to_string().parse::<f64>().unwrap_or(0.0);
+        let volume = kline.volume.to_f64().unwrap_or(0.0);
         self.bars_seen += 1;
 
         if self.bars_seen > 1 {
@@ -610,6 +800,7 @@ impl SignalGenerator for ObvSignalGenerator {
 
         let sma_obv: f64 =
             self.obv_history.iter().sum::<f64>() / self.obv_history.len() as f64;
+        self.last_obv_sma = sma_obv;
 
         // Compute OBV slope (change over last few bars) for confidence
         let slope = if self.obv_history.len() >= 3 {
@@ -636,9 +827,14 @@ impl SignalGenerator for ObvSignalGenerator {
     fn reset(&mut self) {
         self.obv = 0.0;
         self.obv_history.clear();
+        self.last_obv_sma = 0.0;
         self.prev_close = 0.0;
         self.bars_seen = 0;
     }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        vec![("obv", self.obv), ("obv_sma", self.last_obv_sma)]
+    }
 }
 
 // ============================================================================
@@ -651,6 +847,7 @@ pub struct WilliamsRSignalGenerator {
     oversold: f64,
     highs: Vec<f64>,
     lows: Vec<f64>,
+    last_wr: f64,
 }
 
 impl WilliamsRSignalGenerator {
@@ -661,6 +858,7 @@ impl WilliamsRSignalGenerator {
             oversold,
             highs: Vec::with_capacity(period),
             lows: Vec::with_capacity(period),
+            last_wr: -50.0,
         }
     }
 }
@@ -670,10 +868,14 @@ impl SignalGenerator for WilliamsRSignalGenerator {
         "WilliamsR"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.period
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
-        let high = kline.high.to_string().parse::<f64>().unwrap_or(close);
-        let low = kline.low.to_string().parse::<f64>().unwrap_or(close);
+        let high = kline.high.to_f64().unwrap_or(close);
+        let low = kline.low.to_f64().unwrap_or(close);
 
         self.highs.push(high);
         self.lows.push(low);
@@ -695,6 +897,7 @@ impl SignalGenerator for WilliamsRSignalGenerator {
 
         // Williams %R = (Highest High - Close) / (Highest High - Lowest Low) * -100
         let wr = (highest - close) / (highest - lowest) * -100.0;
+        self.last_wr = wr;
 
         if wr < self.oversold {
             // Oversold → Buy
@@ -712,6 +915,11 @@ impl SignalGenerator for WilliamsRSignalGenerator {
     fn reset(&mut self) {
         self.highs.clear();
         self.lows.clear();
+        self.last_wr = -50.0;
+    }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        vec![("williams_r", self.last_wr)]
     }
 }
 
@@ -729,6 +937,8 @@ pub struct AdxSignalGenerator {
     minus_dm_ema: f64,
     tr_ema: f64,
     adx_ema: f64,
+    last_plus_di: f64,
+    last_minus_di: f64,
     bars_seen: usize,
 }
 
@@ -744,6 +954,8 @@ impl AdxSignalGenerator {
             minus_dm_ema: 0.0,
             tr_ema: 0.0,
             adx_ema: 0.0,
+            last_plus_di: 0.0,
+            last_minus_di: 0.0,
             bars_seen: 0,
         }
     }
@@ -754,9 +966,13 @@ impl SignalGenerator for AdxSignalGenerator {
         "ADX"
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.period * 2 - 1
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
-        let high = kline.high.to_string().parse::<f64>().unwrap_or(0.0);
-        let low = kline.low.to_string().parse::<f64>().unwrap_or(0.0);
+        let high = kline.high.to_f64().unwrap_or(0.0);
+        let low = kline.low.to_f64().unwrap_or(0.0);
         let close = close_f64(kline);
         self.bars_seen += 1;
 
@@ -810,6 +1026,8 @@ impl SignalGenerator for AdxSignalGenerator {
 
         let plus_di = (self.plus_dm_ema / self.tr_ema) * 100.0;
         let minus_di = (self.minus_dm_ema / self.tr_ema) * 100.0;
+        self.last_plus_di = plus_di;
+        self.last_minus_di = minus_di;
 
         let di_sum = plus_di + minus_di;
         let dx = if di_sum > 0.0 {
@@ -846,8 +1064,18 @@ impl SignalGenerator for AdxSignalGenerator {
         self.minus_dm_ema = 0.0;
         self.tr_ema = 0.0;
         self.adx_ema = 0.0;
+        self.last_plus_di = 0.0;
+        self.last_minus_di = 0.0;
         self.bars_seen = 0;
     }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("adx", self.adx_ema),
+            ("plus_di", self.last_plus_di),
+            ("minus_di", self.last_minus_di),
+        ]
+    }
 }
 
 // ============================================================================
@@ -886,6 +1114,14 @@ impl SignalGenerator for ComboSignalGenerator {
         &self.name
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.generators
+            .iter()
+            .map(|g| g.warmup_bars())
+            .max()
+            .unwrap_or(0)
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let signals: Vec<SignalWithConfidence> = self
             .generators
@@ -960,6 +1196,255 @@ impl SignalGenerator for ComboSignalGenerator {
             g.reset();
         }
     }
+
+    /// Values of every member, in member order
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        self.generators.iter().flat_map(|g| g.features()).collect()
+    }
+}
+
+// ============================================================================
+// Precomputed indicator series (shared across combos)
+// ============================================================================
+
+/// Max series kept in memory (a year of 15m bars is ~35k signals, ~560 KB per series)
+pub const SERIES_CACHE_CAPACITY: usize = 256;
+
+/// One indicator configuration over one kline window of one symbol
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SeriesKey {
+    indicator: crate::discovery::SingleIndicatorType,
+    /// `Debug` of the params — they hold f64 fields, so they can't be hashed directly
+    params: String,
+    symbol: String,
+    interval_ms: i64,
+    first_open_time: i64,
+    bars: usize,
+}
+
+struct CachedSeries {
+    signals: Arc<[SignalWithConfidence]>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct SeriesCacheState {
+    entries: HashMap<SeriesKey, CachedSeries>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// Per-bar signals of single indicators, computed once per run and replayed by every
+/// DynamicCombo that uses the same indicator, params and klines.
+///
+/// Hundreds of combos share a handful of sub-indicator configurations (3 param variants
+/// per indicator in the grid), so most sub-generators become a lookup instead of a full
+/// RSI/BB/MACD pass. Least recently used series are evicted beyond `capacity`.
+///
+/// Under a `CandleTransform` other than raw, every generator reads the transformed
+/// candles of its klines, and the series are computed on them: a cache serves a single
+/// transform.
+pub struct IndicatorSeriesCache {
+    state: Mutex<SeriesCacheState>,
+    capacity: usize,
+    candles: CandleTransform,
+}
+
+impl IndicatorSeriesCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(SeriesCacheState::default()),
+            capacity: capacity.max(1),
+            candles: CandleTransform::Raw,
+        }
+    }
+
+    /// Feed the generators the `candles` of their klines
+    pub fn with_candles(self, candles: CandleTransform) -> Self {
+        Self { candles, ..self }
+    }
+
+    /// Signal series of one indicator over `klines`, computed on first use
+    pub fn series(
+        &self,
+        indicator: &crate::discovery::SingleIndicatorType,
+        params: &crate::discovery::IndicatorParams,
+        symbol: &str,
+        klines: &[Kline],
+    ) -> Arc<[SignalWithConfidence]> {
+        let key = SeriesKey {
+            indicator: *indicator,
+            params: format!("{:?}", params),
+            symbol: symbol.to_string(),
+            interval_ms: klines
+                .first()
+                .map(|k| k.close_time + 1 - k.open_time)
+                .unwrap_or(0),
+            first_open_time: klines.first().map(|k| k.open_time).unwrap_or(0),
+            bars: klines.len(),
+        };
+
+        {
+            let mut state = self.state.lock().unwrap();
+            state.tick += 1;
+            let tick = state.tick;
+            if let Some(entry) = state.entries.get_mut(&key) {
+                entry.last_used = tick;
+                let signals = entry.signals.clone();
+                state.hits += 1;
+                return signals;
+            }
+            state.misses += 1;
+        }
+
+        // Computed outside the lock; a concurrent miss on the same key just recomputes it
+        let mut gen = build_single_generator(indicator, params);
+        let signals: Arc<[SignalWithConfidence]> = klines.iter().map(|k| gen.on_bar(k)).collect();
+
+        let mut state = self.state.lock().unwrap();
+        if state.entries.len() >= self.capacity {
+            if let Some(oldest) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone())
+            {
+                state.entries.remove(&oldest);
+            }
+        }
+        let last_used = state.tick;
+        state.entries.insert(
+            key,
+            CachedSeries {
+                signals: signals.clone(),
+                last_used,
+            },
+        );
+        signals
+    }
+
+    /// Generator for `strategy_type` over `klines`: DynamicCombo sub-indicators replay
+    /// cached series, every other strategy is built as usual.
+    /// The generator must then be fed exactly these `klines`, from the first bar.
+    pub fn build_generator(
+        &self,
+        strategy_type: &DiscoveryStrategyType,
+        symbol: &str,
+        klines: &[Kline],
+    ) -> Box<dyn SignalGenerator> {
+        if self.candles.is_raw() {
+            return self.build_on_candles(strategy_type, symbol, klines);
+        }
+        let candles = self.candles.apply(klines);
+        Box::new(CandleSignalGenerator::new(
+            self.build_on_candles(strategy_type, symbol, &candles),
+            self.candles,
+        ))
+    }
+
+    fn build_on_candles(
+        &self,
+        strategy_type: &DiscoveryStrategyType,
+        symbol: &str,
+        klines: &[Kline],
+    ) -> Box<dyn SignalGenerator> {
+        match strategy_type {
+            DiscoveryStrategyType::DynamicCombo {
+                indicators,
+                params,
+                combine_mode,
+            } => build_dynamic_combo(indicators, params, *combine_mode, |ind, p| {
+                Box::new(ReplaySignalGenerator::new(
+                    ind.short_name(),
+                    self.series(ind, p, symbol, klines),
+                    build_single_generator(ind, p).warmup_bars(),
+                ))
+            }),
+            DiscoveryStrategyType::Ensemble {
+                members, weights, ..
+            } => Box::new(crate::ensemble::EnsembleSignalGenerator::new(
+                members
+                    .iter()
+                    .map(|m| self.build_on_candles(m, symbol, klines))
+                    .collect(),
+                weights.clone(),
+            )),
+            DiscoveryStrategyType::Filtered { inner, filter } => {
+                Box::new(crate::entry_filter::FilteredSignalGenerator::new(
+                    self.build_on_candles(inner, symbol, klines),
+                    *filter,
+                ))
+            }
+            DiscoveryStrategyType::Trailing { inner, .. } => {
+                self.build_on_candles(inner, symbol, klines)
+            }
+            DiscoveryStrategyType::HtfFiltered { inner, htf_filter } => {
+                Box::new(crate::htf_filter::HtfFilteredSignalGenerator::new(
+                    self.build_on_candles(inner, symbol, klines),
+                    *htf_filter,
+                ))
+            }
+            DiscoveryStrategyType::ZScoreFiltered {
+                inner,
+                zscore_filter,
+            } => Box::new(crate::zscore_filter::ZScoreFilteredSignalGenerator::new(
+                self.build_generator(inner, symbol, klines),
+                *zscore_filter,
+            )),
+            _ => build_signal_generator(strategy_type),
+        }
+    }
+
+    /// (hits, misses, cached series)
+    pub fn stats(&self) -> (u64, u64, usize) {
+        let state = self.state.lock().unwrap();
+        (state.hits, state.misses, state.entries.len())
+    }
+}
+
+/// Replays a precomputed signal series, one entry per `on_bar` call
+pub struct ReplaySignalGenerator {
+    name: &'static str,
+    signals: Arc<[SignalWithConfidence]>,
+    /// Warm-up of the generator the signals were computed by
+    warmup: usize,
+    pos: usize,
+}
+
+impl ReplaySignalGenerator {
+    pub fn new(name: &'static str, signals: Arc<[SignalWithConfidence]>, warmup: usize) -> Self {
+        Self {
+            name,
+            signals,
+            warmup,
+            pos: 0,
+        }
+    }
+}
+
+impl SignalGenerator for ReplaySignalGenerator {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn warmup_bars(&self) -> usize {
+        self.warmup
+    }
+
+    fn on_bar(&mut self, _kline: &Kline) -> SignalWithConfidence {
+        let sig = self
+            .signals
+            .get(self.pos)
+            .copied()
+            .unwrap_or_else(SignalWithConfidence::hold);
+        self.pos += 1;
+        sig
+    }
+
+    fn reset(&mut self) {
+        self.pos = 0;
+    }
 }
 
 // ============================================================================
@@ -1146,8 +1631,8 @@ pub fn build_signal_generator(strategy_type: &DiscoveryStrategyType) -> Box<dyn
         )),
 
         // New singles
-        DiscoveryStrategyType::Vwap { period } => {
-            Box::new(VwapSignalGenerator::new(*period))
+        DiscoveryStrategyType::Vwap { period, anchor } => {
+            Box::new(VwapSignalGenerator::anchored(*period, *anchor))
         }
 
         DiscoveryStrategyType::Obv { sma_period } => {
@@ -1229,44 +1714,112 @@ pub fn build_signal_generator(strategy_type: &DiscoveryStrategyType) -> Box<dyn
         )),
 
         // Dynamic combos: build each sub-indicator, wrap in ComboSignalGenerator
-        DiscoveryStrategyType::DynamicCombo { indicators, params, combine_mode } => {
-            use crate::discovery::DynCombineMode;
-
-            let generators: Vec<Box<dyn SignalGenerator>> = indicators.iter()
-                .zip(params.iter())
-                .map(|(ind, p)| build_single_generator(ind, p))
-                .collect();
-
-            let name = format!(
-                "{}({})",
-                indicators.iter().map(|i| i.short_name()).collect::<Vec<_>>().join("+"),
-                combine_mode.short_suffix(),
-            );
-
-            let mode = match combine_mode {
-                DynCombineMode::Unanimous => CombineMode::Unanimous,
-                DynCombineMode::Majority => CombineMode::Majority,
-                DynCombineMode::PrimaryConfirmed => CombineMode::PrimaryConfirmed,
-            };
-
-            Box::new(ComboSignalGenerator::new(name, generators, mode))
-        }
+        DiscoveryStrategyType::DynamicCombo {
+            indicators,
+            params,
+            combine_mode,
+        } => build_dynamic_combo(indicators, params, *combine_mode, build_single_generator),
 
         // Web strategies: delegate to web_strategies module
         DiscoveryStrategyType::WebStrategy { id, params } => {
             crate::web_strategies::build_web_generator(id, params)
         }
 
-        // Gabagool is handled separately in discovery.rs, not via SignalGenerator
-        DiscoveryStrategyType::Gabagool { .. } => {
-            // Return a dummy RSI that always holds — Gabagool uses its own engine
+        // ML strategies: user-supplied ONNX classifier
+        DiscoveryStrategyType::OnnxModel {
+            model_path,
+            threshold,
+        } => Box::new(crate::onnx::OnnxSignalGenerator::new(
+            model_path, *threshold,
+        )),
+
+        // Ensembles: weighted vote of their members' generators
+        DiscoveryStrategyType::Ensemble {
+            members, weights, ..
+        } => Box::new(crate::ensemble::EnsembleSignalGenerator::new(
+            members.iter().map(build_signal_generator).collect(),
+            weights.clone(),
+        )),
+
+        // Filtered strategies: the inner generator with its entries gated
+        DiscoveryStrategyType::Filtered { inner, filter } => {
+            Box::new(crate::entry_filter::FilteredSignalGenerator::new(
+                build_signal_generator(inner),
+                *filter,
+            ))
+        }
+
+        // Trailing strategies: the inner generator (the backtester runs the stop)
+        DiscoveryStrategyType::Trailing { inner, .. } => build_signal_generator(inner),
+
+        // HTF-filtered strategies: the inner generator with its entries gated by the
+        // higher-timeframe trend
+        DiscoveryStrategyType::HtfFiltered { inner, htf_filter } => {
+            Box::new(crate::htf_filter::HtfFilteredSignalGenerator::new(
+                build_signal_generator(inner),
+                *htf_filter,
+            ))
+        }
+
+        // Z-score-filtered strategies: the inner generator with its marginal signals held
+        DiscoveryStrategyType::ZScoreFiltered {
+            inner,
+            zscore_filter,
+        } => Box::new(crate::zscore_filter::ZScoreFilteredSignalGenerator::new(
+            build_signal_generator(inner),
+            *zscore_filter,
+        )),
+
+        // Gabagool and cross-sectional strategies are handled separately in discovery.rs,
+        // not via SignalGenerator
+        DiscoveryStrategyType::Gabagool { .. }
+        | DiscoveryStrategyType::RelativeStrength { .. }
+        | DiscoveryStrategyType::PairSpread { .. } => {
+            // Return a dummy RSI that always holds — these use their own engines
             Box::new(RsiSignalGenerator::new(14, 99.0, 1.0))
         }
     }
 }
 
+/// Wrap one sub-generator per (indicator, params) pair in a ComboSignalGenerator
+fn build_dynamic_combo(
+    indicators: &[crate::discovery::SingleIndicatorType],
+    params: &[crate::discovery::IndicatorParams],
+    combine_mode: crate::discovery::DynCombineMode,
+    mut build: impl FnMut(
+        &crate::discovery::SingleIndicatorType,
+        &crate::discovery::IndicatorParams,
+    ) -> Box<dyn SignalGenerator>,
+) -> Box<dyn SignalGenerator> {
+    use crate::discovery::DynCombineMode;
+
+    let generators: Vec<Box<dyn SignalGenerator>> = indicators
+        .iter()
+        .zip(params.iter())
+        .map(|(ind, p)| build(ind, p))
+        .collect();
+
+    let name = format!(
+        "{}({})",
+        indicators
+            .iter()
+            .map(|i| i.short_name())
+            .collect::<Vec<_>>()
+            .join("+"),
+        combine_mode.short_suffix(),
+    );
+
+    let mode = match combine_mode {
+        DynCombineMode::Unanimous => CombineMode::Unanimous,
+        DynCombineMode::Majority => CombineMode::Majority,
+        DynCombineMode::PrimaryConfirmed => CombineMode::PrimaryConfirmed,
+    };
+
+    Box::new(ComboSignalGenerator::new(name, generators, mode))
+}
+
 /// Build a single indicator signal generator from its type and params
-fn build_single_generator(
+pub(crate) fn build_single_generator(
     ind: &crate::discovery::SingleIndicatorType,
     params: &crate::discovery::IndicatorParams,
 ) -> Box<dyn SignalGenerator> {
@@ -1291,8 +1844,8 @@ fn build_single_generator(
         (SingleIndicatorType::AtrMeanReversion, IndicatorParams::AtrMeanReversion { atr_period, sma_period, multiplier }) => {
             Box::new(AtrMeanReversionSignalGenerator::new(*atr_period, *sma_period, *multiplier))
         }
-        (SingleIndicatorType::Vwap, IndicatorParams::Vwap { period }) => {
-            Box::new(VwapSignalGenerator::new(*period))
+        (SingleIndicatorType::Vwap, IndicatorParams::Vwap { period, anchor }) => {
+            Box::new(VwapSignalGenerator::anchored(*period, *anchor))
         }
         (SingleIndicatorType::Obv, IndicatorParams::Obv { sma_period }) => {
             Box::new(ObvSignalGenerator::new(*sma_period))
@@ -1426,4 +1979,57 @@ mod tests {
         let sig = gen.on_bar(&klines[0]);
         assert_eq!(sig.signal, Signal::Hold);
     }
+
+    #[test]
+    fn test_series_cache_replays_dynamic_combos() {
+        use crate::discovery::{DynCombineMode, SingleIndicatorType};
+
+        let prices: Vec<f64> = (0..400)
+            .map(|i| 100.0 + 10.0 * (i as f64 / 15.0).sin())
+            .collect();
+        let klines = make_klines(&prices);
+        let combo = |indicators: Vec<SingleIndicatorType>, mode| {
+            let params = indicators.iter().map(|i| i.default_params()).collect();
+            DiscoveryStrategyType::DynamicCombo {
+                indicators,
+                params,
+                combine_mode: mode,
+            }
+        };
+        let strategies = [
+            combo(
+                vec![SingleIndicatorType::Rsi, SingleIndicatorType::Stochastic],
+                DynCombineMode::Majority,
+            ),
+            combo(
+                vec![SingleIndicatorType::Rsi, SingleIndicatorType::Macd],
+                DynCombineMode::PrimaryConfirmed,
+            ),
+        ];
+
+        let cache = IndicatorSeriesCache::new(SERIES_CACHE_CAPACITY);
+        for strategy in &strategies {
+            let mut fresh = build_signal_generator(strategy);
+            let mut cached = cache.build_generator(strategy, "BTCUSDT", &klines);
+            assert_eq!(cached.name(), fresh.name());
+            for kline in &klines {
+                let (a, b) = (fresh.on_bar(kline), cached.on_bar(kline));
+                assert_eq!(a.signal, b.signal);
+                assert_eq!(a.confidence, b.confidence);
+            }
+        }
+        // RSI was computed once and replayed by the second combo
+        assert_eq!(cache.stats(), (1, 3, 3));
+
+        // Another window (or symbol) is a different series
+        cache.build_generator(&strategies[0], "BTCUSDT", &klines[100..]);
+        cache.build_generator(&strategies[0], "ETHUSDT", &klines);
+        assert_eq!(cache.stats(), (1, 7, 7));
+
+        // Least recently used series are evicted beyond capacity
+        let small = IndicatorSeriesCache::new(2);
+        small.build_generator(&strategies[1], "BTCUSDT", &klines);
+        small.build_generator(&strategies[0], "BTCUSDT", &klines);
+        assert_eq!(small.stats(), (1, 3, 2));
+    }
 }
@@ -0,0 +1,287 @@
+//! Streaming indicator API
+//!
+//! Push closed klines one at a time into an `IndicatorStream` and get back what a
+//! backtest of the same strategy sees on that bar: the signal (held during the
+//! generator's warm-up, like `run_generic_backtest()` does) and the typed values of the
+//! indicators it was computed from. The stream is built with `build_signal_generator()`,
+//! so a live consumer trades exactly the signals the backtests were scored on. Order
+//! execution (sizing, fills, trailing stops) stays with the consumer.
+
+use serde::Serialize;
+
+use crate::discovery::DiscoveryStrategyType;
+use crate::indicators::{build_signal_generator, close_f64, SignalGenerator, SignalWithConfidence};
+use crate::types::Kline;
+
+/// Value of one indicator on the last bar, as computed by the generator
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "indicator", rename_all = "snake_case")]
+pub enum IndicatorValue {
+    Rsi {
+        rsi: f64,
+    },
+    BollingerBands {
+        upper: f64,
+        middle: f64,
+        lower: f64,
+    },
+    Macd {
+        macd: f64,
+        signal: f64,
+        histogram: f64,
+    },
+    EmaCrossover {
+        fast: f64,
+        slow: f64,
+    },
+    Stochastic {
+        k: f64,
+        d: f64,
+    },
+    AtrMeanReversion {
+        atr: f64,
+        sma: f64,
+    },
+    Vwap {
+        vwap: f64,
+    },
+    Obv {
+        obv: f64,
+        sma: f64,
+    },
+    WilliamsR {
+        williams_r: f64,
+    },
+    Adx {
+        adx: f64,
+        plus_di: f64,
+        minus_di: f64,
+    },
+}
+
+impl IndicatorValue {
+    /// Typed values of a generator's `features()`, in order (members of a combo or an
+    /// ensemble each give their own). Unknown columns are skipped.
+    pub fn from_features(features: &[(&'static str, f64)]) -> Vec<IndicatorValue> {
+        let mut values = Vec::new();
+        let mut rest = features;
+        while !rest.is_empty() {
+            let (value, tail) = match rest {
+                [("rsi", rsi), tail @ ..] => (Some(Self::Rsi { rsi: *rsi }), tail),
+                [("bb_upper", upper), ("bb_middle", middle), ("bb_lower", lower), tail @ ..] => (
+                    Some(Self::BollingerBands {
+                        upper: *upper,
+                        middle: *middle,
+                        lower: *lower,
+                    }),
+                    tail,
+                ),
+                [("macd", macd), ("macd_signal", signal), ("macd_histogram", hist), tail @ ..] => (
+                    Some(Self::Macd {
+                        macd: *macd,
+                        signal: *signal,
+                        histogram: *hist,
+                    }),
+                    tail,
+                ),
+                [("ema_fast", fast), ("ema_slow", slow), tail @ ..] => (
+                    Some(Self::EmaCrossover {
+                        fast: *fast,
+                        slow: *slow,
+                    }),
+                    tail,
+                ),
+                [("stoch_k", k), ("stoch_d", d), tail @ ..] => {
+                    (Some(Self::Stochastic { k: *k, d: *d }), tail)
+                }
+                [("atr", atr), ("atr_sma", sma), tail @ ..] => (
+                    Some(Self::AtrMeanReversion {
+                        atr: *atr,
+                        sma: *sma,
+                    }),
+                    tail,
+                ),
+                [("vwap", vwap), tail @ ..] => (Some(Self::Vwap { vwap: *vwap }), tail),
+                [("obv", obv), ("obv_sma", sma), tail @ ..] => (
+                    Some(Self::Obv {
+                        obv: *obv,
+                        sma: *sma,
+                    }),
+                    tail,
+                ),
+                [("williams_r", williams_r), tail @ ..] => (
+                    Some(Self::WilliamsR {
+                        williams_r: *williams_r,
+                    }),
+                    tail,
+                ),
+                [("adx", adx), ("plus_di", plus_di), ("minus_di", minus_di), tail @ ..] => (
+                    Some(Self::Adx {
+                        adx: *adx,
+                        plus_di: *plus_di,
+                        minus_di: *minus_di,
+                    }),
+                    tail,
+                ),
+                [_, tail @ ..] => (None, tail),
+                [] => unreachable!(),
+            };
+            values.extend(value);
+            rest = tail;
+        }
+        values
+    }
+}
+
+/// Output of the stream for one kline
+#[derive(Debug, Clone)]
+pub struct StreamUpdate {
+    pub open_time: i64,
+    pub close: f64,
+    /// Hold while the stream is warming up
+    pub signal: SignalWithConfidence,
+    /// Past the generator's warm-up: `signal` is what a backtest would trade
+    pub warm: bool,
+    pub values: Vec<IndicatorValue>,
+}
+
+/// A strategy's signal generator fed kline by kline
+pub struct IndicatorStream {
+    generator: Box<dyn SignalGenerator>,
+    warmup: usize,
+    bars_seen: usize,
+    last_open_time: Option<i64>,
+}
+
+impl IndicatorStream {
+    /// Stream of `strategy`'s signals; refused for strategies without bar signals
+    /// (Gabagool, cross-sectional)
+    pub fn new(strategy: &DiscoveryStrategyType) -> Result<Self, String> {
+        if strategy.is_gabagool() || strategy.is_cross_sectional() {
+            return Err(format!("{} has no bar signals to stream", strategy.name()));
+        }
+        Ok(Self::from_generator(build_signal_generator(strategy)))
+    }
+
+    pub fn from_generator(generator: Box<dyn SignalGenerator>) -> Self {
+        Self {
+            warmup: generator.warmup_bars(),
+            generator,
+            bars_seen: 0,
+            last_open_time: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.generator.name()
+    }
+
+    /// Leading bars whose signals are held
+    pub fn warmup_bars(&self) -> usize {
+        self.warmup
+    }
+
+    pub fn bars_seen(&self) -> usize {
+        self.bars_seen
+    }
+
+    /// Feed the next closed kline. `None` for a kline that doesn't open after the last
+    /// one (a poll returning the same candle again): indicators only see each bar once.
+    pub fn push(&mut self, kline: &Kline) -> Option<StreamUpdate> {
+        if self.last_open_time.is_some_and(|t| kline.open_time <= t) {
+            return None;
+        }
+        self.last_open_time = Some(kline.open_time);
+
+        let signal = self.generator.on_bar(kline);
+        let warm = self.bars_seen >= self.warmup;
+        self.bars_seen += 1;
+        Some(StreamUpdate {
+            open_time: kline.open_time,
+            close: close_f64(kline),
+            signal: if warm {
+                signal
+            } else {
+                SignalWithConfidence::hold()
+            },
+            warm,
+            values: IndicatorValue::from_features(&self.generator.features()),
+        })
+    }
+
+    /// Back to the state before the first kline
+    pub fn reset(&mut self) {
+        self.generator.reset();
+        self.bars_seen = 0;
+        self.last_open_time = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Signal;
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_stream_matches_the_backtest_generator() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 500,
+            ..SyntheticConfig::for_model(SyntheticModel::RegimeSwitching)
+        });
+        let strategy = DiscoveryStrategyType::RsiBollinger {
+            rsi_period: 7,
+            rsi_ob: 65.0,
+            rsi_os: 35.0,
+            bb_period: 10,
+            bb_mult: 1.5,
+        };
+        let mut stream = IndicatorStream::new(&strategy).unwrap();
+        let mut generator = build_signal_generator(&strategy);
+        assert_eq!(stream.warmup_bars(), 10);
+
+        let mut trades = 0;
+        for (idx, kline) in klines.iter().enumerate() {
+            let update = stream.push(kline).unwrap();
+            let expected = generator.on_bar(kline);
+            assert_eq!(update.warm, idx >= 10);
+            if update.warm {
+                assert_eq!(update.signal.signal, expected.signal);
+                assert_eq!(update.signal.confidence, expected.confidence);
+            } else {
+                assert_eq!(update.signal.signal, Signal::Hold);
+            }
+            trades += (update.signal.signal != Signal::Hold) as usize;
+
+            // One value per combo member, typed
+            let [IndicatorValue::Rsi { rsi }, IndicatorValue::BollingerBands {
+                upper,
+                middle,
+                lower,
+            }] = update.values[..]
+            else {
+                panic!("unexpected values {:?}", update.values);
+            };
+            assert!((0.0..=100.0).contains(&rsi));
+            assert!(lower <= middle && middle <= upper);
+        }
+        assert!(trades > 0);
+        assert_eq!(stream.bars_seen(), klines.len());
+
+        // Stale or repeated klines are ignored; a reset replays from scratch
+        assert!(stream.push(&klines[10]).is_none());
+        assert!(stream.push(klines.last().unwrap()).is_none());
+        stream.reset();
+        assert!(!stream.push(&klines[0]).unwrap().warm);
+
+        let json = serde_json::to_value(IndicatorValue::Rsi { rsi: 42.0 }).unwrap();
+        assert_eq!(json, serde_json::json!({"indicator": "rsi", "rsi": 42.0}));
+        assert!(
+            IndicatorStream::new(&DiscoveryStrategyType::RelativeStrength {
+                lookback: 96,
+                top_k: 1
+            })
+            .is_err()
+        );
+    }
+}
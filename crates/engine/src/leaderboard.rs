@@ -499,7 +499,7 @@ fn detect_arbitrage_pattern(trades: &[TraderTrade]) -> f64 {
     let mut arb_count = 0u32;
     let mut total_events = 0u32;
 
-    for (_slug, event_trades) in &by_event {
+    for event_trades in by_event.values() {
         if event_trades.len() < 2 {
             continue;
         }
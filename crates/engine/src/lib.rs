@@ -3,52 +3,193 @@
 //! Self-contained crate extracted from poly_bot's trading-engine.
 //! Provides:
 //! - RSI and Gabagool backtesting engines
-//! - 14-strategy Discovery Agent with 2-phase scanning
+//! - 14-strategy Discovery Agent with 2-phase scanning, embeddable via `DiscoveryEngine`
 //! - Automatic parameter optimizer (grid search)
 //! - Binance public API client for market data
 
+pub mod adaptive;
 pub mod api;
+pub mod backup;
+pub mod benchmark;
+pub mod candles;
+pub mod capital;
+#[cfg(feature = "charts")]
+pub mod chart;
+pub mod daily_pnl;
+pub mod data_quality;
+pub mod data_source;
 pub mod discovery;
+pub mod discovery_engine;
+pub mod distributed;
+pub mod dry_run;
 pub mod engine;
+pub mod ensemble;
+pub mod entry_filter;
+pub mod error;
+pub mod explain;
+pub mod features;
+pub mod fee_sensitivity;
 pub mod fees;
+pub mod fill_sensitivity;
+pub mod fills;
 pub mod gabagool;
+pub mod gabagool_scanner;
+pub mod grid_snapshot;
+pub mod htf_filter;
+#[cfg(test)]
+mod indicator_tests;
 pub mod indicators;
 pub mod leaderboard;
+pub mod local_search;
+pub mod maintenance;
+pub mod manual_backtest;
+pub mod onnx;
 pub mod optimizer;
 pub mod orderbook_backtest;
 pub mod orderbook_collector;
+pub mod pacing;
+pub mod pairs;
+pub mod pipeline;
+pub mod population;
+pub mod probability;
 pub mod profile;
+pub mod resolution;
+pub mod result_stream;
+pub mod risk;
+pub mod rotation;
+pub mod sampling;
+pub mod similarity;
+pub mod sizing;
+pub mod store;
 pub mod strategy;
+#[cfg(feature = "json-schema")]
+pub mod strategy_schema;
+pub mod strategy_stub;
+pub mod strategy_validation;
+pub mod supervisor;
+pub mod symbols;
+pub mod synthetic;
+pub mod trailing_stop;
 pub mod types;
+pub mod validation;
 pub mod watcher;
 pub mod web_strategies;
+pub mod zscore_filter;
 
 // Re-exports for convenience
 pub use api::BinanceClient;
 pub use api::PolymarketDataClient;
+pub use backup::{backup_file_name, create_backup, restore_backup, BackupReport, RestoreReport};
+#[cfg(feature = "charts")]
+pub use chart::{chart_strategy, record_equity_curve, render_chart, ChartFormat, EquityCurve};
 pub use leaderboard::{analyze_leaderboard, LeaderboardProgress, LeaderboardStatus, TraderAnalysis};
 pub use profile::{analyze_profile, ProfileAnalysis, ProfileProgress, ProfileStatus};
 pub use watcher::{run_trade_watcher, TradeAlert, WatcherProgress, WatcherStatus};
+pub use candles::{CandleSignalGenerator, CandleTransform};
+pub use capital::{Allocation, CapitalAllocator, CapitalConfig, CapitalStats, ExhaustedCapital};
+pub use daily_pnl::{aggregate_daily_pnl, daily_pnl_strategy, record_daily_pnl};
+pub use data_quality::{
+    check_klines, diff_klines, fetch_checked_klines, DataQualityConfig, DataQualityReport,
+    KlineDiff, KlineGap, RevisedKline,
+};
+pub use data_source::{infer_interval_ms, load_klines_file, DataSource};
 pub use discovery::{
-    run_continuous_discovery, run_discovery, DiscoveryProgress, DiscoveryRequest, DiscoveryResult,
-    DiscoveryStatus, DiscoveryStrategyType, SizingMode,
+    decode_strategy_params, migrate_strategy_params, run_backtest, run_backtest_with_fees,
+    BacktestLimits, DiscoveryProgress, DiscoveryRequest, DiscoveryResult, DiscoveryStatus,
+    DiscoveryStrategyType, FailedCombo, ParamsMigrationReport, ProgressBreakdown, ProgressEstimate,
+    RefinementDepth, SizingMode, StrategyExclusions, WorkProgress, STRATEGY_PARAMS_VERSION,
+};
+pub use discovery::{is_current_logic, BACKTEST_LOGIC_VERSION, ENGINE_VERSION};
+#[doc(hidden)]
+pub use discovery::{run_continuous_discovery, run_discovery};
+pub use discovery_engine::{DiscoveryEngine, DEFAULT_SYMBOLS};
+pub use distributed::{
+    run_local_worker, ChunkRunner, Coordinator, CoordinatorStatus, GridChunk, WorkerInfo,
 };
 pub use engine::BacktestEngine;
-pub use fees::{calculate_taker_fee, PolymarketFeeConfig};
+pub use ensemble::{build_ensemble, EnsembleBuild, EnsembleMember, EnsembleVote};
+pub use error::{EngineError, EngineResult};
+pub use explain::{explain_record, explain_strategy, StrategyExplanation, StrategyRules};
+pub use maintenance::{
+    duration_until_utc_hour, run_maintenance, MaintenanceProgress, MaintenanceReport,
+    MaintenanceStatus,
+};
+pub use manual_backtest::{run_manual_backtest, ManualBacktest, ManualBacktestRequest};
+pub use entry_filter::EntryFilter;
+pub use features::{compute_features, write_features_parquet, FeatureRow, FeatureTable};
+pub use fee_sensitivity::{fee_sensitivity, FeeScenario, FeeSensitivity, FeeSensitivityReport};
+pub use fees::{calculate_taker_fee, FeePreset, PolymarketFeeConfig};
+pub use fill_sensitivity::{
+    fill_sensitivity, FillScenario, FillSensitivity, FillSensitivityReport,
+};
+pub use fills::{FillModel, ParticipationLimit, UnfilledRemainder, UnfilledStats};
 pub use gabagool::{
-    GabagoolBacktestConfig, GabagoolBacktestEngine, GabagoolBacktestResult, GabagoolWindowResult,
+    GabagoolBacktestConfig, GabagoolBacktestEngine, GabagoolBacktestResult, GabagoolWindow,
+    GabagoolWindowResult,
 };
-pub use indicators::{build_signal_generator, SignalGenerator, SignalWithConfidence};
+pub use gabagool_scanner::{
+    run_gabagool_scanner, GabagoolOpportunity, GabagoolScannerConfig, GabagoolScannerProgress,
+    ScannerStatus,
+};
+pub use grid_snapshot::CycleGrid;
+pub use htf_filter::{HtfFilter, HtfInterval, HtfTrend};
+pub use indicators::stream::{IndicatorStream, IndicatorValue, StreamUpdate};
+pub use indicators::{
+    build_signal_generator, IndicatorSeriesCache, SignalGenerator, SignalWithConfidence,
+};
+pub use onnx::OnnxSignalGenerator;
 pub use optimizer::{
-    run_optimization, OptimizeProgress, OptimizeRequest, OptimizeStatus, OptimizeStrategy,
-    ScoredResult,
+    check_optimization_klines, run_optimization, OptimizeProgress, OptimizeRequest,
+    OptimizeStatus, OptimizeStrategy, ScoredResult, MIN_OPTIMIZATION_KLINES,
+};
+pub use optimizer::{derive_param_grid, optimization_records, MAX_CUSTOM_COMBINATIONS};
+pub use pacing::{Pacer, ScanPacing};
+pub use pairs::{run_pair_spread_backtest, PairBacktest, PairSpreadParams};
+pub use population::{IslandModel, IslandStats, Population};
+pub use risk::{
+    paper_strategy, paper_trade, run_paper_session, run_portfolio_paper_session, PaperSession,
+    PortfolioPaperSession, RiskEvent, RiskLimitKind, RiskLimits, RiskMonitor,
+};
+pub use resolution::{
+    run_resolution_backtest, resolution_backtest, MarketSide, ResolutionBacktest, ResolutionTrade,
+    ResolvedMarket,
+};
+pub use rotation::{run_rotation_backtest, KlineMatrix, RotationBacktest};
+pub use similarity::{
+    rank_similar, similarity, NeighborhoodSummary, Similarity, StrategyProfile,
+};
+pub use store::{
+    MemoryResultStore, ModelScopedStore, NoopResultStore, ResultStore, SqliteResultStore,
 };
 pub use strategy::{RsiStrategy, Signal};
+#[cfg(feature = "json-schema")]
+pub use strategy_schema::{strategy_schemas, StrategySchemas};
+pub use strategy_stub::{record_stub, render_stub, StubTarget};
+pub use supervisor::{last_session_crashes, supervise, RestartPolicy, RunCrash};
+pub use symbols::{SymbolFilters, SymbolMetadata};
+pub use synthetic::{generate_klines, simulate, SimulationReport, SyntheticConfig, SyntheticModel};
+pub use trailing_stop::TrailingStop;
 pub use types::*;
+pub use validation::{
+    drift_report, revalidate, validation_strategy, DecayThresholds, DriftPoint, StrategyDrift,
+};
 pub use web_strategies::{get_catalog, WebStrategyCatalogEntry, WebStrategyId, WebStrategyParams};
+pub use zscore_filter::ZScoreFilter;
 pub use orderbook_backtest::{
     run_orderbook_backtest, DetectedPattern, ObBacktestProgress, ObBacktestStats, ObBacktestStatus,
 };
 pub use orderbook_collector::{
     run_orderbook_collector, CollectorStatus, ObCollectorProgress,
 };
+pub use pipeline::{
+    apply_rules as apply_pipeline_rules, PipelineReport, PipelineRules, PipelineStage,
+    PipelineTransitionRequest,
+};
+pub use probability::{
+    calibrate_logistic, ConstantModel, LinearModel, LogisticCalibration, LogisticModel,
+    ProbabilityModel, ProbabilityModelConfig,
+};
+pub use benchmark::{bench_cases, find_regressions, run_benchmarks, BenchConfig, BenchReport};
+pub use dry_run::{estimate_discovery, DiscoveryEstimate, StrategyTypeCount};
+pub use result_stream::{NdjsonResultSink, ResultSink};
+pub use sizing::{DrawdownThrottle, KellyConfig, KellySizer, PositionSizer};
@@ -0,0 +1,388 @@
+//! Hill-climbing local search after refinement
+//!
+//! Refinement tries a fixed neighbourhood around each top result. The local search
+//! starts from the best result of each strategy family (`type_tag()`) and walks its
+//! numeric parameters one at a time (coordinate descent): a step one way, then the
+//! other; an improving move is kept and its step doubled, a parameter neither way
+//! improves gets half the step. A parameter is settled once its step drops below its
+//! resolution (1 for integers), and the climb ends when every parameter is settled or
+//! `LOCAL_SEARCH_MAX_EVALS` backtests are spent.
+//!
+//! Parameters are the numeric leaves of the strategy's serde params, so wrapped
+//! strategies (filters, trailing stops) and dynamic combos are climbed like the others.
+
+use std::collections::{HashMap, HashSet};
+
+use rust_decimal::Decimal;
+use serde_json::{Number, Value};
+
+use crate::discovery::{score_result, DiscoveryResult, DiscoveryStrategyType};
+
+/// Families climbed by the local search, best first
+pub const LOCAL_SEARCH_TOP: usize = 10;
+
+/// Backtests of one climb at most
+pub const LOCAL_SEARCH_MAX_EVALS: usize = 30;
+
+/// Upper bound of the backtests of the local search
+pub(crate) const LOCAL_SEARCH_COMBOS: u32 = (LOCAL_SEARCH_TOP * LOCAL_SEARCH_MAX_EVALS) as u32;
+
+/// Float steps start at this share of the value, and settle after 3 halvings
+const INITIAL_STEP_RATIO: f64 = 0.1;
+
+/// Smallest value of an integer parameter (periods, lookbacks)
+const MIN_INTEGER: i64 = 2;
+
+/// Best result of each family the search can climb (single-symbol strategies of the
+/// generic backtester that ran to the end), best first, `limit` families at most
+pub(crate) fn local_search_starts(
+    results: &[DiscoveryResult],
+    initial_capital: Decimal,
+    limit: usize,
+) -> Vec<DiscoveryResult> {
+    let mut best: HashMap<&'static str, (Decimal, &DiscoveryResult)> = HashMap::new();
+    for result in results.iter().filter(|r| {
+        r.drawdown_throttle.is_none()
+            && r.abort_reason.is_none()
+            && !r.strategy_type.is_gabagool()
+            && !r.strategy_type.is_cross_sectional()
+    }) {
+        let score = score_result(result, initial_capital);
+        let entry = best
+            .entry(result.strategy_type.type_tag())
+            .or_insert((score, result));
+        if score > entry.0 {
+            *entry = (score, result);
+        }
+    }
+    let mut starts: Vec<(Decimal, &DiscoveryResult)> = best.into_values().collect();
+    starts.sort_by(|a, b| {
+        b.0.cmp(&a.0).then_with(|| {
+            a.1.strategy_type
+                .type_tag()
+                .cmp(b.1.strategy_type.type_tag())
+        })
+    });
+    starts
+        .into_iter()
+        .take(limit)
+        .map(|(_, r)| r.clone())
+        .collect()
+}
+
+/// One numeric parameter of the climbed strategy
+#[derive(Debug, Clone)]
+struct Coordinate {
+    /// JSON pointer of the value in the serialized params
+    pointer: String,
+    integer: bool,
+    /// Float parameters that started positive stay positive
+    positive: bool,
+    step: f64,
+    /// The parameter is settled below this step
+    min_step: f64,
+    /// Direction tried first: the last one that improved
+    direction: f64,
+}
+
+/// Coordinate descent from one result. The caller backtests each `next_candidate()` and
+/// hands the result to `report()`.
+pub struct HillClimber {
+    initial_capital: Decimal,
+    params: Value,
+    best: DiscoveryResult,
+    best_score: Decimal,
+    start_score: Decimal,
+    coordinates: Vec<Coordinate>,
+    /// Coordinate being moved
+    cursor: usize,
+    /// Second direction of the current coordinate being tried
+    reversed: bool,
+    /// Params of the candidate handed out, awaiting its result
+    pending: Option<Value>,
+    /// Params already backtested or rejected
+    tried: HashSet<String>,
+    evaluations: usize,
+    max_evaluations: usize,
+}
+
+impl HillClimber {
+    pub fn new(start: &DiscoveryResult, initial_capital: Decimal, max_evaluations: usize) -> Self {
+        let params = serde_json::to_value(&start.strategy_type).unwrap_or(Value::Null);
+        let coordinates = collect_coordinates(&params);
+        let mut tried = HashSet::new();
+        tried.insert(params.to_string());
+        let score = score_result(start, initial_capital);
+        Self {
+            initial_capital,
+            params,
+            best: start.clone(),
+            best_score: score,
+            start_score: score,
+            coordinates,
+            cursor: 0,
+            reversed: false,
+            pending: None,
+            tried,
+            evaluations: 0,
+            max_evaluations,
+        }
+    }
+
+    /// Next params to backtest; `None` once every parameter is settled or the budget
+    /// is spent
+    pub fn next_candidate(&mut self) -> Option<DiscoveryStrategyType> {
+        while self.evaluations < self.max_evaluations {
+            if self.coordinates.is_empty() {
+                return None;
+            }
+            let coordinate = &self.coordinates[self.cursor];
+            let sign = if self.reversed { -1.0 } else { 1.0 };
+            let candidate = moved(&self.params, coordinate, sign * coordinate.direction);
+            let strategy = candidate
+                .filter(|c| self.tried.insert(c.to_string()))
+                .and_then(|c| {
+                    let strategy: DiscoveryStrategyType = serde_json::from_value(c.clone()).ok()?;
+                    strategy.validate().ok()?;
+                    Some((c, strategy))
+                });
+            match strategy {
+                Some((params, strategy)) => {
+                    self.pending = Some(params);
+                    self.evaluations += 1;
+                    return Some(strategy);
+                }
+                None => self.reject(),
+            }
+        }
+        None
+    }
+
+    /// Result of the last candidate
+    pub fn report(&mut self, result: &DiscoveryResult) {
+        let Some(params) = self.pending.take() else {
+            return;
+        };
+        let score = score_result(result, self.initial_capital);
+        if score > self.best_score && result.abort_reason.is_none() {
+            let coordinate = &mut self.coordinates[self.cursor];
+            if self.reversed {
+                coordinate.direction = -coordinate.direction;
+            }
+            coordinate.step *= 2.0;
+            self.params = params;
+            self.best = result.clone();
+            self.best_score = score;
+            self.reversed = false;
+        } else {
+            self.reject();
+        }
+    }
+
+    /// The candidate could not be backtested: counted as a move that did not improve
+    pub fn report_failure(&mut self) {
+        if self.pending.take().is_some() {
+            self.reject();
+        }
+    }
+
+    /// The current move did not improve: try the other direction, or shrink the step
+    /// and go to the next unsettled coordinate
+    fn reject(&mut self) {
+        if !self.reversed {
+            self.reversed = true;
+            return;
+        }
+        self.reversed = false;
+        let coordinate = &mut self.coordinates[self.cursor];
+        coordinate.step /= 2.0;
+        if coordinate.step < coordinate.min_step {
+            self.coordinates.remove(self.cursor);
+        } else {
+            self.cursor += 1;
+        }
+        if self.cursor >= self.coordinates.len() {
+            self.cursor = 0;
+        }
+    }
+
+    pub fn best(&self) -> &DiscoveryResult {
+        &self.best
+    }
+
+    /// Score gained over the starting result
+    pub fn improvement(&self) -> Decimal {
+        self.best_score - self.start_score
+    }
+
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+}
+
+/// Numeric leaves of `value` under `pointer`, as (JSON pointer, number)
+pub(crate) fn numeric_leaves<'a>(
+    value: &'a Value,
+    pointer: String,
+    out: &mut Vec<(String, &'a Number)>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                numeric_leaves(child, format!("{}/{}", pointer, key), out);
+            }
+        }
+        Value::Array(items) => {
+            for (idx, child) in items.iter().enumerate() {
+                numeric_leaves(child, format!("{}/{}", pointer, idx), out);
+            }
+        }
+        Value::Number(n) => out.push((pointer, n)),
+        _ => {}
+    }
+}
+
+/// One coordinate per numeric leaf of `params`
+fn collect_coordinates(params: &Value) -> Vec<Coordinate> {
+    let mut leaves = Vec::new();
+    numeric_leaves(params, String::new(), &mut leaves);
+    leaves
+        .into_iter()
+        .filter_map(|(pointer, n)| {
+            if let Some(int) = n.as_i64().filter(|_| !n.is_f64()) {
+                Some(Coordinate {
+                    pointer,
+                    integer: true,
+                    positive: true,
+                    step: (int.abs() as f64 * INITIAL_STEP_RATIO * 2.0)
+                        .round()
+                        .max(1.0),
+                    min_step: 1.0,
+                    direction: 1.0,
+                })
+            } else {
+                let float = n.as_f64()?;
+                let step = if float == 0.0 {
+                    INITIAL_STEP_RATIO
+                } else {
+                    float.abs() * INITIAL_STEP_RATIO
+                };
+                Some(Coordinate {
+                    pointer,
+                    integer: false,
+                    positive: float > 0.0,
+                    step,
+                    min_step: step / 8.0,
+                    direction: 1.0,
+                })
+            }
+        })
+        .collect()
+}
+
+/// `params` with the coordinate moved one step in `direction`; `None` when the move
+/// leaves the parameter's range
+fn moved(params: &Value, coordinate: &Coordinate, direction: f64) -> Option<Value> {
+    let mut params = params.clone();
+    let value = params.pointer_mut(&coordinate.pointer)?;
+    let current = value.as_f64()?;
+    let next = current + direction * coordinate.step;
+    *value = if coordinate.integer {
+        let next = next.round() as i64;
+        if next < MIN_INTEGER {
+            return None;
+        }
+        Value::from(next)
+    } else {
+        if coordinate.positive && next <= 0.0 {
+            return None;
+        }
+        // Keep the params (and their hash) readable
+        Value::from((next * 1e6).round() / 1e6)
+    };
+    Some(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    use crate::discovery::{run_backtest, BacktestLimits, SizingMode};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_hill_climber_improves_and_converges() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 3_000,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let run = |s: &DiscoveryStrategyType| {
+            run_backtest(
+                s,
+                &klines,
+                "SYNTH",
+                SizingMode::Fixed,
+                &BacktestLimits::default(),
+            )
+        };
+        let start = run(&DiscoveryStrategyType::Rsi {
+            period: 30,
+            overbought: 60.0,
+            oversold: 40.0,
+        });
+
+        let mut climber = HillClimber::new(&start, dec!(10000), 200);
+        assert_eq!(climber.coordinates.len(), 3);
+        let mut seen = HashSet::new();
+        while let Some(candidate) = climber.next_candidate() {
+            // Every candidate is new and stays in range
+            assert!(seen.insert(serde_json::to_string(&candidate).unwrap()));
+            let DiscoveryStrategyType::Rsi {
+                period, oversold, ..
+            } = candidate
+            else {
+                panic!("climbed out of the family");
+            };
+            assert!(period >= MIN_INTEGER as usize && oversold > 0.0);
+            climber.report(&run(&candidate));
+        }
+        // Settled before the budget, better than the start
+        assert!(climber.evaluations() < 200);
+        assert!(climber.coordinates.is_empty());
+        assert!(climber.improvement() > Decimal::ZERO);
+        assert_eq!(
+            score_result(climber.best(), dec!(10000)) - score_result(&start, dec!(10000)),
+            climber.improvement()
+        );
+
+        // The budget caps the climb
+        let mut capped = HillClimber::new(&start, dec!(10000), 4);
+        let mut evaluations = 0;
+        while let Some(candidate) = capped.next_candidate() {
+            capped.report(&run(&candidate));
+            evaluations += 1;
+        }
+        assert_eq!(evaluations, 4);
+
+        // One start per family, the better one
+        let better = DiscoveryResult {
+            net_pnl: start.net_pnl + dec!(1000),
+            total_trades: start.total_trades.max(5),
+            ..start.clone()
+        };
+        let macd = run(&DiscoveryStrategyType::Macd {
+            fast: 12,
+            slow: 26,
+            signal: 9,
+        });
+        let starts = local_search_starts(
+            &[start.clone(), better.clone(), macd],
+            dec!(10000),
+            LOCAL_SEARCH_TOP,
+        );
+        assert_eq!(starts.len(), 2);
+        let rsi = starts.iter().find(|r| r.strategy_type.type_tag() == "rsi");
+        assert_eq!(rsi.unwrap().net_pnl, better.net_pnl);
+    }
+}
@@ -0,0 +1,271 @@
+//! Knowledge base maintenance — cleanup, ANALYZE and incremental VACUUM
+//!
+//! Runs the same cleanup as the `cleanup` CLI command, but online: the server keeps
+//! serving requests while the steps execute, and progress is exposed for polling.
+//! Can be triggered manually (API) or scheduled nightly.
+
+use chrono::{DateTime, Duration, Utc};
+use persistence::repository::discovery::DiscoveryRepository;
+use persistence::SqlitePool;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::RwLock;
+use tracing::{error, info};
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum MaintenanceStatus {
+    Idle,
+    CleaningUp,
+    Analyzing,
+    Vacuuming,
+    Complete,
+    Error,
+}
+
+/// Summary of a completed maintenance run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MaintenanceReport {
+    /// "manual" or "scheduled"
+    pub trigger: String,
+    /// Best results kept per strategy_name
+    pub keep: i64,
+    /// Workspace cleaned up (`None` = each workspace in turn)
+    pub workspace: Option<String>,
+    pub deleted: u64,
+    pub remaining: i64,
+    pub freed_pages: i64,
+    /// True if the DB was switched to auto_vacuum=INCREMENTAL with a full VACUUM
+    pub full_vacuum: bool,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub duration_ms: i64,
+}
+
+// ---------------------------------------------------------------------------
+// Progress tracking
+// ---------------------------------------------------------------------------
+
+pub struct MaintenanceProgress {
+    pub status: RwLock<MaintenanceStatus>,
+    pub total_steps: AtomicU32,
+    pub completed_steps: AtomicU32,
+    pub current_step: RwLock<String>,
+    pub trigger: RwLock<String>,
+    /// Report of the last successful run (kept across runs for the status endpoint)
+    pub last_report: RwLock<Option<MaintenanceReport>>,
+    pub error_message: RwLock<Option<String>>,
+}
+
+impl MaintenanceProgress {
+    pub fn new() -> Self {
+        Self {
+            status: RwLock::new(MaintenanceStatus::Idle),
+            total_steps: AtomicU32::new(3),
+            completed_steps: AtomicU32::new(0),
+            current_step: RwLock::new(String::new()),
+            trigger: RwLock::new(String::new()),
+            last_report: RwLock::new(None),
+            error_message: RwLock::new(None),
+        }
+    }
+
+    pub fn reset(&self, trigger: &str) {
+        *self.status.write().unwrap() = MaintenanceStatus::CleaningUp;
+        self.total_steps.store(3, Ordering::Relaxed);
+        self.completed_steps.store(0, Ordering::Relaxed);
+        *self.current_step.write().unwrap() = "Starting maintenance...".to_string();
+        *self.trigger.write().unwrap() = trigger.to_string();
+        *self.error_message.write().unwrap() = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        let status = self.status.read().unwrap();
+        !matches!(
+            *status,
+            MaintenanceStatus::Idle | MaintenanceStatus::Complete | MaintenanceStatus::Error
+        )
+    }
+
+    fn set_step(&self, status: MaintenanceStatus, step_name: &str) {
+        *self.status.write().unwrap() = status;
+        *self.current_step.write().unwrap() = step_name.to_string();
+    }
+
+    fn advance(&self) {
+        self.completed_steps.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn set_error(&self, msg: String) {
+        *self.status.write().unwrap() = MaintenanceStatus::Error;
+        *self.error_message.write().unwrap() = Some(msg);
+    }
+}
+
+impl Default for MaintenanceProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Runner
+// ---------------------------------------------------------------------------
+
+/// Run cleanup (keep top `keep` per strategy) → ANALYZE → incremental VACUUM.
+///
+/// The cleanup only touches `workspace`, or every workspace separately (each keeps its
+/// own top `keep`) when `None`. ANALYZE and VACUUM always cover the whole database.
+/// The caller is expected to have called `progress.reset()` beforehand.
+pub async fn run_maintenance(
+    pool: &SqlitePool,
+    keep: i64,
+    workspace: Option<&str>,
+    progress: &MaintenanceProgress,
+) {
+    let started = Utc::now();
+    let trigger = progress.trigger.read().unwrap().clone();
+    let repo = DiscoveryRepository::new(pool);
+
+    info!(keep, trigger = %trigger, workspace, "Starting knowledge base maintenance");
+
+    // Step 1: cleanup
+    progress.set_step(
+        MaintenanceStatus::CleaningUp,
+        &format!("Keeping top {} per strategy...", keep),
+    );
+    let workspaces = match workspace {
+        Some(ws) => vec![ws.to_string()],
+        None => match repo.list_workspaces().await {
+            Ok(list) => list,
+            Err(e) => {
+                error!("Maintenance cleanup failed: {}", e);
+                progress.set_error(format!("Cleanup failed: {}", e));
+                return;
+            }
+        },
+    };
+    let (mut deleted, mut remaining) = (0, 0);
+    for ws in &workspaces {
+        match DiscoveryRepository::new(pool)
+            .with_workspace(ws)
+            .cleanup_keep_top_n(keep)
+            .await
+        {
+            Ok((d, r)) => {
+                deleted += d;
+                remaining += r;
+            }
+            Err(e) => {
+                error!(workspace = %ws, "Maintenance cleanup failed: {}", e);
+                progress.set_error(format!("Cleanup failed: {}", e));
+                return;
+            }
+        }
+    }
+    progress.advance();
+
+    // Step 2: ANALYZE
+    progress.set_step(
+        MaintenanceStatus::Analyzing,
+        "Refreshing query planner statistics...",
+    );
+    if let Err(e) = repo.analyze().await {
+        error!("Maintenance ANALYZE failed: {}", e);
+        progress.set_error(format!("ANALYZE failed: {}", e));
+        return;
+    }
+    progress.advance();
+
+    // Step 3: incremental VACUUM
+    progress.set_step(MaintenanceStatus::Vacuuming, "Reclaiming free pages...");
+    let vacuum = match repo.incremental_vacuum().await {
+        Ok(v) => v,
+        Err(e) => {
+            error!("Maintenance VACUUM failed: {}", e);
+            progress.set_error(format!("VACUUM failed: {}", e));
+            return;
+        }
+    };
+    progress.advance();
+
+    let finished = Utc::now();
+    let report = MaintenanceReport {
+        trigger,
+        keep,
+        workspace: workspace.map(String::from),
+        deleted,
+        remaining,
+        freed_pages: vacuum.freed_pages,
+        full_vacuum: vacuum.full_vacuum,
+        started_at: started.timestamp(),
+        finished_at: finished.timestamp(),
+        duration_ms: (finished - started).num_milliseconds(),
+    };
+
+    info!(
+        deleted,
+        remaining,
+        freed_pages = report.freed_pages,
+        full_vacuum = report.full_vacuum,
+        duration_ms = report.duration_ms,
+        "Knowledge base maintenance complete"
+    );
+
+    *progress.last_report.write().unwrap() = Some(report);
+    progress.set_step(MaintenanceStatus::Complete, "Done");
+}
+
+/// Time to wait from `now` until the next occurrence of `hour`:00 UTC
+pub fn duration_until_utc_hour(now: DateTime<Utc>, hour: u32) -> std::time::Duration {
+    let today = now
+        .date_naive()
+        .and_hms_opt(hour.min(23), 0, 0)
+        .expect("valid hour")
+        .and_utc();
+    let next = if today > now {
+        today
+    } else {
+        today + Duration::days(1)
+    };
+    (next - now).to_std().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_progress_reset_and_running() {
+        let progress = MaintenanceProgress::new();
+        assert!(!progress.is_running());
+
+        progress.reset("manual");
+        assert!(progress.is_running());
+        assert_eq!(*progress.trigger.read().unwrap(), "manual");
+        assert_eq!(progress.completed_steps.load(Ordering::Relaxed), 0);
+
+        progress.set_error("boom".to_string());
+        assert!(!progress.is_running());
+        assert_eq!(*progress.status.read().unwrap(), MaintenanceStatus::Error);
+    }
+
+    #[test]
+    fn test_duration_until_utc_hour() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 22, 30, 0).unwrap();
+        // Later today
+        assert_eq!(duration_until_utc_hour(now, 23).as_secs(), 30 * 60);
+        // Already passed today → tomorrow
+        assert_eq!(
+            duration_until_utc_hour(now, 3).as_secs(),
+            (4 * 60 + 30) * 60
+        );
+        // Exactly on the hour → next day
+        let on_hour = Utc.with_ymd_and_hms(2026, 3, 1, 3, 0, 0).unwrap();
+        assert_eq!(duration_until_utc_hour(on_hour, 3).as_secs(), 24 * 3600);
+    }
+}
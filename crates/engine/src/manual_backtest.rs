@@ -0,0 +1,222 @@
+//! One-off backtests of hand-written strategies
+//!
+//! A manual experiment sends a full `DiscoveryStrategyType` with a symbol and a window.
+//! It is backtested the way discovery does it (same limits, fees, scoring and
+//! `params_hash`), so a saved result dedups against the discovery results of the same
+//! parameters. Saved records carry the `manual` phase and belong to no discovery run.
+
+use anyhow::bail;
+use persistence::repository::DiscoveryBacktestRecord;
+use serde::{Deserialize, Serialize};
+
+use crate::api::BinanceClient;
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::discovery::{
+    compute_params_hash, result_to_record, run_backtest_with_fees, BacktestLimits, DiscoveryResult,
+    DiscoveryStrategyType, SizingMode,
+};
+use crate::ensemble::MAX_MEMBERS;
+use crate::fees::PolymarketFeeConfig;
+use crate::fills::FillModel;
+use crate::store::ResultStore;
+use crate::types::Kline;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Phase of the knowledge base records saved from a manual backtest
+pub const MANUAL_PHASE: &str = "manual";
+
+/// Longest window of a manual backtest, in days
+pub const MAX_MANUAL_DAYS: u32 = 365;
+
+/// A strategy to backtest once on the klines of `symbol` ending now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ManualBacktestRequest {
+    /// Tagged like the stored `strategy_params`, e.g. `{"type": "rsi", "period": 14, ...}`
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub strategy: DiscoveryStrategyType,
+    pub symbol: String,
+    #[serde(default = "default_manual_days")]
+    pub days: u32,
+    pub sizing_mode: Option<SizingMode>,
+    pub fill_model: Option<FillModel>,
+    /// Store the result in the knowledge base
+    #[serde(default)]
+    pub save: bool,
+}
+
+fn default_manual_days() -> u32 {
+    90
+}
+
+impl ManualBacktestRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.symbol.trim().is_empty() {
+            return Err("symbol is required".to_string());
+        }
+        if !(1..=MAX_MANUAL_DAYS).contains(&self.days) {
+            return Err(format!("days must be between 1 and {}", MAX_MANUAL_DAYS));
+        }
+        if self.strategy.is_cross_sectional() {
+            return Err(format!(
+                "{} ranks a basket of symbols and cannot be backtested on one",
+                self.strategy.name()
+            ));
+        }
+        if let DiscoveryStrategyType::Ensemble {
+            members, weights, ..
+        } = &self.strategy
+        {
+            if !(2..=MAX_MEMBERS).contains(&members.len()) || weights.len() != members.len() {
+                return Err(format!(
+                    "an ensemble needs 2 to {} members and one weight per member",
+                    MAX_MEMBERS
+                ));
+            }
+            if members.iter().any(|m| m.is_ensemble() || m.is_gabagool()) {
+                return Err("ensemble members cannot be ensembles or Gabagool".to_string());
+            }
+        }
+        self.strategy.validate()
+    }
+}
+
+/// Outcome of a manual backtest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ManualBacktest {
+    pub result: DiscoveryResult,
+    pub params_hash: String,
+    /// Knowledge base record of `params_hash`: the new one when `created`, else the
+    /// one already stored (discovery or manual), if any
+    pub record: Option<DiscoveryBacktestRecord>,
+    /// The result was saved (false when not asked or the hash was already known)
+    pub created: bool,
+}
+
+/// Backtest `request.strategy` on `klines` and build its unsaved record
+pub fn manual_backtest_record(
+    request: &ManualBacktestRequest,
+    klines: &[Kline],
+    limits: &BacktestLimits,
+) -> (DiscoveryResult, DiscoveryBacktestRecord) {
+    let sizing_mode = request.sizing_mode.unwrap_or_default();
+    let result = run_backtest_with_fees(
+        &request.strategy,
+        klines,
+        &request.symbol,
+        sizing_mode,
+        limits,
+        &PolymarketFeeConfig::default(),
+    );
+    let hash = compute_params_hash(
+        &request.strategy,
+        &request.symbol,
+        request.days,
+        sizing_mode,
+    );
+    let record = DiscoveryBacktestRecord {
+        discovery_run_id: None,
+        ..result_to_record(&result, &hash, "", MANUAL_PHASE, request.days)
+    };
+    (result, record)
+}
+
+/// Run `request` on fresh 15m klines, saving it to `store` when asked
+pub async fn run_manual_backtest(
+    request: &ManualBacktestRequest,
+    binance: &BinanceClient,
+    store: &dyn ResultStore,
+) -> anyhow::Result<ManualBacktest> {
+    let end_time = chrono::Utc::now().timestamp_millis();
+    let (klines, quality) = fetch_checked_klines(
+        binance,
+        &request.symbol,
+        "15m",
+        end_time - request.days as i64 * DAY_MS,
+        end_time,
+        &DataQualityConfig::default(),
+    )
+    .await?;
+    quality.warn_if_degraded();
+    if klines.is_empty() {
+        bail!("No klines for {}", request.symbol);
+    }
+    let filters = binance.symbol_filters(&request.symbol).await.ok();
+    let limits = BacktestLimits::default()
+        .with_fill_model(request.fill_model.unwrap_or_default())
+        .with_symbol_filters(filters);
+
+    let (result, record) = manual_backtest_record(request, &klines, &limits);
+    let params_hash = record.params_hash.clone();
+    let (record, created) = if request.save {
+        let (record, created) = store.save_new(record).await?;
+        (Some(record), created)
+    } else {
+        (store.get_by_hash(&params_hash).await?, false)
+    };
+    Ok(ManualBacktest {
+        result,
+        params_hash,
+        record,
+        created,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryResultStore;
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[tokio::test]
+    async fn test_manual_backtest_shares_discovery_hash() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 2_000,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let request: ManualBacktestRequest = serde_json::from_str(
+            r#"{"strategy": {"type": "rsi", "period": 14, "overbought": 70.0, "oversold": 30.0},
+                "symbol": "BTCUSDT", "save": true}"#,
+        )
+        .unwrap();
+        assert_eq!(request.days, 90);
+        request.validate().unwrap();
+
+        let (result, record) =
+            manual_backtest_record(&request, &klines, &BacktestLimits::unlimited());
+        assert_eq!(result.strategy_name, request.strategy.name());
+        assert_eq!(record.phase.as_deref(), Some(MANUAL_PHASE));
+        assert_eq!(record.discovery_run_id, None);
+        assert_eq!(
+            record.params_hash,
+            compute_params_hash(&request.strategy, "BTCUSDT", 90, SizingMode::Fixed)
+        );
+
+        let store = MemoryResultStore::new();
+        assert!(store.save_new(record.clone()).await.unwrap().1);
+        assert!(!store.save_new(record).await.unwrap().1);
+
+        let invalid = |strategy: DiscoveryStrategyType, days| ManualBacktestRequest {
+            strategy,
+            days,
+            ..request.clone()
+        };
+        assert!(invalid(request.strategy.clone(), 0).validate().is_err());
+        let ensemble = DiscoveryStrategyType::Ensemble {
+            members: vec![request.strategy.clone()],
+            weights: vec![1.0],
+            vote: Default::default(),
+        };
+        assert!(invalid(ensemble, 30).validate().is_err());
+        let fast_above_slow = DiscoveryStrategyType::EmaCrossover {
+            fast_period: 30,
+            slow_period: 10,
+        };
+        assert!(invalid(fast_above_slow, 30)
+            .validate()
+            .unwrap_err()
+            .contains("fast period (30)"));
+    }
+}
@@ -0,0 +1,284 @@
+//! ONNX model-based signal generator
+//!
+//! Runs a user-supplied ONNX classifier on the current indicator snapshot (the same
+//! columns as the `features` Parquet export, in the same order) and trades its
+//! prediction. Model contract:
+//! - one float32 input of shape `[1, N]`, N = number of indicator columns;
+//! - a float32 probability output of shape `[1, 3]` (`[sell, hold, buy]`) or `[1, 2]`
+//!   (`[down, up]`). Other outputs (e.g. a predicted label) are ignored.
+//!
+//! The class with the highest probability wins if it reaches `threshold`; its
+//! probability is the signal confidence. Models are loaded once per path and shared.
+
+use anyhow::{bail, Context};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::warn;
+use tract_onnx::prelude::*;
+
+use crate::features::{IndicatorSnapshot, FEATURE_WARMUP_BARS};
+use crate::indicators::{SignalGenerator, SignalWithConfidence};
+use crate::types::Kline;
+
+type OnnxPlan = TypedRunnableModel<TypedModel>;
+
+/// Loaded models by path. A model that failed to load is cached as `None` so the
+/// error is logged once, not on every backtest.
+static MODELS: OnceLock<Mutex<HashMap<String, Option<Arc<OnnxPlan>>>>> = OnceLock::new();
+
+fn load_model(path: &str, n_features: usize) -> anyhow::Result<OnnxPlan> {
+    if !Path::new(path).exists() {
+        bail!("{} does not exist", path);
+    }
+    let plan = tract_onnx::onnx()
+        .model_for_path(path)
+        .with_context(|| format!("cannot parse ONNX model {}", path))?
+        .with_input_fact(0, f32::fact([1, n_features]).into())?
+        .into_optimized()?
+        .into_runnable()?;
+    Ok(plan)
+}
+
+fn cached_model(path: &str, n_features: usize) -> Option<Arc<OnnxPlan>> {
+    let models = MODELS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut map = models.lock().unwrap();
+    map.entry(path.to_string())
+        .or_insert_with(|| match load_model(path, n_features) {
+            Ok(plan) => Some(Arc::new(plan)),
+            Err(e) => {
+                warn!(model = %path, error = %e, "ONNX model unavailable, strategy will hold");
+                None
+            }
+        })
+        .clone()
+}
+
+/// `[sell, hold, buy]` probabilities from the first 2- or 3-class float output
+fn class_probabilities(outputs: &[TValue]) -> Option<[f64; 3]> {
+    outputs
+        .iter()
+        .find_map(|t| match t.as_slice::<f32>().ok()? {
+            [down, up] => Some([*down as f64, 0.0, *up as f64]),
+            [sell, hold, buy] => Some([*sell as f64, *hold as f64, *buy as f64]),
+            _ => None,
+        })
+}
+
+pub struct OnnxSignalGenerator {
+    model_path: String,
+    threshold: f64,
+    snapshot: IndicatorSnapshot,
+    model: Option<Arc<OnnxPlan>>,
+}
+
+impl OnnxSignalGenerator {
+    pub fn new(model_path: &str, threshold: f64) -> Self {
+        let snapshot = IndicatorSnapshot::new();
+        let model = cached_model(model_path, snapshot.columns().len());
+        Self {
+            model_path: model_path.to_string(),
+            threshold,
+            snapshot,
+            model,
+        }
+    }
+
+    fn predict(&self, model: &OnnxPlan) -> anyhow::Result<Option<[f64; 3]>> {
+        let values: Vec<f32> = self.snapshot.values().iter().map(|v| *v as f32).collect();
+        let input = Tensor::from_shape(&[1, values.len()], &values)?;
+        let outputs = model.run(tvec!(input.into()))?;
+        Ok(class_probabilities(&outputs))
+    }
+}
+
+impl SignalGenerator for OnnxSignalGenerator {
+    fn name(&self) -> &str {
+        "ONNX"
+    }
+
+    fn warmup_bars(&self) -> usize {
+        FEATURE_WARMUP_BARS
+    }
+
+    fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
+        self.snapshot.on_bar(kline);
+        let Some(model) = self.model.clone() else {
+            return SignalWithConfidence::hold();
+        };
+        if !self.snapshot.is_warm() {
+            return SignalWithConfidence::hold();
+        }
+
+        match self.predict(&model) {
+            Ok(Some([sell, hold, buy])) if buy >= self.threshold && buy > sell.max(hold) => {
+                SignalWithConfidence::buy(buy)
+            }
+            Ok(Some([sell, hold, buy])) if sell >= self.threshold && sell > buy.max(hold) => {
+                SignalWithConfidence::sell(sell)
+            }
+            Ok(_) => SignalWithConfidence::hold(),
+            Err(e) => {
+                warn!(model = %self.model_path, error = %e, "ONNX inference failed");
+                SignalWithConfidence::hold()
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.snapshot.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Signal;
+    use prost::Message;
+    use rust_decimal::Decimal;
+    use tract_onnx::pb::{
+        tensor_shape_proto::{dimension, Dimension},
+        type_proto, GraphProto, ModelProto, NodeProto, OperatorSetIdProto, TensorProto,
+        TensorShapeProto, TypeProto, ValueInfoProto,
+    };
+
+    const FLOAT: i32 = 1;
+
+    fn value_info(name: &str, dims: &[i64]) -> ValueInfoProto {
+        ValueInfoProto {
+            name: name.to_string(),
+            r#type: Some(TypeProto {
+                value: Some(type_proto::Value::TensorType(type_proto::Tensor {
+                    elem_type: FLOAT,
+                    shape: Some(TensorShapeProto {
+                        dim: dims
+                            .iter()
+                            .map(|d| Dimension {
+                                value: Some(dimension::Value::DimValue(*d)),
+                                ..Default::default()
+                            })
+                            .collect(),
+                    }),
+                })),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// `softmax(X · 0 + bias)`: constant class probabilities whatever the features
+    fn constant_model(path: &Path, n_features: usize, bias: &[f32]) {
+        let node = |op: &str, inputs: &[&str], output: &str| NodeProto {
+            op_type: op.to_string(),
+            input: inputs.iter().map(|s| s.to_string()).collect(),
+            output: vec![output.to_string()],
+            ..Default::default()
+        };
+        let classes = bias.len() as i64;
+        let model = ModelProto {
+            ir_version: 7,
+            opset_import: vec![OperatorSetIdProto {
+                domain: String::new(),
+                version: 13,
+            }],
+            graph: Some(GraphProto {
+                name: "test".to_string(),
+                node: vec![
+                    node("MatMul", &["X", "W"], "XW"),
+                    node("Add", &["XW", "B"], "logits"),
+                    node("Softmax", &["logits"], "probabilities"),
+                ],
+                initializer: vec![
+                    TensorProto {
+                        name: "W".to_string(),
+                        dims: vec![n_features as i64, classes],
+                        data_type: FLOAT,
+                        float_data: vec![0.0; n_features * bias.len()],
+                        ..Default::default()
+                    },
+                    TensorProto {
+                        name: "B".to_string(),
+                        dims: vec![classes],
+                        data_type: FLOAT,
+                        float_data: bias.to_vec(),
+                        ..Default::default()
+                    },
+                ],
+                input: vec![value_info("X", &[1, n_features as i64])],
+                output: vec![value_info("probabilities", &[1, classes])],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        std::fs::write(path, model.encode_to_vec()).unwrap();
+    }
+
+    fn klines(n: usize) -> Vec<Kline> {
+        (0..n)
+            .map(|i| {
+                let close = Decimal::from(100 + (i % 10) as i64);
+                Kline {
+                    open_time: i as i64 * 900_000,
+                    open: close,
+                    high: close + Decimal::ONE,
+                    low: close - Decimal::ONE,
+                    close,
+                    volume: Decimal::from(10),
+                    close_time: i as i64 * 900_000 + 899_999,
+                }
+            })
+            .collect()
+    }
+
+    fn last_signals(generator: &mut OnnxSignalGenerator) -> Vec<SignalWithConfidence> {
+        klines(FEATURE_WARMUP_BARS + 5)
+            .iter()
+            .map(|k| generator.on_bar(k))
+            .collect()
+    }
+
+    #[test]
+    fn test_onnx_generator_follows_class_probabilities() {
+        let dir = std::env::temp_dir().join(format!("poly-onnx-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let n_features = IndicatorSnapshot::new().columns().len();
+
+        // 3 classes, buy dominant: e^3 / (1 + 1 + e^3) ≈ 0.91
+        let buy_path = dir.join("buy.onnx");
+        constant_model(&buy_path, n_features, &[0.0, 0.0, 3.0]);
+        let mut generator = OnnxSignalGenerator::new(buy_path.to_str().unwrap(), 0.6);
+        let signals = last_signals(&mut generator);
+        assert!(signals[..FEATURE_WARMUP_BARS]
+            .iter()
+            .all(|s| s.signal == Signal::Hold));
+        let last = signals.last().unwrap();
+        assert_eq!(last.signal, Signal::Buy);
+        assert!((last.confidence - 0.9094).abs() < 1e-3);
+
+        // Same model, threshold above the probability → hold
+        let mut strict = OnnxSignalGenerator::new(buy_path.to_str().unwrap(), 0.95);
+        assert_eq!(
+            last_signals(&mut strict).last().unwrap().signal,
+            Signal::Hold
+        );
+
+        // 2 classes [down, up], down dominant → sell
+        let sell_path = dir.join("sell.onnx");
+        constant_model(&sell_path, n_features, &[2.0, 0.0]);
+        let mut generator = OnnxSignalGenerator::new(sell_path.to_str().unwrap(), 0.55);
+        assert_eq!(
+            last_signals(&mut generator).last().unwrap().signal,
+            Signal::Sell
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_model_holds() {
+        let mut generator = OnnxSignalGenerator::new("/nonexistent/model.onnx", 0.5);
+        assert!(last_signals(&mut generator)
+            .iter()
+            .all(|s| s.signal == Signal::Hold));
+    }
+}
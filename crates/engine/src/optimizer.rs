@@ -2,21 +2,39 @@
 //!
 //! Runs a grid search over parameter combinations, applies Polymarket fees,
 //! scores results with a composite metric, and returns the top N configurations.
-
+//!
+//! The named strategies have hand-made grids. A `custom` strategy is any serialized
+//! `DiscoveryStrategyType` (a knowledge base record's params, a dynamic combo, a
+//! filtered or trailing-stop wrapper): its grid is derived from the numeric leaves of
+//! its params and each combination runs through the discovery backtester.
+
+use persistence::repository::OptimizationRecord;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
 use std::sync::{
     atomic::{AtomicBool, AtomicU32, Ordering},
     Arc, RwLock,
 };
 use tracing::{info, warn};
 
-use crate::discovery::DiscoveryStrategyType;
+use crate::discovery::{
+    dec_to_f64, run_backtest_with_fees, BacktestLimits, DiscoveryStrategyType, SizingMode,
+    VwapAnchor,
+};
 use crate::engine::BacktestEngine;
+use crate::error::{EngineError, EngineResult};
 use crate::fees::{calculate_taker_fee, PolymarketFeeConfig};
-use crate::gabagool::{GabagoolBacktestConfig, GabagoolBacktestEngine, GabagoolBacktestResult};
+use crate::gabagool::{
+    GabagoolBacktestConfig, GabagoolBacktestEngine, GabagoolBacktestResult, GabagoolWindow,
+};
 use crate::indicators::build_signal_generator;
+use crate::sampling::latin_hypercube;
+use crate::strategy_validation::retain_valid;
 use crate::types::{BacktestConfig, BacktestResult, Kline};
 
 // ============================================================================
@@ -25,6 +43,7 @@ use crate::types::{BacktestConfig, BacktestResult, Kline};
 
 /// Strategy type for optimization
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum OptimizeStrategy {
     Rsi,
@@ -38,6 +57,10 @@ pub enum OptimizeStrategy {
     WilliamsR,
     Adx,
     Gabagool,
+    /// Any single-symbol discovery strategy, e.g. `{"custom": {"type": "rsi", ...}}`;
+    /// its grid is derived from its params (`derive_param_grid()`)
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    Custom(Box<DiscoveryStrategyType>),
 }
 
 impl std::fmt::Display for OptimizeStrategy {
@@ -54,18 +77,42 @@ impl std::fmt::Display for OptimizeStrategy {
             OptimizeStrategy::WilliamsR => write!(f, "Williams %R"),
             OptimizeStrategy::Adx => write!(f, "ADX"),
             OptimizeStrategy::Gabagool => write!(f, "Gabagool"),
+            OptimizeStrategy::Custom(strategy) => write!(f, "Custom {}", strategy.name()),
         }
     }
 }
 
 /// Request to start an optimization
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct OptimizeRequest {
     pub strategy: OptimizeStrategy,
     pub symbol: String,
     pub days: u32,
     /// Number of top results to return (default 2)
     pub top_n: Option<usize>,
+    /// Gabagool market window (default 15m)
+    #[serde(default)]
+    pub gabagool_window: Option<GabagoolWindow>,
+    /// Knowledge base record the sweep starts from; its results are linked to it
+    #[serde(default)]
+    pub source_record_id: Option<i64>,
+}
+
+impl OptimizeRequest {
+    /// `InvalidParams` for a custom strategy the optimizer cannot run on one symbol
+    /// (cross-sectional strategies need a basket)
+    pub fn validate(&self) -> EngineResult<()> {
+        if let OptimizeStrategy::Custom(strategy) = &self.strategy {
+            if strategy.is_cross_sectional() {
+                return Err(EngineError::InvalidParams(format!(
+                    "{} is cross-sectional and cannot be optimized on one symbol",
+                    strategy.name()
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// RSI parameter set for grid search
@@ -104,6 +151,41 @@ pub struct ScoredResult {
     pub avg_locked_profit: Option<Decimal>,
 }
 
+/// Rows of the `optimizations` table for the ranked results of one sweep
+pub fn optimization_records(
+    request: &OptimizeRequest,
+    run_id: &str,
+    workspace: &str,
+    results: &[ScoredResult],
+) -> Vec<OptimizationRecord> {
+    results
+        .iter()
+        .map(|r| OptimizationRecord {
+            id: None,
+            run_id: run_id.to_string(),
+            workspace: workspace.to_string(),
+            source_record_id: request.source_record_id,
+            strategy: request.strategy.to_string(),
+            symbol: request.symbol.clone(),
+            days: request.days as i64,
+            rank: r.rank as i64,
+            composite_score: dec_to_f64(r.composite_score),
+            params: r.params.to_string(),
+            net_pnl: dec_to_f64(r.net_pnl),
+            gross_pnl: dec_to_f64(r.gross_pnl),
+            total_fees: dec_to_f64(r.total_fees),
+            win_rate: dec_to_f64(r.win_rate),
+            sharpe_ratio: dec_to_f64(r.sharpe_ratio),
+            max_drawdown_pct: dec_to_f64(r.max_drawdown_pct),
+            profit_factor: dec_to_f64(r.profit_factor),
+            total_trades: r.total_trades as i64,
+            hit_rate: r.hit_rate.map(dec_to_f64),
+            avg_locked_profit: r.avg_locked_profit.map(dec_to_f64),
+            created_at: None,
+        })
+        .collect()
+}
+
 /// Optimization run status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -302,7 +384,10 @@ fn generate_indicator_grid(strategy: &OptimizeStrategy) -> Vec<DiscoveryStrategy
         }
         OptimizeStrategy::Vwap => {
             for &period in &[5usize, 10, 15, 20, 25, 30, 40, 50] {
-                grid.push(DiscoveryStrategyType::Vwap { period });
+                grid.push(DiscoveryStrategyType::Vwap {
+                    period,
+                    anchor: VwapAnchor::Rolling,
+                });
             }
         }
         OptimizeStrategy::Obv => {
@@ -333,13 +418,180 @@ fn generate_indicator_grid(strategy: &OptimizeStrategy) -> Vec<DiscoveryStrategy
                 }
             }
         }
-        // RSI and Gabagool use their own dedicated grids
+        // RSI, Gabagool and custom strategies use their own grids
         _ => {}
     }
 
     grid
 }
 
+/// Combinations of a custom strategy's grid, at most
+pub const MAX_CUSTOM_COMBINATIONS: usize = 400;
+
+/// Multipliers of each numeric parameter in a custom strategy's grid
+const CUSTOM_PARAM_FACTORS: [f64; 5] = [0.5, 0.75, 1.0, 1.25, 1.5];
+
+/// Smallest value of an integer parameter (periods, lookbacks)
+const MIN_INTEGER_PARAM: i64 = 2;
+
+/// Grid around `strategy`, derived from the numeric leaves of its serialized params
+/// (numbers, and decimals serialized as strings): each one is scaled by
+/// `CUSTOM_PARAM_FACTORS` (integers rounded and kept at 2 or more). The full product is
+/// used when it fits in `MAX_CUSTOM_COMBINATIONS`; beyond, each parameter is swept alone
+/// and a seeded Latin hypercube sample of the product fills the rest, so the grid is the
+/// same on every run. `strategy` itself comes first; variants that no longer
+/// deserialize or validate are dropped.
+pub fn derive_param_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoveryStrategyType> {
+    let Ok(base) = serde_json::to_value(strategy) else {
+        return vec![strategy.clone()];
+    };
+    let mut axes = Vec::new();
+    param_axes(&base, String::new(), &mut axes);
+
+    let with = |picks: &[usize]| {
+        let mut params = base.clone();
+        for (axis, &pick) in axes.iter().zip(picks) {
+            if let Some(value) = params.pointer_mut(&axis.pointer) {
+                *value = axis.values[pick].clone();
+            }
+        }
+        params
+    };
+    let start: Vec<usize> = axes.iter().map(|axis| axis.start).collect();
+
+    let mut candidates = vec![with(&start)];
+    let product = axes
+        .iter()
+        .try_fold(1usize, |acc, axis| acc.checked_mul(axis.values.len()));
+    match product {
+        Some(total) if total <= MAX_CUSTOM_COMBINATIONS => {
+            let mut picks = vec![0; axes.len()];
+            for _ in 0..total {
+                candidates.push(with(&picks));
+                for (pick, axis) in picks.iter_mut().zip(&axes) {
+                    *pick += 1;
+                    if *pick < axis.values.len() {
+                        break;
+                    }
+                    *pick = 0;
+                }
+            }
+        }
+        _ => {
+            for (idx, axis) in axes.iter().enumerate() {
+                for pick in 0..axis.values.len() {
+                    let mut picks = start.clone();
+                    picks[idx] = pick;
+                    candidates.push(with(&picks));
+                }
+            }
+            let sample = MAX_CUSTOM_COMBINATIONS.saturating_sub(candidates.len());
+            let mut rng = StdRng::seed_from_u64(0);
+            for point in latin_hypercube(sample, axes.len(), &mut rng) {
+                let picks: Vec<usize> = point
+                    .iter()
+                    .zip(&axes)
+                    .map(|(u, axis)| {
+                        let len = axis.values.len();
+                        ((u * len as f64) as usize).min(len - 1)
+                    })
+                    .collect();
+                candidates.push(with(&picks));
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut grid: Vec<DiscoveryStrategyType> = candidates
+        .into_iter()
+        .filter(|params| seen.insert(params.to_string()))
+        .filter_map(|params| serde_json::from_value(params).ok())
+        .collect();
+    retain_valid(&mut grid);
+    grid.truncate(MAX_CUSTOM_COMBINATIONS);
+    grid
+}
+
+/// One parameter of a custom grid
+struct ParamAxis {
+    /// JSON pointer of the value in the serialized params
+    pointer: String,
+    /// Distinct values, in increasing factor order
+    values: Vec<Value>,
+    /// Index of the unscaled value
+    start: usize,
+}
+
+/// Parameters of a custom grid under `pointer`; leaves with a single value are left out
+fn param_axes(value: &Value, pointer: String, out: &mut Vec<ParamAxis>) {
+    let scaled: Vec<Value> = match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                param_axes(child, format!("{}/{}", pointer, key), out);
+            }
+            return;
+        }
+        Value::Array(items) => {
+            for (idx, child) in items.iter().enumerate() {
+                param_axes(child, format!("{}/{}", pointer, idx), out);
+            }
+            return;
+        }
+        Value::Number(n) => match n.as_i64().filter(|_| !n.is_f64()) {
+            Some(int) => CUSTOM_PARAM_FACTORS
+                .iter()
+                .map(|factor| (int as f64 * factor).round() as i64)
+                .filter(|scaled| *scaled >= MIN_INTEGER_PARAM || *scaled == int)
+                .map(Value::from)
+                .collect(),
+            None => {
+                let Some(float) = n.as_f64() else {
+                    return;
+                };
+                // Keep the params (and their hash) readable
+                CUSTOM_PARAM_FACTORS
+                    .iter()
+                    .map(|factor| Value::from((float * factor * 1e6).round() / 1e6))
+                    .collect()
+            }
+        },
+        Value::String(text) => {
+            let Ok(decimal) = text.parse::<Decimal>() else {
+                return;
+            };
+            CUSTOM_PARAM_FACTORS
+                .iter()
+                .filter_map(|factor| Decimal::try_from(*factor).ok())
+                .map(|factor| Value::from((decimal * factor).round_dp(6).normalize().to_string()))
+                .collect()
+        }
+        _ => return,
+    };
+    let mut values: Vec<Value> = Vec::new();
+    for value in scaled {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    // Factor 1, rounded like the others
+    let unscaled = match value {
+        Value::Number(n) if n.is_f64() => n.as_f64().map(|f| Value::from((f * 1e6).round() / 1e6)),
+        Value::String(text) => text
+            .parse::<Decimal>()
+            .ok()
+            .map(|d| Value::from(d.round_dp(6).normalize().to_string())),
+        other => Some(other.clone()),
+    };
+    let start = unscaled.and_then(|u| values.iter().position(|v| *v == u));
+    if let (Some(start), true) = (start, values.len() > 1) {
+        out.push(ParamAxis {
+            pointer,
+            values,
+            start,
+        });
+    }
+}
+
 // ============================================================================
 // Fee Calculation Helpers
 // ============================================================================
@@ -358,25 +610,6 @@ fn calculate_rsi_fees(result: &BacktestResult, fee_config: &PolymarketFeeConfig)
     total_fees
 }
 
-/// Calculate total Polymarket fees for Gabagool backtest.
-fn calculate_gabagool_fees(
-    result: &GabagoolBacktestResult,
-    fee_config: &PolymarketFeeConfig,
-) -> Decimal {
-    let size = result.config.size_per_side;
-    let mut total_fees = Decimal::ZERO;
-
-    for window in &result.windows {
-        if window.traded {
-            let fee_yes = calculate_taker_fee(size, window.yes_fill, fee_config);
-            let fee_no = calculate_taker_fee(size, window.no_fill, fee_config);
-            total_fees += fee_yes + fee_no;
-        }
-    }
-
-    total_fees
-}
-
 // ============================================================================
 // Scoring
 // ============================================================================
@@ -451,15 +684,41 @@ fn score_gabagool(result: &GabagoolBacktestResult, total_fees: Decimal) -> Decim
 // Main Optimization Runner
 // ============================================================================
 
-/// Run the full parameter optimization grid search.
+/// Klines an optimization needs: one day of 15m bars, past the longest indicator
+/// period of the grids (50)
+pub const MIN_OPTIMIZATION_KLINES: usize = 96;
+
+/// `InsufficientData` below `MIN_OPTIMIZATION_KLINES`
+pub fn check_optimization_klines(klines: &[Kline]) -> EngineResult<()> {
+    if klines.len() < MIN_OPTIMIZATION_KLINES {
+        return Err(EngineError::InsufficientData(format!(
+            "Only {} klines, an optimization needs at least {}",
+            klines.len(),
+            MIN_OPTIMIZATION_KLINES
+        )));
+    }
+    Ok(())
+}
+
+/// Run the full parameter optimization grid search. A cancelled run keeps the
+/// ranking of the combinations done so far and returns `Err(Cancelled)`.
 pub async fn run_optimization(
     request: OptimizeRequest,
     klines: Vec<Kline>,
     progress: Arc<OptimizeProgress>,
-) {
+) -> EngineResult<()> {
     let fee_config = PolymarketFeeConfig::default();
     let top_n = request.top_n.unwrap_or(2);
 
+    if let Err(e) = request
+        .validate()
+        .and_then(|()| check_optimization_klines(&klines))
+    {
+        *progress.error_message.write().unwrap() = Some(e.to_string());
+        *progress.status.write().unwrap() = OptimizeStatus::Error;
+        return Err(e);
+    }
+
     info!(
         strategy = %request.strategy,
         symbol = %request.symbol,
@@ -474,6 +733,10 @@ pub async fn run_optimization(
         OptimizeStrategy::Gabagool => {
             run_gabagool_optimization(&request, &klines, &fee_config, top_n, &progress).await;
         }
+        OptimizeStrategy::Custom(ref strategy) => {
+            run_custom_optimization(strategy, &request, &klines, &fee_config, top_n, &progress)
+                .await;
+        }
         ref s @ (OptimizeStrategy::BollingerBands
         | OptimizeStrategy::Macd
         | OptimizeStrategy::EmaCrossover
@@ -486,6 +749,11 @@ pub async fn run_optimization(
             run_indicator_optimization(s, &klines, &fee_config, top_n, &progress).await;
         }
     }
+
+    if progress.cancelled.load(Ordering::Relaxed) {
+        return Err(EngineError::Cancelled);
+    }
+    Ok(())
 }
 
 async fn run_rsi_optimization(
@@ -782,11 +1050,13 @@ async fn run_gabagool_optimization(
             max_pair_cost: params.max_pair_cost,
             bid_offset: params.bid_offset,
             spread_multiplier: params.spread_multiplier,
+            window: request.gabagool_window.unwrap_or_default(),
+            fee_config: fee_config.clone(),
         };
 
         let result = GabagoolBacktestEngine::run(&config, klines);
-        let total_fees = calculate_gabagool_fees(&result, fee_config);
-        let net_profit = result.total_locked_profit - total_fees;
+        let total_fees = result.total_fees;
+        let net_profit = result.net_profit;
         let composite = score_gabagool(&result, total_fees);
 
         scored.push(ScoredResult {
@@ -797,8 +1067,8 @@ async fn run_gabagool_optimization(
             gross_pnl: result.total_locked_profit,
             total_fees,
             win_rate: result.hit_rate,
-            sharpe_ratio: Decimal::ZERO,
-            max_drawdown_pct: Decimal::ZERO,
+            sharpe_ratio: result.sharpe_ratio,
+            max_drawdown_pct: result.max_drawdown_pct,
             profit_factor: Decimal::ZERO,
             total_trades: result.traded_windows,
             hit_rate: Some(result.hit_rate),
@@ -815,9 +1085,70 @@ async fn run_gabagool_optimization(
     finalize_results(scored, top_n, progress);
 }
 
+/// Custom strategy optimization: the derived grid through the discovery backtester,
+/// scored like discovery
+async fn run_custom_optimization(
+    strategy: &DiscoveryStrategyType,
+    request: &OptimizeRequest,
+    klines: &[Kline],
+    fee_config: &PolymarketFeeConfig,
+    top_n: usize,
+    progress: &Arc<OptimizeProgress>,
+) {
+    let grid = derive_param_grid(strategy);
+    let total = grid.len() as u32;
+    progress.total_combinations.store(total, Ordering::Relaxed);
+
+    info!(combinations = total, strategy = %strategy.name(), "Custom grid generated");
+
+    let limits = BacktestLimits::default();
+    let mut scored: Vec<ScoredResult> = Vec::with_capacity(grid.len());
+
+    for (i, strategy_type) in grid.iter().enumerate() {
+        if progress.cancelled.load(Ordering::Relaxed) {
+            warn!("Optimization cancelled");
+            break;
+        }
+
+        let result = run_backtest_with_fees(
+            strategy_type,
+            klines,
+            &request.symbol,
+            SizingMode::Fixed,
+            &limits,
+            fee_config,
+        );
+        if result.abort_reason.is_none() {
+            scored.push(ScoredResult {
+                rank: 0,
+                composite_score: result.composite_score,
+                params: serde_json::to_value(strategy_type).unwrap_or_default(),
+                net_pnl: result.net_pnl,
+                gross_pnl: result.gross_pnl,
+                total_fees: result.total_fees,
+                win_rate: result.win_rate,
+                sharpe_ratio: result.sharpe_ratio,
+                max_drawdown_pct: result.max_drawdown_pct,
+                profit_factor: result.profit_factor,
+                total_trades: result.total_trades,
+                hit_rate: result.hit_rate,
+                avg_locked_profit: result.avg_locked_profit,
+            });
+        }
+
+        progress.completed.store((i + 1) as u32, Ordering::Relaxed);
+
+        if i % 10 == 0 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    finalize_results(scored, top_n, progress);
+}
+
 fn finalize_results(mut scored: Vec<ScoredResult>, top_n: usize, progress: &Arc<OptimizeProgress>) {
     // Sort by composite score descending
-    scored.sort_by(|a, b| b.composite_score.cmp(&a.composite_score));
+    scored.sort_by_key(|s| std::cmp::Reverse(s.composite_score));
 
     // Assign ranks and keep top N
     for (i, s) in scored.iter_mut().enumerate() {
@@ -862,6 +1193,163 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_run_errors() {
+        use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 200,
+            ..SyntheticConfig::for_model(SyntheticModel::RegimeSwitching)
+        });
+        let request = OptimizeRequest {
+            strategy: OptimizeStrategy::Vwap,
+            symbol: "BTCUSDT".to_string(),
+            days: 2,
+            top_n: None,
+            gabagool_window: None,
+            source_record_id: None,
+        };
+
+        let progress = Arc::new(OptimizeProgress::new());
+        progress.reset(OptimizeStrategy::Vwap);
+        let err = run_optimization(request.clone(), klines[..10].to_vec(), progress.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineError::InsufficientData(_)));
+        assert!(matches!(
+            *progress.status.read().unwrap(),
+            OptimizeStatus::Error
+        ));
+        assert!(progress.error_message.read().unwrap().is_some());
+
+        // Cancelled before the first combination: an empty ranking, and `Cancelled`
+        progress.reset(OptimizeStrategy::Vwap);
+        progress.cancelled.store(true, Ordering::Relaxed);
+        let err = run_optimization(request, klines, progress.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(err, EngineError::Cancelled);
+        assert!(progress.results.read().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_custom_strategy_grid_and_run() {
+        use crate::discovery::{DynCombineMode, IndicatorParams, SingleIndicatorType};
+        use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+        // The request carries any serialized strategy
+        let request: OptimizeRequest = serde_json::from_value(serde_json::json!({
+            "strategy": {
+                "custom": {"type": "rsi", "period": 14, "overbought": 70.0, "oversold": 30.0}
+            },
+            "symbol": "BTCUSDT",
+            "days": 30,
+            "top_n": 3,
+            "source_record_id": 42,
+        }))
+        .unwrap();
+        let OptimizeStrategy::Custom(rsi) = &request.strategy else {
+            panic!("expected a custom strategy");
+        };
+        assert_eq!(request.strategy.to_string(), "Custom RSI");
+
+        // Small products: the full grid without the invalid levels (overbought 105,
+        // oversold above overbought 35), the strategy itself first
+        let grid = derive_param_grid(rsi);
+        assert_eq!(grid.len(), 5 * (5 * 5 - 5 - 2));
+        assert!(grid.iter().all(|s| s.validate().is_ok()));
+        let params = |s: &DiscoveryStrategyType| serde_json::to_value(s).unwrap();
+        assert_eq!(params(&grid[0]), params(rsi));
+        assert!(grid.iter().any(|s| params(s)["period"] == 7));
+        assert!(grid.iter().any(|s| params(s)["oversold"] == 45.0));
+
+        // Decimal params are scaled too
+        let gabagool = DiscoveryStrategyType::Gabagool {
+            max_pair_cost: dec!(0.95),
+            bid_offset: dec!(0.02),
+            spread_multiplier: dec!(1.5),
+        };
+        let grid = derive_param_grid(&gabagool);
+        // max_pair_cost above 1 is dropped
+        assert_eq!(grid.len(), 3 * 5 * 5);
+        assert!(grid.iter().all(|s| s.validate().is_ok()));
+        assert!(grid.iter().any(|s| params(s)["bid_offset"] == "0.01"));
+
+        // Dynamic combos: sweeps and a sample capped at MAX_CUSTOM_COMBINATIONS
+        let combo = DiscoveryStrategyType::DynamicCombo {
+            indicators: vec![SingleIndicatorType::Rsi, SingleIndicatorType::Macd],
+            params: vec![
+                IndicatorParams::Rsi {
+                    period: 14,
+                    overbought: 70.0,
+                    oversold: 30.0,
+                },
+                IndicatorParams::Macd {
+                    fast: 12,
+                    slow: 26,
+                    signal: 9,
+                },
+            ],
+            combine_mode: DynCombineMode::Unanimous,
+        };
+        let grid = derive_param_grid(&combo);
+        assert!(grid.len() > 5 * 6 && grid.len() <= MAX_CUSTOM_COMBINATIONS);
+        assert!(grid.iter().all(|s| s.validate().is_ok()));
+        assert_eq!(params(&grid[0]), params(&combo));
+        assert_eq!(
+            grid.iter().map(params).collect::<Vec<_>>(),
+            derive_param_grid(&combo)
+                .iter()
+                .map(params)
+                .collect::<Vec<_>>()
+        );
+        assert!(grid
+            .iter()
+            .all(|s| matches!(s, DiscoveryStrategyType::DynamicCombo { .. })));
+
+        // Cross-sectional strategies need a basket
+        let basket = OptimizeRequest {
+            strategy: OptimizeStrategy::Custom(Box::new(DiscoveryStrategyType::RelativeStrength {
+                lookback: 20,
+                top_k: 1,
+            })),
+            ..request.clone()
+        };
+        assert!(matches!(
+            basket.validate(),
+            Err(EngineError::InvalidParams(_))
+        ));
+
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 1_000,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let progress = Arc::new(OptimizeProgress::new());
+        progress.reset(request.strategy.clone());
+        run_optimization(request.clone(), klines, progress.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            progress.total_combinations.load(Ordering::Relaxed) as usize,
+            derive_param_grid(rsi).len()
+        );
+        let results = progress.results.read().unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].composite_score >= results[2].composite_score);
+        assert!(serde_json::from_value::<DiscoveryStrategyType>(results[0].params.clone()).is_ok());
+
+        // Stored rows keep the rank, the strategy and the originating record
+        let records = optimization_records(&request, "1000", "default", &results);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].rank, 1);
+        assert_eq!(records[2].source_record_id, Some(42));
+        assert_eq!(records[0].strategy, "Custom RSI");
+        assert_eq!(
+            serde_json::from_str::<Value>(&records[0].params).unwrap(),
+            results[0].params
+        );
+    }
+
     #[test]
     fn test_gabagool_grid_generation() {
         let grid = generate_gabagool_grid();
@@ -950,11 +1438,16 @@ mod tests {
             hit_rate: dec!(5),
             total_capital_used: dec!(100),
             total_locked_profit: dec!(10),
+            total_fees: Decimal::ZERO,
+            net_profit: dec!(10),
             avg_pair_cost: dec!(0.95),
             avg_locked_profit: dec!(2),
             best_pair_cost: dec!(0.90),
             worst_pair_cost: dec!(0.98),
             avg_spread: dec!(0.05),
+            pnl_series: vec![],
+            max_drawdown_pct: Decimal::ZERO,
+            sharpe_ratio: Decimal::ZERO,
             profit_curve: vec![],
             windows: vec![],
         };
@@ -330,7 +330,7 @@ pub async fn run_orderbook_backtest(
     // Convert to DB records and save (INSERT OR IGNORE handles duplicates)
     let market_records: Vec<ObMarketRecord> = markets
         .iter()
-        .filter_map(|m| gamma_market_to_record(m))
+        .filter_map(gamma_market_to_record)
         .collect();
 
     if !market_records.is_empty() {
@@ -584,10 +584,9 @@ pub async fn run_orderbook_backtest(
     }
 
     // Sequence patterns
-    let features_grouped = match OrderbookRepository::get_features_grouped_by_market(&db_pool).await {
-        Ok(f) => f,
-        Err(_) => HashMap::new(),
-    };
+    let features_grouped = OrderbookRepository::get_features_grouped_by_market(&db_pool)
+        .await
+        .unwrap_or_default();
     if features_grouped.len() >= 20 {
         let sequence = detect_sequence_patterns(&features_grouped);
         all_patterns.extend(sequence);
@@ -796,7 +795,7 @@ fn gamma_market_to_record(m: &GammaMarket) -> Option<ObMarketRecord> {
 }
 
 /// Parse clobTokenIds from Gamma API (JSON string like "[\"token1\",\"token2\"]")
-fn parse_clob_token_ids(raw: Option<&str>) -> (Option<String>, Option<String>) {
+pub(crate) fn parse_clob_token_ids(raw: Option<&str>) -> (Option<String>, Option<String>) {
     if let Some(s) = raw {
         if let Ok(ids) = serde_json::from_str::<Vec<String>>(s) {
             return (ids.first().cloned(), ids.get(1).cloned());
@@ -1040,9 +1039,12 @@ fn compute_std_dev(values: &[f64]) -> f64 {
     variance.sqrt()
 }
 
+/// (buy_volume, sell_volume, volume_imbalance, trade_count, avg_trade_size, large_trade_ratio)
+type VolumeFeatures = (Option<f64>, Option<f64>, Option<f64>, Option<i64>, Option<f64>, Option<f64>);
+
 fn compute_volume_features(
     prices: &[&persistence::repository::orderbook::ObPriceRecord],
-) -> (Option<f64>, Option<f64>, Option<f64>, Option<i64>, Option<f64>, Option<f64>) {
+) -> VolumeFeatures {
     let mut buy_vol = 0.0;
     let mut sell_vol = 0.0;
     let mut trade_sizes: Vec<f64> = Vec::new();
@@ -1764,8 +1766,8 @@ mod tests {
 
     fn make_prices(data: &[(f64, f64)]) -> Vec<ObPriceRecord> {
         data.iter()
-            .enumerate()
-            .map(|(_i, (elapsed, price))| ObPriceRecord {
+            
+            .map(|(elapsed, price)| ObPriceRecord {
                 id: None,
                 market_id: 1,
                 timestamp_ms: (1000.0 * elapsed) as i64,
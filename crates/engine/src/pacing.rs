@@ -0,0 +1,193 @@
+//! CPU pacing of the discovery scan loops
+//!
+//! A run's `ScanPacing` comes from `DiscoveryRequest.pacing` and lives in
+//! `DiscoveryProgress`, so it can be changed while the run goes on. Each scan loop owns
+//! a `Pacer`: after every backtest it owes a pause — what is left of the backtest's
+//! `1 / max_backtests_per_sec` slot, and `busy * (1 - f) / f` for a `cpu_fraction` of
+//! `f` — and sleeps once the owed time reaches `MIN_SLEEP`. Unpaced, it only sleeps
+//! 1 ms every `YIELD_EVERY` backtests to let the other tasks run.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Owed pause below which the pacer keeps going (tokio sleeps are ~1 ms granular)
+const MIN_SLEEP: Duration = Duration::from_millis(5);
+
+/// Backtests between two 1 ms yields of an unpaced loop
+const YIELD_EVERY: u32 = 50;
+
+/// Speed limits of a scan, both optional (the stricter one wins)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScanPacing {
+    /// Computed backtests per second, at most (cached results are not counted)
+    #[serde(default)]
+    pub max_backtests_per_sec: Option<f64>,
+    /// Share of one core the scan loop may keep busy, in (0, 1]
+    #[serde(default)]
+    pub cpu_fraction: Option<f64>,
+}
+
+impl ScanPacing {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_backtests_per_sec.is_none() && self.cpu_fraction.is_none()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(rate) = self.max_backtests_per_sec {
+            if !rate.is_finite() || rate <= 0.0 {
+                return Err(format!(
+                    "max_backtests_per_sec must be positive, got {}",
+                    rate
+                ));
+            }
+        }
+        if let Some(fraction) = self.cpu_fraction {
+            if !(fraction > 0.0 && fraction <= 1.0) {
+                return Err(format!("cpu_fraction must be in (0, 1], got {}", fraction));
+            }
+        }
+        Ok(())
+    }
+
+    /// Pause owed after a backtest that kept the loop busy for `busy`
+    pub fn pause_after(&self, busy: Duration) -> Duration {
+        let slot = self
+            .max_backtests_per_sec
+            .map(|rate| Duration::from_secs_f64(1.0 / rate).saturating_sub(busy))
+            .unwrap_or_default();
+        let share = self
+            .cpu_fraction
+            .map(|fraction| busy.mul_f64((1.0 - fraction) / fraction))
+            .unwrap_or_default();
+        slot.max(share)
+    }
+}
+
+/// Sleeps of one scan loop, see the module docs
+#[derive(Debug)]
+pub struct Pacer {
+    /// End of the previous step (its sleep included)
+    last: Instant,
+    owed: Duration,
+    since_yield: u32,
+}
+
+impl Pacer {
+    pub fn new() -> Self {
+        Self {
+            last: Instant::now(),
+            owed: Duration::ZERO,
+            since_yield: 0,
+        }
+    }
+
+    /// After a computed backtest: sleep what `pacing` asks for
+    pub async fn backtested(&mut self, pacing: ScanPacing) {
+        self.owed += pacing.pause_after(self.last.elapsed());
+        self.step().await;
+    }
+
+    /// After a result answered from the store: only the periodic yield
+    pub async fn cached(&mut self) {
+        self.step().await;
+    }
+
+    async fn step(&mut self) {
+        self.since_yield += 1;
+        if self.owed >= MIN_SLEEP {
+            tokio::time::sleep(self.owed).await;
+            self.owed = Duration::ZERO;
+            self.since_yield = 0;
+        } else if self.since_yield >= YIELD_EVERY {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            self.since_yield = 0;
+        }
+        self.last = Instant::now();
+    }
+}
+
+impl Default for Pacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `pause` within 1 µs of `ms` milliseconds (f64 rounding)
+    fn assert_pause(pause: Duration, ms: u64) {
+        let expected = Duration::from_millis(ms);
+        let diff = pause.max(expected) - pause.min(expected);
+        assert!(diff < Duration::from_micros(1), "{:?} != {}ms", pause, ms);
+    }
+
+    #[test]
+    fn test_pause_after() {
+        let ms = Duration::from_millis;
+        assert_eq!(ScanPacing::default().pause_after(ms(30)), Duration::ZERO);
+
+        let rate = ScanPacing {
+            max_backtests_per_sec: Some(20.0),
+            cpu_fraction: None,
+        };
+        assert_pause(rate.pause_after(ms(20)), 30);
+        assert_eq!(rate.pause_after(ms(80)), Duration::ZERO);
+
+        let quarter = ScanPacing {
+            max_backtests_per_sec: None,
+            cpu_fraction: Some(0.25),
+        };
+        assert_pause(quarter.pause_after(ms(10)), 30);
+
+        // Both set: the stricter limit
+        let both = ScanPacing {
+            max_backtests_per_sec: Some(20.0),
+            cpu_fraction: Some(0.25),
+        };
+        assert_pause(both.pause_after(ms(5)), 45);
+        assert_pause(both.pause_after(ms(40)), 120);
+
+        assert!(both.validate().is_ok());
+        for bad in [
+            ScanPacing {
+                max_backtests_per_sec: Some(0.0),
+                ..Default::default()
+            },
+            ScanPacing {
+                cpu_fraction: Some(1.5),
+                ..Default::default()
+            },
+            ScanPacing {
+                cpu_fraction: Some(f64::NAN),
+                ..Default::default()
+            },
+        ] {
+            assert!(bad.validate().is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pacer_limits_the_rate() {
+        let pacing = ScanPacing {
+            max_backtests_per_sec: Some(200.0),
+            cpu_fraction: None,
+        };
+        let mut pacer = Pacer::new();
+        let start = Instant::now();
+        for _ in 0..10 {
+            pacer.backtested(pacing).await;
+        }
+        // 10 slots of 5 ms, the last one possibly still owed
+        assert!(start.elapsed() >= Duration::from_millis(45));
+
+        let start = Instant::now();
+        for _ in 0..10 {
+            pacer.cached().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(45));
+    }
+}
@@ -0,0 +1,283 @@
+//! Pairs trading — spread mean-reversion between two symbols
+//!
+//! The spread is the log price ratio `ln(A / B)`. Its z-score against the previous
+//! `lookback` bars drives the position: above `entry_z` the spread is sold (short A,
+//! long B), below `-entry_z` it is bought (long A, short B), and the position is
+//! closed once z has reverted back to `±exit_z` (`exit_z = 0` waits for the mean).
+//! Both legs get the same notional and
+//! pay their own taker fees; a round trip is recorded as one trade with the combined
+//! PnL of the two legs.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::fees::{calculate_taker_fee, PolymarketFeeConfig};
+use crate::probability::ProbabilityModel;
+use crate::types::{BacktestTrade, ExitReason, TradeSide};
+
+pub struct PairBacktest {
+    /// One trade per round trip, `pnl` = leg A + leg B (before fees)
+    pub trades: Vec<BacktestTrade>,
+    pub final_equity: Decimal,
+    pub total_fees: Decimal,
+    pub max_drawdown_pct: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairSpreadParams {
+    pub lookback: usize,
+    pub entry_z: f64,
+    pub exit_z: f64,
+    /// Notional of each leg, in % of equity
+    pub leg_pct: Decimal,
+}
+
+struct OpenSpread {
+    /// +1 = long A / short B, -1 = short A / long B
+    direction: i32,
+    entry_time: i64,
+    entry_a: Decimal,
+    entry_b: Decimal,
+    size_a: Decimal,
+    size_b: Decimal,
+    entry_ratio: Decimal,
+    notional: Decimal,
+}
+
+impl OpenSpread {
+    fn pnl(&self, price_a: Decimal, price_b: Decimal) -> Decimal {
+        let dir = Decimal::from(self.direction);
+        dir * ((price_a - self.entry_a) * self.size_a - (price_b - self.entry_b) * self.size_b)
+    }
+}
+
+/// z-score of the log ratio at `bar` against the `lookback` bars before it
+pub fn spread_zscore(log_ratio: &[f64], bar: usize, lookback: usize) -> Option<f64> {
+    if lookback < 2 || bar < lookback {
+        return None;
+    }
+    let window = &log_ratio[bar - lookback..bar];
+    let n = window.len() as f64;
+    let mean = window.iter().sum::<f64>() / n;
+    let variance = window.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let std_dev = variance.sqrt();
+    if std_dev < 1e-12 {
+        return None;
+    }
+    Some((log_ratio[bar] - mean) / std_dev)
+}
+
+/// Backtest the A/B spread on aligned closes (`times`, `a` and `b` have the same length)
+pub fn run_pair_spread_backtest(
+    times: &[i64],
+    a: &[Decimal],
+    b: &[Decimal],
+    params: PairSpreadParams,
+    initial_capital: Decimal,
+    fee_config: &PolymarketFeeConfig,
+) -> PairBacktest {
+    let hundred = Decimal::from(100);
+    let log_ratio: Vec<f64> = a
+        .iter()
+        .zip(b)
+        .map(|(pa, pb)| match (pa.to_f64(), pb.to_f64()) {
+            (Some(x), Some(y)) if x > 0.0 && y > 0.0 => (x / y).ln(),
+            _ => 0.0,
+        })
+        .collect();
+    let (base_a, base_b) = (
+        a.first().copied().unwrap_or(Decimal::ONE),
+        b.first().copied().unwrap_or(Decimal::ONE),
+    );
+    let leg_fee = |base: Decimal, size: Decimal, price: Decimal| {
+        let p = fee_config.probability_model.probability_dec(base, price);
+        calculate_taker_fee(size, p, fee_config)
+    };
+
+    let mut equity = initial_capital;
+    let mut peak_equity = initial_capital;
+    let mut max_drawdown_pct = Decimal::ZERO;
+    let mut total_fees = Decimal::ZERO;
+    let mut position: Option<OpenSpread> = None;
+    let mut trades = Vec::new();
+
+    let close = |pos: OpenSpread,
+                 i: usize,
+                 equity: &mut Decimal,
+                 total_fees: &mut Decimal,
+                 trades: &mut Vec<BacktestTrade>,
+                 exit_reason: ExitReason| {
+        let pnl = pos.pnl(a[i], b[i]);
+        let fees = leg_fee(base_a, pos.size_a, a[i]) + leg_fee(base_b, pos.size_b, b[i]);
+        *equity += pnl - fees;
+        *total_fees += fees;
+        let exit_ratio = if b[i] > Decimal::ZERO {
+            a[i] / b[i]
+        } else {
+            pos.entry_ratio
+        };
+        trades.push(BacktestTrade {
+            entry_time: pos.entry_time,
+            exit_time: times[i],
+            side: if pos.direction > 0 {
+                TradeSide::Buy
+            } else {
+                TradeSide::Sell
+            },
+            entry_price: pos.entry_ratio,
+            exit_price: exit_ratio,
+            // size × entry_price = total notional of both legs
+            size: if pos.entry_ratio > Decimal::ZERO {
+                pos.notional * Decimal::TWO / pos.entry_ratio
+            } else {
+                Decimal::ZERO
+            },
+            pnl,
+            pnl_pct: if pos.notional > Decimal::ZERO {
+                pnl / (pos.notional * Decimal::TWO) * hundred
+            } else {
+                Decimal::ZERO
+            },
+            exit_reason,
+        });
+    };
+
+    for i in 0..a.len() {
+        let z = spread_zscore(&log_ratio, i, params.lookback);
+
+        match (&position, z) {
+            // Short spread exits once z <= exit_z, long spread once z >= -exit_z
+            (Some(pos), Some(z)) if z * f64::from(pos.direction) >= -params.exit_z => {
+                let pos = position.take().expect("position checked above");
+                close(
+                    pos,
+                    i,
+                    &mut equity,
+                    &mut total_fees,
+                    &mut trades,
+                    ExitReason::Signal,
+                );
+            }
+            (None, Some(z))
+                if z.abs() > params.entry_z && a[i] > Decimal::ZERO && b[i] > Decimal::ZERO =>
+            {
+                let notional = equity * params.leg_pct / hundred;
+                let (size_a, size_b) = (notional / a[i], notional / b[i]);
+                let fees = leg_fee(base_a, size_a, a[i]) + leg_fee(base_b, size_b, b[i]);
+                equity -= fees;
+                total_fees += fees;
+                position = Some(OpenSpread {
+                    // Spread too high → expect it to fall → short A / long B
+                    direction: if z > 0.0 { -1 } else { 1 },
+                    entry_time: times[i],
+                    entry_a: a[i],
+                    entry_b: b[i],
+                    size_a,
+                    size_b,
+                    entry_ratio: a[i] / b[i],
+                    notional,
+                });
+            }
+            _ => {}
+        }
+
+        let unrealized = position
+            .as_ref()
+            .map(|p| p.pnl(a[i], b[i]))
+            .unwrap_or(Decimal::ZERO);
+        let current = equity + unrealized;
+        if current > peak_equity {
+            peak_equity = current;
+        }
+        if peak_equity > Decimal::ZERO {
+            max_drawdown_pct =
+                max_drawdown_pct.max((peak_equity - current) / peak_equity * hundred);
+        }
+    }
+
+    if let Some(pos) = position.take() {
+        if !a.is_empty() {
+            close(
+                pos,
+                a.len() - 1,
+                &mut equity,
+                &mut total_fees,
+                &mut trades,
+                ExitReason::Timeout,
+            );
+        }
+    }
+
+    PairBacktest {
+        trades,
+        final_equity: equity,
+        total_fees,
+        max_drawdown_pct,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_spread_zscore() {
+        let flat = vec![0.0; 10];
+        assert_eq!(spread_zscore(&flat, 5, 5), None);
+
+        let mut series: Vec<f64> = (0..20)
+            .map(|i| if i % 2 == 0 { 0.01 } else { -0.01 })
+            .collect();
+        series.push(0.05);
+        let z = spread_zscore(&series, 20, 20).unwrap();
+        assert!(z > 4.0, "z = {}", z);
+        assert_eq!(spread_zscore(&series, 3, 20), None);
+    }
+
+    #[test]
+    fn test_pair_spread_captures_reversion_on_both_legs() {
+        // B is flat; A oscillates around 100 then spikes to 110 and reverts
+        let mut a: Vec<Decimal> = (0..30)
+            .map(|i| if i % 2 == 0 { dec!(100.5) } else { dec!(99.5) })
+            .collect();
+        a.extend([dec!(110), dec!(108), dec!(100)]);
+        let b = vec![dec!(50); a.len()];
+        let times: Vec<i64> = (0..a.len() as i64).map(|i| i * 900_000).collect();
+
+        let params = PairSpreadParams {
+            lookback: 20,
+            entry_z: 2.0,
+            exit_z: 0.5,
+            leg_pct: dec!(10),
+        };
+        let no_fees = PolymarketFeeConfig {
+            fee_rate: Decimal::ZERO,
+            ..PolymarketFeeConfig::default()
+        };
+        let bt = run_pair_spread_backtest(&times, &a, &b, params, dec!(10000), &no_fees);
+
+        // Short A / long B at 110, closed at 100: 1000 / 110 × 10 on leg A, 0 on leg B
+        assert_eq!(bt.trades.len(), 1);
+        let trade = &bt.trades[0];
+        assert_eq!(trade.side, TradeSide::Sell);
+        assert_eq!(trade.entry_price, dec!(2.2));
+        assert!((trade.pnl - dec!(90.909)).abs() < dec!(0.001));
+        assert_eq!(bt.final_equity, dec!(10000) + trade.pnl);
+
+        // With fees, both legs pay on entry and exit
+        let bt = run_pair_spread_backtest(
+            &times,
+            &a,
+            &b,
+            params,
+            dec!(10000),
+            &PolymarketFeeConfig::default(),
+        );
+        assert!(bt.total_fees > Decimal::ZERO);
+        assert_eq!(
+            bt.final_equity,
+            dec!(10000) + bt.trades[0].pnl - bt.total_fees
+        );
+    }
+}
@@ -0,0 +1,418 @@
+//! Strategy promotion pipeline — discovered → validated → paper_trading → approved
+//!
+//! Every knowledge base record sits in one `PipelineStage` (no `strategy_pipeline` row
+//! = `discovered`). Stages move forward one step at a time; any live stage can be
+//! rejected and a rejected record can be reopened as discovered. Transitions go through
+//! the API or through `apply_rules`:
+//!
+//! - discovered → validated when the backtest clears `min_score`, `min_confidence`
+//!   and `min_trades`
+//! - validated / paper_trading → rejected when the latest re-validation shows decay
+//!   (see `validation::drift_report`)
+
+use persistence::repository::{
+    DiscoveryBacktestRecord, DiscoveryRepository, PipelineRepository, ValidationRepository,
+};
+use persistence::SqlitePool;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::validation::{drift_report, DecayThresholds, StrategyDrift};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    Discovered,
+    Validated,
+    PaperTrading,
+    Approved,
+    Rejected,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 5] = [
+        PipelineStage::Discovered,
+        PipelineStage::Validated,
+        PipelineStage::PaperTrading,
+        PipelineStage::Approved,
+        PipelineStage::Rejected,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PipelineStage::Discovered => "discovered",
+            PipelineStage::Validated => "validated",
+            PipelineStage::PaperTrading => "paper_trading",
+            PipelineStage::Approved => "approved",
+            PipelineStage::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|stage| stage.as_str() == s)
+    }
+
+    /// Stages reachable in one transition
+    pub fn next_stages(&self) -> &'static [PipelineStage] {
+        match self {
+            PipelineStage::Discovered => &[PipelineStage::Validated, PipelineStage::Rejected],
+            PipelineStage::Validated => &[PipelineStage::PaperTrading, PipelineStage::Rejected],
+            PipelineStage::PaperTrading => &[PipelineStage::Approved, PipelineStage::Rejected],
+            PipelineStage::Approved => &[PipelineStage::Rejected],
+            PipelineStage::Rejected => &[PipelineStage::Discovered],
+        }
+    }
+
+    pub fn can_move_to(&self, next: PipelineStage) -> bool {
+        self.next_stages().contains(&next)
+    }
+}
+
+/// Body of a manual transition
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PipelineTransitionRequest {
+    pub stage: PipelineStage,
+    pub reason: Option<String>,
+}
+
+/// Thresholds of the automatic transitions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct PipelineRules {
+    /// discovered → validated: minimum composite score
+    pub min_score: f64,
+    /// discovered → validated: minimum strategy confidence (0-100)
+    pub min_confidence: f64,
+    /// discovered → validated: minimum number of trades
+    pub min_trades: i64,
+    /// Records promoted per pass, best scores first
+    pub max_promotions: i64,
+    /// validated / paper_trading → rejected when the latest validation decays
+    pub reject_on_decay: bool,
+    pub decay: DecayThresholds,
+}
+
+impl Default for PipelineRules {
+    fn default() -> Self {
+        Self {
+            min_score: 300.0,
+            min_confidence: 50.0,
+            min_trades: 20,
+            max_promotions: 100,
+            reject_on_decay: true,
+            decay: DecayThresholds::default(),
+        }
+    }
+}
+
+/// Record ids moved by one `apply_rules` pass
+#[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PipelineReport {
+    pub promoted: Vec<i64>,
+    pub rejected: Vec<i64>,
+}
+
+/// Current stage of a record
+pub async fn current_stage(pool: &SqlitePool, backtest_id: i64) -> anyhow::Result<PipelineStage> {
+    let entry = PipelineRepository::new(pool).get_stage(backtest_id).await?;
+    Ok(entry
+        .and_then(|e| PipelineStage::parse(&e.stage))
+        .unwrap_or(PipelineStage::Discovered))
+}
+
+/// Move a record to `next`; errors if the state machine does not allow it
+pub async fn transition(
+    pool: &SqlitePool,
+    backtest_id: i64,
+    next: PipelineStage,
+    reason: Option<&str>,
+    automatic: bool,
+) -> anyhow::Result<PipelineStage> {
+    let current = current_stage(pool, backtest_id).await?;
+    if !current.can_move_to(next) {
+        anyhow::bail!("cannot move from {} to {}", current.as_str(), next.as_str());
+    }
+    PipelineRepository::new(pool)
+        .transition(
+            backtest_id,
+            current.as_str(),
+            next.as_str(),
+            reason,
+            automatic,
+        )
+        .await?;
+    info!(
+        backtest_id,
+        from = current.as_str(),
+        to = next.as_str(),
+        automatic,
+        "Pipeline transition"
+    );
+    Ok(current)
+}
+
+/// Reject a validated / paper-trading record whose latest validation decays.
+/// Returns whether it was rejected.
+pub async fn reject_if_decaying(pool: &SqlitePool, drift: &StrategyDrift) -> anyhow::Result<bool> {
+    if !drift.decaying {
+        return Ok(false);
+    }
+    let current = current_stage(pool, drift.backtest_id).await?;
+    if !matches!(
+        current,
+        PipelineStage::Validated | PipelineStage::PaperTrading
+    ) {
+        return Ok(false);
+    }
+    let reason = format!(
+        "auto: {}",
+        drift.decay_reason.as_deref().unwrap_or("performance decay")
+    );
+    transition(
+        pool,
+        drift.backtest_id,
+        PipelineStage::Rejected,
+        Some(&reason),
+        true,
+    )
+    .await?;
+    Ok(true)
+}
+
+/// One pass of the automatic transitions over the whole knowledge base
+pub async fn apply_rules(
+    pool: &SqlitePool,
+    rules: &PipelineRules,
+) -> anyhow::Result<PipelineReport> {
+    let pipeline = PipelineRepository::new(pool);
+    let mut report = PipelineReport::default();
+
+    let candidates = pipeline
+        .promotion_candidates(
+            rules.min_score,
+            rules.min_confidence,
+            rules.min_trades,
+            rules.max_promotions,
+        )
+        .await?;
+    for record in candidates {
+        let Some(id) = record.id else { continue };
+        let reason = promotion_reason(&record, rules);
+        transition(pool, id, PipelineStage::Validated, Some(&reason), true).await?;
+        report.promoted.push(id);
+    }
+
+    if rules.reject_on_decay {
+        let validations = ValidationRepository::new(pool);
+        for workspace in DiscoveryRepository::new(pool).list_workspaces().await? {
+            let pipeline = PipelineRepository::new(pool).with_workspace(&workspace);
+            for stage in [PipelineStage::Validated, PipelineStage::PaperTrading] {
+                for record in pipeline.list_stage(stage.as_str(), i64::MAX).await? {
+                    let Some(id) = record.id else { continue };
+                    let history = validations.get_history(id).await?;
+                    let drift = drift_report(&record, &history, rules.decay);
+                    if reject_if_decaying(pool, &drift).await? {
+                        report.rejected.push(id);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn promotion_reason(record: &DiscoveryBacktestRecord, rules: &PipelineRules) -> String {
+    format!(
+        "auto: score {:.1} ≥ {}, confidence {:.0} ≥ {}, {} trades ≥ {}",
+        record.composite_score,
+        rules.min_score,
+        record.strategy_confidence.unwrap_or(0.0),
+        rules.min_confidence,
+        record.total_trades,
+        rules.min_trades
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use persistence::repository::{DiscoveryRepository, StrategyValidationRecord};
+    use persistence::Database;
+
+    fn record(i: usize, score: f64, confidence: f64) -> DiscoveryBacktestRecord {
+        DiscoveryBacktestRecord {
+            id: None,
+            params_hash: format!("hash-{i}"),
+            strategy_type: "rsi".to_string(),
+            strategy_name: format!("RSI {i}"),
+            strategy_params: "{}".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            days: 90,
+            sizing_mode: "Fixed".to_string(),
+            composite_score: score,
+            net_pnl: 500.0,
+            gross_pnl: 600.0,
+            total_fees: 100.0,
+            win_rate: 60.0,
+            total_trades: 40,
+            sharpe_ratio: 1.5,
+            max_drawdown_pct: 8.0,
+            profit_factor: 1.6,
+            avg_trade_pnl: 12.5,
+            hit_rate: None,
+            avg_locked_profit: None,
+            discovery_run_id: Some("run".to_string()),
+            phase: None,
+            sortino_ratio: None,
+            max_consecutive_losses: None,
+            avg_win_pnl: None,
+            avg_loss_pnl: None,
+            total_volume: None,
+            annualized_return_pct: None,
+            annualized_sharpe: None,
+            strategy_confidence: Some(confidence),
+            schema_version: Some(1),
+            abort_reason: None,
+            probability_model: None,
+            drawdown_throttle: None,
+            cross_symbol_score: None,
+            exit_breakdown: None,
+            decayed_score: None,
+            score_30d: None,
+            net_pnl_30d: None,
+            win_rate_30d: None,
+            trades_30d: None,
+            score_90d: None,
+            net_pnl_90d: None,
+            win_rate_90d: None,
+            trades_90d: None,
+            pnl_hhi: None,
+            top_week_pnl_share: None,
+            active_weeks: None,
+            longest_drawdown_days: None,
+            avg_recovery_days: None,
+            max_exposure_pct: None,
+            avg_exposure_pct: None,
+            warmup_bars: None,
+            effective_days: None,
+            engine_version: None,
+            logic_version: None,
+            cost_attribution: None,
+        }
+    }
+
+    #[test]
+    fn test_stage_transitions() {
+        use PipelineStage::*;
+        assert!(Discovered.can_move_to(Validated));
+        assert!(!Discovered.can_move_to(PaperTrading));
+        assert!(!Validated.can_move_to(Approved));
+        assert!(PaperTrading.can_move_to(Approved));
+        assert!(Rejected.can_move_to(Discovered));
+        for stage in PipelineStage::ALL {
+            assert_eq!(PipelineStage::parse(stage.as_str()), Some(stage));
+            assert_eq!(stage != Rejected, stage.can_move_to(Rejected));
+        }
+        assert_eq!(
+            serde_json::to_string(&PaperTrading).unwrap(),
+            "\"paper_trading\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_rules_promotes_and_rejects_on_decay() {
+        let db = Database::in_memory().await.unwrap();
+        let pool = db.pool();
+        let repo = DiscoveryRepository::new(pool);
+        let strong = repo.save(&record(0, 450.0, 70.0)).await.unwrap();
+        let unsure = repo.save(&record(1, 450.0, 20.0)).await.unwrap();
+        let weak = repo.save(&record(2, 100.0, 90.0)).await.unwrap();
+
+        let rules = PipelineRules::default();
+        let report = apply_rules(pool, &rules).await.unwrap();
+        assert_eq!(report.promoted, [strong]);
+        assert!(report.rejected.is_empty());
+        assert_eq!(
+            current_stage(pool, strong).await.unwrap(),
+            PipelineStage::Validated
+        );
+        assert_eq!(
+            current_stage(pool, unsure).await.unwrap(),
+            PipelineStage::Discovered
+        );
+        // Already promoted records are not promoted twice
+        assert!(apply_rules(pool, &rules).await.unwrap().promoted.is_empty());
+
+        // Manual moves follow the state machine
+        assert!(transition(pool, weak, PipelineStage::Approved, None, false)
+            .await
+            .is_err());
+        transition(
+            pool,
+            strong,
+            PipelineStage::PaperTrading,
+            Some("looks good"),
+            false,
+        )
+        .await
+        .unwrap();
+
+        // A decaying re-validation rejects it on the next pass
+        ValidationRepository::new(pool)
+            .save(&StrategyValidationRecord {
+                id: None,
+                backtest_id: strong,
+                days: 90,
+                composite_score: 120.0,
+                net_pnl: -50.0,
+                win_rate: 45.0,
+                total_trades: 38,
+                sharpe_ratio: 0.2,
+                max_drawdown_pct: 15.0,
+                profit_factor: 0.9,
+                abort_reason: None,
+                validated_at: None,
+            })
+            .await
+            .unwrap();
+        let report = apply_rules(pool, &rules).await.unwrap();
+        assert_eq!(report.rejected, [strong]);
+        assert_eq!(
+            current_stage(pool, strong).await.unwrap(),
+            PipelineStage::Rejected
+        );
+
+        let transitions = PipelineRepository::new(pool)
+            .get_transitions(strong)
+            .await
+            .unwrap();
+        let steps: Vec<(&str, bool)> = transitions
+            .iter()
+            .map(|t| (t.to_stage.as_str(), t.automatic))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                ("validated", true),
+                ("paper_trading", false),
+                ("rejected", true)
+            ]
+        );
+
+        // Cleanup keeps records that are live in the pipeline
+        transition(pool, strong, PipelineStage::Discovered, None, false)
+            .await
+            .unwrap();
+        transition(pool, strong, PipelineStage::Validated, None, false)
+            .await
+            .unwrap();
+        let (deleted, remaining) = repo.cleanup_keep_top_n(0).await.unwrap();
+        assert_eq!((deleted, remaining), (2, 1));
+        assert!(repo.get_by_id(strong).await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,553 @@
+//! Population of the evolutionary search, persisted across restarts
+//!
+//! ML-guided cycles (3+) mutate and cross the fittest genomes found so far. The
+//! population holds the `POPULATION_SIZE` fittest distinct genomes (strategy params,
+//! with the composite score and symbol of their best result); each ML-guided cycle is a
+//! generation that merges the results of the run into it before breeding. It is saved
+//! to `ga_population` every generation and loaded when a continuous run starts, so a
+//! restarted server resumes evolution from the parents it had instead of re-seeding
+//! from the first cycles of the new run.
+//!
+//! Continuous runs keep one population per symbol (`IslandModel`): each island selects
+//! on the results of its symbol and breeds the grid tested on it, so lineages adapt to
+//! their symbol. Every `MIGRATION_INTERVAL` generations the `MIGRANTS` fittest genomes
+//! of each island join the next island (symbols in order, the last one feeding the
+//! first), which keeps the islands from converging alone.
+
+use std::collections::{BTreeMap, HashMap};
+
+use persistence::repository::GaIndividualRecord;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::discovery::{
+    decode_strategy_params, f64_to_dec, generate_ml_guided_grid, DiscoveryResult,
+    DiscoveryStrategyType, STRATEGY_PARAMS_VERSION,
+};
+use crate::store::ResultStore;
+use crate::strategy_validation::retain_valid;
+
+/// Parents bred by an ML-guided cycle
+pub const POPULATION_SIZE: usize = 30;
+
+/// Generations between two migrations of the island model
+pub const MIGRATION_INTERVAL: u32 = 3;
+
+/// Fittest genomes an island sends to the next one at a migration
+pub const MIGRANTS: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct Individual {
+    pub genome: DiscoveryStrategyType,
+    /// Symbol of the genome's best result
+    pub symbol: String,
+    /// Composite score of the genome's best result
+    pub fitness: Decimal,
+}
+
+impl From<&DiscoveryResult> for Individual {
+    fn from(result: &DiscoveryResult) -> Self {
+        Self {
+            genome: result.strategy_type.clone(),
+            symbol: result.symbol.clone(),
+            fitness: result.composite_score,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Population {
+    generation: u32,
+    /// Fittest first
+    individuals: Vec<Individual>,
+}
+
+impl Population {
+    /// Fittest distinct genomes of `results`, at generation 0
+    pub fn from_results(results: &[DiscoveryResult]) -> Self {
+        let mut population = Self::default();
+        population.merge(results);
+        population
+    }
+
+    /// Keep the `POPULATION_SIZE` fittest distinct genomes of the population and
+    /// `results`; a genome already in the population keeps its best fitness
+    pub fn merge(&mut self, results: &[DiscoveryResult]) {
+        let mut ranked: Vec<&DiscoveryResult> = results.iter().collect();
+        ranked.sort_by_key(|r| std::cmp::Reverse(r.composite_score));
+        self.absorb(ranked.into_iter().map(Individual::from));
+    }
+
+    /// Keep the fittest distinct genomes of the population and `individuals`, which
+    /// come fittest first
+    fn absorb(&mut self, individuals: impl IntoIterator<Item = Individual>) {
+        let mut best: HashMap<String, Individual> = HashMap::new();
+        for individual in std::mem::take(&mut self.individuals) {
+            keep_fittest(&mut best, individual);
+        }
+        // Past the population size of new genomes, the rest cannot make it
+        let mut added = 0;
+        for individual in individuals {
+            if added == POPULATION_SIZE {
+                break;
+            }
+            added += usize::from(keep_fittest(&mut best, individual));
+        }
+
+        let mut individuals: Vec<Individual> = best.into_values().collect();
+        individuals.sort_by_key(|i| std::cmp::Reverse(i.fitness));
+        individuals.truncate(POPULATION_SIZE);
+        self.individuals = individuals;
+    }
+
+    /// Merge `results` as the next generation
+    pub fn evolve(&mut self, results: &[DiscoveryResult]) {
+        self.merge(results);
+        self.generation += 1;
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Fittest first
+    pub fn individuals(&self) -> &[Individual] {
+        &self.individuals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.individuals.is_empty()
+    }
+
+    pub fn to_records(&self) -> Vec<GaIndividualRecord> {
+        self.individuals
+            .iter()
+            .enumerate()
+            .filter_map(|(rank, individual)| {
+                Some(GaIndividualRecord {
+                    rank: rank as i64,
+                    genome: serde_json::to_string(&individual.genome).ok()?,
+                    schema_version: STRATEGY_PARAMS_VERSION,
+                    strategy_name: individual.genome.name().to_string(),
+                    symbol: individual.symbol.clone(),
+                    fitness: individual.fitness.to_f64().unwrap_or(0.0),
+                    generation: self.generation as i64,
+                    island: String::new(),
+                    updated_at: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Population of saved records; genomes that no longer decode are dropped
+    pub fn from_records(records: &[GaIndividualRecord]) -> Self {
+        let mut individuals: Vec<Individual> = records
+            .iter()
+            .filter_map(|record| {
+                let genome = decode_strategy_params(&record.genome, record.schema_version)
+                    .map_err(|e| warn!(rank = record.rank, error = %e, "Dropping saved genome"))
+                    .ok()?;
+                Some(Individual {
+                    genome,
+                    symbol: record.symbol.clone(),
+                    fitness: f64_to_dec(record.fitness),
+                })
+            })
+            .collect();
+        individuals.sort_by_key(|i| std::cmp::Reverse(i.fitness));
+        Self {
+            generation: records.iter().map(|r| r.generation).max().unwrap_or(0) as u32,
+            individuals,
+        }
+    }
+
+    /// Population saved in `store` (empty on error)
+    pub(crate) async fn load(store: &dyn ResultStore) -> Self {
+        match store.load_population().await {
+            Ok(records) => {
+                let population = Self::from_records(&records);
+                if !population.is_empty() {
+                    info!(
+                        generation = population.generation,
+                        individuals = population.individuals.len(),
+                        "Resuming evolutionary search from the saved population"
+                    );
+                }
+                population
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to load the saved population, evolving from scratch");
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Statistics of one island, reported in the discovery progress
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IslandStats {
+    pub symbol: String,
+    pub generation: u32,
+    pub individuals: usize,
+    /// Composite score of the fittest genome, `None` while the island is empty
+    pub best_fitness: Option<f64>,
+    pub mean_fitness: Option<f64>,
+    /// Genomes that migrated from other islands (scored on their symbol)
+    pub immigrants: usize,
+}
+
+/// One population per symbol, exchanging their fittest genomes every
+/// `MIGRATION_INTERVAL` generations
+#[derive(Debug, Clone, Default)]
+pub struct IslandModel {
+    generation: u32,
+    /// By symbol
+    islands: BTreeMap<String, Population>,
+}
+
+impl IslandModel {
+    /// An empty island per symbol
+    pub fn new<S: Into<String>>(symbols: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            generation: 0,
+            islands: symbols
+                .into_iter()
+                .map(|symbol| (symbol.into(), Population::default()))
+                .collect(),
+        }
+    }
+
+    /// An empty island for `symbol`, unless it already has one
+    pub fn add_island(&mut self, symbol: &str) {
+        self.islands.entry(symbol.to_string()).or_default();
+    }
+
+    /// Islands of `symbols` from saved records; records of other islands are dropped,
+    /// those saved without an island go to the island of their symbol
+    pub fn from_records<S: Into<String>>(
+        symbols: impl IntoIterator<Item = S>,
+        records: &[GaIndividualRecord],
+    ) -> Self {
+        let mut model = Self::new(symbols);
+        let mut by_island: BTreeMap<&str, Vec<GaIndividualRecord>> = BTreeMap::new();
+        for record in records {
+            let island = if record.island.is_empty() {
+                &record.symbol
+            } else {
+                &record.island
+            };
+            by_island.entry(island).or_default().push(record.clone());
+        }
+        for (symbol, island) in model.islands.iter_mut() {
+            if let Some(records) = by_island.get(symbol.as_str()) {
+                *island = Population::from_records(records);
+            }
+        }
+        model.generation = model
+            .islands
+            .values()
+            .map(Population::generation)
+            .max()
+            .unwrap_or(0);
+        model
+    }
+
+    /// Every island merges the results of its symbol as the next generation, then
+    /// the islands migrate if it is time to
+    pub fn evolve(&mut self, results: &[DiscoveryResult]) {
+        let mut by_symbol: HashMap<&str, Vec<DiscoveryResult>> = HashMap::new();
+        for result in results {
+            if self.islands.contains_key(&result.symbol) {
+                by_symbol
+                    .entry(&result.symbol)
+                    .or_default()
+                    .push(result.clone());
+            }
+        }
+        for (symbol, island) in self.islands.iter_mut() {
+            island.evolve(by_symbol.get(symbol.as_str()).map_or(&[], Vec::as_slice));
+        }
+        self.generation += 1;
+        if self.generation.is_multiple_of(MIGRATION_INTERVAL) {
+            self.migrate();
+        }
+    }
+
+    /// The `MIGRANTS` fittest genomes of each island join the next one
+    fn migrate(&mut self) {
+        if self.islands.len() < 2 {
+            return;
+        }
+        let emigrants: Vec<Vec<Individual>> = self
+            .islands
+            .values()
+            .map(|island| island.individuals.iter().take(MIGRANTS).cloned().collect())
+            .collect();
+        let count = emigrants.len();
+        for (idx, island) in self.islands.values_mut().enumerate() {
+            island.absorb(emigrants[(idx + count - 1) % count].clone());
+        }
+        info!(
+            generation = self.generation,
+            islands = count,
+            "Islands migrated"
+        );
+    }
+
+    /// ML-guided grid of each island, tested on its symbol, without the offspring
+    /// whose parameters do not validate
+    pub fn breed(&self, cycle: u32) -> HashMap<String, Vec<DiscoveryStrategyType>> {
+        self.islands
+            .iter()
+            .map(|(symbol, island)| {
+                let mut grid = generate_ml_guided_grid(island.individuals(), cycle);
+                retain_valid(&mut grid);
+                (symbol.clone(), grid)
+            })
+            .collect()
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn island(&self, symbol: &str) -> Option<&Population> {
+        self.islands.get(symbol)
+    }
+
+    pub fn stats(&self) -> Vec<IslandStats> {
+        self.islands
+            .iter()
+            .map(|(symbol, island)| {
+                let fitness: Vec<f64> = island
+                    .individuals
+                    .iter()
+                    .map(|i| i.fitness.to_f64().unwrap_or(0.0))
+                    .collect();
+                IslandStats {
+                    symbol: symbol.clone(),
+                    generation: self.generation,
+                    individuals: fitness.len(),
+                    best_fitness: fitness.first().copied(),
+                    mean_fitness: (!fitness.is_empty())
+                        .then(|| fitness.iter().sum::<f64>() / fitness.len() as f64),
+                    immigrants: island
+                        .individuals
+                        .iter()
+                        .filter(|i| &i.symbol != symbol)
+                        .count(),
+                }
+            })
+            .collect()
+    }
+
+    /// Records of every island, ranked across islands
+    pub fn to_records(&self) -> Vec<GaIndividualRecord> {
+        let mut records = Vec::new();
+        for (symbol, island) in &self.islands {
+            for mut record in island.to_records() {
+                record.rank = records.len() as i64;
+                record.island = symbol.clone();
+                record.generation = self.generation as i64;
+                records.push(record);
+            }
+        }
+        records
+    }
+
+    /// Islands of `symbols` saved in `store` (empty on error)
+    pub(crate) async fn load(store: &dyn ResultStore, symbols: &[String]) -> Self {
+        match store.load_population().await {
+            Ok(records) => {
+                let model = Self::from_records(symbols.iter().cloned(), &records);
+                if model.generation > 0 {
+                    info!(
+                        generation = model.generation,
+                        islands = model.islands.len(),
+                        "Resuming evolutionary search from the saved islands"
+                    );
+                }
+                model
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to load the saved islands, evolving from scratch");
+                Self::new(symbols.iter().cloned())
+            }
+        }
+    }
+
+    /// Replace the islands saved in `store`; errors are only logged
+    pub(crate) async fn save(&self, store: &dyn ResultStore) {
+        if let Err(e) = store.save_population(&self.to_records()).await {
+            warn!(generation = self.generation, error = %e, "Failed to save the islands");
+        }
+    }
+}
+
+/// Add `individual` to `best`, or keep the fitter of the two of its genome; true if
+/// its genome was new
+fn keep_fittest(best: &mut HashMap<String, Individual>, individual: Individual) -> bool {
+    let Ok(key) = serde_json::to_string(&individual.genome) else {
+        return false;
+    };
+    match best.get_mut(&key) {
+        Some(known) => {
+            if individual.fitness > known.fitness {
+                *known = individual;
+            }
+            false
+        }
+        None => {
+            best.insert(key, individual);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    use crate::discovery::{run_backtest, BacktestLimits, SizingMode};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_population_keeps_fittest_genomes_across_generations() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 500,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let result = |period: usize, score: Decimal| DiscoveryResult {
+            composite_score: score,
+            ..run_backtest(
+                &DiscoveryStrategyType::Rsi {
+                    period,
+                    overbought: 70.0,
+                    oversold: 30.0,
+                },
+                &klines,
+                "SYNTH",
+                SizingMode::Fixed,
+                &BacktestLimits::default(),
+            )
+        };
+
+        // More distinct genomes than the population holds, one of them twice
+        let mut results: Vec<_> = (0..40).map(|i| result(5 + i, Decimal::from(i))).collect();
+        results.push(result(44, dec!(100)));
+        let mut population = Population::from_results(&results);
+        assert_eq!(population.generation(), 0);
+        assert_eq!(population.individuals().len(), POPULATION_SIZE);
+        assert_eq!(population.individuals()[0].fitness, dec!(100));
+        assert_eq!(population.individuals()[1].fitness, dec!(38));
+        assert_eq!(population.individuals()[29].fitness, dec!(10));
+
+        // A saved population comes back as it was
+        let records = population.to_records();
+        assert_eq!(records[0].rank, 0);
+        let restored = Population::from_records(&records);
+        assert_eq!(restored.individuals().len(), POPULATION_SIZE);
+        assert_eq!(
+            serde_json::to_string(&restored.individuals()[0].genome).unwrap(),
+            records[0].genome
+        );
+
+        // The next generation of a restarted run breeds from the saved parents and its
+        // own results
+        let mut resumed = Population::from_records(&records);
+        resumed.evolve(&[result(80, dec!(50))]);
+        assert_eq!(resumed.generation(), 1);
+        assert_eq!(resumed.individuals().len(), POPULATION_SIZE);
+        assert_eq!(resumed.individuals()[1].fitness, dec!(50));
+        assert_eq!(resumed.individuals()[29].fitness, dec!(11));
+
+        // Undecodable genomes are dropped
+        let mut broken = records.clone();
+        broken[0].genome = "{\"type\":\"retired_strategy\"}".to_string();
+        broken[0].generation = 7;
+        let partial = Population::from_records(&broken);
+        assert_eq!(partial.individuals().len(), POPULATION_SIZE - 1);
+        assert_eq!(partial.generation(), 7);
+
+        population.evolve(&[]);
+        assert_eq!(population.generation(), 1);
+        assert_eq!(population.individuals()[0].fitness, dec!(100));
+    }
+
+    #[test]
+    fn test_islands_select_per_symbol_and_migrate() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 500,
+            ..SyntheticConfig::for_model(SyntheticModel::Trending)
+        });
+        let result = |symbol: &str, period: usize, score: i64| DiscoveryResult {
+            composite_score: Decimal::from(score),
+            ..run_backtest(
+                &DiscoveryStrategyType::Rsi {
+                    period,
+                    overbought: 70.0,
+                    oversold: 30.0,
+                },
+                &klines,
+                symbol,
+                SizingMode::Fixed,
+                &BacktestLimits::default(),
+            )
+        };
+        let periods = |island: &Population| -> Vec<usize> {
+            island
+                .individuals()
+                .iter()
+                .map(|i| match i.genome {
+                    DiscoveryStrategyType::Rsi { period, .. } => period,
+                    _ => unreachable!(),
+                })
+                .collect()
+        };
+
+        // Each island selects on its symbol only; symbols outside the run are ignored
+        let mut model = IslandModel::new(["BTCUSDT", "ETHUSDT"]);
+        let results = vec![
+            result("BTCUSDT", 10, 5),
+            result("BTCUSDT", 11, 9),
+            result("ETHUSDT", 20, 7),
+            result("SOLUSDT", 30, 100),
+        ];
+        model.evolve(&results);
+        assert_eq!(periods(model.island("BTCUSDT").unwrap()), [11, 10]);
+        assert_eq!(periods(model.island("ETHUSDT").unwrap()), [20]);
+        let grids = model.breed(3);
+        assert_eq!(grids.len(), 2);
+        assert!(grids.values().all(|grid| !grid.is_empty()));
+
+        // Every MIGRATION_INTERVAL generations the elites join the next island
+        for _ in 1..MIGRATION_INTERVAL {
+            model.evolve(&[]);
+        }
+        assert_eq!(model.generation(), MIGRATION_INTERVAL);
+        assert_eq!(periods(model.island("BTCUSDT").unwrap()), [11, 20, 10]);
+        assert_eq!(periods(model.island("ETHUSDT").unwrap()), [11, 20, 10]);
+        let stats = model.stats();
+        assert_eq!(stats[0].symbol, "BTCUSDT");
+        assert_eq!((stats[0].individuals, stats[0].immigrants), (3, 1));
+        assert_eq!(stats[1].best_fitness, Some(9.0));
+        assert_eq!(stats[1].mean_fitness, Some(7.0));
+
+        // Saved and restored island by island; records saved before islands join the
+        // island of their symbol
+        let records = model.to_records();
+        assert_eq!(records.len(), 6);
+        assert_eq!(records[3].rank, 3);
+        assert_eq!(records[3].island, "ETHUSDT");
+        let restored = IslandModel::from_records(["BTCUSDT", "ETHUSDT"], &records);
+        assert_eq!(restored.generation(), MIGRATION_INTERVAL);
+        assert_eq!(periods(restored.island("ETHUSDT").unwrap()), [11, 20, 10]);
+        let legacy = Population::from_results(&results).to_records();
+        let resumed = IslandModel::from_records(["ETHUSDT"], &legacy);
+        assert_eq!(periods(resumed.island("ETHUSDT").unwrap()), [20]);
+    }
+}
@@ -0,0 +1,570 @@
+//! Polymarket probability models
+//!
+//! Backtests trade Binance prices, but the Polymarket fee depends on the share price
+//! p (the market's probability): `fee = C × rate × (p × (1 - p))^2`. A
+//! `ProbabilityModel` estimates p from the move since a baseline price:
+//!
+//! - `LinearModel` — the original mapping, 0.50 + 0.05 per % of move, clamped to
+//!   [0.05, 0.95]
+//! - `LogisticModel` — `1 / (1 + e^(-slope × move %))`, the slope fitted on the prices
+//!   of resolved up/down markets (`calibrate_logistic`)
+//! - `ConstantModel` — a fixed p (0.50 prices every trade at the maximum fee)
+//!
+//! `ProbabilityModelConfig` selects one per request and is what results record.
+
+use anyhow::{bail, Context};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::api::{BinanceClient, PolymarketDataClient};
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::resolution::{fetch_resolved_markets, market_asset};
+use crate::types::Kline;
+
+/// Logistic slope when no calibration is available: the linear model's slope at
+/// p = 0.50 (0.05 per %, i.e. slope / 4)
+pub const DEFAULT_LOGISTIC_SLOPE: f64 = 0.2;
+
+/// Probabilities a logistic or constant model can return
+const MIN_PROBABILITY: f64 = 0.01;
+const MAX_PROBABILITY: f64 = 0.99;
+
+/// Samples below which a calibration is rejected
+const MIN_CALIBRATION_SAMPLES: usize = 20;
+
+/// Markets used by a calibration (each costs one price-history request)
+const MAX_CALIBRATION_MARKETS: usize = 200;
+
+const DAY_MS: i64 = 86_400_000;
+const MINUTE_MS: i64 = 60_000;
+
+/// Market probability of a trade at `price` when the reference price was `baseline_price`
+pub trait ProbabilityModel: Send + Sync {
+    /// Name recorded with each result, e.g. `logistic(slope=0.2)`
+    fn name(&self) -> String;
+
+    /// Probability in (0, 1), rounded to 4 decimals
+    fn probability(&self, baseline_price: f64, price: f64) -> f64;
+
+    /// Decimal version of [`ProbabilityModel::probability`]
+    fn probability_dec(&self, baseline_price: Decimal, price: Decimal) -> Decimal {
+        let p = self.probability(
+            baseline_price.to_f64().unwrap_or(0.0),
+            price.to_f64().unwrap_or(0.0),
+        );
+        Decimal::from_f64(p).map_or(Decimal::new(5, 1), |p| p.round_dp(4))
+    }
+}
+
+fn change_pct(baseline_price: f64, price: f64) -> Option<f64> {
+    (baseline_price > 0.0).then(|| (price - baseline_price) / baseline_price * 100.0)
+}
+
+fn round4(p: f64) -> f64 {
+    (p * 10_000.0).round() / 10_000.0
+}
+
+/// 0.50 + 0.05 per % of move, clamped to [0.05, 0.95]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinearModel;
+
+impl ProbabilityModel for LinearModel {
+    fn name(&self) -> String {
+        "linear".to_string()
+    }
+
+    fn probability(&self, baseline_price: f64, price: f64) -> f64 {
+        crate::discovery::estimate_poly_probability_f64(baseline_price, price)
+    }
+
+    fn probability_dec(&self, baseline_price: Decimal, price: Decimal) -> Decimal {
+        crate::discovery::estimate_poly_probability(baseline_price, price)
+    }
+}
+
+/// `1 / (1 + e^(-slope × move %))`
+#[derive(Debug, Clone, Copy)]
+pub struct LogisticModel {
+    pub slope: f64,
+}
+
+impl ProbabilityModel for LogisticModel {
+    fn name(&self) -> String {
+        format!("logistic(slope={})", self.slope)
+    }
+
+    fn probability(&self, baseline_price: f64, price: f64) -> f64 {
+        let Some(change) = change_pct(baseline_price, price) else {
+            return 0.5;
+        };
+        let p = 1.0 / (1.0 + (-self.slope * change).exp());
+        round4(p.clamp(MIN_PROBABILITY, MAX_PROBABILITY))
+    }
+}
+
+/// The same probability for every trade
+#[derive(Debug, Clone, Copy)]
+pub struct ConstantModel {
+    pub probability: f64,
+}
+
+impl ProbabilityModel for ConstantModel {
+    fn name(&self) -> String {
+        format!("constant(p={})", self.probability)
+    }
+
+    fn probability(&self, _baseline_price: f64, _price: f64) -> f64 {
+        round4(self.probability.clamp(MIN_PROBABILITY, MAX_PROBABILITY))
+    }
+}
+
+/// Probability model of a request
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum ProbabilityModelConfig {
+    #[default]
+    Linear,
+    /// Without a slope, the run calibrates one on recent resolved markets first
+    Logistic {
+        #[serde(default)]
+        slope: Option<f64>,
+    },
+    Constant {
+        probability: f64,
+    },
+}
+
+impl ProbabilityModelConfig {
+    /// `linear`, `logistic` (calibrated), `logistic:<slope>`, `constant` (0.5) or
+    /// `constant:<p>`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim().to_lowercase();
+        let (name, value) = match s.split_once(':') {
+            Some((name, value)) => (name, Some(value.trim())),
+            None => (s.as_str(), None),
+        };
+        let number = |v: &str| {
+            let v = v.rsplit('=').next().unwrap_or(v).trim();
+            v.parse::<f64>()
+                .map_err(|_| format!("Invalid {} parameter {:?}", name, v))
+        };
+        let config = match (name, value) {
+            ("linear", None) => Self::Linear,
+            ("logistic", None) => Self::Logistic { slope: None },
+            ("logistic", Some(v)) => Self::Logistic {
+                slope: Some(number(v)?),
+            },
+            ("constant", None) => Self::Constant { probability: 0.5 },
+            ("constant", Some(v)) => Self::Constant {
+                probability: number(v)?,
+            },
+            _ => {
+                return Err(format!(
+                    "Unknown probability model {:?}: use linear, logistic or constant",
+                    s
+                ))
+            }
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Logistic { slope: Some(slope) } if !slope.is_finite() || *slope <= 0.0 => {
+                Err("logistic slope must be positive".to_string())
+            }
+            Self::Constant { probability }
+                if probability.is_nan() || *probability <= 0.0 || *probability >= 1.0 =>
+            {
+                Err("constant probability must be between 0 and 1".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Model of a recorded name (see [`ProbabilityModel::name`]); NULL or unknown names
+    /// are the linear model every record had before the choice existed
+    pub fn from_name(name: Option<&str>) -> Self {
+        let param = |prefix: &str| {
+            name?
+                .strip_prefix(prefix)?
+                .strip_suffix(')')?
+                .parse::<f64>()
+                .ok()
+        };
+        if let Some(slope) = param("logistic(slope=") {
+            Self::Logistic { slope: Some(slope) }
+        } else if let Some(probability) = param("constant(p=") {
+            Self::Constant { probability }
+        } else {
+            Self::Linear
+        }
+    }
+
+    /// A logistic model still waiting for its calibrated slope
+    pub fn needs_calibration(&self) -> bool {
+        matches!(self, Self::Logistic { slope: None })
+    }
+}
+
+impl ProbabilityModel for ProbabilityModelConfig {
+    fn name(&self) -> String {
+        match self {
+            Self::Linear => LinearModel.name(),
+            Self::Logistic { slope } => LogisticModel {
+                slope: slope.unwrap_or(DEFAULT_LOGISTIC_SLOPE),
+            }
+            .name(),
+            Self::Constant { probability } => ConstantModel {
+                probability: *probability,
+            }
+            .name(),
+        }
+    }
+
+    fn probability(&self, baseline_price: f64, price: f64) -> f64 {
+        match self {
+            Self::Linear => LinearModel.probability(baseline_price, price),
+            Self::Logistic { slope } => LogisticModel {
+                slope: slope.unwrap_or(DEFAULT_LOGISTIC_SLOPE),
+            }
+            .probability(baseline_price, price),
+            Self::Constant { probability } => ConstantModel {
+                probability: *probability,
+            }
+            .probability(baseline_price, price),
+        }
+    }
+
+    fn probability_dec(&self, baseline_price: Decimal, price: Decimal) -> Decimal {
+        match self {
+            Self::Linear => LinearModel.probability_dec(baseline_price, price),
+            _ => {
+                let p = self.probability(
+                    baseline_price.to_f64().unwrap_or(0.0),
+                    price.to_f64().unwrap_or(0.0),
+                );
+                Decimal::from_f64(p).map_or(Decimal::new(5, 1), |p| p.round_dp(4))
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Calibration
+// ============================================================================
+
+/// Up price of a live market against the move of its asset since the market opened
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProbabilitySample {
+    pub change_pct: f64,
+    pub up_price: f64,
+}
+
+/// Slope fitted on the resolved markets of a symbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LogisticCalibration {
+    pub symbol: String,
+    pub markets: usize,
+    pub samples: usize,
+    pub slope: f64,
+    /// Root mean square error of the fitted probabilities
+    pub rmse: f64,
+    /// Same error under the linear model, for comparison
+    pub linear_rmse: f64,
+}
+
+/// Least-squares slope of `logit(p) = slope × move %` (a line through the origin: no
+/// move means an even market). `None` with too few samples or no fitted edge.
+pub fn fit_logistic_slope(samples: &[ProbabilitySample]) -> Option<f64> {
+    if samples.len() < MIN_CALIBRATION_SAMPLES {
+        return None;
+    }
+    let (mut xy, mut xx) = (0.0, 0.0);
+    for s in samples {
+        let p = s.up_price.clamp(MIN_PROBABILITY, MAX_PROBABILITY);
+        xy += s.change_pct * (p / (1.0 - p)).ln();
+        xx += s.change_pct * s.change_pct;
+    }
+    let slope = xy / xx;
+    (slope.is_finite() && slope > 0.0).then(|| (slope * 100.0).round() / 100.0)
+}
+
+/// Root mean square error of `model` on the samples (the move is applied to a baseline of 1)
+pub fn calibration_rmse(model: &dyn ProbabilityModel, samples: &[ProbabilitySample]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sse: f64 = samples
+        .iter()
+        .map(|s| {
+            let p = model.probability(1.0, 1.0 + s.change_pct / 100.0);
+            (p - s.up_price).powi(2)
+        })
+        .sum();
+    (sse / samples.len() as f64).sqrt()
+}
+
+/// Samples of one market: every price-history point inside the window against the
+/// 1m kline it falls in (`klines` sorted by open time)
+fn market_samples(
+    klines: &[Kline],
+    start_time: i64,
+    end_time: i64,
+    history: &[(i64, f64)],
+) -> Vec<ProbabilitySample> {
+    let kline_at = |t: i64| {
+        let i = klines.partition_point(|k| k.open_time <= t);
+        i.checked_sub(1)
+            .map(|i| &klines[i])
+            .filter(|k| t - k.open_time < MINUTE_MS)
+    };
+    let Some(open) = kline_at(start_time).and_then(|k| k.open.to_f64()) else {
+        return Vec::new();
+    };
+    history
+        .iter()
+        .filter(|(t, p)| *t > start_time && *t < end_time && *p > 0.0 && *p < 1.0)
+        .filter_map(|&(t, up_price)| {
+            let price = kline_at(t)?.open.to_f64()?;
+            Some(ProbabilitySample {
+                change_pct: change_pct(open, price)?,
+                up_price,
+            })
+        })
+        .filter(|s| s.change_pct != 0.0)
+        .collect()
+}
+
+/// Fit the logistic slope on the up/down markets of `symbol` resolved in the last
+/// `days`: CLOB price history of the Up share against Binance 1m klines
+pub async fn calibrate_logistic(
+    binance: &BinanceClient,
+    polymarket: &PolymarketDataClient,
+    symbol: &str,
+    days: i64,
+) -> anyhow::Result<LogisticCalibration> {
+    let end_time = chrono::Utc::now().timestamp_millis();
+    let markets = fetch_resolved_markets(
+        polymarket,
+        symbol,
+        end_time - days * DAY_MS,
+        MAX_CALIBRATION_MARKETS,
+    )
+    .await?;
+    let Some(first) = markets.first() else {
+        bail!(
+            "No resolved up/down markets for {} in the last {} days",
+            symbol,
+            days
+        );
+    };
+    let (klines, quality) = fetch_checked_klines(
+        binance,
+        symbol,
+        "1m",
+        first.start_time,
+        end_time,
+        &DataQualityConfig::default(),
+    )
+    .await?;
+    quality.warn_if_degraded();
+
+    let mut samples = Vec::new();
+    for market in &markets {
+        let Some(token) = market.token_id_up.as_deref() else {
+            continue;
+        };
+        match polymarket
+            .get_prices_history(token, market.start_time / 1000, market.end_time / 1000)
+            .await
+        {
+            Ok(points) => {
+                let history: Vec<(i64, f64)> = points.iter().map(|p| (p.t * 1000, p.p)).collect();
+                samples.extend(market_samples(
+                    &klines,
+                    market.start_time,
+                    market.end_time,
+                    &history,
+                ));
+            }
+            Err(e) => warn!(condition_id = %market.condition_id, error = %e, "No price history"),
+        }
+    }
+
+    let slope = fit_logistic_slope(&samples).with_context(|| {
+        format!(
+            "{} usable price points on {} markets, need {}",
+            samples.len(),
+            markets.len(),
+            MIN_CALIBRATION_SAMPLES
+        )
+    })?;
+    let calibration = LogisticCalibration {
+        symbol: symbol.to_string(),
+        markets: markets.len(),
+        samples: samples.len(),
+        slope,
+        rmse: calibration_rmse(&LogisticModel { slope }, &samples),
+        linear_rmse: calibration_rmse(&LinearModel, &samples),
+    };
+    info!(
+        symbol,
+        markets = calibration.markets,
+        samples = calibration.samples,
+        slope,
+        "Logistic probability model calibrated"
+    );
+    Ok(calibration)
+}
+
+/// The model a run prices fees with: a logistic model without a slope is calibrated on
+/// the first symbol with up/down markets, falling back to `DEFAULT_LOGISTIC_SLOPE`
+pub async fn resolve_probability_model(
+    config: ProbabilityModelConfig,
+    symbols: &[String],
+    binance: &BinanceClient,
+) -> ProbabilityModelConfig {
+    if !config.needs_calibration() {
+        return config;
+    }
+    let polymarket = PolymarketDataClient::new();
+    for symbol in symbols.iter().filter(|s| market_asset(s).is_some()) {
+        match calibrate_logistic(binance, &polymarket, symbol, 2).await {
+            Ok(calibration) => {
+                return ProbabilityModelConfig::Logistic {
+                    slope: Some(calibration.slope),
+                }
+            }
+            Err(e) => warn!(symbol = %symbol, error = %e, "Logistic calibration failed"),
+        }
+    }
+    warn!(
+        slope = DEFAULT_LOGISTIC_SLOPE,
+        "No calibration available, using the default logistic slope"
+    );
+    ProbabilityModelConfig::Logistic {
+        slope: Some(DEFAULT_LOGISTIC_SLOPE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_probability_models() {
+        // The linear model is the original estimate
+        for price in [90.0, 99.5, 100.0, 101.0, 140.0] {
+            assert_eq!(
+                LinearModel.probability(100.0, price),
+                crate::discovery::estimate_poly_probability_f64(100.0, price)
+            );
+        }
+        assert_eq!(LinearModel.probability_dec(dec!(100), dec!(102)), dec!(0.6));
+
+        let logistic = LogisticModel { slope: 2.0 };
+        assert_eq!(logistic.probability(100.0, 100.0), 0.5);
+        // Symmetric around an even market, saturates without kinks
+        let up = logistic.probability(100.0, 100.5);
+        let down = logistic.probability(100.0, 99.5);
+        assert!((up + down - 1.0).abs() < 1e-4);
+        assert_eq!(up, 0.7311);
+        assert_eq!(logistic.probability(100.0, 150.0), MAX_PROBABILITY);
+        assert_eq!(logistic.probability(0.0, 150.0), 0.5);
+
+        assert_eq!(
+            ConstantModel { probability: 0.3 }.probability(1.0, 9.0),
+            0.3
+        );
+
+        assert_eq!(
+            ProbabilityModelConfig::parse("logistic:slope=1.5"),
+            Ok(ProbabilityModelConfig::Logistic { slope: Some(1.5) })
+        );
+        assert_eq!(
+            ProbabilityModelConfig::parse("constant"),
+            Ok(ProbabilityModelConfig::Constant { probability: 0.5 })
+        );
+        assert!(ProbabilityModelConfig::parse("logistic:-1").is_err());
+        assert!(ProbabilityModelConfig::parse("constant:1.2").is_err());
+        assert!(ProbabilityModelConfig::parse("probit").is_err());
+        assert!(ProbabilityModelConfig::parse("logistic")
+            .unwrap()
+            .needs_calibration());
+        assert_eq!(
+            ProbabilityModelConfig::Logistic { slope: Some(1.5) }.name(),
+            "logistic(slope=1.5)"
+        );
+        let json = r#"{"model":"constant","probability":0.4}"#;
+        let config: ProbabilityModelConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.name(), "constant(p=0.4)");
+        for config in [
+            config,
+            ProbabilityModelConfig::Linear,
+            ProbabilityModelConfig::Logistic { slope: Some(7.25) },
+        ] {
+            assert_eq!(
+                ProbabilityModelConfig::from_name(Some(&config.name())),
+                config
+            );
+        }
+        assert_eq!(
+            ProbabilityModelConfig::from_name(None),
+            ProbabilityModelConfig::Linear
+        );
+    }
+
+    #[test]
+    fn test_calibration_recovers_slope() {
+        // 1m klines moving 0.1 % a minute, prices from a slope-3 market
+        const T0: i64 = 1_700_000_100_000;
+        let klines: Vec<Kline> = (0..30)
+            .map(|i| {
+                let price = Decimal::from_f64(100.0 * (1.0 + 0.001 * (i % 10) as f64)).unwrap();
+                let open_time = T0 + i as i64 * MINUTE_MS;
+                Kline {
+                    open_time,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Decimal::ONE,
+                    close_time: open_time + MINUTE_MS - 1,
+                }
+            })
+            .collect();
+        let truth = LogisticModel { slope: 3.0 };
+        let mut samples = Vec::new();
+        for window in 0..3 {
+            let start = T0 + window * 10 * MINUTE_MS;
+            let history: Vec<(i64, f64)> = (1..10)
+                .map(|m| {
+                    let t = start + m * MINUTE_MS + 30_000;
+                    let price = 100.0 * (1.0 + 0.001 * m as f64);
+                    (t, truth.probability(100.0, price))
+                })
+                .collect();
+            samples.extend(market_samples(
+                &klines,
+                start,
+                start + 10 * MINUTE_MS,
+                &history,
+            ));
+        }
+        assert_eq!(samples.len(), 27);
+        assert!((samples[0].change_pct - 0.1).abs() < 1e-9);
+
+        let slope = fit_logistic_slope(&samples).unwrap();
+        assert!((slope - 3.0).abs() < 0.05, "slope {slope}");
+        assert!(calibration_rmse(&LogisticModel { slope }, &samples) < 0.001);
+        assert!(calibration_rmse(&LinearModel, &samples) > 0.01);
+        assert_eq!(fit_logistic_slope(&samples[..5]), None);
+    }
+}
@@ -423,6 +423,7 @@ fn analyze_markets(
 }
 
 /// Infer trading strategy for a single market based on trade patterns
+#[allow(clippy::too_many_arguments)]
 fn infer_market_strategy(
     buy_count: usize,
     sell_count: usize,
@@ -1225,7 +1226,7 @@ mod tests {
             make_trade("SELL", 0.70, 100.0, "cid1", "evt1", 1700086500.0),
         ];
         let timeline = compute_activity_timeline(&trades);
-        assert!(timeline.len() >= 1); // At least one day
+        assert!(!timeline.is_empty()); // At least one day
         let total_trades: usize = timeline.iter().map(|p| p.trade_count).sum();
         assert_eq!(total_trades, 3);
     }
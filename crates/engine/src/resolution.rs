@@ -0,0 +1,426 @@
+//! Backtests on resolved Polymarket up/down markets
+//!
+//! Discovery scores strategies on Binance price moves with Polymarket-like fees. Here a
+//! strategy bets on real resolved "<Asset> Up or Down" markets instead: the signal of the
+//! last 15m bar closed before a market opens picks the side (Buy → Up, Sell → Down,
+//! Hold → no bet), shares are bought at the market's opening price (CLOB price history,
+//! 0.50 when unknown) with the taker fee, and pay 1 USDC if the market resolved that way.
+
+use anyhow::{bail, Context};
+use persistence::repository::DiscoveryBacktestRecord;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::api::polymarket::{GammaMarket, PolymarketDataClient};
+use crate::api::BinanceClient;
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::discovery::DiscoveryStrategyType;
+use crate::fees::{calculate_taker_fee_f64, PolymarketFeeConfig};
+use crate::indicators::build_signal_generator;
+use crate::orderbook_backtest::{parse_clob_token_ids, parse_market_outcome};
+use crate::risk::paper_strategy;
+use crate::strategy::Signal;
+use crate::types::Kline;
+
+const DAY_MS: i64 = 86_400_000;
+const BAR_MS: i64 = 900_000;
+
+/// Klines fetched before the first market so the indicators are warmed up
+const WARMUP_DAYS: i64 = 3;
+
+/// Markets pulled per run (each costs one price-history request)
+pub const MAX_RESOLVED_MARKETS: usize = 1_000;
+
+/// Entry price when the CLOB has no price history for a market
+const FALLBACK_PRICE: f64 = 0.5;
+
+const PAGE_SIZE: u32 = 100;
+
+/// Pages in a row whose matching markets all ended before the window
+const MAX_OLD_PAGES: u32 = 5;
+
+/// Side bought on an up/down market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MarketSide {
+    Up,
+    Down,
+}
+
+/// Resolved up/down market (times in Unix ms)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedMarket {
+    pub condition_id: String,
+    pub question: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub up_won: bool,
+    /// Price of the Up share when the market opened
+    pub up_price: Option<f64>,
+    pub token_id_up: Option<String>,
+}
+
+impl ResolvedMarket {
+    /// Closed up/down market with a known outcome and window
+    pub fn from_gamma(m: &GammaMarket) -> Option<Self> {
+        let question = m.question.clone()?;
+        let window = market_window_ms(&question)?;
+        let end_date = m.end_date.as_deref()?;
+        let end_time = chrono::DateTime::parse_from_rfc3339(end_date)
+            .ok()?
+            .timestamp_millis();
+        let (outcome, _, _) = parse_market_outcome(m.outcome_prices.as_deref());
+        let (token_id_up, _) = parse_clob_token_ids(m.clob_token_ids.as_deref());
+        Some(Self {
+            condition_id: m.condition_id.clone()?,
+            question,
+            start_time: end_time - window,
+            end_time,
+            up_won: outcome? == "Up",
+            up_price: None,
+            token_id_up,
+        })
+    }
+}
+
+/// Length of an up/down market from its question: "2:45PM-3:00PM ET" is a 15-minute
+/// market, "3PM ET" an hourly one. Daily and weekly up/down markets are skipped.
+pub fn market_window_ms(question: &str) -> Option<i64> {
+    let q = question.to_lowercase();
+    if !q.contains("up or down") {
+        return None;
+    }
+    if q.contains(':') && (q.contains("am-") || q.contains("pm-")) {
+        return Some(BAR_MS);
+    }
+    let hourly = q.split(|c: char| !c.is_ascii_alphanumeric()).any(|word| {
+        let hour = word.trim_end_matches("am").trim_end_matches("pm");
+        hour.len() < word.len() && !hour.is_empty() && hour.chars().all(|c| c.is_ascii_digit())
+    });
+    hourly.then_some(4 * BAR_MS)
+}
+
+/// Asset name opening the up/down questions of a Binance symbol
+pub fn market_asset(symbol: &str) -> Option<&'static str> {
+    match symbol.to_uppercase().trim_end_matches("USDT") {
+        "BTC" => Some("bitcoin"),
+        "ETH" => Some("ethereum"),
+        "SOL" => Some("solana"),
+        "XRP" => Some("xrp"),
+        _ => None,
+    }
+}
+
+/// One bet of a resolution backtest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResolutionTrade {
+    pub condition_id: String,
+    pub start_time: i64,
+    pub side: MarketSide,
+    pub entry_price: f64,
+    pub shares: f64,
+    pub fee: f64,
+    pub won: bool,
+    /// Payout − stake − fee, in USDC
+    pub pnl: f64,
+}
+
+/// Strategy bets on resolved markets
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResolutionBacktest {
+    /// Resolved markets in the window
+    pub markets: usize,
+    /// Markets with a CLOB opening price (the others are bought at 0.50)
+    pub priced_markets: usize,
+    /// USDC staked per bet
+    pub stake: f64,
+    pub trades: u32,
+    pub wins: u32,
+    pub win_rate: f64,
+    /// Net of fees, in USDC
+    pub net_pnl: f64,
+    pub total_fees: f64,
+    /// Net PnL over the total staked, in %
+    pub roi_pct: f64,
+    pub bets: Vec<ResolutionTrade>,
+}
+
+/// Bet `stake` USDC on each market the strategy has a signal for. `markets` are taken
+/// in start order; a market without a bar closed in the 15 minutes before it is skipped.
+pub fn resolution_backtest(
+    strategy: &DiscoveryStrategyType,
+    klines: &[Kline],
+    markets: &[ResolvedMarket],
+    stake: f64,
+) -> ResolutionBacktest {
+    let fee_config = PolymarketFeeConfig::default();
+    let mut generator = build_signal_generator(strategy);
+    let mut markets: Vec<&ResolvedMarket> = markets.iter().collect();
+    markets.sort_by_key(|m| m.start_time);
+
+    let mut report = ResolutionBacktest {
+        markets: markets.len(),
+        priced_markets: markets.iter().filter(|m| m.up_price.is_some()).count(),
+        stake,
+        ..Default::default()
+    };
+    let mut bars = klines.iter().peekable();
+    let mut last_bar: Option<(i64, Signal)> = None;
+
+    for market in markets {
+        while let Some(kline) = bars.next_if(|k| k.close_time < market.start_time) {
+            last_bar = Some((kline.close_time, generator.on_bar(kline).signal));
+        }
+        let side = match last_bar {
+            Some((close_time, signal)) if close_time >= market.start_time - BAR_MS => {
+                match signal {
+                    Signal::Buy => MarketSide::Up,
+                    Signal::Sell => MarketSide::Down,
+                    Signal::Hold => continue,
+                }
+            }
+            _ => continue,
+        };
+
+        let up_price = market.up_price.unwrap_or(FALLBACK_PRICE);
+        let entry_price = match side {
+            MarketSide::Up => up_price,
+            MarketSide::Down => 1.0 - up_price,
+        };
+        if entry_price <= 0.0 || entry_price >= 1.0 {
+            continue;
+        }
+        let shares = stake / entry_price;
+        let fee = calculate_taker_fee_f64(shares, entry_price, &fee_config);
+        let won = (side == MarketSide::Up) == market.up_won;
+        let payout = if won { shares } else { 0.0 };
+        let pnl = payout - stake - fee;
+
+        report.trades += 1;
+        if won {
+            report.wins += 1;
+        }
+        report.net_pnl += pnl;
+        report.total_fees += fee;
+        report.bets.push(ResolutionTrade {
+            condition_id: market.condition_id.clone(),
+            start_time: market.start_time,
+            side,
+            entry_price,
+            shares,
+            fee,
+            won,
+            pnl,
+        });
+    }
+
+    if report.trades > 0 {
+        report.win_rate = report.wins as f64 / report.trades as f64 * 100.0;
+        report.roi_pct = report.net_pnl / (report.trades as f64 * stake) * 100.0;
+    }
+    report
+}
+
+/// Closed up/down markets of `symbol` that ended after `since` (Unix ms), newest first
+/// through the Gamma API, with the Up price at each market's open from the CLOB
+pub async fn fetch_resolved_markets(
+    client: &PolymarketDataClient,
+    symbol: &str,
+    since: i64,
+    max_markets: usize,
+) -> anyhow::Result<Vec<ResolvedMarket>> {
+    let asset = market_asset(symbol)
+        .with_context(|| format!("no Polymarket up/down markets for {}", symbol))?;
+    let mut markets: Vec<ResolvedMarket> = Vec::new();
+    let mut offset = 0u32;
+    let mut old_pages = 0u32;
+
+    while markets.len() < max_markets && old_pages < MAX_OLD_PAGES && offset <= 100_000 {
+        let page = client
+            .search_markets(offset, PAGE_SIZE, Some(true), true)
+            .await?;
+        let page_len = page.len() as u32;
+        let matching: Vec<ResolvedMarket> = page
+            .iter()
+            .filter(|m| {
+                m.question
+                    .as_deref()
+                    .is_some_and(|q| q.to_lowercase().starts_with(asset))
+            })
+            .filter_map(ResolvedMarket::from_gamma)
+            .collect();
+        if !matching.is_empty() && matching.iter().all(|m| m.end_time < since) {
+            old_pages += 1;
+        } else if !matching.is_empty() {
+            old_pages = 0;
+        }
+        for market in matching {
+            let known = markets
+                .iter()
+                .any(|m| m.condition_id == market.condition_id);
+            if market.end_time >= since && !known && markets.len() < max_markets {
+                markets.push(market);
+            }
+        }
+        if page_len < PAGE_SIZE {
+            break;
+        }
+        offset += PAGE_SIZE;
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    for market in markets.iter_mut() {
+        let Some(token) = market.token_id_up.as_deref() else {
+            continue;
+        };
+        let start = market.start_time / 1000;
+        match client.get_prices_history(token, start, start + 300).await {
+            Ok(points) => {
+                market.up_price = points.first().map(|p| p.p).filter(|p| *p > 0.0 && *p < 1.0);
+            }
+            Err(e) => {
+                warn!(condition_id = %market.condition_id, error = %e, "No opening price");
+            }
+        }
+    }
+    markets.sort_by_key(|m| m.start_time);
+    Ok(markets)
+}
+
+/// Backtest a knowledge base record on the resolved up/down markets of its symbol over
+/// the last `days`, staking `stake` USDC per bet
+pub async fn run_resolution_backtest(
+    record: &DiscoveryBacktestRecord,
+    binance: &BinanceClient,
+    polymarket: &PolymarketDataClient,
+    days: i64,
+    stake: f64,
+) -> anyhow::Result<ResolutionBacktest> {
+    let strategy = paper_strategy(record).map_err(anyhow::Error::msg)?;
+    let end_time = chrono::Utc::now().timestamp_millis();
+    let since = end_time - days * DAY_MS;
+    let markets =
+        fetch_resolved_markets(polymarket, &record.symbol, since, MAX_RESOLVED_MARKETS).await?;
+    let Some(first) = markets.first() else {
+        bail!(
+            "No resolved up/down markets for {} in the last {} days",
+            record.symbol,
+            days
+        );
+    };
+
+    let (klines, quality) = fetch_checked_klines(
+        binance,
+        &record.symbol,
+        "15m",
+        first.start_time - WARMUP_DAYS * DAY_MS,
+        end_time,
+        &DataQualityConfig::default(),
+    )
+    .await?;
+    quality.warn_if_degraded();
+    if klines.is_empty() {
+        bail!("No klines for {}", record.symbol);
+    }
+
+    let report = resolution_backtest(&strategy, &klines, &markets, stake);
+    info!(
+        symbol = %record.symbol,
+        markets = report.markets,
+        trades = report.trades,
+        net_pnl = report.net_pnl,
+        "Resolution backtest finished"
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_market_window_and_asset() {
+        let window = |q| market_window_ms(q).map(|ms| ms / 60_000);
+        assert_eq!(
+            window("Bitcoin Up or Down - October 16, 2:45PM-3:00PM ET"),
+            Some(15)
+        );
+        assert_eq!(window("Ethereum Up or Down - October 16, 3PM ET"), Some(60));
+        assert_eq!(window("Bitcoin Up or Down on October 16?"), None);
+        assert_eq!(window("Will Bitcoin reach $150k in October?"), None);
+        assert_eq!(market_asset("solusdt"), Some("solana"));
+        assert_eq!(market_asset("DOGEUSDT"), None);
+
+        let gamma: GammaMarket = serde_json::from_value(serde_json::json!({
+            "conditionId": "0xabc",
+            "question": "Bitcoin Up or Down - October 16, 2:45PM-3:00PM ET",
+            "endDate": "2026-10-16T19:00:00Z",
+            "closed": true,
+            "outcomePrices": "[\"0\", \"1\"]",
+            "clobTokenIds": "[\"111\", \"222\"]"
+        }))
+        .unwrap();
+        let market = ResolvedMarket::from_gamma(&gamma).unwrap();
+        assert!(!market.up_won);
+        assert_eq!(market.end_time - market.start_time, BAR_MS);
+        assert_eq!(market.token_id_up.as_deref(), Some("111"));
+    }
+
+    #[test]
+    fn test_resolution_backtest_settles_bets() {
+        let config = SyntheticConfig {
+            bars: 3_000,
+            volatility: 0.01,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        };
+        let klines = generate_klines(&config);
+        // One 15-minute market per bar, resolved by the bar itself; every other
+        // market opened at 0.80 for Up
+        let markets: Vec<ResolvedMarket> = klines
+            .iter()
+            .enumerate()
+            .map(|(i, k)| ResolvedMarket {
+                condition_id: format!("m{}", i),
+                question: String::new(),
+                start_time: k.open_time,
+                end_time: k.close_time + 1,
+                up_won: k.close > k.open,
+                up_price: i.is_multiple_of(2).then_some(0.8),
+                token_id_up: None,
+            })
+            .collect();
+        let strategy = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 60.0,
+            oversold: 40.0,
+        };
+
+        let report = resolution_backtest(&strategy, &klines, &markets, 100.0);
+        assert_eq!(report.markets, 3_000);
+        assert_eq!(report.priced_markets, 1_500);
+        assert!(report.trades > 50, "{} trades", report.trades);
+        assert_eq!(report.trades as usize, report.bets.len());
+        assert_eq!(
+            report.wins as usize,
+            report.bets.iter().filter(|b| b.won).count()
+        );
+        let pnl: f64 = report.bets.iter().map(|b| b.pnl).sum();
+        assert!((pnl - report.net_pnl).abs() < 1e-6);
+        for bet in &report.bets {
+            let i: usize = bet.condition_id[1..].parse().unwrap();
+            let expected = match (i.is_multiple_of(2), bet.side) {
+                (true, MarketSide::Up) => 0.8,
+                (true, MarketSide::Down) => 0.2,
+                (false, _) => 0.5,
+            };
+            assert!((bet.entry_price - expected).abs() < 1e-9);
+            let payout = if bet.won { 100.0 / expected } else { 0.0 };
+            assert!((bet.pnl - (payout - 100.0 - bet.fee)).abs() < 1e-6);
+            assert_eq!(bet.won, (bet.side == MarketSide::Up) == markets[i].up_won);
+        }
+    }
+}
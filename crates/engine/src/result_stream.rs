@@ -0,0 +1,126 @@
+//! Live result streaming
+//!
+//! A `ResultSink` set on `DiscoveryProgress` receives every `DiscoveryResult` a run
+//! finishes (tested or answered from the store), as soon as it exists — independently
+//! of where results are stored. `NdjsonResultSink` appends them to a newline-delimited
+//! JSON file that external pipelines can tail during the run.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use anyhow::Context;
+use tracing::warn;
+
+use crate::discovery::DiscoveryResult;
+
+/// Receives each finished result of a discovery run
+pub trait ResultSink: Send + Sync {
+    fn on_result(&self, result: &DiscoveryResult);
+}
+
+impl<F> ResultSink for F
+where
+    F: Fn(&DiscoveryResult) + Send + Sync,
+{
+    fn on_result(&self, result: &DiscoveryResult) {
+        self(result)
+    }
+}
+
+/// Appends one JSON line per result; each line is written and flushed whole
+pub struct NdjsonResultSink {
+    path: PathBuf,
+    file: Mutex<File>,
+    /// Set after the first failed write, so a full disk logs once
+    failed: AtomicBool,
+}
+
+impl NdjsonResultSink {
+    /// Open `path` for appending, creating it (and its directory) if needed
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Cannot create {}", dir.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Cannot open {}", path.display()))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            failed: AtomicBool::new(false),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl ResultSink for NdjsonResultSink {
+    fn on_result(&self, result: &DiscoveryResult) {
+        let written = serde_json::to_vec(result)
+            .map_err(anyhow::Error::from)
+            .and_then(|mut line| {
+                line.push(b'\n');
+                let mut file = self.file.lock().unwrap();
+                file.write_all(&line)?;
+                file.flush()?;
+                Ok(())
+            });
+        if let Err(e) = written {
+            if !self.failed.swap(true, Ordering::Relaxed) {
+                warn!(path = %self.path.display(), error = %e, "Result stream write failed");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{run_backtest, BacktestLimits, DiscoveryStrategyType, SizingMode};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_ndjson_sink_appends_one_line_per_result() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 500,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let result = run_backtest(
+            &DiscoveryStrategyType::Rsi {
+                period: 14,
+                overbought: 70.0,
+                oversold: 30.0,
+            },
+            &klines,
+            "SYNTH",
+            SizingMode::Fixed,
+            &BacktestLimits::default(),
+        );
+        let dir = std::env::temp_dir().join(format!("result-stream-{}", std::process::id()));
+        let path = dir.join("results.ndjson");
+        let _ = std::fs::remove_file(&path);
+
+        let sink = NdjsonResultSink::open(&path).unwrap();
+        sink.on_result(&result);
+        sink.on_result(&result);
+        // Reopening appends instead of truncating
+        NdjsonResultSink::open(&path).unwrap().on_result(&result);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let parsed: DiscoveryResult = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(parsed.strategy_name, result.strategy_name);
+        assert_eq!(parsed.total_trades, result.total_trades);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
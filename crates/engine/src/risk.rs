@@ -0,0 +1,860 @@
+//! Risk limits for paper trading
+//!
+//! A `RiskMonitor` follows the fills of one strategy and enforces its `RiskLimits`:
+//! realized loss over the current UTC day, notional of open positions and losing
+//! streak. The first breach halts the strategy — every later order is refused until
+//! the halt is cleared — and is returned as a `RiskEvent`.
+//!
+//! `run_paper_session` trades a knowledge base strategy on recent klines under the
+//! limits stored for it (`strategy_risk_limits`); a breach is logged in `risk_events`
+//! and halts the record until `POST /knowledge/{id}/risk/resume`.
+
+use anyhow::{bail, Context};
+use persistence::repository::{
+    DiscoveryBacktestRecord, RiskEventRecord, RiskRepository, StrategyRiskLimits,
+};
+use persistence::SqlitePool;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::api::BinanceClient;
+use crate::capital::{Allocation, CapitalAllocator, CapitalConfig, CapitalStats};
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::discovery::{estimate_poly_probability_f64, DiscoveryStrategyType};
+use crate::fees::{calculate_taker_fee_f64, PolymarketFeeConfig};
+use crate::indicators::stream::IndicatorStream;
+use crate::indicators::{build_signal_generator, close_f64};
+use crate::strategy::Signal;
+use crate::symbols::SymbolFilters;
+use crate::types::{Kline, TradeSide};
+use crate::validation::validation_strategy;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Paper-trading limits of one strategy (`None` = not enforced)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct RiskLimits {
+    /// Realized loss over one UTC day, in USDC
+    pub max_daily_loss: Option<f64>,
+    /// Notional of open positions, in USDC
+    pub max_open_exposure: Option<f64>,
+    pub max_consecutive_losses: Option<u32>,
+}
+
+impl RiskLimits {
+    /// Limits must be positive when set
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("max_daily_loss", self.max_daily_loss),
+            ("max_open_exposure", self.max_open_exposure),
+        ] {
+            if value.is_some_and(|v| v.is_nan() || v <= 0.0) {
+                return Err(format!("{name} must be positive"));
+            }
+        }
+        if self.max_consecutive_losses == Some(0) {
+            return Err("max_consecutive_losses must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl From<&StrategyRiskLimits> for RiskLimits {
+    fn from(row: &StrategyRiskLimits) -> Self {
+        Self {
+            max_daily_loss: row.max_daily_loss,
+            max_open_exposure: row.max_open_exposure,
+            max_consecutive_losses: row.max_consecutive_losses.map(|n| n.max(0) as u32),
+        }
+    }
+}
+
+/// Which limit was breached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLimitKind {
+    DailyLoss,
+    OpenExposure,
+    ConsecutiveLosses,
+}
+
+impl RiskLimitKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DailyLoss => "daily_loss",
+            Self::OpenExposure => "open_exposure",
+            Self::ConsecutiveLosses => "consecutive_losses",
+        }
+    }
+}
+
+/// A limit breach — the strategy is halted from this point on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RiskEvent {
+    pub kind: RiskLimitKind,
+    /// Value that breached the limit (loss, exposure or streak)
+    pub value: f64,
+    pub limit: f64,
+    /// Unix ms of the fill or order that breached it
+    pub time: i64,
+    pub message: String,
+}
+
+impl RiskEvent {
+    fn new(kind: RiskLimitKind, value: f64, limit: f64, time: i64) -> Self {
+        let message = match kind {
+            RiskLimitKind::DailyLoss => {
+                format!("daily loss {value:.2} USDC reached the {limit:.2} USDC limit")
+            }
+            RiskLimitKind::OpenExposure => {
+                format!("open exposure {value:.2} USDC would exceed the {limit:.2} USDC limit")
+            }
+            RiskLimitKind::ConsecutiveLosses => {
+                format!("{value:.0} consecutive losses reached the limit of {limit:.0}")
+            }
+        };
+        Self {
+            kind,
+            value,
+            limit,
+            time,
+            message,
+        }
+    }
+
+    pub fn to_record(&self, backtest_id: i64) -> RiskEventRecord {
+        RiskEventRecord {
+            id: None,
+            backtest_id,
+            kind: self.kind.as_str().to_string(),
+            value: self.value,
+            limit_value: self.limit,
+            message: self.message.clone(),
+            created_at: None,
+        }
+    }
+}
+
+/// Running risk state of one strategy
+#[derive(Debug, Clone)]
+pub struct RiskMonitor {
+    limits: RiskLimits,
+    /// UTC day (`time / DAY_MS`) of `daily_pnl`
+    day: Option<i64>,
+    daily_pnl: f64,
+    open_exposure: f64,
+    consecutive_losses: u32,
+    halted: Option<RiskEvent>,
+}
+
+impl RiskMonitor {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            day: None,
+            daily_pnl: 0.0,
+            open_exposure: 0.0,
+            consecutive_losses: 0,
+            halted: None,
+        }
+    }
+
+    /// Breach that halted the strategy
+    pub fn halted(&self) -> Option<&RiskEvent> {
+        self.halted.as_ref()
+    }
+
+    pub fn open_exposure(&self) -> f64 {
+        self.open_exposure
+    }
+
+    /// Ask to open a position of `notional` USDC. Refused while halted; a breach of
+    /// the exposure limit halts the strategy and is returned.
+    pub fn try_open(&mut self, notional: f64, time: i64) -> Result<(), RiskEvent> {
+        if let Some(event) = &self.halted {
+            return Err(event.clone());
+        }
+        let exposure = self.open_exposure + notional;
+        if let Some(max) = self.limits.max_open_exposure {
+            if exposure > max {
+                return Err(self.halt(RiskEvent::new(
+                    RiskLimitKind::OpenExposure,
+                    exposure,
+                    max,
+                    time,
+                )));
+            }
+        }
+        self.open_exposure = exposure;
+        Ok(())
+    }
+
+    /// Record the close of a `notional` USDC position with its net `pnl`.
+    /// Returns the breach if this fill halts the strategy.
+    pub fn on_close(&mut self, notional: f64, pnl: f64, time: i64) -> Option<RiskEvent> {
+        self.open_exposure = (self.open_exposure - notional).max(0.0);
+
+        let day = time.div_euclid(DAY_MS);
+        if self.day != Some(day) {
+            self.day = Some(day);
+            self.daily_pnl = 0.0;
+        }
+        self.daily_pnl += pnl;
+        if pnl < 0.0 {
+            self.consecutive_losses += 1;
+        } else {
+            self.consecutive_losses = 0;
+        }
+
+        if self.halted.is_some() {
+            return None;
+        }
+        if let Some(max) = self.limits.max_daily_loss {
+            if -self.daily_pnl >= max {
+                let event = RiskEvent::new(RiskLimitKind::DailyLoss, -self.daily_pnl, max, time);
+                return Some(self.halt(event));
+            }
+        }
+        if let Some(max) = self.limits.max_consecutive_losses {
+            if self.consecutive_losses >= max {
+                let event = RiskEvent::new(
+                    RiskLimitKind::ConsecutiveLosses,
+                    self.consecutive_losses as f64,
+                    max as f64,
+                    time,
+                );
+                return Some(self.halt(event));
+            }
+        }
+        None
+    }
+
+    fn halt(&mut self, event: RiskEvent) -> RiskEvent {
+        self.halted = Some(event.clone());
+        event
+    }
+}
+
+// ============================================================================
+// Paper trading
+// ============================================================================
+
+/// Outcome of a paper-trading session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PaperSession {
+    pub bars: usize,
+    pub trades: u32,
+    pub winning_trades: u32,
+    /// Net of fees, in USDC
+    pub net_pnl: f64,
+    pub total_fees: f64,
+    pub limits: RiskLimits,
+    /// Breach that stopped the session
+    pub halted: Option<RiskEvent>,
+}
+
+/// Share of the pool's equity a paper entry asks for
+const PAPER_POSITION_SHARE: f64 = 0.10;
+
+/// One strategy of a paper-trading portfolio
+#[derive(Debug, Clone, Copy)]
+pub struct PaperLeg<'a> {
+    pub backtest_id: i64,
+    pub strategy: &'a DiscoveryStrategyType,
+    pub klines: &'a [Kline],
+    pub limits: RiskLimits,
+    /// Fills are rounded to the symbol's filters when known
+    pub filters: Option<SymbolFilters>,
+}
+
+/// Session of one strategy of a portfolio
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PaperLegSession {
+    pub backtest_id: i64,
+    pub session: PaperSession,
+    /// Entries the pool rejected, or left waiting until they expired or were withdrawn
+    pub missed_entries: u32,
+}
+
+/// Outcome of a paper-trading session of strategies sharing one capital pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PortfolioPaperSession {
+    pub capital: CapitalConfig,
+    pub legs: Vec<PaperLegSession>,
+    pub trades: u32,
+    /// Net of fees, in USDC
+    pub net_pnl: f64,
+    pub total_fees: f64,
+    /// Capital plus the realized PnL of every strategy
+    pub final_equity: f64,
+    pub allocation: CapitalStats,
+}
+
+/// Open paper position
+struct PaperPosition {
+    entry_price: f64,
+    shares: f64,
+    notional: f64,
+}
+
+/// Running state of one strategy of a portfolio session
+struct LegState<'a> {
+    stream: IndicatorStream,
+    monitor: RiskMonitor,
+    session: PaperSession,
+    /// Next kline of the leg to run
+    next: usize,
+    last_bar: Option<&'a Kline>,
+    position: Option<PaperPosition>,
+    /// Close of the first kline, the baseline of the fee probability
+    baseline_price: f64,
+    /// Waiting for the pool since a buy signal
+    waiting: bool,
+    missed_entries: u32,
+}
+
+impl<'a> LegState<'a> {
+    fn new(leg: &PaperLeg<'a>) -> Self {
+        Self {
+            stream: IndicatorStream::from_generator(build_signal_generator(leg.strategy)),
+            monitor: RiskMonitor::new(leg.limits),
+            session: PaperSession {
+                limits: leg.limits,
+                ..Default::default()
+            },
+            next: 0,
+            last_bar: None,
+            position: None,
+            baseline_price: leg.klines.first().map(close_f64).unwrap_or(1.0),
+            waiting: false,
+            missed_entries: 0,
+        }
+    }
+
+    fn fee(&self, shares: f64, price: f64) -> f64 {
+        let p = estimate_poly_probability_f64(self.baseline_price, price);
+        calculate_taker_fee_f64(shares, p, &PolymarketFeeConfig::default())
+    }
+
+    /// Fill an entry of `notional` granted by the pool at the close of the last bar
+    fn open(&mut self, leg: &PaperLeg, notional: f64, pool: &mut CapitalAllocator) {
+        self.waiting = false;
+        let Some(kline) = self.last_bar else {
+            pool.release(notional, 0.0);
+            return;
+        };
+        let close = close_f64(kline);
+        let entry_price = leg
+            .filters
+            .map_or(close, |f| f.round_price(close, TradeSide::Buy));
+        let shares = notional / entry_price;
+        let shares = leg
+            .filters
+            .map_or(shares, |f| f.round_quantity(shares, entry_price));
+        if shares <= 0.0 {
+            // Below the symbol's minimum order
+            pool.release(notional, 0.0);
+            return;
+        }
+        let filled = shares * entry_price;
+        pool.release(notional - filled, 0.0);
+        if self.monitor.try_open(filled, kline.open_time).is_err() {
+            pool.release(filled, 0.0);
+            return;
+        }
+        let entry_fee = self.fee(shares, entry_price);
+        pool.book(-entry_fee);
+        self.session.total_fees += entry_fee;
+        self.session.net_pnl -= entry_fee;
+        self.position = Some(PaperPosition {
+            entry_price,
+            shares,
+            notional: filled,
+        });
+    }
+
+    /// Close the open position at `price` on `kline`, releasing its capital
+    fn close(&mut self, kline: &Kline, price: f64, pool: &mut CapitalAllocator) {
+        let Some(pos) = self.position.take() else {
+            return;
+        };
+        let exit_fee = self.fee(pos.shares, price);
+        let pnl = close_paper_position(&mut self.session, &pos, price, exit_fee);
+        pool.release(pos.notional, pnl);
+        self.monitor.on_close(pos.notional, pnl, kline.open_time);
+    }
+}
+
+/// Trade `strategy` on `klines` with simulated fills (10 000 USDC capital, 10 %
+/// positions, Polymarket taker fees) until the last bar or the first limit breach.
+/// Signals come from an `IndicatorStream`, held during warm-up like in the backtests.
+/// Fills are rounded to the symbol's `filters` when known. A position still open at
+/// the end is closed on the last bar run.
+pub fn paper_trade(
+    strategy: &DiscoveryStrategyType,
+    klines: &[Kline],
+    limits: RiskLimits,
+    filters: Option<SymbolFilters>,
+) -> PaperSession {
+    let leg = PaperLeg {
+        backtest_id: 0,
+        strategy,
+        klines,
+        limits,
+        filters,
+    };
+    let mut portfolio = paper_trade_portfolio(&[leg], CapitalConfig::default());
+    portfolio.legs.remove(0).session
+}
+
+/// Paper trade several strategies bar by bar over one capital pool: each entry asks
+/// the pool for 10 % of its equity, and one the free capital cannot cover is rejected
+/// or waits, per `capital.on_exhausted`. The bars of all legs run in time order; on
+/// each timestamp the exits go first, then the waiting entries, then the new ones, so
+/// capital freed on a bar serves the oldest requests. Each leg keeps its own risk
+/// limits, and a breach only halts that leg.
+pub fn paper_trade_portfolio(legs: &[PaperLeg], capital: CapitalConfig) -> PortfolioPaperSession {
+    let mut pool = CapitalAllocator::new(capital);
+    let mut states: Vec<LegState> = legs.iter().map(LegState::new).collect();
+    let mut times: Vec<i64> = legs
+        .iter()
+        .flat_map(|leg| leg.klines.iter().map(|k| k.open_time))
+        .collect();
+    times.sort_unstable();
+    times.dedup();
+
+    for time in times {
+        let mut buys = Vec::new();
+        for (idx, (leg, state)) in legs.iter().zip(states.iter_mut()).enumerate() {
+            if state.monitor.halted().is_some() {
+                continue;
+            }
+            let Some(kline) = leg.klines.get(state.next).filter(|k| k.open_time == time) else {
+                continue;
+            };
+            state.next += 1;
+            state.session.bars += 1;
+            state.last_bar = Some(kline);
+            let close = close_f64(kline);
+            match state
+                .stream
+                .push(kline)
+                .map_or(Signal::Hold, |u| u.signal.signal)
+            {
+                Signal::Buy if state.position.is_none() && !state.waiting && close > 0.0 => {
+                    buys.push(idx);
+                }
+                Signal::Sell => {
+                    if state.waiting && pool.cancel(idx) {
+                        state.waiting = false;
+                        state.missed_entries += 1;
+                    }
+                    let price = leg
+                        .filters
+                        .map_or(close, |f| f.round_price(close, TradeSide::Sell));
+                    state.close(kline, price, &mut pool);
+                }
+                _ => {}
+            }
+        }
+
+        // Entries that expired in the queue are missed; the others fill by age
+        let expired = pool.stats().expired;
+        for (idx, notional) in pool.grant_queued(time) {
+            states[idx].open(&legs[idx], notional, &mut pool);
+        }
+        if pool.stats().expired > expired {
+            for (idx, state) in states.iter_mut().enumerate() {
+                if state.waiting && !pool.is_queued(idx) {
+                    state.waiting = false;
+                    state.missed_entries += 1;
+                }
+            }
+        }
+        for idx in buys {
+            let notional = pool.equity() * PAPER_POSITION_SHARE;
+            match pool.request(idx, notional, time) {
+                Allocation::Granted => states[idx].open(&legs[idx], notional, &mut pool),
+                Allocation::Queued => states[idx].waiting = true,
+                Allocation::Rejected => states[idx].missed_entries += 1,
+            }
+        }
+        // A leg halted by its limits withdraws its waiting entry
+        for (idx, state) in states.iter_mut().enumerate() {
+            if state.waiting && state.monitor.halted().is_some() && pool.cancel(idx) {
+                state.waiting = false;
+                state.missed_entries += 1;
+            }
+        }
+    }
+
+    // Positions still open close on their leg's last bar run
+    for state in &mut states {
+        if let Some(last) = state.last_bar {
+            state.close(last, close_f64(last), &mut pool);
+        }
+    }
+
+    let legs: Vec<PaperLegSession> = legs
+        .iter()
+        .zip(states)
+        .map(|(leg, mut state)| {
+            state.session.halted = state.monitor.halted().cloned();
+            PaperLegSession {
+                backtest_id: leg.backtest_id,
+                session: state.session,
+                missed_entries: state.missed_entries + u32::from(state.waiting),
+            }
+        })
+        .collect();
+    PortfolioPaperSession {
+        capital,
+        trades: legs.iter().map(|l| l.session.trades).sum(),
+        net_pnl: legs.iter().map(|l| l.session.net_pnl).sum(),
+        total_fees: legs.iter().map(|l| l.session.total_fees).sum(),
+        final_equity: pool.equity(),
+        allocation: pool.stats(),
+        legs,
+    }
+}
+
+/// Book the close of `pos`, returns its PnL net of the exit fee
+fn close_paper_position(
+    session: &mut PaperSession,
+    pos: &PaperPosition,
+    close: f64,
+    exit_fee: f64,
+) -> f64 {
+    let pnl = (close - pos.entry_price) * pos.shares - exit_fee;
+    session.total_fees += exit_fee;
+    session.net_pnl += pnl;
+    session.trades += 1;
+    if pnl > 0.0 {
+        session.winning_trades += 1;
+    }
+    pnl
+}
+
+/// Strategy of a stored record, if it can be paper traded bar by bar
+pub fn paper_strategy(record: &DiscoveryBacktestRecord) -> Result<DiscoveryStrategyType, String> {
+    let strategy = validation_strategy(record)?;
+    if strategy.is_gabagool() {
+        return Err(format!(
+            "{} runs its own arbitrage engine and cannot be paper traded",
+            record.strategy_name
+        ));
+    }
+    Ok(strategy)
+}
+
+/// Paper trade a knowledge base record on its last `days` of 15m klines under its
+/// stored limits. A breach is logged in `risk_events` and halts the record.
+pub async fn run_paper_session(
+    pool: &SqlitePool,
+    record: &DiscoveryBacktestRecord,
+    binance: &BinanceClient,
+    days: i64,
+) -> anyhow::Result<PaperSession> {
+    let mut portfolio = run_portfolio_paper_session(
+        pool,
+        std::slice::from_ref(record),
+        binance,
+        days,
+        CapitalConfig::default(),
+    )
+    .await?;
+    Ok(portfolio.legs.remove(0).session)
+}
+
+/// Paper trade knowledge base records together over one `capital` pool, on their last
+/// `days` of 15m klines, each under its stored limits. Fails if any record is halted;
+/// a breach during the session is logged in `risk_events` and halts its record only.
+pub async fn run_portfolio_paper_session(
+    pool: &SqlitePool,
+    records: &[DiscoveryBacktestRecord],
+    binance: &BinanceClient,
+    days: i64,
+    capital: CapitalConfig,
+) -> anyhow::Result<PortfolioPaperSession> {
+    capital.validate().map_err(anyhow::Error::msg)?;
+    let risk = RiskRepository::new(pool);
+    let end_time = chrono::Utc::now().timestamp_millis();
+    let mut inputs = Vec::with_capacity(records.len());
+    for record in records {
+        let backtest_id = record.id.context("record has no id")?;
+        let strategy = paper_strategy(record).map_err(anyhow::Error::msg)?;
+        let stored = risk.get_limits(backtest_id).await?;
+        if let Some(halted) = stored.as_ref().filter(|l| l.halted_at.is_some()) {
+            bail!(
+                "strategy {} is halted: {}",
+                backtest_id,
+                halted.halt_reason.as_deref().unwrap_or("-")
+            );
+        }
+        let limits = stored.as_ref().map(RiskLimits::from).unwrap_or_default();
+
+        let (klines, quality) = fetch_checked_klines(
+            binance,
+            &record.symbol,
+            "15m",
+            end_time - days * DAY_MS,
+            end_time,
+            &DataQualityConfig::default(),
+        )
+        .await?;
+        quality.warn_if_degraded();
+        if klines.is_empty() {
+            bail!("No klines for {}", record.symbol);
+        }
+
+        let filters = match binance.symbol_filters(&record.symbol).await {
+            Ok(filters) => Some(filters),
+            Err(e) => {
+                warn!(symbol = %record.symbol, error = %e, "No symbol filters, fills are not rounded");
+                None
+            }
+        };
+        inputs.push((backtest_id, strategy, klines, limits, filters));
+    }
+
+    let legs: Vec<PaperLeg> = inputs
+        .iter()
+        .map(
+            |(backtest_id, strategy, klines, limits, filters)| PaperLeg {
+                backtest_id: *backtest_id,
+                strategy,
+                klines,
+                limits: *limits,
+                filters: *filters,
+            },
+        )
+        .collect();
+    let portfolio = paper_trade_portfolio(&legs, capital);
+    for leg in &portfolio.legs {
+        if let Some(event) = &leg.session.halted {
+            risk.halt(&event.to_record(leg.backtest_id)).await?;
+            warn!(
+                backtest_id = leg.backtest_id,
+                kind = event.kind.as_str(),
+                "Risk limit breached, strategy halted: {}",
+                event.message
+            );
+        }
+    }
+    Ok(portfolio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capital::ExhaustedCapital;
+    use persistence::Database;
+    use rust_decimal::Decimal;
+
+    const HOUR_MS: i64 = 3_600_000;
+
+    fn klines(prices: impl Iterator<Item = f64>) -> Vec<Kline> {
+        prices
+            .enumerate()
+            .map(|(i, p)| {
+                let price = Decimal::from_f64_retain(p).unwrap().round_dp(2);
+                Kline {
+                    open_time: i as i64 * 900_000,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Decimal::ONE,
+                    close_time: (i as i64 + 1) * 900_000 - 1,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_risk_monitor_halts_on_first_breach() {
+        let mut monitor = RiskMonitor::new(RiskLimits {
+            max_daily_loss: Some(100.0),
+            max_open_exposure: Some(1_000.0),
+            max_consecutive_losses: Some(3),
+        });
+        assert!(monitor.try_open(600.0, 0).is_ok());
+        let event = monitor.try_open(600.0, 0).unwrap_err();
+        assert_eq!(event.kind, RiskLimitKind::OpenExposure);
+        assert_eq!(event.value, 1_200.0);
+        // Halted: every later order is refused with the same event
+        assert_eq!(monitor.try_open(1.0, HOUR_MS), Err(event));
+
+        // Daily loss resets at midnight UTC
+        let mut monitor = RiskMonitor::new(RiskLimits {
+            max_daily_loss: Some(100.0),
+            ..Default::default()
+        });
+        assert_eq!(monitor.on_close(0.0, -60.0, 23 * HOUR_MS), None);
+        assert_eq!(monitor.on_close(0.0, -60.0, 25 * HOUR_MS), None);
+        let event = monitor.on_close(0.0, -40.0, 26 * HOUR_MS).unwrap();
+        assert_eq!(event.kind, RiskLimitKind::DailyLoss);
+        assert_eq!(event.value, 100.0);
+        assert!(monitor.halted().is_some());
+
+        // A win breaks the losing streak
+        let mut monitor = RiskMonitor::new(RiskLimits {
+            max_consecutive_losses: Some(2),
+            ..Default::default()
+        });
+        for pnl in [-1.0, 5.0, -1.0] {
+            assert_eq!(monitor.on_close(0.0, pnl, 0), None);
+        }
+        let event = monitor.on_close(0.0, -1.0, 0).unwrap();
+        assert_eq!(event.kind, RiskLimitKind::ConsecutiveLosses);
+
+        assert!(RiskLimits::default().validate().is_ok());
+        assert!(RiskLimits {
+            max_daily_loss: Some(-5.0),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_paper_trade_halt_is_persisted() {
+        // RSI swings on a falling sine: trades happen and mostly lose
+        let prices = klines((0..2_000).map(|i| {
+            let i = i as f64;
+            1_000.0 - i * 0.2 + 30.0 * (i / 12.0).sin()
+        }));
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+
+        let free = paper_trade(&rsi, &prices, RiskLimits::default(), None);
+        assert!(free.trades > 2);
+        assert_eq!(free.bars, prices.len());
+        assert!(free.halted.is_none());
+
+        let capped = paper_trade(
+            &rsi,
+            &prices,
+            RiskLimits {
+                max_open_exposure: Some(500.0),
+                ..Default::default()
+            },
+            None,
+        );
+        assert_eq!(capped.trades, 0);
+        assert_eq!(
+            capped.halted.as_ref().unwrap().kind,
+            RiskLimitKind::OpenExposure
+        );
+
+        let streak = paper_trade(
+            &rsi,
+            &prices,
+            RiskLimits {
+                max_consecutive_losses: Some(2),
+                ..Default::default()
+            },
+            None,
+        );
+        let event = streak.halted.clone().unwrap();
+        assert_eq!(event.kind, RiskLimitKind::ConsecutiveLosses);
+        assert!(streak.trades < free.trades);
+        assert!(streak.bars < prices.len());
+
+        // Breach → event + halt; new limits keep the halt until resumed
+        let db = Database::in_memory().await.unwrap();
+        let repo = RiskRepository::new(db.pool());
+        let row = StrategyRiskLimits {
+            backtest_id: 3,
+            max_consecutive_losses: Some(2),
+            ..Default::default()
+        };
+        repo.save_limits(&row).await.unwrap();
+        repo.halt(&event.to_record(3)).await.unwrap();
+        repo.save_limits(&row).await.unwrap();
+        let stored = repo.get_limits(3).await.unwrap().unwrap();
+        assert_eq!(RiskLimits::from(&stored).max_consecutive_losses, Some(2));
+        assert_eq!(stored.halt_reason.as_deref(), Some(event.message.as_str()));
+        assert_eq!(
+            repo.get_events(3, 10).await.unwrap()[0].kind,
+            "consecutive_losses"
+        );
+        assert!(repo.resume(3).await.unwrap());
+        assert!(!repo.resume(3).await.unwrap());
+        assert!(repo
+            .get_limits(3)
+            .await
+            .unwrap()
+            .unwrap()
+            .halted_at
+            .is_none());
+    }
+
+    #[test]
+    fn test_portfolio_shares_one_capital_pool() {
+        let prices = klines((0..2_000).map(|i| {
+            let i = i as f64;
+            1_000.0 + 30.0 * (i / 12.0).sin()
+        }));
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        let leg = PaperLeg {
+            backtest_id: 1,
+            strategy: &rsi,
+            klines: &prices,
+            limits: RiskLimits::default(),
+            filters: None,
+        };
+
+        // One leg alone never runs out of capital
+        let single = paper_trade_portfolio(&[leg], CapitalConfig::default());
+        let alone = paper_trade(&rsi, &prices, RiskLimits::default(), None);
+        assert_eq!(single.legs[0].session.trades, alone.trades);
+        assert_eq!(single.allocation.rejected, 0);
+        assert!((single.final_equity - 10_000.0 - alone.net_pnl).abs() < 1e-6);
+
+        // Twelve legs entering together at 10 % each: the pool covers fewer than ten
+        let legs: Vec<PaperLeg> = (0..12)
+            .map(|id| PaperLeg {
+                backtest_id: id,
+                ..leg
+            })
+            .collect();
+        let rejecting = paper_trade_portfolio(&legs, CapitalConfig::default());
+        let stats = rejecting.allocation;
+        assert!(stats.rejected > 0);
+        assert!(stats.peak_reserved <= 10_000.0);
+        let missed: u32 = rejecting.legs.iter().map(|l| l.missed_entries).sum();
+        assert_eq!(missed, stats.rejected);
+        assert_eq!(stats.granted, rejecting.trades);
+        assert!(rejecting.legs[0].session.trades > rejecting.legs[11].session.trades);
+
+        // Queued entries wait for the first exit, or are dropped when they wait too long
+        let queueing = paper_trade_portfolio(
+            &legs,
+            CapitalConfig {
+                on_exhausted: ExhaustedCapital::Queue { max_wait_ms: 0 },
+                ..Default::default()
+            },
+        );
+        let stats = queueing.allocation;
+        assert_eq!(stats.rejected, 0);
+        assert!(stats.queued > 0);
+        let missed: u32 = queueing.legs.iter().map(|l| l.missed_entries).sum();
+        assert_eq!(missed, stats.expired + stats.cancelled);
+        assert_eq!(stats.granted, queueing.trades);
+    }
+}
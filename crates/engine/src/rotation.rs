@@ -0,0 +1,336 @@
+//! Cross-sectional relative strength rotation backtest
+//!
+//! Unlike the single-symbol strategies, rotation works on several symbols at once:
+//! klines are aligned into a bar × symbol close matrix, every bar the symbols are
+//! ranked by momentum (return over `lookback` bars) and the portfolio holds the top-K
+//! with equal allocations. A symbol is sold when it drops out of the top-K and replaced
+//! by the one that entered. Fees use the same Polymarket taker model as the generic
+//! backtester.
+
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::fees::{calculate_taker_fee, PolymarketFeeConfig};
+use crate::probability::ProbabilityModel;
+use crate::types::{BacktestTrade, ExitReason, Kline, TradeSide};
+
+const DAY_MS: i64 = 86_400_000;
+
+// ---------------------------------------------------------------------------
+// Aligned multi-symbol klines
+// ---------------------------------------------------------------------------
+
+/// Closes of several symbols on their common bars (`closes[bar][symbol]`)
+#[derive(Debug, Clone)]
+pub struct KlineMatrix {
+    pub symbols: Vec<String>,
+    pub open_times: Vec<i64>,
+    pub closes: Vec<Vec<Decimal>>,
+}
+
+impl KlineMatrix {
+    /// Keep only the bars present for every symbol. Needs at least 2 symbols.
+    pub fn align(series: &[(String, Vec<Kline>)]) -> Option<Self> {
+        if series.len() < 2 {
+            return None;
+        }
+
+        let by_time: Vec<HashMap<i64, Decimal>> = series
+            .iter()
+            .map(|(_, klines)| klines.iter().map(|k| (k.open_time, k.close)).collect())
+            .collect();
+
+        let common: BTreeSet<i64> = series[0]
+            .1
+            .iter()
+            .map(|k| k.open_time)
+            .filter(|t| by_time[1..].iter().all(|m| m.contains_key(t)))
+            .collect();
+
+        let open_times: Vec<i64> = common.into_iter().collect();
+        let closes = open_times
+            .iter()
+            .map(|t| by_time.iter().map(|m| m[t]).collect())
+            .collect();
+
+        Some(Self {
+            symbols: series.iter().map(|(s, _)| s.clone()).collect(),
+            open_times,
+            closes,
+        })
+    }
+
+    /// Basket label used as the result `symbol`, e.g. `BTCUSDT+ETHUSDT+SOLUSDT`
+    pub fn label(&self) -> String {
+        self.symbols.join("+")
+    }
+
+    pub fn len(&self) -> usize {
+        self.open_times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.open_times.is_empty()
+    }
+
+    /// Bars of the last `days` days
+    pub fn last_days(&self, days: u32) -> Self {
+        let Some(&last) = self.open_times.last() else {
+            return self.clone();
+        };
+        let cutoff = last - days as i64 * DAY_MS;
+        let start = self.open_times.partition_point(|&t| t <= cutoff);
+        Self {
+            symbols: self.symbols.clone(),
+            open_times: self.open_times[start..].to_vec(),
+            closes: self.closes[start..].to_vec(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Backtest
+// ---------------------------------------------------------------------------
+
+pub struct RotationBacktest {
+    /// One trade per holding period of a symbol
+    pub trades: Vec<BacktestTrade>,
+    pub final_equity: Decimal,
+    pub total_fees: Decimal,
+    pub max_drawdown_pct: Decimal,
+}
+
+struct Holding {
+    entry_time: i64,
+    entry_price: Decimal,
+    size: Decimal,
+}
+
+/// Indices of the `top_k` symbols with the highest return over `lookback` bars at `bar`
+pub fn rank_top_k(matrix: &KlineMatrix, bar: usize, lookback: usize, top_k: usize) -> Vec<usize> {
+    if bar < lookback {
+        return Vec::new();
+    }
+    let now = &matrix.closes[bar];
+    let past = &matrix.closes[bar - lookback];
+
+    let mut momentum: Vec<(usize, Decimal)> = (0..matrix.symbols.len())
+        .filter(|&i| past[i] > Decimal::ZERO)
+        .map(|i| (i, now[i] / past[i]))
+        .collect();
+    // Highest momentum first, ties broken by symbol order
+    momentum.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    momentum.into_iter().take(top_k).map(|(i, _)| i).collect()
+}
+
+pub fn run_rotation_backtest(
+    matrix: &KlineMatrix,
+    lookback: usize,
+    top_k: usize,
+    initial_capital: Decimal,
+    fee_config: &PolymarketFeeConfig,
+) -> RotationBacktest {
+    let hundred = Decimal::from(100);
+    let n_symbols = matrix.symbols.len();
+    let top_k = top_k.clamp(1, n_symbols.max(1));
+    let baselines: Vec<Decimal> = matrix.closes.first().cloned().unwrap_or_default();
+
+    let mut cash = initial_capital;
+    let mut holdings: Vec<Option<Holding>> = (0..n_symbols).map(|_| None).collect();
+    let mut trades = Vec::new();
+    let mut total_fees = Decimal::ZERO;
+    let mut peak_equity = initial_capital;
+    let mut max_drawdown_pct = Decimal::ZERO;
+
+    let fee_for = |i: usize, size: Decimal, price: Decimal| {
+        let p = fee_config
+            .probability_model
+            .probability_dec(baselines[i], price);
+        calculate_taker_fee(size, p, fee_config)
+    };
+
+    let mut close_position = |i: usize,
+                              holding: Holding,
+                              price: Decimal,
+                              time: i64,
+                              cash: &mut Decimal,
+                              total_fees: &mut Decimal,
+                              exit_reason: ExitReason| {
+        let fee = fee_for(i, holding.size, price);
+        *cash += holding.size * price - fee;
+        *total_fees += fee;
+        trades.push(BacktestTrade {
+            entry_time: holding.entry_time,
+            exit_time: time,
+            side: TradeSide::Buy,
+            entry_price: holding.entry_price,
+            exit_price: price,
+            size: holding.size,
+            pnl: (price - holding.entry_price) * holding.size,
+            pnl_pct: if holding.entry_price > Decimal::ZERO {
+                (price - holding.entry_price) / holding.entry_price * hundred
+            } else {
+                Decimal::ZERO
+            },
+            exit_reason,
+        });
+    };
+
+    for bar in lookback..matrix.len() {
+        let prices = &matrix.closes[bar];
+        let time = matrix.open_times[bar];
+        let top = rank_top_k(matrix, bar, lookback, top_k);
+
+        // Sell what left the top-K
+        for (i, slot) in holdings.iter_mut().enumerate() {
+            if !top.contains(&i) {
+                if let Some(holding) = slot.take() {
+                    close_position(
+                        i,
+                        holding,
+                        prices[i],
+                        time,
+                        &mut cash,
+                        &mut total_fees,
+                        ExitReason::Signal,
+                    );
+                }
+            }
+        }
+
+        // Buy the newcomers with an equal share of current equity
+        let equity = cash + market_value(&holdings, prices);
+        for &i in &top {
+            if holdings[i].is_some() || prices[i] <= Decimal::ZERO {
+                continue;
+            }
+            let allocation = (equity / Decimal::from(top_k)).min(cash);
+            if allocation <= Decimal::ZERO {
+                continue;
+            }
+            let size = allocation / prices[i];
+            let fee = fee_for(i, size, prices[i]);
+            cash -= allocation + fee;
+            total_fees += fee;
+            holdings[i] = Some(Holding {
+                entry_time: time,
+                entry_price: prices[i],
+                size,
+            });
+        }
+
+        let equity = cash + market_value(&holdings, prices);
+        if equity > peak_equity {
+            peak_equity = equity;
+        }
+        if peak_equity > Decimal::ZERO {
+            max_drawdown_pct = max_drawdown_pct.max((peak_equity - equity) / peak_equity * hundred);
+        }
+    }
+
+    // Close everything on the last bar
+    if let (Some(prices), Some(&time)) = (matrix.closes.last(), matrix.open_times.last()) {
+        for (i, slot) in holdings.iter_mut().enumerate() {
+            if let Some(holding) = slot.take() {
+                close_position(
+                    i,
+                    holding,
+                    prices[i],
+                    time,
+                    &mut cash,
+                    &mut total_fees,
+                    ExitReason::Timeout,
+                );
+            }
+        }
+    }
+
+    RotationBacktest {
+        trades,
+        final_equity: cash,
+        total_fees,
+        max_drawdown_pct,
+    }
+}
+
+fn market_value(holdings: &[Option<Holding>], prices: &[Decimal]) -> Decimal {
+    holdings
+        .iter()
+        .zip(prices)
+        .filter_map(|(h, p)| h.as_ref().map(|h| h.size * *p))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn klines(closes: &[f64], start: i64) -> Vec<Kline> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let close = Decimal::from_f64_retain(c).unwrap();
+                let open_time = (start + i as i64) * 900_000;
+                Kline {
+                    open_time,
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: dec!(1),
+                    close_time: open_time + 899_999,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_align_keeps_common_bars() {
+        let series = vec![
+            ("AAA".to_string(), klines(&[1.0, 2.0, 3.0, 4.0], 0)),
+            ("BBB".to_string(), klines(&[10.0, 20.0, 30.0], 1)),
+        ];
+        let m = KlineMatrix::align(&series).unwrap();
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.closes[0], vec![dec!(2), dec!(10)]);
+        assert_eq!(m.label(), "AAA+BBB");
+        assert!(KlineMatrix::align(&series[..1]).is_none());
+    }
+
+    #[test]
+    fn test_rotation_follows_the_leader() {
+        // AAA rallies first, then BBB takes over; CCC is flat
+        let series = vec![
+            (
+                "AAA".to_string(),
+                klines(&[100.0, 110.0, 120.0, 120.0, 120.0, 120.0], 0),
+            ),
+            (
+                "BBB".to_string(),
+                klines(&[100.0, 100.0, 100.0, 110.0, 125.0, 140.0], 0),
+            ),
+            ("CCC".to_string(), klines(&[100.0; 6], 0)),
+        ];
+        let m = KlineMatrix::align(&series).unwrap();
+        assert_eq!(rank_top_k(&m, 1, 1, 1), vec![0]);
+        assert_eq!(rank_top_k(&m, 4, 1, 1), vec![1]);
+        assert_eq!(rank_top_k(&m, 5, 5, 3), vec![1, 0, 2]);
+        // Flat start: all tied, first symbols win
+        assert_eq!(rank_top_k(&m, 5, 0, 2), vec![0, 1]);
+
+        let fees = PolymarketFeeConfig {
+            fee_rate: Decimal::ZERO,
+            ..PolymarketFeeConfig::default()
+        };
+        let bt = run_rotation_backtest(&m, 1, 1, dec!(1000), &fees);
+        // Held AAA from bar 1, rotated into BBB at bar 3
+        assert_eq!(bt.trades.len(), 2);
+        assert_eq!(bt.trades[0].entry_price, dec!(110));
+        assert_eq!(bt.trades[1].entry_price, dec!(110));
+        assert_eq!(bt.trades[1].exit_price, dec!(140));
+        assert!(bt.final_equity > dec!(1000));
+        assert_eq!(bt.total_fees, Decimal::ZERO);
+    }
+}
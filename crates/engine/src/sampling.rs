@@ -0,0 +1,63 @@
+//! Quasi-random sampling of the exploration grids
+//!
+//! Independent uniform draws leave gaps and clusters in samples as small as the ones
+//! of an exploration grid. A Latin hypercube splits each dimension of the unit cube
+//! into as many equal strata as there are points and puts exactly one point in every
+//! stratum of every dimension, so `n` draws cover each parameter's range evenly; the
+//! pairing of strata across dimensions stays random.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// `n` points of the `dims`-dimensional unit cube, one in each of the `n` strata of
+/// every dimension, uniformly placed within their stratum
+pub fn latin_hypercube(n: usize, dims: usize, rng: &mut impl Rng) -> Vec<Vec<f64>> {
+    let mut points = vec![vec![0.0; dims]; n];
+    for dim in 0..dims {
+        let mut strata: Vec<usize> = (0..n).collect();
+        strata.shuffle(rng);
+        for (point, stratum) in points.iter_mut().zip(strata) {
+            point[dim] = (stratum as f64 + rng.gen::<f64>()) / n as f64;
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{IndicatorParams, SingleIndicatorType};
+
+    #[test]
+    fn test_latin_hypercube_fills_every_stratum() {
+        let mut rng = rand::thread_rng();
+        let points = latin_hypercube(50, 3, &mut rng);
+        assert_eq!(points.len(), 50);
+        for dim in 0..3 {
+            let mut strata: Vec<usize> = points.iter().map(|p| (p[dim] * 50.0) as usize).collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..50).collect::<Vec<_>>());
+        }
+        assert!(latin_hypercube(0, 3, &mut rng).is_empty());
+
+        // As many draws as integer values: each RSI period exactly once, where uniform
+        // draws would repeat some and miss others
+        let mut periods: Vec<usize> = latin_hypercube(31, 3, &mut rng)
+            .iter()
+            .map(|point| match SingleIndicatorType::Rsi.params_at(point) {
+                IndicatorParams::Rsi {
+                    period,
+                    overbought,
+                    oversold,
+                } => {
+                    assert!((60.0..85.0).contains(&overbought));
+                    assert!((15.0..40.0).contains(&oversold));
+                    period
+                }
+                _ => unreachable!(),
+            })
+            .collect();
+        periods.sort_unstable();
+        assert_eq!(periods, (5..=35).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,359 @@
+//! Strategies similar to a knowledge base record
+//!
+//! A strategy's profile is its indicator set (the indicators of a single-indicator or
+//! combo strategy, its type tag for the others) and its numeric parameters keyed by
+//! group and field: `rsi.period` is the RSI period of a plain RSI as well as of an
+//! RSI+Bollinger combo. Wrappers (entry filter, higher-timeframe trend, z-score filter,
+//! trailing stop) add their parameters under their own group, not an indicator.
+//!
+//! Two profiles sharing at least one indicator score `overlap × (1 − distance)`, where
+//! `overlap` is the Jaccard index of the indicator sets and `distance` the mean relative
+//! difference `|a − b| / max(|a|, |b|)` of the parameters of the shared indicators and
+//! of the wrappers (1 for a parameter only one side has).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use persistence::repository::{DiscoveryBacktestRecord, StrategyParamsRow};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::discovery::{decode_strategy_params, DiscoveryStrategyType};
+use crate::explain::strategy_indicators;
+
+/// Similarity below which a record is not a neighbor
+pub const DEFAULT_MIN_SIMILARITY: f64 = 0.5;
+
+/// Neighbors returned by default, and at most
+pub const DEFAULT_SIMILAR_LIMIT: usize = 20;
+pub const MAX_SIMILAR_LIMIT: usize = 100;
+
+/// Indicator set and numeric parameters of a strategy
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StrategyProfile {
+    pub indicators: BTreeSet<String>,
+    /// `group.field` → value, the group being an indicator or a wrapper tag
+    pub params: BTreeMap<String, f64>,
+}
+
+impl StrategyProfile {
+    pub fn of(strategy: &DiscoveryStrategyType) -> Self {
+        use DiscoveryStrategyType as S;
+        let mut profile = Self::default();
+        let (inner, wrapper) = match strategy {
+            S::Filtered { inner, filter } => (inner, serde_json::to_value(filter)),
+            S::Trailing { inner, stop } => (inner, serde_json::to_value(stop)),
+            S::HtfFiltered { inner, htf_filter } => (inner, serde_json::to_value(htf_filter)),
+            S::ZScoreFiltered {
+                inner,
+                zscore_filter,
+            } => (inner, serde_json::to_value(zscore_filter)),
+            _ => {
+                profile.add_strategy(strategy);
+                return profile;
+            }
+        };
+        profile = Self::of(inner);
+        if let Ok(value) = wrapper {
+            add_leaves(strategy.type_tag(), &value, &mut profile.params);
+        }
+        profile
+    }
+
+    fn add_strategy(&mut self, strategy: &DiscoveryStrategyType) {
+        match strategy_indicators(strategy) {
+            Some((indicators, _)) => {
+                for params in indicators {
+                    let Ok(Value::Object(mut fields)) = serde_json::to_value(&params) else {
+                        continue;
+                    };
+                    let Some(Value::String(tag)) = fields.remove("indicator") else {
+                        continue;
+                    };
+                    // A combo holding the same indicator twice is compared on the first
+                    if self.indicators.insert(tag.clone()) {
+                        add_leaves(&tag, &Value::Object(fields), &mut self.params);
+                    }
+                }
+            }
+            None => {
+                let tag = strategy.type_tag();
+                self.indicators.insert(tag.to_string());
+                if let Ok(Value::Object(mut fields)) = serde_json::to_value(strategy) {
+                    fields.remove("type");
+                    add_leaves(tag, &Value::Object(fields), &mut self.params);
+                }
+            }
+        }
+    }
+}
+
+/// Numeric leaves of `value` as `prefix.key.key...`
+fn add_leaves(prefix: &str, value: &Value, out: &mut BTreeMap<String, f64>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                add_leaves(&format!("{}.{}", prefix, key), child, out);
+            }
+        }
+        Value::Array(items) => {
+            for (idx, child) in items.iter().enumerate() {
+                add_leaves(&format!("{}.{}", prefix, idx), child, out);
+            }
+        }
+        Value::Number(n) => {
+            if let Some(n) = n.as_f64() {
+                out.insert(prefix.to_string(), n);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// How close two strategies are
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Similarity {
+    /// `indicator_overlap × (1 − param_distance)`, from 0 to 1
+    pub score: f64,
+    /// Jaccard index of the indicator sets
+    pub indicator_overlap: f64,
+    /// Mean relative difference of the compared parameters, from 0 to 1
+    pub param_distance: f64,
+    /// Indicators both strategies use
+    pub shared_indicators: Vec<String>,
+}
+
+/// Similarity of `a` and `b`, `None` when they have no indicator in common
+pub fn similarity(a: &StrategyProfile, b: &StrategyProfile) -> Option<Similarity> {
+    let shared_indicators: Vec<String> =
+        a.indicators.intersection(&b.indicators).cloned().collect();
+    if shared_indicators.is_empty() {
+        return None;
+    }
+    let union = a.indicators.union(&b.indicators).count();
+    let indicator_overlap = shared_indicators.len() as f64 / union as f64;
+
+    // Parameters of the shared indicators and of the wrappers, from either side
+    let compared = |key: &String| {
+        let group = key.split('.').next().unwrap_or_default();
+        let in_a = a.indicators.contains(group);
+        let in_b = b.indicators.contains(group);
+        in_a == in_b
+    };
+    let keys: BTreeSet<&String> = a
+        .params
+        .keys()
+        .chain(b.params.keys())
+        .filter(|key| compared(key))
+        .collect();
+    let param_distance = if keys.is_empty() {
+        0.0
+    } else {
+        let total: f64 = keys
+            .iter()
+            .map(|key| match (a.params.get(*key), b.params.get(*key)) {
+                (Some(x), Some(y)) => relative_difference(*x, *y),
+                _ => 1.0,
+            })
+            .sum();
+        total / keys.len() as f64
+    };
+
+    Some(Similarity {
+        score: indicator_overlap * (1.0 - param_distance),
+        indicator_overlap,
+        param_distance,
+        shared_indicators,
+    })
+}
+
+fn relative_difference(x: f64, y: f64) -> f64 {
+    let scale = x.abs().max(y.abs());
+    if scale == 0.0 {
+        0.0
+    } else {
+        ((x - y).abs() / scale).min(1.0)
+    }
+}
+
+/// Ids of the `limit` candidates most similar to `reference` (itself excluded), scoring
+/// at least `min_similarity`, most similar first. Candidates whose params no longer
+/// decode are skipped.
+pub fn rank_similar(
+    reference: &DiscoveryBacktestRecord,
+    candidates: &[StrategyParamsRow],
+    min_similarity: f64,
+    limit: usize,
+) -> Result<Vec<(i64, Similarity)>, String> {
+    let strategy = decode_strategy_params(
+        &reference.strategy_params,
+        reference.schema_version.unwrap_or(0),
+    )?;
+    let profile = StrategyProfile::of(&strategy);
+
+    let mut ranked: Vec<(i64, Similarity)> = candidates
+        .iter()
+        .filter(|row| Some(row.id) != reference.id)
+        .filter_map(|row| {
+            let strategy =
+                decode_strategy_params(&row.strategy_params, row.schema_version.unwrap_or(0))
+                    .ok()?;
+            let similarity = similarity(&profile, &StrategyProfile::of(&strategy))?;
+            (similarity.score >= min_similarity).then_some((row.id, similarity))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then(a.0.cmp(&b.0)));
+    ranked.truncate(limit);
+    Ok(ranked)
+}
+
+/// Performance of a record's neighbors
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NeighborhoodSummary {
+    pub neighbors: usize,
+    /// Neighbors with a positive net PnL, in %
+    pub profitable_pct: f64,
+    pub avg_composite_score: f64,
+    pub avg_net_pnl: f64,
+    pub avg_win_rate: f64,
+    /// Rank of the record's composite score among itself and its neighbors (1 = best)
+    pub reference_rank: usize,
+}
+
+impl NeighborhoodSummary {
+    pub fn of(reference: &DiscoveryBacktestRecord, neighbors: &[DiscoveryBacktestRecord]) -> Self {
+        let n = neighbors.len();
+        let reference_rank = 1 + neighbors
+            .iter()
+            .filter(|r| r.composite_score > reference.composite_score)
+            .count();
+        if n == 0 {
+            return Self {
+                reference_rank,
+                ..Self::default()
+            };
+        }
+        let mean = |f: fn(&DiscoveryBacktestRecord) -> f64| {
+            neighbors.iter().map(f).sum::<f64>() / n as f64
+        };
+        let profitable = neighbors.iter().filter(|r| r.net_pnl > 0.0).count();
+        Self {
+            neighbors: n,
+            profitable_pct: profitable as f64 * 100.0 / n as f64,
+            avg_composite_score: mean(|r| r.composite_score),
+            avg_net_pnl: mean(|r| r.net_pnl),
+            avg_win_rate: mean(|r| r.win_rate),
+            reference_rank,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{result_to_record, run_backtest, BacktestLimits, SizingMode};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+    use crate::trailing_stop::TrailingStop;
+
+    fn rsi(period: usize) -> DiscoveryStrategyType {
+        DiscoveryStrategyType::Rsi {
+            period,
+            overbought: 70.0,
+            oversold: 30.0,
+        }
+    }
+
+    fn row(id: i64, strategy: &DiscoveryStrategyType) -> StrategyParamsRow {
+        StrategyParamsRow {
+            id,
+            params_hash: id.to_string(),
+            workspace: "default".into(),
+            strategy_type: strategy.type_tag().into(),
+            strategy_params: serde_json::to_string(strategy).unwrap(),
+            symbol: "BTCUSDT".into(),
+            days: 30,
+            sizing_mode: "fixed".into(),
+            schema_version: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_similarity_and_ranking() {
+        let base = StrategyProfile::of(&rsi(14));
+        assert_eq!(base.params["rsi.period"], 14.0);
+
+        // Same indicator, period 14 vs 16: one of three parameters 12.5% apart
+        let near = similarity(&base, &StrategyProfile::of(&rsi(16))).unwrap();
+        assert_eq!(near.indicator_overlap, 1.0);
+        assert!((near.param_distance - 0.125 / 3.0).abs() < 1e-9);
+
+        // The RSI of a combo is compared with the plain RSI, the Bollinger half only
+        // lowers the overlap
+        let combo = DiscoveryStrategyType::RsiBollinger {
+            rsi_period: 14,
+            rsi_ob: 70.0,
+            rsi_os: 30.0,
+            bb_period: 20,
+            bb_mult: 2.0,
+        };
+        let half = similarity(&base, &StrategyProfile::of(&combo)).unwrap();
+        assert_eq!((half.indicator_overlap, half.param_distance), (0.5, 0.0));
+        assert_eq!(half.shared_indicators, ["rsi"]);
+        let macd = DiscoveryStrategyType::Macd {
+            fast: 12,
+            slow: 26,
+            signal: 9,
+        };
+        assert!(similarity(&base, &StrategyProfile::of(&macd)).is_none());
+
+        // A trailing stop is a parameter the plain RSI does not have
+        let trailing = DiscoveryStrategyType::Trailing {
+            inner: Box::new(rsi(14)),
+            stop: TrailingStop::Percent { pct: 2.0 },
+        };
+        let wrapped = similarity(&base, &StrategyProfile::of(&trailing)).unwrap();
+        assert_eq!(wrapped.indicator_overlap, 1.0);
+        assert!(wrapped.param_distance > 0.0 && wrapped.score < 1.0);
+
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 300,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let result = run_backtest(
+            &rsi(14),
+            &klines,
+            "BTCUSDT",
+            SizingMode::Fixed,
+            &BacktestLimits::unlimited(),
+        );
+        let reference = DiscoveryBacktestRecord {
+            id: Some(1),
+            composite_score: 1.0,
+            net_pnl: 10.0,
+            ..result_to_record(&result, "1", "run", "phase1", 30)
+        };
+        let candidates = vec![
+            row(1, &rsi(14)),
+            row(2, &combo),
+            row(3, &rsi(16)),
+            row(4, &macd),
+            row(5, &trailing),
+        ];
+        let ranked = rank_similar(&reference, &candidates, 0.5, 2).unwrap();
+        let ids: Vec<i64> = ranked.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, [3, 5]);
+        let all = rank_similar(&reference, &candidates, 0.5, MAX_SIMILAR_LIMIT).unwrap();
+        assert_eq!(all.len(), 3);
+
+        let better = DiscoveryBacktestRecord {
+            composite_score: 2.0,
+            net_pnl: -5.0,
+            ..reference.clone()
+        };
+        let summary = NeighborhoodSummary::of(&reference, &[better, reference.clone()]);
+        assert_eq!((summary.neighbors, summary.reference_rank), (2, 2));
+        assert_eq!(summary.profitable_pct, 50.0);
+        assert_eq!(summary.avg_net_pnl, 2.5);
+    }
+}
@@ -0,0 +1,390 @@
+//! Position sizing of the generic backtest
+//!
+//! `PositionSizer` turns a `SizingMode` into the percentage of equity committed at each
+//! entry. Kelly sizing works on a rolling window of the last trades' price returns
+//! (`KellySizer`): below `min_trades` it falls back to the base size, then it bets a
+//! fraction of the full Kelly stake, capped at `max_pct`.
+//!
+//! An optional `DrawdownThrottle` scales every entry down once the strategy's
+//! unthrottled equity curve is in drawdown, and pauses it past a deeper level. The curve
+//! keeps following the signals while paused, so size comes back as the strategy recovers.
+//!
+//! A max exposure caps every entry, whatever the mode: without it a Kelly window of
+//! lucky trades bets up to `max_pct` of equity on a single position.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::SizingMode;
+
+/// Rolling-window fractional Kelly parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KellyConfig {
+    /// Trades kept in the window (the oldest drops out)
+    pub window: usize,
+    /// Trades needed before Kelly replaces the base size
+    pub min_trades: usize,
+    /// Multiplier applied to the full Kelly stake (0.5 = half Kelly)
+    pub fraction: f64,
+    /// Largest position, % of equity
+    pub max_pct: f64,
+}
+
+impl Default for KellyConfig {
+    fn default() -> Self {
+        Self {
+            window: 20,
+            min_trades: 10,
+            fraction: 0.5,
+            max_pct: 25.0,
+        }
+    }
+}
+
+/// Kelly stake estimated from the last `window` trade returns
+#[derive(Debug, Clone)]
+pub struct KellySizer {
+    config: KellyConfig,
+    returns: VecDeque<f64>,
+}
+
+impl KellySizer {
+    pub fn new(config: KellyConfig) -> Self {
+        Self {
+            config,
+            returns: VecDeque::with_capacity(config.window + 1),
+        }
+    }
+
+    /// Add a closed trade's return (`(exit - entry) / entry`)
+    pub fn record(&mut self, trade_return: f64) {
+        self.returns.push_back(trade_return);
+        while self.returns.len() > self.config.window.max(1) {
+            self.returns.pop_front();
+        }
+    }
+
+    /// Trades currently in the window
+    pub fn trades(&self) -> usize {
+        self.returns.len()
+    }
+
+    /// Full Kelly fraction `p - q / b` of the window (`b` = mean win / mean loss), `None`
+    /// below `min_trades`. A window without losses gives 1, one without wins 0.
+    pub fn kelly_fraction(&self) -> Option<f64> {
+        let n = self.returns.len();
+        if n == 0 || n < self.config.min_trades {
+            return None;
+        }
+        let (mut wins, mut win_sum, mut loss_sum) = (0usize, 0.0f64, 0.0f64);
+        for &r in &self.returns {
+            if r > 0.0 {
+                wins += 1;
+                win_sum += r;
+            } else {
+                loss_sum -= r;
+            }
+        }
+        let losses = n - wins;
+        if wins == 0 {
+            return Some(0.0);
+        }
+        if losses == 0 || loss_sum <= 0.0 {
+            return Some(1.0);
+        }
+        let p = wins as f64 / n as f64;
+        let b = (win_sum / wins as f64) / (loss_sum / losses as f64);
+        Some(p - (1.0 - p) / b)
+    }
+
+    /// Position size in % of equity: `base_pct` until the window holds `min_trades`,
+    /// then the fractional Kelly stake clamped to `[0, max_pct]`
+    pub fn size_pct(&self, base_pct: f64) -> f64 {
+        match self.kelly_fraction() {
+            Some(kelly) => (kelly * self.config.fraction * 100.0).clamp(0.0, self.config.max_pct),
+            None => base_pct,
+        }
+    }
+}
+
+/// Equity protection: entries shrink linearly from full size at `start_pct` of drawdown
+/// to `min_scale` of it just before `pause_pct`, where trading pauses
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DrawdownThrottle {
+    /// Drawdown (%) where scaling down starts
+    pub start_pct: f64,
+    /// Drawdown (%) from which no new position is opened
+    pub pause_pct: f64,
+    /// Size multiplier reached just before the pause
+    pub min_scale: f64,
+}
+
+impl DrawdownThrottle {
+    /// Variants tried by the discovery throttle search
+    pub const SEARCH_PRESETS: [DrawdownThrottle; 3] = [
+        DrawdownThrottle {
+            start_pct: 5.0,
+            pause_pct: 15.0,
+            min_scale: 0.5,
+        },
+        DrawdownThrottle {
+            start_pct: 10.0,
+            pause_pct: 25.0,
+            min_scale: 0.25,
+        },
+        DrawdownThrottle {
+            start_pct: 3.0,
+            pause_pct: 10.0,
+            min_scale: 0.25,
+        },
+    ];
+
+    /// Size multiplier at `drawdown_pct`
+    pub fn scale(&self, drawdown_pct: f64) -> f64 {
+        if drawdown_pct <= self.start_pct {
+            1.0
+        } else if drawdown_pct >= self.pause_pct {
+            0.0
+        } else {
+            let depth = (drawdown_pct - self.start_pct) / (self.pause_pct - self.start_pct);
+            1.0 - depth * (1.0 - self.min_scale.clamp(0.0, 1.0))
+        }
+    }
+
+    /// Short label, e.g. `dd5-15x0.5`
+    pub fn label(&self) -> String {
+        format!("dd{}-{}x{}", self.start_pct, self.pause_pct, self.min_scale)
+    }
+}
+
+/// Unthrottled equity curve (before fees, relative to 1) the throttle follows
+#[derive(Debug, Clone)]
+struct ThrottleState {
+    throttle: DrawdownThrottle,
+    equity: f64,
+    peak: f64,
+}
+
+impl ThrottleState {
+    fn drawdown_pct(&self) -> f64 {
+        (self.peak - self.equity) / self.peak * 100.0
+    }
+}
+
+/// Position size of each entry under a `SizingMode`
+#[derive(Debug, Clone)]
+pub struct PositionSizer {
+    mode: SizingMode,
+    base_pct: f64,
+    kelly: KellySizer,
+    throttle: Option<ThrottleState>,
+    /// Unthrottled size of the last entry, for the throttle's equity curve
+    entry_pct: f64,
+    /// Largest entry, % of equity
+    max_pct: Option<f64>,
+}
+
+impl PositionSizer {
+    pub fn new(mode: SizingMode, base_pct: f64) -> Self {
+        Self::with_kelly(mode, base_pct, KellyConfig::default())
+    }
+
+    pub fn with_kelly(mode: SizingMode, base_pct: f64, kelly: KellyConfig) -> Self {
+        Self {
+            mode,
+            base_pct,
+            kelly: KellySizer::new(kelly),
+            throttle: None,
+            entry_pct: 0.0,
+            max_pct: None,
+        }
+    }
+
+    pub fn with_throttle(mut self, throttle: Option<DrawdownThrottle>) -> Self {
+        self.throttle = throttle.map(|throttle| ThrottleState {
+            throttle,
+            equity: 1.0,
+            peak: 1.0,
+        });
+        self
+    }
+
+    pub fn with_max_exposure(mut self, max_pct: Option<f64>) -> Self {
+        self.max_pct = max_pct;
+        self
+    }
+
+    /// Drawdown (%) of the unthrottled equity curve, 0 without a throttle
+    pub fn drawdown_pct(&self) -> f64 {
+        self.throttle
+            .as_ref()
+            .map_or(0.0, ThrottleState::drawdown_pct)
+    }
+
+    /// Size in % of equity of an entry signalled with `confidence`, capped at the max
+    /// exposure and throttled. An entry sized 0 should still be closed through
+    /// `record_trade` when its exit comes.
+    pub fn entry_pct(&mut self, confidence: f64) -> f64 {
+        let size = self.size_pct(confidence);
+        self.entry_pct = self.max_pct.map_or(size, |max| size.min(max));
+        match &self.throttle {
+            Some(state) => self.entry_pct * state.throttle.scale(state.drawdown_pct()),
+            None => self.entry_pct,
+        }
+    }
+
+    /// Unthrottled size in % of equity of an entry signalled with `confidence`
+    pub fn size_pct(&self, confidence: f64) -> f64 {
+        match self.mode {
+            SizingMode::Fixed => self.base_pct,
+            SizingMode::Kelly => self.kelly.size_pct(self.base_pct),
+            SizingMode::ConfidenceWeighted => {
+                self.base_pct * (confidence * 10_000.0).round() / 10_000.0
+            }
+        }
+    }
+
+    /// Feed the return of the round trip opened by the last `entry_pct` to the Kelly
+    /// window and the throttle's equity curve
+    pub fn record_trade(&mut self, trade_return: f64) {
+        self.kelly.record(trade_return);
+        if let Some(state) = &mut self.throttle {
+            state.equity *= (1.0 + self.entry_pct / 100.0 * trade_return).max(0.0);
+            state.peak = state.peak.max(state.equity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizer(window: usize, min_trades: usize, fraction: f64) -> KellySizer {
+        KellySizer::new(KellyConfig {
+            window,
+            min_trades,
+            fraction,
+            max_pct: 25.0,
+        })
+    }
+
+    #[test]
+    fn test_kelly_sizer_matches_hand_computed_values() {
+        // 6 wins of +2% and 4 losses of -1%: p = 0.6, b = 2, f* = 0.6 - 0.4 / 2 = 0.4
+        let mut kelly = sizer(10, 10, 0.5);
+        for r in [0.02, -0.01, 0.02, 0.02, -0.01, 0.02, -0.01, 0.02, -0.01] {
+            kelly.record(r);
+        }
+        assert_eq!(kelly.kelly_fraction(), None);
+        assert_eq!(kelly.size_pct(10.0), 10.0, "base size below min_trades");
+        kelly.record(0.02);
+        assert!((kelly.kelly_fraction().unwrap() - 0.4).abs() < 1e-12);
+        // Half Kelly: 20%
+        assert!((kelly.size_pct(10.0) - 20.0).abs() < 1e-9);
+        // Quarter Kelly: 10%; full Kelly (40%) hits the 25% cap
+        let mut quarter = sizer(10, 10, 0.25);
+        let mut full = sizer(10, 10, 1.0);
+        for &r in &kelly.returns {
+            quarter.record(r);
+            full.record(r);
+        }
+        assert!((quarter.size_pct(10.0) - 10.0).abs() < 1e-9);
+        assert_eq!(full.size_pct(10.0), 25.0);
+
+        // The window rolls: 4 losses of -3% push out the 4 oldest (+2, -1, +2, +2).
+        // Window: -1, +2, -1, +2, -1, +2, -3, -3, -3, -3 → p = 0.3, mean win 2%,
+        // mean loss (3 * 1 + 4 * 3) / 7 = 15/7 %, b = 14/15, f* = 0.3 - 0.7 * 15/14 = -0.45
+        for _ in 0..4 {
+            kelly.record(-0.03);
+        }
+        assert_eq!(kelly.trades(), 10);
+        assert!((kelly.kelly_fraction().unwrap() + 0.45).abs() < 1e-12);
+        assert_eq!(kelly.size_pct(10.0), 0.0, "negative edge: no position");
+
+        // Degenerate windows
+        let mut winners = sizer(5, 3, 0.5);
+        let mut losers = sizer(5, 3, 0.5);
+        for _ in 0..3 {
+            winners.record(0.01);
+            losers.record(-0.01);
+        }
+        assert_eq!(winners.kelly_fraction(), Some(1.0));
+        assert_eq!(winners.size_pct(10.0), 25.0);
+        assert_eq!(losers.kelly_fraction(), Some(0.0));
+
+        let fixed = PositionSizer::new(SizingMode::Fixed, 10.0);
+        let weighted = PositionSizer::new(SizingMode::ConfidenceWeighted, 10.0);
+        assert_eq!(fixed.size_pct(0.3), 10.0);
+        assert!((weighted.size_pct(0.3) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_drawdown_throttle_scales_pauses_and_recovers() {
+        let throttle = DrawdownThrottle {
+            start_pct: 5.0,
+            pause_pct: 15.0,
+            min_scale: 0.5,
+        };
+        assert_eq!(throttle.scale(4.0), 1.0);
+        assert!((throttle.scale(10.0) - 0.75).abs() < 1e-12);
+        assert_eq!(throttle.scale(15.0), 0.0);
+        assert_eq!(throttle.label(), "dd5-15x0.5");
+
+        // 50% entries: a -20% trade costs 10% of the unthrottled curve
+        let mut sizer = PositionSizer::new(SizingMode::Fixed, 50.0).with_throttle(Some(throttle));
+        assert_eq!(sizer.entry_pct(1.0), 50.0);
+        sizer.record_trade(-0.2);
+        assert!((sizer.drawdown_pct() - 10.0).abs() < 1e-9);
+        assert!((sizer.entry_pct(1.0) - 37.5).abs() < 1e-9);
+        // Curve at 0.9 * 0.9 = 0.81: paused, but the skipped round trip still counts
+        sizer.record_trade(-0.2);
+        assert!((sizer.drawdown_pct() - 19.0).abs() < 1e-9);
+        assert_eq!(sizer.entry_pct(1.0), 0.0);
+        sizer.record_trade(0.2);
+        // 0.81 * 1.1 = 0.891: 10.9% below the peak, scaled back up to 70.5%
+        assert!((sizer.drawdown_pct() - 10.9).abs() < 1e-9);
+        assert!((sizer.entry_pct(1.0) - 50.0 * 0.705).abs() < 1e-9);
+
+        let unthrottled = PositionSizer::new(SizingMode::Fixed, 50.0);
+        assert_eq!(unthrottled.drawdown_pct(), 0.0);
+    }
+
+    #[test]
+    fn test_max_exposure_caps_every_mode() {
+        // A window of wins alone: full Kelly bets all the equity
+        let kelly = KellyConfig {
+            window: 20,
+            min_trades: 10,
+            fraction: 1.0,
+            max_pct: 100.0,
+        };
+        let mut levered = PositionSizer::with_kelly(SizingMode::Kelly, 10.0, kelly);
+        let mut capped =
+            PositionSizer::with_kelly(SizingMode::Kelly, 10.0, kelly).with_max_exposure(Some(30.0));
+        for _ in 0..10 {
+            levered.record_trade(0.1);
+            capped.record_trade(0.1);
+        }
+        assert_eq!(levered.entry_pct(1.0), 100.0);
+        assert_eq!(capped.entry_pct(1.0), 30.0);
+
+        // Sizes below the cap are left alone
+        let mut fixed = PositionSizer::new(SizingMode::Fixed, 10.0).with_max_exposure(Some(30.0));
+        assert_eq!(fixed.entry_pct(1.0), 10.0);
+
+        // The throttle's curve follows the capped entries: a -50% trade costs 15%
+        let throttle = DrawdownThrottle {
+            start_pct: 5.0,
+            pause_pct: 50.0,
+            min_scale: 0.5,
+        };
+        let mut throttled = PositionSizer::new(SizingMode::Fixed, 50.0)
+            .with_throttle(Some(throttle))
+            .with_max_exposure(Some(30.0));
+        assert_eq!(throttled.entry_pct(1.0), 30.0);
+        throttled.record_trade(-0.5);
+        assert!((throttled.drawdown_pct() - 15.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,628 @@
+//! Result store — where discovery reads cached backtests and writes new ones
+//!
+//! The discovery runners only talk to `ResultStore`, so the engine can run against the
+//! SQLite knowledge base (`SqliteResultStore`), a `MemoryResultStore` (tests, embedding)
+//! or a `NoopResultStore` (benchmarks, nothing cached or recorded). `ModelScopedStore`
+//! keeps the results of each probability model apart in any of them.
+//!
+//! `save` / `get_by_hash` / `save_batch` are the core contract. The knowledge-base hooks
+//! (family priors, tested-grid markers, top results, kline quality, run throughput,
+//! retention) have neutral defaults: a store without them just disables work-queue priors
+//! and grid skipping.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use persistence::repository::discovery::{
+    CycleSummaryRecord, DiscoveryBacktestRecord, DiscoveryRepository, FamilyPerformance,
+    GaIndividualRecord, GridSnapshotRecord, KlineQualityRecord, RetentionPolicy, RetentionReport,
+    RunCrashRecord, DEFAULT_RANK_ORDER, DEFAULT_WORKSPACE,
+};
+use persistence::SqlitePool;
+
+use crate::discovery::is_current_logic;
+use crate::probability::{ProbabilityModel, ProbabilityModelConfig};
+
+#[async_trait]
+pub trait ResultStore: Send + Sync {
+    /// Store one result; a record whose `params_hash` already exists is ignored
+    async fn save(&self, record: &DiscoveryBacktestRecord) -> anyhow::Result<()>;
+
+    /// Cached result for a `params_hash`, if any
+    async fn get_by_hash(&self, hash: &str) -> anyhow::Result<Option<DiscoveryBacktestRecord>>;
+
+    /// Store many results; returns how many were new
+    async fn save_batch(&self, records: &[DiscoveryBacktestRecord]) -> anyhow::Result<usize> {
+        let mut inserted = 0;
+        for record in records {
+            if self.get_by_hash(&record.params_hash).await?.is_none() {
+                self.save(record).await?;
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+
+    /// Store `record` unless its hash is already known; returns the stored record (with
+    /// its id when the store assigns one) and whether it was new
+    async fn save_new(
+        &self,
+        record: DiscoveryBacktestRecord,
+    ) -> anyhow::Result<(DiscoveryBacktestRecord, bool)> {
+        if let Some(existing) = self.get_by_hash(&record.params_hash).await? {
+            return Ok((existing, false));
+        }
+        self.save(&record).await?;
+        let stored = self.get_by_hash(&record.params_hash).await?;
+        Ok((stored.unwrap_or(record), true))
+    }
+
+    /// Aggregated composite scores per strategy family × symbol (work-queue priors)
+    async fn family_performance(&self) -> anyhow::Result<Vec<FamilyPerformance>> {
+        Ok(Vec::new())
+    }
+
+    /// Strategy params (JSON) of up to `limit` stored results, newest first (adaptive
+    /// cycle planning)
+    async fn recent_strategy_params(&self, _limit: i64) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Whether this store keeps tested-grid markers; grid skipping is off otherwise
+    fn tracks_grids(&self) -> bool {
+        false
+    }
+
+    async fn is_grid_tested(&self, _grid_hash: &str) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn mark_grid_tested(
+        &self,
+        _grid_hash: &str,
+        _grid_id: &str,
+        _symbol: &str,
+        _days: i64,
+        _sizing_mode: &str,
+        _combinations: i64,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Best stored results of a symbol, by composite score
+    async fn top_results(
+        &self,
+        _limit: i64,
+        _symbol: &str,
+    ) -> anyhow::Result<Vec<DiscoveryBacktestRecord>> {
+        Ok(Vec::new())
+    }
+
+    /// Record the kline quality of a run's symbol
+    async fn save_data_quality(&self, _record: &KlineQualityRecord) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Record a finished cycle of a continuous run
+    async fn save_cycle_summary(&self, _record: &CycleSummaryRecord) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Store the grid of a continuous cycle, once per `snapshot_id`
+    async fn save_grid_snapshot(&self, _record: &GridSnapshotRecord) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Record a panic of a supervised run's task
+    async fn save_run_crash(&self, _record: &RunCrashRecord) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Replace the saved population of the evolutionary search
+    async fn save_population(&self, _individuals: &[GaIndividualRecord]) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Saved population of the evolutionary search, fittest first
+    async fn load_population(&self) -> anyhow::Result<Vec<GaIndividualRecord>> {
+        Ok(Vec::new())
+    }
+
+    /// Executed backtests per second of the latest recorded run (dry-run estimates)
+    async fn recent_throughput(&self) -> anyhow::Result<Option<f64>> {
+        Ok(None)
+    }
+
+    /// Apply the store's retention policy, called after each run or continuous cycle;
+    /// `None` when the store has no policy
+    async fn apply_retention(&self) -> anyhow::Result<Option<RetentionReport>> {
+        Ok(None)
+    }
+
+    /// Attach a cross-symbol score to the stored result of `hash`, if any
+    async fn set_cross_symbol_score(&self, _hash: &str, _score: f64) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// ============================================================================
+// SQLite knowledge base
+// ============================================================================
+
+/// Latest runs searched for a throughput (the last one may be too short to time)
+const RECENT_RUNS: i64 = 5;
+
+/// The `discovery_backtests` knowledge base, through `DiscoveryRepository`.
+/// Results are written to, and looked up in, a single workspace. Results of an earlier
+/// backtest logic version (see `is_current_logic`) are never served from the cache: they
+/// are backtested again, and the new result replaces the stale row.
+pub struct SqliteResultStore {
+    pool: SqlitePool,
+    workspace: String,
+    retention: RetentionPolicy,
+}
+
+impl SqliteResultStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            workspace: DEFAULT_WORKSPACE.to_string(),
+            retention: RetentionPolicy::default(),
+        }
+    }
+
+    pub fn with_workspace(mut self, workspace: impl Into<String>) -> Self {
+        self.workspace = workspace.into();
+        self
+    }
+
+    /// Retention applied to the workspace after each run or cycle (none by default)
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    fn repo(&self) -> DiscoveryRepository<'_> {
+        DiscoveryRepository::new(&self.pool).with_workspace(&self.workspace)
+    }
+}
+
+#[async_trait]
+impl ResultStore for SqliteResultStore {
+    async fn save(&self, record: &DiscoveryBacktestRecord) -> anyhow::Result<()> {
+        self.repo().save(record).await?;
+        Ok(())
+    }
+
+    async fn get_by_hash(&self, hash: &str) -> anyhow::Result<Option<DiscoveryBacktestRecord>> {
+        Ok(self
+            .repo()
+            .get_by_hash(hash)
+            .await?
+            .filter(is_current_logic))
+    }
+
+    async fn save_batch(&self, records: &[DiscoveryBacktestRecord]) -> anyhow::Result<usize> {
+        Ok(self.repo().save_batch(records).await?)
+    }
+
+    async fn family_performance(&self) -> anyhow::Result<Vec<FamilyPerformance>> {
+        Ok(self.repo().get_family_performance().await?)
+    }
+
+    async fn recent_strategy_params(&self, limit: i64) -> anyhow::Result<Vec<String>> {
+        Ok(self.repo().get_recent_strategy_params(limit).await?)
+    }
+
+    fn tracks_grids(&self) -> bool {
+        true
+    }
+
+    async fn is_grid_tested(&self, grid_hash: &str) -> anyhow::Result<bool> {
+        Ok(self.repo().is_grid_tested(grid_hash).await?)
+    }
+
+    async fn mark_grid_tested(
+        &self,
+        grid_hash: &str,
+        grid_id: &str,
+        symbol: &str,
+        days: i64,
+        sizing_mode: &str,
+        combinations: i64,
+    ) -> anyhow::Result<()> {
+        self.repo()
+            .mark_grid_tested(grid_hash, grid_id, symbol, days, sizing_mode, combinations)
+            .await?;
+        Ok(())
+    }
+
+    async fn top_results(
+        &self,
+        limit: i64,
+        symbol: &str,
+    ) -> anyhow::Result<Vec<DiscoveryBacktestRecord>> {
+        let mut records = self
+            .repo()
+            .get_top_results(limit, None, Some(symbol), DEFAULT_RANK_ORDER)
+            .await?;
+        records.retain(is_current_logic);
+        Ok(records)
+    }
+
+    async fn save_data_quality(&self, record: &KlineQualityRecord) -> anyhow::Result<()> {
+        self.repo().save_data_quality(record).await?;
+        Ok(())
+    }
+
+    async fn save_cycle_summary(&self, record: &CycleSummaryRecord) -> anyhow::Result<()> {
+        self.repo().save_cycle_summary(record).await?;
+        Ok(())
+    }
+
+    async fn save_grid_snapshot(&self, record: &GridSnapshotRecord) -> anyhow::Result<()> {
+        self.repo().save_grid_snapshot(record).await?;
+        Ok(())
+    }
+
+    async fn save_run_crash(&self, record: &RunCrashRecord) -> anyhow::Result<()> {
+        self.repo().save_run_crash(record).await?;
+        Ok(())
+    }
+
+    async fn save_population(&self, individuals: &[GaIndividualRecord]) -> anyhow::Result<()> {
+        self.repo().save_ga_population(individuals).await?;
+        Ok(())
+    }
+
+    async fn load_population(&self) -> anyhow::Result<Vec<GaIndividualRecord>> {
+        Ok(self.repo().get_ga_population().await?)
+    }
+
+    async fn recent_throughput(&self) -> anyhow::Result<Option<f64>> {
+        let runs = self.repo().list_runs(RECENT_RUNS).await?;
+        Ok(runs.iter().find_map(|run| run.throughput_per_sec()))
+    }
+
+    async fn apply_retention(&self) -> anyhow::Result<Option<RetentionReport>> {
+        if !self.retention.is_enabled() {
+            return Ok(None);
+        }
+        Ok(Some(self.repo().apply_retention(&self.retention).await?))
+    }
+
+    async fn set_cross_symbol_score(&self, hash: &str, score: f64) -> anyhow::Result<()> {
+        self.repo().update_cross_symbol_score(hash, score).await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// In-memory and no-op stores
+// ============================================================================
+
+/// Results kept in a map keyed by `params_hash` — nothing survives the process
+#[derive(Default)]
+pub struct MemoryResultStore {
+    records: Mutex<HashMap<String, DiscoveryBacktestRecord>>,
+}
+
+impl MemoryResultStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All stored records, best composite score first
+    pub fn records(&self) -> Vec<DiscoveryBacktestRecord> {
+        let mut records: Vec<_> = self.records.lock().unwrap().values().cloned().collect();
+        records.sort_by(|a, b| b.composite_score.total_cmp(&a.composite_score));
+        records
+    }
+}
+
+#[async_trait]
+impl ResultStore for MemoryResultStore {
+    async fn save(&self, record: &DiscoveryBacktestRecord) -> anyhow::Result<()> {
+        self.records
+            .lock()
+            .unwrap()
+            .entry(record.params_hash.clone())
+            .or_insert_with(|| record.clone());
+        Ok(())
+    }
+
+    async fn get_by_hash(&self, hash: &str) -> anyhow::Result<Option<DiscoveryBacktestRecord>> {
+        Ok(self.records.lock().unwrap().get(hash).cloned())
+    }
+
+    async fn save_batch(&self, records: &[DiscoveryBacktestRecord]) -> anyhow::Result<usize> {
+        let mut map = self.records.lock().unwrap();
+        let before = map.len();
+        for record in records {
+            map.entry(record.params_hash.clone())
+                .or_insert_with(|| record.clone());
+        }
+        Ok(map.len() - before)
+    }
+
+    async fn top_results(
+        &self,
+        limit: i64,
+        symbol: &str,
+    ) -> anyhow::Result<Vec<DiscoveryBacktestRecord>> {
+        Ok(self
+            .records()
+            .into_iter()
+            .filter(|r| r.symbol == symbol)
+            .take(limit.max(0) as usize)
+            .collect())
+    }
+
+    async fn recent_strategy_params(&self, limit: i64) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .records
+            .lock()
+            .unwrap()
+            .values()
+            .take(limit.max(0) as usize)
+            .map(|r| r.strategy_params.clone())
+            .collect())
+    }
+
+    async fn set_cross_symbol_score(&self, hash: &str, score: f64) -> anyhow::Result<()> {
+        if let Some(record) = self.records.lock().unwrap().get_mut(hash) {
+            record.cross_symbol_score = Some(score);
+        }
+        Ok(())
+    }
+}
+
+/// Records nothing and never has a cached result — every combination is backtested
+pub struct NoopResultStore;
+
+#[async_trait]
+impl ResultStore for NoopResultStore {
+    async fn save(&self, _record: &DiscoveryBacktestRecord) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn get_by_hash(&self, _hash: &str) -> anyhow::Result<Option<DiscoveryBacktestRecord>> {
+        Ok(None)
+    }
+
+    async fn save_batch(&self, _records: &[DiscoveryBacktestRecord]) -> anyhow::Result<usize> {
+        Ok(0)
+    }
+}
+
+// ============================================================================
+// Probability-model scoping
+// ============================================================================
+
+/// A store seen through one probability model. Results priced with another model than
+/// the linear default get their own `params_hash` / `grid_hash` (prefixed with the model
+/// name, as `workspace_key` does for workspaces), so they are cached and skipped apart
+/// from the linear results instead of being reused for them.
+pub struct ModelScopedStore {
+    inner: Arc<dyn ResultStore>,
+    model: String,
+}
+
+impl ModelScopedStore {
+    /// `inner` unchanged for the linear model, scoped otherwise
+    pub fn scope(
+        inner: Arc<dyn ResultStore>,
+        model: &ProbabilityModelConfig,
+    ) -> Arc<dyn ResultStore> {
+        if *model == ProbabilityModelConfig::Linear {
+            return inner;
+        }
+        Arc::new(Self {
+            inner,
+            model: model.name(),
+        })
+    }
+
+    fn key(&self, hash: &str) -> String {
+        format!("{}:{}", self.model, hash)
+    }
+
+    fn scoped(&self, record: &DiscoveryBacktestRecord) -> DiscoveryBacktestRecord {
+        DiscoveryBacktestRecord {
+            params_hash: self.key(&record.params_hash),
+            ..record.clone()
+        }
+    }
+}
+
+#[async_trait]
+impl ResultStore for ModelScopedStore {
+    async fn save(&self, record: &DiscoveryBacktestRecord) -> anyhow::Result<()> {
+        self.inner.save(&self.scoped(record)).await
+    }
+
+    async fn get_by_hash(&self, hash: &str) -> anyhow::Result<Option<DiscoveryBacktestRecord>> {
+        self.inner.get_by_hash(&self.key(hash)).await
+    }
+
+    async fn save_batch(&self, records: &[DiscoveryBacktestRecord]) -> anyhow::Result<usize> {
+        let scoped: Vec<_> = records.iter().map(|r| self.scoped(r)).collect();
+        self.inner.save_batch(&scoped).await
+    }
+
+    async fn family_performance(&self) -> anyhow::Result<Vec<FamilyPerformance>> {
+        self.inner.family_performance().await
+    }
+
+    async fn recent_strategy_params(&self, limit: i64) -> anyhow::Result<Vec<String>> {
+        self.inner.recent_strategy_params(limit).await
+    }
+
+    fn tracks_grids(&self) -> bool {
+        self.inner.tracks_grids()
+    }
+
+    async fn is_grid_tested(&self, grid_hash: &str) -> anyhow::Result<bool> {
+        self.inner.is_grid_tested(&self.key(grid_hash)).await
+    }
+
+    async fn mark_grid_tested(
+        &self,
+        grid_hash: &str,
+        grid_id: &str,
+        symbol: &str,
+        days: i64,
+        sizing_mode: &str,
+        combinations: i64,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .mark_grid_tested(
+                &self.key(grid_hash),
+                grid_id,
+                symbol,
+                days,
+                sizing_mode,
+                combinations,
+            )
+            .await
+    }
+
+    /// Top results of the same model only
+    async fn top_results(
+        &self,
+        limit: i64,
+        symbol: &str,
+    ) -> anyhow::Result<Vec<DiscoveryBacktestRecord>> {
+        Ok(self
+            .inner
+            .top_results(limit, symbol)
+            .await?
+            .into_iter()
+            .filter(|r| r.probability_model.as_deref() == Some(self.model.as_str()))
+            .collect())
+    }
+
+    async fn save_data_quality(&self, record: &KlineQualityRecord) -> anyhow::Result<()> {
+        self.inner.save_data_quality(record).await
+    }
+
+    async fn save_cycle_summary(&self, record: &CycleSummaryRecord) -> anyhow::Result<()> {
+        self.inner.save_cycle_summary(record).await
+    }
+
+    async fn save_grid_snapshot(&self, record: &GridSnapshotRecord) -> anyhow::Result<()> {
+        self.inner.save_grid_snapshot(record).await
+    }
+
+    async fn save_run_crash(&self, record: &RunCrashRecord) -> anyhow::Result<()> {
+        self.inner.save_run_crash(record).await
+    }
+
+    async fn save_population(&self, individuals: &[GaIndividualRecord]) -> anyhow::Result<()> {
+        self.inner.save_population(individuals).await
+    }
+
+    async fn load_population(&self) -> anyhow::Result<Vec<GaIndividualRecord>> {
+        self.inner.load_population().await
+    }
+
+    async fn recent_throughput(&self) -> anyhow::Result<Option<f64>> {
+        self.inner.recent_throughput().await
+    }
+
+    async fn apply_retention(&self) -> anyhow::Result<Option<RetentionReport>> {
+        self.inner.apply_retention().await
+    }
+
+    async fn set_cross_symbol_score(&self, hash: &str, score: f64) -> anyhow::Result<()> {
+        self.inner
+            .set_cross_symbol_score(&self.key(hash), score)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{BACKTEST_LOGIC_VERSION, ENGINE_VERSION};
+
+    #[tokio::test]
+    async fn test_model_scoped_store_keeps_models_apart() {
+        let memory = Arc::new(MemoryResultStore::new());
+        let linear = ModelScopedStore::scope(memory.clone(), &ProbabilityModelConfig::Linear);
+        let logistic = ModelScopedStore::scope(
+            memory.clone(),
+            &ProbabilityModelConfig::Logistic { slope: Some(2.0) },
+        );
+        let record: DiscoveryBacktestRecord = serde_json::from_value(serde_json::json!({
+            "id": null, "params_hash": "h", "strategy_type": "rsi", "strategy_name": "RSI",
+            "strategy_params": "{}", "symbol": "BTCUSDT", "days": 30, "sizing_mode": "Fixed",
+            "composite_score": 1.0, "net_pnl": 1.0, "gross_pnl": 1.0, "total_fees": 0.0,
+            "win_rate": 50.0, "total_trades": 10, "sharpe_ratio": 0.0, "max_drawdown_pct": 0.0,
+            "profit_factor": 1.0, "avg_trade_pnl": 0.1, "hit_rate": null,
+            "avg_locked_profit": null, "discovery_run_id": null, "phase": null,
+            "sortino_ratio": null, "max_consecutive_losses": null, "avg_win_pnl": null,
+            "avg_loss_pnl": null, "total_volume": null, "annualized_return_pct": null,
+            "annualized_sharpe": null, "strategy_confidence": null, "schema_version": 1,
+            "abort_reason": null, "probability_model": "linear"
+        }))
+        .unwrap();
+
+        linear.save(&record).await.unwrap();
+        assert!(logistic.get_by_hash("h").await.unwrap().is_none());
+        let priced = DiscoveryBacktestRecord {
+            probability_model: Some("logistic(slope=2)".to_string()),
+            ..record
+        };
+        logistic.save(&priced).await.unwrap();
+        assert_eq!(memory.len(), 2);
+        assert!(memory
+            .get_by_hash("logistic(slope=2):h")
+            .await
+            .unwrap()
+            .is_some());
+        let top = logistic.top_results(10, "BTCUSDT").await.unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].params_hash, "logistic(slope=2):h");
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_backtests_earlier_logic_again() {
+        let db = persistence::Database::in_memory().await.unwrap();
+        let store = SqliteResultStore::new(db.pool_clone());
+        let legacy: DiscoveryBacktestRecord = serde_json::from_value(serde_json::json!({
+            "id": null, "params_hash": "h", "strategy_type": "rsi", "strategy_name": "RSI",
+            "strategy_params": "{}", "symbol": "BTCUSDT", "days": 30, "sizing_mode": "Fixed",
+            "composite_score": 1.0, "net_pnl": 1.0, "gross_pnl": 1.0, "total_fees": 0.0,
+            "win_rate": 50.0, "total_trades": 10, "sharpe_ratio": 0.0, "max_drawdown_pct": 0.0,
+            "profit_factor": 1.0, "avg_trade_pnl": 0.1
+        }))
+        .unwrap();
+        store.save(&legacy).await.unwrap();
+        let id = store.repo().get_by_hash("h").await.unwrap().unwrap().id;
+
+        // Written before logic versioning: not a cache hit, nor a refinement seed
+        assert!(store.get_by_hash("h").await.unwrap().is_none());
+        assert!(store.top_results(10, "BTCUSDT").await.unwrap().is_empty());
+
+        let current = DiscoveryBacktestRecord {
+            composite_score: 2.0,
+            engine_version: Some(ENGINE_VERSION.to_string()),
+            logic_version: Some(BACKTEST_LOGIC_VERSION),
+            ..legacy
+        };
+        let (stored, new) = store.save_new(current).await.unwrap();
+        assert!(new);
+        assert_eq!((stored.id, stored.composite_score), (id, 2.0));
+        assert_eq!(store.top_results(10, "BTCUSDT").await.unwrap().len(), 1);
+    }
+}
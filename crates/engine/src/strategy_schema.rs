@@ -0,0 +1,77 @@
+//! JSON Schema of the strategy parameters
+//!
+//! Generated from the serde definitions of `DiscoveryStrategyType` and
+//! `IndicatorParams`, so forms and payload validation in the web UI or external tools
+//! follow the engine without hand-syncing. Each strategy is one `oneOf` branch keyed
+//! by its `type` tag, the indicators of a dynamic combo by their `indicator` tag.
+
+use serde::Serialize;
+
+use crate::discovery::{DiscoveryStrategyType, IndicatorParams};
+
+/// JSON Schemas (draft 2020-12) of the strategy payloads
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StrategySchemas {
+    /// `DiscoveryStrategyType`, as in `strategy_params` and `POST /backtest`
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub strategy: serde_json::Value,
+    /// `IndicatorParams` of a `dynamic_combo`'s `params`
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub indicator_params: serde_json::Value,
+}
+
+pub fn strategy_schemas() -> StrategySchemas {
+    StrategySchemas {
+        strategy: schemars::schema_for!(DiscoveryStrategyType).to_value(),
+        indicator_params: schemars::schema_for!(IndicatorParams).to_value(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branch_tags(schema: &serde_json::Value, tag: &str) -> Vec<String> {
+        schema["oneOf"]
+            .as_array()
+            .expect("one branch per variant")
+            .iter()
+            .map(|branch| {
+                branch["properties"][tag]["const"]
+                    .as_str()
+                    .expect("tagged branch")
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_schema_covers_every_variant() {
+        let schemas = strategy_schemas();
+        let strategies = branch_tags(&schemas.strategy, "type");
+        assert_eq!(strategies.len(), 32);
+        for tag in [
+            "rsi",
+            "dynamic_combo",
+            "gabagool",
+            "pair_spread",
+            "ensemble",
+        ] {
+            assert!(strategies.iter().any(|t| t == tag), "{} missing", tag);
+        }
+        // A tag of the schema is the tag serde writes
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 70.0,
+            oversold: 30.0,
+        };
+        assert_eq!(serde_json::to_value(&rsi).unwrap()["type"], "rsi");
+
+        let indicators = branch_tags(&schemas.indicator_params, "indicator");
+        assert_eq!(indicators.len(), 10);
+        assert!(indicators.iter().any(|t| t == "williams_r"));
+        // Nested types are shared definitions
+        assert!(schemas.strategy["$defs"]["IndicatorParams"].is_object());
+    }
+}
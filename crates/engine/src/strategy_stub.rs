@@ -0,0 +1,832 @@
+//! Python strategy stubs of discovered strategies
+//!
+//! `render_stub()` writes a Backtrader or QuantConnect (LEAN) strategy with the
+//! parameters and combine logic of a single-indicator or combo strategy, so a result can
+//! be re-verified on another engine. Each indicator gives a `(buy, sell)` pair of
+//! conditions and `combine()` reduces them the way `ComboSignalGenerator` does. Like the
+//! discovery backtests, the strategy trades 15m bars long only: it buys
+//! `BASE_POSITION_PCT` % of equity on a buy signal while flat and closes on a sell.
+//!
+//! The indicators are the target's own, so warm-ups and smoothing differ a little (its
+//! Stochastic reads highs and lows, ours the closes) and Polymarket fees are not
+//! modelled. The conditions of wrappers (entry filter, higher-timeframe trend, trailing
+//! stop) and the anchor of an anchored VWAP are left as TODO comments; the other
+//! strategies have no stub.
+
+use std::fmt::Write;
+
+use persistence::repository::DiscoveryBacktestRecord;
+
+use crate::discovery::{
+    decode_strategy_params, DiscoveryStrategyType, DynCombineMode, IndicatorParams,
+};
+use crate::explain::{explain_strategy, strategy_indicators};
+
+/// Share of equity the stub buys, as in `run_discovery()`
+const BASE_POSITION_PCT: f64 = 10.0;
+
+/// Bar interval of the discovery backtests, in minutes
+const BAR_MINUTES: u32 = 15;
+
+/// Engine a stub is written for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StubTarget {
+    #[default]
+    Backtrader,
+    QuantConnect,
+}
+
+impl StubTarget {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Backtrader => "backtrader",
+            Self::QuantConnect => "quantconnect",
+        }
+    }
+
+    /// `backtrader` (or `bt`) or `quantconnect` (or `quant_connect`, `lean`)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "backtrader" | "bt" => Ok(Self::Backtrader),
+            "quantconnect" | "quant_connect" | "lean" => Ok(Self::QuantConnect),
+            other => Err(format!(
+                "Unknown stub target {:?}: use backtrader or quantconnect",
+                other
+            )),
+        }
+    }
+}
+
+/// One indicator of a stub
+#[derive(Debug, Default)]
+struct StubIndicator {
+    /// (name, Python literal)
+    params: Vec<(String, String)>,
+    /// Statements creating its indicator objects
+    setup: Vec<String>,
+    /// QuantConnect: statements feeding it the bar
+    update: Vec<String>,
+    /// QuantConnect: attributes that must be ready before a signal
+    ready: Vec<String>,
+    /// QuantConnect: values kept from the previous bar, as (key, expression)
+    tracked: Vec<(String, String)>,
+    buy: String,
+    sell: String,
+}
+
+/// Python strategy of `strategy` on `symbol`, over a backtest of `days` days ending now;
+/// `title` opens its docstring
+pub fn render_stub(
+    strategy: &DiscoveryStrategyType,
+    symbol: &str,
+    days: i64,
+    target: StubTarget,
+    title: &str,
+) -> Result<String, String> {
+    // Wrappers only add conditions, which the rules list as TODOs
+    let mut inner = strategy;
+    while let DiscoveryStrategyType::Filtered { inner: wrapped, .. }
+    | DiscoveryStrategyType::HtfFiltered { inner: wrapped, .. }
+    | DiscoveryStrategyType::ZScoreFiltered { inner: wrapped, .. }
+    | DiscoveryStrategyType::Trailing { inner: wrapped, .. } = inner
+    {
+        inner = wrapped;
+    }
+    let (params, mode) = strategy_indicators(inner).ok_or_else(|| {
+        format!(
+            "{} is not a single-indicator or combo strategy and has no stub",
+            strategy.name()
+        )
+    })?;
+    let rules = explain_strategy(strategy);
+
+    let mut names: Vec<&str> = Vec::new();
+    let indicators: Vec<StubIndicator> = params
+        .iter()
+        .map(|params| {
+            let kind = kind_name(params);
+            let repeats = names.iter().filter(|n| **n == kind).count();
+            names.push(kind);
+            let name = match repeats {
+                0 => kind.to_string(),
+                n => format!("{}_{}", kind, n + 1),
+            };
+            match target {
+                StubTarget::Backtrader => backtrader_indicator(params, &name),
+                StubTarget::QuantConnect => quantconnect_indicator(params, &name),
+            }
+        })
+        .collect();
+
+    let mut out = String::new();
+    out.push_str("\"\"\"");
+    out.push_str(title);
+    out.push_str("\n\n");
+    out.push_str(&rules.entry);
+    out.push('\n');
+    out.push_str(&rules.exit);
+    out.push('\n');
+    if let Some(combine) = &rules.combine {
+        let _ = writeln!(out, "Combine: {}", combine);
+    }
+    let _ = writeln!(
+        out,
+        "\nLong only on {}m bars: buys {}% of equity on a buy signal while flat and\n\
+         closes the position on a sell signal. Polymarket fees are not modelled.",
+        BAR_MINUTES, BASE_POSITION_PCT
+    );
+    for condition in &rules.conditions {
+        let _ = writeln!(out, "TODO not reproduced: {}", condition);
+    }
+    for params in &params {
+        if let IndicatorParams::Vwap { anchor, .. } = params {
+            if !anchor.is_rolling() {
+                let _ = writeln!(
+                    out,
+                    "TODO not reproduced: VWAP anchored at {} (the stub sums a rolling window)",
+                    anchor.label()
+                );
+            }
+        }
+    }
+    out.push_str("\"\"\"\n");
+
+    match target {
+        StubTarget::Backtrader => {
+            write_backtrader(&mut out, &indicators, mode, symbol, days);
+        }
+        StubTarget::QuantConnect => {
+            write_quantconnect(&mut out, &indicators, mode, symbol, days);
+        }
+    }
+    Ok(out)
+}
+
+/// Stub of a stored result, with its backtest numbers in the docstring
+pub fn record_stub(record: &DiscoveryBacktestRecord, target: StubTarget) -> Result<String, String> {
+    let strategy =
+        decode_strategy_params(&record.strategy_params, record.schema_version.unwrap_or(0))?;
+    let title = format!(
+        "{} on {}, exported from poly_discover{}\n\n\
+         Backtest over {} days: net PnL {:.2} USDC, {} trades, {:.1}% win rate, Sharpe {:.2}",
+        record.strategy_name,
+        record.symbol,
+        record
+            .id
+            .map(|id| format!(" (knowledge base #{})", id))
+            .unwrap_or_default(),
+        record.days,
+        record.net_pnl,
+        record.total_trades,
+        record.win_rate * 100.0,
+        record.sharpe_ratio
+    );
+    render_stub(&strategy, &record.symbol, record.days, target, &title)
+}
+
+fn kind_name(params: &IndicatorParams) -> &'static str {
+    match params {
+        IndicatorParams::Rsi { .. } => "rsi",
+        IndicatorParams::BollingerBands { .. } => "bb",
+        IndicatorParams::Macd { .. } => "macd",
+        IndicatorParams::EmaCrossover { .. } => "ema",
+        IndicatorParams::Stochastic { .. } => "stoch",
+        IndicatorParams::AtrMeanReversion { .. } => "atr",
+        IndicatorParams::Vwap { .. } => "vwap",
+        IndicatorParams::Obv { .. } => "obv",
+        IndicatorParams::WilliamsR { .. } => "wr",
+        IndicatorParams::Adx { .. } => "adx",
+    }
+}
+
+/// Parameters of an indicator, as (suffix, Python literal)
+fn param_values(params: &IndicatorParams) -> Vec<(&'static str, String)> {
+    let float = |v: f64| format!("{:?}", v);
+    match *params {
+        IndicatorParams::Rsi {
+            period,
+            overbought,
+            oversold,
+        }
+        | IndicatorParams::Stochastic {
+            period,
+            overbought,
+            oversold,
+        }
+        | IndicatorParams::WilliamsR {
+            period,
+            overbought,
+            oversold,
+        } => vec![
+            ("period", period.to_string()),
+            ("overbought", float(overbought)),
+            ("oversold", float(oversold)),
+        ],
+        IndicatorParams::BollingerBands { period, multiplier } => vec![
+            ("period", period.to_string()),
+            ("multiplier", float(multiplier)),
+        ],
+        IndicatorParams::Macd { fast, slow, signal } => vec![
+            ("fast", fast.to_string()),
+            ("slow", slow.to_string()),
+            ("signal", signal.to_string()),
+        ],
+        IndicatorParams::EmaCrossover {
+            fast_period,
+            slow_period,
+        } => vec![
+            ("fast", fast_period.to_string()),
+            ("slow", slow_period.to_string()),
+        ],
+        IndicatorParams::AtrMeanReversion {
+            atr_period,
+            sma_period,
+            multiplier,
+        } => vec![
+            ("period", atr_period.to_string()),
+            ("sma_period", sma_period.to_string()),
+            ("multiplier", float(multiplier)),
+        ],
+        IndicatorParams::Vwap { period, .. } => vec![("period", period.to_string())],
+        IndicatorParams::Obv { sma_period } => vec![("sma_period", sma_period.to_string())],
+        IndicatorParams::Adx {
+            period,
+            adx_threshold,
+        } => vec![
+            ("period", period.to_string()),
+            ("threshold", float(adx_threshold)),
+        ],
+    }
+}
+
+fn named_params(params: &IndicatorParams, name: &str) -> Vec<(String, String)> {
+    param_values(params)
+        .into_iter()
+        .map(|(suffix, value)| (format!("{}_{}", name, suffix), value))
+        .collect()
+}
+
+fn backtrader_indicator(params: &IndicatorParams, n: &str) -> StubIndicator {
+    let p = |suffix: &str| format!("self.p.{}_{}", n, suffix);
+    let (setup, buy, sell) = match params {
+        IndicatorParams::Rsi { .. } => (
+            vec![format!(
+                "self.{n} = bt.ind.RSI(self.data.close, period={})",
+                p("period")
+            )],
+            format!("self.{n}[0] < {}", p("oversold")),
+            format!("self.{n}[0] > {}", p("overbought")),
+        ),
+        IndicatorParams::BollingerBands { .. } => (
+            vec![format!(
+                "self.{n} = bt.ind.BollingerBands(self.data.close, period={}, devfactor={})",
+                p("period"),
+                p("multiplier")
+            )],
+            format!("self.data.close[0] < self.{n}.bot[0]"),
+            format!("self.data.close[0] > self.{n}.top[0]"),
+        ),
+        IndicatorParams::Macd { .. } => (
+            vec![format!(
+                "self.{n} = bt.ind.MACDHisto(self.data.close, period_me1={}, period_me2={}, period_signal={})",
+                p("fast"),
+                p("slow"),
+                p("signal")
+            )],
+            format!("self.{n}.histo[-1] <= 0 < self.{n}.histo[0]"),
+            format!("self.{n}.histo[-1] >= 0 > self.{n}.histo[0]"),
+        ),
+        IndicatorParams::EmaCrossover { .. } => (
+            vec![format!(
+                "self.{n} = bt.ind.CrossOver(bt.ind.EMA(self.data.close, period={}), bt.ind.EMA(self.data.close, period={}))",
+                p("fast"),
+                p("slow")
+            )],
+            format!("self.{n}[0] > 0"),
+            format!("self.{n}[0] < 0"),
+        ),
+        IndicatorParams::Stochastic { .. } => (
+            vec![format!(
+                "self.{n} = bt.ind.Stochastic(self.data, period={}, period_dfast=3, period_dslow=3)",
+                p("period")
+            )],
+            format!(
+                "self.{n}.percK[-1] < self.{n}.percD[-1] and self.{n}.percK[0] > self.{n}.percD[0] and self.{n}.percK[0] < {}",
+                p("oversold")
+            ),
+            format!(
+                "self.{n}.percK[-1] > self.{n}.percD[-1] and self.{n}.percK[0] < self.{n}.percD[0] and self.{n}.percK[0] > {}",
+                p("overbought")
+            ),
+        ),
+        IndicatorParams::AtrMeanReversion { .. } => (
+            vec![
+                format!("self.{n} = bt.ind.ATR(self.data, period={})", p("period")),
+                format!(
+                    "self.{n}_sma = bt.ind.SMA(self.data.close, period={})",
+                    p("sma_period")
+                ),
+            ],
+            format!(
+                "self.data.close[0] < self.{n}_sma[0] - {} * self.{n}[0]",
+                p("multiplier")
+            ),
+            format!(
+                "self.data.close[0] > self.{n}_sma[0] + {} * self.{n}[0]",
+                p("multiplier")
+            ),
+        ),
+        IndicatorParams::Vwap { .. } => (
+            vec![
+                "typical = (self.data.high + self.data.low + self.data.close) / 3.0".to_string(),
+                format!(
+                    "self.{n} = bt.ind.SumN(typical * self.data.volume, period={period}) / bt.ind.SumN(self.data.volume, period={period})",
+                    period = p("period")
+                ),
+            ],
+            format!("self.data.close[0] < self.{n}[0]"),
+            format!("self.data.close[0] > self.{n}[0]"),
+        ),
+        IndicatorParams::Obv { .. } => (
+            vec![
+                "close, previous = self.data.close, self.data.close(-1)".to_string(),
+                format!(
+                    "self.{n} = bt.ind.Accum(bt.If(close > previous, self.data.volume, bt.If(close < previous, self.data.volume * -1.0, 0.0)))"
+                ),
+                // The engine averages the current OBV and the `sma_period` before it
+                format!(
+                    "self.{n}_sma = bt.ind.SMA(self.{n}, period={} + 1)",
+                    p("sma_period")
+                ),
+            ],
+            format!("self.{n}[0] > self.{n}_sma[0]"),
+            format!("self.{n}[0] < self.{n}_sma[0]"),
+        ),
+        IndicatorParams::WilliamsR { .. } => (
+            vec![format!(
+                "self.{n} = bt.ind.WilliamsR(self.data, period={})",
+                p("period")
+            )],
+            format!("self.{n}[0] < {}", p("oversold")),
+            format!("self.{n}[0] > {}", p("overbought")),
+        ),
+        IndicatorParams::Adx { .. } => (
+            vec![format!(
+                "self.{n} = bt.ind.DirectionalMovement(self.data, period={})",
+                p("period")
+            )],
+            format!(
+                "self.{n}.adx[0] >= {} and self.{n}.plusDI[0] > self.{n}.minusDI[0]",
+                p("threshold")
+            ),
+            format!(
+                "self.{n}.adx[0] >= {} and self.{n}.minusDI[0] > self.{n}.plusDI[0]",
+                p("threshold")
+            ),
+        ),
+    };
+    StubIndicator {
+        params: named_params(params, n),
+        setup,
+        buy,
+        sell,
+        ..Default::default()
+    }
+}
+
+fn quantconnect_indicator(params: &IndicatorParams, n: &str) -> StubIndicator {
+    let p = |suffix: &str| format!("self.{}_{}", n, suffix);
+    // `self.rsi` and the like would hide the QCAlgorithm helpers of the same name
+    let i = format!("{n}_indicator");
+    let value = |attr: &str| format!("self.{}.current.value", attr);
+    let on_close = |attr: &str| format!("self.{}.update(bar.end_time, bar.close)", attr);
+    let on_bar = |attr: &str| format!("self.{}.update(bar)", attr);
+    let mut indicator = StubIndicator {
+        params: named_params(params, n),
+        ready: vec![i.clone()],
+        ..Default::default()
+    };
+    match params {
+        IndicatorParams::Rsi { .. } => {
+            indicator.setup = vec![format!(
+                "self.{i} = RelativeStrengthIndex({}, MovingAverageType.WILDERS)",
+                p("period")
+            )];
+            indicator.update = vec![on_close(&i)];
+            indicator.buy = format!("{} < {}", value(&i), p("oversold"));
+            indicator.sell = format!("{} > {}", value(&i), p("overbought"));
+        }
+        IndicatorParams::BollingerBands { .. } => {
+            indicator.setup = vec![format!(
+                "self.{i} = BollingerBands({}, {})",
+                p("period"),
+                p("multiplier")
+            )];
+            indicator.update = vec![on_close(&i)];
+            indicator.buy = format!("bar.close < self.{i}.lower_band.current.value");
+            indicator.sell = format!("bar.close > self.{i}.upper_band.current.value");
+        }
+        IndicatorParams::Macd { .. } => {
+            indicator.setup = vec![format!(
+                "self.{i} = MovingAverageConvergenceDivergence({}, {}, {})",
+                p("fast"),
+                p("slow"),
+                p("signal")
+            )];
+            indicator.update = vec![on_close(&i)];
+            indicator.tracked = vec![(
+                format!("{n}_histogram"),
+                format!("self.{i}.histogram.current.value"),
+            )];
+            indicator.buy = format!("prev[\"{n}_histogram\"] <= 0 < now[\"{n}_histogram\"]");
+            indicator.sell = format!("prev[\"{n}_histogram\"] >= 0 > now[\"{n}_histogram\"]");
+        }
+        IndicatorParams::EmaCrossover { .. } => {
+            indicator.setup = vec![
+                format!(
+                    "self.{n}_fast_indicator = ExponentialMovingAverage({})",
+                    p("fast")
+                ),
+                format!(
+                    "self.{n}_slow_indicator = ExponentialMovingAverage({})",
+                    p("slow")
+                ),
+            ];
+            indicator.update = vec![
+                on_close(&format!("{n}_fast_indicator")),
+                on_close(&format!("{n}_slow_indicator")),
+            ];
+            indicator.ready = vec![format!("{n}_fast_indicator"), format!("{n}_slow_indicator")];
+            indicator.tracked = vec![(
+                format!("{n}_spread"),
+                format!(
+                    "{} - {}",
+                    value(&format!("{n}_fast_indicator")),
+                    value(&format!("{n}_slow_indicator"))
+                ),
+            )];
+            indicator.buy = format!("prev[\"{n}_spread\"] <= 0 < now[\"{n}_spread\"]");
+            indicator.sell = format!("prev[\"{n}_spread\"] >= 0 > now[\"{n}_spread\"]");
+        }
+        IndicatorParams::Stochastic { .. } => {
+            indicator.setup = vec![format!("self.{i} = Stochastic({}, 3, 3)", p("period"))];
+            indicator.update = vec![on_bar(&i)];
+            indicator.tracked = vec![
+                (format!("{n}_k"), format!("self.{i}.stoch_k.current.value")),
+                (format!("{n}_d"), format!("self.{i}.stoch_d.current.value")),
+            ];
+            let (k, d) = (format!("now[\"{n}_k\"]"), format!("now[\"{n}_d\"]"));
+            let (prev_k, prev_d) = (format!("prev[\"{n}_k\"]"), format!("prev[\"{n}_d\"]"));
+            indicator.buy = format!(
+                "{prev_k} < {prev_d} and {k} > {d} and {k} < {}",
+                p("oversold")
+            );
+            indicator.sell = format!(
+                "{prev_k} > {prev_d} and {k} < {d} and {k} > {}",
+                p("overbought")
+            );
+        }
+        IndicatorParams::AtrMeanReversion { .. } => {
+            indicator.setup = vec![
+                format!("self.{i} = AverageTrueRange({})", p("period")),
+                format!(
+                    "self.{n}_sma_indicator = SimpleMovingAverage({})",
+                    p("sma_period")
+                ),
+            ];
+            indicator.update = vec![on_bar(&i), on_close(&format!("{n}_sma_indicator"))];
+            indicator.ready.push(format!("{n}_sma_indicator"));
+            indicator.buy = format!(
+                "bar.close < {} - {} * {}",
+                value(&format!("{n}_sma_indicator")),
+                p("multiplier"),
+                value(&i)
+            );
+            indicator.sell = format!(
+                "bar.close > {} + {} * {}",
+                value(&format!("{n}_sma_indicator")),
+                p("multiplier"),
+                value(&i)
+            );
+        }
+        IndicatorParams::Vwap { .. } => {
+            indicator.setup = vec![format!(
+                "self.{i} = VolumeWeightedAveragePriceIndicator({})",
+                p("period")
+            )];
+            indicator.update = vec![on_bar(&i)];
+            indicator.buy = format!("bar.close < {}", value(&i));
+            indicator.sell = format!("bar.close > {}", value(&i));
+        }
+        IndicatorParams::Obv { .. } => {
+            // The engine averages the current OBV and the `sma_period` before it
+            indicator.setup = vec![
+                format!("self.{i} = OnBalanceVolume()"),
+                format!(
+                    "self.{n}_sma_indicator = SimpleMovingAverage({} + 1)",
+                    p("sma_period")
+                ),
+            ];
+            indicator.update = vec![
+                on_bar(&i),
+                format!("self.{n}_sma_indicator.update(bar.end_time, {})", value(&i)),
+            ];
+            indicator.ready.push(format!("{n}_sma_indicator"));
+            indicator.buy = format!("{} > {}", value(&i), value(&format!("{n}_sma_indicator")));
+            indicator.sell = format!("{} < {}", value(&i), value(&format!("{n}_sma_indicator")));
+        }
+        IndicatorParams::WilliamsR { .. } => {
+            indicator.setup = vec![format!("self.{i} = WilliamsPercentR({})", p("period"))];
+            indicator.update = vec![on_bar(&i)];
+            indicator.buy = format!("{} < {}", value(&i), p("oversold"));
+            indicator.sell = format!("{} > {}", value(&i), p("overbought"));
+        }
+        IndicatorParams::Adx { .. } => {
+            indicator.setup = vec![format!(
+                "self.{i} = AverageDirectionalIndex({})",
+                p("period")
+            )];
+            indicator.update = vec![on_bar(&i)];
+            let plus = format!("self.{i}.positive_directional_index.current.value");
+            let minus = format!("self.{i}.negative_directional_index.current.value");
+            indicator.buy = format!("{} >= {} and {plus} > {minus}", value(&i), p("threshold"));
+            indicator.sell = format!("{} >= {} and {minus} > {plus}", value(&i), p("threshold"));
+        }
+    }
+    indicator
+}
+
+/// `combine()`: the signal of the indicators' `(buy, sell)` pairs, as in
+/// `ComboSignalGenerator` (a single indicator is its own signal)
+fn write_combine(out: &mut String, mode: Option<DynCombineMode>) {
+    out.push_str(
+        "\nBUY, SELL, HOLD = 1, -1, 0\n\n\n\
+         def combine(signals):\n    \
+         votes = [BUY if buy else SELL if sell else HOLD for buy, sell in signals]\n",
+    );
+    let body = match mode {
+        None => "    return votes[0]\n",
+        Some(DynCombineMode::Unanimous) => {
+            "    # All indicators agree\n    \
+             if all(v == BUY for v in votes):\n        return BUY\n    \
+             if all(v == SELL for v in votes):\n        return SELL\n    \
+             return HOLD\n"
+        }
+        Some(DynCombineMode::Majority) => {
+            "    # A majority, buys checked first\n    \
+             threshold = (len(votes) + 1) // 2\n    \
+             if votes.count(BUY) >= threshold:\n        return BUY\n    \
+             if votes.count(SELL) >= threshold:\n        return SELL\n    \
+             return HOLD\n"
+        }
+        Some(DynCombineMode::PrimaryConfirmed) => {
+            "    # The first indicator, unless all the others disagree\n    \
+             primary = votes[0]\n    \
+             if primary != HOLD and any(v in (primary, HOLD) for v in votes[1:]):\n        \
+             return primary\n    \
+             return HOLD\n"
+        }
+    };
+    out.push_str(body);
+}
+
+/// Lines of `(buy, sell)` pairs, indented by `indent`
+fn write_signals(out: &mut String, indicators: &[StubIndicator], indent: &str) {
+    let _ = writeln!(out, "{}signals = [", indent);
+    for indicator in indicators {
+        let _ = writeln!(
+            out,
+            "{}    ({}, {}),",
+            indent, indicator.buy, indicator.sell
+        );
+    }
+    let _ = writeln!(out, "{}]", indent);
+}
+
+fn write_backtrader(
+    out: &mut String,
+    indicators: &[StubIndicator],
+    mode: Option<DynCombineMode>,
+    symbol: &str,
+    days: i64,
+) {
+    out.push_str("\nimport backtrader as bt\n");
+    write_combine(out, mode);
+    out.push_str("\n\nclass DiscoveredStrategy(bt.Strategy):\n    params = dict(\n");
+    for (name, value) in indicators.iter().flat_map(|i| &i.params) {
+        let _ = writeln!(out, "        {}={},", name, value);
+    }
+    let _ = writeln!(
+        out,
+        "        position_pct={:?},\n    )\n\n    def __init__(self):",
+        BASE_POSITION_PCT
+    );
+    for line in indicators.iter().flat_map(|i| &i.setup) {
+        let _ = writeln!(out, "        {}", line);
+    }
+    out.push_str("\n    def next(self):\n");
+    write_signals(out, indicators, "        ");
+    out.push_str(
+        "        signal = combine(signals)\n        \
+         if signal == BUY and not self.position:\n            \
+         self.order_target_percent(target=self.p.position_pct / 100)\n        \
+         elif signal == SELL and self.position:\n            \
+         self.close()\n",
+    );
+    let _ = write!(
+        out,
+        "\n\nif __name__ == \"__main__\":\n    \
+         cerebro = bt.Cerebro()\n    \
+         # TODO: {days} days of {symbol} {minutes}m klines (datetime, open, high, low, close, volume)\n    \
+         data = bt.feeds.GenericCSVData(\n        \
+         dataname=\"{symbol}_{minutes}m.csv\",\n        \
+         dtformat=\"%Y-%m-%d %H:%M:%S\",\n        \
+         timeframe=bt.TimeFrame.Minutes,\n        \
+         compression={minutes},\n        \
+         openinterest=-1,\n    \
+         )\n    \
+         cerebro.adddata(data)\n    \
+         cerebro.addstrategy(DiscoveredStrategy)\n    \
+         cerebro.broker.setcash(10_000.0)\n    \
+         # Fill at the close of the signal bar, like the discovery backtests\n    \
+         cerebro.broker.set_coc(True)\n    \
+         cerebro.run()\n    \
+         print(f\"Final value: {{cerebro.broker.getvalue():.2f}}\")\n",
+        days = days,
+        symbol = symbol,
+        minutes = BAR_MINUTES
+    );
+}
+
+fn write_quantconnect(
+    out: &mut String,
+    indicators: &[StubIndicator],
+    mode: Option<DynCombineMode>,
+    symbol: &str,
+    days: i64,
+) {
+    out.push_str("\nfrom AlgorithmImports import *\n");
+    write_combine(out, mode);
+    out.push_str("\n\nclass DiscoveredStrategy(QCAlgorithm):\n");
+    for (name, value) in indicators.iter().flat_map(|i| &i.params) {
+        let _ = writeln!(out, "    {} = {}", name, value);
+    }
+    let _ = write!(
+        out,
+        "    position_pct = {pct:?}\n\n    \
+         def initialize(self):\n        \
+         self.set_start_date(datetime.now() - timedelta(days={days}))\n        \
+         self.set_cash(10_000)\n        \
+         self.ticker = self.add_crypto(\"{symbol}\", Resolution.MINUTE, Market.BINANCE).symbol\n        \
+         self.consolidate(self.ticker, timedelta(minutes={minutes}), self.on_bar)\n",
+        pct = BASE_POSITION_PCT,
+        days = days,
+        symbol = symbol,
+        minutes = BAR_MINUTES
+    );
+    for line in indicators.iter().flat_map(|i| &i.setup) {
+        let _ = writeln!(out, "        {}", line);
+    }
+    let tracked: Vec<&(String, String)> = indicators.iter().flat_map(|i| &i.tracked).collect();
+    if !tracked.is_empty() {
+        out.push_str("        self.previous = None\n");
+    }
+    out.push_str("\n    def on_bar(self, bar):\n");
+    for line in indicators.iter().flat_map(|i| &i.update) {
+        let _ = writeln!(out, "        {}", line);
+    }
+    let ready: Vec<String> = indicators
+        .iter()
+        .flat_map(|i| &i.ready)
+        .map(|attr| format!("self.{}", attr))
+        .collect();
+    let _ = writeln!(
+        out,
+        "        if not all(i.is_ready for i in [{}]):\n            return",
+        ready.join(", ")
+    );
+    if !tracked.is_empty() {
+        out.push_str("        now = {\n");
+        for (key, expr) in tracked {
+            let _ = writeln!(out, "            \"{}\": {},", key, expr);
+        }
+        out.push_str(
+            "        }\n        \
+             prev, self.previous = self.previous, now\n        \
+             if prev is None:\n            \
+             return\n",
+        );
+    }
+    write_signals(out, indicators, "        ");
+    out.push_str(
+        "        signal = combine(signals)\n        \
+         invested = self.portfolio[self.ticker].invested\n        \
+         if signal == BUY and not invested:\n            \
+         self.set_holdings(self.ticker, self.position_pct / 100)\n        \
+         elif signal == SELL and invested:\n            \
+         self.liquidate(self.ticker)\n",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::SingleIndicatorType;
+    use crate::trailing_stop::TrailingStop;
+
+    #[test]
+    fn test_render_stubs() {
+        let strategy = DiscoveryStrategyType::Trailing {
+            inner: Box::new(DiscoveryStrategyType::MacdRsi {
+                macd_fast: 12,
+                macd_slow: 26,
+                macd_signal: 9,
+                rsi_period: 14,
+                rsi_ob: 70.0,
+                rsi_os: 30.0,
+            }),
+            stop: TrailingStop::Percent { pct: 2.0 },
+        };
+        let stub = render_stub(
+            &strategy,
+            "BTCUSDT",
+            30,
+            StubTarget::Backtrader,
+            "MACD+RSI on BTCUSDT",
+        )
+        .unwrap();
+        assert!(stub.starts_with("\"\"\"MACD+RSI on BTCUSDT\n"));
+        for line in [
+            "        macd_fast=12,",
+            "        rsi_oversold=30.0,",
+            "        self.rsi = bt.ind.RSI(self.data.close, period=self.p.rsi_period)",
+            "            (self.rsi[0] < self.p.rsi_oversold, self.rsi[0] > self.p.rsi_overbought),",
+            "    # The first indicator, unless all the others disagree",
+            "TODO not reproduced: Positions also close when price falls 2% below its high since entry",
+            "        dataname=\"BTCUSDT_15m.csv\",",
+        ] {
+            assert!(stub.contains(line), "missing {:?} in\n{}", line, stub);
+        }
+
+        // QuantConnect keeps the MACD histogram of the previous bar for its crossings
+        let stub = render_stub(
+            &strategy,
+            "BTCUSDT",
+            30,
+            StubTarget::QuantConnect,
+            "MACD+RSI on BTCUSDT",
+        )
+        .unwrap();
+        for line in [
+            "    macd_slow = 26",
+            "        self.set_start_date(datetime.now() - timedelta(days=30))",
+            "            \"macd_histogram\": self.macd_indicator.histogram.current.value,",
+            "            (prev[\"macd_histogram\"] <= 0 < now[\"macd_histogram\"], prev[\"macd_histogram\"] >= 0 > now[\"macd_histogram\"]),",
+            "        if not all(i.is_ready for i in [self.macd_indicator, self.rsi_indicator]):",
+        ] {
+            assert!(stub.contains(line), "missing {:?} in\n{}", line, stub);
+        }
+
+        // Repeated indicators get their own names
+        let combo = DiscoveryStrategyType::DynamicCombo {
+            indicators: vec![SingleIndicatorType::Rsi, SingleIndicatorType::Rsi],
+            params: vec![
+                IndicatorParams::Rsi {
+                    period: 7,
+                    overbought: 80.0,
+                    oversold: 20.0,
+                },
+                IndicatorParams::Rsi {
+                    period: 21,
+                    overbought: 65.0,
+                    oversold: 35.0,
+                },
+            ],
+            combine_mode: DynCombineMode::Majority,
+        };
+        let stub = render_stub(&combo, "ETHUSDT", 7, StubTarget::Backtrader, "combo").unwrap();
+        assert!(stub.contains("        rsi_2_period=21,"));
+        assert!(stub.contains("    threshold = (len(votes) + 1) // 2"));
+
+        // The stub sums a rolling VWAP; the anchor is left as a TODO
+        let anchored = DiscoveryStrategyType::Vwap {
+            period: 10,
+            anchor: crate::discovery::VwapAnchor::Session,
+        };
+        let stub = render_stub(&anchored, "BTCUSDT", 30, StubTarget::Backtrader, "").unwrap();
+        assert!(stub.contains(
+            "TODO not reproduced: VWAP anchored at session (the stub sums a rolling window)"
+        ));
+        assert!(stub.contains("        vwap_period=10,"));
+
+        let basket = DiscoveryStrategyType::RelativeStrength {
+            lookback: 96,
+            top_k: 3,
+        };
+        assert!(render_stub(&basket, "BTCUSDT", 30, StubTarget::Backtrader, "").is_err());
+
+        assert_eq!(StubTarget::parse("LEAN").unwrap(), StubTarget::QuantConnect);
+        assert_eq!(StubTarget::parse("bt").unwrap(), StubTarget::Backtrader);
+        assert!(StubTarget::parse("zipline").is_err());
+    }
+}
@@ -0,0 +1,780 @@
+//! Parameter validation of strategies
+//!
+//! The signal generators build their `ta` indicators with `.expect()`, so a zero period
+//! only shows as a panic in the middle of a backtest, and a fast EMA slower than its
+//! slow one or an oversold level above the overbought one silently backtests nonsense.
+//! `validate()` on `DiscoveryStrategyType` and `IndicatorParams` checks the parameters
+//! up front, with an error naming the indicator and the field. Grids drop the strategies
+//! it rejects (`StrategyExclusions::retain`, the hill climber's candidates) and ad-hoc
+//! strategies (`POST /backtest`, the Python bindings) are refused with its message.
+
+use rust_decimal::Decimal;
+use tracing::warn;
+
+use crate::discovery::{DiscoveryStrategyType, IndicatorParams, VwapAnchor};
+use crate::entry_filter::EntryFilter;
+use crate::htf_filter::{HtfFilter, HtfTrend};
+use crate::trailing_stop::TrailingStop;
+use crate::web_strategies::WebStrategyParams;
+use crate::zscore_filter::ZScoreFilter;
+
+/// Longest indicator period or lookback, in bars
+pub const MAX_PERIOD: usize = 1_000;
+
+fn check_period(field: &str, value: usize) -> Result<(), String> {
+    if (1..=MAX_PERIOD).contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} must be between 1 and {} (got {})",
+            field, MAX_PERIOD, value
+        ))
+    }
+}
+
+fn check_fast_slow(indicator: &str, fast: usize, slow: usize) -> Result<(), String> {
+    check_period(&format!("{} fast period", indicator), fast)?;
+    check_period(&format!("{} slow period", indicator), slow)?;
+    if fast >= slow {
+        return Err(format!(
+            "{} fast period ({}) must be below the slow one ({})",
+            indicator, fast, slow
+        ));
+    }
+    Ok(())
+}
+
+fn check_finite(field: &str, value: f64) -> Result<(), String> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(format!("{} must be a finite number", field))
+    }
+}
+
+fn check_positive(field: &str, value: f64) -> Result<(), String> {
+    check_finite(field, value)?;
+    if value <= 0.0 {
+        return Err(format!("{} must be positive (got {})", field, value));
+    }
+    Ok(())
+}
+
+fn check_range(field: &str, value: f64, min: f64, max: f64) -> Result<(), String> {
+    check_finite(field, value)?;
+    if !(min..=max).contains(&value) {
+        return Err(format!(
+            "{} must be between {} and {} (got {})",
+            field, min, max, value
+        ));
+    }
+    Ok(())
+}
+
+/// Oversold and overbought levels within `[min, max]`, oversold strictly below
+fn check_levels(
+    indicator: &str,
+    oversold: f64,
+    overbought: f64,
+    min: f64,
+    max: f64,
+) -> Result<(), String> {
+    check_range(&format!("{} oversold", indicator), oversold, min, max)?;
+    check_range(&format!("{} overbought", indicator), overbought, min, max)?;
+    if oversold >= overbought {
+        return Err(format!(
+            "{} oversold ({}) must be below overbought ({})",
+            indicator, oversold, overbought
+        ));
+    }
+    Ok(())
+}
+
+impl IndicatorParams {
+    /// Parameters the indicator's generator accepts
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            Self::Rsi {
+                period,
+                overbought,
+                oversold,
+            } => {
+                check_period("RSI period", period)?;
+                check_levels("RSI", oversold, overbought, 0.0, 100.0)
+            }
+            Self::BollingerBands { period, multiplier } => {
+                check_period("BB period", period)?;
+                check_positive("BB multiplier", multiplier)
+            }
+            Self::Macd { fast, slow, signal } => {
+                check_fast_slow("MACD", fast, slow)?;
+                check_period("MACD signal period", signal)
+            }
+            Self::EmaCrossover {
+                fast_period,
+                slow_period,
+            } => check_fast_slow("EMA", fast_period, slow_period),
+            Self::Stochastic {
+                period,
+                overbought,
+                oversold,
+            } => {
+                check_period("Stoch period", period)?;
+                check_levels("Stoch", oversold, overbought, 0.0, 100.0)
+            }
+            Self::AtrMeanReversion {
+                atr_period,
+                sma_period,
+                multiplier,
+            } => {
+                check_period("ATR period", atr_period)?;
+                check_period("ATR SMA period", sma_period)?;
+                check_positive("ATR multiplier", multiplier)
+            }
+            Self::Vwap { period, anchor } => {
+                check_period("VWAP period", period)?;
+                match anchor {
+                    VwapAnchor::Days { days: 0 } => {
+                        Err("VWAP anchor days must be at least 1".to_string())
+                    }
+                    VwapAnchor::SwingLow { lookback } => {
+                        check_period("VWAP swing low lookback", lookback)
+                    }
+                    _ => Ok(()),
+                }
+            }
+            Self::Obv { sma_period } => check_period("OBV SMA period", sma_period),
+            Self::WilliamsR {
+                period,
+                overbought,
+                oversold,
+            } => {
+                check_period("WR period", period)?;
+                check_levels("WR", oversold, overbought, -100.0, 0.0)
+            }
+            Self::Adx {
+                period,
+                adx_threshold,
+            } => {
+                check_period("ADX period", period)?;
+                check_range("ADX threshold", adx_threshold, 0.0, 100.0)
+            }
+        }
+    }
+}
+
+impl DiscoveryStrategyType {
+    /// Parameters the strategy's generators accept: period bounds, fast below slow,
+    /// oversold below overbought, positive multipliers, matching list lengths, and the
+    /// same for the strategies it wraps or combines
+    pub fn validate(&self) -> Result<(), String> {
+        use IndicatorParams as P;
+        let all = |params: &[IndicatorParams]| params.iter().try_for_each(P::validate);
+        match self {
+            Self::Rsi {
+                period,
+                overbought,
+                oversold,
+            } => P::Rsi {
+                period: *period,
+                overbought: *overbought,
+                oversold: *oversold,
+            }
+            .validate(),
+            Self::BollingerBands { period, multiplier } => P::BollingerBands {
+                period: *period,
+                multiplier: *multiplier,
+            }
+            .validate(),
+            Self::Macd { fast, slow, signal } => P::Macd {
+                fast: *fast,
+                slow: *slow,
+                signal: *signal,
+            }
+            .validate(),
+            Self::EmaCrossover {
+                fast_period,
+                slow_period,
+            } => P::EmaCrossover {
+                fast_period: *fast_period,
+                slow_period: *slow_period,
+            }
+            .validate(),
+            Self::Stochastic {
+                period,
+                overbought,
+                oversold,
+            } => P::Stochastic {
+                period: *period,
+                overbought: *overbought,
+                oversold: *oversold,
+            }
+            .validate(),
+            Self::AtrMeanReversion {
+                atr_period,
+                sma_period,
+                multiplier,
+            } => P::AtrMeanReversion {
+                atr_period: *atr_period,
+                sma_period: *sma_period,
+                multiplier: *multiplier,
+            }
+            .validate(),
+            Self::RsiBollinger {
+                rsi_period,
+                rsi_ob,
+                rsi_os,
+                bb_period,
+                bb_mult,
+            } => all(&[
+                P::Rsi {
+                    period: *rsi_period,
+                    overbought: *rsi_ob,
+                    oversold: *rsi_os,
+                },
+                P::BollingerBands {
+                    period: *bb_period,
+                    multiplier: *bb_mult,
+                },
+            ]),
+            Self::MacdRsi {
+                macd_fast,
+                macd_slow,
+                macd_signal,
+                rsi_period,
+                rsi_ob,
+                rsi_os,
+            } => all(&[
+                P::Macd {
+                    fast: *macd_fast,
+                    slow: *macd_slow,
+                    signal: *macd_signal,
+                },
+                P::Rsi {
+                    period: *rsi_period,
+                    overbought: *rsi_ob,
+                    oversold: *rsi_os,
+                },
+            ]),
+            Self::EmaRsi {
+                ema_fast,
+                ema_slow,
+                rsi_period,
+                rsi_ob,
+                rsi_os,
+            } => all(&[
+                P::EmaCrossover {
+                    fast_period: *ema_fast,
+                    slow_period: *ema_slow,
+                },
+                P::Rsi {
+                    period: *rsi_period,
+                    overbought: *rsi_ob,
+                    oversold: *rsi_os,
+                },
+            ]),
+            Self::StochRsi {
+                stoch_period,
+                stoch_ob,
+                stoch_os,
+                rsi_period,
+                rsi_ob,
+                rsi_os,
+            } => all(&[
+                P::Stochastic {
+                    period: *stoch_period,
+                    overbought: *stoch_ob,
+                    oversold: *stoch_os,
+                },
+                P::Rsi {
+                    period: *rsi_period,
+                    overbought: *rsi_ob,
+                    oversold: *rsi_os,
+                },
+            ]),
+            Self::MacdBollinger {
+                macd_fast,
+                macd_slow,
+                macd_signal,
+                bb_period,
+                bb_mult,
+            } => all(&[
+                P::Macd {
+                    fast: *macd_fast,
+                    slow: *macd_slow,
+                    signal: *macd_signal,
+                },
+                P::BollingerBands {
+                    period: *bb_period,
+                    multiplier: *bb_mult,
+                },
+            ]),
+            Self::TripleRsiMacdBb {
+                rsi_period,
+                rsi_ob,
+                rsi_os,
+                macd_fast,
+                macd_slow,
+                macd_signal,
+                bb_period,
+                bb_mult,
+            } => all(&[
+                P::Rsi {
+                    period: *rsi_period,
+                    overbought: *rsi_ob,
+                    oversold: *rsi_os,
+                },
+                P::Macd {
+                    fast: *macd_fast,
+                    slow: *macd_slow,
+                    signal: *macd_signal,
+                },
+                P::BollingerBands {
+                    period: *bb_period,
+                    multiplier: *bb_mult,
+                },
+            ]),
+            Self::TripleEmaRsiStoch {
+                ema_fast,
+                ema_slow,
+                rsi_period,
+                rsi_ob,
+                rsi_os,
+                stoch_period,
+                stoch_ob,
+                stoch_os,
+            } => all(&[
+                P::EmaCrossover {
+                    fast_period: *ema_fast,
+                    slow_period: *ema_slow,
+                },
+                P::Rsi {
+                    period: *rsi_period,
+                    overbought: *rsi_ob,
+                    oversold: *rsi_os,
+                },
+                P::Stochastic {
+                    period: *stoch_period,
+                    overbought: *stoch_ob,
+                    oversold: *stoch_os,
+                },
+            ]),
+            Self::Vwap { period, anchor } => P::Vwap {
+                period: *period,
+                anchor: *anchor,
+            }
+            .validate(),
+            Self::Obv { sma_period } => P::Obv {
+                sma_period: *sma_period,
+            }
+            .validate(),
+            Self::WilliamsR {
+                period,
+                overbought,
+                oversold,
+            } => P::WilliamsR {
+                period: *period,
+                overbought: *overbought,
+                oversold: *oversold,
+            }
+            .validate(),
+            Self::Adx {
+                period,
+                adx_threshold,
+            } => P::Adx {
+                period: *period,
+                adx_threshold: *adx_threshold,
+            }
+            .validate(),
+            Self::VwapRsi {
+                vwap_period,
+                rsi_period,
+                rsi_overbought,
+                rsi_oversold,
+            } => all(&[
+                P::Vwap {
+                    period: *vwap_period,
+                    anchor: VwapAnchor::Rolling,
+                },
+                P::Rsi {
+                    period: *rsi_period,
+                    overbought: *rsi_overbought,
+                    oversold: *rsi_oversold,
+                },
+            ]),
+            Self::ObvMacd {
+                obv_sma_period,
+                macd_fast,
+                macd_slow,
+                macd_signal,
+            } => all(&[
+                P::Obv {
+                    sma_period: *obv_sma_period,
+                },
+                P::Macd {
+                    fast: *macd_fast,
+                    slow: *macd_slow,
+                    signal: *macd_signal,
+                },
+            ]),
+            Self::AdxEma {
+                adx_period,
+                adx_threshold,
+                ema_fast,
+                ema_slow,
+            } => all(&[
+                P::Adx {
+                    period: *adx_period,
+                    adx_threshold: *adx_threshold,
+                },
+                P::EmaCrossover {
+                    fast_period: *ema_fast,
+                    slow_period: *ema_slow,
+                },
+            ]),
+            Self::WilliamsRStoch {
+                wr_period,
+                wr_overbought,
+                wr_oversold,
+                stoch_period,
+                stoch_overbought,
+                stoch_oversold,
+            } => all(&[
+                P::WilliamsR {
+                    period: *wr_period,
+                    overbought: *wr_overbought,
+                    oversold: *wr_oversold,
+                },
+                P::Stochastic {
+                    period: *stoch_period,
+                    overbought: *stoch_overbought,
+                    oversold: *stoch_oversold,
+                },
+            ]),
+            Self::DynamicCombo {
+                indicators, params, ..
+            } => {
+                if indicators.is_empty() || indicators.len() != params.len() {
+                    return Err(format!(
+                        "a dynamic combo needs at least one indicator and one params entry \
+                         per indicator (got {} indicators, {} params)",
+                        indicators.len(),
+                        params.len()
+                    ));
+                }
+                all(params)
+            }
+            Self::WebStrategy { params, .. } => validate_web_params(params),
+            Self::Gabagool {
+                max_pair_cost,
+                bid_offset,
+                spread_multiplier,
+            } => {
+                if *max_pair_cost <= Decimal::ZERO || *max_pair_cost > Decimal::ONE {
+                    return Err(format!(
+                        "Gabagool max_pair_cost must be in (0, 1] (got {})",
+                        max_pair_cost
+                    ));
+                }
+                if bid_offset.is_sign_negative() {
+                    return Err(format!(
+                        "Gabagool bid_offset must not be negative (got {})",
+                        bid_offset
+                    ));
+                }
+                if *spread_multiplier <= Decimal::ZERO {
+                    return Err(format!(
+                        "Gabagool spread_multiplier must be positive (got {})",
+                        spread_multiplier
+                    ));
+                }
+                Ok(())
+            }
+            Self::RelativeStrength { lookback, top_k } => {
+                check_period("Relative Strength lookback", *lookback)?;
+                if *top_k == 0 {
+                    return Err("Relative Strength top_k must be at least 1".to_string());
+                }
+                Ok(())
+            }
+            Self::PairSpread {
+                leg_a,
+                leg_b,
+                lookback,
+                entry_z,
+                exit_z,
+            } => {
+                if leg_a.trim().is_empty() || leg_b.trim().is_empty() || leg_a == leg_b {
+                    return Err("Pair Spread needs two different legs (leg_a, leg_b)".to_string());
+                }
+                if *lookback < 2 {
+                    return Err(format!(
+                        "Pair Spread lookback must be at least 2 (got {})",
+                        lookback
+                    ));
+                }
+                check_period("Pair Spread lookback", *lookback)?;
+                check_positive("Pair Spread entry_z", *entry_z)?;
+                check_range("Pair Spread exit_z", *exit_z, 0.0, f64::MAX)?;
+                if exit_z >= entry_z {
+                    return Err(format!(
+                        "Pair Spread exit_z ({}) must be below entry_z ({})",
+                        exit_z, entry_z
+                    ));
+                }
+                Ok(())
+            }
+            Self::OnnxModel {
+                model_path,
+                threshold,
+            } => {
+                if model_path.trim().is_empty() {
+                    return Err("ONNX model_path is required".to_string());
+                }
+                check_range("ONNX threshold", *threshold, 0.0, 1.0)
+            }
+            Self::Ensemble {
+                members, weights, ..
+            } => {
+                if members.is_empty() || weights.len() != members.len() {
+                    return Err(format!(
+                        "an ensemble needs at least one member and one weight per member \
+                         (got {} members, {} weights)",
+                        members.len(),
+                        weights.len()
+                    ));
+                }
+                for (i, weight) in weights.iter().enumerate() {
+                    check_range(
+                        &format!("ensemble weight {}", i + 1),
+                        *weight,
+                        0.0,
+                        f64::MAX,
+                    )?;
+                }
+                members.iter().try_for_each(Self::validate)
+            }
+            Self::Filtered { inner, filter } => {
+                validate_entry_filter(filter)?;
+                inner.validate()
+            }
+            Self::Trailing { inner, stop } => {
+                validate_trailing_stop(stop)?;
+                inner.validate()
+            }
+            Self::HtfFiltered { inner, htf_filter } => {
+                validate_htf_filter(htf_filter)?;
+                inner.validate()
+            }
+            Self::ZScoreFiltered {
+                inner,
+                zscore_filter,
+            } => {
+                validate_zscore_filter(zscore_filter)?;
+                inner.validate()
+            }
+        }
+    }
+}
+
+fn validate_web_params(params: &WebStrategyParams) -> Result<(), String> {
+    match *params {
+        WebStrategyParams::ProbabilityEdge {
+            edge_threshold,
+            rsi_period,
+            momentum_period,
+            vol_period,
+        } => {
+            check_range("edge_threshold", edge_threshold, 0.0, 1.0)?;
+            check_period("rsi_period", rsi_period)?;
+            check_period("momentum_period", momentum_period)?;
+            check_period("vol_period", vol_period)
+        }
+        WebStrategyParams::CatalystMomentum {
+            spike_threshold,
+            trailing_stop_pct,
+            lookback,
+        } => {
+            check_positive("spike_threshold", spike_threshold)?;
+            check_positive("trailing_stop_pct", trailing_stop_pct)?;
+            check_period("lookback", lookback)
+        }
+        WebStrategyParams::FavoriteCompounder {
+            min_probability,
+            take_profit,
+            sma_period,
+        } => {
+            check_range("min_probability", min_probability, 0.0, 1.0)?;
+            check_positive("take_profit", take_profit)?;
+            check_period("sma_period", sma_period)
+        }
+        WebStrategyParams::MarketMakingSim {
+            spread,
+            sma_period,
+            inventory_limit,
+        } => {
+            check_positive("spread", spread)?;
+            check_period("sma_period", sma_period)?;
+            check_positive("inventory_limit", inventory_limit)
+        }
+        WebStrategyParams::MeanReversionPoly {
+            sma_period,
+            entry_dev,
+            exit_dev,
+        } => {
+            check_period("sma_period", sma_period)?;
+            check_positive("entry_dev", entry_dev)?;
+            check_range("exit_dev", exit_dev, 0.0, entry_dev)
+        }
+    }
+}
+
+fn validate_entry_filter(filter: &EntryFilter) -> Result<(), String> {
+    let bounds = [
+        ("min_volume_24h", filter.min_volume_24h),
+        ("min_volatility_pct", filter.min_volatility_pct),
+        ("max_volatility_pct", filter.max_volatility_pct),
+    ];
+    for (field, bound) in bounds {
+        if let Some(value) = bound {
+            check_range(&format!("entry filter {}", field), value, 0.0, f64::MAX)?;
+        }
+    }
+    if let (Some(min), Some(max)) = (filter.min_volatility_pct, filter.max_volatility_pct) {
+        if min > max {
+            return Err(format!(
+                "entry filter min_volatility_pct ({}) must not exceed max_volatility_pct ({})",
+                min, max
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_trailing_stop(stop: &TrailingStop) -> Result<(), String> {
+    match *stop {
+        TrailingStop::Percent { pct } => {
+            check_positive("trailing stop pct", pct)?;
+            check_range("trailing stop pct", pct, 0.0, 100.0)
+        }
+        TrailingStop::Atr { period, multiplier } => {
+            check_period("trailing stop ATR period", period)?;
+            check_positive("trailing stop multiplier", multiplier)
+        }
+    }
+}
+
+fn validate_htf_filter(filter: &HtfFilter) -> Result<(), String> {
+    match filter.trend {
+        HtfTrend::EmaSlope { period } => check_period("HTF EMA period", period),
+        HtfTrend::Adx { period, min_adx } => {
+            check_period("HTF ADX period", period)?;
+            check_range("HTF min_adx", min_adx, 0.0, 100.0)
+        }
+    }
+}
+
+fn validate_zscore_filter(filter: &ZScoreFilter) -> Result<(), String> {
+    if filter.window < 2 {
+        return Err(format!(
+            "z-score filter window must be at least 2 (got {})",
+            filter.window
+        ));
+    }
+    check_period("z-score filter window", filter.window)?;
+    check_range("z-score filter threshold", filter.threshold, 0.0, f64::MAX)
+}
+
+/// Drop the strategies of `grid` whose parameters `validate()` rejects, logging the
+/// first error. Returns how many were dropped.
+pub fn retain_valid(grid: &mut Vec<DiscoveryStrategyType>) -> usize {
+    let before = grid.len();
+    let mut first_error = None;
+    grid.retain(|strategy| match strategy.validate() {
+        Ok(()) => true,
+        Err(e) => {
+            first_error.get_or_insert(e);
+            false
+        }
+    });
+    let dropped = before - grid.len();
+    if let Some(error) = first_error {
+        warn!(
+            dropped,
+            "Invalid strategies dropped from the grid: {}", error
+        );
+    }
+    dropped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{generate_exploratory_grid, generate_phase1_grid};
+
+    #[test]
+    fn test_validation_rejects_bad_params_and_keeps_the_grids() {
+        let rsi = |period, overbought, oversold| DiscoveryStrategyType::Rsi {
+            period,
+            overbought,
+            oversold,
+        };
+        assert!(rsi(14, 70.0, 30.0).validate().is_ok());
+        assert_eq!(
+            rsi(0, 70.0, 30.0).validate().unwrap_err(),
+            "RSI period must be between 1 and 1000 (got 0)"
+        );
+        assert_eq!(
+            rsi(14, 30.0, 70.0).validate().unwrap_err(),
+            "RSI oversold (70) must be below overbought (30)"
+        );
+        assert!(rsi(14, f64::NAN, 30.0).validate().is_err());
+        let macd = DiscoveryStrategyType::Macd {
+            fast: 26,
+            slow: 12,
+            signal: 9,
+        };
+        assert_eq!(
+            macd.validate().unwrap_err(),
+            "MACD fast period (26) must be below the slow one (12)"
+        );
+
+        // Wrapped, combined and voting strategies are checked through
+        let wrapped = DiscoveryStrategyType::ZScoreFiltered {
+            inner: Box::new(rsi(0, 70.0, 30.0)),
+            zscore_filter: ZScoreFilter {
+                window: 50,
+                threshold: 1.0,
+            },
+        };
+        assert!(wrapped.validate().unwrap_err().starts_with("RSI period"));
+        let combo: DiscoveryStrategyType = serde_json::from_value(serde_json::json!({
+            "type": "dynamic_combo",
+            "indicators": ["rsi", "ema_crossover"],
+            "params": [
+                {"indicator": "rsi", "period": 14, "overbought": 70.0, "oversold": 30.0},
+                {"indicator": "ema_crossover", "fast_period": 20, "slow_period": 20},
+            ],
+            "combine_mode": "majority",
+        }))
+        .unwrap();
+        assert!(combo.validate().unwrap_err().starts_with("EMA fast period"));
+        let ensemble = DiscoveryStrategyType::Ensemble {
+            members: vec![rsi(14, 70.0, 30.0), rsi(14, 70.0, 30.0)],
+            weights: vec![1.0],
+            vote: crate::ensemble::EnsembleVote::Majority,
+        };
+        assert!(ensemble.validate().is_err());
+
+        // Everything discovery generates passes, and only the invalid ones are dropped
+        let mut grid = generate_phase1_grid();
+        for cycle in 0..4 {
+            grid.extend(generate_exploratory_grid(cycle));
+        }
+        let valid = grid.len();
+        grid.push(rsi(0, 70.0, 30.0));
+        grid.push(macd);
+        assert_eq!(retain_valid(&mut grid), 2);
+        assert_eq!(grid.len(), valid);
+    }
+}
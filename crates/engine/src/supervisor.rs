@@ -0,0 +1,337 @@
+//! Watchdog of spawned discovery runs
+//!
+//! A run spawned in the background that panics leaves its `DiscoveryProgress` in the
+//! phase it crashed in, so the status would read "running" forever. `supervise` runs
+//! the engine as its own task and watches its `JoinHandle`: a panic is recorded on the
+//! tracker (`Error` status, `crashes`) and in the engine's store under the run's
+//! `discovery_run_id` (`run_crashes`), and a request with `restart_on_crash` is rebuilt
+//! and run again after a backoff that doubles with each crash. The results already
+//! stored keep a restarted run from recomputing them.
+//!
+//! Within a run, `catch_panic` isolates a single backtest: the scan loops skip a
+//! combination whose signal generator panics instead of losing the whole run.
+
+use std::any::Any;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use persistence::repository::discovery::RunCrashRecord;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use crate::discovery::{DiscoveryProgress, DiscoveryResult, DiscoveryStatus};
+use crate::discovery_engine::DiscoveryEngine;
+use crate::error::EngineResult;
+use crate::store::ResultStore;
+
+/// Polling interval of the cancel flag while waiting to restart
+const RESTART_POLL: Duration = Duration::from_millis(200);
+
+/// Restarts of a run whose task panicked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct RestartPolicy {
+    /// Restarts before the run is left stopped (default 3)
+    pub max_restarts: u32,
+    /// Wait before the first restart in seconds, doubled after each crash (default 30)
+    pub backoff_secs: u64,
+    /// Longest wait in seconds (default 600)
+    pub max_backoff_secs: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            backoff_secs: 30,
+            max_backoff_secs: 600,
+        }
+    }
+}
+
+impl RestartPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.backoff_secs > self.max_backoff_secs {
+            return Err(format!(
+                "backoff_secs ({}) must not exceed max_backoff_secs ({})",
+                self.backoff_secs, self.max_backoff_secs
+            ));
+        }
+        Ok(())
+    }
+
+    /// Wait before restart number `restarts + 1`, `None` once they are used up
+    pub fn backoff(&self, restarts: u32) -> Option<Duration> {
+        (restarts < self.max_restarts).then(|| {
+            let secs = self
+                .backoff_secs
+                .saturating_mul(1u64 << restarts.min(32))
+                .min(self.max_backoff_secs);
+            Duration::from_secs(secs)
+        })
+    }
+}
+
+/// Panic of a supervised run's task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunCrash {
+    /// `discovery_run_id` of the run that crashed
+    pub run_id: String,
+    /// RFC 3339
+    pub at: String,
+    pub message: String,
+    /// Restarts done before this crash (0 = the first run)
+    pub restarts: u32,
+    /// Wait before the next restart, `None` when the run stays stopped
+    pub restart_in_secs: Option<u64>,
+}
+
+impl RunCrash {
+    pub fn to_record(&self) -> RunCrashRecord {
+        RunCrashRecord {
+            run_id: self.run_id.clone(),
+            restarts: self.restarts as i64,
+            crashed_at: self.at.clone(),
+            message: self.message.clone(),
+            restart_in_secs: self.restart_in_secs.map(|s| s as i64),
+            created_at: None,
+        }
+    }
+
+    pub fn from_record(record: &RunCrashRecord) -> Self {
+        Self {
+            run_id: record.run_id.clone(),
+            at: record.crashed_at.clone(),
+            message: record.message.clone(),
+            restarts: record.restarts.max(0) as u32,
+            restart_in_secs: record.restart_in_secs.map(|s| s.max(0) as u64),
+        }
+    }
+}
+
+/// Crashes of the latest supervised session among `recent` (stored crashes, newest
+/// first), oldest first: the newest crash back to the one of the session's first run
+pub fn last_session_crashes(recent: &[RunCrashRecord]) -> Vec<RunCrash> {
+    let end = recent
+        .iter()
+        .position(|r| r.restarts <= 0)
+        .map_or(recent.len(), |idx| idx + 1);
+    recent[..end]
+        .iter()
+        .rev()
+        .map(RunCrash::from_record)
+        .collect()
+}
+
+/// Run the engine built by `build` as its own task until it ends without panicking,
+/// restarting it per the request's `restart_on_crash`. Returns the outcome of the last
+/// run, `None` when it crashed for good or was cancelled while waiting to restart.
+pub async fn supervise<F>(build: F) -> Option<EngineResult<Vec<DiscoveryResult>>>
+where
+    F: Fn() -> DiscoveryEngine,
+{
+    let engine = build();
+    let progress = engine.progress();
+    let store = engine.store();
+    let policy = engine.request().restart_on_crash;
+    let mut engine = Some(engine);
+    supervise_runs(progress, store, policy, || {
+        engine.take().unwrap_or_else(&build).run()
+    })
+    .await
+}
+
+/// `supervise` over any run factory
+async fn supervise_runs<F, Fut>(
+    progress: Arc<DiscoveryProgress>,
+    store: Option<Arc<dyn ResultStore>>,
+    policy: Option<RestartPolicy>,
+    mut run: F,
+) -> Option<EngineResult<Vec<DiscoveryResult>>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = EngineResult<Vec<DiscoveryResult>>> + Send + 'static,
+{
+    progress.crashes.write().unwrap().clear();
+    let mut restarts = 0;
+    loop {
+        let message = match tokio::spawn(run()).await {
+            Ok(outcome) => return Some(outcome),
+            Err(e) if e.is_panic() => panic_message(e.into_panic()),
+            // Runtime shutting down
+            Err(_) => return None,
+        };
+        let backoff = policy.and_then(|p| p.backoff(restarts));
+        // A run that panicked before picking its id gets one the same way
+        let run_id = progress
+            .run_id
+            .read()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| Utc::now().timestamp_millis().to_string());
+        error!(
+            run_id = %run_id,
+            restarts,
+            restart_in_secs = backoff.map(|d| d.as_secs()),
+            "Discovery task panicked: {}",
+            message
+        );
+        let crash = RunCrash {
+            run_id,
+            at: Utc::now().to_rfc3339(),
+            message,
+            restarts,
+            restart_in_secs: backoff.map(|d| d.as_secs()),
+        };
+        if let Some(store) = &store {
+            if let Err(e) = store.save_run_crash(&crash.to_record()).await {
+                warn!(error = %e, "Failed to record the crash");
+            }
+        }
+        progress.record_crash(crash);
+        let wait = backoff?;
+        if !wait_unless_cancelled(&progress, wait).await {
+            info!("Discovery restart cancelled by user");
+            *progress.status.write().unwrap() = DiscoveryStatus::Idle;
+            return None;
+        }
+        restarts += 1;
+        warn!(restarts, "Restarting crashed discovery");
+    }
+}
+
+/// Sleep `wait`; false if the run was cancelled meanwhile
+async fn wait_unless_cancelled(progress: &DiscoveryProgress, wait: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + wait;
+    loop {
+        if progress.cancelled.load(Ordering::Relaxed) {
+            return false;
+        }
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        tokio::time::sleep(RESTART_POLL.min(deadline - now)).await;
+    }
+}
+
+/// Run `f`, turning a panic into its message. Backtests only read shared state (the
+/// series cache computes outside of its lock), so nothing is left half-updated.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(panic_message)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map_or_else(|| "unknown panic".to_string(), |s| s.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::SqliteResultStore;
+    use persistence::repository::DiscoveryRepository;
+    use std::sync::atomic::AtomicU32;
+
+    #[tokio::test]
+    async fn test_supervisor_records_panics_and_restarts() {
+        let policy = RestartPolicy {
+            max_restarts: 2,
+            backoff_secs: 0,
+            max_backoff_secs: 0,
+        };
+        assert!(policy.validate().is_ok());
+        let default = RestartPolicy::default();
+        let waits: Vec<_> = (0..4).map(|n| default.backoff(n)).collect();
+        assert_eq!(
+            waits,
+            vec![
+                Some(Duration::from_secs(30)),
+                Some(Duration::from_secs(60)),
+                Some(Duration::from_secs(120)),
+                None
+            ]
+        );
+        let capped = RestartPolicy {
+            max_restarts: 10,
+            ..default
+        };
+        assert_eq!(capped.backoff(9), Some(Duration::from_secs(600)));
+
+        // Crashes twice, then finishes: both panics recorded, the outcome returned
+        let db = persistence::Database::in_memory().await.unwrap();
+        let store: Arc<dyn ResultStore> = Arc::new(SqliteResultStore::new(db.pool_clone()));
+        let progress = Arc::new(DiscoveryProgress::new());
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        let tracker = progress.clone();
+        let outcome = supervise_runs(progress.clone(), Some(store), Some(policy), move || {
+            let run = counter.fetch_add(1, Ordering::Relaxed);
+            *tracker.run_id.write().unwrap() = Some(format!("run-{}", run));
+            async move {
+                if run < 2 {
+                    panic!("crash {}", run);
+                }
+                Ok(Vec::new())
+            }
+        })
+        .await;
+        assert!(matches!(outcome, Some(Ok(_))));
+        assert_eq!(runs.load(Ordering::Relaxed), 3);
+        let crashes = progress.crashes.read().unwrap().clone();
+        assert_eq!(crashes.len(), 2);
+        assert_eq!(crashes[1].message, "crash 1");
+        assert_eq!(
+            (crashes[1].restarts, crashes[1].restart_in_secs),
+            (1, Some(0))
+        );
+
+        // Stored under the id of the run that crashed, and read back after a restart
+        let repo = DiscoveryRepository::new(db.pool());
+        let stored = repo.get_run_crashes("run-1").await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(RunCrash::from_record(&stored[0]), crashes[1]);
+        assert!(repo.get_run_crashes("run-2").await.unwrap().is_empty());
+        let recent = repo.get_recent_run_crashes(10).await.unwrap();
+        assert_eq!(last_session_crashes(&recent), crashes);
+
+        // Without a policy the first panic stops the run, status `Error`
+        let progress = Arc::new(DiscoveryProgress::new());
+        *progress.status.write().unwrap() = DiscoveryStatus::Phase1BroadScan;
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        let outcome = supervise_runs(progress.clone(), None, None, move || {
+            let run = counter.fetch_add(1, Ordering::Relaxed);
+            async move {
+                if run == 0 {
+                    panic!("{}", String::from("owned message"));
+                }
+                Ok(Vec::new())
+            }
+        })
+        .await;
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        assert!(outcome.is_none());
+        assert!(matches!(
+            *progress.status.read().unwrap(),
+            DiscoveryStatus::Error
+        ));
+        assert!(!progress.is_running());
+        assert_eq!(
+            progress.error_message.read().unwrap().as_deref(),
+            Some("Discovery task panicked: owned message")
+        );
+        // Panicked before picking a run id: one is made up for the record
+        assert!(!progress.crashes.read().unwrap()[0].run_id.is_empty());
+    }
+}
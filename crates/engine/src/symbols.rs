@@ -0,0 +1,150 @@
+//! Exchange metadata of the traded symbols
+//!
+//! Binance only accepts prices on a symbol's tick size and quantities on its lot step,
+//! above a minimum quantity and order notional (`exchangeInfo` filters). The generic
+//! backtester and the paper trader round their simulated fills with `SymbolFilters`:
+//! prices move to the tick against the trader (buys up, sells down), quantities are
+//! floored to the step, and orders below the minimums are not placed.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::TradeSide;
+
+/// Relative tolerance under which a value counts as already on its grid
+const GRID_EPSILON: f64 = 1e-9;
+
+/// A symbol as listed by the exchange, with its order filters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SymbolMetadata {
+    pub symbol: String,
+    /// `TRADING`, `BREAK`, ...
+    pub status: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    /// Price increment (`PRICE_FILTER`), 0 when unconstrained
+    pub tick_size: f64,
+    /// Quantity increment (`LOT_SIZE`), 0 when unconstrained
+    pub step_size: f64,
+    pub min_qty: f64,
+    /// Minimum order value in the quote asset (`NOTIONAL` / `MIN_NOTIONAL`)
+    pub min_notional: f64,
+}
+
+impl SymbolMetadata {
+    pub fn filters(&self) -> SymbolFilters {
+        SymbolFilters {
+            tick_size: self.tick_size,
+            step_size: self.step_size,
+            min_qty: self.min_qty,
+            min_notional: self.min_notional,
+        }
+    }
+}
+
+/// Rounding rules of a symbol's orders
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SymbolFilters {
+    pub tick_size: f64,
+    pub step_size: f64,
+    pub min_qty: f64,
+    pub min_notional: f64,
+}
+
+impl SymbolFilters {
+    /// `price` on the tick grid, rounded up for a buy and down for a sell (a price
+    /// already on the grid is returned as is)
+    pub fn round_price(&self, price: f64, side: TradeSide) -> f64 {
+        if self.tick_size <= 0.0 || on_grid(price, self.tick_size) {
+            return price;
+        }
+        let ticks = price / self.tick_size;
+        match side {
+            TradeSide::Buy => ticks.ceil() * self.tick_size,
+            TradeSide::Sell => ticks.floor() * self.tick_size,
+        }
+    }
+
+    /// `quantity` floored to the lot step; 0 when the order at `price` would be below
+    /// the minimum quantity or notional
+    pub fn round_quantity(&self, quantity: f64, price: f64) -> f64 {
+        let rounded = if self.step_size <= 0.0 || on_grid(quantity, self.step_size) {
+            quantity
+        } else {
+            (quantity / self.step_size).floor() * self.step_size
+        };
+        if rounded <= 0.0 || rounded < self.min_qty || rounded * price < self.min_notional {
+            0.0
+        } else {
+            rounded
+        }
+    }
+}
+
+/// `value` is a whole number of `step`s (up to float noise)
+fn on_grid(value: f64, step: f64) -> bool {
+    let steps = value / step;
+    (steps - steps.round()).abs() <= GRID_EPSILON * steps.abs().max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{run_backtest, BacktestLimits, DiscoveryStrategyType, SizingMode};
+    use crate::fills::FillModel;
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_tick_and_lot_rounding() {
+        let filters = SymbolFilters {
+            tick_size: 0.01,
+            step_size: 0.001,
+            min_qty: 0.001,
+            min_notional: 5.0,
+        };
+        assert_eq!(filters.round_price(102.375, TradeSide::Buy), 102.38);
+        assert_eq!(filters.round_price(102.375, TradeSide::Sell), 102.37);
+        // On the grid already (up to float noise): unchanged
+        assert_eq!(filters.round_price(100.1, TradeSide::Buy), 100.1);
+        assert!((filters.round_quantity(0.12345, 100.0) - 0.123).abs() < 1e-12);
+        assert_eq!(filters.round_quantity(0.3, 100.0), 0.3);
+        // Below the minimum quantity, then below the minimum notional
+        assert_eq!(filters.round_quantity(0.0009, 100_000.0), 0.0);
+        assert_eq!(filters.round_quantity(0.04, 100.0), 0.0);
+        assert_eq!(
+            SymbolFilters::default().round_price(1.234, TradeSide::Buy),
+            1.234
+        );
+
+        // Backtests: an unreachable minimum notional places no order, a tick grid
+        // only moves the intrabar fills
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 3_000,
+            volatility: 0.01,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 65.0,
+            oversold: 35.0,
+        };
+        let run = |limits: BacktestLimits| {
+            run_backtest(&rsi, &klines, "SYNTH", SizingMode::Fixed, &limits)
+        };
+        let limits = BacktestLimits::unlimited().with_fill_model(FillModel::OhlcPath);
+        let free = run(limits);
+        assert!(free.total_trades > 0);
+        let blocked = run(limits.with_symbol_filters(Some(SymbolFilters {
+            min_notional: 1e9,
+            ..filters
+        })));
+        assert_eq!(blocked.total_trades, 0);
+        let ticked = run(limits.with_symbol_filters(Some(SymbolFilters {
+            tick_size: 1.0,
+            ..SymbolFilters::default()
+        })));
+        assert_eq!(ticked.total_trades, free.total_trades);
+        // Buys rounded up and sells rounded down only cost
+        assert!(ticked.net_pnl < free.net_pnl);
+    }
+}
@@ -0,0 +1,186 @@
+//! Trailing stop exits of the generic backtester
+//!
+//! `DiscoveryStrategyType::Trailing` wraps a bar-signal strategy with a `TrailingStop`:
+//! once a position is open, its stop follows the highest price since entry, a fixed
+//! percentage or a multiple of the ATR below it, and the position is closed when a bar
+//! trades through the stop. Refinement re-runs each top result under a few stops and the
+//! genetic exploration perturbs them. Stop exits are tagged `ExitReason::Stop` or
+//! `ExitReason::Trailing` in the trade log and the result's `ExitBreakdown`.
+
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::DiscoveryStrategyType;
+use crate::types::Kline;
+
+/// Stops tried on each top result by refinement
+pub const REFINEMENT_STOPS: usize = 5;
+
+/// Distance of a trailing stop below the highest price since entry
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum TrailingStop {
+    /// `pct` % below the high
+    Percent { pct: f64 },
+    /// `multiplier` × the ATR over `period` bars below the high
+    Atr { period: usize, multiplier: f64 },
+}
+
+impl TrailingStop {
+    /// Short suffix of the wrapped strategy's name, e.g. `trail 2%` or `trail 3xATR14`
+    pub fn label(&self) -> String {
+        match self {
+            Self::Percent { pct } => format!("trail {}%", pct),
+            Self::Atr { period, multiplier } => format!("trail {}xATR{}", multiplier, period),
+        }
+    }
+}
+
+/// Stop levels of a `TrailingStop` over one series of klines
+pub(crate) struct StopTracker {
+    stop: TrailingStop,
+    /// ATR at each bar (ATR stops only)
+    atr: Vec<f64>,
+}
+
+impl StopTracker {
+    pub fn new(stop: TrailingStop, klines: &[Kline]) -> Self {
+        let atr = match stop {
+            TrailingStop::Atr { period, .. } => atr_series(klines, period),
+            TrailingStop::Percent { .. } => Vec::new(),
+        };
+        Self { stop, atr }
+    }
+
+    /// Stop level at bar `idx` of a position whose highest price so far is `high`
+    pub fn level(&self, high: f64, idx: usize) -> f64 {
+        match self.stop {
+            TrailingStop::Percent { pct } => high * (1.0 - pct / 100.0),
+            TrailingStop::Atr { multiplier, .. } => high - multiplier * self.atr[idx],
+        }
+    }
+}
+
+/// Average true range at every bar: the mean of the ranges so far over the first
+/// `period` bars, Wilder's smoothing after
+fn atr_series(klines: &[Kline], period: usize) -> Vec<f64> {
+    let period = period.max(1);
+    let mut atr = Vec::with_capacity(klines.len());
+    let mut prev_close: Option<f64> = None;
+    let mut value = 0.0;
+    for (idx, kline) in klines.iter().enumerate() {
+        let high = kline.high.to_f64().unwrap_or(0.0);
+        let low = kline.low.to_f64().unwrap_or(0.0);
+        let true_range = match prev_close {
+            Some(prev) => (high - low)
+                .max((high - prev).abs())
+                .max((low - prev).abs()),
+            None => high - low,
+        };
+        value += (true_range - value) / (idx + 1).min(period) as f64;
+        atr.push(value);
+        prev_close = kline.close.to_f64();
+    }
+    atr
+}
+
+/// `strategy` (unwrapped if already trailing) under `REFINEMENT_STOPS` stops, 1-4% or
+/// 2-3 ATRs. Empty for strategies without bar signals.
+pub fn stop_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoveryStrategyType> {
+    let (inner, current) = match strategy {
+        DiscoveryStrategyType::Trailing { inner, stop } => (inner.as_ref(), Some(*stop)),
+        other => (other, None),
+    };
+    if inner.is_gabagool() || inner.is_cross_sectional() {
+        return Vec::new();
+    }
+    let stops = [
+        TrailingStop::Percent { pct: 1.0 },
+        TrailingStop::Percent { pct: 2.0 },
+        TrailingStop::Percent { pct: 4.0 },
+        TrailingStop::Atr {
+            period: 14,
+            multiplier: 2.0,
+        },
+        TrailingStop::Atr {
+            period: 14,
+            multiplier: 3.0,
+        },
+    ];
+    stops
+        .into_iter()
+        .filter(|s| Some(*s) != current)
+        .map(|stop| DiscoveryStrategyType::Trailing {
+            inner: Box::new(inner.clone()),
+            stop,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{generate_refinement_grid, run_backtest, BacktestLimits, SizingMode};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_trailing_stop_exits_and_refines() {
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 3_000,
+            ..SyntheticConfig::for_model(SyntheticModel::RegimeSwitching)
+        });
+        let atr = atr_series(&klines, 14);
+        assert_eq!(atr.len(), klines.len());
+        assert!(atr.iter().all(|a| *a > 0.0));
+
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 65.0,
+            oversold: 35.0,
+        };
+        let grid = stop_refinement_grid(&rsi);
+        assert_eq!(grid.len(), REFINEMENT_STOPS);
+        assert_eq!(grid[1].name(), "RSI|trail 2%");
+        assert_eq!(
+            grid[1].trailing_stop(),
+            Some(TrailingStop::Percent { pct: 2.0 })
+        );
+
+        // A trailing strategy is refined as its inner strategy, under the same stop,
+        // and re-tried under the other stops
+        let refined = generate_refinement_grid(&grid[3]);
+        assert_eq!(refined.len(), generate_refinement_grid(&rsi).len());
+        assert!(refined
+            .iter()
+            .all(|s| s.trailing_stop() == grid[3].trailing_stop()));
+        assert_eq!(stop_refinement_grid(&grid[3]).len(), REFINEMENT_STOPS - 1);
+
+        // Round trip through the stored params
+        let json = serde_json::to_string(&grid[3]).unwrap();
+        let back: DiscoveryStrategyType = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.name(), "RSI|trail 2xATR14");
+
+        // Without a stop every exit is a signal or the end of the data; a tight stop
+        // closes positions early, and every trade has one reason
+        let limits = BacktestLimits::unlimited();
+        let run = |s: &DiscoveryStrategyType| {
+            run_backtest(s, &klines, "SYNTH", SizingMode::Fixed, &limits)
+        };
+        let plain = run(&rsi).exit_breakdown.unwrap();
+        assert!(plain.signal.trades > 0);
+        assert_eq!(plain.stop.trades + plain.trailing.trades, 0);
+        assert!(plain.timeout.trades <= 1);
+
+        let tight = DiscoveryStrategyType::Trailing {
+            inner: Box::new(rsi.clone()),
+            stop: TrailingStop::Percent { pct: 0.5 },
+        };
+        let result = run(&tight);
+        let exits = result.exit_breakdown.unwrap();
+        assert!(exits.stop.trades + exits.trailing.trades > 0);
+        assert_eq!(exits.total_trades(), result.total_trades);
+        assert!(exits.stop.pnl <= 0.0);
+    }
+}
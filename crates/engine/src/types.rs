@@ -1,5 +1,8 @@
 //! Types for the backtesting engine
 
+use std::collections::BTreeMap;
+
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
@@ -68,6 +71,188 @@ pub struct BacktestTrade {
     pub size: Decimal,
     pub pnl: Decimal,
     pub pnl_pct: Decimal,
+    pub exit_reason: ExitReason,
+}
+
+/// Why a position was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    /// Sell signal of the strategy
+    Signal,
+    /// Trailing stop hit before it rose above the entry price
+    Stop,
+    /// Trailing stop hit after it rose above the entry price
+    Trailing,
+    /// Still open at the last bar run (end of the data or early stop)
+    Timeout,
+}
+
+/// Closed trades of one exit reason
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ExitStats {
+    pub trades: u32,
+    pub winning_trades: u32,
+    /// Sum of the trades' price PnL, before fees
+    pub pnl: f64,
+}
+
+/// Trades of a backtest by exit reason
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ExitBreakdown {
+    pub signal: ExitStats,
+    pub stop: ExitStats,
+    pub trailing: ExitStats,
+    pub timeout: ExitStats,
+}
+
+impl ExitBreakdown {
+    pub fn from_trades(trades: &[BacktestTrade]) -> Self {
+        let mut breakdown = Self::default();
+        for trade in trades {
+            breakdown.record(trade.exit_reason, trade.pnl.to_f64().unwrap_or(0.0));
+        }
+        breakdown
+    }
+
+    pub fn record(&mut self, reason: ExitReason, pnl: f64) {
+        let stats = match reason {
+            ExitReason::Signal => &mut self.signal,
+            ExitReason::Stop => &mut self.stop,
+            ExitReason::Trailing => &mut self.trailing,
+            ExitReason::Timeout => &mut self.timeout,
+        };
+        stats.trades += 1;
+        if pnl > 0.0 {
+            stats.winning_trades += 1;
+        }
+        stats.pnl += pnl;
+    }
+
+    pub fn total_trades(&self) -> u32 {
+        self.signal.trades + self.stop.trades + self.trailing.trades + self.timeout.trades
+    }
+}
+
+/// Where a backtest's PnL went between its signals and its net result:
+/// `signal_pnl - timing_cost - slippage_drag = gross_pnl` and
+/// `gross_pnl - fee_drag = net_pnl`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CostAttribution {
+    /// Price PnL had every order filled at the price it was decided on: the close of
+    /// the signal bar, the level of a stop
+    pub signal_pnl: f64,
+    /// PnL lost to the fill model: fills on the next bar, tick rounding, stops gapping
+    /// through their level (negative when the fills beat the signal prices)
+    pub timing_cost: f64,
+    /// PnL lost to slippage, 0 as no slippage is modeled yet
+    pub slippage_drag: f64,
+    /// Price PnL of the fills
+    pub gross_pnl: f64,
+    /// Entry and exit fees
+    pub fee_drag: f64,
+    pub net_pnl: f64,
+    pub trades: u32,
+    /// Trades with a positive price PnL
+    pub winning_trades: u32,
+    /// Winning trades whose own fees turned them into losses
+    pub fee_flipped_trades: u32,
+}
+
+impl CostAttribution {
+    /// Add a closed trade: its price PnL, the fees of its entry and exit, and the PnL
+    /// its fills lost against the signal prices
+    pub fn record_trade(&mut self, pnl: f64, fees: f64, timing_cost: f64) {
+        self.trades += 1;
+        self.signal_pnl += pnl + timing_cost;
+        self.timing_cost += timing_cost;
+        self.gross_pnl += pnl;
+        self.fee_drag += fees;
+        self.net_pnl += pnl - fees;
+        if pnl > 0.0 {
+            self.winning_trades += 1;
+            if pnl <= fees {
+                self.fee_flipped_trades += 1;
+            }
+        }
+    }
+
+    /// Mean fees of a trade, 0 without trades
+    pub fn fee_per_trade(&self) -> f64 {
+        if self.trades == 0 {
+            0.0
+        } else {
+            self.fee_drag / self.trades as f64
+        }
+    }
+
+    /// Trades with a positive price PnL, in %
+    pub fn win_rate(&self) -> f64 {
+        self.pct_of_trades(self.winning_trades)
+    }
+
+    /// Trades still positive once their fees are paid, in %
+    pub fn net_win_rate(&self) -> f64 {
+        self.pct_of_trades(self.winning_trades - self.fee_flipped_trades)
+    }
+
+    fn pct_of_trades(&self, count: u32) -> f64 {
+        if self.trades == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / self.trades as f64
+        }
+    }
+}
+
+/// Share of the best week above which a result is flagged as concentrated
+pub const CONCENTRATED_WEEK_SHARE: f64 = 0.5;
+
+const WEEK_MS: i64 = 7 * 86_400_000;
+
+/// How the price PnL of a backtest spreads over the weeks its trades closed in. A few
+/// lucky days carrying the result show as a high Herfindahl-Hirschman index (sum of
+/// the squared weekly shares of the absolute PnL: 1 when one week holds it all, 1/n
+/// for n even weeks) and a large best-week share.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PnlConcentration {
+    /// Herfindahl-Hirschman index of the weekly PnL, in (0, 1]
+    pub hhi: f64,
+    /// Share (0-1) of the absolute PnL made or lost in the biggest week
+    pub top_week_share: f64,
+    /// Weeks with at least one closed trade
+    pub active_weeks: u32,
+}
+
+impl PnlConcentration {
+    /// `None` without trades, or when they all broke even
+    pub fn from_trades(trades: &[BacktestTrade]) -> Option<Self> {
+        let mut weeks: BTreeMap<i64, f64> = BTreeMap::new();
+        for trade in trades {
+            let week = trade.exit_time.div_euclid(WEEK_MS);
+            *weeks.entry(week).or_default() += trade.pnl.to_f64().unwrap_or(0.0);
+        }
+        let total: f64 = weeks.values().map(|pnl| pnl.abs()).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let shares = weeks.values().map(|pnl| pnl.abs() / total);
+        Some(Self {
+            hhi: shares.clone().map(|s| s * s).sum(),
+            top_week_share: shares.fold(0.0, f64::max),
+            active_weeks: weeks.len() as u32,
+        })
+    }
+
+    /// More than `CONCENTRATED_WEEK_SHARE` of the PnL comes from a single week
+    pub fn is_concentrated(&self) -> bool {
+        self.top_week_share > CONCENTRATED_WEEK_SHARE
+    }
 }
 
 /// A point on the equity curve
@@ -0,0 +1,365 @@
+//! Strategy re-validation and performance drift
+//!
+//! A knowledge base strategy is re-validated by backtesting its stored parameters again
+//! on the latest klines (same symbol, window, sizing mode and probability model as the
+//! original result).
+//! Every run is kept in `strategy_validations`; `drift_report` lines the series up
+//! against the original backtest and flags strategies whose performance decays past
+//! the `DecayThresholds`.
+
+use anyhow::{bail, Context};
+use persistence::repository::{DiscoveryBacktestRecord, StrategyValidationRecord};
+use serde::{Deserialize, Serialize};
+
+use crate::api::BinanceClient;
+use crate::data_quality::{fetch_checked_klines, DataQualityConfig};
+use crate::discovery::{
+    dec_to_f64, decode_strategy_params, parse_sizing_mode, run_backtest_with_fees, BacktestLimits,
+    DiscoveryResult, DiscoveryStrategyType,
+};
+use crate::fees::PolymarketFeeConfig;
+use crate::probability::ProbabilityModelConfig;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Strategy of a stored record, if it can be re-validated on its own symbol
+pub fn validation_strategy(
+    record: &DiscoveryBacktestRecord,
+) -> Result<DiscoveryStrategyType, String> {
+    let strategy =
+        decode_strategy_params(&record.strategy_params, record.schema_version.unwrap_or(0))?;
+    if strategy.is_cross_sectional() {
+        return Err(format!(
+            "{} trades a basket of symbols and cannot be re-validated on {}",
+            record.strategy_name, record.symbol
+        ));
+    }
+    Ok(strategy)
+}
+
+/// Backtest a stored strategy again over its `days` window ending now.
+/// The returned record is not saved yet.
+pub async fn revalidate(
+    record: &DiscoveryBacktestRecord,
+    binance: &BinanceClient,
+) -> anyhow::Result<StrategyValidationRecord> {
+    let backtest_id = record.id.context("record has no id")?;
+    let strategy = validation_strategy(record).map_err(anyhow::Error::msg)?;
+
+    let end_time = chrono::Utc::now().timestamp_millis();
+    let start_time = end_time - record.days * DAY_MS;
+    let (klines, quality) = fetch_checked_klines(
+        binance,
+        &record.symbol,
+        "15m",
+        start_time,
+        end_time,
+        &DataQualityConfig::default(),
+    )
+    .await?;
+    quality.warn_if_degraded();
+    if klines.is_empty() {
+        bail!("No klines for {}", record.symbol);
+    }
+
+    // Same fee estimates as the stored result
+    let fee_config = PolymarketFeeConfig {
+        probability_model: ProbabilityModelConfig::from_name(record.probability_model.as_deref()),
+        ..PolymarketFeeConfig::default()
+    };
+    let result = run_backtest_with_fees(
+        &strategy,
+        &klines,
+        &record.symbol,
+        parse_sizing_mode(&record.sizing_mode),
+        &BacktestLimits::default(),
+        &fee_config,
+    );
+    Ok(validation_record(backtest_id, record.days, &result))
+}
+
+fn validation_record(
+    backtest_id: i64,
+    days: i64,
+    result: &DiscoveryResult,
+) -> StrategyValidationRecord {
+    StrategyValidationRecord {
+        id: None,
+        backtest_id,
+        days,
+        composite_score: dec_to_f64(result.composite_score),
+        net_pnl: dec_to_f64(result.net_pnl),
+        win_rate: dec_to_f64(result.win_rate),
+        total_trades: result.total_trades as i64,
+        sharpe_ratio: dec_to_f64(result.sharpe_ratio),
+        max_drawdown_pct: dec_to_f64(result.max_drawdown_pct),
+        profit_factor: dec_to_f64(result.profit_factor),
+        abort_reason: result.abort_reason.map(|r| r.as_str().to_string()),
+        validated_at: None,
+    }
+}
+
+// ============================================================================
+// Drift
+// ============================================================================
+
+/// How far the latest validation may fall below the original backtest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DecayThresholds {
+    /// Win rate drop, in percentage points
+    pub win_rate_drop: f64,
+    /// Composite score drop, in % of the original (positive) score
+    pub score_drop_pct: f64,
+}
+
+impl Default for DecayThresholds {
+    fn default() -> Self {
+        Self {
+            win_rate_drop: 10.0,
+            score_drop_pct: 30.0,
+        }
+    }
+}
+
+/// One validation, with its change from the original backtest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DriftPoint {
+    pub validated_at: Option<i64>,
+    pub days: i64,
+    pub composite_score: f64,
+    pub win_rate: f64,
+    pub net_pnl: f64,
+    pub total_trades: i64,
+    pub abort_reason: Option<String>,
+    pub win_rate_change: f64,
+    pub score_change: f64,
+}
+
+/// Validation history of a knowledge base strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StrategyDrift {
+    pub backtest_id: i64,
+    pub strategy_name: String,
+    pub symbol: String,
+    pub baseline_score: f64,
+    pub baseline_win_rate: f64,
+    /// Oldest first
+    pub validations: Vec<DriftPoint>,
+    /// Least-squares win rate change per validation, the original backtest being
+    /// point 0 (`None` before the first validation)
+    pub win_rate_slope: Option<f64>,
+    /// The latest validation breaches a threshold
+    pub decaying: bool,
+    pub decay_reason: Option<String>,
+    pub thresholds: DecayThresholds,
+}
+
+/// Compare the validations of `baseline` (oldest first) with its original result
+pub fn drift_report(
+    baseline: &DiscoveryBacktestRecord,
+    history: &[StrategyValidationRecord],
+    thresholds: DecayThresholds,
+) -> StrategyDrift {
+    let validations: Vec<DriftPoint> = history
+        .iter()
+        .map(|v| DriftPoint {
+            validated_at: v.validated_at,
+            days: v.days,
+            composite_score: v.composite_score,
+            win_rate: v.win_rate,
+            net_pnl: v.net_pnl,
+            total_trades: v.total_trades,
+            abort_reason: v.abort_reason.clone(),
+            win_rate_change: v.win_rate - baseline.win_rate,
+            score_change: v.composite_score - baseline.composite_score,
+        })
+        .collect();
+
+    let win_rates: Vec<f64> = std::iter::once(baseline.win_rate)
+        .chain(history.iter().map(|v| v.win_rate))
+        .collect();
+    let win_rate_slope = (win_rates.len() >= 2).then(|| slope(&win_rates));
+
+    let decay_reason = validations
+        .last()
+        .and_then(|latest| decay_reason(baseline, latest, &thresholds));
+
+    StrategyDrift {
+        backtest_id: baseline.id.unwrap_or_default(),
+        strategy_name: baseline.strategy_name.clone(),
+        symbol: baseline.symbol.clone(),
+        baseline_score: baseline.composite_score,
+        baseline_win_rate: baseline.win_rate,
+        validations,
+        win_rate_slope,
+        decaying: decay_reason.is_some(),
+        decay_reason,
+        thresholds,
+    }
+}
+
+fn decay_reason(
+    baseline: &DiscoveryBacktestRecord,
+    latest: &DriftPoint,
+    thresholds: &DecayThresholds,
+) -> Option<String> {
+    if let Some(reason) = &latest.abort_reason {
+        return Some(format!("latest validation aborted ({reason})"));
+    }
+    if -latest.win_rate_change > thresholds.win_rate_drop {
+        return Some(format!(
+            "win rate {:.1}% → {:.1}% (-{:.1} pts)",
+            baseline.win_rate, latest.win_rate, -latest.win_rate_change
+        ));
+    }
+    // A relative drop is only meaningful from a positive score
+    if baseline.composite_score > 0.0 {
+        let drop_pct = -latest.score_change / baseline.composite_score * 100.0;
+        if drop_pct > thresholds.score_drop_pct {
+            return Some(format!(
+                "composite score {:.2} → {:.2} (-{:.0}%)",
+                baseline.composite_score, latest.composite_score, drop_pct
+            ));
+        }
+    }
+    None
+}
+
+/// Least-squares slope of `values` against their index
+fn slope(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+    let (num, den) = values
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(num, den), (i, y)| {
+            let dx = i as f64 - mean_x;
+            (num + dx * (y - mean_y), den + dx * dx)
+        });
+    if den == 0.0 {
+        0.0
+    } else {
+        num / den
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline() -> DiscoveryBacktestRecord {
+        DiscoveryBacktestRecord {
+            id: Some(7),
+            params_hash: "h".to_string(),
+            strategy_type: "rsi".to_string(),
+            strategy_name: "RSI".to_string(),
+            strategy_params: "{}".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            days: 90,
+            sizing_mode: "Fixed".to_string(),
+            composite_score: 50.0,
+            net_pnl: 120.0,
+            gross_pnl: 150.0,
+            total_fees: 30.0,
+            win_rate: 60.0,
+            total_trades: 40,
+            sharpe_ratio: 1.2,
+            max_drawdown_pct: 8.0,
+            profit_factor: 1.5,
+            avg_trade_pnl: 3.0,
+            hit_rate: None,
+            avg_locked_profit: None,
+            discovery_run_id: None,
+            phase: None,
+            sortino_ratio: None,
+            max_consecutive_losses: None,
+            avg_win_pnl: None,
+            avg_loss_pnl: None,
+            total_volume: None,
+            annualized_return_pct: None,
+            annualized_sharpe: None,
+            strategy_confidence: None,
+            schema_version: Some(1),
+            abort_reason: None,
+            probability_model: None,
+            drawdown_throttle: None,
+            cross_symbol_score: None,
+            exit_breakdown: None,
+            decayed_score: None,
+            score_30d: None,
+            net_pnl_30d: None,
+            win_rate_30d: None,
+            trades_30d: None,
+            score_90d: None,
+            net_pnl_90d: None,
+            win_rate_90d: None,
+            trades_90d: None,
+            pnl_hhi: None,
+            top_week_pnl_share: None,
+            active_weeks: None,
+            longest_drawdown_days: None,
+            avg_recovery_days: None,
+            max_exposure_pct: None,
+            avg_exposure_pct: None,
+            warmup_bars: None,
+            effective_days: None,
+            engine_version: None,
+            logic_version: None,
+            cost_attribution: None,
+        }
+    }
+
+    fn validation(win_rate: f64, score: f64) -> StrategyValidationRecord {
+        StrategyValidationRecord {
+            id: None,
+            backtest_id: 7,
+            days: 90,
+            composite_score: score,
+            net_pnl: 10.0,
+            win_rate,
+            total_trades: 35,
+            sharpe_ratio: 1.0,
+            max_drawdown_pct: 9.0,
+            profit_factor: 1.2,
+            abort_reason: None,
+            validated_at: Some(1_700_000_000),
+        }
+    }
+
+    #[test]
+    fn test_drift_report_flags_decay() {
+        let base = baseline();
+        let thresholds = DecayThresholds::default();
+
+        let fresh = drift_report(&base, &[], thresholds);
+        assert!(fresh.validations.is_empty());
+        assert_eq!(fresh.win_rate_slope, None);
+        assert!(!fresh.decaying);
+
+        // 60 → 56 → 52: steady slide, still within 10 pts and 30 % of the score
+        let history = [validation(56.0, 45.0), validation(52.0, 40.0)];
+        let drift = drift_report(&base, &history, thresholds);
+        assert_eq!(drift.validations[1].win_rate_change, -8.0);
+        assert_eq!(drift.validations[1].score_change, -10.0);
+        assert!((drift.win_rate_slope.unwrap() + 4.0).abs() < 1e-9);
+        assert!(!drift.decaying);
+
+        let history = [validation(56.0, 45.0), validation(48.0, 40.0)];
+        let drift = drift_report(&base, &history, thresholds);
+        assert!(drift.decaying);
+        assert!(drift.decay_reason.unwrap().starts_with("win rate"));
+
+        let history = [validation(58.0, 30.0)];
+        assert!(drift_report(&base, &history, thresholds).decaying);
+
+        // Only the latest validation counts
+        let mut ruined = validation(60.0, -9999.0);
+        ruined.abort_reason = Some("ruin".to_string());
+        assert!(drift_report(&base, &[ruined.clone()], thresholds).decaying);
+        assert!(!drift_report(&base, &[ruined, validation(59.0, 49.0)], thresholds).decaying);
+    }
+}
@@ -4,11 +4,9 @@
 //! internet research. 5 are backtestable (compatible with Binance klines + Polymarket
 //! fee model), 7 are display-only (require data not available in the current engine).
 
-use crate::discovery::estimate_poly_probability;
+use crate::discovery::estimate_poly_probability_f64;
 use crate::indicators::{close_f64, SignalGenerator, SignalWithConfidence};
 use crate::types::Kline;
-use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use ta::indicators::{RelativeStrengthIndex, SimpleMovingAverage};
 use ta::Next;
@@ -19,6 +17,7 @@ use ta::Next;
 
 /// Identifier for web-researched strategies
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum WebStrategyId {
     ProbabilityEdge,
@@ -52,6 +51,7 @@ impl WebStrategyId {
 
 /// Parameters for each web strategy
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(tag = "web_strategy", rename_all = "snake_case")]
 pub enum WebStrategyParams {
     ProbabilityEdge {
@@ -214,6 +214,7 @@ impl WebStrategyParams {
 
 /// A catalogue entry for a web-researched strategy
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct WebStrategyCatalogEntry {
     pub id: String,
     pub name: &'static str,
@@ -434,6 +435,11 @@ impl SignalGenerator for ProbabilityEdgeGenerator {
         &self.name
     }
 
+    fn warmup_bars(&self) -> usize {
+        let periods = self.rsi_period.max(self.momentum_period);
+        periods.max(self.vol_period) + 4
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
         self.bars_seen += 1;
@@ -468,7 +474,7 @@ impl SignalGenerator for ProbabilityEdgeGenerator {
             let std_dev = variance.sqrt();
             self.vol_sma.next(std_dev);
             // Low vol = squeeze = potential breakout
-            if std_dev > 0.0 { (0.01 - std_dev).max(-0.5).min(0.5) } else { 0.0 }
+            if std_dev > 0.0 { (0.01 - std_dev).clamp(-0.5, 0.5) } else { 0.0 }
         } else {
             0.0
         };
@@ -482,10 +488,7 @@ impl SignalGenerator for ProbabilityEdgeGenerator {
         let composite = 0.4 * rsi_signal + 0.3 * momentum_signal + 0.3 * vol_signal;
 
         // Convert to "estimated probability" using Polymarket mapping
-        let entry_dec = Decimal::from_str_exact(&format!("{:.4}", self.baseline_price)).unwrap_or(dec!(1));
-        let current_dec = Decimal::from_str_exact(&format!("{:.4}", close)).unwrap_or(dec!(1));
-        let market_prob: f64 = estimate_poly_probability(entry_dec, current_dec)
-            .to_string().parse().unwrap_or(0.5);
+        let market_prob = estimate_poly_probability_f64(self.baseline_price, close);
 
         // Our estimated probability (composite → probability shift)
         let estimated_prob = (market_prob + composite * 0.3).clamp(0.05, 0.95);
@@ -545,6 +548,10 @@ impl SignalGenerator for CatalystMomentumGenerator {
         &self.name
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.lookback + 1
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
         let sma_val = self.sma.next(close);
@@ -626,6 +633,10 @@ impl SignalGenerator for FavoriteCompounderGenerator {
         &self.name
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.sma_period + 1
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
         let sma_val = self.sma.next(close);
@@ -640,10 +651,7 @@ impl SignalGenerator for FavoriteCompounderGenerator {
         }
 
         // Estimate probability from price vs baseline
-        let entry_dec = Decimal::from_str_exact(&format!("{:.4}", self.baseline_price)).unwrap_or(dec!(1));
-        let current_dec = Decimal::from_str_exact(&format!("{:.4}", close)).unwrap_or(dec!(1));
-        let prob: f64 = estimate_poly_probability(entry_dec, current_dec)
-            .to_string().parse().unwrap_or(0.5);
+        let prob = estimate_poly_probability_f64(self.baseline_price, close);
 
         if self.in_position {
             // Take profit when price has risen enough
@@ -710,6 +718,10 @@ impl SignalGenerator for MarketMakingSimGenerator {
         &self.name
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.sma_period + 1
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
         let mid = self.sma.next(close);
@@ -780,6 +792,10 @@ impl SignalGenerator for MeanReversionPolyGenerator {
         &self.name
     }
 
+    fn warmup_bars(&self) -> usize {
+        self.sma_period + 1
+    }
+
     fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
         let close = close_f64(kline);
         let fair_value = self.sma.next(close);
@@ -886,8 +902,8 @@ mod tests {
         // Generate a trending price series that should trigger signals
         let mut prices: Vec<f64> = (0..100).map(|i| 100.0 + (i as f64) * 0.1).collect();
         // Add a sharp drop to trigger buy signal
-        for i in 80..100 {
-            prices[i] = 100.0 - (i as f64 - 80.0) * 2.0;
+        for (i, price) in prices.iter_mut().enumerate().skip(80) {
+            *price = 100.0 - (i as f64 - 80.0) * 2.0;
         }
         let klines = make_klines(&prices);
         let mut gen = ProbabilityEdgeGenerator::new(0.03, 14, 10, 20);
@@ -912,9 +928,7 @@ mod tests {
             prices.push(100.0 + (i as f64 + 1.0) * 5.0);
         }
         // Drop for trailing stop
-        for _ in 0..10 {
-            prices.push(90.0);
-        }
+        prices.extend(std::iter::repeat_n(90.0, 10));
         let klines = make_klines(&prices);
         let mut gen = CatalystMomentumGenerator::new(0.02, 0.015, 20);
         let mut signals = Vec::new();
@@ -955,9 +969,7 @@ mod tests {
         // Trending up then sharp drop
         let mut prices: Vec<f64> = (0..70).map(|i| 100.0 + (i as f64) * 0.3).collect();
         // Sharp drop
-        for _ in 0..20 {
-            prices.push(90.0);
-        }
+        prices.extend(std::iter::repeat_n(90.0, 20));
         let klines = make_klines(&prices);
         let mut gen = MeanReversionPolyGenerator::new(50, 0.03, 0.01);
         let mut signals = Vec::new();
@@ -0,0 +1,246 @@
+//! Z-score conviction filter on a strategy's signals
+//!
+//! `DiscoveryStrategyType::ZScoreFiltered` wraps a bar-signal strategy with a
+//! `ZScoreFilter`: the inner generator's raw metric (its first feature, e.g. the RSI
+//! value or the MACD line, or its signed confidence when it reports none) is turned into
+//! a z-score over a rolling window, and buys and sells are only kept when `|z|` reaches
+//! the threshold. Marginal signals near the metric's usual range churn trades whose edge
+//! the fees eat; refinement re-runs each top result under a few filters.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::DiscoveryStrategyType;
+use crate::indicators::{SignalGenerator, SignalWithConfidence};
+use crate::strategy::Signal;
+use crate::types::Kline;
+
+/// Filters tried on each top result by refinement
+pub const REFINEMENT_ZSCORE_FILTERS: usize = 3;
+
+/// Signals kept only when the inner metric stands out of its recent range
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct ZScoreFilter {
+    /// Bars of the rolling mean and standard deviation
+    pub window: usize,
+    /// Smallest `|z|` of a kept buy or sell
+    pub threshold: f64,
+}
+
+impl ZScoreFilter {
+    /// Short suffix of the wrapped strategy's name, e.g. `z50>1.5`
+    pub fn label(&self) -> String {
+        format!("z{}>{}", self.window, self.threshold)
+    }
+}
+
+/// Rolling z-score of a metric
+#[derive(Debug, Clone)]
+pub struct RollingZScore {
+    window: usize,
+    values: VecDeque<f64>,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl RollingZScore {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(2),
+            values: VecDeque::with_capacity(window.max(2) + 1),
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    /// Add a value; its z-score against the window it closes, `None` until the window
+    /// is full or while the window is flat
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        let value = if value.is_finite() { value } else { 0.0 };
+        self.values.push_back(value);
+        self.sum += value;
+        self.sum_sq += value * value;
+        if self.values.len() > self.window {
+            let old = self.values.pop_front().unwrap();
+            self.sum -= old;
+            self.sum_sq -= old * old;
+        }
+        if self.values.len() < self.window {
+            return None;
+        }
+        let n = self.window as f64;
+        let mean = self.sum / n;
+        let std = (self.sum_sq / n - mean * mean).max(0.0).sqrt();
+        // Running sums drift by a few ulps: treat a near-flat window as flat
+        (std > 1e-9 * mean.abs().max(1.0)).then(|| (value - mean) / std)
+    }
+}
+
+/// Holds the inner generator's buys and sells unless the z-score of its metric reaches
+/// the threshold (and until the window is full); holds pass through
+pub struct ZScoreFilteredSignalGenerator {
+    inner: Box<dyn SignalGenerator>,
+    filter: ZScoreFilter,
+    zscore: RollingZScore,
+    last_z: f64,
+}
+
+impl ZScoreFilteredSignalGenerator {
+    pub fn new(inner: Box<dyn SignalGenerator>, filter: ZScoreFilter) -> Self {
+        Self {
+            inner,
+            filter,
+            zscore: RollingZScore::new(filter.window),
+            last_z: 0.0,
+        }
+    }
+}
+
+impl SignalGenerator for ZScoreFilteredSignalGenerator {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn warmup_bars(&self) -> usize {
+        self.inner.warmup_bars().max(self.filter.window)
+    }
+
+    fn on_bar(&mut self, kline: &Kline) -> SignalWithConfidence {
+        let signal = self.inner.on_bar(kline);
+        let metric = match self.inner.features().first() {
+            Some((_, value)) => *value,
+            None => match signal.signal {
+                Signal::Buy => signal.confidence,
+                Signal::Sell => -signal.confidence,
+                Signal::Hold => 0.0,
+            },
+        };
+        self.last_z = self.zscore.push(metric).unwrap_or(0.0);
+        if signal.signal != Signal::Hold && self.last_z.abs() < self.filter.threshold {
+            return SignalWithConfidence::hold();
+        }
+        signal
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.zscore = RollingZScore::new(self.filter.window);
+        self.last_z = 0.0;
+    }
+
+    fn features(&self) -> Vec<(&'static str, f64)> {
+        let mut features = self.inner.features();
+        features.push(("zscore", self.last_z));
+        features
+    }
+}
+
+/// `strategy` (unwrapped if already z-score-filtered) under `REFINEMENT_ZSCORE_FILTERS`
+/// filters: |z| above 1 and 1.5 over 50 bars, and above 2 over 100 bars. Empty for
+/// strategies without bar signals.
+pub fn zscore_refinement_grid(strategy: &DiscoveryStrategyType) -> Vec<DiscoveryStrategyType> {
+    let (inner, current) = match strategy {
+        DiscoveryStrategyType::ZScoreFiltered {
+            inner,
+            zscore_filter,
+        } => (inner.as_ref(), Some(*zscore_filter)),
+        other => (other, None),
+    };
+    if inner.is_gabagool() || inner.is_cross_sectional() {
+        return Vec::new();
+    }
+    let filters = [
+        ZScoreFilter {
+            window: 50,
+            threshold: 1.0,
+        },
+        ZScoreFilter {
+            window: 50,
+            threshold: 1.5,
+        },
+        ZScoreFilter {
+            window: 100,
+            threshold: 2.0,
+        },
+    ];
+    filters
+        .into_iter()
+        .filter(|f| Some(*f) != current)
+        .map(|zscore_filter| DiscoveryStrategyType::ZScoreFiltered {
+            inner: Box::new(inner.clone()),
+            zscore_filter,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{generate_refinement_grid, run_backtest, BacktestLimits, SizingMode};
+    use crate::synthetic::{generate_klines, SyntheticConfig, SyntheticModel};
+
+    #[test]
+    fn test_zscore_filter_suppresses_marginal_signals_and_refines() {
+        // Window of 4 over 1, 2, 3, 4: mean 2.5, population std sqrt(1.25)
+        let mut zscore = RollingZScore::new(4);
+        assert_eq!(zscore.push(1.0), None);
+        assert_eq!(zscore.push(2.0), None);
+        assert_eq!(zscore.push(3.0), None);
+        let z = zscore.push(4.0).unwrap();
+        assert!((z - 1.5 / 1.25_f64.sqrt()).abs() < 1e-9);
+        // 1 leaves the window: 2, 3, 4, 0
+        let z = zscore.push(0.0).unwrap();
+        assert!((z + 2.25 / 2.1875_f64.sqrt()).abs() < 1e-9);
+        let mut flat = RollingZScore::new(3);
+        assert!((0..5).all(|_| flat.push(7.0).is_none()));
+
+        let rsi = DiscoveryStrategyType::Rsi {
+            period: 14,
+            overbought: 65.0,
+            oversold: 35.0,
+        };
+        let grid = zscore_refinement_grid(&rsi);
+        assert_eq!(grid.len(), REFINEMENT_ZSCORE_FILTERS);
+        assert_eq!(grid[1].name(), "RSI|z50>1.5");
+        assert_eq!(grid[1].type_tag(), "zscore_filtered");
+        let DiscoveryStrategyType::ZScoreFiltered { zscore_filter, .. } = &grid[1] else {
+            panic!("not z-score-filtered");
+        };
+
+        // Refined as the inner strategy under the same filter, re-tried under the others
+        let refined = generate_refinement_grid(&grid[1]);
+        assert_eq!(refined.len(), generate_refinement_grid(&rsi).len());
+        assert!(refined.iter().all(|s| matches!(
+            s,
+            DiscoveryStrategyType::ZScoreFiltered { zscore_filter: f, .. } if f == zscore_filter
+        )));
+        assert_eq!(
+            zscore_refinement_grid(&grid[1]).len(),
+            REFINEMENT_ZSCORE_FILTERS - 1
+        );
+
+        // Round trip through the stored params
+        let json = serde_json::to_string(&grid[2]).unwrap();
+        assert!(json.contains("\"window\":100"));
+        let back: DiscoveryStrategyType = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.name(), grid[2].name());
+
+        // Filters only remove signals, and a higher threshold removes more
+        let klines = generate_klines(&SyntheticConfig {
+            bars: 3_000,
+            ..SyntheticConfig::for_model(SyntheticModel::MeanReverting)
+        });
+        let limits = BacktestLimits::unlimited();
+        let run = |s: &DiscoveryStrategyType| {
+            run_backtest(s, &klines, "SYNTH", SizingMode::Fixed, &limits).total_trades
+        };
+        let unfiltered = run(&rsi);
+        assert!(unfiltered > 0);
+        let filtered: Vec<u32> = grid.iter().map(run).collect();
+        assert!(filtered.iter().all(|&t| t <= unfiltered));
+        assert!(filtered[1] <= filtered[0]);
+    }
+}
@@ -6,7 +6,7 @@ pub mod repository;
 pub mod schema;
 
 pub use sqlx::sqlite::SqlitePool;
-use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::sqlite::{SqliteConnection, SqlitePoolOptions};
 use std::path::Path;
 use thiserror::Error;
 
@@ -57,6 +57,20 @@ impl Database {
         Ok(db)
     }
 
+    /// Open an existing database read-only: no migrations and no pragma that writes, so
+    /// a `serve --read-only` dashboard leaves a file shared with a scanning instance as is
+    pub async fn open_read_only(path: impl AsRef<Path>) -> DbResult<Self> {
+        let url = format!("sqlite:{}?mode=ro", path.as_ref().display());
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect(&url)
+            .await
+            .map_err(|e| DbError::Connection(e.to_string()))?;
+
+        Ok(Self { pool })
+    }
+
     /// Create an in-memory database (for testing)
     pub async fn in_memory() -> DbResult<Self> {
         let pool = SqlitePoolOptions::new()
@@ -74,27 +88,14 @@ impl Database {
 
     /// Run database migrations (execute each statement individually)
     async fn run_migrations(&self) -> DbResult<()> {
+        let mut conn = self.pool.acquire().await?;
+
         // Create tables
-        for statement in schema::CREATE_TABLES.split(';') {
-            // Strip comment-only lines, then check if any SQL remains
-            let sql: String = statement
-                .lines()
-                .filter(|line| !line.trim().starts_with("--"))
-                .collect::<Vec<_>>()
-                .join("\n");
-            let sql = sql.trim();
-            if sql.is_empty() {
-                continue;
-            }
-            sqlx::query(sql)
-                .execute(&self.pool)
-                .await
-                .map_err(|e| DbError::Migration(format!("{e}: {sql}")))?;
-        }
+        execute_script(&mut conn, schema::CREATE_TABLES).await?;
 
         // Run ALTER TABLE migrations (tolerate "duplicate column name" errors)
         for migration in schema::MIGRATIONS {
-            match sqlx::query(migration).execute(&self.pool).await {
+            match sqlx::query(migration).execute(&mut *conn).await {
                 Ok(_) => {}
                 Err(e) => {
                     let err_msg = e.to_string();
@@ -107,6 +108,66 @@ impl Database {
             }
         }
 
+        // Release the connection first: the in-memory pool only has one
+        drop(conn);
+        self.migrate_discovery_metrics_to_real().await?;
+
+        Ok(())
+    }
+
+    /// Data migration: older databases store discovery metrics as TEXT, which makes
+    /// sorts lexicographic. SQLite cannot change a column type in place, so the table
+    /// is rebuilt with REAL columns and every metric is copied with CAST(.. AS REAL).
+    async fn migrate_discovery_metrics_to_real(&self) -> DbResult<()> {
+        let column_type: Option<(String,)> = sqlx::query_as(
+            "SELECT type FROM pragma_table_info('discovery_backtests') WHERE name = 'composite_score'",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match column_type {
+            Some((ty,)) if ty.eq_ignore_ascii_case("TEXT") => {}
+            _ => return Ok(()),
+        }
+
+        tracing::info!("Migrating discovery_backtests metrics from TEXT to REAL...");
+
+        let columns = schema::DISCOVERY_BACKTESTS_COLUMNS.join(", ");
+        let select = schema::DISCOVERY_BACKTESTS_COLUMNS
+            .iter()
+            .map(|c| {
+                if schema::DISCOVERY_METRIC_COLUMNS.contains(c) {
+                    format!("CAST({c} AS REAL)")
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("ALTER TABLE discovery_backtests RENAME TO discovery_backtests_legacy")
+            .execute(&mut *tx)
+            .await?;
+        // Recreates discovery_backtests with the typed schema
+        execute_script(&mut tx, schema::CREATE_TABLES).await?;
+        let copied = sqlx::query(&format!(
+            "INSERT INTO discovery_backtests ({columns}) SELECT {select} FROM discovery_backtests_legacy"
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DbError::Migration(format!("discovery metrics copy failed: {e}")))?
+        .rows_affected();
+        sqlx::query("DROP TABLE discovery_backtests_legacy")
+            .execute(&mut *tx)
+            .await?;
+        // Index names were still held by the legacy table — create them on the new one
+        execute_script(&mut tx, schema::CREATE_TABLES).await?;
+
+        tx.commit().await?;
+
+        tracing::info!("Migrated {} discovery backtests to REAL metrics", copied);
         Ok(())
     }
 
@@ -148,4 +209,190 @@ impl Database {
     pub fn pool_clone(&self) -> SqlitePool {
         self.pool.clone()
     }
+
+    /// Write a transactionally consistent copy of the live database to `dest`
+    /// (`VACUUM INTO`). Safe while other connections keep reading and writing; the copy
+    /// is a single self-contained file with no WAL sidecar. `dest` must not exist.
+    pub async fn snapshot_into(&self, dest: impl AsRef<Path>) -> DbResult<()> {
+        let dest = dest.as_ref().to_string_lossy().to_string();
+        sqlx::query("VACUUM INTO ?")
+            .bind(&dest)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DbError::Query(format!("snapshot to {dest} failed: {e}")))?;
+        Ok(())
+    }
+
+    /// Run `PRAGMA quick_check` — true when SQLite reports the file as intact
+    pub async fn quick_check(&self) -> DbResult<bool> {
+        let (result,): (String,) = sqlx::query_as("PRAGMA quick_check")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(result == "ok")
+    }
+
+    /// Close all pooled connections (checkpoints the WAL so the file can be moved)
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+}
+
+/// Execute a multi-statement SQL script, one statement at a time
+async fn execute_script(conn: &mut SqliteConnection, script: &str) -> DbResult<()> {
+    for statement in script.split(';') {
+        // Strip comment-only lines, then check if any SQL remains
+        let sql: String = statement
+            .lines()
+            .filter(|line| !line.trim().starts_with("--"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let sql = sql.trim();
+        if sql.is_empty() {
+            continue;
+        }
+        sqlx::query(sql)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DbError::Migration(format!("{e}: {sql}")))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `discovery_backtests` as created before the REAL migration: metrics as TEXT,
+    /// with the CAST expression indexes that went with them
+    const TEXT_METRICS_SCHEMA: &str = r#"
+CREATE TABLE discovery_backtests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    params_hash TEXT NOT NULL UNIQUE,
+    strategy_type TEXT NOT NULL,
+    strategy_name TEXT NOT NULL,
+    strategy_params TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    days INTEGER NOT NULL,
+    sizing_mode TEXT NOT NULL,
+    composite_score TEXT NOT NULL DEFAULT '0',
+    net_pnl TEXT NOT NULL DEFAULT '0',
+    gross_pnl TEXT NOT NULL DEFAULT '0',
+    total_fees TEXT NOT NULL DEFAULT '0',
+    win_rate TEXT NOT NULL DEFAULT '0',
+    total_trades INTEGER NOT NULL DEFAULT 0,
+    sharpe_ratio TEXT NOT NULL DEFAULT '0',
+    max_drawdown_pct TEXT NOT NULL DEFAULT '0',
+    profit_factor TEXT NOT NULL DEFAULT '0',
+    avg_trade_pnl TEXT NOT NULL DEFAULT '0',
+    hit_rate TEXT,
+    avg_locked_profit TEXT,
+    discovery_run_id TEXT,
+    phase TEXT,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+CREATE INDEX idx_discovery_hash ON discovery_backtests(params_hash);
+CREATE INDEX idx_discovery_strategy ON discovery_backtests(strategy_type, symbol);
+CREATE INDEX idx_discovery_score ON discovery_backtests(composite_score DESC);
+CREATE INDEX idx_disc_composite_real ON discovery_backtests(CAST(composite_score AS REAL) DESC);
+CREATE INDEX idx_disc_win_rate_real ON discovery_backtests(CAST(win_rate AS REAL) DESC)
+"#;
+
+    /// Database rebuilt from a TEXT-metrics file keeps every row and value, with REAL
+    /// columns, numeric sorts and the current indexes
+    #[tokio::test]
+    async fn test_text_metrics_migrated_to_real() {
+        let dir = std::env::temp_dir().join(format!("poly-migrate-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("discovery.db");
+
+        let legacy = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&format!("sqlite:{}?mode=rwc", path.display()))
+            .await
+            .unwrap();
+        let mut conn = legacy.acquire().await.unwrap();
+        execute_script(&mut conn, TEXT_METRICS_SCHEMA)
+            .await
+            .unwrap();
+        // Lexicographic TEXT order would be 9.5 > 100 > 10.25
+        for (hash, score, win_rate, hit_rate) in [
+            ("a", "9.5", "0.55", Some("0.8")),
+            ("b", "100", "0.6", None),
+            ("c", "10.25", "0.45", None),
+        ] {
+            sqlx::query(
+                "INSERT INTO discovery_backtests (params_hash, strategy_type, strategy_name, \
+                 strategy_params, symbol, days, sizing_mode, composite_score, net_pnl, win_rate, \
+                 total_trades, hit_rate) \
+                 VALUES (?, 'rsi', 'RSI', '{}', 'BTCUSDT', 30, 'fixed', ?, '-12.5', ?, 7, ?)",
+            )
+            .bind(hash)
+            .bind(score)
+            .bind(win_rate)
+            .bind(hit_rate)
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        }
+        drop(conn);
+        legacy.close().await;
+
+        let db = Database::new(&path).await.unwrap();
+        let pool = db.pool();
+
+        for column in schema::DISCOVERY_METRIC_COLUMNS {
+            let (ty,): (String,) = sqlx::query_as(
+                "SELECT type FROM pragma_table_info('discovery_backtests') WHERE name = ?",
+            )
+            .bind(column)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            assert_eq!(ty, "REAL", "{column}");
+        }
+
+        let rows: Vec<(String, f64, f64, f64, Option<f64>, String)> = sqlx::query_as(
+            "SELECT params_hash, composite_score, net_pnl, win_rate, hit_rate, \
+             typeof(composite_score) FROM discovery_backtests ORDER BY composite_score DESC",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap();
+        let hashes: Vec<&str> = rows.iter().map(|r| r.0.as_str()).collect();
+        assert_eq!(hashes, ["b", "c", "a"]);
+        assert_eq!(rows[0].1, 100.0);
+        assert_eq!(rows[1].1, 10.25);
+        assert_eq!(rows[2].1, 9.5);
+        assert!(rows.iter().all(|r| r.2 == -12.5 && r.5 == "real"));
+        assert!((rows[2].3 - 0.55).abs() < 1e-12);
+        assert!((rows[2].4.unwrap() - 0.8).abs() < 1e-12);
+        assert_eq!(rows[0].4, None);
+
+        let indexes: Vec<(String,)> =
+            sqlx::query_as("SELECT name FROM pragma_index_list('discovery_backtests')")
+                .fetch_all(pool)
+                .await
+                .unwrap();
+        let indexes: Vec<&str> = indexes.iter().map(|(name,)| name.as_str()).collect();
+        let expected = schema::CREATE_TABLES
+            .lines()
+            .filter(|line| line.contains(" ON discovery_backtests("))
+            .filter_map(|line| line.split_whitespace().nth(5));
+        for index in expected {
+            assert!(indexes.contains(&index), "{index} missing: {indexes:?}");
+        }
+        // The TEXT-era CAST indexes went with the legacy table
+        assert!(!indexes.contains(&"idx_disc_composite_real"));
+
+        let (legacy_tables,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = 'discovery_backtests_legacy'",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        assert_eq!(legacy_tables, 0);
+
+        db.close().await;
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,104 @@
+//! Daily PnL repository — per-day aggregates of the trades of knowledge base strategies
+
+use crate::DbResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Trades of a stored strategy (`discovery_backtests.id`) closed on one UTC day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DailyPnlRecord {
+    pub backtest_id: i64,
+    /// `YYYY-MM-DD`
+    pub day: String,
+    /// Sum of the trades' PnL, before fees, in USDC
+    pub pnl: f64,
+    pub trades: i64,
+    pub winning_trades: i64,
+}
+
+/// Repository for the daily PnL of stored strategies
+pub struct DailyPnlRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> DailyPnlRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the days of `backtest_id` with `days`
+    pub async fn replace(&self, backtest_id: i64, days: &[DailyPnlRecord]) -> DbResult<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM daily_pnl WHERE backtest_id = ?1")
+            .bind(backtest_id)
+            .execute(&mut *tx)
+            .await?;
+        for day in days {
+            sqlx::query(
+                r#"INSERT INTO daily_pnl (backtest_id, day, pnl, trades, winning_trades)
+                   VALUES (?1, ?2, ?3, ?4, ?5)"#,
+            )
+            .bind(backtest_id)
+            .bind(&day.day)
+            .bind(day.pnl)
+            .bind(day.trades)
+            .bind(day.winning_trades)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Days of a backtest with at least one closed trade, oldest first
+    pub async fn get(&self, backtest_id: i64) -> DbResult<Vec<DailyPnlRecord>> {
+        let rows = sqlx::query_as::<_, DailyPnlRecord>(
+            r#"
+            SELECT backtest_id, day, pnl, trades, winning_trades
+            FROM daily_pnl
+            WHERE backtest_id = ?1
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(backtest_id)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    #[tokio::test]
+    async fn test_daily_pnl_replaced_per_backtest() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = DailyPnlRepository::new(db.pool());
+        let day = |backtest_id: i64, day: &str, pnl: f64| DailyPnlRecord {
+            backtest_id,
+            day: day.to_string(),
+            pnl,
+            trades: 1,
+            winning_trades: (pnl > 0.0) as i64,
+        };
+
+        repo.replace(1, &[day(1, "2026-10-02", -4.0), day(1, "2026-10-01", 7.5)])
+            .await
+            .unwrap();
+        repo.replace(2, &[day(2, "2026-10-01", 1.0)]).await.unwrap();
+        let days = repo.get(1).await.unwrap();
+        assert_eq!(
+            days,
+            [day(1, "2026-10-01", 7.5), day(1, "2026-10-02", -4.0)]
+        );
+
+        // A replay replaces every day of its backtest, and only those
+        repo.replace(1, &[day(1, "2026-10-03", 2.0)]).await.unwrap();
+        assert_eq!(repo.get(1).await.unwrap(), [day(1, "2026-10-03", 2.0)]);
+        assert_eq!(repo.get(2).await.unwrap().len(), 1);
+        assert!(repo.get(3).await.unwrap().is_empty());
+    }
+}
@@ -1,11 +1,44 @@
 //! Discovery backtests repository — knowledge base for strategy discovery
 
-use crate::DbResult;
+use crate::{DbError, DbResult};
 use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteQueryResult;
 use sqlx::{FromRow, SqlitePool};
+use std::sync::atomic::{AtomicU64, Ordering};
 
-/// A single discovery backtest record stored in the knowledge base
+/// Bumped on every write to `discovery_backtests` (process-wide), so read caches can tell
+/// whether the knowledge base changed since they stored a result.
+static WRITE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Current knowledge base write generation (see `WRITE_GENERATION`)
+pub fn write_generation() -> u64 {
+    WRITE_GENERATION.load(Ordering::Relaxed)
+}
+
+fn bump_write_generation() {
+    WRITE_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Workspace of records written without one (and of every record stored before
+/// workspaces existed)
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// `params_hash` / `grid_hash` as stored for `workspace`. Both columns are UNIQUE, so
+/// outside the default workspace the hash is prefixed with the workspace name: each
+/// workspace keeps its own copy of a result instead of reusing another one's.
+pub fn workspace_key(workspace: &str, hash: &str) -> String {
+    if workspace == DEFAULT_WORKSPACE {
+        hash.to_string()
+    } else {
+        format!("{workspace}:{hash}")
+    }
+}
+
+/// A single discovery backtest record stored in the knowledge base.
+///
+/// Metrics are REAL columns mapped to `f64`; the engine converts them to `Decimal`.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DiscoveryBacktestRecord {
     pub id: Option<i64>,
     pub params_hash: String,
@@ -15,110 +48,768 @@ pub struct DiscoveryBacktestRecord {
     pub symbol: String,
     pub days: i64,
     pub sizing_mode: String,
-    pub composite_score: String,
-    pub net_pnl: String,
-    pub gross_pnl: String,
-    pub total_fees: String,
-    pub win_rate: String,
+    pub composite_score: f64,
+    pub net_pnl: f64,
+    pub gross_pnl: f64,
+    pub total_fees: f64,
+    pub win_rate: f64,
     pub total_trades: i64,
-    pub sharpe_ratio: String,
-    pub max_drawdown_pct: String,
-    pub profit_factor: String,
-    pub avg_trade_pnl: String,
-    pub hit_rate: Option<String>,
-    pub avg_locked_profit: Option<String>,
+    pub sharpe_ratio: f64,
+    pub max_drawdown_pct: f64,
+    pub profit_factor: f64,
+    pub avg_trade_pnl: f64,
+    pub hit_rate: Option<f64>,
+    pub avg_locked_profit: Option<f64>,
     pub discovery_run_id: Option<String>,
     pub phase: Option<String>,
     // Advanced metrics (added via migration)
-    pub sortino_ratio: Option<String>,
+    pub sortino_ratio: Option<f64>,
     pub max_consecutive_losses: Option<i64>,
-    pub avg_win_pnl: Option<String>,
-    pub avg_loss_pnl: Option<String>,
-    pub total_volume: Option<String>,
-    pub annualized_return_pct: Option<String>,
-    pub annualized_sharpe: Option<String>,
-    pub strategy_confidence: Option<String>,
+    pub avg_win_pnl: Option<f64>,
+    pub avg_loss_pnl: Option<f64>,
+    pub total_volume: Option<f64>,
+    pub annualized_return_pct: Option<f64>,
+    pub annualized_sharpe: Option<f64>,
+    pub strategy_confidence: Option<f64>,
+    /// Format version of `strategy_params` (0 = written before versioning)
+    pub schema_version: Option<i64>,
+    /// Why the backtest stopped early (`ruin`, `time_budget`), NULL if it ran to the end
+    pub abort_reason: Option<String>,
+    /// Probability model the fees were estimated with (NULL = linear, the only model
+    /// before it became configurable)
+    #[sqlx(default)]
+    pub probability_model: Option<String>,
+    /// Drawdown throttle the backtest ran under, as JSON (NULL = none)
+    #[sqlx(default)]
+    pub drawdown_throttle: Option<String>,
+    /// Share (0-1) of the run's other symbols the same params were profitable on
+    /// (NULL = not cross-validated)
+    #[sqlx(default)]
+    pub cross_symbol_score: Option<f64>,
+    /// Trades and PnL by exit reason (signal, stop, trailing, timeout), as JSON
+    /// (NULL = not recorded)
+    #[sqlx(default)]
+    pub exit_breakdown: Option<String>,
+    /// Composite score of the time-decayed trades (NULL = run without a decay
+    /// half-life)
+    #[sqlx(default)]
+    pub decayed_score: Option<f64>,
+    /// Composite score, net PnL, win rate and trades of the trades closed in the last
+    /// 30 days of the backtest (NULL = not recorded)
+    #[sqlx(default)]
+    pub score_30d: Option<f64>,
+    #[sqlx(default)]
+    pub net_pnl_30d: Option<f64>,
+    #[sqlx(default)]
+    pub win_rate_30d: Option<f64>,
+    #[sqlx(default)]
+    pub trades_30d: Option<i64>,
+    /// Same over the last 90 days
+    #[sqlx(default)]
+    pub score_90d: Option<f64>,
+    #[sqlx(default)]
+    pub net_pnl_90d: Option<f64>,
+    #[sqlx(default)]
+    pub win_rate_90d: Option<f64>,
+    #[sqlx(default)]
+    pub trades_90d: Option<i64>,
+    /// Herfindahl-Hirschman index of the weekly PnL, 1 when a single week holds it
+    /// (NULL = no PnL)
+    #[sqlx(default)]
+    pub pnl_hhi: Option<f64>,
+    /// Share (0-1) of the absolute PnL of the biggest week
+    #[sqlx(default)]
+    pub top_week_pnl_share: Option<f64>,
+    /// Weeks with at least one closed trade
+    #[sqlx(default)]
+    pub active_weeks: Option<i64>,
+    /// Longest time under a previous equity peak, in days (NULL = not measured)
+    #[sqlx(default)]
+    pub longest_drawdown_days: Option<f64>,
+    /// Mean time back to the peak of the drawdowns that recovered, in days (NULL = no
+    /// recovery)
+    #[sqlx(default)]
+    pub avg_recovery_days: Option<f64>,
+    /// Largest position value over equity of any bar, in % (NULL = not measured)
+    #[sqlx(default)]
+    pub max_exposure_pct: Option<f64>,
+    /// Mean position value over equity across the bars, flat ones included, in %
+    #[sqlx(default)]
+    pub avg_exposure_pct: Option<f64>,
+    /// Leading bars held through while the indicators warmed up (NULL = not recorded)
+    #[sqlx(default)]
+    pub warmup_bars: Option<i64>,
+    /// Days tested after the warm-up
+    #[sqlx(default)]
+    pub effective_days: Option<f64>,
+    /// Engine crate version that computed the result (NULL = written before versioning)
+    #[sqlx(default)]
+    pub engine_version: Option<String>,
+    /// Backtest logic version that computed the result; a row is only replaced by a
+    /// result of a later logic version
+    #[sqlx(default)]
+    pub logic_version: Option<i64>,
+    /// Signal PnL, timing cost, slippage and fees behind the net PnL, as JSON (NULL =
+    /// not recorded)
+    #[sqlx(default)]
+    pub cost_attribution: Option<String>,
+}
+
+/// Minimal view of a discovery row: strategy_params migration and similarity search
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StrategyParamsRow {
+    pub id: i64,
+    pub params_hash: String,
+    pub workspace: String,
+    pub strategy_type: String,
+    pub strategy_params: String,
+    pub symbol: String,
+    pub days: i64,
+    pub sizing_mode: String,
+    pub schema_version: Option<i64>,
+}
+
+/// Track record of one strategy family (`strategy_name`) on one symbol
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FamilyPerformance {
+    pub strategy_name: String,
+    pub symbol: String,
+    pub backtests: i64,
+    pub avg_score: f64,
+    pub best_score: f64,
+}
+
+/// Outcome of an incremental VACUUM pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VacuumStats {
+    /// Free pages returned to the filesystem
+    pub freed_pages: i64,
+    /// True when the database had to be switched to auto_vacuum=INCREMENTAL with a full VACUUM
+    pub full_vacuum: bool,
 }
 
 /// Aggregated stats for the knowledge base
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct KnowledgeBaseStats {
     pub total_backtests: i64,
     pub unique_strategies: i64,
     pub unique_symbols: i64,
-    pub best_win_rate: String,
-    pub best_net_pnl: String,
+    pub best_win_rate: f64,
+    pub best_net_pnl: f64,
     pub best_strategy_name: String,
     pub total_discovery_runs: i64,
 }
 
-/// Repository for discovery backtest results
+/// One discovery run (`discovery_run_id`): a one-shot scan or a whole continuous session.
+/// Only the backtests the run actually executed carry its id, not cache hits.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DiscoveryRunSummary {
+    pub run_id: String,
+    pub backtests: i64,
+    /// Backtests stopped early (`abort_reason` set), left out of the comparison
+    pub aborted: i64,
+    pub families: i64,
+    /// Unix seconds of the first and last stored backtest
+    pub started_at: Option<i64>,
+    pub last_at: Option<i64>,
+}
+
+impl DiscoveryRunSummary {
+    /// Executed backtests per second between the first and last stored one (`None` for
+    /// a run shorter than a second)
+    pub fn throughput_per_sec(&self) -> Option<f64> {
+        let span = self.last_at? - self.started_at?;
+        (span > 0 && self.backtests > 1).then(|| self.backtests as f64 / span as f64)
+    }
+}
+
+/// Knowledge base retention applied after each discovery run or continuous cycle.
+/// Every rule is optional; results promoted in the pipeline (and not rejected) are
+/// always kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RetentionPolicy {
+    /// Results kept per strategy (`strategy_name`) per symbol, best composite score first
+    pub keep_top_n: Option<i64>,
+    /// Results with a composite score below this floor are purged
+    pub min_score: Option<f64>,
+    /// Negative-PnL results older than this many days are purged
+    pub drop_negative_after_days: Option<i64>,
+}
+
+impl RetentionPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.keep_top_n.is_some()
+            || self.min_score.is_some()
+            || self.drop_negative_after_days.is_some()
+    }
+}
+
+/// Rows deleted by each retention rule
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RetentionReport {
+    pub below_min_score: u64,
+    pub stale_negative: u64,
+    pub beyond_top_n: u64,
+    /// Rows left in the workspace
+    pub remaining: i64,
+}
+
+impl RetentionReport {
+    pub fn deleted(&self) -> u64 {
+        self.below_min_score + self.stale_negative + self.beyond_top_n
+    }
+}
+
+/// Backtests still in the promotion pipeline, never deleted by cleanup or retention
+const PIPELINE_BACKTESTS: &str =
+    "SELECT backtest_id FROM strategy_pipeline WHERE stage != 'rejected'";
+
+/// Composite score distribution of a run's completed backtests
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScoreDistribution {
+    pub count: i64,
+    pub min: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Backtests with a positive net PnL
+    pub profitable: i64,
+}
+
+impl ScoreDistribution {
+    /// Nearest-rank percentiles of `(composite_score, net_pnl)` pairs
+    pub fn from_scores(rows: &[(f64, f64)]) -> Self {
+        if rows.is_empty() {
+            return Self::default();
+        }
+        let mut scores: Vec<f64> = rows.iter().map(|(score, _)| *score).collect();
+        scores.sort_by(|a, b| a.total_cmp(b));
+        let rank = |pct: f64| {
+            let idx = ((pct / 100.0) * scores.len() as f64).ceil() as usize;
+            scores[idx.clamp(1, scores.len()) - 1]
+        };
+        Self {
+            count: scores.len() as i64,
+            min: scores[0],
+            p25: rank(25.0),
+            median: rank(50.0),
+            p75: rank(75.0),
+            p90: rank(90.0),
+            max: scores[scores.len() - 1],
+            mean: scores.iter().sum::<f64>() / scores.len() as f64,
+            profitable: rows.iter().filter(|(_, pnl)| *pnl > 0.0).count() as i64,
+        }
+    }
+}
+
+/// Best completed backtest of a strategy family (`strategy_name`) within one run
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunFamilyScore {
+    pub strategy_name: String,
+    pub backtests: i64,
+    pub best_score: f64,
+}
+
+/// A family from either run's top list, with its rank and best score on both sides
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FamilyScoreDiff {
+    pub strategy_name: String,
+    /// 1-based rank by best score (`None`: not tested in that run)
+    pub rank_a: Option<usize>,
+    pub rank_b: Option<usize>,
+    pub best_score_a: Option<f64>,
+    pub best_score_b: Option<f64>,
+    /// `best_score_b - best_score_a` when tested in both runs
+    pub score_delta: Option<f64>,
+}
+
+/// One side of a `RunComparison`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunSide {
+    pub run: DiscoveryRunSummary,
+    pub scores: ScoreDistribution,
+}
+
+/// Run B against run A: did the latest code / grid change improve outcomes?
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunComparison {
+    pub run_a: RunSide,
+    pub run_b: RunSide,
+    /// Union of both runs' top families, ordered by rank in B then A
+    pub top_strategies: Vec<FamilyScoreDiff>,
+    /// Families run B tested and run A did not
+    pub new_families: Vec<RunFamilyScore>,
+    /// Families run A tested and run B did not (dropped, or skipped as already known)
+    pub dropped_families: Vec<RunFamilyScore>,
+    pub best_score_delta: f64,
+    pub median_score_delta: f64,
+}
+
+impl RunComparison {
+    /// Diff two runs from their summaries, score rows and per-family bests
+    pub fn build(
+        a: (DiscoveryRunSummary, &[(f64, f64)], Vec<RunFamilyScore>),
+        b: (DiscoveryRunSummary, &[(f64, f64)], Vec<RunFamilyScore>),
+        top_n: usize,
+    ) -> Self {
+        let (run_a, scores_a, mut families_a) = a;
+        let (run_b, scores_b, mut families_b) = b;
+        let by_score = |x: &RunFamilyScore, y: &RunFamilyScore| {
+            y.best_score
+                .total_cmp(&x.best_score)
+                .then_with(|| x.strategy_name.cmp(&y.strategy_name))
+        };
+        families_a.sort_by(by_score);
+        families_b.sort_by(by_score);
+        let position = |families: &[RunFamilyScore], name: &str| {
+            families.iter().position(|f| f.strategy_name == name)
+        };
+
+        let mut top_strategies: Vec<FamilyScoreDiff> = Vec::new();
+        for family in families_b
+            .iter()
+            .take(top_n)
+            .chain(families_a.iter().take(top_n))
+        {
+            if top_strategies
+                .iter()
+                .any(|d| d.strategy_name == family.strategy_name)
+            {
+                continue;
+            }
+            let rank_a = position(&families_a, &family.strategy_name);
+            let rank_b = position(&families_b, &family.strategy_name);
+            let best_score_a = rank_a.map(|i| families_a[i].best_score);
+            let best_score_b = rank_b.map(|i| families_b[i].best_score);
+            top_strategies.push(FamilyScoreDiff {
+                strategy_name: family.strategy_name.clone(),
+                rank_a: rank_a.map(|i| i + 1),
+                rank_b: rank_b.map(|i| i + 1),
+                best_score_a,
+                best_score_b,
+                score_delta: best_score_a.zip(best_score_b).map(|(a, b)| b - a),
+            });
+        }
+
+        let new_families = families_b
+            .iter()
+            .filter(|f| position(&families_a, &f.strategy_name).is_none())
+            .cloned()
+            .collect();
+        let dropped_families = families_a
+            .iter()
+            .filter(|f| position(&families_b, &f.strategy_name).is_none())
+            .cloned()
+            .collect();
+
+        let scores_a = ScoreDistribution::from_scores(scores_a);
+        let scores_b = ScoreDistribution::from_scores(scores_b);
+        Self {
+            best_score_delta: scores_b.max - scores_a.max,
+            median_score_delta: scores_b.median - scores_a.median,
+            run_a: RunSide {
+                run: run_a,
+                scores: scores_a,
+            },
+            run_b: RunSide {
+                run: run_b,
+                scores: scores_b,
+            },
+            top_strategies,
+            new_families,
+            dropped_families,
+        }
+    }
+}
+
+/// Data quality of the klines a run fetched for one symbol
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct KlineQualityRecord {
+    pub run_id: String,
+    pub symbol: String,
+    /// 0-100, share of the expected bars that are present and clean
+    pub score: f64,
+    pub bars: i64,
+    pub expected_bars: i64,
+    pub missing_bars: i64,
+    pub gaps: i64,
+    pub duplicates: i64,
+    pub zero_volume_bars: i64,
+    pub spikes: i64,
+    /// Missing bars recovered by re-fetching the gaps
+    pub repaired_bars: i64,
+    pub created_at: Option<i64>,
+}
+
+/// Summary of one finished cycle of a continuous run
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CycleSummaryRecord {
+    pub run_id: String,
+    /// 0-based cycle number
+    pub cycle: i64,
+    /// Strategies of the cycle's grid (single-symbol and cross-sectional)
+    pub grid_size: i64,
+    /// Backtests scheduled (grid × symbols × days × sizing modes)
+    pub combinations: i64,
+    /// Backtests executed and added to the knowledge base
+    pub new_results: i64,
+    /// Composite score of the best result of the run so far
+    pub best_score: Option<f64>,
+    pub duration_ms: i64,
+    /// New results per second of the cycle
+    pub throughput_per_sec: f64,
+    /// `snapshot_id` of the grid the cycle ran in `grid_snapshots` (NULL = recorded
+    /// before grids were snapshotted)
+    #[sqlx(default)]
+    pub grid_snapshot_id: Option<String>,
+    pub created_at: Option<i64>,
+}
+
+/// Panic of the task of a supervised discovery run
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunCrashRecord {
+    /// `discovery_run_id` of the run that crashed
+    pub run_id: String,
+    /// Restarts of the supervised session before this crash (0 = its first run)
+    pub restarts: i64,
+    /// RFC 3339
+    pub crashed_at: String,
+    pub message: String,
+    /// Wait before the next restart, `None` when the run was left stopped
+    pub restart_in_secs: Option<i64>,
+    pub created_at: Option<i64>,
+}
+
+/// Strategy grid run by a continuous cycle, stored once per content hash
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GridSnapshotRecord {
+    /// SHA-256 of the uncompressed grid JSON
+    pub snapshot_id: String,
+    pub strategy_count: i64,
+    /// Version of the strategies' params encoding
+    pub schema_version: i64,
+    /// zstd-compressed JSON of the grid
+    pub grid: Vec<u8>,
+    pub created_at: Option<i64>,
+}
+
+/// Member of the persisted population of the evolutionary search
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GaIndividualRecord {
+    /// 0-based, fittest first
+    pub rank: i64,
+    /// Strategy params (JSON), as `strategy_params` of a backtest
+    pub genome: String,
+    /// Version of the genome's params encoding
+    pub schema_version: i64,
+    pub strategy_name: String,
+    /// Symbol the fitness was scored on
+    pub symbol: String,
+    /// Composite score
+    pub fitness: f64,
+    /// Generation the population was saved at
+    pub generation: i64,
+    /// Symbol of the island the individual lives on (empty without islands)
+    pub island: String,
+    pub updated_at: Option<i64>,
+}
+
+/// Position in the knowledge base listing for keyset pagination: the sort value and
+/// id of the last row returned. Serialized as an opaque hex token for API clients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnowledgeCursor {
+    /// Sort column the cursor was issued for (see `sort_column`)
+    pub sort_by: String,
+    /// Sort value of the last row (`None` when it was NULL)
+    pub value: Option<f64>,
+    pub id: i64,
+}
+
+impl KnowledgeCursor {
+    /// Cursor pointing just after `record` in a listing sorted by `order_col`
+    pub fn after(record: &DiscoveryBacktestRecord, order_col: &str) -> Self {
+        let value = match order_col {
+            "win_rate" => Some(record.win_rate),
+            "net_pnl" => Some(record.net_pnl),
+            "sharpe_ratio" => Some(record.sharpe_ratio),
+            "total_trades" => Some(record.total_trades as f64),
+            "max_drawdown_pct" => Some(record.max_drawdown_pct),
+            "strategy_confidence" => record.strategy_confidence,
+            "annualized_return_pct" => record.annualized_return_pct,
+            "sortino_ratio" => record.sortino_ratio,
+            "id" => record.id.map(|id| id as f64),
+            _ => Some(record.composite_score),
+        };
+        Self {
+            sort_by: order_col.to_string(),
+            value,
+            id: record.id.unwrap_or_default(),
+        }
+    }
+
+    /// Opaque token: hex of `sort_by|value|id`
+    pub fn encode(&self) -> String {
+        let value = self
+            .value
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "null".to_string());
+        format!("{}|{}|{}", self.sort_by, value, self.id)
+            .bytes()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        if !token.len().is_multiple_of(2) {
+            return None;
+        }
+        let bytes = (0..token.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(token.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let text = String::from_utf8(bytes).ok()?;
+
+        let mut parts = text.split('|');
+        let sort_by = parts.next()?.to_string();
+        let value = match parts.next()? {
+            "null" => None,
+            v => Some(v.parse::<f64>().ok().filter(|v| v.is_finite())?),
+        };
+        let id = parts.next()?.parse().ok()?;
+        if parts.next().is_some() || sort_column(Some(&sort_by)) != sort_by {
+            return None;
+        }
+        Some(Self { sort_by, value, id })
+    }
+}
+
+/// Insert one record into `workspace`, on the pool or inside a transaction. A row of the
+/// same `params_hash` is kept as is, unless the record was computed by a later backtest
+/// logic version: the row is then overwritten in place (same id) with the new result.
+async fn insert_record<'e, E>(
+    executor: E,
+    record: &DiscoveryBacktestRecord,
+    workspace: &str,
+) -> Result<SqliteQueryResult, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO discovery_backtests (
+            params_hash, strategy_type, strategy_name, strategy_params,
+            symbol, days, sizing_mode,
+            composite_score, net_pnl, gross_pnl, total_fees,
+            win_rate, total_trades, sharpe_ratio, max_drawdown_pct,
+            profit_factor, avg_trade_pnl,
+            hit_rate, avg_locked_profit,
+            discovery_run_id, phase,
+            sortino_ratio, max_consecutive_losses, avg_win_pnl, avg_loss_pnl,
+            total_volume, annualized_return_pct, annualized_sharpe, strategy_confidence,
+            schema_version, abort_reason, workspace, probability_model, drawdown_throttle,
+            cross_symbol_score, exit_breakdown, decayed_score,
+            score_30d, net_pnl_30d, win_rate_30d, trades_30d,
+            score_90d, net_pnl_90d, win_rate_90d, trades_90d,
+            pnl_hhi, top_week_pnl_share, active_weeks,
+            longest_drawdown_days, avg_recovery_days, max_exposure_pct, avg_exposure_pct,
+            warmup_bars, effective_days, engine_version, logic_version, cost_attribution
+        ) VALUES (
+            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+        )
+        ON CONFLICT(params_hash) DO UPDATE SET
+            strategy_type = excluded.strategy_type,
+            strategy_name = excluded.strategy_name,
+            strategy_params = excluded.strategy_params, symbol = excluded.symbol,
+            days = excluded.days, sizing_mode = excluded.sizing_mode,
+            composite_score = excluded.composite_score, net_pnl = excluded.net_pnl,
+            gross_pnl = excluded.gross_pnl, total_fees = excluded.total_fees,
+            win_rate = excluded.win_rate, total_trades = excluded.total_trades,
+            sharpe_ratio = excluded.sharpe_ratio,
+            max_drawdown_pct = excluded.max_drawdown_pct,
+            profit_factor = excluded.profit_factor,
+            avg_trade_pnl = excluded.avg_trade_pnl, hit_rate = excluded.hit_rate,
+            avg_locked_profit = excluded.avg_locked_profit,
+            discovery_run_id = excluded.discovery_run_id, phase = excluded.phase,
+            sortino_ratio = excluded.sortino_ratio,
+            max_consecutive_losses = excluded.max_consecutive_losses,
+            avg_win_pnl = excluded.avg_win_pnl, avg_loss_pnl = excluded.avg_loss_pnl,
+            total_volume = excluded.total_volume,
+            annualized_return_pct = excluded.annualized_return_pct,
+            annualized_sharpe = excluded.annualized_sharpe,
+            strategy_confidence = excluded.strategy_confidence,
+            schema_version = excluded.schema_version,
+            abort_reason = excluded.abort_reason,
+            probability_model = excluded.probability_model,
+            drawdown_throttle = excluded.drawdown_throttle,
+            cross_symbol_score = excluded.cross_symbol_score,
+            exit_breakdown = excluded.exit_breakdown,
+            decayed_score = excluded.decayed_score, score_30d = excluded.score_30d,
+            net_pnl_30d = excluded.net_pnl_30d, win_rate_30d = excluded.win_rate_30d,
+            trades_30d = excluded.trades_30d, score_90d = excluded.score_90d,
+            net_pnl_90d = excluded.net_pnl_90d, win_rate_90d = excluded.win_rate_90d,
+            trades_90d = excluded.trades_90d, pnl_hhi = excluded.pnl_hhi,
+            top_week_pnl_share = excluded.top_week_pnl_share,
+            active_weeks = excluded.active_weeks,
+            longest_drawdown_days = excluded.longest_drawdown_days,
+            avg_recovery_days = excluded.avg_recovery_days,
+            max_exposure_pct = excluded.max_exposure_pct,
+            avg_exposure_pct = excluded.avg_exposure_pct,
+            warmup_bars = excluded.warmup_bars, effective_days = excluded.effective_days,
+            engine_version = excluded.engine_version,
+            logic_version = excluded.logic_version,
+            cost_attribution = excluded.cost_attribution,
+            created_at = strftime('%s', 'now')
+        WHERE COALESCE(excluded.logic_version, 0)
+            > COALESCE(discovery_backtests.logic_version, 0)
+        "#,
+    )
+    .bind(workspace_key(workspace, &record.params_hash))
+    .bind(&record.strategy_type)
+    .bind(&record.strategy_name)
+    .bind(&record.strategy_params)
+    .bind(&record.symbol)
+    .bind(record.days)
+    .bind(&record.sizing_mode)
+    .bind(record.composite_score)
+    .bind(record.net_pnl)
+    .bind(record.gross_pnl)
+    .bind(record.total_fees)
+    .bind(record.win_rate)
+    .bind(record.total_trades)
+    .bind(record.sharpe_ratio)
+    .bind(record.max_drawdown_pct)
+    .bind(record.profit_factor)
+    .bind(record.avg_trade_pnl)
+    .bind(record.hit_rate)
+    .bind(record.avg_locked_profit)
+    .bind(&record.discovery_run_id)
+    .bind(&record.phase)
+    .bind(record.sortino_ratio)
+    .bind(record.max_consecutive_losses)
+    .bind(record.avg_win_pnl)
+    .bind(record.avg_loss_pnl)
+    .bind(record.total_volume)
+    .bind(record.annualized_return_pct)
+    .bind(record.annualized_sharpe)
+    .bind(record.strategy_confidence)
+    .bind(record.schema_version)
+    .bind(&record.abort_reason)
+    .bind(workspace)
+    .bind(&record.probability_model)
+    .bind(&record.drawdown_throttle)
+    .bind(record.cross_symbol_score)
+    .bind(&record.exit_breakdown)
+    .bind(record.decayed_score)
+    .bind(record.score_30d)
+    .bind(record.net_pnl_30d)
+    .bind(record.win_rate_30d)
+    .bind(record.trades_30d)
+    .bind(record.score_90d)
+    .bind(record.net_pnl_90d)
+    .bind(record.win_rate_90d)
+    .bind(record.trades_90d)
+    .bind(record.pnl_hhi)
+    .bind(record.top_week_pnl_share)
+    .bind(record.active_weeks)
+    .bind(record.longest_drawdown_days)
+    .bind(record.avg_recovery_days)
+    .bind(record.max_exposure_pct)
+    .bind(record.avg_exposure_pct)
+    .bind(record.warmup_bars)
+    .bind(record.effective_days)
+    .bind(&record.engine_version)
+    .bind(record.logic_version)
+    .bind(&record.cost_attribution)
+    .execute(executor)
+    .await
+}
+
+/// Repository for discovery backtest results.
+///
+/// Reads, writes and cleanup only see the records of one workspace (`DEFAULT_WORKSPACE`
+/// unless set with `with_workspace`); schema migrations and VACUUM cover every workspace.
 pub struct DiscoveryRepository<'a> {
     pool: &'a SqlitePool,
+    workspace: &'a str,
 }
 
 impl<'a> DiscoveryRepository<'a> {
     pub fn new(pool: &'a SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            workspace: DEFAULT_WORKSPACE,
+        }
+    }
+
+    pub fn with_workspace(mut self, workspace: &'a str) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    /// Workspaces holding at least one record
+    pub async fn list_workspaces(&self) -> DbResult<Vec<String>> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT DISTINCT workspace FROM discovery_backtests ORDER BY workspace")
+                .fetch_all(self.pool)
+                .await?;
+        Ok(rows.into_iter().map(|(w,)| w).collect())
     }
 
-    /// Save a backtest result (INSERT OR IGNORE — skips if params_hash already exists)
+    /// Save a backtest result (skipped if params_hash already exists, unless it replaces
+    /// a result of an earlier backtest logic version)
     pub async fn save(&self, record: &DiscoveryBacktestRecord) -> DbResult<i64> {
-        let result = sqlx::query(
-            r#"
-            INSERT OR IGNORE INTO discovery_backtests (
-                params_hash, strategy_type, strategy_name, strategy_params,
-                symbol, days, sizing_mode,
-                composite_score, net_pnl, gross_pnl, total_fees,
-                win_rate, total_trades, sharpe_ratio, max_drawdown_pct,
-                profit_factor, avg_trade_pnl,
-                hit_rate, avg_locked_profit,
-                discovery_run_id, phase,
-                sortino_ratio, max_consecutive_losses, avg_win_pnl, avg_loss_pnl,
-                total_volume, annualized_return_pct, annualized_sharpe, strategy_confidence
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(&record.params_hash)
-        .bind(&record.strategy_type)
-        .bind(&record.strategy_name)
-        .bind(&record.strategy_params)
-        .bind(&record.symbol)
-        .bind(record.days)
-        .bind(&record.sizing_mode)
-        .bind(&record.composite_score)
-        .bind(&record.net_pnl)
-        .bind(&record.gross_pnl)
-        .bind(&record.total_fees)
-        .bind(&record.win_rate)
-        .bind(record.total_trades)
-        .bind(&record.sharpe_ratio)
-        .bind(&record.max_drawdown_pct)
-        .bind(&record.profit_factor)
-        .bind(&record.avg_trade_pnl)
-        .bind(&record.hit_rate)
-        .bind(&record.avg_locked_profit)
-        .bind(&record.discovery_run_id)
-        .bind(&record.phase)
-        .bind(&record.sortino_ratio)
-        .bind(record.max_consecutive_losses)
-        .bind(&record.avg_win_pnl)
-        .bind(&record.avg_loss_pnl)
-        .bind(&record.total_volume)
-        .bind(&record.annualized_return_pct)
-        .bind(&record.annualized_sharpe)
-        .bind(&record.strategy_confidence)
-        .execute(self.pool)
-        .await?;
+        let result = insert_record(self.pool, record, self.workspace).await?;
 
+        if result.rows_affected() > 0 {
+            bump_write_generation();
+        }
         Ok(result.last_insert_rowid())
     }
 
+    /// Save many results in transactions of 500 rows, as `save` does.
+    /// Returns the number of rows inserted or replaced.
+    pub async fn save_batch(&self, records: &[DiscoveryBacktestRecord]) -> DbResult<usize> {
+        let mut inserted = 0usize;
+        for chunk in records.chunks(500) {
+            let mut tx = self.pool.begin().await?;
+            for record in chunk {
+                let result = insert_record(&mut *tx, record, self.workspace).await?;
+                if result.rows_affected() > 0 {
+                    inserted += 1;
+                }
+            }
+            tx.commit().await?;
+        }
+
+        if inserted > 0 {
+            bump_write_generation();
+        }
+        Ok(inserted)
+    }
+
     /// Check if a backtest with this params_hash already exists
     pub async fn exists_by_hash(&self, hash: &str) -> DbResult<bool> {
         let row: (i64,) =
             sqlx::query_as("SELECT COUNT(*) FROM discovery_backtests WHERE params_hash = ?")
-                .bind(hash)
+                .bind(workspace_key(self.workspace, hash))
                 .fetch_one(self.pool)
                 .await?;
 
@@ -137,24 +828,45 @@ impl<'a> DiscoveryRepository<'a> {
                    hit_rate, avg_locked_profit,
                    discovery_run_id, phase,
                    sortino_ratio, max_consecutive_losses, avg_win_pnl, avg_loss_pnl,
-                   total_volume, annualized_return_pct, annualized_sharpe, strategy_confidence
+                   total_volume, annualized_return_pct, annualized_sharpe, strategy_confidence,
+                   schema_version, abort_reason, probability_model,
+                   drawdown_throttle, cross_symbol_score, exit_breakdown, decayed_score,
+                   score_30d, net_pnl_30d, win_rate_30d, trades_30d,
+                   score_90d, net_pnl_90d, win_rate_90d, trades_90d,
+                   pnl_hhi, top_week_pnl_share, active_weeks,
+                   longest_drawdown_days, avg_recovery_days,
+                   max_exposure_pct, avg_exposure_pct, warmup_bars, effective_days,
+                   engine_version, logic_version, cost_attribution
             FROM discovery_backtests
             WHERE params_hash = ?
             "#,
         )
-        .bind(hash)
+        .bind(workspace_key(self.workspace, hash))
         .fetch_optional(self.pool)
         .await?;
 
         Ok(record)
     }
 
-    /// Get top results ordered by composite score, with optional filters
+    pub async fn get_by_id(&self, id: i64) -> DbResult<Option<DiscoveryBacktestRecord>> {
+        let sql = format!(
+            "SELECT {RECORD_COLUMNS} FROM discovery_backtests WHERE id = ?1 AND workspace = ?2"
+        );
+        let record = sqlx::query_as::<_, DiscoveryBacktestRecord>(&sql)
+            .bind(id)
+            .bind(self.workspace)
+            .fetch_optional(self.pool)
+            .await?;
+        Ok(record)
+    }
+
+    /// Get top results in `rank_by` order (remaining ties by id), with optional filters
     pub async fn get_top_results(
         &self,
         limit: i64,
         strategy_type: Option<&str>,
         symbol: Option<&str>,
+        rank_by: &[RankKey],
     ) -> DbResult<Vec<DiscoveryBacktestRecord>> {
         let mut sql = String::from(
             r#"
@@ -166,13 +878,21 @@ impl<'a> DiscoveryRepository<'a> {
                    hit_rate, avg_locked_profit,
                    discovery_run_id, phase,
                    sortino_ratio, max_consecutive_losses, avg_win_pnl, avg_loss_pnl,
-                   total_volume, annualized_return_pct, annualized_sharpe, strategy_confidence
+                   total_volume, annualized_return_pct, annualized_sharpe, strategy_confidence,
+                   schema_version, abort_reason, probability_model,
+                   drawdown_throttle, cross_symbol_score, exit_breakdown, decayed_score,
+                   score_30d, net_pnl_30d, win_rate_30d, trades_30d,
+                   score_90d, net_pnl_90d, win_rate_90d, trades_90d,
+                   pnl_hhi, top_week_pnl_share, active_weeks,
+                   longest_drawdown_days, avg_recovery_days,
+                   max_exposure_pct, avg_exposure_pct, warmup_bars, effective_days,
+                   engine_version, logic_version, cost_attribution
             FROM discovery_backtests
-            WHERE 1=1
+            WHERE workspace = ?
             "#,
         );
 
-        let mut binds: Vec<String> = Vec::new();
+        let mut binds: Vec<String> = vec![self.workspace.to_string()];
 
         if let Some(st) = strategy_type {
             sql.push_str(" AND strategy_type = ?");
@@ -183,7 +903,10 @@ impl<'a> DiscoveryRepository<'a> {
             binds.push(sym.to_string());
         }
 
-        sql.push_str(" ORDER BY CAST(composite_score AS REAL) DESC LIMIT ?");
+        sql.push_str(&format!(
+            " ORDER BY {} LIMIT ?",
+            rank_order_sql(rank_by, "")
+        ));
 
         // Build query dynamically
         let mut query = sqlx::query_as::<_, DiscoveryBacktestRecord>(&sql);
@@ -196,7 +919,10 @@ impl<'a> DiscoveryRepository<'a> {
         Ok(records)
     }
 
-    /// Get paginated results with optional filters
+    /// Get paginated results with optional filters. `min_recent_win_rate` screens on the
+    /// win rate of the last 30 days of each backtest, `max_pnl_hhi` on the concentration
+    /// of its PnL over weeks (records without the value are left out).
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_all_paginated(
         &self,
         limit: i64,
@@ -204,69 +930,47 @@ impl<'a> DiscoveryRepository<'a> {
         strategy_type: Option<&str>,
         symbol: Option<&str>,
         min_win_rate: Option<f64>,
+        min_recent_win_rate: Option<f64>,
+        max_pnl_hhi: Option<f64>,
         sort_by: Option<&str>,
     ) -> DbResult<(Vec<DiscoveryBacktestRecord>, i64)> {
-        let mut where_clauses = vec!["1=1".to_string()];
-        let mut binds: Vec<String> = Vec::new();
-
-        if let Some(st) = strategy_type {
-            where_clauses.push("strategy_type = ?".to_string());
-            binds.push(st.to_string());
-        }
-        if let Some(sym) = symbol {
-            where_clauses.push("symbol = ?".to_string());
-            binds.push(sym.to_string());
-        }
-        if let Some(mwr) = min_win_rate {
-            where_clauses.push("CAST(win_rate AS REAL) >= ?".to_string());
-            binds.push(format!("{mwr}"));
-        }
+        let (count_sql, data_sql) = paginated_sql(
+            strategy_type.is_some(),
+            symbol.is_some(),
+            min_win_rate.is_some(),
+            min_recent_win_rate.is_some(),
+            max_pnl_hhi.is_some(),
+            sort_by,
+        );
 
-        let where_sql = where_clauses.join(" AND ");
+        // Filters are bound in the order paginated_sql() emits them
+        let text_binds: Vec<&str> = [Some(self.workspace), strategy_type, symbol]
+            .into_iter()
+            .flatten()
+            .collect();
 
         // Count total
-        let count_sql = format!("SELECT COUNT(*) FROM discovery_backtests WHERE {where_sql}");
         let mut count_query = sqlx::query_as::<_, (i64,)>(&count_sql);
-        for b in &binds {
-            count_query = count_query.bind(b);
+        for b in &text_binds {
+            count_query = count_query.bind(*b);
+        }
+        for bound in [min_win_rate, min_recent_win_rate, max_pnl_hhi]
+            .into_iter()
+            .flatten()
+        {
+            count_query = count_query.bind(bound);
         }
         let (total,) = count_query.fetch_one(self.pool).await?;
 
-        // Sort column
-        let order_col = match sort_by {
-            Some("win_rate") => "CAST(win_rate AS REAL)",
-            Some("net_pnl") => "CAST(net_pnl AS REAL)",
-            Some("sharpe_ratio") => "CAST(sharpe_ratio AS REAL)",
-            Some("total_trades") => "total_trades",
-            Some("max_drawdown_pct") => "CAST(max_drawdown_pct AS REAL)",
-            Some("created_at") => "created_at",
-            Some("strategy_confidence") => "CAST(strategy_confidence AS REAL)",
-            Some("annualized_return_pct") => "CAST(annualized_return_pct AS REAL)",
-            Some("sortino_ratio") => "CAST(sortino_ratio AS REAL)",
-            _ => "CAST(composite_score AS REAL)",
-        };
-
-        let data_sql = format!(
-            r#"
-            SELECT id, params_hash, strategy_type, strategy_name, strategy_params,
-                   symbol, days, sizing_mode,
-                   composite_score, net_pnl, gross_pnl, total_fees,
-                   win_rate, total_trades, sharpe_ratio, max_drawdown_pct,
-                   profit_factor, avg_trade_pnl,
-                   hit_rate, avg_locked_profit,
-                   discovery_run_id, phase,
-                   sortino_ratio, max_consecutive_losses, avg_win_pnl, avg_loss_pnl,
-                   total_volume, annualized_return_pct, annualized_sharpe, strategy_confidence
-            FROM discovery_backtests
-            WHERE {where_sql}
-            ORDER BY {order_col} DESC
-            LIMIT ? OFFSET ?
-            "#
-        );
-
         let mut data_query = sqlx::query_as::<_, DiscoveryBacktestRecord>(&data_sql);
-        for b in &binds {
-            data_query = data_query.bind(b);
+        for b in &text_binds {
+            data_query = data_query.bind(*b);
+        }
+        for bound in [min_win_rate, min_recent_win_rate, max_pnl_hhi]
+            .into_iter()
+            .flatten()
+        {
+            data_query = data_query.bind(bound);
         }
         data_query = data_query.bind(limit).bind(offset);
 
@@ -274,25 +978,90 @@ impl<'a> DiscoveryRepository<'a> {
         Ok((records, total))
     }
 
-    /// Get top unique strategies (one per strategy_name), deduplicated by sort_by column
-    pub async fn get_top_unique_strategies(
+    /// Keyset pagination: the `limit` rows following `cursor` in the same order as
+    /// `get_all_paginated`. Cost is independent of how deep the page is, unlike OFFSET.
+    /// Returns the page and the cursor of the next one (`None` on the last page).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_page_after(
         &self,
         limit: i64,
+        cursor: Option<&KnowledgeCursor>,
+        strategy_type: Option<&str>,
+        symbol: Option<&str>,
+        min_win_rate: Option<f64>,
+        min_recent_win_rate: Option<f64>,
+        max_pnl_hhi: Option<f64>,
         sort_by: Option<&str>,
-    ) -> DbResult<Vec<DiscoveryBacktestRecord>> {
-        let order_col = match sort_by {
-            Some("net_pnl") => "CAST(net_pnl AS REAL)",
-            Some("composite_score") => "CAST(composite_score AS REAL)",
-            _ => "CAST(win_rate AS REAL)",
+    ) -> DbResult<(Vec<DiscoveryBacktestRecord>, Option<KnowledgeCursor>)> {
+        let order_col = sort_column(sort_by);
+        if let Some(c) = cursor {
+            if c.sort_by != order_col {
+                return Err(DbError::Query(format!(
+                    "cursor was issued for sort_by={}, not {}",
+                    c.sort_by, order_col
+                )));
+            }
+        }
+
+        let sql = keyset_sql(
+            strategy_type.is_some(),
+            symbol.is_some(),
+            min_win_rate.is_some(),
+            min_recent_win_rate.is_some(),
+            max_pnl_hhi.is_some(),
+            sort_by,
+            cursor,
+        );
+
+        let mut query = sqlx::query_as::<_, DiscoveryBacktestRecord>(&sql);
+        query = query.bind(self.workspace);
+        for b in [strategy_type, symbol].into_iter().flatten() {
+            query = query.bind(b);
+        }
+        for bound in [min_win_rate, min_recent_win_rate, max_pnl_hhi]
+            .into_iter()
+            .flatten()
+        {
+            query = query.bind(bound);
+        }
+        if let Some(c) = cursor {
+            if let Some(v) = c.value {
+                query = query.bind(v).bind(v);
+            }
+            query = query.bind(c.id);
+        }
+        // One extra row tells whether another page exists
+        query = query.bind(limit + 1);
+
+        let mut records = query.fetch_all(self.pool).await?;
+        let has_more = records.len() as i64 > limit;
+        records.truncate(limit.max(0) as usize);
+
+        let next = if has_more {
+            records.last().map(|r| KnowledgeCursor::after(r, order_col))
+        } else {
+            None
         };
+        Ok((records, next))
+    }
+
+    /// Get top unique strategies: the best record of each strategy_name in `rank_by`
+    /// order, the records themselves in the same order
+    pub async fn get_top_unique_strategies(
+        &self,
+        limit: i64,
+        rank_by: &[RankKey],
+    ) -> DbResult<Vec<DiscoveryBacktestRecord>> {
+        let partition_order = rank_order_sql(rank_by, "");
+        let order = rank_order_sql(rank_by, "d");
 
         let sql = format!(
             r#"
             WITH best_ids AS (
               SELECT id,
-                ROW_NUMBER() OVER (PARTITION BY strategy_name ORDER BY {order_col} DESC) as rn
+                ROW_NUMBER() OVER (PARTITION BY strategy_name ORDER BY {partition_order}) as rn
               FROM discovery_backtests
-              WHERE total_trades >= 5
+              WHERE total_trades >= 5 AND workspace = ?
             )
             SELECT d.id, d.params_hash, d.strategy_type, d.strategy_name, d.strategy_params,
                    d.symbol, d.days, d.sizing_mode,
@@ -302,16 +1071,25 @@ impl<'a> DiscoveryRepository<'a> {
                    d.hit_rate, d.avg_locked_profit,
                    d.discovery_run_id, d.phase,
                    d.sortino_ratio, d.max_consecutive_losses, d.avg_win_pnl, d.avg_loss_pnl,
-                   d.total_volume, d.annualized_return_pct, d.annualized_sharpe, d.strategy_confidence
+                   d.total_volume, d.annualized_return_pct, d.annualized_sharpe, d.strategy_confidence,
+                   d.schema_version, d.abort_reason, d.probability_model,
+                   d.drawdown_throttle, d.cross_symbol_score, d.exit_breakdown, d.decayed_score,
+                   d.score_30d, d.net_pnl_30d, d.win_rate_30d, d.trades_30d,
+                   d.score_90d, d.net_pnl_90d, d.win_rate_90d, d.trades_90d,
+                   d.pnl_hhi, d.top_week_pnl_share, d.active_weeks,
+                   d.longest_drawdown_days, d.avg_recovery_days,
+                   d.max_exposure_pct, d.avg_exposure_pct, d.warmup_bars, d.effective_days,
+                   d.engine_version, d.logic_version, d.cost_attribution
             FROM best_ids b
             JOIN discovery_backtests d ON d.id = b.id
             WHERE b.rn = 1
-            ORDER BY {order_col} DESC
+            ORDER BY {order}
             LIMIT ?
             "#
         );
 
         let records = sqlx::query_as::<_, DiscoveryBacktestRecord>(&sql)
+            .bind(self.workspace)
             .bind(limit)
             .fetch_all(self.pool)
             .await?;
@@ -320,20 +1098,26 @@ impl<'a> DiscoveryRepository<'a> {
     }
 
     /// Cleanup: keep top N results per strategy_name (positive PnL only), delete the rest.
-    /// Returns (deleted_count, remaining_count).
+    /// Only touches the repository's workspace. Returns (deleted_count, remaining_count).
     pub async fn cleanup_keep_top_n(&self, keep: i64) -> DbResult<(u64, i64)> {
         // Count before
         let (total_before,): (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM discovery_backtests")
+            sqlx::query_as("SELECT COUNT(*) FROM discovery_backtests WHERE workspace = ?")
+                .bind(self.workspace)
                 .fetch_one(self.pool)
                 .await?;
 
-        tracing::info!("Total records before cleanup: {}", total_before);
+        tracing::info!(
+            "Total records before cleanup in {}: {}",
+            self.workspace,
+            total_before
+        );
 
         // Per-strategy counts
         let strategy_counts: Vec<(String, i64)> = sqlx::query_as(
-            "SELECT strategy_name, COUNT(*) as cnt FROM discovery_backtests GROUP BY strategy_name ORDER BY cnt DESC"
+            "SELECT strategy_name, COUNT(*) as cnt FROM discovery_backtests WHERE workspace = ? GROUP BY strategy_name ORDER BY cnt DESC"
         )
+        .bind(self.workspace)
         .fetch_all(self.pool)
         .await?;
 
@@ -342,29 +1126,39 @@ impl<'a> DiscoveryRepository<'a> {
             tracing::info!("  {} : {} records", name, cnt);
         }
 
-        // Delete records NOT in the top N per strategy_name (ranked by net_pnl DESC, positive only)
+        // Delete records NOT in the top N per strategy_name (ranked by net_pnl DESC, positive
+        // only), except those promoted past `discovered` and not rejected in the pipeline
         let delete_sql = format!(
             r#"
             DELETE FROM discovery_backtests
-            WHERE id NOT IN (
+            WHERE workspace = ?1
+            AND id NOT IN (
                 SELECT id FROM (
                     SELECT id,
-                        ROW_NUMBER() OVER (PARTITION BY strategy_name ORDER BY CAST(net_pnl AS REAL) DESC) as rn
+                        ROW_NUMBER() OVER (PARTITION BY strategy_name ORDER BY net_pnl DESC) as rn
                     FROM discovery_backtests
-                    WHERE CAST(net_pnl AS REAL) > 0
+                    WHERE net_pnl > 0 AND workspace = ?1
                 )
                 WHERE rn <= {}
             )
+            AND id NOT IN ({})
             "#,
-            keep
+            keep, PIPELINE_BACKTESTS
         );
 
-        let result = sqlx::query(&delete_sql).execute(self.pool).await?;
+        let result = sqlx::query(&delete_sql)
+            .bind(self.workspace)
+            .execute(self.pool)
+            .await?;
         let deleted = result.rows_affected();
+        if deleted > 0 {
+            bump_write_generation();
+        }
 
         // Count after
         let (total_after,): (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM discovery_backtests")
+            sqlx::query_as("SELECT COUNT(*) FROM discovery_backtests WHERE workspace = ?")
+                .bind(self.workspace)
                 .fetch_one(self.pool)
                 .await?;
 
@@ -373,8 +1167,9 @@ impl<'a> DiscoveryRepository<'a> {
 
         // Show remaining per-strategy
         let remaining: Vec<(String, i64)> = sqlx::query_as(
-            "SELECT strategy_name, COUNT(*) as cnt FROM discovery_backtests GROUP BY strategy_name ORDER BY cnt DESC"
+            "SELECT strategy_name, COUNT(*) as cnt FROM discovery_backtests WHERE workspace = ? GROUP BY strategy_name ORDER BY cnt DESC"
         )
+        .bind(self.workspace)
         .fetch_all(self.pool)
         .await?;
 
@@ -386,70 +1181,715 @@ impl<'a> DiscoveryRepository<'a> {
         Ok((deleted, total_after))
     }
 
-    /// VACUUM the database to reclaim disk space
-    pub async fn vacuum(&self) -> DbResult<()> {
-        sqlx::query("VACUUM").execute(self.pool).await?;
-        Ok(())
-    }
+    /// Apply `policy` to the workspace: purge results below the score floor, then stale
+    /// negative-PnL ones, then everything past the top N per strategy and symbol
+    pub async fn apply_retention(&self, policy: &RetentionPolicy) -> DbResult<RetentionReport> {
+        let mut report = RetentionReport::default();
 
-    /// Get aggregated knowledge base stats
-    pub async fn get_stats(&self) -> DbResult<KnowledgeBaseStats> {
-        let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM discovery_backtests")
-            .fetch_one(self.pool)
-            .await?;
+        if let Some(min_score) = policy.min_score {
+            let sql = format!(
+                "DELETE FROM discovery_backtests
+                 WHERE workspace = ?1 AND composite_score < ?2 AND id NOT IN ({PIPELINE_BACKTESTS})"
+            );
+            report.below_min_score = sqlx::query(&sql)
+                .bind(self.workspace)
+                .bind(min_score)
+                .execute(self.pool)
+                .await?
+                .rows_affected();
+        }
 
-        let unique_strategies: (i64,) =
-            sqlx::query_as("SELECT COUNT(DISTINCT strategy_type) FROM discovery_backtests")
-                .fetch_one(self.pool)
-                .await?;
+        if let Some(days) = policy.drop_negative_after_days {
+            let sql = format!(
+                "DELETE FROM discovery_backtests
+                 WHERE workspace = ?1 AND net_pnl < 0
+                 AND created_at < strftime('%s', 'now') - ?2 * 86400
+                 AND id NOT IN ({PIPELINE_BACKTESTS})"
+            );
+            report.stale_negative = sqlx::query(&sql)
+                .bind(self.workspace)
+                .bind(days)
+                .execute(self.pool)
+                .await?
+                .rows_affected();
+        }
+
+        if let Some(keep) = policy.keep_top_n {
+            let sql = format!(
+                r#"
+                DELETE FROM discovery_backtests
+                WHERE workspace = ?1
+                AND id IN (
+                    SELECT id FROM (
+                        SELECT id,
+                            ROW_NUMBER() OVER (
+                                PARTITION BY strategy_name, symbol
+                                ORDER BY composite_score DESC, id
+                            ) AS rn
+                        FROM discovery_backtests
+                        WHERE workspace = ?1
+                    )
+                    WHERE rn > ?2
+                )
+                AND id NOT IN ({PIPELINE_BACKTESTS})
+                "#
+            );
+            report.beyond_top_n = sqlx::query(&sql)
+                .bind(self.workspace)
+                .bind(keep)
+                .execute(self.pool)
+                .await?
+                .rows_affected();
+        }
 
-        let unique_symbols: (i64,) =
-            sqlx::query_as("SELECT COUNT(DISTINCT symbol) FROM discovery_backtests")
+        if report.deleted() > 0 {
+            bump_write_generation();
+        }
+        (report.remaining,) =
+            sqlx::query_as("SELECT COUNT(*) FROM discovery_backtests WHERE workspace = ?")
+                .bind(self.workspace)
                 .fetch_one(self.pool)
                 .await?;
+        Ok(report)
+    }
 
-        let total_runs: (i64,) = sqlx::query_as(
-            "SELECT COUNT(DISTINCT discovery_run_id) FROM discovery_backtests WHERE discovery_run_id IS NOT NULL",
+    /// Get rows whose strategy_params were written with a version older than
+    /// `current_version`, in every workspace
+    pub async fn get_outdated_params(
+        &self,
+        current_version: i64,
+    ) -> DbResult<Vec<StrategyParamsRow>> {
+        let rows = sqlx::query_as::<_, StrategyParamsRow>(
+            r#"
+            SELECT id, params_hash, workspace, strategy_type, strategy_params,
+                   symbol, days, sizing_mode, schema_version
+            FROM discovery_backtests
+            WHERE COALESCE(schema_version, 0) < ?
+            ORDER BY id
+            "#,
         )
-        .fetch_one(self.pool)
+        .bind(current_version)
+        .fetch_all(self.pool)
         .await?;
 
-        // Best win rate (from records with at least 5 trades)
-        let best_wr: Option<(String, String)> = sqlx::query_as(
+        Ok(rows)
+    }
+
+    /// Strategy params of the workspace's records (of `symbol` only, when given), for
+    /// scans that decode every stored strategy
+    pub async fn get_strategy_params_rows(
+        &self,
+        symbol: Option<&str>,
+    ) -> DbResult<Vec<StrategyParamsRow>> {
+        let rows = sqlx::query_as::<_, StrategyParamsRow>(
             r#"
-            SELECT win_rate, strategy_name
+            SELECT id, params_hash, workspace, strategy_type, strategy_params,
+                   symbol, days, sizing_mode, schema_version
             FROM discovery_backtests
-            WHERE total_trades >= 5
-            ORDER BY CAST(win_rate AS REAL) DESC
-            LIMIT 1
+            WHERE workspace = ?1 AND (?2 IS NULL OR symbol = ?2)
+            ORDER BY id
             "#,
         )
-        .fetch_optional(self.pool)
+        .bind(self.workspace)
+        .bind(symbol)
+        .fetch_all(self.pool)
         .await?;
 
-        // Best net PnL
-        let best_pnl: Option<(String, String)> = sqlx::query_as(
+        Ok(rows)
+    }
+
+    /// Rewrite a row's strategy_params in the current format. `params_hash` is stored
+    /// as is (already keyed with `workspace_key`).
+    /// Returns false if another row already holds `params_hash` (the row is left untouched).
+    pub async fn update_strategy_params(
+        &self,
+        id: i64,
+        params_hash: &str,
+        strategy_params: &str,
+        schema_version: i64,
+    ) -> DbResult<bool> {
+        let result = sqlx::query(
+            "UPDATE OR IGNORE discovery_backtests SET params_hash = ?, strategy_params = ?, schema_version = ? WHERE id = ?",
+        )
+        .bind(params_hash)
+        .bind(strategy_params)
+        .bind(schema_version)
+        .bind(id)
+        .execute(self.pool)
+        .await?;
+
+        bump_write_generation();
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Set the cross-symbol score of the record stored under `hash`.
+    /// Returns false when no record has that hash.
+    pub async fn update_cross_symbol_score(&self, hash: &str, score: f64) -> DbResult<bool> {
+        let result = sqlx::query(
+            "UPDATE discovery_backtests SET cross_symbol_score = ? WHERE params_hash = ?",
+        )
+        .bind(score)
+        .bind(workspace_key(self.workspace, hash))
+        .execute(self.pool)
+        .await?;
+
+        bump_write_generation();
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// True when the grid unit identified by `grid_hash` was fully tested
+    pub async fn is_grid_tested(&self, grid_hash: &str) -> DbResult<bool> {
+        let row: Option<(i64,)> = sqlx::query_as("SELECT 1 FROM tested_grids WHERE grid_hash = ?")
+            .bind(workspace_key(self.workspace, grid_hash))
+            .fetch_optional(self.pool)
+            .await?;
+        Ok(row.is_some())
+    }
+
+    /// Record that every combination of a grid unit is in the knowledge base
+    pub async fn mark_grid_tested(
+        &self,
+        grid_hash: &str,
+        grid_id: &str,
+        symbol: &str,
+        days: i64,
+        sizing_mode: &str,
+        combinations: i64,
+    ) -> DbResult<()> {
+        sqlx::query(
             r#"
-            SELECT net_pnl, strategy_name
-            FROM discovery_backtests
-            WHERE total_trades >= 5
-            ORDER BY CAST(net_pnl AS REAL) DESC
-            LIMIT 1
+            INSERT OR REPLACE INTO tested_grids (
+                grid_hash, grid_id, symbol, days, sizing_mode, combinations
+            ) VALUES (?, ?, ?, ?, ?, ?)
             "#,
         )
-        .fetch_optional(self.pool)
+        .bind(workspace_key(self.workspace, grid_hash))
+        .bind(grid_id)
+        .bind(symbol)
+        .bind(days)
+        .bind(sizing_mode)
+        .bind(combinations)
+        .execute(self.pool)
         .await?;
+        Ok(())
+    }
 
-        let (best_win_rate, best_strategy_name) =
-            best_wr.unwrap_or_else(|| ("0".to_string(), "N/A".to_string()));
-
-        let best_net_pnl = best_pnl
-            .map(|(pnl, _)| pnl)
-            .unwrap_or_else(|| "0".to_string());
+    /// Move a row to `discovery_quarantine` with the reason it could not be migrated
+    pub async fn quarantine(&self, id: i64, reason: &str) -> DbResult<()> {
+        let mut tx = self.pool.begin().await?;
 
-        Ok(KnowledgeBaseStats {
-            total_backtests: total.0,
-            unique_strategies: unique_strategies.0,
+        sqlx::query(
+            r#"
+            INSERT INTO discovery_quarantine (
+                original_id, params_hash, strategy_type, strategy_name, strategy_params,
+                symbol, days, sizing_mode, schema_version, reason
+            )
+            SELECT id, params_hash, strategy_type, strategy_name, strategy_params,
+                   symbol, days, sizing_mode, schema_version, ?
+            FROM discovery_backtests
+            WHERE id = ?
+            "#,
+        )
+        .bind(reason)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM discovery_backtests WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        bump_write_generation();
+        Ok(())
+    }
+
+    /// Delete a single row by id
+    pub async fn delete_by_id(&self, id: i64) -> DbResult<()> {
+        sqlx::query("DELETE FROM discovery_backtests WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        bump_write_generation();
+        Ok(())
+    }
+
+    /// Number of rows currently held in quarantine
+    pub async fn count_quarantined(&self) -> DbResult<i64> {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM discovery_quarantine")
+            .fetch_one(self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// VACUUM the database to reclaim disk space
+    pub async fn vacuum(&self) -> DbResult<()> {
+        sqlx::query("VACUUM").execute(self.pool).await?;
+        Ok(())
+    }
+
+    /// Refresh the query planner statistics
+    pub async fn analyze(&self) -> DbResult<()> {
+        sqlx::query("ANALYZE").execute(self.pool).await?;
+        Ok(())
+    }
+
+    /// Reclaim free pages without rewriting the whole file.
+    ///
+    /// Incremental VACUUM needs `auto_vacuum=INCREMENTAL`, which SQLite only applies to an
+    /// existing database after a full VACUUM — so the first call on an older DB runs one.
+    pub async fn incremental_vacuum(&self) -> DbResult<VacuumStats> {
+        // The auto_vacuum change only applies to the connection that runs the VACUUM
+        let mut conn = self.pool.acquire().await?;
+
+        let (auto_vacuum,): (i64,) = sqlx::query_as("PRAGMA auto_vacuum")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        let (freelist_before,): (i64,) = sqlx::query_as("PRAGMA freelist_count")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        // 2 = INCREMENTAL
+        let full_vacuum = auto_vacuum != 2;
+        if full_vacuum {
+            sqlx::query("PRAGMA auto_vacuum=INCREMENTAL")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("VACUUM").execute(&mut *conn).await?;
+        } else {
+            sqlx::query("PRAGMA incremental_vacuum")
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        let (freelist_after,): (i64,) = sqlx::query_as("PRAGMA freelist_count")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(VacuumStats {
+            freed_pages: (freelist_before - freelist_after).max(0),
+            full_vacuum,
+        })
+    }
+
+    /// Composite score aggregates per (strategy_name, symbol), used to order discovery
+    /// work so historically strong families run first
+    pub async fn get_family_performance(&self) -> DbResult<Vec<FamilyPerformance>> {
+        let rows = sqlx::query_as::<_, FamilyPerformance>(
+            r#"
+            SELECT strategy_name, symbol,
+                   COUNT(*) AS backtests,
+                   AVG(composite_score) AS avg_score,
+                   MAX(composite_score) AS best_score
+            FROM discovery_backtests
+            WHERE workspace = ?1
+            GROUP BY strategy_name, symbol
+            "#,
+        )
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Strategy params of the `limit` most recent records, newest first
+    pub async fn get_recent_strategy_params(&self, limit: i64) -> DbResult<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT strategy_params FROM discovery_backtests WHERE workspace = ?1 \
+             ORDER BY id DESC LIMIT ?2",
+        )
+        .bind(self.workspace)
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(params,)| params).collect())
+    }
+
+    /// Most recent discovery runs first (by first stored backtest)
+    pub async fn list_runs(&self, limit: i64) -> DbResult<Vec<DiscoveryRunSummary>> {
+        let sql = format!(
+            "{RUN_SUMMARY_SQL} WHERE discovery_run_id IS NOT NULL AND workspace = ?2 \
+             GROUP BY discovery_run_id ORDER BY MIN(id) DESC LIMIT ?1"
+        );
+        let rows = sqlx::query_as::<_, DiscoveryRunSummary>(&sql)
+            .bind(limit)
+            .bind(self.workspace)
+            .fetch_all(self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_run(&self, run_id: &str) -> DbResult<Option<DiscoveryRunSummary>> {
+        let sql = format!(
+            "{RUN_SUMMARY_SQL} WHERE discovery_run_id = ?1 AND workspace = ?2 \
+             GROUP BY discovery_run_id"
+        );
+        let row = sqlx::query_as::<_, DiscoveryRunSummary>(&sql)
+            .bind(run_id)
+            .bind(self.workspace)
+            .fetch_optional(self.pool)
+            .await?;
+        Ok(row)
+    }
+
+    /// Compare the completed backtests of two runs (`None` if either run is unknown)
+    pub async fn compare_runs(
+        &self,
+        run_a: &str,
+        run_b: &str,
+        top_n: usize,
+    ) -> DbResult<Option<RunComparison>> {
+        let (Some(summary_a), Some(summary_b)) =
+            (self.get_run(run_a).await?, self.get_run(run_b).await?)
+        else {
+            return Ok(None);
+        };
+        let scores_a = self.run_scores(run_a).await?;
+        let scores_b = self.run_scores(run_b).await?;
+        let families_a = self.run_family_scores(run_a).await?;
+        let families_b = self.run_family_scores(run_b).await?;
+        Ok(Some(RunComparison::build(
+            (summary_a, &scores_a, families_a),
+            (summary_b, &scores_b, families_b),
+            top_n,
+        )))
+    }
+
+    /// `(composite_score, net_pnl)` of a run's completed backtests
+    async fn run_scores(&self, run_id: &str) -> DbResult<Vec<(f64, f64)>> {
+        let rows = sqlx::query_as(
+            r#"
+            SELECT composite_score, net_pnl
+            FROM discovery_backtests
+            WHERE discovery_run_id = ?1 AND workspace = ?2 AND abort_reason IS NULL
+            "#,
+        )
+        .bind(run_id)
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn run_family_scores(&self, run_id: &str) -> DbResult<Vec<RunFamilyScore>> {
+        let rows = sqlx::query_as::<_, RunFamilyScore>(
+            r#"
+            SELECT strategy_name,
+                   COUNT(*) AS backtests,
+                   MAX(composite_score) AS best_score
+            FROM discovery_backtests
+            WHERE discovery_run_id = ?1 AND workspace = ?2 AND abort_reason IS NULL
+            GROUP BY strategy_name
+            "#,
+        )
+        .bind(run_id)
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Record the kline quality of a run's symbol (a later check of the same symbol,
+    /// e.g. after a continuous run refreshed its klines, replaces it)
+    pub async fn save_data_quality(&self, record: &KlineQualityRecord) -> DbResult<()> {
+        sqlx::query(
+            r#"INSERT INTO kline_quality
+                (workspace, run_id, symbol, score, bars, expected_bars, missing_bars, gaps,
+                 duplicates, zero_volume_bars, spikes, repaired_bars)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+               ON CONFLICT(workspace, run_id, symbol) DO UPDATE SET
+                 score = excluded.score,
+                 bars = excluded.bars,
+                 expected_bars = excluded.expected_bars,
+                 missing_bars = excluded.missing_bars,
+                 gaps = excluded.gaps,
+                 duplicates = excluded.duplicates,
+                 zero_volume_bars = excluded.zero_volume_bars,
+                 spikes = excluded.spikes,
+                 repaired_bars = excluded.repaired_bars,
+                 created_at = strftime('%s', 'now')"#,
+        )
+        .bind(self.workspace)
+        .bind(&record.run_id)
+        .bind(&record.symbol)
+        .bind(record.score)
+        .bind(record.bars)
+        .bind(record.expected_bars)
+        .bind(record.missing_bars)
+        .bind(record.gaps)
+        .bind(record.duplicates)
+        .bind(record.zero_volume_bars)
+        .bind(record.spikes)
+        .bind(record.repaired_bars)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Kline quality of every symbol of a run, worst first
+    pub async fn get_data_quality(&self, run_id: &str) -> DbResult<Vec<KlineQualityRecord>> {
+        let rows = sqlx::query_as::<_, KlineQualityRecord>(
+            r#"
+            SELECT run_id, symbol, score, bars, expected_bars, missing_bars, gaps,
+                   duplicates, zero_volume_bars, spikes, repaired_bars, created_at
+            FROM kline_quality
+            WHERE run_id = ?1 AND workspace = ?2
+            ORDER BY score ASC, symbol ASC
+            "#,
+        )
+        .bind(run_id)
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Record a finished cycle of a continuous run
+    pub async fn save_cycle_summary(&self, record: &CycleSummaryRecord) -> DbResult<()> {
+        sqlx::query(
+            r#"INSERT OR REPLACE INTO cycle_summaries
+                (workspace, run_id, cycle, grid_size, combinations, new_results, best_score,
+                 duration_ms, throughput_per_sec, grid_snapshot_id)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"#,
+        )
+        .bind(self.workspace)
+        .bind(&record.run_id)
+        .bind(record.cycle)
+        .bind(record.grid_size)
+        .bind(record.combinations)
+        .bind(record.new_results)
+        .bind(record.best_score)
+        .bind(record.duration_ms)
+        .bind(record.throughput_per_sec)
+        .bind(&record.grid_snapshot_id)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Store a grid snapshot; one already stored under its `snapshot_id` is kept
+    pub async fn save_grid_snapshot(&self, record: &GridSnapshotRecord) -> DbResult<()> {
+        sqlx::query(
+            r#"INSERT OR IGNORE INTO grid_snapshots
+                (workspace, snapshot_id, strategy_count, schema_version, grid)
+               VALUES (?1, ?2, ?3, ?4, ?5)"#,
+        )
+        .bind(self.workspace)
+        .bind(&record.snapshot_id)
+        .bind(record.strategy_count)
+        .bind(record.schema_version)
+        .bind(&record.grid)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_grid_snapshot(
+        &self,
+        snapshot_id: &str,
+    ) -> DbResult<Option<GridSnapshotRecord>> {
+        let row = sqlx::query_as::<_, GridSnapshotRecord>(
+            r#"
+            SELECT snapshot_id, strategy_count, schema_version, grid, created_at
+            FROM grid_snapshots
+            WHERE snapshot_id = ?1 AND workspace = ?2
+            "#,
+        )
+        .bind(snapshot_id)
+        .bind(self.workspace)
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Cycles of a continuous run, oldest first
+    pub async fn get_cycle_summaries(&self, run_id: &str) -> DbResult<Vec<CycleSummaryRecord>> {
+        let rows = sqlx::query_as::<_, CycleSummaryRecord>(
+            r#"
+            SELECT run_id, cycle, grid_size, combinations, new_results, best_score,
+                   duration_ms, throughput_per_sec, grid_snapshot_id, created_at
+            FROM cycle_summaries
+            WHERE run_id = ?1 AND workspace = ?2
+            ORDER BY cycle ASC
+            "#,
+        )
+        .bind(run_id)
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Record a crash of a supervised run
+    pub async fn save_run_crash(&self, record: &RunCrashRecord) -> DbResult<()> {
+        sqlx::query(
+            r#"INSERT OR REPLACE INTO run_crashes
+                (workspace, run_id, restarts, crashed_at, message, restart_in_secs)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+        )
+        .bind(self.workspace)
+        .bind(&record.run_id)
+        .bind(record.restarts)
+        .bind(&record.crashed_at)
+        .bind(&record.message)
+        .bind(record.restart_in_secs)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Crashes of a run, oldest first
+    pub async fn get_run_crashes(&self, run_id: &str) -> DbResult<Vec<RunCrashRecord>> {
+        let rows = sqlx::query_as::<_, RunCrashRecord>(
+            r#"
+            SELECT run_id, restarts, crashed_at, message, restart_in_secs, created_at
+            FROM run_crashes
+            WHERE run_id = ?1 AND workspace = ?2
+            ORDER BY restarts ASC
+            "#,
+        )
+        .bind(run_id)
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Up to `limit` crashes of any run, newest first
+    pub async fn get_recent_run_crashes(&self, limit: i64) -> DbResult<Vec<RunCrashRecord>> {
+        let rows = sqlx::query_as::<_, RunCrashRecord>(
+            r#"
+            SELECT run_id, restarts, crashed_at, message, restart_in_secs, created_at
+            FROM run_crashes
+            WHERE workspace = ?2
+            ORDER BY crashed_at DESC, restarts DESC
+            LIMIT ?1
+            "#,
+        )
+        .bind(limit)
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Replace the population of the evolutionary search
+    pub async fn save_ga_population(&self, individuals: &[GaIndividualRecord]) -> DbResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM ga_population WHERE workspace = ?")
+            .bind(self.workspace)
+            .execute(&mut *tx)
+            .await?;
+
+        for individual in individuals {
+            sqlx::query(
+                r#"INSERT INTO ga_population
+                    (workspace, rank, genome, schema_version, strategy_name, symbol, fitness,
+                     generation, island)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+            )
+            .bind(self.workspace)
+            .bind(individual.rank)
+            .bind(&individual.genome)
+            .bind(individual.schema_version)
+            .bind(&individual.strategy_name)
+            .bind(&individual.symbol)
+            .bind(individual.fitness)
+            .bind(individual.generation)
+            .bind(&individual.island)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Population of the evolutionary search, fittest first (empty if none was saved)
+    pub async fn get_ga_population(&self) -> DbResult<Vec<GaIndividualRecord>> {
+        let rows = sqlx::query_as::<_, GaIndividualRecord>(
+            r#"
+            SELECT rank, genome, schema_version, strategy_name, symbol, fitness, generation,
+                   island, updated_at
+            FROM ga_population
+            WHERE workspace = ?1
+            ORDER BY rank ASC
+            "#,
+        )
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Get aggregated knowledge base stats
+    pub async fn get_stats(&self) -> DbResult<KnowledgeBaseStats> {
+        let total: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM discovery_backtests WHERE workspace = ?")
+                .bind(self.workspace)
+                .fetch_one(self.pool)
+                .await?;
+
+        let unique_strategies: (i64,) = sqlx::query_as(
+            "SELECT COUNT(DISTINCT strategy_type) FROM discovery_backtests WHERE workspace = ?",
+        )
+        .bind(self.workspace)
+        .fetch_one(self.pool)
+        .await?;
+
+        let unique_symbols: (i64,) = sqlx::query_as(
+            "SELECT COUNT(DISTINCT symbol) FROM discovery_backtests WHERE workspace = ?",
+        )
+        .bind(self.workspace)
+        .fetch_one(self.pool)
+        .await?;
+
+        let total_runs: (i64,) = sqlx::query_as(
+            "SELECT COUNT(DISTINCT discovery_run_id) FROM discovery_backtests WHERE discovery_run_id IS NOT NULL AND workspace = ?",
+        )
+        .bind(self.workspace)
+        .fetch_one(self.pool)
+        .await?;
+
+        // Best win rate (from records with at least 5 trades)
+        let best_wr: Option<(f64, String)> = sqlx::query_as(
+            r#"
+            SELECT win_rate, strategy_name
+            FROM discovery_backtests
+            WHERE total_trades >= 5 AND workspace = ?
+            ORDER BY win_rate DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(self.workspace)
+        .fetch_optional(self.pool)
+        .await?;
+
+        // Best net PnL
+        let best_pnl: Option<(f64, String)> = sqlx::query_as(
+            r#"
+            SELECT net_pnl, strategy_name
+            FROM discovery_backtests
+            WHERE total_trades >= 5 AND workspace = ?
+            ORDER BY net_pnl DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(self.workspace)
+        .fetch_optional(self.pool)
+        .await?;
+
+        let (best_win_rate, best_strategy_name) =
+            best_wr.unwrap_or_else(|| (0.0, "N/A".to_string()));
+
+        let best_net_pnl = best_pnl.map(|(pnl, _)| pnl).unwrap_or(0.0);
+
+        Ok(KnowledgeBaseStats {
+            total_backtests: total.0,
+            unique_strategies: unique_strategies.0,
             unique_symbols: unique_symbols.0,
             best_win_rate,
             best_net_pnl,
@@ -458,3 +1898,1037 @@ impl<'a> DiscoveryRepository<'a> {
         })
     }
 }
+
+/// Columns selected into `DiscoveryBacktestRecord` by the paginated queries
+pub(crate) const RECORD_COLUMNS: &str = r#"
+        id, params_hash, strategy_type, strategy_name, strategy_params,
+        symbol, days, sizing_mode,
+        composite_score, net_pnl, gross_pnl, total_fees,
+        win_rate, total_trades, sharpe_ratio, max_drawdown_pct,
+        profit_factor, avg_trade_pnl,
+        hit_rate, avg_locked_profit,
+        discovery_run_id, phase,
+        sortino_ratio, max_consecutive_losses, avg_win_pnl, avg_loss_pnl,
+        total_volume, annualized_return_pct, annualized_sharpe, strategy_confidence,
+        schema_version, abort_reason, probability_model, drawdown_throttle,
+        cross_symbol_score, exit_breakdown, decayed_score,
+        score_30d, net_pnl_30d, win_rate_30d, trades_30d,
+        score_90d, net_pnl_90d, win_rate_90d, trades_90d,
+        pnl_hhi, top_week_pnl_share, active_weeks,
+        longest_drawdown_days, avg_recovery_days, max_exposure_pct, avg_exposure_pct,
+        warmup_bars, effective_days, engine_version, logic_version, cost_attribution"#;
+
+/// SELECT/FROM of `DiscoveryRunSummary`, completed with a WHERE on discovery_run_id
+const RUN_SUMMARY_SQL: &str = r#"
+        SELECT discovery_run_id AS run_id,
+               COUNT(*) AS backtests,
+               COUNT(abort_reason) AS aborted,
+               COUNT(DISTINCT strategy_name) AS families,
+               MIN(created_at) AS started_at,
+               MAX(created_at) AS last_at
+        FROM discovery_backtests"#;
+
+/// One key of a ranking: results are ordered by the first key of a rank order, ties by
+/// the next ones, and remaining ties by id (insertion order)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RankKey {
+    Score,
+    Sharpe,
+    Trades,
+    NetPnl,
+    WinRate,
+    ProfitFactor,
+    /// Lowest max drawdown first
+    Drawdown,
+}
+
+/// Composite score, then Sharpe ratio, then trade count
+pub const DEFAULT_RANK_ORDER: &[RankKey] = &[RankKey::Score, RankKey::Sharpe, RankKey::Trades];
+
+impl RankKey {
+    pub const ALL: [RankKey; 7] = [
+        Self::Score,
+        Self::Sharpe,
+        Self::Trades,
+        Self::NetPnl,
+        Self::WinRate,
+        Self::ProfitFactor,
+        Self::Drawdown,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Score => "score",
+            Self::Sharpe => "sharpe",
+            Self::Trades => "trades",
+            Self::NetPnl => "net_pnl",
+            Self::WinRate => "win_rate",
+            Self::ProfitFactor => "profit_factor",
+            Self::Drawdown => "drawdown",
+        }
+    }
+
+    /// Key named `name`, by its own name or its column's
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|k| k.as_str() == name || k.column() == name)
+    }
+
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Score => "composite_score",
+            Self::Sharpe => "sharpe_ratio",
+            Self::Trades => "total_trades",
+            Self::NetPnl => "net_pnl",
+            Self::WinRate => "win_rate",
+            Self::ProfitFactor => "profit_factor",
+            Self::Drawdown => "max_drawdown_pct",
+        }
+    }
+
+    /// Drawdown ranks lowest first, every other key highest first
+    pub fn descending(self) -> bool {
+        self != Self::Drawdown
+    }
+}
+
+/// Parse a comma-separated rank order (`score,sharpe,trades`); a key listed twice only
+/// counts once
+pub fn parse_rank_order(order: &str) -> Result<Vec<RankKey>, String> {
+    let mut keys = Vec::new();
+    for name in order.split(',').filter(|n| !n.trim().is_empty()) {
+        let key = RankKey::parse(name).ok_or_else(|| {
+            let names: Vec<&str> = RankKey::ALL.iter().map(|k| k.as_str()).collect();
+            format!(
+                "Unknown rank key '{}' (expected one of: {})",
+                name.trim(),
+                names.join(", ")
+            )
+        })?;
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    if keys.is_empty() {
+        return Err("Empty rank order".to_string());
+    }
+    Ok(keys)
+}
+
+/// `score,sharpe,trades` form of a rank order
+pub fn format_rank_order(order: &[RankKey]) -> String {
+    let names: Vec<&str> = order.iter().map(|k| k.as_str()).collect();
+    names.join(",")
+}
+
+/// ORDER BY terms of a rank order on the columns of table alias `alias` (none if empty),
+/// ending with the id tie-break
+fn rank_order_sql(order: &[RankKey], alias: &str) -> String {
+    let prefix = if alias.is_empty() {
+        String::new()
+    } else {
+        format!("{alias}.")
+    };
+    let mut terms: Vec<String> = order
+        .iter()
+        .map(|k| {
+            let direction = if k.descending() { "DESC" } else { "ASC" };
+            format!("{prefix}{} {direction}", k.column())
+        })
+        .collect();
+    terms.push(format!("{prefix}id ASC"));
+    terms.join(", ")
+}
+
+/// Whitelisted sort column for the knowledge base listing (default: composite_score)
+pub fn sort_column(sort_by: Option<&str>) -> &'static str {
+    match sort_by {
+        Some("win_rate") => "win_rate",
+        Some("net_pnl") => "net_pnl",
+        Some("sharpe_ratio") => "sharpe_ratio",
+        Some("total_trades") => "total_trades",
+        Some("max_drawdown_pct") => "max_drawdown_pct",
+        // ids follow insertion order (= created_at order) and are unique, which keeps
+        // keyset cursors exact; created_at itself is not part of the record
+        Some("created_at") | Some("id") => "id",
+        Some("strategy_confidence") => "strategy_confidence",
+        Some("annualized_return_pct") => "annualized_return_pct",
+        Some("sortino_ratio") => "sortino_ratio",
+        Some("decayed_score") => "decayed_score",
+        Some("score_30d") => "score_30d",
+        Some("score_90d") => "score_90d",
+        _ => "composite_score",
+    }
+}
+
+/// WHERE clauses for the workspace and the optional filters. Placeholders, in order:
+/// workspace, then strategy_type, symbol, min_win_rate, min_recent_win_rate,
+/// max_pnl_hhi (each only if present). Equality
+/// filters come first so the `(strategy_type, symbol, composite_score)`-style indexes
+/// can serve the ORDER BY. The workspace is left out of every index and checked on the
+/// rows those indexes return; `likelihood(.., 1.0)` keeps the planner from trading the
+/// ordered index walk for a smaller estimated row count and a temp B-tree sort.
+fn filter_clauses(
+    has_strategy_type: bool,
+    has_symbol: bool,
+    has_min_win_rate: bool,
+    has_min_recent_win_rate: bool,
+    has_max_pnl_hhi: bool,
+) -> Vec<&'static str> {
+    let mut where_clauses = vec!["likelihood(workspace = ?, 1.0)"];
+    if has_strategy_type {
+        where_clauses.push("strategy_type = ?");
+    }
+    if has_symbol {
+        where_clauses.push("symbol = ?");
+    }
+    if has_min_win_rate {
+        where_clauses.push("win_rate >= ?");
+    }
+    if has_min_recent_win_rate {
+        where_clauses.push("win_rate_30d >= ?");
+    }
+    if has_max_pnl_hhi {
+        where_clauses.push("pnl_hhi <= ?");
+    }
+    where_clauses
+}
+
+/// Build the (count, data) SQL for `get_all_paginated`.
+///
+/// Placeholders: the filters (see `filter_clauses`), then LIMIT and OFFSET for the data
+/// query. Ties are broken by `id ASC`, the order the sort indexes store them in.
+fn paginated_sql(
+    has_strategy_type: bool,
+    has_symbol: bool,
+    has_min_win_rate: bool,
+    has_min_recent_win_rate: bool,
+    has_max_pnl_hhi: bool,
+    sort_by: Option<&str>,
+) -> (String, String) {
+    let where_sql = filter_clauses(
+        has_strategy_type,
+        has_symbol,
+        has_min_win_rate,
+        has_min_recent_win_rate,
+        has_max_pnl_hhi,
+    )
+    .join(" AND ");
+    let order_col = sort_column(sort_by);
+
+    let count_sql = format!("SELECT COUNT(*) FROM discovery_backtests WHERE {where_sql}");
+    let data_sql = format!(
+        r#"
+        SELECT {RECORD_COLUMNS}
+        FROM discovery_backtests
+        WHERE {where_sql}
+        ORDER BY {order_col} DESC, id ASC
+        LIMIT ? OFFSET ?
+        "#
+    );
+    (count_sql, data_sql)
+}
+
+/// Build the data SQL for `get_page_after` (same order as `paginated_sql`).
+///
+/// Placeholders: the filters, then the cursor position — `(value, value, id)` after a
+/// non-NULL sort value or `(id)` once inside the trailing NULLs — then LIMIT.
+fn keyset_sql(
+    has_strategy_type: bool,
+    has_symbol: bool,
+    has_min_win_rate: bool,
+    has_min_recent_win_rate: bool,
+    has_max_pnl_hhi: bool,
+    sort_by: Option<&str>,
+    cursor: Option<&KnowledgeCursor>,
+) -> String {
+    let order_col = sort_column(sort_by);
+    let mut where_clauses = filter_clauses(
+        has_strategy_type,
+        has_symbol,
+        has_min_win_rate,
+        has_min_recent_win_rate,
+        has_max_pnl_hhi,
+    )
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<_>>();
+
+    // NULL sort values come last in DESC order, so they follow every non-NULL value
+    match cursor.map(|c| c.value) {
+        Some(Some(_)) => where_clauses.push(format!(
+            "({order_col} < ? OR ({order_col} = ? AND id > ?) OR {order_col} IS NULL)"
+        )),
+        Some(None) => where_clauses.push(format!("({order_col} IS NULL AND id > ?)")),
+        None => {}
+    }
+    let where_sql = where_clauses.join(" AND ");
+
+    format!(
+        r#"
+        SELECT {RECORD_COLUMNS}
+        FROM discovery_backtests
+        WHERE {where_sql}
+        ORDER BY {order_col} DESC, id ASC
+        LIMIT ?
+        "#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    const SYMBOLS: [&str; 4] = ["BTCUSDT", "ETHUSDT", "SOLUSDT", "XRPUSDT"];
+    const STRATEGY_TYPES: [&str; 5] = ["rsi", "macd", "bollinger", "ema_cross", "dynamic_combo"];
+
+    fn record(i: usize) -> DiscoveryBacktestRecord {
+        // 1000 distinct scores over 2000 rows: ties exercise the id tie-break
+        let score = ((i * 7919) % 1_000) as f64 / 10.0;
+        DiscoveryBacktestRecord {
+            id: None,
+            params_hash: format!("hash-{i}"),
+            strategy_type: STRATEGY_TYPES[i % STRATEGY_TYPES.len()].to_string(),
+            strategy_name: format!("strategy-{}", i % 40),
+            strategy_params: "{}".to_string(),
+            symbol: SYMBOLS[i % SYMBOLS.len()].to_string(),
+            days: 90,
+            sizing_mode: "fixed".to_string(),
+            composite_score: score,
+            net_pnl: score * 3.0 - 100.0,
+            gross_pnl: score * 3.0,
+            total_fees: 1.0,
+            win_rate: score / 100.0,
+            total_trades: (i % 50) as i64,
+            sharpe_ratio: score / 50.0,
+            max_drawdown_pct: 10.0,
+            profit_factor: 1.2,
+            avg_trade_pnl: 0.5,
+            hit_rate: None,
+            avg_locked_profit: None,
+            discovery_run_id: Some("run".to_string()),
+            phase: Some("explore".to_string()),
+            sortino_ratio: (!i.is_multiple_of(3)).then_some(score / 20.0),
+            max_consecutive_losses: Some(3),
+            avg_win_pnl: Some(2.0),
+            avg_loss_pnl: Some(-1.0),
+            total_volume: Some(1000.0),
+            annualized_return_pct: Some(12.0),
+            annualized_sharpe: Some(1.1),
+            strategy_confidence: Some(0.5),
+            schema_version: Some(1),
+            abort_reason: None,
+            probability_model: None,
+            drawdown_throttle: None,
+            cross_symbol_score: None,
+            exit_breakdown: None,
+            decayed_score: None,
+            // A fifth of the records had no trade in their last 30 days
+            score_30d: (!i.is_multiple_of(5)).then_some(score / 2.0),
+            net_pnl_30d: (!i.is_multiple_of(5)).then_some(score - 50.0),
+            win_rate_30d: (!i.is_multiple_of(5)).then_some((i % 100) as f64),
+            trades_30d: Some((i % 5) as i64),
+            score_90d: None,
+            net_pnl_90d: None,
+            win_rate_90d: None,
+            trades_90d: None,
+            // Every fourth record without PnL, the others spread over 1, 2 or 4 weeks
+            pnl_hhi: [None, Some(1.0), Some(0.5), Some(0.25)][i % 4],
+            top_week_pnl_share: None,
+            active_weeks: None,
+            longest_drawdown_days: None,
+            avg_recovery_days: None,
+            max_exposure_pct: None,
+            avg_exposure_pct: None,
+            warmup_bars: None,
+            effective_days: None,
+            engine_version: None,
+            logic_version: None,
+            cost_attribution: None,
+        }
+    }
+
+    async fn query_plan(pool: &SqlitePool, sql: &str, binds: usize) -> String {
+        let explain = format!("EXPLAIN QUERY PLAN {sql}");
+        let mut query = sqlx::query_as::<_, (i64, i64, i64, String)>(&explain);
+        for _ in 0..binds {
+            query = query.bind("x");
+        }
+        let rows = query.fetch_all(pool).await.unwrap();
+        rows.into_iter()
+            .map(|(_, _, _, detail)| detail)
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    /// Every filter/sort combination served by a dedicated index must avoid both a full
+    /// table scan and a temp B-tree sort, and the paged results must stay ordered.
+    #[tokio::test]
+    async fn test_paginated_queries_use_indexes() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool());
+
+        for i in 0..2_000 {
+            repo.save(&record(i)).await.unwrap();
+        }
+        repo.analyze().await.unwrap();
+
+        // (strategy_type filter, symbol filter, sort_by)
+        let cases: [(bool, bool, Option<&str>); 6] = [
+            (false, false, None),
+            (true, false, None),
+            (false, true, None),
+            (true, true, None),
+            (false, true, Some("win_rate")),
+            (false, true, Some("net_pnl")),
+        ];
+
+        for (has_type, has_symbol, sort_by) in cases {
+            let (count_sql, data_sql) =
+                paginated_sql(has_type, has_symbol, false, false, false, sort_by);
+            let binds = has_type as usize + has_symbol as usize;
+
+            // The workspace is always bound first
+            let plan = query_plan(db.pool(), &data_sql, 1 + binds + 2).await;
+            assert!(plan.contains("USING INDEX"), "{sort_by:?}: {plan}");
+            assert!(!plan.contains("TEMP B-TREE"), "{sort_by:?}: {plan}");
+
+            let count_plan = query_plan(db.pool(), &count_sql, 1 + binds).await;
+            if binds > 0 {
+                assert!(count_plan.contains("INDEX"), "{sort_by:?}: {count_plan}");
+            }
+        }
+
+        let (page, total) = repo
+            .get_all_paginated(
+                50,
+                100,
+                Some("rsi"),
+                Some("BTCUSDT"),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(total, 100);
+        assert!(page
+            .iter()
+            .all(|r| r.strategy_type == "rsi" && r.symbol == "BTCUSDT"));
+        assert!(page
+            .windows(2)
+            .all(|w| w[0].composite_score >= w[1].composite_score));
+
+        let (filtered, _) = repo
+            .get_all_paginated(
+                20,
+                0,
+                None,
+                Some("ETHUSDT"),
+                Some(0.5),
+                None,
+                None,
+                Some("win_rate"),
+            )
+            .await
+            .unwrap();
+        assert!(filtered
+            .iter()
+            .all(|r| r.win_rate >= 0.5 && r.symbol == "ETHUSDT"));
+        assert!(filtered.windows(2).all(|w| w[0].win_rate >= w[1].win_rate));
+
+        // Records without recent trades never pass the recent win rate screen
+        let (recent, recent_total) = repo
+            .get_all_paginated(20, 0, None, None, None, Some(60.0), None, Some("score_30d"))
+            .await
+            .unwrap();
+        assert_eq!(recent_total, 640);
+        assert!(recent.iter().all(|r| r.win_rate_30d.unwrap() >= 60.0));
+        assert!(recent.windows(2).all(|w| w[0].score_30d >= w[1].score_30d));
+
+        // Neither do records without PnL through the concentration screen
+        let (spread, spread_total) = repo
+            .get_all_paginated(20, 0, None, None, None, None, Some(0.25), None)
+            .await
+            .unwrap();
+        assert_eq!(spread_total, 500);
+        assert!(spread.iter().all(|r| r.pnl_hhi.unwrap() <= 0.25));
+    }
+
+    #[test]
+    fn test_cursor_token_roundtrip() {
+        let cursor = KnowledgeCursor {
+            sort_by: "win_rate".to_string(),
+            value: Some(0.625),
+            id: 42,
+        };
+        assert_eq!(KnowledgeCursor::decode(&cursor.encode()), Some(cursor));
+
+        let null_cursor = KnowledgeCursor {
+            sort_by: "sortino_ratio".to_string(),
+            value: None,
+            id: 7,
+        };
+        assert_eq!(
+            KnowledgeCursor::decode(&null_cursor.encode()),
+            Some(null_cursor)
+        );
+
+        assert_eq!(KnowledgeCursor::decode("zz"), None);
+        assert_eq!(KnowledgeCursor::decode("abc"), None);
+        // Sort column outside the whitelist
+        let injected: String = "id; DROP TABLE x|1|1"
+            .bytes()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        assert_eq!(KnowledgeCursor::decode(&injected), None);
+    }
+
+    #[tokio::test]
+    async fn test_tested_grids_roundtrip() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool());
+        assert!(!repo.is_grid_tested("g1").await.unwrap());
+
+        repo.mark_grid_tested("g1", "grid-a", "BTCUSDT", 90, "Fixed", 1743)
+            .await
+            .unwrap();
+        // Marking twice (e.g. a second full run) is harmless
+        repo.mark_grid_tested("g1", "grid-a", "BTCUSDT", 90, "Fixed", 1743)
+            .await
+            .unwrap();
+        assert!(repo.is_grid_tested("g1").await.unwrap());
+        assert!(!repo.is_grid_tested("g2").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_save_batch_ignores_existing_hashes() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool());
+        repo.save(&record(0)).await.unwrap();
+
+        let batch: Vec<_> = (0..1_200).map(record).collect();
+        let generation = write_generation();
+        assert_eq!(repo.save_batch(&batch).await.unwrap(), 1_199);
+        assert!(write_generation() > generation);
+        assert_eq!(repo.save_batch(&batch[..10]).await.unwrap(), 0);
+        assert_eq!(repo.get_stats().await.unwrap().total_backtests, 1_200);
+        let stored = repo.get_by_hash(&batch[700].params_hash).await.unwrap();
+        assert!(stored.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_later_logic_version_replaces_row() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool());
+        let legacy = record(0);
+        let id = repo.save(&legacy).await.unwrap();
+
+        // Same params recomputed by a later logic: same row, new result
+        let current = DiscoveryBacktestRecord {
+            composite_score: 99.0,
+            engine_version: Some("0.2.0".to_string()),
+            logic_version: Some(2),
+            ..legacy.clone()
+        };
+        let batch = std::slice::from_ref(&current);
+        assert_eq!(repo.save_batch(batch).await.unwrap(), 1);
+        let stored = repo.get_by_id(id).await.unwrap().unwrap();
+        assert_eq!(stored.id, Some(id));
+        assert_eq!(stored.composite_score, 99.0);
+        assert_eq!(stored.engine_version.as_deref(), Some("0.2.0"));
+
+        // Neither the same nor an earlier logic overwrites it
+        let older = DiscoveryBacktestRecord {
+            composite_score: 1.0,
+            logic_version: Some(1),
+            ..current.clone()
+        };
+        assert_eq!(repo.save_batch(&[current, older, legacy]).await.unwrap(), 0);
+        let stored = repo.get_by_id(id).await.unwrap().unwrap();
+        assert_eq!(stored.composite_score, 99.0);
+        assert_eq!(stored.logic_version, Some(2));
+        assert_eq!(repo.get_stats().await.unwrap().total_backtests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rank_order_breaks_ties() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool());
+        // Same score: sharpe decides, then trades, then insertion order
+        let tied = |i: usize, sharpe: f64, trades: i64, drawdown: f64| DiscoveryBacktestRecord {
+            strategy_name: format!("tied-{i}"),
+            strategy_type: "rsi".to_string(),
+            composite_score: 50.0,
+            sharpe_ratio: sharpe,
+            total_trades: trades,
+            max_drawdown_pct: drawdown,
+            ..record(i)
+        };
+        let mut ids = Vec::new();
+        for (i, sharpe, trades, drawdown) in [
+            (0, 1.0, 10, 5.0),
+            (1, 2.0, 10, 9.0),
+            (2, 1.0, 30, 7.0),
+            (3, 1.0, 30, 3.0),
+        ] {
+            ids.push(repo.save(&tied(i, sharpe, trades, drawdown)).await.unwrap());
+        }
+
+        let ranked = |records: Vec<DiscoveryBacktestRecord>| -> Vec<i64> {
+            records.iter().filter_map(|r| r.id).collect()
+        };
+        let top = repo
+            .get_top_results(10, None, None, DEFAULT_RANK_ORDER)
+            .await
+            .unwrap();
+        assert_eq!(ranked(top), vec![ids[1], ids[2], ids[3], ids[0]]);
+        let unique = repo
+            .get_top_unique_strategies(10, DEFAULT_RANK_ORDER)
+            .await
+            .unwrap();
+        assert_eq!(ranked(unique), vec![ids[1], ids[2], ids[3], ids[0]]);
+
+        // Drawdown ranks the smallest first
+        let order = parse_rank_order("drawdown, score").unwrap();
+        assert_eq!(format_rank_order(&order), "drawdown,score");
+        let top = repo.get_top_results(10, None, None, &order).await.unwrap();
+        assert_eq!(ranked(top), vec![ids[3], ids[0], ids[2], ids[1]]);
+
+        // Keys are accepted by name or column, once each
+        assert_eq!(
+            parse_rank_order("sharpe_ratio,trades,sharpe").unwrap(),
+            vec![RankKey::Sharpe, RankKey::Trades]
+        );
+        assert!(parse_rank_order("score,volume").is_err());
+        assert!(parse_rank_order(" ").is_err());
+    }
+
+    /// Walking every page with cursors must return exactly the rows of one big ordered
+    /// query — including ties on the sort value and trailing NULLs.
+    #[tokio::test]
+    async fn test_keyset_pages_match_offset_order() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool());
+        for i in 0..2_000 {
+            repo.save(&record(i)).await.unwrap();
+        }
+
+        for (symbol, sort_by) in [
+            (None, None),
+            (Some("BTCUSDT"), None),
+            (None, Some("sortino_ratio")),
+            (Some("ETHUSDT"), Some("win_rate")),
+            (None, Some("created_at")),
+        ] {
+            let (expected, total) = repo
+                .get_all_paginated(10_000, 0, None, symbol, None, None, None, sort_by)
+                .await
+                .unwrap();
+            let expected: Vec<i64> = expected.iter().filter_map(|r| r.id).collect();
+            assert_eq!(expected.len() as i64, total);
+
+            let mut walked = Vec::new();
+            let mut cursor: Option<KnowledgeCursor> = None;
+            loop {
+                let (page, next) = repo
+                    .get_page_after(97, cursor.as_ref(), None, symbol, None, None, None, sort_by)
+                    .await
+                    .unwrap();
+                walked.extend(page.iter().filter_map(|r| r.id));
+                // Cursors survive the token round-trip used by the API
+                cursor = match next {
+                    Some(c) => KnowledgeCursor::decode(&c.encode()),
+                    None => break,
+                };
+            }
+            assert_eq!(walked, expected, "{symbol:?} / {sort_by:?}");
+        }
+
+        // Deep pages stay on the index
+        let cursor = KnowledgeCursor {
+            sort_by: "composite_score".to_string(),
+            value: Some(50.0),
+            id: 1000,
+        };
+        let plan = query_plan(
+            db.pool(),
+            &keyset_sql(false, true, false, false, false, None, Some(&cursor)),
+            5,
+        )
+        .await;
+        assert!(plan.contains("USING INDEX"), "{plan}");
+        assert!(!plan.contains("TEMP B-TREE"), "{plan}");
+
+        // A cursor issued for another sort order is rejected
+        let err = repo
+            .get_page_after(
+                10,
+                Some(&cursor),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some("net_pnl"),
+            )
+            .await;
+        assert!(err.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_compare_runs() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool());
+        let row = |i: usize, run: &str, name: &str, score: f64| DiscoveryBacktestRecord {
+            strategy_name: name.to_string(),
+            composite_score: score,
+            net_pnl: score - 50.0,
+            discovery_run_id: Some(run.to_string()),
+            ..record(i)
+        };
+        let mut rows = vec![
+            row(0, "a", "rsi", 40.0),
+            row(1, "a", "macd", 60.0),
+            row(2, "a", "macd", 20.0),
+            row(3, "b", "macd", 55.0),
+            row(4, "b", "vwap", 80.0),
+            row(5, "b", "rsi", 70.0),
+        ];
+        rows.push(DiscoveryBacktestRecord {
+            abort_reason: Some("ruin".to_string()),
+            ..row(6, "b", "ema", -9999.0)
+        });
+        for r in &rows {
+            repo.save(r).await.unwrap();
+        }
+
+        let runs = repo.list_runs(10).await.unwrap();
+        let ids: Vec<&str> = runs.iter().map(|r| r.run_id.as_str()).collect();
+        assert_eq!(ids, ["b", "a"]);
+        assert_eq!(
+            (runs[0].backtests, runs[0].aborted, runs[0].families),
+            (4, 1, 4)
+        );
+
+        let cmp = repo.compare_runs("a", "b", 2).await.unwrap().unwrap();
+        assert_eq!(cmp.run_a.scores.count, 3);
+        assert_eq!(cmp.run_a.scores.median, 40.0);
+        // The aborted backtest is left out of the distribution and the families
+        assert_eq!(cmp.run_b.scores.count, 3);
+        assert_eq!(cmp.run_b.scores.min, 55.0);
+        assert_eq!(cmp.run_b.scores.profitable, 3);
+        assert_eq!(cmp.best_score_delta, 20.0);
+        assert_eq!(cmp.median_score_delta, 30.0);
+
+        let top: Vec<&str> = cmp
+            .top_strategies
+            .iter()
+            .map(|d| d.strategy_name.as_str())
+            .collect();
+        assert_eq!(top, ["vwap", "rsi", "macd"]);
+        let rsi = &cmp.top_strategies[1];
+        assert_eq!((rsi.rank_a, rsi.rank_b), (Some(2), Some(2)));
+        assert_eq!(rsi.score_delta, Some(30.0));
+        assert_eq!(cmp.top_strategies[0].rank_a, None);
+        assert_eq!(cmp.new_families.len(), 1);
+        assert_eq!(cmp.new_families[0].strategy_name, "vwap");
+        assert!(cmp.dropped_families.is_empty());
+
+        assert!(repo
+            .compare_runs("a", "missing", 10)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_workspaces_are_isolated() {
+        let db = Database::in_memory().await.unwrap();
+        let alice = DiscoveryRepository::new(db.pool()).with_workspace("alice");
+        let bob = DiscoveryRepository::new(db.pool()).with_workspace("bob");
+
+        // Same params hash in both workspaces: each keeps its own row
+        for i in 0..10 {
+            alice.save(&record(i)).await.unwrap();
+        }
+        bob.save(&record(0)).await.unwrap();
+        assert!(bob.exists_by_hash("hash-0").await.unwrap());
+        assert!(!bob.exists_by_hash("hash-1").await.unwrap());
+
+        assert_eq!(alice.get_stats().await.unwrap().total_backtests, 10);
+        assert_eq!(bob.get_stats().await.unwrap().total_backtests, 1);
+        let (_, total) = bob
+            .get_all_paginated(50, 0, None, None, None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        let bob_record = bob.get_by_hash("hash-0").await.unwrap().unwrap();
+        let bob_id = bob_record.id.unwrap();
+        assert!(alice.get_by_id(bob_id).await.unwrap().is_none());
+        let default = DiscoveryRepository::new(db.pool());
+        assert!(default.get_by_id(bob_id).await.unwrap().is_none());
+
+        alice
+            .mark_grid_tested("g1", "grid-a", "BTCUSDT", 90, "Fixed", 10)
+            .await
+            .unwrap();
+        assert!(!bob.is_grid_tested("g1").await.unwrap());
+
+        // Cleanup only deletes the repository's own workspace
+        alice.cleanup_keep_top_n(1).await.unwrap();
+        assert_eq!(bob.get_stats().await.unwrap().total_backtests, 1);
+        assert_eq!(
+            alice.list_workspaces().await.unwrap(),
+            ["alice".to_string(), "bob".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retention_policy() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = DiscoveryRepository::new(db.pool()).with_workspace("alice");
+        let records: Vec<DiscoveryBacktestRecord> = (0..200).map(record).collect();
+        repo.save_batch(&records).await.unwrap();
+        DiscoveryRepository::new(db.pool())
+            .save(&record(0))
+            .await
+            .unwrap();
+        // Odd ids (even i) are 40 days old
+        sqlx::query(
+            "UPDATE discovery_backtests SET created_at = created_at - 40 * 86400
+             WHERE workspace = 'alice' AND id % 2 = 1",
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+        // The worst result is in paper trading
+        let worst = records
+            .iter()
+            .min_by(|a, b| a.composite_score.total_cmp(&b.composite_score))
+            .unwrap();
+        let stored = repo.get_by_hash(&worst.params_hash).await.unwrap().unwrap();
+        sqlx::query("INSERT INTO strategy_pipeline (backtest_id, stage) VALUES (?, 'paper')")
+            .bind(stored.id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        let none = RetentionPolicy::default();
+        assert!(!none.is_enabled());
+        assert_eq!(repo.apply_retention(&none).await.unwrap().deleted(), 0);
+
+        let policy = RetentionPolicy {
+            keep_top_n: Some(2),
+            min_score: Some(20.0),
+            drop_negative_after_days: Some(30),
+        };
+        let report = repo.apply_retention(&policy).await.unwrap();
+        let protected = |r: &DiscoveryBacktestRecord| r.params_hash == worst.params_hash;
+        let below: Vec<usize> = (0..200)
+            .filter(|&i| records[i].composite_score < 20.0 && !protected(&records[i]))
+            .collect();
+        let stale = (0..200)
+            .filter(|&i| !below.contains(&i) && i % 2 == 0 && records[i].net_pnl < 0.0)
+            .filter(|&i| !protected(&records[i]))
+            .count();
+        assert_eq!(report.below_min_score as usize, below.len());
+        assert_eq!(report.stale_negative as usize, stale);
+        assert!(report.beyond_top_n > 0);
+        assert_eq!(report.remaining, 200 - report.deleted() as i64);
+
+        // At most 2 per strategy × symbol, plus the protected row
+        let (max_per_group,): (i64,) = sqlx::query_as(
+            "SELECT MAX(n) FROM (
+                SELECT COUNT(*) AS n FROM discovery_backtests
+                WHERE workspace = 'alice' AND id != ?
+                GROUP BY strategy_name, symbol
+            )",
+        )
+        .bind(stored.id)
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(max_per_group, 2);
+        assert!(repo.exists_by_hash(&worst.params_hash).await.unwrap());
+        // Other workspaces are untouched
+        let default = DiscoveryRepository::new(db.pool());
+        assert_eq!(default.get_stats().await.unwrap().total_backtests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_data_quality_upsert_per_workspace() {
+        let db = Database::in_memory().await.unwrap();
+        let alice = DiscoveryRepository::new(db.pool()).with_workspace("alice");
+        let bob = DiscoveryRepository::new(db.pool()).with_workspace("bob");
+        let quality = |symbol: &str, score: f64| KlineQualityRecord {
+            run_id: "run".to_string(),
+            symbol: symbol.to_string(),
+            score,
+            bars: 96,
+            expected_bars: 100,
+            missing_bars: 4,
+            gaps: 1,
+            duplicates: 0,
+            zero_volume_bars: 0,
+            spikes: 0,
+            repaired_bars: 0,
+            created_at: None,
+        };
+
+        // A later check of the same symbol replaces the first one
+        for (symbol, score) in [("BTCUSDT", 96.0), ("ETHUSDT", 99.0), ("ETHUSDT", 80.0)] {
+            let record = quality(symbol, score);
+            alice.save_data_quality(&record).await.unwrap();
+        }
+
+        let rows = alice.get_data_quality("run").await.unwrap();
+        let scores: Vec<(&str, f64)> = rows.iter().map(|r| (r.symbol.as_str(), r.score)).collect();
+        assert_eq!(scores, [("ETHUSDT", 80.0), ("BTCUSDT", 96.0)]);
+        assert!(rows[0].created_at.is_some());
+        assert!(bob.get_data_quality("run").await.unwrap().is_empty());
+        assert!(alice.get_data_quality("other").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cycle_summaries_in_order_per_workspace() {
+        let db = Database::in_memory().await.unwrap();
+        let alice = DiscoveryRepository::new(db.pool()).with_workspace("alice");
+        let bob = DiscoveryRepository::new(db.pool()).with_workspace("bob");
+        let summary = |cycle: i64, new_results: i64| CycleSummaryRecord {
+            run_id: "run".to_string(),
+            cycle,
+            grid_size: 40,
+            combinations: 160,
+            new_results,
+            best_score: Some(12.5),
+            duration_ms: 2_000,
+            throughput_per_sec: new_results as f64 / 2.0,
+            grid_snapshot_id: Some(format!("grid-{cycle}")),
+            created_at: None,
+        };
+
+        for (cycle, new_results) in [(1, 80), (0, 160), (1, 90)] {
+            alice
+                .save_cycle_summary(&summary(cycle, new_results))
+                .await
+                .unwrap();
+        }
+
+        // Oldest first, a cycle saved twice keeps its last summary
+        let rows = alice.get_cycle_summaries("run").await.unwrap();
+        let cycles: Vec<(i64, i64)> = rows.iter().map(|r| (r.cycle, r.new_results)).collect();
+        assert_eq!(cycles, [(0, 160), (1, 90)]);
+        assert_eq!(rows[1].throughput_per_sec, 45.0);
+        assert_eq!(rows[1].grid_snapshot_id.as_deref(), Some("grid-1"));
+        assert!(rows[0].created_at.is_some());
+        assert!(bob.get_cycle_summaries("run").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_crashes_per_run_and_workspace() {
+        let db = Database::in_memory().await.unwrap();
+        let alice = DiscoveryRepository::new(db.pool()).with_workspace("alice");
+        let bob = DiscoveryRepository::new(db.pool()).with_workspace("bob");
+        let crash = |run_id: &str, restarts: i64, at: &str| RunCrashRecord {
+            run_id: run_id.to_string(),
+            restarts,
+            crashed_at: at.to_string(),
+            message: format!("crash {restarts}"),
+            restart_in_secs: (restarts < 1).then_some(30),
+            created_at: None,
+        };
+
+        for record in [
+            crash("run-2", 1, "2026-10-16T10:05:00+00:00"),
+            crash("run-1", 0, "2026-10-16T10:00:00+00:00"),
+        ] {
+            alice.save_run_crash(&record).await.unwrap();
+        }
+
+        let rows = alice.get_run_crashes("run-1").await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].message, "crash 0");
+        assert_eq!(rows[0].restart_in_secs, Some(30));
+        assert!(rows[0].created_at.is_some());
+
+        // Newest first, across runs
+        let recent = alice.get_recent_run_crashes(10).await.unwrap();
+        let runs: Vec<&str> = recent.iter().map(|r| r.run_id.as_str()).collect();
+        assert_eq!(runs, ["run-2", "run-1"]);
+        assert_eq!(recent[0].restart_in_secs, None);
+        assert!(bob.get_run_crashes("run-1").await.unwrap().is_empty());
+        assert!(bob.get_recent_run_crashes(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_grid_snapshots_stored_once_per_workspace() {
+        let db = Database::in_memory().await.unwrap();
+        let alice = DiscoveryRepository::new(db.pool()).with_workspace("alice");
+        let bob = DiscoveryRepository::new(db.pool()).with_workspace("bob");
+        let snapshot = |grid: &[u8]| GridSnapshotRecord {
+            snapshot_id: "abc".to_string(),
+            strategy_count: 2,
+            schema_version: 1,
+            grid: grid.to_vec(),
+            created_at: None,
+        };
+
+        // The first grid stored under an id is kept
+        alice
+            .save_grid_snapshot(&snapshot(&[1, 2, 3]))
+            .await
+            .unwrap();
+        alice.save_grid_snapshot(&snapshot(&[9])).await.unwrap();
+        let stored = alice.get_grid_snapshot("abc").await.unwrap().unwrap();
+        assert_eq!(stored.grid, [1, 2, 3]);
+        assert_eq!(stored.strategy_count, 2);
+        assert!(stored.created_at.is_some());
+        assert!(alice.get_grid_snapshot("other").await.unwrap().is_none());
+        assert!(bob.get_grid_snapshot("abc").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ga_population_replaced_per_workspace() {
+        let db = Database::in_memory().await.unwrap();
+        let alice = DiscoveryRepository::new(db.pool()).with_workspace("alice");
+        let bob = DiscoveryRepository::new(db.pool()).with_workspace("bob");
+        let individual = |rank: i64, fitness: f64, generation: i64| GaIndividualRecord {
+            rank,
+            genome: format!("{{\"type\":\"rsi\",\"period\":{}}}", 10 + rank),
+            schema_version: 1,
+            strategy_name: "RSI".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            fitness,
+            generation,
+            island: "BTCUSDT".to_string(),
+            updated_at: None,
+        };
+
+        alice
+            .save_ga_population(&[individual(1, 5.0, 1), individual(0, 9.0, 1)])
+            .await
+            .unwrap();
+        let rows = alice.get_ga_population().await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].fitness, 9.0);
+        assert!(rows[0].updated_at.is_some());
+
+        // A new generation replaces the whole population
+        alice
+            .save_ga_population(&[individual(0, 12.0, 2)])
+            .await
+            .unwrap();
+        let rows = alice.get_ga_population().await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!((rows[0].fitness, rows[0].generation), (12.0, 2));
+        assert!(bob.get_ga_population().await.unwrap().is_empty());
+    }
+}
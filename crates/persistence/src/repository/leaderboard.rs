@@ -6,6 +6,7 @@ use sqlx::{FromRow, SqlitePool};
 
 /// A persisted trader analysis record
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct LeaderboardTraderRecord {
     pub id: Option<i64>,
     pub proxy_wallet: String,
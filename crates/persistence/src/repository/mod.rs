@@ -1,11 +1,25 @@
 //! Repository implementations for database operations
 
+pub mod daily_pnl;
 pub mod discovery;
 pub mod leaderboard;
+pub mod optimization;
 pub mod orderbook;
+pub mod pipeline;
+pub mod preset;
 pub mod profile;
+pub mod risk;
+pub mod validation;
+pub mod work_queue;
 
+pub use daily_pnl::*;
 pub use discovery::*;
 pub use leaderboard::*;
+pub use optimization::*;
 pub use orderbook::*;
+pub use pipeline::*;
+pub use preset::*;
 pub use profile::*;
+pub use risk::*;
+pub use validation::*;
+pub use work_queue::*;
@@ -0,0 +1,191 @@
+//! Optimizations repository — ranked results of past parameter optimization sweeps
+
+use crate::DbResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// One ranked result of an optimization sweep
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OptimizationRecord {
+    pub id: Option<i64>,
+    /// Groups the results of one sweep (unix ms of its start)
+    pub run_id: String,
+    pub workspace: String,
+    /// Knowledge base record (`discovery_backtests.id`) the sweep started from
+    pub source_record_id: Option<i64>,
+    pub strategy: String,
+    pub symbol: String,
+    pub days: i64,
+    /// 1 = best of the sweep
+    pub rank: i64,
+    pub composite_score: f64,
+    /// Parameters of the result, as JSON
+    pub params: String,
+    pub net_pnl: f64,
+    pub gross_pnl: f64,
+    pub total_fees: f64,
+    pub win_rate: f64,
+    pub sharpe_ratio: f64,
+    pub max_drawdown_pct: f64,
+    pub profit_factor: f64,
+    pub total_trades: i64,
+    /// Gabagool only
+    pub hit_rate: Option<f64>,
+    /// Gabagool only
+    pub avg_locked_profit: Option<f64>,
+    /// Unix seconds, set on insert
+    pub created_at: Option<i64>,
+}
+
+/// Repository for optimization sweep results
+pub struct OptimizationRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> OptimizationRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Store the results of one sweep in a single transaction
+    pub async fn save_run(&self, records: &[OptimizationRecord]) -> DbResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for record in records {
+            sqlx::query(
+                r#"INSERT INTO optimizations
+                    (run_id, workspace, source_record_id, strategy, symbol, days, rank,
+                     composite_score, params, net_pnl, gross_pnl, total_fees, win_rate,
+                     sharpe_ratio, max_drawdown_pct, profit_factor, total_trades, hit_rate,
+                     avg_locked_profit)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
+                           ?16, ?17, ?18, ?19)"#,
+            )
+            .bind(&record.run_id)
+            .bind(&record.workspace)
+            .bind(record.source_record_id)
+            .bind(&record.strategy)
+            .bind(&record.symbol)
+            .bind(record.days)
+            .bind(record.rank)
+            .bind(record.composite_score)
+            .bind(&record.params)
+            .bind(record.net_pnl)
+            .bind(record.gross_pnl)
+            .bind(record.total_fees)
+            .bind(record.win_rate)
+            .bind(record.sharpe_ratio)
+            .bind(record.max_drawdown_pct)
+            .bind(record.profit_factor)
+            .bind(record.total_trades)
+            .bind(record.hit_rate)
+            .bind(record.avg_locked_profit)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Results of the `limit` latest sweeps of a workspace, newest sweep first and by
+    /// rank within a sweep. `source_record_id` keeps only the sweeps of that record.
+    pub async fn get_history(
+        &self,
+        workspace: &str,
+        source_record_id: Option<i64>,
+        limit: i64,
+    ) -> DbResult<Vec<OptimizationRecord>> {
+        let rows = sqlx::query_as::<_, OptimizationRecord>(
+            r#"
+            SELECT id, run_id, workspace, source_record_id, strategy, symbol, days, rank,
+                   composite_score, params, net_pnl, gross_pnl, total_fees, win_rate,
+                   sharpe_ratio, max_drawdown_pct, profit_factor, total_trades, hit_rate,
+                   avg_locked_profit, created_at
+            FROM optimizations
+            WHERE workspace = ?1
+              AND run_id IN (
+                  SELECT DISTINCT run_id FROM optimizations
+                  WHERE workspace = ?1 AND (?2 IS NULL OR source_record_id = ?2)
+                  ORDER BY run_id DESC
+                  LIMIT ?3
+              )
+            ORDER BY run_id DESC, rank ASC
+            "#,
+        )
+        .bind(workspace)
+        .bind(source_record_id)
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    fn record(run_id: &str, source_record_id: Option<i64>, rank: i64) -> OptimizationRecord {
+        OptimizationRecord {
+            id: None,
+            run_id: run_id.to_string(),
+            workspace: "default".to_string(),
+            source_record_id,
+            strategy: "RSI".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            days: 30,
+            rank,
+            composite_score: 10.0 / rank as f64,
+            params: r#"{"period":14}"#.to_string(),
+            net_pnl: 12.5,
+            gross_pnl: 14.0,
+            total_fees: 1.5,
+            win_rate: 55.0,
+            sharpe_ratio: 1.2,
+            max_drawdown_pct: 8.0,
+            profit_factor: 1.4,
+            total_trades: 40,
+            hit_rate: None,
+            avg_locked_profit: None,
+            created_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_optimization_history_by_sweep() {
+        let db = Database::in_memory().await.unwrap();
+        let repo = OptimizationRepository::new(db.pool());
+        repo.save_run(&[record("1000", Some(7), 1), record("1000", Some(7), 2)])
+            .await
+            .unwrap();
+        repo.save_run(&[record("2000", None, 1)]).await.unwrap();
+        repo.save_run(&[record("3000", Some(7), 1), record("3000", Some(7), 2)])
+            .await
+            .unwrap();
+
+        let runs_ranks = |rows: &[OptimizationRecord]| {
+            rows.iter()
+                .map(|r| (r.run_id.clone(), r.rank))
+                .collect::<Vec<_>>()
+        };
+        // Newest sweep first, by rank within a sweep; the limit counts sweeps
+        let latest = repo.get_history("default", None, 2).await.unwrap();
+        assert_eq!(
+            runs_ranks(&latest),
+            [("3000".into(), 1), ("3000".into(), 2), ("2000".into(), 1)]
+        );
+        assert!(latest[0].created_at.is_some());
+        assert_eq!(latest[0].params, r#"{"period":14}"#);
+
+        // Sweeps of one knowledge base record, and only the caller's workspace
+        let linked = repo.get_history("default", Some(7), 10).await.unwrap();
+        assert_eq!(linked.len(), 4);
+        assert!(linked.iter().all(|r| r.source_record_id == Some(7)));
+        assert!(repo
+            .get_history("other", None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -86,6 +86,7 @@ pub struct ObSnapshotRecord {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ObPatternRecord {
     pub id: Option<i64>,
     pub pattern_name: String,
@@ -111,6 +112,7 @@ pub struct ObPatternRecord {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ObMarketStats {
     pub total_markets: i64,
     pub fetched_markets: i64,
@@ -122,6 +124,7 @@ pub struct ObMarketStats {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DbSizeStats {
     pub ob_markets: i64,
     pub ob_market_prices: i64,
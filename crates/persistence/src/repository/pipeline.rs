@@ -0,0 +1,213 @@
+//! Strategy pipeline repository — promotion stage of knowledge base records
+//!
+//! Stages are stored as strings (the engine's `PipelineStage` owns the state machine).
+//! A record without a `strategy_pipeline` row is in the `discovered` stage.
+
+use super::discovery::{DiscoveryBacktestRecord, DEFAULT_WORKSPACE, RECORD_COLUMNS};
+use crate::DbResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Stage of records without a `strategy_pipeline` row
+pub const DISCOVERED_STAGE: &str = "discovered";
+
+/// Current stage of a record
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PipelineEntry {
+    pub backtest_id: i64,
+    pub stage: String,
+    pub reason: Option<String>,
+    pub updated_at: Option<i64>,
+}
+
+/// One stage change of a record
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PipelineTransitionRecord {
+    pub id: Option<i64>,
+    pub backtest_id: i64,
+    pub from_stage: String,
+    pub to_stage: String,
+    pub reason: Option<String>,
+    /// Applied by a pipeline rule rather than through the API
+    pub automatic: bool,
+    pub created_at: Option<i64>,
+}
+
+/// Number of knowledge base records in a stage
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StageCount {
+    pub stage: String,
+    pub count: i64,
+}
+
+/// Repository for the strategy promotion pipeline. The overview (`stage_counts`,
+/// `list_stage`) covers one workspace; promotion rules apply to every workspace.
+pub struct PipelineRepository<'a> {
+    pool: &'a SqlitePool,
+    workspace: &'a str,
+}
+
+impl<'a> PipelineRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self {
+            pool,
+            workspace: DEFAULT_WORKSPACE,
+        }
+    }
+
+    pub fn with_workspace(mut self, workspace: &'a str) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    /// Stage row of a record (`None` = discovered)
+    pub async fn get_stage(&self, backtest_id: i64) -> DbResult<Option<PipelineEntry>> {
+        let row = sqlx::query_as::<_, PipelineEntry>(
+            "SELECT backtest_id, stage, reason, updated_at FROM strategy_pipeline WHERE backtest_id = ?1",
+        )
+        .bind(backtest_id)
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Move a record to `to_stage` and log the transition, atomically
+    pub async fn transition(
+        &self,
+        backtest_id: i64,
+        from_stage: &str,
+        to_stage: &str,
+        reason: Option<&str>,
+        automatic: bool,
+    ) -> DbResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"INSERT INTO strategy_pipeline (backtest_id, stage, reason, updated_at)
+               VALUES (?1, ?2, ?3, strftime('%s', 'now'))
+               ON CONFLICT(backtest_id) DO UPDATE SET
+                 stage = excluded.stage,
+                 reason = excluded.reason,
+                 updated_at = excluded.updated_at"#,
+        )
+        .bind(backtest_id)
+        .bind(to_stage)
+        .bind(reason)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"INSERT INTO pipeline_transitions
+                (backtest_id, from_stage, to_stage, reason, automatic)
+               VALUES (?1, ?2, ?3, ?4, ?5)"#,
+        )
+        .bind(backtest_id)
+        .bind(from_stage)
+        .bind(to_stage)
+        .bind(reason)
+        .bind(automatic)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Stage changes of a record, oldest first
+    pub async fn get_transitions(
+        &self,
+        backtest_id: i64,
+    ) -> DbResult<Vec<PipelineTransitionRecord>> {
+        let rows = sqlx::query_as::<_, PipelineTransitionRecord>(
+            r#"
+            SELECT id, backtest_id, from_stage, to_stage, reason, automatic, created_at
+            FROM pipeline_transitions
+            WHERE backtest_id = ?1
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(backtest_id)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Knowledge base records per stage (stages without records are omitted)
+    pub async fn stage_counts(&self) -> DbResult<Vec<StageCount>> {
+        let rows = sqlx::query_as::<_, StageCount>(
+            r#"
+            SELECT COALESCE(p.stage, ?1) AS stage, COUNT(*) AS count
+            FROM discovery_backtests d
+            LEFT JOIN strategy_pipeline p ON p.backtest_id = d.id
+            WHERE d.workspace = ?2
+            GROUP BY 1
+            "#,
+        )
+        .bind(DISCOVERED_STAGE)
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Best records of a stage, by composite score
+    pub async fn list_stage(
+        &self,
+        stage: &str,
+        limit: i64,
+    ) -> DbResult<Vec<DiscoveryBacktestRecord>> {
+        let sql = format!(
+            r#"
+            SELECT {RECORD_COLUMNS}
+            FROM discovery_backtests d
+            LEFT JOIN strategy_pipeline p ON p.backtest_id = d.id
+            WHERE COALESCE(p.stage, ?2) = ?1 AND d.workspace = ?4
+            ORDER BY composite_score DESC
+            LIMIT ?3
+            "#
+        );
+        let rows = sqlx::query_as::<_, DiscoveryBacktestRecord>(&sql)
+            .bind(stage)
+            .bind(DISCOVERED_STAGE)
+            .bind(limit)
+            .bind(self.workspace)
+            .fetch_all(self.pool)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Discovered records that completed their backtest and meet the thresholds, best first
+    pub async fn promotion_candidates(
+        &self,
+        min_score: f64,
+        min_confidence: f64,
+        min_trades: i64,
+        limit: i64,
+    ) -> DbResult<Vec<DiscoveryBacktestRecord>> {
+        let sql = format!(
+            r#"
+            SELECT {RECORD_COLUMNS}
+            FROM discovery_backtests d
+            LEFT JOIN strategy_pipeline p ON p.backtest_id = d.id
+            WHERE p.backtest_id IS NULL
+              AND abort_reason IS NULL
+              AND composite_score >= ?1
+              AND COALESCE(strategy_confidence, 0) >= ?2
+              AND total_trades >= ?3
+            ORDER BY composite_score DESC
+            LIMIT ?4
+            "#
+        );
+        let rows = sqlx::query_as::<_, DiscoveryBacktestRecord>(&sql)
+            .bind(min_score)
+            .bind(min_confidence)
+            .bind(min_trades)
+            .bind(limit)
+            .fetch_all(self.pool)
+            .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,144 @@
+//! Discovery presets repository — named `DiscoveryRequest` templates
+
+use crate::repository::DEFAULT_WORKSPACE;
+use crate::DbResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// A saved discovery request; `request` is the JSON of the engine's `DiscoveryRequest`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DiscoveryPresetRecord {
+    pub name: String,
+    pub description: Option<String>,
+    pub request: String,
+    /// Unix seconds
+    pub created_at: Option<i64>,
+    /// Unix seconds of the last save
+    pub updated_at: Option<i64>,
+}
+
+/// Repository for the discovery presets of one workspace
+pub struct PresetRepository<'a> {
+    pool: &'a SqlitePool,
+    workspace: &'a str,
+}
+
+impl<'a> PresetRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self {
+            pool,
+            workspace: DEFAULT_WORKSPACE,
+        }
+    }
+
+    pub fn with_workspace(mut self, workspace: &'a str) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    /// Create or replace the preset `name` (its creation time is kept)
+    pub async fn save(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        request: &str,
+    ) -> DbResult<DiscoveryPresetRecord> {
+        sqlx::query(
+            r#"INSERT INTO discovery_presets (workspace, name, description, request)
+               VALUES (?1, ?2, ?3, ?4)
+               ON CONFLICT(workspace, name) DO UPDATE SET
+                 description = excluded.description,
+                 request = excluded.request,
+                 updated_at = strftime('%s', 'now')"#,
+        )
+        .bind(self.workspace)
+        .bind(name)
+        .bind(description)
+        .bind(request)
+        .execute(self.pool)
+        .await?;
+        Ok(self
+            .get(name)
+            .await?
+            .expect("preset exists after its upsert"))
+    }
+
+    pub async fn get(&self, name: &str) -> DbResult<Option<DiscoveryPresetRecord>> {
+        let row = sqlx::query_as::<_, DiscoveryPresetRecord>(
+            r#"
+            SELECT name, description, request, created_at, updated_at
+            FROM discovery_presets
+            WHERE workspace = ?1 AND name = ?2
+            "#,
+        )
+        .bind(self.workspace)
+        .bind(name)
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Presets of the workspace, by name
+    pub async fn list(&self) -> DbResult<Vec<DiscoveryPresetRecord>> {
+        let rows = sqlx::query_as::<_, DiscoveryPresetRecord>(
+            r#"
+            SELECT name, description, request, created_at, updated_at
+            FROM discovery_presets
+            WHERE workspace = ?1
+            ORDER BY name ASC
+            "#,
+        )
+        .bind(self.workspace)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Delete the preset `name`, returns whether it existed
+    pub async fn delete(&self, name: &str) -> DbResult<bool> {
+        let result =
+            sqlx::query("DELETE FROM discovery_presets WHERE workspace = ?1 AND name = ?2")
+                .bind(self.workspace)
+                .bind(name)
+                .execute(self.pool)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    #[tokio::test]
+    async fn test_presets_upsert_per_workspace() {
+        let db = Database::in_memory().await.unwrap();
+        let alice = PresetRepository::new(db.pool()).with_workspace("alice");
+        let bob = PresetRepository::new(db.pool()).with_workspace("bob");
+
+        alice.save("weekly", None, r#"{"days":7}"#).await.unwrap();
+        alice.save("alts", Some("altcoins"), "{}").await.unwrap();
+        let saved = alice
+            .save("weekly", Some("one week"), r#"{"days":14}"#)
+            .await
+            .unwrap();
+        assert_eq!(saved.request, r#"{"days":14}"#);
+        assert_eq!(saved.description.as_deref(), Some("one week"));
+        assert!(saved.created_at.is_some());
+
+        let names: Vec<String> = alice
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, ["alts", "weekly"]);
+        assert!(bob.get("weekly").await.unwrap().is_none());
+        assert!(!bob.delete("weekly").await.unwrap());
+        assert!(alice.delete("weekly").await.unwrap());
+        assert_eq!(alice.list().await.unwrap().len(), 1);
+    }
+}
@@ -6,6 +6,7 @@ use sqlx::{FromRow, SqlitePool};
 
 /// A persisted profile analysis record
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ProfileAnalysisRecord {
     pub id: Option<i64>,
     pub wallet: String,
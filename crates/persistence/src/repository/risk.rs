@@ -0,0 +1,147 @@
+//! Risk repository — paper-trading limits per strategy and the breaches they caught
+
+use crate::DbResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Risk limits of a knowledge base record (`None` = limit not enforced) and its halt state
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StrategyRiskLimits {
+    pub backtest_id: i64,
+    /// Realized loss over one UTC day, in USDC
+    pub max_daily_loss: Option<f64>,
+    /// Notional of open positions, in USDC
+    pub max_open_exposure: Option<f64>,
+    pub max_consecutive_losses: Option<i64>,
+    /// Unix seconds of the breach that halted the strategy (`None` = trading)
+    pub halted_at: Option<i64>,
+    pub halt_reason: Option<String>,
+    pub updated_at: Option<i64>,
+}
+
+/// One risk limit breach
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RiskEventRecord {
+    pub id: Option<i64>,
+    pub backtest_id: i64,
+    /// `daily_loss`, `open_exposure` or `consecutive_losses`
+    pub kind: String,
+    pub value: f64,
+    pub limit_value: f64,
+    pub message: String,
+    pub created_at: Option<i64>,
+}
+
+/// Repository for strategy risk limits and risk events
+pub struct RiskRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> RiskRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Limits of a record (`None` = no limits set)
+    pub async fn get_limits(&self, backtest_id: i64) -> DbResult<Option<StrategyRiskLimits>> {
+        let row = sqlx::query_as::<_, StrategyRiskLimits>(
+            r#"
+            SELECT backtest_id, max_daily_loss, max_open_exposure, max_consecutive_losses,
+                   halted_at, halt_reason, updated_at
+            FROM strategy_risk_limits
+            WHERE backtest_id = ?1
+            "#,
+        )
+        .bind(backtest_id)
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Create or replace the limits of a record; the halt state is left untouched
+    pub async fn save_limits(&self, limits: &StrategyRiskLimits) -> DbResult<()> {
+        sqlx::query(
+            r#"INSERT INTO strategy_risk_limits
+                (backtest_id, max_daily_loss, max_open_exposure, max_consecutive_losses, updated_at)
+               VALUES (?1, ?2, ?3, ?4, strftime('%s', 'now'))
+               ON CONFLICT(backtest_id) DO UPDATE SET
+                 max_daily_loss = excluded.max_daily_loss,
+                 max_open_exposure = excluded.max_open_exposure,
+                 max_consecutive_losses = excluded.max_consecutive_losses,
+                 updated_at = excluded.updated_at"#,
+        )
+        .bind(limits.backtest_id)
+        .bind(limits.max_daily_loss)
+        .bind(limits.max_open_exposure)
+        .bind(limits.max_consecutive_losses)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Log a breach and halt the record, atomically
+    pub async fn halt(&self, event: &RiskEventRecord) -> DbResult<i64> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            r#"INSERT INTO risk_events (backtest_id, kind, value, limit_value, message)
+               VALUES (?1, ?2, ?3, ?4, ?5)"#,
+        )
+        .bind(event.backtest_id)
+        .bind(&event.kind)
+        .bind(event.value)
+        .bind(event.limit_value)
+        .bind(&event.message)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"INSERT INTO strategy_risk_limits (backtest_id, halted_at, halt_reason, updated_at)
+               VALUES (?1, strftime('%s', 'now'), ?2, strftime('%s', 'now'))
+               ON CONFLICT(backtest_id) DO UPDATE SET
+                 halted_at = excluded.halted_at,
+                 halt_reason = excluded.halt_reason,
+                 updated_at = excluded.updated_at"#,
+        )
+        .bind(event.backtest_id)
+        .bind(&event.message)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Clear the halt of a record. Returns whether it was halted.
+    pub async fn resume(&self, backtest_id: i64) -> DbResult<bool> {
+        let result = sqlx::query(
+            r#"UPDATE strategy_risk_limits
+               SET halted_at = NULL, halt_reason = NULL, updated_at = strftime('%s', 'now')
+               WHERE backtest_id = ?1 AND halted_at IS NOT NULL"#,
+        )
+        .bind(backtest_id)
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Breaches of a record, newest first
+    pub async fn get_events(&self, backtest_id: i64, limit: i64) -> DbResult<Vec<RiskEventRecord>> {
+        let rows = sqlx::query_as::<_, RiskEventRecord>(
+            r#"
+            SELECT id, backtest_id, kind, value, limit_value, message, created_at
+            FROM risk_events
+            WHERE backtest_id = ?1
+            ORDER BY id DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(backtest_id)
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,75 @@
+//! Strategy validations repository — successive re-runs of knowledge base strategies
+
+use crate::DbResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Metrics of one re-run of a stored strategy (`discovery_backtests.id`) on fresh klines
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StrategyValidationRecord {
+    pub id: Option<i64>,
+    pub backtest_id: i64,
+    pub days: i64,
+    pub composite_score: f64,
+    pub net_pnl: f64,
+    pub win_rate: f64,
+    pub total_trades: i64,
+    pub sharpe_ratio: f64,
+    pub max_drawdown_pct: f64,
+    pub profit_factor: f64,
+    pub abort_reason: Option<String>,
+    /// Unix seconds, set on insert
+    pub validated_at: Option<i64>,
+}
+
+/// Repository for strategy validation runs
+pub struct ValidationRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> ValidationRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a validation run, returns its id
+    pub async fn save(&self, record: &StrategyValidationRecord) -> DbResult<i64> {
+        let result = sqlx::query(
+            r#"INSERT INTO strategy_validations
+                (backtest_id, days, composite_score, net_pnl, win_rate, total_trades,
+                 sharpe_ratio, max_drawdown_pct, profit_factor, abort_reason)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"#,
+        )
+        .bind(record.backtest_id)
+        .bind(record.days)
+        .bind(record.composite_score)
+        .bind(record.net_pnl)
+        .bind(record.win_rate)
+        .bind(record.total_trades)
+        .bind(record.sharpe_ratio)
+        .bind(record.max_drawdown_pct)
+        .bind(record.profit_factor)
+        .bind(&record.abort_reason)
+        .execute(self.pool)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Validations of a backtest, oldest first
+    pub async fn get_history(&self, backtest_id: i64) -> DbResult<Vec<StrategyValidationRecord>> {
+        let rows = sqlx::query_as::<_, StrategyValidationRecord>(
+            r#"
+            SELECT id, backtest_id, days, composite_score, net_pnl, win_rate, total_trades,
+                   sharpe_ratio, max_drawdown_pct, profit_factor, abort_reason, validated_at
+            FROM strategy_validations
+            WHERE backtest_id = ?1
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(backtest_id)
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,309 @@
+//! Work queue repository — chunks of offloaded work and their leases
+//!
+//! A chunk is `pending`, `claimed` by one worker until its lease expires, `done` with
+//! its result, or `failed` once it ran out of attempts. Chunks are keyed by
+//! `chunk_key`, so the same work queued again (a restarted scan) finds the rows, and
+//! the results, it left behind.
+
+use crate::DbResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+const COLUMNS: &str = "id, run_id, chunk_key, payload, status, worker_id, attempts, \
+                       lease_expires_at, result, error, created_at, updated_at";
+
+/// One chunk of the work queue; `payload` and `result` are JSON
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WorkItemRecord {
+    pub id: i64,
+    pub run_id: String,
+    pub chunk_key: String,
+    pub payload: String,
+    /// `pending`, `claimed`, `done` or `failed`
+    pub status: String,
+    /// Worker holding the lease, or the one that completed the chunk
+    pub worker_id: Option<String>,
+    pub attempts: i64,
+    /// Unix ms
+    pub lease_expires_at: Option<i64>,
+    pub result: Option<String>,
+    /// Last failure reported for the chunk
+    pub error: Option<String>,
+    /// Unix seconds
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+}
+
+/// Chunks of the queue by status
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkQueueCounts {
+    pub pending: i64,
+    pub claimed: i64,
+    pub done: i64,
+    pub failed: i64,
+}
+
+/// Repository for the work queue
+pub struct WorkQueueRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> WorkQueueRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Queue `(chunk_key, payload)` items for `run_id`, in order. A key already queued
+    /// keeps its row: a pending, claimed or done chunk is adopted by the run as is, a
+    /// failed one is queued again with fresh attempts.
+    pub async fn enqueue(
+        &self,
+        run_id: &str,
+        items: &[(String, String)],
+    ) -> DbResult<Vec<WorkItemRecord>> {
+        let mut tx = self.pool.begin().await?;
+        let mut rows = Vec::with_capacity(items.len());
+        for (key, payload) in items {
+            sqlx::query(
+                r#"INSERT INTO work_queue (run_id, chunk_key, payload)
+                   VALUES (?1, ?2, ?3)
+                   ON CONFLICT(chunk_key) DO UPDATE SET
+                     run_id = excluded.run_id,
+                     payload = excluded.payload,
+                     status = CASE WHEN status = 'failed' THEN 'pending' ELSE status END,
+                     attempts = CASE WHEN status = 'failed' THEN 0 ELSE attempts END,
+                     error = CASE WHEN status = 'failed' THEN NULL ELSE error END,
+                     updated_at = strftime('%s', 'now')"#,
+            )
+            .bind(run_id)
+            .bind(key)
+            .bind(payload)
+            .execute(&mut *tx)
+            .await?;
+            let row = sqlx::query_as::<_, WorkItemRecord>(&format!(
+                "SELECT {COLUMNS} FROM work_queue WHERE chunk_key = ?1"
+            ))
+            .bind(key)
+            .fetch_one(&mut *tx)
+            .await?;
+            rows.push(row);
+        }
+        tx.commit().await?;
+        Ok(rows)
+    }
+
+    /// Lease the oldest pending chunk to `worker_id` until `lease_expires_at` (unix ms)
+    pub async fn claim(
+        &self,
+        worker_id: &str,
+        lease_expires_at: i64,
+    ) -> DbResult<Option<WorkItemRecord>> {
+        let row = sqlx::query_as::<_, WorkItemRecord>(&format!(
+            r#"UPDATE work_queue SET
+                 status = 'claimed',
+                 worker_id = ?1,
+                 attempts = attempts + 1,
+                 lease_expires_at = ?2,
+                 updated_at = strftime('%s', 'now')
+               WHERE id = (SELECT id FROM work_queue WHERE status = 'pending'
+                           ORDER BY id LIMIT 1)
+               RETURNING {COLUMNS}"#
+        ))
+        .bind(worker_id)
+        .bind(lease_expires_at)
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Release the leases expired at `now` (unix ms): their chunks go back to pending,
+    /// or fail once leased `max_attempts` times. Returns how many were released.
+    pub async fn release_expired(&self, now: i64, max_attempts: i64) -> DbResult<u64> {
+        let result = sqlx::query(
+            r#"UPDATE work_queue SET
+                 status = CASE WHEN attempts >= ?2 THEN 'failed' ELSE 'pending' END,
+                 error = 'lease of ' || worker_id || ' expired',
+                 worker_id = NULL,
+                 lease_expires_at = NULL,
+                 updated_at = strftime('%s', 'now')
+               WHERE status = 'claimed' AND lease_expires_at <= ?1"#,
+        )
+        .bind(now)
+        .bind(max_attempts)
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Store the result of a chunk that is not done yet; false when it is done already
+    /// (by the worker it was reassigned to) or no longer queued
+    pub async fn complete(&self, id: i64, worker_id: &str, result: &str) -> DbResult<bool> {
+        let done = sqlx::query(
+            r#"UPDATE work_queue SET
+                 status = 'done',
+                 worker_id = ?2,
+                 result = ?3,
+                 lease_expires_at = NULL,
+                 updated_at = strftime('%s', 'now')
+               WHERE id = ?1 AND status IN ('pending', 'claimed')"#,
+        )
+        .bind(id)
+        .bind(worker_id)
+        .bind(result)
+        .execute(self.pool)
+        .await?;
+        Ok(done.rows_affected() > 0)
+    }
+
+    /// `worker_id` could not run its chunk: back to pending, or failed once leased
+    /// `max_attempts` times. False when the worker held no lease on it.
+    pub async fn fail(
+        &self,
+        id: i64,
+        worker_id: &str,
+        error: &str,
+        max_attempts: i64,
+    ) -> DbResult<bool> {
+        let result = sqlx::query(
+            r#"UPDATE work_queue SET
+                 status = CASE WHEN attempts >= ?4 THEN 'failed' ELSE 'pending' END,
+                 error = ?3,
+                 worker_id = NULL,
+                 lease_expires_at = NULL,
+                 updated_at = strftime('%s', 'now')
+               WHERE id = ?1 AND status = 'claimed' AND worker_id = ?2"#,
+        )
+        .bind(id)
+        .bind(worker_id)
+        .bind(error)
+        .bind(max_attempts)
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Chunks of the given ids, by id
+    pub async fn get_many(&self, ids: &[i64]) -> DbResult<Vec<WorkItemRecord>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = format!(
+            "SELECT {COLUMNS} FROM work_queue WHERE id IN ({}) ORDER BY id",
+            placeholders(ids.len())
+        );
+        let mut query = sqlx::query_as::<_, WorkItemRecord>(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(self.pool).await?)
+    }
+
+    /// Chunks leased right now
+    pub async fn claimed(&self) -> DbResult<Vec<WorkItemRecord>> {
+        let rows = sqlx::query_as::<_, WorkItemRecord>(&format!(
+            "SELECT {COLUMNS} FROM work_queue WHERE status = 'claimed' ORDER BY id"
+        ))
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Drop the chunks of `ids` that are not done; late results for them are refused
+    pub async fn cancel(&self, ids: &[i64]) -> DbResult<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let sql = format!(
+            "DELETE FROM work_queue WHERE status != 'done' AND id IN ({})",
+            placeholders(ids.len())
+        );
+        let mut query = sqlx::query(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        Ok(query.execute(self.pool).await?.rows_affected())
+    }
+
+    /// Delete the done and failed chunks not touched for `older_than_secs`
+    pub async fn purge_finished(&self, older_than_secs: i64) -> DbResult<u64> {
+        let result = sqlx::query(
+            r#"DELETE FROM work_queue
+               WHERE status IN ('done', 'failed')
+                 AND updated_at < strftime('%s', 'now') - ?1"#,
+        )
+        .bind(older_than_secs)
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn counts(&self) -> DbResult<WorkQueueCounts> {
+        let rows: Vec<(String, i64)> =
+            sqlx::query_as("SELECT status, COUNT(*) FROM work_queue GROUP BY status")
+                .fetch_all(self.pool)
+                .await?;
+        let mut counts = WorkQueueCounts::default();
+        for (status, n) in rows {
+            match status.as_str() {
+                "pending" => counts.pending = n,
+                "claimed" => counts.claimed = n,
+                "done" => counts.done = n,
+                _ => counts.failed += n,
+            }
+        }
+        Ok(counts)
+    }
+}
+
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    #[tokio::test]
+    async fn test_work_queue_leases_and_requeue() {
+        let db = Database::in_memory().await.unwrap();
+        let queue = WorkQueueRepository::new(db.pool());
+        let items = vec![
+            ("a".to_string(), "{\"n\":1}".to_string()),
+            ("b".to_string(), "{\"n\":2}".to_string()),
+        ];
+        let rows = queue.enqueue("run-1", &items).await.unwrap();
+        let (a, b) = (rows[0].id, rows[1].id);
+        assert!(rows.iter().all(|r| r.status == "pending"));
+
+        // The first lease expires and the chunk goes to the next worker
+        let lost = queue.claim("dead", 1_000).await.unwrap().unwrap();
+        assert_eq!((lost.id, lost.attempts), (a, 1));
+        assert_eq!(queue.release_expired(999, 3).await.unwrap(), 0);
+        assert_eq!(queue.release_expired(1_000, 3).await.unwrap(), 1);
+        let retry = queue.claim("live", 5_000).await.unwrap().unwrap();
+        assert_eq!((retry.id, retry.attempts), (a, 2));
+        assert!(queue.complete(a, "live", "[1]").await.unwrap());
+        assert!(!queue.complete(a, "dead", "[]").await.unwrap());
+
+        // A failure only counts from the lease holder; the last attempt fails the chunk
+        let chunk = queue.claim("live", 5_000).await.unwrap().unwrap();
+        assert_eq!(chunk.id, b);
+        assert!(!queue.fail(b, "dead", "boom", 1).await.unwrap());
+        assert!(queue.fail(b, "live", "boom", 1).await.unwrap());
+        assert!(queue.claim("live", 5_000).await.unwrap().is_none());
+        let counts = queue.counts().await.unwrap();
+        assert_eq!((counts.done, counts.failed), (1, 1));
+
+        // Queued again (a restarted run): the done chunk keeps its result, the failed
+        // one gets fresh attempts
+        let rows = queue.enqueue("run-2", &items).await.unwrap();
+        assert_eq!((rows[0].id, rows[0].status.as_str()), (a, "done"));
+        assert_eq!(rows[0].result.as_deref(), Some("[1]"));
+        assert_eq!((rows[1].status.as_str(), rows[1].attempts), ("pending", 0));
+        assert_eq!(queue.cancel(&[a, b]).await.unwrap(), 1);
+        let left = queue.get_many(&[a, b]).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].run_id, "run-2");
+    }
+}
@@ -1,7 +1,9 @@
 //! Database schema definitions
 
 /// SQL to create all tables
-/// NOTE: All prices/amounts stored as TEXT to preserve rust_decimal::Decimal precision
+/// NOTE: Metrics and amounts are stored as REAL so SQL sorts and filters are numeric;
+/// older databases with TEXT discovery metrics are rebuilt on startup (see
+/// `Database::migrate_discovery_metrics_to_real`)
 pub const CREATE_TABLES: &str = r#"
 -- Discovery backtest results (knowledge base)
 CREATE TABLE IF NOT EXISTS discovery_backtests (
@@ -13,40 +15,110 @@ CREATE TABLE IF NOT EXISTS discovery_backtests (
     symbol TEXT NOT NULL,
     days INTEGER NOT NULL,
     sizing_mode TEXT NOT NULL,
-    composite_score TEXT NOT NULL DEFAULT '0',
-    net_pnl TEXT NOT NULL DEFAULT '0',
-    gross_pnl TEXT NOT NULL DEFAULT '0',
-    total_fees TEXT NOT NULL DEFAULT '0',
-    win_rate TEXT NOT NULL DEFAULT '0',
+    composite_score REAL NOT NULL DEFAULT 0,
+    net_pnl REAL NOT NULL DEFAULT 0,
+    gross_pnl REAL NOT NULL DEFAULT 0,
+    total_fees REAL NOT NULL DEFAULT 0,
+    win_rate REAL NOT NULL DEFAULT 0,
     total_trades INTEGER NOT NULL DEFAULT 0,
-    sharpe_ratio TEXT NOT NULL DEFAULT '0',
-    max_drawdown_pct TEXT NOT NULL DEFAULT '0',
-    profit_factor TEXT NOT NULL DEFAULT '0',
-    avg_trade_pnl TEXT NOT NULL DEFAULT '0',
-    hit_rate TEXT,
-    avg_locked_profit TEXT,
+    sharpe_ratio REAL NOT NULL DEFAULT 0,
+    max_drawdown_pct REAL NOT NULL DEFAULT 0,
+    profit_factor REAL NOT NULL DEFAULT 0,
+    avg_trade_pnl REAL NOT NULL DEFAULT 0,
+    hit_rate REAL,
+    avg_locked_profit REAL,
     discovery_run_id TEXT,
     phase TEXT,
-    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+    created_at INTEGER DEFAULT (strftime('%s', 'now')),
+    sortino_ratio REAL DEFAULT 0,
+    max_consecutive_losses INTEGER DEFAULT 0,
+    avg_win_pnl REAL DEFAULT 0,
+    avg_loss_pnl REAL DEFAULT 0,
+    total_volume REAL DEFAULT 0,
+    annualized_return_pct REAL DEFAULT 0,
+    annualized_sharpe REAL DEFAULT 0,
+    strategy_confidence REAL DEFAULT 0,
+    schema_version INTEGER DEFAULT 0,
+    abort_reason TEXT,
+    workspace TEXT NOT NULL DEFAULT 'default',
+    probability_model TEXT,
+    drawdown_throttle TEXT,
+    cross_symbol_score REAL,
+    exit_breakdown TEXT,
+    decayed_score REAL,
+    score_30d REAL,
+    net_pnl_30d REAL,
+    win_rate_30d REAL,
+    trades_30d INTEGER,
+    score_90d REAL,
+    net_pnl_90d REAL,
+    win_rate_90d REAL,
+    trades_90d INTEGER,
+    pnl_hhi REAL,
+    top_week_pnl_share REAL,
+    active_weeks INTEGER,
+    longest_drawdown_days REAL,
+    avg_recovery_days REAL,
+    max_exposure_pct REAL,
+    avg_exposure_pct REAL,
+    warmup_bars INTEGER,
+    effective_days REAL,
+    engine_version TEXT,
+    logic_version INTEGER,
+    cost_attribution TEXT
+);
+
+-- Discovery rows whose strategy_params could not be upgraded to the current format
+CREATE TABLE IF NOT EXISTS discovery_quarantine (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    original_id INTEGER NOT NULL,
+    params_hash TEXT NOT NULL,
+    strategy_type TEXT NOT NULL,
+    strategy_name TEXT NOT NULL,
+    strategy_params TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    days INTEGER NOT NULL,
+    sizing_mode TEXT NOT NULL,
+    schema_version INTEGER,
+    reason TEXT NOT NULL,
+    quarantined_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Discovery grids fully covered in discovery_backtests, one row per
+-- (grid content, symbol, days, sizing_mode): a restart skips them with one lookup
+CREATE TABLE IF NOT EXISTS tested_grids (
+    grid_hash TEXT PRIMARY KEY,
+    grid_id TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    days INTEGER NOT NULL,
+    sizing_mode TEXT NOT NULL,
+    combinations INTEGER NOT NULL,
+    completed_at INTEGER DEFAULT (strftime('%s', 'now'))
 );
 
 -- ========== INDEXES ==========
 
 -- Discovery backtests indexes
 CREATE INDEX IF NOT EXISTS idx_discovery_hash ON discovery_backtests(params_hash);
-CREATE INDEX IF NOT EXISTS idx_discovery_strategy ON discovery_backtests(strategy_type, symbol);
 CREATE INDEX IF NOT EXISTS idx_discovery_score ON discovery_backtests(composite_score DESC);
 CREATE INDEX IF NOT EXISTS idx_discovery_run ON discovery_backtests(discovery_run_id);
 CREATE INDEX IF NOT EXISTS idx_discovery_name_trades ON discovery_backtests(strategy_name, total_trades);
 
--- Expression indexes for fast CAST-based sorts (critical with large datasets)
-CREATE INDEX IF NOT EXISTS idx_disc_composite_real ON discovery_backtests(CAST(composite_score AS REAL) DESC);
-CREATE INDEX IF NOT EXISTS idx_disc_win_rate_real ON discovery_backtests(CAST(win_rate AS REAL) DESC);
-CREATE INDEX IF NOT EXISTS idx_disc_net_pnl_real ON discovery_backtests(CAST(net_pnl AS REAL) DESC);
+-- Metric sort indexes (metrics are REAL, so sorts are numeric without CAST)
+CREATE INDEX IF NOT EXISTS idx_disc_win_rate ON discovery_backtests(win_rate DESC);
+CREATE INDEX IF NOT EXISTS idx_disc_net_pnl ON discovery_backtests(net_pnl DESC);
 -- Covering index for top-strategies CTE: PARTITION BY strategy_name ORDER BY win_rate
-CREATE INDEX IF NOT EXISTS idx_disc_name_winrate ON discovery_backtests(strategy_name, CAST(win_rate AS REAL) DESC) WHERE total_trades >= 5;
-CREATE INDEX IF NOT EXISTS idx_disc_name_pnl ON discovery_backtests(strategy_name, CAST(net_pnl AS REAL) DESC) WHERE total_trades >= 5;
-CREATE INDEX IF NOT EXISTS idx_disc_name_score ON discovery_backtests(strategy_name, CAST(composite_score AS REAL) DESC) WHERE total_trades >= 5;
+CREATE INDEX IF NOT EXISTS idx_disc_name_winrate ON discovery_backtests(strategy_name, win_rate DESC) WHERE total_trades >= 5;
+CREATE INDEX IF NOT EXISTS idx_disc_name_pnl ON discovery_backtests(strategy_name, net_pnl DESC) WHERE total_trades >= 5;
+CREATE INDEX IF NOT EXISTS idx_disc_name_score ON discovery_backtests(strategy_name, composite_score DESC) WHERE total_trades >= 5;
+-- Filtered pagination (/api/knowledge): equality filters first, sort column last, so
+-- `WHERE strategy_type = ? [AND symbol = ?] ORDER BY composite_score DESC LIMIT ?` walks
+-- the index in order instead of sorting every matching row
+CREATE INDEX IF NOT EXISTS idx_disc_type_symbol_score ON discovery_backtests(strategy_type, symbol, composite_score DESC);
+CREATE INDEX IF NOT EXISTS idx_disc_type_score ON discovery_backtests(strategy_type, composite_score DESC);
+CREATE INDEX IF NOT EXISTS idx_disc_symbol_score ON discovery_backtests(symbol, composite_score DESC);
+CREATE INDEX IF NOT EXISTS idx_disc_symbol_win_rate ON discovery_backtests(symbol, win_rate DESC);
+CREATE INDEX IF NOT EXISTS idx_disc_symbol_net_pnl ON discovery_backtests(symbol, net_pnl DESC);
 
 -- Leaderboard traders (persisted analysis results)
 CREATE TABLE IF NOT EXISTS leaderboard_traders (
@@ -144,6 +216,191 @@ CREATE INDEX IF NOT EXISTS idx_profile_trades_market ON profile_trades(wallet, c
 
 -- ========== ORDERBOOK BACKTEST TABLES ==========
 
+-- Re-runs of stored discovery strategies on fresh klines (drift tracking)
+CREATE TABLE IF NOT EXISTS strategy_validations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    backtest_id INTEGER NOT NULL,
+    days INTEGER NOT NULL,
+    composite_score REAL NOT NULL,
+    net_pnl REAL NOT NULL,
+    win_rate REAL NOT NULL,
+    total_trades INTEGER NOT NULL,
+    sharpe_ratio REAL NOT NULL,
+    max_drawdown_pct REAL NOT NULL,
+    profit_factor REAL NOT NULL,
+    abort_reason TEXT,
+    validated_at INTEGER DEFAULT (strftime('%s','now'))
+);
+CREATE INDEX IF NOT EXISTS idx_validations_backtest ON strategy_validations(backtest_id, id);
+
+-- Ranked results of parameter optimization sweeps, one row per result: `run_id` groups
+-- the rows of one sweep and `source_record_id` is the knowledge base record it started from.
+CREATE TABLE IF NOT EXISTS optimizations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id TEXT NOT NULL,
+    workspace TEXT NOT NULL DEFAULT 'default',
+    source_record_id INTEGER,
+    strategy TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    days INTEGER NOT NULL,
+    rank INTEGER NOT NULL,
+    composite_score REAL NOT NULL,
+    params TEXT NOT NULL,
+    net_pnl REAL NOT NULL,
+    gross_pnl REAL NOT NULL,
+    total_fees REAL NOT NULL,
+    win_rate REAL NOT NULL,
+    sharpe_ratio REAL NOT NULL,
+    max_drawdown_pct REAL NOT NULL,
+    profit_factor REAL NOT NULL,
+    total_trades INTEGER NOT NULL,
+    hit_rate REAL,
+    avg_locked_profit REAL,
+    created_at INTEGER DEFAULT (strftime('%s','now'))
+);
+CREATE INDEX IF NOT EXISTS idx_optimizations_run ON optimizations(workspace, run_id, rank);
+CREATE INDEX IF NOT EXISTS idx_optimizations_source ON optimizations(source_record_id, run_id);
+
+-- Promotion stage of knowledge base records (no row = discovered)
+CREATE TABLE IF NOT EXISTS strategy_pipeline (
+    backtest_id INTEGER PRIMARY KEY,
+    stage TEXT NOT NULL,
+    reason TEXT,
+    updated_at INTEGER DEFAULT (strftime('%s','now'))
+);
+CREATE INDEX IF NOT EXISTS idx_pipeline_stage ON strategy_pipeline(stage);
+
+-- Audit trail of pipeline stage changes
+CREATE TABLE IF NOT EXISTS pipeline_transitions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    backtest_id INTEGER NOT NULL,
+    from_stage TEXT NOT NULL,
+    to_stage TEXT NOT NULL,
+    reason TEXT,
+    automatic INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER DEFAULT (strftime('%s','now'))
+);
+CREATE INDEX IF NOT EXISTS idx_pipeline_transitions_backtest ON pipeline_transitions(backtest_id, id);
+
+-- Paper-trading risk limits per knowledge base record (NULL limit = not enforced)
+CREATE TABLE IF NOT EXISTS strategy_risk_limits (
+    backtest_id INTEGER PRIMARY KEY,
+    max_daily_loss REAL,
+    max_open_exposure REAL,
+    max_consecutive_losses INTEGER,
+    halted_at INTEGER,
+    halt_reason TEXT,
+    updated_at INTEGER DEFAULT (strftime('%s','now'))
+);
+
+-- Risk limit breaches (each one halts the strategy)
+CREATE TABLE IF NOT EXISTS risk_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    backtest_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    value REAL NOT NULL,
+    limit_value REAL NOT NULL,
+    message TEXT NOT NULL,
+    created_at INTEGER DEFAULT (strftime('%s','now'))
+);
+CREATE INDEX IF NOT EXISTS idx_risk_events_backtest ON risk_events(backtest_id, id);
+
+-- PnL per UTC calendar day of a knowledge base record's trades, by exit day, from a
+-- replay of its backtest (calendar heatmaps)
+CREATE TABLE IF NOT EXISTS daily_pnl (
+    backtest_id INTEGER NOT NULL,
+    day TEXT NOT NULL,
+    pnl REAL NOT NULL,
+    trades INTEGER NOT NULL,
+    winning_trades INTEGER NOT NULL,
+    PRIMARY KEY (backtest_id, day)
+);
+
+-- Data quality of the klines a discovery run fetched, per symbol
+CREATE TABLE IF NOT EXISTS kline_quality (
+    workspace TEXT NOT NULL DEFAULT 'default',
+    run_id TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    score REAL NOT NULL,
+    bars INTEGER NOT NULL,
+    expected_bars INTEGER NOT NULL,
+    missing_bars INTEGER NOT NULL,
+    gaps INTEGER NOT NULL,
+    duplicates INTEGER NOT NULL,
+    zero_volume_bars INTEGER NOT NULL,
+    spikes INTEGER NOT NULL,
+    repaired_bars INTEGER NOT NULL,
+    created_at INTEGER DEFAULT (strftime('%s','now')),
+    PRIMARY KEY (workspace, run_id, symbol)
+);
+
+-- One row per finished cycle of a continuous discovery run
+CREATE TABLE IF NOT EXISTS cycle_summaries (
+    workspace TEXT NOT NULL DEFAULT 'default',
+    run_id TEXT NOT NULL,
+    cycle INTEGER NOT NULL,
+    grid_size INTEGER NOT NULL,
+    combinations INTEGER NOT NULL,
+    new_results INTEGER NOT NULL,
+    best_score REAL,
+    duration_ms INTEGER NOT NULL,
+    throughput_per_sec REAL NOT NULL,
+    grid_snapshot_id TEXT,
+    created_at INTEGER DEFAULT (strftime('%s','now')),
+    PRIMARY KEY (workspace, run_id, cycle)
+);
+
+-- Panics of the task of a supervised discovery run, one row per crash (`restarts` is the
+-- number of restarts of the supervised session before this crash)
+CREATE TABLE IF NOT EXISTS run_crashes (
+    workspace TEXT NOT NULL DEFAULT 'default',
+    run_id TEXT NOT NULL,
+    restarts INTEGER NOT NULL,
+    crashed_at TEXT NOT NULL,
+    message TEXT NOT NULL,
+    restart_in_secs INTEGER,
+    created_at INTEGER DEFAULT (strftime('%s','now')),
+    PRIMARY KEY (workspace, run_id, restarts)
+);
+
+-- Strategy grids run by continuous discovery cycles (zstd-compressed JSON), stored once
+-- per content hash and shared by the cycles that ran the same grid
+CREATE TABLE IF NOT EXISTS grid_snapshots (
+    workspace TEXT NOT NULL DEFAULT 'default',
+    snapshot_id TEXT NOT NULL,
+    strategy_count INTEGER NOT NULL,
+    schema_version INTEGER NOT NULL,
+    grid BLOB NOT NULL,
+    created_at INTEGER DEFAULT (strftime('%s','now')),
+    PRIMARY KEY (workspace, snapshot_id)
+);
+
+-- Population of the evolutionary search of continuous discovery, fittest first
+CREATE TABLE IF NOT EXISTS ga_population (
+    workspace TEXT NOT NULL DEFAULT 'default',
+    rank INTEGER NOT NULL,
+    genome TEXT NOT NULL,
+    schema_version INTEGER NOT NULL,
+    strategy_name TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    fitness REAL NOT NULL,
+    generation INTEGER NOT NULL,
+    island TEXT NOT NULL DEFAULT '',
+    updated_at INTEGER DEFAULT (strftime('%s','now')),
+    PRIMARY KEY (workspace, rank)
+);
+
+-- Named DiscoveryRequest templates (JSON), per workspace
+CREATE TABLE IF NOT EXISTS discovery_presets (
+    workspace TEXT NOT NULL DEFAULT 'default',
+    name TEXT NOT NULL,
+    description TEXT,
+    request TEXT NOT NULL,
+    created_at INTEGER DEFAULT (strftime('%s','now')),
+    updated_at INTEGER DEFAULT (strftime('%s','now')),
+    PRIMARY KEY (workspace, name)
+);
+
 -- BTC 15-min markets discovered from Polymarket (permanent)
 CREATE TABLE IF NOT EXISTS ob_markets (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -250,6 +507,26 @@ CREATE TABLE IF NOT EXISTS ob_patterns (
     created_at INTEGER DEFAULT (strftime('%s','now'))
 );
 
+-- Chunks of offloaded work (Phase 1 chunks of the distributed mode): pending, claimed
+-- by a worker until `lease_expires_at` (unix ms), done with their results, or failed
+-- once out of attempts. `chunk_key` identifies the same work across runs and restarts.
+CREATE TABLE IF NOT EXISTS work_queue (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id TEXT NOT NULL,
+    chunk_key TEXT NOT NULL UNIQUE,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    worker_id TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    lease_expires_at INTEGER,
+    result TEXT,
+    error TEXT,
+    created_at INTEGER DEFAULT (strftime('%s','now')),
+    updated_at INTEGER DEFAULT (strftime('%s','now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_work_queue_status ON work_queue(status, id);
+
 -- Backtest process state (key-value store for incremental resume)
 CREATE TABLE IF NOT EXISTS ob_backtest_state (
     key TEXT PRIMARY KEY,
@@ -268,4 +545,140 @@ pub const MIGRATIONS: &[&str] = &[
     "ALTER TABLE discovery_backtests ADD COLUMN annualized_return_pct TEXT DEFAULT '0'",
     "ALTER TABLE discovery_backtests ADD COLUMN annualized_sharpe TEXT DEFAULT '0'",
     "ALTER TABLE discovery_backtests ADD COLUMN strategy_confidence TEXT DEFAULT '0'",
+    // Rows written before versioning keep schema_version = 0
+    "ALTER TABLE discovery_backtests ADD COLUMN schema_version INTEGER DEFAULT 0",
+    // Backtests stopped early by the ruin threshold or the time budget
+    "ALTER TABLE discovery_backtests ADD COLUMN abort_reason TEXT",
+    // Superseded by idx_disc_type_symbol_score (same prefix, plus the sort column)
+    "DROP INDEX IF EXISTS idx_discovery_strategy",
+    // Owner of the record; every knowledge query is scoped to one workspace
+    "ALTER TABLE discovery_backtests ADD COLUMN workspace TEXT NOT NULL DEFAULT 'default'",
+    // Probability model behind the fee estimates (NULL = linear)
+    "ALTER TABLE discovery_backtests ADD COLUMN probability_model TEXT",
+    // Drawdown throttle of throttle-search backtests, as JSON (NULL = none)
+    "ALTER TABLE discovery_backtests ADD COLUMN drawdown_throttle TEXT",
+    // Share of the other symbols of the run the same params were profitable on
+    "ALTER TABLE discovery_backtests ADD COLUMN cross_symbol_score REAL",
+    // Trades and PnL by exit reason, as JSON (NULL = not recorded)
+    "ALTER TABLE discovery_backtests ADD COLUMN exit_breakdown TEXT",
+    // Composite score of the time-decayed trades (NULL = no decay half-life)
+    "ALTER TABLE discovery_backtests ADD COLUMN decayed_score REAL",
+    // Score, net PnL, win rate and trades of the last 30 / 90 days of the backtest
+    "ALTER TABLE discovery_backtests ADD COLUMN score_30d REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN net_pnl_30d REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN win_rate_30d REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN trades_30d INTEGER",
+    "ALTER TABLE discovery_backtests ADD COLUMN score_90d REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN net_pnl_90d REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN win_rate_90d REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN trades_90d INTEGER",
+    // Concentration of the PnL over the weeks of the backtest (NULL = no PnL)
+    "ALTER TABLE discovery_backtests ADD COLUMN pnl_hhi REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN top_week_pnl_share REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN active_weeks INTEGER",
+    // Longest time under water and mean recovery time, in days (NULL = not measured)
+    "ALTER TABLE discovery_backtests ADD COLUMN longest_drawdown_days REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN avg_recovery_days REAL",
+    // Symbol of the island an individual of the evolutionary search lives on
+    "ALTER TABLE ga_population ADD COLUMN island TEXT NOT NULL DEFAULT ''",
+    // Largest and mean position value over equity, in % (NULL = not measured)
+    "ALTER TABLE discovery_backtests ADD COLUMN max_exposure_pct REAL",
+    "ALTER TABLE discovery_backtests ADD COLUMN avg_exposure_pct REAL",
+    // Warm-up bars held through and days tested after them (NULL = not recorded)
+    "ALTER TABLE discovery_backtests ADD COLUMN warmup_bars INTEGER",
+    "ALTER TABLE discovery_backtests ADD COLUMN effective_days REAL",
+    // Snapshot of the grid a continuous cycle ran (NULL = cycle recorded before snapshots)
+    "ALTER TABLE cycle_summaries ADD COLUMN grid_snapshot_id TEXT",
+    // Engine crate and backtest logic versions that computed the result (NULL = written
+    // before versioning, never reused as a cached result)
+    "ALTER TABLE discovery_backtests ADD COLUMN engine_version TEXT",
+    "ALTER TABLE discovery_backtests ADD COLUMN logic_version INTEGER",
+    // Signal PnL, timing cost, slippage and fees behind the net PnL, as JSON (NULL = not
+    // recorded)
+    "ALTER TABLE discovery_backtests ADD COLUMN cost_attribution TEXT",
+];
+
+/// All `discovery_backtests` columns, in table order (used by the TEXT → REAL rebuild)
+pub const DISCOVERY_BACKTESTS_COLUMNS: &[&str] = &[
+    "id",
+    "params_hash",
+    "strategy_type",
+    "strategy_name",
+    "strategy_params",
+    "symbol",
+    "days",
+    "sizing_mode",
+    "composite_score",
+    "net_pnl",
+    "gross_pnl",
+    "total_fees",
+    "win_rate",
+    "total_trades",
+    "sharpe_ratio",
+    "max_drawdown_pct",
+    "profit_factor",
+    "avg_trade_pnl",
+    "hit_rate",
+    "avg_locked_profit",
+    "discovery_run_id",
+    "phase",
+    "created_at",
+    "sortino_ratio",
+    "max_consecutive_losses",
+    "avg_win_pnl",
+    "avg_loss_pnl",
+    "total_volume",
+    "annualized_return_pct",
+    "annualized_sharpe",
+    "strategy_confidence",
+    "schema_version",
+    "abort_reason",
+    "workspace",
+    "probability_model",
+    "drawdown_throttle",
+    "cross_symbol_score",
+    "exit_breakdown",
+    "decayed_score",
+    "score_30d",
+    "net_pnl_30d",
+    "win_rate_30d",
+    "trades_30d",
+    "score_90d",
+    "net_pnl_90d",
+    "win_rate_90d",
+    "trades_90d",
+    "pnl_hhi",
+    "top_week_pnl_share",
+    "active_weeks",
+    "longest_drawdown_days",
+    "avg_recovery_days",
+    "max_exposure_pct",
+    "avg_exposure_pct",
+    "warmup_bars",
+    "effective_days",
+    "engine_version",
+    "logic_version",
+    "cost_attribution",
+];
+
+/// Metric columns of `discovery_backtests` stored as REAL (were TEXT in older databases)
+pub const DISCOVERY_METRIC_COLUMNS: &[&str] = &[
+    "composite_score",
+    "net_pnl",
+    "gross_pnl",
+    "total_fees",
+    "win_rate",
+    "sharpe_ratio",
+    "max_drawdown_pct",
+    "profit_factor",
+    "avg_trade_pnl",
+    "hit_rate",
+    "avg_locked_profit",
+    "sortino_ratio",
+    "avg_win_pnl",
+    "avg_loss_pnl",
+    "total_volume",
+    "annualized_return_pct",
+    "annualized_sharpe",
+    "strategy_confidence",
 ];
@@ -0,0 +1,284 @@
+//! Python bindings — `poly_discover` module
+//!
+//! Exposes the discovery backtester (same fees, sizing and composite score as the
+//! server), the signal generators and `DiscoveryStrategyType` (de)serialization.
+//! Build with `maturin develop --release` from this directory.
+//!
+//! Bars are passed as `(open_time_ms, open, high, low, close, volume)` tuples.
+
+// The `#[pymethods]` expansion converts `PyErr` into itself
+#![allow(clippy::useless_conversion)]
+
+use engine::{
+    decode_strategy_params, BacktestLimits, DiscoveryResult, DiscoveryStrategyType, Kline, Signal,
+    SignalGenerator, SizingMode, STRATEGY_PARAMS_VERSION,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+/// `(open_time_ms, open, high, low, close, volume)`
+type Bar = (i64, f64, f64, f64, f64, f64);
+
+/// Duration of one bar, used for `close_time`
+const BAR_MS: i64 = 15 * 60 * 1000;
+
+fn to_decimal(value: f64) -> Result<Decimal, String> {
+    Decimal::from_f64(value).ok_or_else(|| format!("not a finite price: {value}"))
+}
+
+fn to_klines(bars: &[Bar]) -> Result<Vec<Kline>, String> {
+    bars.iter()
+        .map(|&(open_time, open, high, low, close, volume)| {
+            Ok(Kline {
+                open_time,
+                open: to_decimal(open)?,
+                high: to_decimal(high)?,
+                low: to_decimal(low)?,
+                close: to_decimal(close)?,
+                volume: to_decimal(volume)?,
+                close_time: open_time + BAR_MS - 1,
+            })
+        })
+        .collect()
+}
+
+fn parse_sizing_mode(name: &str) -> Result<SizingMode, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| format!("unknown sizing mode '{name}' (fixed, kelly, confidence_weighted)"))
+}
+
+fn signal_name(signal: Signal) -> &'static str {
+    match signal {
+        Signal::Buy => "buy",
+        Signal::Sell => "sell",
+        Signal::Hold => "hold",
+    }
+}
+
+fn value_error(message: String) -> PyErr {
+    PyValueError::new_err(message)
+}
+
+fn result_dict<'py>(py: Python<'py>, r: &DiscoveryResult) -> PyResult<Bound<'py, PyDict>> {
+    let f = |d: Decimal| d.to_f64().unwrap_or(0.0);
+    let dict = PyDict::new_bound(py);
+    dict.set_item("strategy_name", &r.strategy_name)?;
+    dict.set_item(
+        "strategy_type",
+        serde_json::to_string(&r.strategy_type).map_err(|e| value_error(e.to_string()))?,
+    )?;
+    dict.set_item("symbol", &r.symbol)?;
+    dict.set_item("composite_score", f(r.composite_score))?;
+    dict.set_item("net_pnl", f(r.net_pnl))?;
+    dict.set_item("gross_pnl", f(r.gross_pnl))?;
+    dict.set_item("total_fees", f(r.total_fees))?;
+    dict.set_item("win_rate", f(r.win_rate))?;
+    dict.set_item("total_trades", r.total_trades)?;
+    dict.set_item("sharpe_ratio", f(r.sharpe_ratio))?;
+    dict.set_item("max_drawdown_pct", f(r.max_drawdown_pct))?;
+    dict.set_item("profit_factor", f(r.profit_factor))?;
+    dict.set_item("avg_trade_pnl", f(r.avg_trade_pnl))?;
+    dict.set_item("sortino_ratio", f(r.sortino_ratio))?;
+    dict.set_item("max_consecutive_losses", r.max_consecutive_losses)?;
+    dict.set_item("avg_win_pnl", f(r.avg_win_pnl))?;
+    dict.set_item("avg_loss_pnl", f(r.avg_loss_pnl))?;
+    dict.set_item("total_volume", f(r.total_volume))?;
+    dict.set_item("annualized_return_pct", f(r.annualized_return_pct))?;
+    dict.set_item("annualized_sharpe", f(r.annualized_sharpe))?;
+    dict.set_item("strategy_confidence", f(r.strategy_confidence))?;
+    dict.set_item("hit_rate", r.hit_rate.map(f))?;
+    dict.set_item("avg_locked_profit", r.avg_locked_profit.map(f))?;
+    dict.set_item("abort_reason", r.abort_reason.map(|a| a.as_str()))?;
+    dict.set_item("probability_model", &r.probability_model)?;
+    dict.set_item("drawdown_throttle", r.drawdown_throttle.map(|t| t.label()))?;
+    dict.set_item("cross_symbol_score", r.cross_symbol_score.map(f))?;
+    dict.set_item("decayed_score", r.decayed_score.map(f))?;
+    for (days, recent) in [(30, r.recent_30d), (90, r.recent_90d)] {
+        dict.set_item(format!("score_{days}d"), recent.map(|w| f(w.score)))?;
+        dict.set_item(format!("net_pnl_{days}d"), recent.map(|w| f(w.net_pnl)))?;
+        dict.set_item(format!("win_rate_{days}d"), recent.map(|w| f(w.win_rate)))?;
+        dict.set_item(format!("trades_{days}d"), recent.map(|w| w.total_trades))?;
+    }
+    let weeks = r.pnl_concentration;
+    dict.set_item("pnl_hhi", weeks.map(|c| c.hhi))?;
+    dict.set_item("top_week_pnl_share", weeks.map(|c| c.top_week_share))?;
+    dict.set_item("active_weeks", weeks.map(|c| c.active_weeks))?;
+    dict.set_item("longest_drawdown_days", r.longest_drawdown_days.map(f))?;
+    dict.set_item("avg_recovery_days", r.avg_recovery_days.map(f))?;
+    dict.set_item("max_exposure_pct", r.max_exposure_pct.map(f))?;
+    dict.set_item("avg_exposure_pct", r.avg_exposure_pct.map(f))?;
+    dict.set_item("warmup_bars", r.warmup_bars)?;
+    dict.set_item("effective_days", r.effective_days.map(f))?;
+    dict.set_item(
+        "exit_breakdown",
+        r.exit_breakdown
+            .map(|b| serde_json::to_string(&b))
+            .transpose()
+            .map_err(|e| value_error(e.to_string()))?,
+    )?;
+    dict.set_item(
+        "cost_attribution",
+        r.cost_attribution
+            .map(|c| serde_json::to_string(&c))
+            .transpose()
+            .map_err(|e| value_error(e.to_string()))?,
+    )?;
+    Ok(dict)
+}
+
+// ============================================================================
+// Strategy type
+// ============================================================================
+
+/// A strategy with its parameters, as stored in `strategy_params`
+#[pyclass(name = "DiscoveryStrategyType", module = "poly_discover")]
+#[derive(Clone)]
+struct PyStrategy {
+    inner: DiscoveryStrategyType,
+}
+
+#[pymethods]
+impl PyStrategy {
+    /// Decode a JSON strategy written with params schema `version` (default: current)
+    #[staticmethod]
+    #[pyo3(signature = (json, version = STRATEGY_PARAMS_VERSION))]
+    fn from_json(json: &str, version: i64) -> PyResult<Self> {
+        decode_strategy_params(json, version)
+            .map(|inner| Self { inner })
+            .map_err(value_error)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.inner).map_err(|e| value_error(e.to_string()))
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.inner.name().to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("DiscoveryStrategyType({})", self.inner.name())
+    }
+}
+
+// ============================================================================
+// Signal generator
+// ============================================================================
+
+/// Bar-by-bar signal generator of a strategy
+#[pyclass(name = "SignalGenerator", module = "poly_discover")]
+struct PySignalGenerator {
+    inner: Box<dyn SignalGenerator>,
+}
+
+#[pymethods]
+impl PySignalGenerator {
+    #[getter]
+    fn name(&self) -> String {
+        self.inner.name().to_string()
+    }
+
+    /// Feed one bar, returns `(signal, confidence)` with signal in buy / sell / hold
+    fn on_bar(&mut self, bar: Bar) -> PyResult<(&'static str, f64)> {
+        let kline = to_klines(&[bar]).map_err(value_error)?.remove(0);
+        let s = self.inner.on_bar(&kline);
+        Ok((signal_name(s.signal), s.confidence))
+    }
+
+    /// Reset, then return the signal of every bar
+    fn signals(&mut self, bars: Vec<Bar>) -> PyResult<Vec<(&'static str, f64)>> {
+        let klines = to_klines(&bars).map_err(value_error)?;
+        self.inner.reset();
+        Ok(klines
+            .iter()
+            .map(|k| {
+                let s = self.inner.on_bar(k);
+                (signal_name(s.signal), s.confidence)
+            })
+            .collect())
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[pyfunction]
+fn build_signal_generator(strategy: &PyStrategy) -> PyResult<PySignalGenerator> {
+    strategy.inner.validate().map_err(value_error)?;
+    Ok(PySignalGenerator {
+        inner: engine::build_signal_generator(&strategy.inner),
+    })
+}
+
+// ============================================================================
+// Backtest
+// ============================================================================
+
+/// Backtest a strategy on bars and return its metrics and composite score as a dict.
+/// `time_budget_ms` / `ruin_threshold_pct`: `None` = discovery default, `0` = off.
+#[pyfunction]
+#[pyo3(signature = (
+    strategy,
+    bars,
+    symbol = "BTCUSDT",
+    sizing_mode = "fixed",
+    time_budget_ms = None,
+    ruin_threshold_pct = None,
+))]
+fn run_backtest<'py>(
+    py: Python<'py>,
+    strategy: &PyStrategy,
+    bars: Vec<Bar>,
+    symbol: &str,
+    sizing_mode: &str,
+    time_budget_ms: Option<u64>,
+    ruin_threshold_pct: Option<f64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    strategy.inner.validate().map_err(value_error)?;
+    let klines = to_klines(&bars).map_err(value_error)?;
+    let sizing_mode = parse_sizing_mode(sizing_mode).map_err(value_error)?;
+    let ruin = ruin_threshold_pct
+        .map(to_decimal)
+        .transpose()
+        .map_err(value_error)?;
+    let limits = BacktestLimits::new(time_budget_ms, ruin);
+
+    let result = py.allow_threads(|| {
+        engine::run_backtest(&strategy.inner, &klines, symbol, sizing_mode, &limits)
+    });
+    result_dict(py, &result)
+}
+
+#[pymodule]
+fn poly_discover(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyStrategy>()?;
+    m.add_class::<PySignalGenerator>()?;
+    m.add_function(wrap_pyfunction!(build_signal_generator, m)?)?;
+    m.add_function(wrap_pyfunction!(run_backtest, m)?)?;
+    m.add("STRATEGY_PARAMS_VERSION", STRATEGY_PARAMS_VERSION)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_and_option_conversion() {
+        let klines = to_klines(&[(900_000, 100.0, 101.5, 99.0, 100.25, 12.0)]).unwrap();
+        assert_eq!(klines[0].close, Decimal::new(10025, 2));
+        assert_eq!(klines[0].close_time, 1_799_999);
+        assert!(to_klines(&[(0, f64::NAN, 1.0, 1.0, 1.0, 1.0)]).is_err());
+
+        assert!(matches!(
+            parse_sizing_mode("confidence_weighted"),
+            Ok(SizingMode::ConfidenceWeighted)
+        ));
+        assert!(parse_sizing_mode("martingale").is_err());
+    }
+}
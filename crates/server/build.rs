@@ -24,4 +24,12 @@ fn main() {
     // Rebuild when HEAD changes (new commit)
     println!("cargo:rerun-if-changed=../../.git/HEAD");
     println!("cargo:rerun-if-changed=../../.git/refs/heads/");
+
+    // gRPC service and client of proto/discovery.proto, built with the vendored protoc
+    #[cfg(feature = "grpc")]
+    {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("no vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::compile_protos("proto/discovery.proto").expect("proto/discovery.proto");
+    }
 }
@@ -0,0 +1,32 @@
+//! Web UI compiled into the binary (`embed-ui` feature)
+//!
+//! `npm run build` must have written dist/ before `cargo build --features embed-ui`;
+//! the files are then served from memory, like `ServeDir` serves dist/ otherwise
+//! (`index.html` for a directory path, 404 for an unknown file).
+
+use axum::http::{header, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use rust_embed::RustEmbed;
+
+#[derive(RustEmbed)]
+#[folder = "../../dist/"]
+#[allow_missing = true]
+struct WebUi;
+
+/// Fallback of the router: the embedded file at the request path
+pub async fn serve_asset(uri: Uri) -> Response {
+    let path = uri.path().trim_start_matches('/');
+    let path = if path.is_empty() || path.ends_with('/') {
+        format!("{}index.html", path)
+    } else {
+        path.to_string()
+    };
+    match WebUi::get(&path) {
+        Some(file) => (
+            [(header::CONTENT_TYPE, file.metadata.mimetype().to_string())],
+            file.data,
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
@@ -0,0 +1,201 @@
+//! Small TTL + LRU cache for hot knowledge-base reads (stats, top strategies)
+//!
+//! The dashboard polls these endpoints continuously; while continuous discovery is
+//! writing, each poll would otherwise run the same aggregate queries against SQLite.
+//! Entries are tagged with the knowledge base write generation and dropped once it
+//! moves on — but every entry is served for at least `min_age`, so a burst of writes
+//! doesn't turn the cache into a pass-through.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct CacheEntry {
+    value: serde_json::Value,
+    generation: u64,
+    inserted: Instant,
+    last_used: Instant,
+}
+
+/// Hit/miss counters exposed by `GET /api/admin/cache`
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    pub ttl_secs: u64,
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries dropped because the knowledge base changed
+    pub invalidations: u64,
+    pub evictions: u64,
+    pub hit_rate: f64,
+}
+
+pub struct QueryCache {
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    capacity: usize,
+    ttl: Duration,
+    min_age: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl QueryCache {
+    pub fn new(capacity: usize, ttl: Duration, min_age: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            capacity: capacity.max(1),
+            ttl,
+            min_age: min_age.min(ttl),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Cached value for `key`, if still fresh for the current write `generation`
+    pub fn get(&self, key: &str, generation: u64) -> Option<serde_json::Value> {
+        self.get_at(key, generation, Instant::now())
+    }
+
+    fn get_at(&self, key: &str, generation: u64, now: Instant) -> Option<serde_json::Value> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let (fresh, stale_by_write) = match entries.get(key) {
+            Some(entry) => {
+                let age = now.saturating_duration_since(entry.inserted);
+                let unchanged = entry.generation == generation || age < self.min_age;
+                (age < self.ttl && unchanged, age < self.ttl && !unchanged)
+            }
+            None => (false, false),
+        };
+
+        if fresh {
+            let entry = entries.get_mut(key).expect("entry checked above");
+            entry.last_used = now;
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(entry.value.clone());
+        }
+
+        if entries.remove(key).is_some() && stale_by_write {
+            self.invalidations.fetch_add(1, Ordering::Relaxed);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Store `value` for `key`, evicting the least recently used entry when full
+    pub fn insert(&self, key: String, value: serde_json::Value, generation: u64) {
+        self.insert_at(key, value, generation, Instant::now());
+    }
+
+    fn insert_at(&self, key: String, value: serde_json::Value, generation: u64, now: Instant) {
+        let mut entries = self.entries.lock().unwrap();
+
+        if !entries.contains_key(&key) && entries.len() >= self.capacity {
+            let lru = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(lru) = lru {
+                entries.remove(&lru);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        entries.insert(
+            key,
+            CacheEntry {
+                value,
+                generation,
+                inserted: now,
+                last_used: now,
+            },
+        );
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        CacheStats {
+            entries: self.entries.lock().unwrap().len(),
+            capacity: self.capacity,
+            ttl_secs: self.ttl.as_secs(),
+            hits,
+            misses,
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            hit_rate: if lookups > 0 {
+                hits as f64 / lookups as f64
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cache() -> QueryCache {
+        QueryCache::new(2, Duration::from_secs(10), Duration::from_secs(1))
+    }
+
+    #[test]
+    fn test_hit_miss_and_ttl() {
+        let c = cache();
+        let t0 = Instant::now();
+        assert!(c.get_at("stats", 0, t0).is_none());
+
+        c.insert_at("stats".into(), json!(1), 0, t0);
+        assert_eq!(
+            c.get_at("stats", 0, t0 + Duration::from_secs(5)),
+            Some(json!(1))
+        );
+        // Expired
+        assert!(c.get_at("stats", 0, t0 + Duration::from_secs(11)).is_none());
+
+        let stats = c.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 0));
+    }
+
+    #[test]
+    fn test_write_generation_invalidates_after_min_age() {
+        let c = cache();
+        let t0 = Instant::now();
+        c.insert_at("top".into(), json!("a"), 3, t0);
+
+        // A write inside the min_age window is tolerated...
+        assert!(c
+            .get_at("top", 4, t0 + Duration::from_millis(500))
+            .is_some());
+        // ...but not after it
+        assert!(c.get_at("top", 4, t0 + Duration::from_secs(2)).is_none());
+        assert_eq!(c.stats().invalidations, 1);
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let c = cache();
+        let t0 = Instant::now();
+        c.insert_at("a".into(), json!(1), 0, t0);
+        c.insert_at("b".into(), json!(2), 0, t0 + Duration::from_millis(1));
+        // Touch "a" so "b" becomes least recently used
+        assert!(c.get_at("a", 0, t0 + Duration::from_millis(2)).is_some());
+        c.insert_at("c".into(), json!(3), 0, t0 + Duration::from_millis(3));
+
+        let later = t0 + Duration::from_millis(4);
+        assert!(c.get_at("a", 0, later).is_some());
+        assert!(c.get_at("b", 0, later).is_none());
+        assert!(c.get_at("c", 0, later).is_some());
+        assert_eq!(c.stats().evictions, 1);
+    }
+}
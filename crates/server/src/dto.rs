@@ -0,0 +1,855 @@
+//! Typed request / response bodies of the HTTP API
+//!
+//! Every JSON handler returns an `ApiResult<T>`. Under `/api/v1` the body is the
+//! envelope as serialized here:
+//!
+//! ```text
+//! 200  { "success": true,  "data": <T>, "meta": { "total": 42, ... } }
+//! 4xx  { "success": false, "error": { "code": "already_running", "message": "..." } }
+//! ```
+//!
+//! The unversioned `/api/...` routes are aliases kept while clients migrate: the same
+//! handlers run, and `legacy_envelope` flattens the envelope back to the pre-v1 shapes
+//! (`data` / `meta` fields at the top level, `error` as a plain string).
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use engine::{
+    BackupReport, CostAttribution, CycleGrid, DataQualityReport, DetectedPattern, DiscoveryRequest,
+    DiscoveryResult, DiscoveryStatus, EngineError, ExitBreakdown, FailedCombo, GabagoolOpportunity,
+    GabagoolScannerConfig, GridChunk, IslandStats, Kline, KlineDiff, LeaderboardStatus,
+    MaintenanceReport, ObBacktestStats, OptimizeStatus, OptimizeStrategy, PipelineStage,
+    ProfileAnalysis, ProgressBreakdown, RunCrash, ScanPacing, ScannerStatus, ScoredResult,
+    TradeAlert, TraderAnalysis, WatcherStatus,
+};
+use engine::{NeighborhoodSummary, Similarity};
+use persistence::repository::{
+    DiscoveryBacktestRecord, PipelineTransitionRecord, RiskEventRecord, StageCount,
+    StrategyRiskLimits,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::cache::CacheStats;
+
+// ============================================================================
+// Envelope
+// ============================================================================
+
+pub type ApiResult<T> = Result<ApiResponse<T>, ApiError>;
+
+/// Successful response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiResponse<T> {
+    /// Always `true`
+    pub success: bool,
+    pub data: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PageMeta>,
+}
+
+/// Paging information of list responses
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct PageMeta {
+    /// Rows matching the query (offset pages and full lists)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+    /// Pass as `cursor` to fetch the next keyset page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Ranking keys applied, in order (e.g. `score,sharpe,trades`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank_by: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn ok(data: T) -> Self {
+        Self {
+            success: true,
+            data,
+            meta: None,
+        }
+    }
+
+    pub fn with_meta(mut self, meta: PageMeta) -> Self {
+        self.meta = Some(meta);
+        self
+    }
+}
+
+impl<T> ApiResponse<Vec<T>> {
+    /// Full list, `meta.total` = its length
+    pub fn list(items: Vec<T>) -> Self {
+        let total = items.len() as i64;
+        Self::ok(items).with_meta(PageMeta {
+            total: Some(total),
+            ..Default::default()
+        })
+    }
+}
+
+/// Marks the responses `legacy_envelope` may rewrite (not NDJSON, not the OpenAPI doc)
+#[derive(Debug, Clone, Copy)]
+struct Enveloped;
+
+impl<T: Serialize> IntoResponse for ApiResponse<T> {
+    fn into_response(self) -> Response {
+        let mut response = Json(self).into_response();
+        response.extensions_mut().insert(Enveloped);
+        response
+    }
+}
+
+/// Machine-readable error kinds, each mapped to one HTTP status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// 400 — bad parameter or body
+    InvalidRequest,
+    /// 403 — mutating endpoint of a `--read-only` server
+    ReadOnly,
+    /// 404 — unknown id
+    NotFound,
+    /// 409 — the background job is already running
+    AlreadyRunning,
+    /// 409 — the strategy is halted by a risk limit
+    Halted,
+    /// 409 — the run was cancelled before the end
+    Cancelled,
+    /// 422 — too little market data for the request
+    InsufficientData,
+    /// 502 — Binance or Polymarket call failed
+    Upstream,
+    /// 500 — SQLite query failed
+    Database,
+    /// 500 — anything else
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
+            ErrorCode::ReadOnly => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::AlreadyRunning | ErrorCode::Halted | ErrorCode::Cancelled => {
+                StatusCode::CONFLICT
+            }
+            ErrorCode::InsufficientData => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::Upstream => StatusCode::BAD_GATEWAY,
+            ErrorCode::Database | ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Failed response, `error` field of the envelope
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidRequest, message)
+    }
+
+    pub fn read_only() -> Self {
+        Self::new(
+            ErrorCode::ReadOnly,
+            "This server is read-only; scans and changes run on the writing instance",
+        )
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn already_running(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::AlreadyRunning, message)
+    }
+
+    pub fn halted(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Halted, message)
+    }
+
+    pub fn upstream(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Upstream, message)
+    }
+
+    pub fn database(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Database, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl From<EngineError> for ApiError {
+    fn from(e: EngineError) -> Self {
+        let code = match &e {
+            EngineError::DataFetch(_) => ErrorCode::Upstream,
+            EngineError::InsufficientData(_) => ErrorCode::InsufficientData,
+            EngineError::InvalidParams(_) => ErrorCode::InvalidRequest,
+            EngineError::Storage(_) => ErrorCode::Database,
+            EngineError::Cancelled => ErrorCode::Cancelled,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
+/// Body of the 4xx / 5xx responses
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Always `false`
+    pub success: bool,
+    pub error: ApiError,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.code.status();
+        let body = ErrorResponse {
+            success: false,
+            error: self,
+        };
+        let mut response = (status, Json(body)).into_response();
+        response.extensions_mut().insert(Enveloped);
+        response
+    }
+}
+
+// ============================================================================
+// Legacy (unversioned) routes
+// ============================================================================
+
+/// Response mapper of the `/api/...` aliases: rewrites v1 envelopes to the pre-v1 shapes
+pub async fn legacy_envelope(response: Response) -> Response {
+    if response.extensions().get::<Enveloped>().is_none() {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let legacy = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(envelope) => serde_json::to_vec(&to_legacy(envelope)).unwrap_or_default(),
+        Err(_) => bytes.to_vec(),
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(legacy))
+}
+
+/// `{ success, data: {..}, meta }` → `{ success, ..data, ..meta }`,
+/// `{ success, data: [..], meta }` → `{ success, data: [..], ..meta }`,
+/// `{ success: false, error: { code, message } }` → `{ success: false, error: message, message }`
+fn to_legacy(envelope: Value) -> Value {
+    let Value::Object(mut envelope) = envelope else {
+        return envelope;
+    };
+    let mut legacy = serde_json::Map::new();
+
+    if let Some(error) = envelope.remove("error") {
+        let message = error.get("message").cloned().unwrap_or(Value::Null);
+        legacy.insert("success".to_string(), Value::Bool(false));
+        legacy.insert("error".to_string(), message.clone());
+        legacy.insert("message".to_string(), message);
+        return Value::Object(legacy);
+    }
+
+    legacy.insert("success".to_string(), Value::Bool(true));
+    match envelope.remove("data") {
+        Some(Value::Object(fields)) => legacy.extend(fields),
+        Some(data) => {
+            legacy.insert("data".to_string(), data);
+        }
+        None => {}
+    }
+    if let Some(Value::Object(meta)) = envelope.remove("meta") {
+        legacy.extend(meta);
+    }
+    Value::Object(legacy)
+}
+
+// ============================================================================
+// Payloads — system / acknowledgements
+// ============================================================================
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthResponse {
+    /// Always `ok`
+    pub status: &'static str,
+    pub service: &'static str,
+    pub version: &'static str,
+    /// `serve --read-only`: the dashboard hides scan and edit controls
+    pub read_only: bool,
+}
+
+/// Start / stop / cancel acknowledgement
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Ack {
+    pub message: String,
+}
+
+impl Ack {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiscoveryStarted {
+    pub message: String,
+    pub continuous: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OptimizationStarted {
+    pub message: String,
+    pub total_combinations: u32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MaintenanceStarted {
+    pub message: String,
+    /// Results kept per strategy
+    pub keep: i64,
+}
+
+// ============================================================================
+// Payloads — discovery / knowledge base
+// ============================================================================
+
+/// Progress of the running (or last) discovery
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiscoveryStatusResponse {
+    pub status: DiscoveryStatus,
+    pub phase: String,
+    pub current_strategy: String,
+    pub current_symbol: String,
+    pub progress_pct: f32,
+    pub completed: u32,
+    /// Combinations answered from the knowledge base
+    pub skipped: u32,
+    pub total: u32,
+    pub throughput_per_sec: f64,
+    pub eta_secs: Option<u64>,
+    /// RFC 3339
+    pub eta_at: Option<String>,
+    pub best_so_far: Vec<DiscoveryResult>,
+    /// `final_results` once complete, `best_so_far` before
+    pub results: Vec<DiscoveryResult>,
+    pub error: Option<String>,
+    pub started_at: Option<String>,
+    pub current_cycle: u32,
+    pub total_tested_all_cycles: u32,
+    pub total_new_this_cycle: u32,
+    pub is_continuous: bool,
+    /// Held by `POST /discover/pause` (the status keeps the running phase)
+    pub paused: bool,
+    /// Speed limits in effect (request's `pacing`, or `PUT /discover/pacing`)
+    pub pacing: ScanPacing,
+    /// Ranking keys of `best_so_far` and the final results, in order
+    pub rank_by: String,
+    /// Kline quality of each fetched symbol
+    pub data_quality: Vec<DataQualityReport>,
+    /// Completed / planned combinations per symbol and per strategy family (running
+    /// phase, or current cycle in continuous mode)
+    pub breakdown: ProgressBreakdown,
+    /// Island of each symbol of the evolutionary search (continuous cycles 3+)
+    pub islands: Vec<IslandStats>,
+    /// Panics of the run's task, oldest first (status `restarting` while a restart
+    /// is planned); those of the last session are reloaded from `run_crashes` when the
+    /// server starts
+    pub crashes: Vec<RunCrash>,
+    /// Combinations skipped because their backtest panicked (counted in `completed`)
+    pub failed: u32,
+    /// The first 100 of them, with the panic message
+    pub failed_combos: Vec<FailedCombo>,
+}
+
+/// Chunk leased to a worker, `None` when nothing is queued
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkerLease {
+    pub chunk: Option<GridChunk>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChunkAccepted {
+    /// False when the chunk was already completed by the worker it was reassigned to,
+    /// or its run is over: the results are dropped
+    pub accepted: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KnowledgeStatsResponse {
+    pub stats: persistence::repository::discovery::KnowledgeBaseStats,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExportResponse {
+    /// RFC 3339
+    pub generated_at: String,
+    pub total_backtests_in_db: i64,
+    pub export_filters: ExportFilters,
+    pub results: Vec<ExportedResult>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExportFilters {
+    pub top_n: usize,
+    pub min_win_rate: Option<f64>,
+    pub min_recent_win_rate: Option<f64>,
+    pub max_pnl_hhi: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExportedResult {
+    pub rank: usize,
+    pub strategy_name: String,
+    pub strategy_type: String,
+    /// Decoded `strategy_params`
+    #[schema(value_type = Object)]
+    pub params: Value,
+    pub symbol: String,
+    pub metrics: ExportedMetrics,
+    pub recommendation: &'static str,
+    /// Equity and drawdown chart of the record (none for strategies without one)
+    pub chart_url: Option<String>,
+    /// Rules, sizing and fees in plain words, one line each (none for undecodable params)
+    pub explanation: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExportedMetrics {
+    pub composite_score: f64,
+    /// Score with recent trades weighted more (runs with a decay half-life only)
+    pub decayed_score: Option<f64>,
+    /// Score over the last 30 / 90 days of the backtest
+    pub score_30d: Option<f64>,
+    pub score_90d: Option<f64>,
+    /// Concentration of the PnL over weeks (HHI, 0-1) and share of the biggest week
+    pub pnl_hhi: Option<f64>,
+    pub top_week_pnl_share: Option<f64>,
+    /// Longest time under water and mean time back to a new peak, in days
+    pub longest_drawdown_days: Option<f64>,
+    pub avg_recovery_days: Option<f64>,
+    /// Largest and mean position value over equity, in %
+    pub max_exposure_pct: Option<f64>,
+    pub avg_exposure_pct: Option<f64>,
+    /// Bars held through while the indicators warmed up, and days tested after them
+    pub warmup_bars: Option<i64>,
+    pub effective_days: Option<f64>,
+    pub net_pnl: f64,
+    pub win_rate: f64,
+    pub sharpe_ratio: f64,
+    pub max_drawdown_pct: f64,
+    pub profit_factor: f64,
+    pub total_trades: i64,
+    pub sortino_ratio: Option<f64>,
+    pub max_consecutive_losses: Option<i64>,
+    pub avg_win_pnl: Option<f64>,
+    pub avg_loss_pnl: Option<f64>,
+    pub total_volume: Option<f64>,
+    pub annualized_return_pct: Option<f64>,
+    pub annualized_sharpe: Option<f64>,
+    pub strategy_confidence: Option<f64>,
+}
+
+// ============================================================================
+// Payloads — optimizer / market data
+// ============================================================================
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OptimizeStatusResponse {
+    #[schema(value_type = String)]
+    pub status: OptimizeStatus,
+    pub strategy: Option<OptimizeStrategy>,
+    pub progress_pct: f32,
+    pub completed: u32,
+    pub total: u32,
+    #[schema(value_type = Vec<Object>)]
+    pub results: Vec<ScoredResult>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KlinesResponse {
+    pub symbol: String,
+    pub interval: String,
+    pub count: usize,
+    /// `{ open_time, open, high, low, close, volume, close_time }`, prices as strings
+    #[schema(value_type = Vec<Object>)]
+    pub klines: Vec<Kline>,
+}
+
+/// Cached klines of a symbol against a fresh Binance fetch of the same window
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KlineDiffResponse {
+    pub symbol: String,
+    pub interval: String,
+    /// Open times compared, Unix ms (inclusive)
+    pub from: i64,
+    pub to: i64,
+    pub diff: KlineDiff,
+}
+
+// ============================================================================
+// Payloads — leaderboard / watcher / gabagool
+// ============================================================================
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LeaderboardStatusResponse {
+    #[schema(value_type = String)]
+    pub status: LeaderboardStatus,
+    pub total_traders: u32,
+    pub analyzed: u32,
+    pub progress_pct: f64,
+    pub current_trader: String,
+    #[schema(value_type = Vec<Object>)]
+    pub results: Vec<TraderAnalysis>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WatcherStatusResponse {
+    #[schema(value_type = String)]
+    pub status: WatcherStatus,
+    pub watched_count: usize,
+    #[schema(value_type = Vec<Object>)]
+    pub alerts: Vec<TradeAlert>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GabagoolOpportunitiesResponse {
+    #[schema(value_type = String)]
+    pub status: ScannerStatus,
+    pub config: GabagoolScannerConfig,
+    pub scans: u32,
+    pub markets_scanned: u32,
+    /// Unix ms
+    pub last_scan_at: Option<i64>,
+    /// Opportunities seen on the last scan
+    #[schema(value_type = Vec<Object>)]
+    pub opportunities: Vec<GabagoolOpportunity>,
+    /// Recent opportunity events, newest last
+    #[schema(value_type = Vec<Object>)]
+    pub events: Vec<GabagoolOpportunity>,
+    pub error: Option<String>,
+}
+
+// ============================================================================
+// Payloads — profile / orderbook / admin
+// ============================================================================
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProfileStatusResponse {
+    pub status: String,
+    pub completed_steps: u32,
+    pub total_steps: u32,
+    pub current_step: String,
+    pub username: String,
+    pub wallet: String,
+    pub running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Full analysis once complete
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub result: Option<ProfileAnalysis>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ObBacktestStatusResponse {
+    pub status: String,
+    pub data_source: String,
+    pub running: bool,
+    pub current_step: String,
+    pub total_markets: u32,
+    pub markets_discovered: u32,
+    pub markets_fetched: u32,
+    pub features_extracted: u32,
+    pub patterns_found: u32,
+    #[schema(value_type = Object)]
+    pub stats: ObBacktestStats,
+    pub logs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub best_patterns: Option<Vec<DetectedPattern>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Resume counters from the database, only when not running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_state: Option<ObDbState>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ObDbState {
+    pub total_markets: i64,
+    pub unfetched: i64,
+    pub fetched: i64,
+    pub extracted: i64,
+    pub patterns: i64,
+    pub last_step: Option<String>,
+    pub data_source: Option<String>,
+    pub last_run_timestamp: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ObStatsResponse {
+    pub market_stats: persistence::repository::orderbook::ObMarketStats,
+    pub db_size: persistence::repository::orderbook::DbSizeStats,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollectorStatusResponse {
+    pub status: String,
+    pub running: bool,
+    pub markets_watched: u32,
+    pub snapshots_recorded: u32,
+    pub current_market: String,
+    pub last_snapshot_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Counters depend on `mode`: `total_reset` (refetch), `total_deleted` (full),
+/// `prices_purged` + `snapshots_purged` (partial)
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct CleanupResponse {
+    pub mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_reset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_deleted: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prices_purged: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshots_purged: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MaintenanceStatusResponse {
+    pub status: String,
+    pub completed_steps: u32,
+    pub total_steps: u32,
+    pub current_step: String,
+    /// `manual` or `scheduled`
+    pub trigger: String,
+    pub running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Report of the last run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub last_report: Option<MaintenanceReport>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackupResponse {
+    /// Snapshot path, sizes and duration
+    #[schema(value_type = Object)]
+    pub backup: BackupReport,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CacheStatsResponse {
+    pub cache: CacheStats,
+    pub write_generation: u64,
+}
+
+/// Records per stage, plus the best records of the requested stage
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PipelineOverview {
+    pub counts: Vec<StageCount>,
+    pub stage: Option<PipelineStage>,
+    pub records: Vec<DiscoveryBacktestRecord>,
+}
+
+/// Pipeline position of one knowledge base record
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PipelineStatusResponse {
+    pub backtest_id: i64,
+    pub stage: PipelineStage,
+    /// Stages a manual transition may move it to
+    pub next_stages: Vec<PipelineStage>,
+    /// Oldest first
+    pub transitions: Vec<PipelineTransitionRecord>,
+}
+
+/// Risk limits, halt state and recent breaches of one knowledge base record
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RiskStatusResponse {
+    /// All limits `null` when none were set
+    pub limits: StrategyRiskLimits,
+    pub halted: bool,
+    /// Newest first
+    pub events: Vec<RiskEventRecord>,
+}
+
+/// A named discovery request template of the workspace
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiscoveryPreset {
+    pub name: String,
+    pub description: Option<String>,
+    pub request: DiscoveryRequest,
+    /// Unix seconds
+    pub created_at: Option<i64>,
+    /// Unix seconds of the last save
+    pub updated_at: Option<i64>,
+}
+
+/// A knowledge base record with its exit breakdown and cost attribution decoded
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KnowledgeDetail {
+    pub record: DiscoveryBacktestRecord,
+    /// Trades and PnL by exit reason (signal, stop, trailing, timeout); none for
+    /// records backtested before exits were recorded
+    pub exit_breakdown: Option<ExitBreakdown>,
+    /// Signal PnL, timing cost, slippage and fees behind the net PnL; none for records
+    /// backtested before costs were recorded
+    pub cost_attribution: Option<CostAttribution>,
+}
+
+/// Where a knowledge base record's PnL went, from the prices its signals were decided
+/// on to its net result
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CostReport {
+    pub record_id: i64,
+    pub strategy_name: String,
+    pub symbol: String,
+    pub costs: CostAttribution,
+    /// Mean fees of a trade
+    pub fee_per_trade: f64,
+    /// Trades with a positive price PnL, in %
+    pub win_rate: f64,
+    /// Trades still positive once their fees are paid, in %
+    pub net_win_rate: f64,
+}
+
+impl CostReport {
+    pub fn new(record: &DiscoveryBacktestRecord, costs: CostAttribution) -> Self {
+        Self {
+            record_id: record.id.unwrap_or_default(),
+            strategy_name: record.strategy_name.clone(),
+            symbol: record.symbol.clone(),
+            costs,
+            fee_per_trade: costs.fee_per_trade(),
+            win_rate: costs.win_rate(),
+            net_win_rate: costs.net_win_rate(),
+        }
+    }
+}
+
+/// A knowledge base record close to another one
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SimilarRecord {
+    pub similarity: Similarity,
+    pub record: DiscoveryBacktestRecord,
+}
+
+/// Records close to a knowledge base record, most similar first, and how they did
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SimilarStrategies {
+    pub id: i64,
+    pub strategy_name: String,
+    /// Indicators of the record (its strategy type when it has none)
+    pub indicators: Vec<String>,
+    pub neighborhood: NeighborhoodSummary,
+    pub neighbors: Vec<SimilarRecord>,
+}
+
+/// Grid a continuous cycle ran, decoded from its snapshot
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CycleGridSnapshot {
+    pub run_id: String,
+    pub cycle: i64,
+    /// SHA-256 of the grid JSON, shared by the cycles that ran the same grid
+    pub snapshot_id: String,
+    pub strategy_count: i64,
+    pub grid: CycleGrid,
+    /// When the grid was first stored (unix seconds)
+    pub created_at: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn legacy_body(response: Response) -> (StatusCode, Value) {
+        let response = legacy_envelope(response).await;
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_legacy_envelope_flattens_v1_bodies() {
+        let v1 = serde_json::to_value(ApiResponse::ok(Ack::new("started"))).unwrap();
+        assert_eq!(
+            v1,
+            json!({ "success": true, "data": { "message": "started" } })
+        );
+
+        let (status, body) =
+            legacy_body(ApiResponse::ok(Ack::new("started")).into_response()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "success": true, "message": "started" }));
+
+        let page = ApiResponse::ok(vec![1, 2]).with_meta(PageMeta {
+            total: Some(9),
+            limit: Some(2),
+            ..Default::default()
+        });
+        let (_, body) = legacy_body(page.into_response()).await;
+        assert_eq!(
+            body,
+            json!({ "success": true, "data": [1, 2], "total": 9, "limit": 2 })
+        );
+
+        let err = ApiError::already_running("Backup already running");
+        let v1 = serde_json::to_value(ErrorResponse {
+            success: false,
+            error: err.clone(),
+        })
+        .unwrap();
+        assert_eq!(v1["error"]["code"], "already_running");
+        let (status, body) = legacy_body(err.into_response()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"], "Backup already running");
+        assert_eq!(body["message"], "Backup already running");
+
+        let err = ApiError::from(EngineError::InsufficientData("Only 12 klines".to_string()));
+        assert_eq!(err.code.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(err.message, "Only 12 klines");
+        let err = ApiError::from(EngineError::DataFetch("Binance is down".to_string()));
+        assert_eq!(err.code.status(), StatusCode::BAD_GATEWAY);
+
+        // Responses not built from the envelope pass through untouched
+        let (_, body) = legacy_body(Json(json!({ "openapi": "3.1.0" })).into_response()).await;
+        assert_eq!(body, json!({ "openapi": "3.1.0" }));
+    }
+}
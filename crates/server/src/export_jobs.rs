@@ -0,0 +1,293 @@
+//! Background exports of the knowledge base to gzipped NDJSON files
+//!
+//! `GET /api/v1/export` builds its whole response in memory and `/export/ndjson` keeps a
+//! connection open for the whole table. For very large knowledge bases,
+//! `POST /api/v1/export/jobs` writes the matching records to a `.ndjson.gz` file of the
+//! export dir in the background, one keyset page at a time; once the job is completed
+//! its status carries the link the file is downloaded from.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use chrono::Utc;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use persistence::repository::{DiscoveryRepository, KnowledgeCursor};
+use persistence::Database;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+use utoipa::ToSchema;
+
+/// Rows read per keyset page by an export job
+const PAGE_SIZE: i64 = 500;
+
+/// Where completed export files are served from
+pub const DOWNLOAD_PATH: &str = "/api/v1/export/files";
+
+/// Records an export job writes (all of the workspace by default)
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct ExportFilter {
+    pub strategy_type: Option<String>,
+    pub symbol: Option<String>,
+    /// Minimum win rate (%)
+    pub min_win_rate: Option<f64>,
+    /// Minimum win rate (%) over the last 30 days of the backtest
+    pub min_recent_win_rate: Option<f64>,
+    /// Largest concentration (0-1 HHI) of the PnL over weeks
+    pub max_pnl_hhi: Option<f64>,
+    /// Same values as `/api/v1/knowledge`
+    pub sort_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportJobStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ExportJob {
+    pub id: u64,
+    pub status: ExportJobStatus,
+    pub workspace: String,
+    pub file_name: String,
+    /// Records written so far
+    pub rows: u64,
+    /// Size of the compressed file, once completed
+    pub bytes: Option<u64>,
+    /// Download link of the file, once completed
+    pub download_url: Option<String>,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+/// Export jobs of this server process, by id
+pub struct ExportJobs {
+    dir: PathBuf,
+    next_id: AtomicU64,
+    jobs: Mutex<HashMap<u64, ExportJob>>,
+}
+
+impl ExportJobs {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            next_id: AtomicU64::new(1),
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Register a running job exporting records of `workspace`
+    pub fn start(&self, workspace: &str) -> ExportJob {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let now = Utc::now();
+        let job = ExportJob {
+            id,
+            status: ExportJobStatus::Running,
+            workspace: workspace.to_string(),
+            file_name: format!(
+                "knowledge-{}-{}-{}.ndjson.gz",
+                workspace,
+                now.format("%Y%m%d-%H%M%S"),
+                id
+            ),
+            rows: 0,
+            bytes: None,
+            download_url: None,
+            error: None,
+            created_at: now.timestamp_millis(),
+            finished_at: None,
+        };
+        self.jobs.lock().unwrap().insert(id, job.clone());
+        job
+    }
+
+    /// Job `id`, if it belongs to `workspace`
+    pub fn get(&self, id: u64, workspace: &str) -> Option<ExportJob> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .filter(|job| job.workspace == workspace)
+            .cloned()
+    }
+
+    fn update(&self, id: u64, f: impl FnOnce(&mut ExportJob)) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(&id) {
+            f(job);
+        }
+    }
+
+    /// Write the file of `job`, then mark it completed or failed
+    pub async fn run(&self, job: &ExportJob, db: &Database, filter: &ExportFilter) {
+        let path = self.dir.join(&job.file_name);
+        let progress = |rows| self.update(job.id, |j| j.rows = rows);
+        let result = write_export(db, &job.workspace, filter, &path, progress).await;
+        let finished_at = Some(Utc::now().timestamp_millis());
+        match result {
+            Ok((rows, bytes)) => self.update(job.id, |j| {
+                j.status = ExportJobStatus::Completed;
+                j.rows = rows;
+                j.bytes = Some(bytes);
+                j.download_url = Some(format!("{}/{}", DOWNLOAD_PATH, j.file_name));
+                j.finished_at = finished_at;
+            }),
+            Err(e) => {
+                error!(job = job.id, "Export job failed: {:#}", e);
+                self.update(job.id, |j| {
+                    j.status = ExportJobStatus::Failed;
+                    j.error = Some(e.to_string());
+                    j.finished_at = finished_at;
+                })
+            }
+        }
+    }
+}
+
+/// Write every record of `workspace` matching `filter` to `path` as gzipped NDJSON.
+///
+/// Pages go to a `.part` file renamed once complete, so a file of the export dir is
+/// never half written. `progress` gets the rows written after each page. Returns the
+/// rows written and the compressed size.
+pub async fn write_export(
+    db: &Database,
+    workspace: &str,
+    filter: &ExportFilter,
+    path: &Path,
+    progress: impl Fn(u64),
+) -> anyhow::Result<(u64, u64)> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let partial = path.with_extension("gz.part");
+    let result = write_pages(db, workspace, filter, &partial, progress).await;
+    match result {
+        Ok(rows) => {
+            std::fs::rename(&partial, path)?;
+            Ok((rows, std::fs::metadata(path)?.len()))
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+async fn write_pages(
+    db: &Database,
+    workspace: &str,
+    filter: &ExportFilter,
+    path: &Path,
+    progress: impl Fn(u64),
+) -> anyhow::Result<u64> {
+    let repo = DiscoveryRepository::new(db.pool()).with_workspace(workspace);
+    let mut out = GzEncoder::new(BufWriter::new(File::create(path)?), Compression::default());
+    let mut cursor: Option<KnowledgeCursor> = None;
+    let mut rows = 0u64;
+    loop {
+        let (records, next) = repo
+            .get_page_after(
+                PAGE_SIZE,
+                cursor.as_ref(),
+                filter.strategy_type.as_deref(),
+                filter.symbol.as_deref(),
+                filter.min_win_rate,
+                filter.min_recent_win_rate,
+                filter.max_pnl_hhi,
+                filter.sort_by.as_deref(),
+            )
+            .await?;
+        for record in &records {
+            serde_json::to_writer(&mut out, record)?;
+            out.write_all(b"\n")?;
+        }
+        rows += records.len() as u64;
+        progress(rows);
+        match next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    out.finish()?.flush()?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use persistence::repository::DiscoveryBacktestRecord;
+    use std::io::{BufRead, BufReader};
+
+    fn record(i: usize) -> DiscoveryBacktestRecord {
+        serde_json::from_value(serde_json::json!({
+            "params_hash": format!("hash-{i}"),
+            "strategy_type": "rsi",
+            "strategy_name": "RSI",
+            "strategy_params": "{}",
+            "symbol": "BTCUSDT",
+            "days": 90,
+            "sizing_mode": "fixed",
+            "composite_score": i as f64,
+            "net_pnl": 10.0,
+            "gross_pnl": 12.0,
+            "total_fees": 2.0,
+            "win_rate": 55.0,
+            "total_trades": 20,
+            "sharpe_ratio": 1.0,
+            "max_drawdown_pct": 5.0,
+            "profit_factor": 1.3,
+            "avg_trade_pnl": 0.5,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_export_job_writes_gzipped_ndjson() {
+        let db = Database::in_memory().await.unwrap();
+        // More than a page, so the job follows the keyset cursor
+        let records: Vec<_> = (0..PAGE_SIZE as usize + 20).map(record).collect();
+        DiscoveryRepository::new(db.pool())
+            .save_batch(&records)
+            .await
+            .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("poly-export-test-{}", std::process::id()));
+        let jobs = ExportJobs::new(dir.clone());
+        let job = jobs.start("default");
+        assert_eq!(job.status, ExportJobStatus::Running);
+        // Jobs of other workspaces are not visible
+        assert!(jobs.get(job.id, "other").is_none());
+
+        jobs.run(&job, &db, &ExportFilter::default()).await;
+        let done = jobs.get(job.id, "default").unwrap();
+        assert_eq!(done.status, ExportJobStatus::Completed, "{:?}", done.error);
+        assert_eq!(done.rows, records.len() as u64);
+        assert_eq!(
+            done.download_url.unwrap(),
+            format!("{}/{}", DOWNLOAD_PATH, done.file_name)
+        );
+
+        let file = File::open(jobs.dir().join(&done.file_name)).unwrap();
+        assert_eq!(file.metadata().unwrap().len(), done.bytes.unwrap());
+        let lines: Vec<String> = BufReader::new(GzDecoder::new(file))
+            .lines()
+            .map(|l| l.unwrap())
+            .collect();
+        assert_eq!(lines.len(), records.len());
+        let first: DiscoveryBacktestRecord = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(first.symbol, "BTCUSDT");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,380 @@
+//! gRPC interface next to the HTTP API (`serve --grpc-port`)
+//!
+//! `proto/discovery.proto` gives non-browser clients typed access to the discovery
+//! agent: `StartDiscovery` runs the same validation as `POST /api/v1/discover`,
+//! `StreamProgress` pushes the status of the running scan until it completes instead of
+//! being polled, and `QueryKnowledge` pages through the knowledge base with the keyset
+//! cursor of `GET /api/v1/knowledge`. Calls read their workspace from the `x-workspace`
+//! metadata, like the HTTP header.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use engine::{DiscoveryProgress, DiscoveryRequest, DiscoveryResult};
+use futures_util::Stream;
+use persistence::repository::{DiscoveryBacktestRecord, DiscoveryRepository, KnowledgeCursor};
+use rust_decimal::prelude::ToPrimitive;
+use tonic::{Request, Response, Status};
+
+use crate::dto::{ApiError, DiscoveryStatusResponse, ErrorCode};
+use crate::workspace::{Workspace, WORKSPACE_HEADER};
+use crate::{discovery_status, start_discovery, AppState};
+
+pub mod pb {
+    tonic::include_proto!("polydiscover.v1");
+}
+
+use pb::discovery_server::{Discovery, DiscoveryServer};
+
+/// Default time between two `StreamProgress` updates
+const DEFAULT_INTERVAL_MS: u32 = 1000;
+
+/// Shortest time between two `StreamProgress` updates
+const MIN_INTERVAL_MS: u32 = 100;
+
+/// Default page size of `QueryKnowledge`
+const DEFAULT_PAGE_SIZE: i64 = 20;
+
+impl From<ApiError> for Status {
+    fn from(e: ApiError) -> Self {
+        match e.code {
+            ErrorCode::InvalidRequest => Status::invalid_argument(e.message),
+            ErrorCode::ReadOnly => Status::permission_denied(e.message),
+            ErrorCode::NotFound => Status::not_found(e.message),
+            ErrorCode::AlreadyRunning => Status::already_exists(e.message),
+            ErrorCode::Halted | ErrorCode::Cancelled | ErrorCode::InsufficientData => {
+                Status::failed_precondition(e.message)
+            }
+            ErrorCode::Upstream => Status::unavailable(e.message),
+            ErrorCode::Database | ErrorCode::Internal => Status::internal(e.message),
+        }
+    }
+}
+
+pub struct DiscoveryService {
+    state: AppState,
+}
+
+impl DiscoveryService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+/// Workspace of a call (`default` when the metadata is absent)
+fn workspace<T>(request: &Request<T>) -> Result<Workspace, ApiError> {
+    match request.metadata().get(WORKSPACE_HEADER) {
+        None => Ok(Workspace::default()),
+        Some(value) => {
+            let name = value
+                .to_str()
+                .map_err(|_| ApiError::invalid_request("x-workspace must be ASCII"))?;
+            Workspace::parse(name).map_err(ApiError::invalid_request)
+        }
+    }
+}
+
+fn progress_update(status: DiscoveryStatusResponse) -> pb::ProgressUpdate {
+    let name = serde_json::to_value(&status.status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    pb::ProgressUpdate {
+        status: name,
+        phase: status.phase,
+        current_strategy: status.current_strategy,
+        current_symbol: status.current_symbol,
+        progress_pct: status.progress_pct as f64,
+        completed: status.completed as u64,
+        skipped: status.skipped as u64,
+        total: status.total as u64,
+        throughput_per_sec: status.throughput_per_sec,
+        eta_secs: status.eta_secs,
+        current_cycle: status.current_cycle as u64,
+        continuous: status.is_continuous,
+        paused: status.paused,
+        error: status.error,
+        results: status.results.iter().map(strategy_result).collect(),
+    }
+}
+
+fn strategy_result(result: &DiscoveryResult) -> pb::StrategyResult {
+    pb::StrategyResult {
+        rank: result.rank as u32,
+        strategy_name: result.strategy_name.clone(),
+        symbol: result.symbol.clone(),
+        composite_score: result.composite_score.to_f64().unwrap_or(0.0),
+        net_pnl: result.net_pnl.to_f64().unwrap_or(0.0),
+        win_rate: result.win_rate.to_f64().unwrap_or(0.0),
+        total_trades: result.total_trades,
+        sharpe_ratio: result.sharpe_ratio.to_f64().unwrap_or(0.0),
+        max_drawdown_pct: result.max_drawdown_pct.to_f64().unwrap_or(0.0),
+        strategy_json: serde_json::to_string(&result.strategy_type).unwrap_or_default(),
+    }
+}
+
+fn knowledge_record(record: DiscoveryBacktestRecord) -> pb::KnowledgeRecord {
+    pb::KnowledgeRecord {
+        id: record.id.unwrap_or_default(),
+        strategy_type: record.strategy_type,
+        strategy_name: record.strategy_name,
+        strategy_params: record.strategy_params,
+        symbol: record.symbol,
+        days: record.days,
+        sizing_mode: record.sizing_mode,
+        composite_score: record.composite_score,
+        net_pnl: record.net_pnl,
+        win_rate: record.win_rate,
+        total_trades: record.total_trades,
+        sharpe_ratio: record.sharpe_ratio,
+        max_drawdown_pct: record.max_drawdown_pct,
+        profit_factor: record.profit_factor,
+        discovery_run_id: record.discovery_run_id,
+    }
+}
+
+type ProgressStream = Pin<Box<dyn Stream<Item = Result<pb::ProgressUpdate, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Discovery for DiscoveryService {
+    async fn start_discovery(
+        &self,
+        request: Request<pb::StartDiscoveryRequest>,
+    ) -> Result<Response<pb::StartDiscoveryReply>, Status> {
+        let workspace = workspace(&request)?;
+        let request = request.into_inner();
+
+        let mut body = if request.request_json.trim().is_empty() {
+            serde_json::Map::new()
+        } else {
+            match serde_json::from_str(&request.request_json) {
+                Ok(serde_json::Value::Object(body)) => body,
+                _ => {
+                    return Err(Status::invalid_argument(
+                        "request_json must be a JSON object",
+                    ))
+                }
+            }
+        };
+        if !request.symbols.is_empty() {
+            body.insert("symbols".into(), request.symbols.into());
+        }
+        body.entry("symbols")
+            .or_insert_with(|| Vec::<String>::new().into());
+        if let Some(days) = request.days {
+            body.insert("days".into(), days.into());
+        }
+        if request.continuous {
+            body.insert("continuous".into(), true.into());
+        }
+        let discovery: DiscoveryRequest = serde_json::from_value(body.into())
+            .map_err(|e| Status::invalid_argument(format!("Invalid request_json: {}", e)))?;
+
+        let started = start_discovery(&self.state, &workspace, discovery)?;
+        Ok(Response::new(pb::StartDiscoveryReply {
+            message: started.message,
+            continuous: started.continuous,
+        }))
+    }
+
+    type StreamProgressStream = ProgressStream;
+
+    async fn stream_progress(
+        &self,
+        request: Request<pb::StreamProgressRequest>,
+    ) -> Result<Response<Self::StreamProgressStream>, Status> {
+        let interval_ms = match request.into_inner().interval_ms {
+            0 => DEFAULT_INTERVAL_MS,
+            ms => ms.max(MIN_INTERVAL_MS),
+        };
+        let interval = Duration::from_millis(interval_ms as u64);
+
+        // State: None = finished, Some(first) = send the next update (after a pause
+        // unless it is the first one); the update after the scan stopped is the last
+        let progress: Arc<DiscoveryProgress> = self.state.discovery_progress.clone();
+        let stream = futures_util::stream::unfold(Some(true), move |next| {
+            let progress = progress.clone();
+            async move {
+                let first = next?;
+                if !first {
+                    tokio::time::sleep(interval).await;
+                }
+                let running = progress.is_running();
+                let update = progress_update(discovery_status(&progress));
+                Some((Ok(update), running.then_some(false)))
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn query_knowledge(
+        &self,
+        request: Request<pb::QueryKnowledgeRequest>,
+    ) -> Result<Response<pb::QueryKnowledgeReply>, Status> {
+        let workspace = workspace(&request)?;
+        let request = request.into_inner();
+        let limit = match request.limit {
+            0 => DEFAULT_PAGE_SIZE,
+            limit => limit as i64,
+        };
+        let cursor = if request.cursor.is_empty() {
+            None
+        } else {
+            Some(
+                KnowledgeCursor::decode(&request.cursor)
+                    .ok_or_else(|| Status::invalid_argument("Invalid cursor"))?,
+            )
+        };
+
+        let (records, next) = DiscoveryRepository::new(self.state.db.pool())
+            .with_workspace(workspace.as_str())
+            .get_page_after(
+                limit,
+                cursor.as_ref(),
+                request.strategy_type.as_deref(),
+                request.symbol.as_deref(),
+                request.min_win_rate,
+                None,
+                None,
+                request.sort_by.as_deref(),
+            )
+            .await
+            .map_err(|e| ApiError::database(format!("Failed to query knowledge base: {}", e)))?;
+        Ok(Response::new(pb::QueryKnowledgeReply {
+            records: records.into_iter().map(knowledge_record).collect(),
+            next_cursor: next.map(|c| c.encode()),
+        }))
+    }
+}
+
+/// Serve the gRPC API on `addr` until the process stops
+pub async fn serve(state: AppState, addr: SocketAddr) -> anyhow::Result<()> {
+    tonic::transport::Server::builder()
+        .add_service(DiscoveryServer::new(DiscoveryService::new(state)))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state;
+    use engine::DiscoveryStatus;
+    use futures_util::StreamExt;
+    use persistence::Database;
+
+    fn record(i: usize, symbol: &str) -> DiscoveryBacktestRecord {
+        serde_json::from_value(serde_json::json!({
+            "params_hash": format!("hash-{i}"),
+            "strategy_type": "rsi",
+            "strategy_name": "RSI",
+            "strategy_params": "{}",
+            "symbol": symbol,
+            "days": 90,
+            "sizing_mode": "fixed",
+            "composite_score": i as f64,
+            "net_pnl": 10.0,
+            "gross_pnl": 12.0,
+            "total_fees": 2.0,
+            "win_rate": 55.0,
+            "total_trades": 20,
+            "sharpe_ratio": 1.0,
+            "max_drawdown_pct": 5.0,
+            "profit_factor": 1.3,
+            "avg_trade_pnl": 0.5,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_grpc_service() {
+        let db = Database::in_memory().await.unwrap();
+        let records: Vec<_> = (0..5).map(|i| record(i, "BTCUSDT")).collect();
+        DiscoveryRepository::new(db.pool())
+            .save_batch(&records)
+            .await
+            .unwrap();
+        let service = DiscoveryService::new(app_state(db, 0).unwrap());
+
+        // Two pages of the default workspace, best score first
+        let query = |cursor: String| pb::QueryKnowledgeRequest {
+            limit: 3,
+            cursor,
+            ..Default::default()
+        };
+        let page = service
+            .query_knowledge(Request::new(query(String::new())))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(page.records.len(), 3);
+        assert_eq!(page.records[0].composite_score, 4.0);
+        let last = service
+            .query_knowledge(Request::new(query(page.next_cursor.unwrap())))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(last.records.len(), 2);
+        assert!(last.next_cursor.is_none());
+
+        // Other workspaces do not see the records
+        let mut other = Request::new(query(String::new()));
+        other
+            .metadata_mut()
+            .insert(WORKSPACE_HEADER, "other".parse().unwrap());
+        let page = service.query_knowledge(other).await.unwrap().into_inner();
+        assert!(page.records.is_empty());
+        let mut invalid = Request::new(query(String::new()));
+        invalid
+            .metadata_mut()
+            .insert(WORKSPACE_HEADER, "no spaces".parse().unwrap());
+        let err = service.query_knowledge(invalid).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        // An idle agent sends one update and ends the stream
+        let updates: Vec<_> = service
+            .stream_progress(Request::new(pb::StreamProgressRequest { interval_ms: 0 }))
+            .await
+            .unwrap()
+            .into_inner()
+            .collect()
+            .await;
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].as_ref().unwrap().status, "idle");
+
+        let start = |request_json: &str| pb::StartDiscoveryRequest {
+            symbols: vec!["BTCUSDT".into()],
+            request_json: request_json.into(),
+            ..Default::default()
+        };
+        let err = service
+            .start_discovery(Request::new(start("[1, 2]")))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        // A scan already running is reported like the HTTP 409
+        *service.state.discovery_progress.status.write().unwrap() =
+            DiscoveryStatus::Phase1BroadScan;
+        let err = service
+            .start_discovery(Request::new(start("")))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::AlreadyExists);
+
+        // A read-only server refuses scans whatever their state
+        let service = DiscoveryService::new(AppState {
+            read_only: true,
+            ..service.state.clone()
+        });
+        *service.state.discovery_progress.status.write().unwrap() = DiscoveryStatus::Idle;
+        let err = service
+            .start_discovery(Request::new(start("")))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    }
+}
@@ -3,34 +3,117 @@
 //! Usage:
 //!   poly-discover serve --port 3001        — Launch web server with UI
 //!   poly-discover run --symbols BTCUSDT    — Run discovery from CLI
+//!   poly-discover migrate-params           — Upgrade stored strategy params to the current format
+//!   poly-discover backup --out kb.db.zst   — Online compressed snapshot of the knowledge base
+//!   poly-discover restore --from kb.db.zst — Replace the database with a snapshot (server stopped)
+
+#[cfg(feature = "embed-ui")]
+mod assets;
+mod cache;
+mod dto;
+mod export_jobs;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod openapi;
+mod worker;
+mod workspace;
 
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
-    response::Json,
-    routing::{get, post},
+    body::Body,
+    extract::{Query, Request, State},
+    http::{header, Method},
+    middleware::{from_fn_with_state, map_response, Next},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post, put},
     Router,
 };
 use chrono::Utc;
 use clap::{Parser, Subcommand};
 use engine::{
-    analyze_leaderboard, analyze_profile, run_continuous_discovery, run_discovery, run_optimization,
-    run_orderbook_backtest, run_orderbook_collector, run_trade_watcher, BinanceClient,
-    DiscoveryProgress, DiscoveryRequest, DiscoveryResult, DiscoveryStatus,
-    LeaderboardProgress, ObBacktestProgress, ObCollectorProgress,
+    analyze_leaderboard, analyze_profile, backup_file_name, compute_features, create_backup,
+    duration_until_utc_hour, migrate_strategy_params,
+    run_maintenance, run_optimization,
+    restore_backup, run_gabagool_scanner, run_orderbook_backtest, run_orderbook_collector, run_trade_watcher, BinanceClient,
+    DiscoveryEngine, DiscoveryProgress, DiscoveryRequest, DiscoveryResult, DiscoveryStatus, GabagoolScannerConfig,
+    GabagoolScannerProgress,
+    LeaderboardProgress, MaintenanceProgress, MaintenanceStatus, ObBacktestProgress, ObCollectorProgress,
     OptimizeProgress, OptimizeRequest, OptimizeStatus, PolymarketDataClient, ProfileProgress,
-    ProfileStatus, SizingMode, WatcherProgress,
+    ProfileStatus, RefinementDepth, SizingMode, SqliteResultStore, StrategyExclusions, WatcherProgress,
+    write_features_parquet, FeatureTable, WebStrategyCatalogEntry, DEFAULT_SYMBOLS,
+    drift_report, revalidate, validation_strategy, DecayThresholds, StrategyDrift,
+    apply_pipeline_rules, PipelineReport, PipelineRules, PipelineStage, PipelineTransitionRequest,
 };
+use engine::pipeline::{current_stage, reject_if_decaying};
+use engine::{paper_strategy, run_paper_session, PaperSession, RiskLimits};
+use engine::{run_portfolio_paper_session, CapitalConfig, PortfolioPaperSession};
+use engine::features::DEFAULT_HORIZONS;
+use engine::{simulate, BacktestLimits, SimulationReport, SyntheticConfig, SyntheticModel};
+use engine::DataSource;
+use engine::{CandleTransform, ParticipationLimit, UnfilledRemainder};
+use engine::resolution::market_asset;
+use engine::data_quality::dedupe_klines;
+use engine::discovery::{KLINE_INTERVAL, KLINE_INTERVAL_MS};
+use engine::{diff_klines, Kline};
+use engine::{run_resolution_backtest, ResolutionBacktest};
+use engine::{chart_strategy, record_equity_curve, render_chart, ChartFormat};
+use engine::{fee_sensitivity, FeePreset, FeeSensitivityReport};
+use engine::{fill_sensitivity, FillModel, FillSensitivityReport};
+use engine::ensemble::{ensemble_strategy, MAX_MEMBERS};
+use engine::{build_ensemble, EnsembleBuild, EnsembleVote};
+use engine::SymbolMetadata;
+use engine::{run_manual_backtest, ManualBacktest, ManualBacktestRequest};
+use engine::{strategy_schemas, StrategySchemas};
+use engine::{calibrate_logistic, ProbabilityModel, ProbabilityModelConfig};
+use engine::{find_regressions, run_benchmarks, BenchConfig, BenchReport};
+use engine::{estimate_discovery, DiscoveryEstimate};
+use engine::NdjsonResultSink;
+use engine::{last_session_crashes, supervise};
+use engine::{explain_record, StrategyExplanation};
+use engine::{record_stub, StubTarget};
+use engine::CycleGrid;
+use engine::{check_optimization_klines, optimization_records, EngineError};
+use engine::ScanPacing;
+use engine::{daily_pnl_strategy, record_daily_pnl};
+use engine::{Coordinator, CoordinatorStatus};
+use engine::similarity::{DEFAULT_MIN_SIMILARITY, DEFAULT_SIMILAR_LIMIT, MAX_SIMILAR_LIMIT};
+use engine::{decode_strategy_params, rank_similar, NeighborhoodSummary, StrategyProfile};
+use persistence::repository::RetentionPolicy;
+use persistence::repository::{format_rank_order, parse_rank_order, RankKey, DEFAULT_RANK_ORDER};
+use persistence::repository::{DailyPnlRecord, DailyPnlRepository};
+use persistence::repository::{OptimizationRecord, OptimizationRepository};
 use persistence::repository::{
-    DiscoveryRepository, LeaderboardRepository, OrderbookRepository, ProfileRepository,
+    write_generation, DiscoveryBacktestRecord, DiscoveryRepository, KnowledgeCursor, LeaderboardRepository,
+    LeaderboardTraderRecord, ObPatternRecord, OrderbookRepository, ProfileAnalysisRecord, ProfileRepository,
+    CycleSummaryRecord, KlineQualityRecord, PipelineRepository, RiskRepository, RunComparison, StrategyRiskLimits,
+    RunCrashRecord,
+    PresetRepository, DiscoveryPresetRecord, ValidationRepository,
 };
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use std::collections::HashMap;
+use cache::QueryCache;
+use dto::{
+    Ack, ApiError, ApiResponse, ApiResult, BackupResponse, CacheStatsResponse, CleanupResponse,
+    CollectorStatusResponse, DiscoveryPreset, DiscoveryStarted, DiscoveryStatusResponse, ErrorResponse, ExportFilters,
+    ExportResponse, ExportedMetrics, ExportedResult, GabagoolOpportunitiesResponse,
+    HealthResponse, KlineDiffResponse, KlinesResponse, KnowledgeDetail, KnowledgeStatsResponse, LeaderboardStatusResponse,
+    MaintenanceStarted, MaintenanceStatusResponse, ObBacktestStatusResponse, ObDbState,
+    ObStatsResponse, OptimizationStarted, OptimizeStatusResponse, PageMeta, PipelineOverview,
+    PipelineStatusResponse, ProfileStatusResponse, RiskStatusResponse, WatcherStatusResponse,
+};
+use dto::CycleGridSnapshot;
+use dto::{ChunkAccepted, WorkerLease};
+use dto::{CostReport, SimilarRecord, SimilarStrategies};
+use export_jobs::{ExportFilter, ExportJob, ExportJobs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use tower_http::compression::predicate::{NotForContentType, Predicate};
+use tower_http::compression::{CompressionLayer, DefaultPredicate};
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::services::ServeDir;
 use tracing::{error, info};
+use workspace::Workspace;
 
 const APP_VERSION: &str = concat!("1.0.", env!("BUILD_NUMBER"), "-", env!("GIT_HASH"));
 
@@ -47,15 +130,42 @@ struct Cli {
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 enum Commands {
     /// Launch the discovery web server
+    ///
+    /// Every option can also be set from its `POLY_DISCOVERY_*` environment variable
+    /// (containers).
     Serve {
         /// Host to bind to
-        #[arg(long, default_value = "0.0.0.0")]
+        #[arg(long, env = "POLY_DISCOVERY_HOST", default_value = "0.0.0.0")]
         host: String,
         /// Port to listen on
-        #[arg(short, long, default_value_t = 3001)]
+        #[arg(short, long, env = "POLY_DISCOVERY_PORT", default_value_t = 3001)]
         port: u16,
+        /// Also serve the gRPC API (proto/discovery.proto) on this port
+        #[arg(long, env = "POLY_DISCOVERY_GRPC_PORT")]
+        grpc_port: Option<u16>,
+        /// In-process workers backtesting the Phase 1 chunks of one-shot scans in
+        /// parallel, on the same queue as remote workers (0 = none)
+        #[arg(long, env = "POLY_DISCOVERY_LOCAL_WORKERS", default_value_t = 0)]
+        local_workers: usize,
+        /// Public dashboard mode: only GET endpoints, no scan, job or background task, and
+        /// the database opened read-only (no migration). Another instance writes the file.
+        #[arg(long, env = "POLY_DISCOVERY_READ_ONLY")]
+        read_only: bool,
+    },
+    /// Backtest the Phase 1 chunks of a coordinator server's one-shot scans
+    Worker {
+        /// Base URL of the coordinator, e.g. http://10.0.0.5:3001
+        #[arg(long)]
+        coordinator: String,
+        /// Name shown in GET /api/v1/workers (default: <host>-<pid>)
+        #[arg(long)]
+        worker_id: Option<String>,
+        /// Seconds between two lease requests while idle
+        #[arg(long, default_value_t = 5)]
+        poll_secs: u64,
     },
     /// Run a discovery scan from CLI (no web server)
     Run {
@@ -68,6 +178,10 @@ enum Commands {
         /// Number of top results to return
         #[arg(long, default_value_t = 10)]
         top_n: usize,
+        /// Results kept for each symbol in the final ranking, even below the global
+        /// top N (default: one global top N)
+        #[arg(long)]
+        per_symbol_top_n: Option<usize>,
         /// Sizing mode: fixed, kelly, confidence
         #[arg(long, default_value = "fixed")]
         sizing: String,
@@ -77,12 +191,209 @@ enum Commands {
         /// Run continuously until Ctrl+C
         #[arg(long)]
         continuous: bool,
+        /// ONNX classifier files to backtest as strategies (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        onnx_model: Vec<String>,
+        /// Per-backtest wall-clock budget in ms (0 = unlimited)
+        #[arg(long, default_value_t = 10_000)]
+        time_budget_ms: u64,
+        /// Abort a backtest once it has lost this % of the capital (0 = never)
+        #[arg(long, default_value = "50")]
+        ruin_threshold: Decimal,
+        /// Re-fetch the gaps found by the kline quality check once
+        #[arg(long)]
+        repair_gaps: bool,
+        /// Kline source: "binance" or "file:<path>" (CSV / Parquet file or directory)
+        #[arg(long, default_value = "binance")]
+        data_source: String,
+        /// Fee preset: polymarket, zero or custom:taker=…,maker=…,maker_share=… (what-if
+        /// presets are not stored)
+        #[arg(long, default_value = "polymarket")]
+        fees: String,
+        /// Probability model of the fee estimates: linear, logistic (calibrated on
+        /// resolved markets), logistic:<slope>, constant or constant:<p>
+        #[arg(long, default_value = "linear")]
+        probability_model: String,
+        /// Fill model of the signals: close, next_open, worst_of_bar or ohlc_path (runs
+        /// under another model than close are not stored)
+        #[arg(long, default_value = "close")]
+        fill_model: String,
+        /// Fill entries with at most this % of the bar's volume, carrying the rest to
+        /// the next bars (runs with a volume cap are not stored)
+        #[arg(long)]
+        max_participation_pct: Option<f64>,
+        /// Cancel what the volume cap leaves unfilled instead of carrying it
+        #[arg(long)]
+        cancel_unfilled: bool,
+        /// Candles the strategies read: raw, heikin_ashi or renko:<brick % of the first
+        /// close> (transformed runs are not stored)
+        #[arg(long, default_value = "raw")]
+        candles: String,
+        /// Re-run the best results under drawdown throttle presets after refinement
+        #[arg(long)]
+        throttle_search: bool,
+        /// Re-run the best results on the other symbols to score how they generalize
+        #[arg(long)]
+        cross_symbol: bool,
+        /// Weight (0-1) of the cross-symbol score in the final ranking (0 = reported only)
+        #[arg(long, default_value = "0")]
+        cross_symbol_weight: Decimal,
+        /// Best results refined per Phase 2 round
+        #[arg(long, default_value_t = 20)]
+        refinement_top_k: usize,
+        /// Phase 2 rounds: each later round refines the best new results of the previous
+        #[arg(long, default_value_t = 1)]
+        refinement_rounds: u32,
+        /// Multiplier of the refinement parameter steps (<1 finer, >1 wider)
+        #[arg(long, default_value_t = 1.0)]
+        refinement_delta_scale: f64,
+        /// Hill-climb the best result of each strategy family after refinement
+        #[arg(long)]
+        local_search: bool,
+        /// Plan continuous cycles 0-2 from the knowledge base coverage instead of the
+        /// fixed grids
+        #[arg(long)]
+        adaptive_cycles: bool,
+        /// Also score results with trades decayed by recency, with this half-life in
+        /// days (stored as decayed_score)
+        #[arg(long)]
+        decay_half_life: Option<f64>,
+        /// Composite points taken off per day of a result's longest drawdown in the
+        /// final ranking (0 = reported only)
+        #[arg(long, default_value = "0")]
+        underwater_penalty: Decimal,
+        /// Ranking keys, compared in order to break ties (comma-separated among
+        /// score, sharpe, trades, net_pnl, win_rate, profit_factor, drawdown)
+        #[arg(long, default_value = "score,sharpe,trades")]
+        rank_by: String,
+        /// Largest position, in % of equity, whatever the sizing mode (default: the
+        /// sizing mode's own size)
+        #[arg(long)]
+        max_exposure_pct: Option<f64>,
+        /// Strategy types left out of the grids (comma-separated, e.g. gabagool,rsi)
+        #[arg(long, value_delimiter = ',')]
+        exclude: Vec<String>,
+        /// Computed backtests per second, at most (default: unpaced)
+        #[arg(long)]
+        max_backtests_per_sec: Option<f64>,
+        /// Share of one core the scan may keep busy, in (0, 1] (default: unpaced)
+        #[arg(long)]
+        cpu_fraction: Option<f64>,
+        /// Knowledge base workspace the results are stored in
+        #[arg(long, default_value = "default")]
+        workspace: String,
+        /// Append every finished result to this NDJSON file as the run goes
+        #[arg(long)]
+        stream_out: Option<String>,
+        /// Print the grid size, cached/new split and estimated runtime, then exit
+        #[arg(long)]
+        dry_run: bool,
+        /// Continuous cycle estimated by --dry-run
+        #[arg(long, default_value_t = 0)]
+        cycle: u32,
+        /// Run the discovery request saved as this preset of the workspace (replaces the
+        /// scan flags; --export, --stream-out and --dry-run still apply)
+        #[arg(long)]
+        preset: Option<String>,
     },
     /// Cleanup DB: keep top N best results per strategy (positive PnL only), delete the rest
     Cleanup {
         /// Number of best results to keep per strategy_name (default 3)
         #[arg(long, default_value_t = 3)]
         keep: i64,
+        /// Knowledge base workspace to clean up
+        #[arg(long, default_value = "default")]
+        workspace: String,
+    },
+    /// Upgrade stored strategy_params to the current schema version, quarantining incompatible rows
+    MigrateParams {
+        /// Report what would change without modifying the DB
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write a zstd-compressed online snapshot of the database (safe while the server runs)
+    Backup {
+        /// Output file (default: <POLY_DISCOVERY_BACKUP_DIR>/discovery-<timestamp>.db.zst)
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Replace the database with a snapshot (.db.zst or plain .db). Stop the server first.
+    Restore {
+        /// Snapshot file to restore
+        #[arg(long)]
+        from: String,
+    },
+    /// Export per-bar indicator features + forward-return labels (15m klines) to Parquet
+    Features {
+        /// Symbols to export (comma-separated)
+        #[arg(long, value_delimiter = ',', default_value = "BTCUSDT")]
+        symbols: Vec<String>,
+        /// Number of days of historical data
+        #[arg(long, default_value_t = 365)]
+        days: u32,
+        /// Output Parquet file
+        #[arg(long, default_value = "features.parquet")]
+        out: String,
+        /// Forward-return label horizons in 15m bars (comma-separated)
+        #[arg(long, value_delimiter = ',', default_values_t = DEFAULT_HORIZONS.to_vec())]
+        horizons: Vec<usize>,
+    },
+    /// Run the Phase 1 grid on synthetic klines (nothing stored) to see what noise scores
+    Simulate {
+        /// Price model: gbm, trending, mean-reverting, regime-switching
+        #[arg(long, default_value = "gbm")]
+        model: String,
+        /// Length of the series, in days of 15m bars
+        #[arg(long, default_value_t = 90)]
+        days: u32,
+        /// Standard deviation of the per-bar return
+        #[arg(long, default_value_t = 0.003)]
+        volatility: f64,
+        /// Per-bar drift of trends (default 0.0003 for trending / regime-switching, else 0)
+        #[arg(long)]
+        drift: Option<f64>,
+        /// Random seed: the same seed gives the same series
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+        /// Sizing mode: fixed, kelly, confidence
+        #[arg(long, default_value = "fixed")]
+        sizing: String,
+        /// Number of top results to show
+        #[arg(long, default_value_t = 10)]
+        top_n: usize,
+        /// Optional JSON export path of the report
+        #[arg(long)]
+        export: Option<String>,
+    },
+    /// Fit the logistic probability model on resolved Polymarket up/down markets
+    CalibrateProbability {
+        /// Symbol whose up/down markets are used (BTCUSDT, ETHUSDT, SOLUSDT, XRPUSDT)
+        #[arg(long, default_value = "BTCUSDT")]
+        symbol: String,
+        /// Days of resolved markets
+        #[arg(long, default_value_t = 2)]
+        days: i64,
+    },
+    /// Time the backtest, indicator, scoring and grid hot paths (use a release build)
+    Bench {
+        /// 15m bars of the synthetic series (8640 = 90 days)
+        #[arg(long, default_value_t = 8640)]
+        bars: usize,
+        /// Timed iterations per case
+        #[arg(long, default_value_t = 20)]
+        iterations: usize,
+        /// Only cases whose name contains this (e.g. backtest/, indicators/RSI)
+        #[arg(long)]
+        filter: Option<String>,
+        /// Save the report as JSON (a baseline for later runs)
+        #[arg(long)]
+        out: Option<String>,
+        /// Baseline report to compare against: exit with an error on a regression
+        #[arg(long)]
+        baseline: Option<String>,
+        /// Slowdown over the baseline tolerated, in %
+        #[arg(long, default_value_t = 25.0)]
+        max_regression_pct: f64,
     },
 }
 
@@ -95,9 +406,103 @@ struct AppState {
     optimize_progress: Arc<OptimizeProgress>,
     leaderboard_progress: Arc<LeaderboardProgress>,
     watcher_progress: Arc<WatcherProgress>,
+    gabagool_scanner_progress: Arc<GabagoolScannerProgress>,
     profile_progress: Arc<ProfileProgress>,
     ob_backtest_progress: Arc<ObBacktestProgress>,
     ob_collector_progress: Arc<ObCollectorProgress>,
+    maintenance_progress: Arc<MaintenanceProgress>,
+    /// Set while a manual or nightly maintenance pass runs
+    maintenance_running: Arc<AtomicBool>,
+    backup_dir: Arc<PathBuf>,
+    backup_running: Arc<AtomicBool>,
+    export_jobs: Arc<ExportJobs>,
+    query_cache: Arc<QueryCache>,
+    /// Retention applied by the discovery store after each run / cycle
+    retention: Arc<RetentionPolicy>,
+    /// Strategy types every discovery run and estimate leaves out
+    excluded_strategy_types: Arc<Vec<String>>,
+    /// Chunk queue (`work_queue` table) of the local and remote workers
+    coordinator: Arc<Coordinator>,
+    /// `serve --read-only`: only GET endpoints are served
+    read_only: bool,
+}
+
+/// Shared state of the HTTP and gRPC handlers, on `db`
+fn app_state(db: persistence::Database, cache_ttl_secs: u64) -> anyhow::Result<AppState> {
+    let coordinator = Arc::new(Coordinator::new(db.pool_clone()));
+    Ok(AppState {
+        binance: Arc::new(BinanceClient::new()),
+        polymarket: Arc::new(PolymarketDataClient::new()),
+        db: Arc::new(db),
+        discovery_progress: Arc::new(DiscoveryProgress::new()),
+        optimize_progress: Arc::new(OptimizeProgress::new()),
+        leaderboard_progress: Arc::new(LeaderboardProgress::new()),
+        watcher_progress: Arc::new(WatcherProgress::new()),
+        gabagool_scanner_progress: Arc::new(GabagoolScannerProgress::new()),
+        profile_progress: Arc::new(ProfileProgress::new()),
+        ob_backtest_progress: Arc::new(ObBacktestProgress::new()),
+        ob_collector_progress: Arc::new(ObCollectorProgress::new()),
+        maintenance_progress: Arc::new(MaintenanceProgress::new()),
+        maintenance_running: Arc::new(AtomicBool::new(false)),
+        backup_dir: Arc::new(backup_dir()),
+        backup_running: Arc::new(AtomicBool::new(false)),
+        export_jobs: Arc::new(ExportJobs::new(export_dir())),
+        query_cache: Arc::new(QueryCache::new(
+            64,
+            std::time::Duration::from_secs(cache_ttl_secs),
+            std::time::Duration::from_secs(2),
+        )),
+        retention: Arc::new(retention_policy()),
+        excluded_strategy_types: Arc::new(excluded_strategy_types()?),
+        coordinator,
+        read_only: false,
+    })
+}
+
+/// Database of `serve`: migrated on open, or opened read-only (no migration, nothing
+/// written) for `--read-only`
+async fn open_database(db_path: &str, read_only: bool) -> anyhow::Result<persistence::Database> {
+    let db = if read_only {
+        persistence::Database::open_read_only(db_path).await
+    } else {
+        persistence::Database::new(db_path).await
+    };
+    let db = db.map_err(|e| {
+        error!("Failed to initialize database: {}", e);
+        anyhow::anyhow!("Database initialization failed: {}", e)
+    })?;
+    info!("Database initialized: {}", db_path);
+    Ok(db)
+}
+
+/// Stored crashes looked at for the last supervised session
+const RESTORED_CRASHES: i64 = 100;
+
+/// Crashes of the last supervised discovery session (default workspace), so the status
+/// still shows them after a server restart
+async fn restore_run_crashes(state: &AppState) {
+    match DiscoveryRepository::new(state.db.pool())
+        .get_recent_run_crashes(RESTORED_CRASHES)
+        .await
+    {
+        Ok(recent) => {
+            *state.discovery_progress.crashes.write().unwrap() = last_session_crashes(&recent)
+        }
+        Err(e) => error!("Failed to load recorded run crashes: {}", e),
+    }
+}
+
+/// Refuses every method but GET / HEAD / OPTIONS when the server is read-only
+async fn read_only_guard(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if state.read_only
+        && !matches!(
+            *request.method(),
+            Method::GET | Method::HEAD | Method::OPTIONS
+        )
+    {
+        return ApiError::read_only().into_response();
+    }
+    next.run(request).await
 }
 
 fn init_logging(verbose: bool) {
@@ -130,21 +535,167 @@ async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
 
     match cli.command {
-        Commands::Serve { host, port } => {
-            cmd_serve(&host, port).await?;
+        Commands::Serve {
+            host,
+            port,
+            grpc_port,
+            local_workers,
+            read_only,
+        } => {
+            cmd_serve(&host, port, grpc_port, local_workers, read_only).await?;
+        }
+        Commands::Worker {
+            coordinator,
+            worker_id,
+            poll_secs,
+        } => {
+            let worker_id = worker_id.unwrap_or_else(worker::default_worker_id);
+            let poll = std::time::Duration::from_secs(poll_secs.max(1));
+            worker::run_worker(&coordinator, worker_id, poll).await?;
         }
         Commands::Run {
             symbols,
             days,
             top_n,
+            per_symbol_top_n,
             sizing,
             export,
             continuous,
+            onnx_model,
+            time_budget_ms,
+            ruin_threshold,
+            repair_gaps,
+            data_source,
+            fees,
+            probability_model,
+            fill_model,
+            max_participation_pct,
+            cancel_unfilled,
+            candles,
+            throttle_search,
+            cross_symbol,
+            cross_symbol_weight,
+            refinement_top_k,
+            refinement_rounds,
+            refinement_delta_scale,
+            local_search,
+            adaptive_cycles,
+            decay_half_life,
+            underwater_penalty,
+            rank_by,
+            max_exposure_pct,
+            exclude,
+            max_backtests_per_sec,
+            cpu_fraction,
+            workspace,
+            stream_out,
+            dry_run,
+            cycle,
+            preset,
+        } => {
+            cmd_run(
+                symbols,
+                days,
+                top_n,
+                per_symbol_top_n,
+                sizing,
+                export,
+                continuous,
+                onnx_model,
+                time_budget_ms,
+                ruin_threshold,
+                repair_gaps,
+                data_source,
+                fees,
+                probability_model,
+                fill_model,
+                max_participation_pct,
+                cancel_unfilled,
+                candles,
+                throttle_search,
+                cross_symbol.then_some(cross_symbol_weight),
+                RefinementDepth {
+                    top_k: refinement_top_k,
+                    rounds: refinement_rounds,
+                    delta_scale: refinement_delta_scale,
+                },
+                local_search,
+                adaptive_cycles,
+                decay_half_life,
+                underwater_penalty,
+                rank_by,
+                max_exposure_pct,
+                exclude,
+                ScanPacing {
+                    max_backtests_per_sec,
+                    cpu_fraction,
+                },
+                workspace,
+                stream_out,
+                dry_run.then_some(cycle),
+                preset,
+            )
+            .await?;
+        }
+        Commands::Cleanup { keep, workspace } => {
+            cmd_cleanup(keep, &workspace).await?;
+        }
+        Commands::MigrateParams { dry_run } => {
+            cmd_migrate_params(dry_run).await?;
+        }
+        Commands::Backup { out } => {
+            cmd_backup(out).await?;
+        }
+        Commands::Restore { from } => {
+            cmd_restore(&from).await?;
+        }
+        Commands::Features {
+            symbols,
+            days,
+            out,
+            horizons,
+        } => {
+            cmd_features(symbols, days, &out, &horizons).await?;
+        }
+        Commands::Simulate {
+            model,
+            days,
+            volatility,
+            drift,
+            seed,
+            sizing,
+            top_n,
+            export,
         } => {
-            cmd_run(symbols, days, top_n, sizing, export, continuous).await?;
+            let model = SyntheticModel::parse(&model)
+                .ok_or_else(|| anyhow::anyhow!("Unknown synthetic model: {}", model))?;
+            let defaults = SyntheticConfig::for_model(model);
+            let config = SyntheticConfig {
+                bars: days as usize * 96,
+                volatility,
+                drift: drift.unwrap_or(defaults.drift),
+                seed,
+                ..defaults
+            };
+            cmd_simulate(config, parse_sizing_mode(&sizing), top_n, export).await?;
+        }
+        Commands::CalibrateProbability { symbol, days } => {
+            cmd_calibrate_probability(&symbol, days).await?;
         }
-        Commands::Cleanup { keep } => {
-            cmd_cleanup(keep).await?;
+        Commands::Bench {
+            bars,
+            iterations,
+            filter,
+            out,
+            baseline,
+            max_regression_pct,
+        } => {
+            let config = BenchConfig {
+                bars,
+                iterations,
+                ..BenchConfig::default()
+            };
+            cmd_bench(config, filter, out, baseline, max_regression_pct).await?;
         }
     }
 
@@ -155,64 +706,181 @@ async fn main() -> anyhow::Result<()> {
 // Serve command — Axum web server
 // ============================================================================
 
-async fn cmd_serve(host: &str, port: u16) -> anyhow::Result<()> {
+/// Web UI directory: dist/ next to the executable, else dist/ in the working directory
+#[cfg(not(feature = "embed-ui"))]
+fn static_dir() -> PathBuf {
+    let exe_path = std::env::current_exe().unwrap_or_default();
+    let exe_dir = exe_path.parent().unwrap_or(std::path::Path::new("."));
+    let dist_dir = exe_dir.join("dist");
+    if dist_dir.exists() {
+        dist_dir
+    } else {
+        PathBuf::from("dist")
+    }
+}
+
+async fn cmd_serve(
+    host: &str,
+    port: u16,
+    grpc_port: Option<u16>,
+    local_workers: usize,
+    read_only: bool,
+) -> anyhow::Result<()> {
     info!("Poly-Discover v{} starting...", APP_VERSION);
 
     let db_path =
         std::env::var("POLY_DISCOVERY_DB_PATH").unwrap_or_else(|_| "data/discovery.db".to_string());
-    let db = persistence::Database::new(&db_path).await.map_err(|e| {
-        error!("Failed to initialize database: {}", e);
-        anyhow::anyhow!("Database initialization failed: {}", e)
-    })?;
-    info!("Database initialized: {}", db_path);
+    let db = open_database(&db_path, read_only).await?;
+
+    // TTL of cached stats / top-strategies responses (0 disables the cache)
+    let cache_ttl_secs: u64 = std::env::var("POLY_DISCOVERY_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(10);
 
     let state = AppState {
-        binance: Arc::new(BinanceClient::new()),
-        polymarket: Arc::new(PolymarketDataClient::new()),
-        db: Arc::new(db),
-        discovery_progress: Arc::new(DiscoveryProgress::new()),
-        optimize_progress: Arc::new(OptimizeProgress::new()),
-        leaderboard_progress: Arc::new(LeaderboardProgress::new()),
-        watcher_progress: Arc::new(WatcherProgress::new()),
-        profile_progress: Arc::new(ProfileProgress::new()),
-        ob_backtest_progress: Arc::new(ObBacktestProgress::new()),
-        ob_collector_progress: Arc::new(ObCollectorProgress::new()),
+        read_only,
+        ..app_state(db, cache_ttl_secs)?
     };
+    if read_only {
+        info!(
+            "Read-only mode: migrations, mutating endpoints, workers and nightly maintenance are off"
+        );
+    } else {
+        restore_run_crashes(&state).await;
+    }
+    if state.retention.is_enabled() {
+        info!(
+            policy = ?state.retention,
+            "Knowledge base retention applied after each discovery run/cycle"
+        );
+    }
+    if !state.excluded_strategy_types.is_empty() {
+        info!(
+            excluded = ?state.excluded_strategy_types,
+            "Strategy types excluded from every discovery run"
+        );
+    }
+    let local_workers = if read_only { 0 } else { local_workers };
+    for i in 1..=local_workers {
+        tokio::spawn(engine::run_local_worker(
+            state.coordinator.clone(),
+            state.binance.clone(),
+            format!("local-{}", i),
+            std::time::Duration::from_secs(1),
+        ));
+    }
+    if local_workers > 0 {
+        info!(local_workers, "Local workers attached to the work queue");
+    }
+
+    // Optional nightly maintenance (cleanup + ANALYZE + incremental VACUUM)
+    let maintenance_hour: Option<u32> = std::env::var("POLY_DISCOVERY_MAINTENANCE_HOUR")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|h| *h < 24);
+    let maintenance_keep: i64 = std::env::var("POLY_DISCOVERY_MAINTENANCE_KEEP")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3);
+    if let Some(hour) = maintenance_hour.filter(|_| !read_only) {
+        info!(
+            "Nightly maintenance scheduled at {:02}:00 UTC (keep top {} per strategy)",
+            hour, maintenance_keep
+        );
+        spawn_nightly_maintenance(state.clone(), hour, maintenance_keep);
+    }
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
-
-    // Determine static files directory
-    let exe_path = std::env::current_exe().unwrap_or_default();
-    let exe_dir = exe_path.parent().unwrap_or(std::path::Path::new("."));
-    let dist_dir = exe_dir.join("dist");
-    let static_dir = if dist_dir.exists() {
-        dist_dir
-    } else {
-        std::path::PathBuf::from("dist")
-    };
+    // gzip when the client sends Accept-Encoding: gzip (export files already are)
+    let compression = CompressionLayer::new().compress_when(
+        DefaultPredicate::new().and(NotForContentType::const_new("application/gzip")),
+    );
 
     let api_routes = Router::new()
         .route("/health", get(api_health))
         .route("/discover", post(api_start_discovery))
         .route("/discover/status", get(api_discovery_status))
+        .route("/discover/estimate", post(api_estimate_discovery))
+        .route("/backtest", post(api_manual_backtest))
         .route("/discover/cancel", post(api_cancel_discovery))
+        .route("/discover/pause", post(api_pause_discovery))
+        .route("/discover/resume", post(api_resume_discovery))
+        .route("/discover/pacing", put(api_set_discovery_pacing))
+        .route("/workers", get(api_workers_status))
+        .route("/workers/lease", post(api_worker_lease))
+        .route("/workers/chunks/:id/results", post(api_worker_results))
+        .route("/workers/chunks/:id/fail", post(api_worker_fail))
+        .route("/presets", get(api_list_presets))
+        .route(
+            "/presets/:name",
+            get(api_get_preset)
+                .put(api_save_preset)
+                .delete(api_delete_preset),
+        )
         .route("/knowledge", get(api_knowledge_base))
         .route("/knowledge/top-strategies", get(api_top_strategies))
         .route("/knowledge/stats", get(api_knowledge_stats))
+        .route("/knowledge/fee-sensitivity", post(api_fee_sensitivity))
+        .route("/knowledge/fill-sensitivity", post(api_fill_sensitivity))
+        .route("/ensemble/build", post(api_build_ensemble))
+        .route("/knowledge/:id", get(api_knowledge_detail))
+        .route("/knowledge/:id/explain", get(api_knowledge_explain))
+        .route("/knowledge/:id/stub.py", get(api_knowledge_stub))
+        .route("/knowledge/:id/validate", post(api_validate_strategy))
+        .route("/knowledge/:id/history", get(api_strategy_history))
+        .route("/knowledge/:id/similar", get(api_knowledge_similar))
+        .route("/knowledge/:id/chart.png", get(api_knowledge_chart_png))
+        .route("/knowledge/:id/chart.svg", get(api_knowledge_chart_svg))
+        .route("/knowledge/:id/daily-pnl", get(api_knowledge_daily_pnl))
+        .route("/knowledge/:id/costs", get(api_knowledge_costs))
+        .route(
+            "/knowledge/:id/resolution-backtest",
+            post(api_resolution_backtest),
+        )
+        .route(
+            "/knowledge/:id/pipeline",
+            get(api_pipeline_status).post(api_pipeline_transition),
+        )
+        .route(
+            "/knowledge/:id/risk",
+            get(api_risk_status).put(api_set_risk_limits),
+        )
+        .route("/knowledge/:id/risk/resume", post(api_resume_strategy))
+        .route("/knowledge/:id/paper-trade", post(api_paper_trade))
+        .route("/paper-trade/portfolio", post(api_paper_trade_portfolio))
+        .route("/pipeline", get(api_pipeline_overview))
+        .route("/pipeline/apply-rules", post(api_apply_pipeline_rules))
         .route("/export", get(api_export))
+        .route("/export/ndjson", get(api_export_ndjson))
+        .route("/export/jobs", post(api_start_export_job))
+        .route("/export/jobs/:id", get(api_export_job_status))
+        .nest_service("/export/files", ServeDir::new(state.export_jobs.dir()))
+        .route("/runs/compare", get(api_compare_runs))
+        .route("/runs/:run_id/data-quality", get(api_run_data_quality))
+        .route("/runs/:run_id/cycles", get(api_run_cycles))
+        .route("/runs/:run_id/crashes", get(api_run_crashes))
+        .route("/runs/:run_id/cycles/:cycle/grid", get(api_run_cycle_grid))
         .route("/optimize", post(api_start_optimization))
         .route("/optimize/status", get(api_optimize_status))
+        .route("/optimize/history", get(api_optimize_history))
         .route("/binance/klines", get(api_binance_klines))
+        .route("/klines/diff", get(api_klines_diff))
+        .route("/symbols", get(api_symbols))
         .route("/leaderboard", post(api_analyze_leaderboard))
         .route("/leaderboard/status", get(api_leaderboard_status))
         .route("/leaderboard/traders", get(api_leaderboard_traders))
         .route("/watcher/start", post(api_start_watcher))
         .route("/watcher/stop", post(api_stop_watcher))
         .route("/watcher/status", get(api_watcher_status))
+        .route("/gabagool/scanner/start", post(api_start_gabagool_scanner))
+        .route("/gabagool/scanner/stop", post(api_stop_gabagool_scanner))
+        .route("/gabagool/opportunities", get(api_gabagool_opportunities))
         .route("/strategies/catalog", get(api_strategies_catalog))
+        .route("/strategies/schema", get(api_strategies_schema))
         .route("/profile/analyze", post(api_start_profile_analysis))
         .route("/profile/status", get(api_profile_status))
         .route("/profile/cancel", post(api_cancel_profile_analysis))
@@ -226,36 +894,96 @@ async fn cmd_serve(host: &str, port: u16) -> anyhow::Result<()> {
         .route("/orderbook/collector/stop", post(api_stop_ob_collector))
         .route("/orderbook/collector/status", get(api_ob_collector_status))
         .route("/orderbook/cleanup", post(api_ob_cleanup))
-        .with_state(state);
-
+        .route("/admin/maintenance", post(api_start_maintenance))
+        .route("/admin/maintenance/status", get(api_maintenance_status))
+        .route("/admin/backup", post(api_backup))
+        .route("/admin/cache", get(api_cache_stats))
+        .route("/openapi.json", get(openapi::openapi_json))
+        .route("/docs", get(openapi::swagger_ui))
+        .layer(from_fn_with_state(state.clone(), read_only_guard));
+
+    #[cfg(feature = "grpc")]
+    let grpc_state = state.clone();
     let app = Router::new()
-        .nest("/api", api_routes)
-        .fallback_service(ServeDir::new(&static_dir))
-        .layer(cors);
+        .nest("/api/v1", api_routes.clone())
+        // Pre-v1 paths: same handlers, bodies flattened back to the old shapes
+        .nest("/api", api_routes.layer(map_response(dto::legacy_envelope)))
+        .with_state(state);
+    // Web UI: compiled into the binary with `embed-ui`, else read from dist/
+    #[cfg(feature = "embed-ui")]
+    let app = app.fallback(assets::serve_asset);
+    #[cfg(not(feature = "embed-ui"))]
+    let app = app.fallback_service(ServeDir::new(static_dir()));
+    let app = app.layer(compression).layer(cors);
 
     let addr: std::net::SocketAddr = format!("{}:{}", host, port).parse()?;
     println!("\n=== Poly-Discover v{} ===", APP_VERSION);
     println!("Discovery Agent Server");
     println!("Listening on http://{}", addr);
-    println!("\nEndpoints:");
+    println!("\nEndpoints (under /api/v1, legacy aliases under /api):");
     println!("  GET  /api/health              - Health check");
     println!("  POST /api/discover            - Start discovery scan");
     println!("  GET  /api/discover/status     - Poll discovery progress");
     println!("  POST /api/discover/cancel     - Cancel running discovery");
-    println!("  GET  /api/knowledge           - Knowledge base (paginated)");
+    println!("  POST /api/discover/pause      - Hold running discovery (state kept)");
+    println!("  POST /api/discover/resume     - Resume paused discovery");
+    println!("  PUT  /api/discover/pacing     - Backtest rate / CPU share of the running scan");
+    println!("  POST /api/discover/estimate   - Dry run: grid size, cached/new split, runtime");
+    println!("  GET  /api/workers             - Remote workers and their Phase 1 chunk queue");
+    println!("  POST /api/workers/lease       - Worker: lease the next Phase 1 chunk");
+    println!("  POST /api/workers/chunks/{{id}}/results|fail - Worker: post or release a chunk");
+    println!("  POST /api/backtest            - One-off backtest of a strategy (optionally saved)");
+    println!("  GET  /api/presets             - Saved discovery requests");
+    println!("  GET|PUT|DELETE /api/presets/:name - Load, save or delete a discovery preset");
+    println!("  GET  /api/knowledge           - Knowledge base (offset or ?cursor= keyset pages)");
     println!("  GET  /api/knowledge/top-strategies - Top unique strategies");
     println!("  GET  /api/knowledge/stats     - Knowledge base stats");
+    println!("  POST /api/knowledge/fee-sensitivity - Top strategies re-run under each fee preset");
+    println!("  POST /api/knowledge/fill-sensitivity - Top strategies under each fill model");
+    println!("  POST /api/ensemble/build      - Backtest and store a voting ensemble of records");
+    println!("  GET  /api/knowledge/{{id}}       - One record, with its exit breakdown");
+    println!("  GET  /api/knowledge/{{id}}/explain  - Strategy rules, sizing and fees as text");
+    println!("  GET  /api/knowledge/{{id}}/stub.py  - Backtrader or QuantConnect strategy stub");
+    println!("  POST /api/knowledge/{{id}}/validate - Re-run a stored strategy on fresh klines");
+    println!("  GET  /api/knowledge/{{id}}/history  - Validation history, drift and decay flag");
+    println!("  GET  /api/knowledge/{{id}}/similar  - Records with overlapping indicators and nearby params");
+    println!("  GET  /api/knowledge/{{id}}/chart.png - Equity + drawdown chart (also chart.svg)");
+    println!("  GET  /api/knowledge/{{id}}/daily-pnl - PnL per calendar day (heatmap)");
+    println!("  GET  /api/knowledge/{{id}}/costs - Signal PnL, timing cost and fees to net PnL");
+    println!("  POST /api/knowledge/{{id}}/resolution-backtest - Bet on resolved Polymarket up/down markets");
+    println!("  GET  /api/knowledge/{{id}}/pipeline - Pipeline stage (POST to move it)");
+    println!("  GET  /api/knowledge/{{id}}/risk  - Risk limits + breaches (PUT to set them)");
+    println!("  POST /api/knowledge/{{id}}/risk/resume - Clear a risk halt");
+    println!("  POST /api/knowledge/{{id}}/paper-trade - Paper trade under the risk limits");
+    println!("  POST /api/paper-trade/portfolio - Paper trade records over one capital pool");
+    println!("  GET  /api/pipeline            - Records per pipeline stage");
+    println!("  POST /api/pipeline/apply-rules - Run the automatic promotion / rejection rules");
     println!("  GET  /api/export              - Export results as JSON");
+    println!("  GET  /api/export/ndjson       - Stream the whole knowledge base as NDJSON");
+    println!("  POST /api/export/jobs         - Write a gzipped NDJSON export to the export dir");
+    println!("  GET  /api/export/jobs/{{id}}    - Export job status and download link");
+    println!("  GET  /api/runs/compare        - Diff two discovery runs (latest vs previous)");
+    println!("  GET  /api/runs/{{run_id}}/data-quality - Kline quality of a run's symbols");
+    println!("  GET  /api/runs/{{run_id}}/cycles     - Per-cycle summaries of a continuous run");
+    println!("  GET  /api/runs/{{run_id}}/crashes    - Panics of a supervised run's task");
+    println!("  GET  /api/runs/{{run_id}}/cycles/{{cycle}}/grid - Strategies a cycle ran");
     println!("  POST /api/optimize            - Start parameter optimization");
     println!("  GET  /api/optimize/status     - Poll optimization progress");
+    println!("  GET  /api/optimize/history    - Results of past optimization sweeps");
     println!("  GET  /api/binance/klines      - Fetch Binance klines (proxy)");
+    println!("  GET  /api/klines/diff         - Cached discovery klines vs a fresh fetch");
+    println!("  GET  /api/symbols             - Tick size, lot step and minimums of symbols");
     println!("  POST /api/leaderboard         - Analyze top Polymarket traders");
     println!("  GET  /api/leaderboard/status  - Poll leaderboard analysis progress");
     println!("  GET  /api/leaderboard/traders - Get persisted traders from DB");
     println!("  POST /api/watcher/start       - Start trade watcher");
     println!("  POST /api/watcher/stop        - Stop trade watcher");
     println!("  GET  /api/watcher/status      - Poll trade watcher status + alerts");
+    println!("  POST /api/gabagool/scanner/start - Start live Gabagool opportunity scanner");
+    println!("  POST /api/gabagool/scanner/stop  - Stop Gabagool scanner");
+    println!("  GET  /api/gabagool/opportunities - Live YES+NO pairs below max_pair_cost");
     println!("  GET  /api/strategies/catalog  - Web-researched strategies catalog");
+    println!("  GET  /api/strategies/schema   - JSON Schema of the strategy parameters");
     println!("  POST /api/profile/analyze     - Analyze a Polymarket user profile");
     println!("  GET  /api/profile/status      - Poll profile analysis progress");
     println!("  POST /api/profile/cancel      - Cancel profile analysis");
@@ -269,9 +997,30 @@ async fn cmd_serve(host: &str, port: u16) -> anyhow::Result<()> {
     println!("  POST /api/orderbook/collector/stop   - Stop live collector");
     println!("  GET  /api/orderbook/collector/status  - Poll collector status");
     println!("  POST /api/orderbook/cleanup  - Manual data cleanup");
+    println!("  POST /api/admin/maintenance  - Run KB cleanup + ANALYZE + incremental VACUUM");
+    println!("  GET  /api/admin/maintenance/status - Poll maintenance progress");
+    println!("  POST /api/admin/backup       - Write a compressed KB snapshot to the backup dir");
+    println!("  GET  /api/admin/cache        - Query cache hit/miss metrics");
+    println!("  GET  /api/openapi.json       - OpenAPI specification");
+    println!("  GET  /api/docs               - Swagger UI");
     println!("\n  Database: {}", db_path);
     println!("\nPress Ctrl+C to stop\n");
 
+    if let Some(grpc_port) = grpc_port {
+        let grpc_addr: std::net::SocketAddr = format!("{}:{}", host, grpc_port).parse()?;
+        #[cfg(feature = "grpc")]
+        {
+            println!("gRPC API (proto/discovery.proto) on {}\n", grpc_addr);
+            tokio::spawn(async move {
+                if let Err(e) = grpc::serve(grpc_state, grpc_addr).await {
+                    error!("gRPC server stopped: {}", e);
+                }
+            });
+        }
+        #[cfg(not(feature = "grpc"))]
+        anyhow::bail!("--grpc-port {} needs the grpc feature", grpc_addr);
+    }
+
     let listener = tokio::net::TcpListener::bind(addr).await?;
     axum::serve(listener, app).await?;
 
@@ -282,15 +1031,44 @@ async fn cmd_serve(host: &str, port: u16) -> anyhow::Result<()> {
 // Run command — CLI mode (no web server)
 // ============================================================================
 
+#[allow(clippy::too_many_arguments)]
 async fn cmd_run(
     symbols: Vec<String>,
     days: u32,
     top_n: usize,
+    per_symbol_top_n: Option<usize>,
     sizing: String,
     export: Option<String>,
     continuous: bool,
+    onnx_models: Vec<String>,
+    time_budget_ms: u64,
+    ruin_threshold: Decimal,
+    repair_gaps: bool,
+    data_source: String,
+    fees: String,
+    probability_model: String,
+    fill_model: String,
+    max_participation_pct: Option<f64>,
+    cancel_unfilled: bool,
+    candles: String,
+    throttle_search: bool,
+    cross_symbol_weight: Option<Decimal>,
+    refinement: RefinementDepth,
+    local_search: bool,
+    adaptive_cycles: bool,
+    decay_half_life: Option<f64>,
+    underwater_penalty: Decimal,
+    rank_by: String,
+    max_exposure_pct: Option<f64>,
+    exclude: Vec<String>,
+    pacing: ScanPacing,
+    workspace: String,
+    stream_out: Option<String>,
+    dry_run_cycle: Option<u32>,
+    preset: Option<String>,
 ) -> anyhow::Result<()> {
     println!("\n=== Poly-Discover v{} ===", APP_VERSION);
+    let workspace = Workspace::parse(&workspace).map_err(anyhow::Error::msg)?;
 
     let db_path =
         std::env::var("POLY_DISCOVERY_DB_PATH").unwrap_or_else(|_| "data/discovery.db".to_string());
@@ -298,55 +1076,180 @@ async fn cmd_run(
         .await
         .map_err(|e| anyhow::anyhow!("Database initialization failed: {}", e))?;
 
+    let mut request = match &preset {
+        Some(name) => load_preset_request(&db, &workspace, name).await?,
+        None => {
+            if let Some(weight) = cross_symbol_weight {
+                validate_cross_symbol_weight(weight).map_err(anyhow::Error::msg)?;
+            }
+            refinement.validate().map_err(anyhow::Error::msg)?;
+            if let Some(days) = decay_half_life {
+                validate_decay_half_life(days).map_err(anyhow::Error::msg)?;
+            }
+            validate_underwater_penalty(underwater_penalty).map_err(anyhow::Error::msg)?;
+            if let Some(pct) = max_exposure_pct {
+                validate_max_exposure(pct).map_err(anyhow::Error::msg)?;
+            }
+            pacing.validate().map_err(anyhow::Error::msg)?;
+            let participation = max_participation_pct.map(|max_pct| ParticipationLimit {
+                max_pct,
+                remainder: if cancel_unfilled {
+                    UnfilledRemainder::Cancel
+                } else {
+                    UnfilledRemainder::Carry
+                },
+            });
+            if let Some(limit) = &participation {
+                limit.validate().map_err(anyhow::Error::msg)?;
+            }
+            DiscoveryRequest {
+                symbols,
+                days,
+                top_n: Some(top_n),
+                per_symbol_top_n,
+                sizing_mode: Some(parse_sizing_mode(&sizing)),
+                continuous: Some(continuous),
+                onnx_models,
+                time_budget_ms: Some(time_budget_ms),
+                ruin_threshold_pct: Some(ruin_threshold),
+                repair_gaps: Some(repair_gaps),
+                data_source: Some(data_source),
+                fee_preset: Some(FeePreset::parse(&fees).map_err(anyhow::Error::msg)?),
+                probability_model: Some(
+                    ProbabilityModelConfig::parse(&probability_model)
+                        .map_err(anyhow::Error::msg)?,
+                ),
+                fill_model: Some(FillModel::parse(&fill_model).map_err(anyhow::Error::msg)?),
+                participation,
+                candle_transform: Some(
+                    CandleTransform::parse(&candles).map_err(anyhow::Error::msg)?,
+                ),
+                throttle_search: Some(throttle_search),
+                cross_symbol: Some(cross_symbol_weight.is_some()),
+                cross_symbol_weight,
+                refinement_top_k: Some(refinement.top_k),
+                refinement_rounds: Some(refinement.rounds),
+                refinement_delta_scale: Some(refinement.delta_scale),
+                local_search: Some(local_search),
+                adaptive_cycles: Some(adaptive_cycles),
+                decay_half_life_days: decay_half_life,
+                underwater_penalty: Some(underwater_penalty),
+                max_exposure_pct,
+                exclude_strategy_types: exclude,
+                pacing: (!pacing.is_unlimited()).then_some(pacing),
+                rank_by: Some(parse_rank_order(&rank_by).map_err(anyhow::Error::msg)?),
+                restart_on_crash: None,
+            }
+        }
+    };
+    add_excluded_strategy_types(&mut request, &excluded_strategy_types()?);
+    let default_symbols = request.symbols.is_empty();
+    if default_symbols {
+        request.symbols = DEFAULT_SYMBOLS.iter().map(|s| s.to_string()).collect();
+    }
+    let source =
+        DataSource::from_option(request.data_source.as_deref()).map_err(anyhow::Error::msg)?;
+    let fee_preset = request.fee_preset.clone().unwrap_or_default();
+    let probability_model = request.probability_model.clone().unwrap_or_default();
+    let fill_model = request.fill_model.unwrap_or_default();
+    let continuous = request.continuous.unwrap_or(false);
+    let cross_symbol_weight = request
+        .cross_symbol
+        .unwrap_or(false)
+        .then(|| request.cross_symbol_weight.unwrap_or(Decimal::ZERO));
+
     // Check cached count
-    let repo = DiscoveryRepository::new(db.pool());
+    let repo = DiscoveryRepository::new(db.pool()).with_workspace(workspace.as_str());
     let cached_stats = repo.get_stats().await;
     let total_cached = cached_stats
         .as_ref()
         .map(|s| s.total_backtests)
         .unwrap_or(0);
-    println!("Database: {} ({} backtests cached)", db_path, total_cached);
+    println!(
+        "Database: {} ({} backtests cached in {})",
+        db_path,
+        total_cached,
+        workspace.as_str()
+    );
+    if let Some(name) = &preset {
+        println!("Preset: {}", name);
+    }
     println!(
         "Symbols: {}",
-        if symbols.is_empty() {
+        if default_symbols {
             "BTC, ETH, SOL, XRP (default)".to_string()
         } else {
-            symbols.join(", ")
+            request.symbols.join(", ")
         }
     );
     println!(
-        "Days: {} | Sizing: {} | Top N: {} | Mode: {}",
-        days,
-        sizing,
-        top_n,
+        "Days: {} | Sizing: {:?} | Top N: {} | Mode: {}",
+        request.days,
+        request.sizing_mode.unwrap_or_default(),
+        request.top_n.unwrap_or(10),
         if continuous { "CONTINUOUS" } else { "single" }
     );
-    if continuous {
+    if let Some(count) = request.per_symbol_top_n.filter(|count| *count > 0) {
+        println!("Per symbol: at least {} results each", count);
+    }
+    // The per-symbol quotas may push the final ranking past top N
+    let shown = match request.per_symbol_top_n {
+        Some(count) if count > 0 => usize::MAX,
+        _ => top_n,
+    };
+    if !request.onnx_models.is_empty() {
+        println!("ONNX models: {}", request.onnx_models.join(", "));
+    }
+    if let DataSource::File(path) = &source {
+        println!("Klines: {} (offline)", path.display());
+    }
+    if fee_preset != FeePreset::Polymarket {
+        println!("Fees: {} (what-if run, nothing stored)", fee_preset.name());
+    }
+    if probability_model.needs_calibration() {
+        println!("Probability model: logistic, calibrated on resolved markets");
+    } else if probability_model != ProbabilityModelConfig::Linear {
+        println!("Probability model: {}", probability_model.name());
+    }
+    if fill_model != FillModel::Close {
+        println!("Fills: {} (what-if run, nothing stored)", fill_model.name());
+    }
+    if let Some(limit) = &request.participation {
+        println!(
+            "Volume cap: {} (what-if run, nothing stored)",
+            limit.label()
+        );
+    }
+    if let Some(candles) = request.candle_transform.filter(|c| !c.is_raw()) {
+        println!("Candles: {} (what-if run, nothing stored)", candles.name());
+    }
+    if request.throttle_search.unwrap_or(false) && !continuous {
+        println!("Throttle search: best results re-run under drawdown throttles");
+    }
+    if let Some(weight) = cross_symbol_weight.filter(|_| !continuous) {
+        println!(
+            "Cross-symbol validation: best results re-run on the other symbols (weight {})",
+            weight
+        );
+    }
+    if continuous && dry_run_cycle.is_none() {
         println!("Press Ctrl+C to stop");
     }
     println!();
 
     let binance = Arc::new(BinanceClient::new());
     let progress = Arc::new(DiscoveryProgress::new());
-    let db_pool = Some(db.pool_clone());
-
-    let sizing_mode = parse_sizing_mode(&sizing);
-    let request = DiscoveryRequest {
-        symbols: if symbols.is_empty() {
-            vec![
-                "BTCUSDT".to_string(),
-                "ETHUSDT".to_string(),
-                "SOLUSDT".to_string(),
-                "XRPUSDT".to_string(),
-            ]
-        } else {
-            symbols
-        },
-        days,
-        top_n: Some(top_n),
-        sizing_mode: Some(sizing_mode),
-        continuous: Some(continuous),
-    };
+    let store = Arc::new(
+        SqliteResultStore::new(db.pool_clone())
+            .with_workspace(workspace.as_str())
+            .with_retention(retention_policy()),
+    );
+
+    if let Some(cycle) = dry_run_cycle {
+        let estimate = estimate_discovery(&request, Some(cycle), Some(store)).await;
+        print_estimate(&estimate);
+        return Ok(());
+    }
 
     // Set up Ctrl+C handler for continuous mode
     let progress_for_ctrlc = progress.clone();
@@ -358,17 +1261,27 @@ async fn cmd_run(
             .store(true, std::sync::atomic::Ordering::Relaxed);
     });
 
-    // Spawn discovery in background and monitor progress
-    let progress_clone = progress.clone();
-    let discovery_handle = if continuous {
-        tokio::spawn(async move {
-            run_continuous_discovery(request, binance, progress_clone, db_pool).await;
-        })
-    } else {
-        tokio::spawn(async move {
-            run_discovery(request, binance, progress_clone, db_pool).await;
-        })
+    // Spawn discovery in background (supervised: a panic ends in the Error status)
+    // and monitor progress
+    let result_sink = match stream_out {
+        Some(path) => {
+            let sink = NdjsonResultSink::open(&path)?;
+            println!("Streaming results to {}", sink.path().display());
+            Some(Arc::new(sink))
+        }
+        None => None,
     };
+    let engine_progress = progress.clone();
+    let discovery_handle = tokio::spawn(supervise(move || {
+        let engine = DiscoveryEngine::from_request(request.clone())
+            .with_binance(binance.clone())
+            .with_store(store.clone())
+            .with_progress(engine_progress.clone());
+        match &result_sink {
+            Some(sink) => engine.with_result_sink(sink.clone()),
+            None => engine,
+        }
+    }));
 
     // Progress display loop
     loop {
@@ -383,6 +1296,15 @@ async fn cmd_run(
             .load(std::sync::atomic::Ordering::Relaxed);
         let skipped = progress.skipped.load(std::sync::atomic::Ordering::Relaxed);
         let pct = progress.progress_pct();
+        let estimate = progress.estimate();
+        let eta = format!(
+            "{:.0}/s, ETA {}",
+            estimate.throughput_per_sec,
+            estimate
+                .eta_secs
+                .map(format_eta)
+                .unwrap_or_else(|| "--".to_string())
+        );
 
         match status {
             DiscoveryStatus::FetchingData => {
@@ -415,8 +1337,8 @@ async fn cmd_run(
 
                 if continuous {
                     print!(
-                        "\r  Cycle {} — {} [{}] {:.0}% ({}/{}, {} cached) — {}   ",
-                        current_cycle, phase, bar, pct, completed, total, skipped, phase
+                        "\r  Cycle {} — {} [{}] {:.0}% ({}/{}, {} cached, {}) — {}   ",
+                        current_cycle, phase, bar, pct, completed, total, skipped, eta, phase
                     );
                     print!(
                         "\n  Total: {} tested | {} new this cycle | Best: {:.1}           \x1b[1A",
@@ -429,8 +1351,8 @@ async fn cmd_run(
                         "Phase 2: Refinement"
                     };
                     print!(
-                        "\r  {} [{}] {:.0}% ({}/{}, {} cached) — {}   ",
-                        phase_label, bar, pct, completed, total, skipped, phase
+                        "\r  {} [{}] {:.0}% ({}/{}, {} cached, {}) — {}   ",
+                        phase_label, bar, pct, completed, total, skipped, eta, phase
                     );
                 }
             }
@@ -480,13 +1402,13 @@ async fn cmd_run(
         }
         print_results(&best, top_n);
     } else {
-        print_results(&results, top_n);
+        print_results(&results, shown);
     }
 
     // Export if requested
     if let Some(export_path) = export {
         let results = progress.final_results.read().unwrap().clone();
-        let export_data = build_export_json(&results, top_n, None);
+        let export_data = build_export_json(&results, shown, None);
         let json = serde_json::to_string_pretty(&export_data)?;
         std::fs::write(&export_path, &json)?;
         println!("\nResults exported to {}", export_path);
@@ -495,6 +1417,68 @@ async fn cmd_run(
     Ok(())
 }
 
+/// `1h05m`, `4m12s`, `37s`
+fn format_eta(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Dry-run summary of `run --dry-run`
+fn print_estimate(estimate: &DiscoveryEstimate) {
+    println!("Dry run — {}", estimate.phase);
+    println!(
+        "Grid: {} strategies × {} symbols × days {:?} × {} sizing modes + {} cross-sectional",
+        estimate.grid_size,
+        estimate.symbols,
+        estimate.days,
+        estimate.sizing_modes.len(),
+        estimate.cross_sectional
+    );
+    println!(
+        "Combinations: {} ({} cached, {} new{})",
+        estimate.total_combinations,
+        estimate.cached,
+        estimate.new,
+        if estimate.skipped_units > 0 {
+            format!(", {} in fully tested grid units", estimate.skipped_units)
+        } else {
+            String::new()
+        }
+    );
+    if estimate.refinement_estimate > 0 {
+        println!(
+            "Refinement: ~{} more after the grid",
+            estimate.refinement_estimate
+        );
+    }
+    if !estimate.uses_cache {
+        println!("Knowledge base not read (what-if fee preset): everything runs");
+    }
+    println!();
+    println!(
+        "{:<24} {:>12} {:>10}",
+        "Strategy type", "Combinations", "Cached"
+    );
+    for count in &estimate.by_strategy_type {
+        println!(
+            "{:<24} {:>12} {:>10}",
+            count.strategy_type, count.combinations, count.cached
+        );
+    }
+    println!();
+    match (estimate.throughput_per_sec, estimate.estimated_runtime_secs) {
+        (Some(rate), Some(secs)) => println!(
+            "Estimated runtime: {} at {:.1} backtests/s (latest run)",
+            format_eta(secs),
+            rate
+        ),
+        _ => println!("Estimated runtime: unknown (no timed run in the knowledge base yet)"),
+    }
+}
+
 fn print_results(results: &[DiscoveryResult], top_n: usize) {
     println!("\nTop {} Results:", results.len().min(top_n));
     println!(
@@ -514,40 +1498,88 @@ fn print_results(results: &[DiscoveryResult], top_n: usize) {
             r.sharpe_ratio,
         );
     }
+    let capped: Vec<_> = results
+        .iter()
+        .take(top_n)
+        .filter_map(|r| r.unfilled)
+        .collect();
+    if !capped.is_empty() {
+        let entries: u32 = capped.iter().map(|u| u.entries).sum();
+        let partial: u32 = capped.iter().map(|u| u.partial_entries).sum();
+        let unfilled_pct = capped.iter().map(|u| u.unfilled_pct).sum::<f64>() / capped.len() as f64;
+        println!(
+            "\n  Volume cap: {} of {} entries partly filled, {:.1}% of the ordered size unfilled (mean)",
+            partial, entries, unfilled_pct
+        );
+    }
 }
 
 // ============================================================================
 // API Handlers — Discovery
 // ============================================================================
 
-/// GET /api/health
-async fn api_health() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "status": "ok",
-        "service": "poly-discover",
-        "version": APP_VERSION,
-    }))
+/// GET /api/v1/health
+#[utoipa::path(
+    get,
+    path = "/api/v1/health",
+    tag = "system",
+    responses((status = 200, description = "OK", body = ApiResponse<HealthResponse>))
+)]
+async fn api_health(State(state): State<AppState>) -> ApiResponse<HealthResponse> {
+    ApiResponse::ok(HealthResponse {
+        status: "ok",
+        service: "poly-discover",
+        version: APP_VERSION,
+        read_only: state.read_only,
+    })
 }
 
-/// POST /api/discover — start a discovery scan
+/// POST /api/v1/discover — start a discovery scan
+#[utoipa::path(
+    post,
+    path = "/api/v1/discover",
+    tag = "discovery",
+    params(Workspace),
+    request_body = DiscoveryRequest,
+    responses(
+        (status = 200, description = "Started", body = ApiResponse<DiscoveryStarted>),
+        (status = 409, description = "A discovery is already running", body = ErrorResponse),
+    )
+)]
 async fn api_start_discovery(
     State(state): State<AppState>,
+    workspace: Workspace,
     Json(request): Json<DiscoveryRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> ApiResult<DiscoveryStarted> {
+    start_discovery(&state, &workspace, request).map(ApiResponse::ok)
+}
+
+/// Validate `request` and run it in the background (HTTP and gRPC)
+fn start_discovery(
+    state: &AppState,
+    workspace: &Workspace,
+    mut request: DiscoveryRequest,
+) -> Result<DiscoveryStarted, ApiError> {
+    if state.read_only {
+        return Err(ApiError::read_only());
+    }
     if state.discovery_progress.is_running() {
         let pct = state.discovery_progress.progress_pct();
-        return Ok(Json(serde_json::json!({
-            "success": false,
-            "message": format!("Discovery agent already running ({:.0}% complete)", pct),
-        })));
+        return Err(ApiError::already_running(format!(
+            "Discovery agent already running ({:.0}% complete)",
+            pct
+        )));
     }
 
+    validate_discovery_request(&request)?;
+    add_excluded_strategy_types(&mut request, &state.excluded_strategy_types);
     let is_continuous = request.continuous.unwrap_or(false);
 
     info!(
         symbols = ?request.symbols,
         days = request.days,
         continuous = is_continuous,
+        workspace = workspace.as_str(),
         "Starting discovery agent"
     );
 
@@ -555,41 +1587,457 @@ async fn api_start_discovery(
 
     let binance = state.binance.clone();
     let progress = state.discovery_progress.clone();
-    let db_pool = Some(state.db.pool_clone());
+    let store = Arc::new(
+        SqliteResultStore::new(state.db.pool_clone())
+            .with_workspace(workspace.as_str())
+            .with_retention((*state.retention).clone()),
+    );
 
-    if is_continuous {
-        tokio::spawn(async move {
-            run_continuous_discovery(request, binance, progress, db_pool).await;
-        });
+    let coordinator = state.coordinator.clone();
+    let build = move || {
+        DiscoveryEngine::from_request(request.clone())
+            .with_binance(binance.clone())
+            .with_store(store.clone())
+            .with_progress(progress.clone())
+            .with_coordinator(coordinator.clone())
+    };
+    let db_pool = state.db.pool_clone();
+    tokio::spawn(async move {
+        if let Some(Ok(_)) = supervise(build).await {
+            // New results may clear the promotion thresholds
+            match apply_pipeline_rules(&db_pool, &PipelineRules::default()).await {
+                Ok(report) => info!(
+                    promoted = report.promoted.len(),
+                    rejected = report.rejected.len(),
+                    "Pipeline rules applied after discovery"
+                ),
+                Err(e) => error!("Pipeline rules failed: {}", e),
+            }
+        }
+    });
+
+    let message = if is_continuous {
+        "Continuous discovery started"
     } else {
-        tokio::spawn(async move {
-            run_discovery(request, binance, progress, db_pool).await;
-        });
-    }
+        "Discovery agent started"
+    };
+    Ok(DiscoveryStarted {
+        message: message.to_string(),
+        continuous: is_continuous,
+    })
+}
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": if is_continuous { "Continuous discovery started" } else { "Discovery agent started" },
-        "continuous": is_continuous,
-    })))
+/// Discovery request saved as the preset `name` of `workspace`
+async fn load_preset_request(
+    db: &persistence::Database,
+    workspace: &Workspace,
+    name: &str,
+) -> anyhow::Result<DiscoveryRequest> {
+    let record = PresetRepository::new(db.pool())
+        .with_workspace(workspace.as_str())
+        .get(name)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No preset {} in workspace {}", name, workspace.as_str()))?;
+    let request: DiscoveryRequest = serde_json::from_str(&record.request)
+        .map_err(|e| anyhow::anyhow!("Preset {} is not a valid request: {}", name, e))?;
+    validate_discovery_request(&request).map_err(|e| anyhow::anyhow!(e.message))?;
+    Ok(request)
 }
 
-/// POST /api/discover/cancel — cancel running discovery
-async fn api_cancel_discovery(State(state): State<AppState>) -> Json<serde_json::Value> {
+/// Data source, fee preset, probability model, volume cap, candle transform, pacing,
+/// refinement depth, decay half-life, underwater penalty, max exposure, restart policy
+/// and excluded strategy types of a discovery request
+fn validate_discovery_request(request: &DiscoveryRequest) -> Result<(), ApiError> {
+    DataSource::from_option(request.data_source.as_deref()).map_err(ApiError::invalid_request)?;
+    if let Some(preset) = &request.fee_preset {
+        preset.validate().map_err(ApiError::invalid_request)?;
+    }
+    if let Some(model) = &request.probability_model {
+        model.validate().map_err(ApiError::invalid_request)?;
+    }
+    if let Some(weight) = request.cross_symbol_weight {
+        validate_cross_symbol_weight(weight).map_err(ApiError::invalid_request)?;
+    }
+    if let Some(days) = request.decay_half_life_days {
+        validate_decay_half_life(days).map_err(ApiError::invalid_request)?;
+    }
+    if let Some(per_day) = request.underwater_penalty {
+        validate_underwater_penalty(per_day).map_err(ApiError::invalid_request)?;
+    }
+    if let Some(pct) = request.max_exposure_pct {
+        validate_max_exposure(pct).map_err(ApiError::invalid_request)?;
+    }
+    if let Some(limit) = &request.participation {
+        limit.validate().map_err(ApiError::invalid_request)?;
+    }
+    if let Some(candles) = &request.candle_transform {
+        candles.validate().map_err(ApiError::invalid_request)?;
+    }
+    if let Some(pacing) = &request.pacing {
+        pacing.validate().map_err(ApiError::invalid_request)?;
+    }
+    if let Some(policy) = &request.restart_on_crash {
+        policy.validate().map_err(ApiError::invalid_request)?;
+    }
+    RefinementDepth::from_request(request)
+        .validate()
+        .map_err(ApiError::invalid_request)?;
+    StrategyExclusions::from_request(request)
+        .validate()
+        .map_err(ApiError::invalid_request)?;
+    Ok(())
+}
+
+/// Strategy types of `POLY_DISCOVERY_EXCLUDE_STRATEGY_TYPES` (comma-separated), left out
+/// of every discovery run and estimate on top of the request's own
+fn excluded_strategy_types() -> anyhow::Result<Vec<String>> {
+    let types: Vec<String> = std::env::var("POLY_DISCOVERY_EXCLUDE_STRATEGY_TYPES")
+        .unwrap_or_default()
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    StrategyExclusions::new(types.iter().cloned())
+        .validate()
+        .map_err(|e| anyhow::anyhow!("POLY_DISCOVERY_EXCLUDE_STRATEGY_TYPES: {}", e))?;
+    Ok(types)
+}
+
+fn add_excluded_strategy_types(request: &mut DiscoveryRequest, excluded: &[String]) {
+    for strategy_type in excluded {
+        if !request.exclude_strategy_types.contains(strategy_type) {
+            request.exclude_strategy_types.push(strategy_type.clone());
+        }
+    }
+}
+
+fn validate_cross_symbol_weight(weight: Decimal) -> Result<(), String> {
+    if weight < Decimal::ZERO || weight > Decimal::ONE {
+        return Err(format!(
+            "cross_symbol_weight must be between 0 and 1, got {}",
+            weight
+        ));
+    }
+    Ok(())
+}
+
+fn validate_decay_half_life(days: f64) -> Result<(), String> {
+    if !(days.is_finite() && days > 0.0) {
+        return Err(format!(
+            "decay_half_life_days must be a positive number of days, got {}",
+            days
+        ));
+    }
+    Ok(())
+}
+
+fn validate_underwater_penalty(per_day: Decimal) -> Result<(), String> {
+    if per_day < Decimal::ZERO {
+        return Err(format!(
+            "underwater_penalty must not be negative, got {}",
+            per_day
+        ));
+    }
+    Ok(())
+}
+
+fn validate_max_exposure(pct: f64) -> Result<(), String> {
+    if !(pct.is_finite() && pct > 0.0 && pct <= 100.0) {
+        return Err(format!(
+            "max_exposure_pct must be between 0 (excluded) and 100, got {}",
+            pct
+        ));
+    }
+    Ok(())
+}
+
+/// Longest name of a discovery preset
+const MAX_PRESET_NAME_LEN: usize = 64;
+
+fn validate_preset_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.trim() != name {
+        return Err("preset name must be non-empty, without surrounding spaces".to_string());
+    }
+    if name.chars().count() > MAX_PRESET_NAME_LEN {
+        return Err(format!(
+            "preset name is longer than {} characters",
+            MAX_PRESET_NAME_LEN
+        ));
+    }
+    Ok(())
+}
+
+fn preset_response(record: DiscoveryPresetRecord) -> Result<DiscoveryPreset, ApiError> {
+    let request = serde_json::from_str(&record.request).map_err(|e| {
+        ApiError::database(format!(
+            "Preset {} is not a valid request: {}",
+            record.name, e
+        ))
+    })?;
+    Ok(DiscoveryPreset {
+        name: record.name,
+        description: record.description,
+        request,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    })
+}
+
+/// Body of the preset save endpoint
+#[derive(Deserialize, utoipa::ToSchema)]
+struct SavePresetRequest {
+    description: Option<String>,
+    /// Discovery request started by `POST /discover` or `run --preset`
+    request: DiscoveryRequest,
+}
+
+/// GET /api/v1/presets — discovery presets of the workspace, by name
+#[utoipa::path(
+    get,
+    path = "/api/v1/presets",
+    tag = "discovery",
+    params(Workspace),
+    responses(
+        (status = 200, description = "Saved discovery requests", body = ApiResponse<Vec<DiscoveryPreset>>),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_list_presets(
+    State(state): State<AppState>,
+    workspace: Workspace,
+) -> ApiResult<Vec<DiscoveryPreset>> {
+    let records = PresetRepository::new(state.db.pool())
+        .with_workspace(workspace.as_str())
+        .list()
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load presets: {}", e)))?;
+    let presets = records
+        .into_iter()
+        .map(preset_response)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ApiResponse::list(presets))
+}
+
+/// GET /api/v1/presets/{name} — one discovery preset
+#[utoipa::path(
+    get,
+    path = "/api/v1/presets/{name}",
+    tag = "discovery",
+    params(Workspace, ("name" = String, Path, description = "Preset name")),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<DiscoveryPreset>),
+        (status = 404, description = "Unknown preset", body = ErrorResponse),
+    )
+)]
+async fn api_get_preset(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(name): axum::extract::Path<String>,
+) -> ApiResult<DiscoveryPreset> {
+    let record = PresetRepository::new(state.db.pool())
+        .with_workspace(workspace.as_str())
+        .get(&name)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load preset: {}", e)))?
+        .ok_or_else(|| ApiError::not_found(format!("Preset {} not found", name)))?;
+    Ok(ApiResponse::ok(preset_response(record)?))
+}
+
+/// PUT /api/v1/presets/{name} — create or replace a discovery preset
+#[utoipa::path(
+    put,
+    path = "/api/v1/presets/{name}",
+    tag = "discovery",
+    params(Workspace, ("name" = String, Path, description = "Preset name")),
+    request_body = SavePresetRequest,
+    responses(
+        (status = 200, description = "Preset stored", body = ApiResponse<DiscoveryPreset>),
+        (status = 400, description = "Invalid name or request", body = ErrorResponse),
+    )
+)]
+async fn api_save_preset(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(name): axum::extract::Path<String>,
+    Json(body): Json<SavePresetRequest>,
+) -> ApiResult<DiscoveryPreset> {
+    validate_preset_name(&name).map_err(ApiError::invalid_request)?;
+    validate_discovery_request(&body.request)?;
+    let request = serde_json::to_string(&body.request)
+        .map_err(|e| ApiError::invalid_request(format!("Unserializable request: {}", e)))?;
+    let record = PresetRepository::new(state.db.pool())
+        .with_workspace(workspace.as_str())
+        .save(&name, body.description.as_deref(), &request)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to save preset: {}", e)))?;
+    Ok(ApiResponse::ok(preset_response(record)?))
+}
+
+/// DELETE /api/v1/presets/{name} — delete a discovery preset
+#[utoipa::path(
+    delete,
+    path = "/api/v1/presets/{name}",
+    tag = "discovery",
+    params(Workspace, ("name" = String, Path, description = "Preset name")),
+    responses(
+        (status = 200, description = "Deleted", body = ApiResponse<Ack>),
+        (status = 404, description = "Unknown preset", body = ErrorResponse),
+    )
+)]
+async fn api_delete_preset(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(name): axum::extract::Path<String>,
+) -> ApiResult<Ack> {
+    let deleted = PresetRepository::new(state.db.pool())
+        .with_workspace(workspace.as_str())
+        .delete(&name)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to delete preset: {}", e)))?;
+    if !deleted {
+        return Err(ApiError::not_found(format!("Preset {} not found", name)));
+    }
+    Ok(ApiResponse::ok(Ack::new(format!(
+        "Preset {} deleted",
+        name
+    ))))
+}
+
+/// Query params for the discovery estimate endpoint
+#[derive(Deserialize)]
+struct EstimateParams {
+    /// Continuous cycle to estimate (default 0)
+    cycle: Option<u32>,
+}
+
+/// POST /api/v1/discover/estimate — dry run: grid size, cached/new split and runtime
+#[utoipa::path(
+    post,
+    path = "/api/v1/discover/estimate",
+    tag = "discovery",
+    params(
+        Workspace,
+        ("cycle" = Option<u32>, Query, description = "Continuous cycle to estimate (default 0; one-shot requests ignore it)"),
+    ),
+    request_body = DiscoveryRequest,
+    responses(
+        (status = 200, description = "Size of the run, nothing started", body = ApiResponse<DiscoveryEstimate>),
+        (status = 400, description = "Invalid data source, fee preset or probability model", body = ErrorResponse),
+    )
+)]
+async fn api_estimate_discovery(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Query(params): Query<EstimateParams>,
+    Json(mut request): Json<DiscoveryRequest>,
+) -> ApiResult<DiscoveryEstimate> {
+    validate_discovery_request(&request)?;
+    add_excluded_strategy_types(&mut request, &state.excluded_strategy_types);
+    let store =
+        Arc::new(SqliteResultStore::new(state.db.pool_clone()).with_workspace(workspace.as_str()));
+    let estimate = estimate_discovery(&request, params.cycle, Some(store)).await;
+    info!(
+        total = estimate.total_combinations,
+        new = estimate.new,
+        workspace = workspace.as_str(),
+        "Discovery estimated"
+    );
+    Ok(ApiResponse::ok(estimate))
+}
+
+/// POST /api/v1/discover/cancel — cancel running discovery
+#[utoipa::path(
+    post,
+    path = "/api/v1/discover/cancel",
+    tag = "discovery",
+    responses((status = 200, description = "OK", body = ApiResponse<Ack>))
+)]
+async fn api_cancel_discovery(State(state): State<AppState>) -> ApiResponse<Ack> {
     state
         .discovery_progress
         .cancelled
         .store(true, std::sync::atomic::Ordering::Relaxed);
     info!("Discovery cancel requested via API");
-    Json(serde_json::json!({
-        "success": true,
-        "message": "Cancel requested"
-    }))
+    ApiResponse::ok(Ack::new("Cancel requested"))
+}
+
+/// POST /api/v1/discover/pause — hold the running discovery between two backtests
+#[utoipa::path(
+    post,
+    path = "/api/v1/discover/pause",
+    tag = "discovery",
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Ack>),
+        (status = 400, description = "No discovery is running", body = ErrorResponse),
+    )
+)]
+async fn api_pause_discovery(State(state): State<AppState>) -> ApiResult<Ack> {
+    if !state.discovery_progress.is_running() {
+        return Err(ApiError::invalid_request("No discovery is running"));
+    }
+    state
+        .discovery_progress
+        .paused
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+    info!("Discovery pause requested via API");
+    Ok(ApiResponse::ok(Ack::new("Discovery paused")))
+}
+
+/// POST /api/v1/discover/resume — resume a paused discovery
+#[utoipa::path(
+    post,
+    path = "/api/v1/discover/resume",
+    tag = "discovery",
+    responses((status = 200, description = "OK", body = ApiResponse<Ack>))
+)]
+async fn api_resume_discovery(State(state): State<AppState>) -> ApiResponse<Ack> {
+    state
+        .discovery_progress
+        .paused
+        .store(false, std::sync::atomic::Ordering::Relaxed);
+    info!("Discovery resume requested via API");
+    ApiResponse::ok(Ack::new("Discovery resumed"))
+}
+
+/// PUT /api/v1/discover/pacing — speed limits of the running discovery, from its next
+/// backtest on (an empty body lifts them)
+#[utoipa::path(
+    put,
+    path = "/api/v1/discover/pacing",
+    tag = "discovery",
+    request_body = ScanPacing,
+    responses(
+        (status = 200, description = "Pacing in effect", body = ApiResponse<ScanPacing>),
+        (status = 400, description = "Invalid limits, or no discovery is running", body = ErrorResponse),
+    )
+)]
+async fn api_set_discovery_pacing(
+    State(state): State<AppState>,
+    Json(pacing): Json<ScanPacing>,
+) -> ApiResult<ScanPacing> {
+    pacing.validate().map_err(ApiError::invalid_request)?;
+    if !state.discovery_progress.is_running() {
+        return Err(ApiError::invalid_request("No discovery is running"));
+    }
+    state.discovery_progress.set_pacing(pacing);
+    Ok(ApiResponse::ok(pacing))
 }
 
-/// GET /api/discover/status — poll discovery progress
-async fn api_discovery_status(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let progress = &state.discovery_progress;
+/// GET /api/v1/discover/status — poll discovery progress
+#[utoipa::path(
+    get,
+    path = "/api/v1/discover/status",
+    tag = "discovery",
+    responses((status = 200, description = "OK", body = ApiResponse<DiscoveryStatusResponse>))
+)]
+async fn api_discovery_status(
+    State(state): State<AppState>,
+) -> ApiResponse<DiscoveryStatusResponse> {
+    ApiResponse::ok(discovery_status(&state.discovery_progress))
+}
+
+/// Snapshot of the discovery progress (HTTP status and gRPC stream)
+fn discovery_status(progress: &DiscoveryProgress) -> DiscoveryStatusResponse {
     let status = progress.status.read().unwrap().clone();
     let phase = progress.phase.read().unwrap().clone();
     let current_strategy = progress.current_strategy.read().unwrap().clone();
@@ -602,6 +2050,7 @@ async fn api_discovery_status(State(state): State<AppState>) -> Json<serde_json:
         .load(std::sync::atomic::Ordering::Relaxed);
     let skipped = progress.skipped.load(std::sync::atomic::Ordering::Relaxed);
     let pct = progress.progress_pct();
+    let estimate = progress.estimate();
     let best_so_far = progress.best_so_far.read().unwrap().clone();
     let final_results = progress.final_results.read().unwrap().clone();
     let error = progress.error_message.read().unwrap().clone();
@@ -618,134 +2067,1910 @@ async fn api_discovery_status(State(state): State<AppState>) -> Json<serde_json:
     let is_continuous = progress
         .is_continuous
         .load(std::sync::atomic::Ordering::Relaxed);
+    let paused = progress.is_paused();
+    let pacing = progress.pacing();
+    let rank_by = format_rank_order(&progress.rank_order());
+    let data_quality = progress.data_quality.read().unwrap().clone();
+    let breakdown = progress.breakdown();
+    let islands = progress.islands.read().unwrap().clone();
+    let crashes = progress.crashes.read().unwrap().clone();
+    let failed = progress.failed.load(std::sync::atomic::Ordering::Relaxed);
+    let failed_combos = progress.failed_combos.read().unwrap().clone();
 
     let results = if matches!(status, DiscoveryStatus::Complete) {
-        &final_results
+        final_results
+    } else {
+        best_so_far.clone()
+    };
+
+    DiscoveryStatusResponse {
+        status,
+        phase,
+        current_strategy,
+        current_symbol,
+        progress_pct: pct,
+        completed,
+        skipped,
+        total,
+        throughput_per_sec: estimate.throughput_per_sec,
+        eta_secs: estimate.eta_secs,
+        eta_at: estimate.eta_at,
+        best_so_far,
+        results,
+        error,
+        started_at,
+        current_cycle,
+        total_tested_all_cycles,
+        total_new_this_cycle,
+        is_continuous,
+        paused,
+        pacing,
+        rank_by,
+        data_quality,
+        breakdown,
+        islands,
+        crashes,
+        failed,
+        failed_combos,
+    }
+}
+
+// ============================================================================
+// API Handlers — Distributed workers
+// ============================================================================
+
+/// Body of the worker lease endpoint
+#[derive(Deserialize, utoipa::ToSchema)]
+struct WorkerLeaseRequest {
+    worker_id: String,
+}
+
+/// Body of the chunk results endpoint
+#[derive(Deserialize, utoipa::ToSchema)]
+struct ChunkResultsRequest {
+    worker_id: String,
+    results: Vec<DiscoveryResult>,
+}
+
+/// Body of the chunk failure endpoint
+#[derive(Deserialize, utoipa::ToSchema)]
+struct ChunkFailureRequest {
+    worker_id: String,
+    error: String,
+}
+
+fn validate_worker_id(worker_id: &str) -> Result<(), ApiError> {
+    if worker_id.trim().is_empty() || worker_id.len() > 128 {
+        return Err(ApiError::invalid_request(
+            "worker_id must be 1-128 characters",
+        ));
+    }
+    Ok(())
+}
+
+/// POST /api/v1/workers/lease — next Phase 1 chunk for a worker (none when idle)
+#[utoipa::path(
+    post,
+    path = "/api/v1/workers/lease",
+    tag = "workers",
+    request_body = WorkerLeaseRequest,
+    responses(
+        (status = 200, description = "Leased chunk, if any", body = ApiResponse<WorkerLease>),
+        (status = 400, description = "Invalid worker id", body = ErrorResponse),
+    )
+)]
+async fn api_worker_lease(
+    State(state): State<AppState>,
+    Json(body): Json<WorkerLeaseRequest>,
+) -> ApiResult<WorkerLease> {
+    validate_worker_id(&body.worker_id)?;
+    Ok(ApiResponse::ok(WorkerLease {
+        chunk: state.coordinator.lease(&body.worker_id).await?,
+    }))
+}
+
+/// POST /api/v1/workers/chunks/{id}/results — results of a leased chunk
+#[utoipa::path(
+    post,
+    path = "/api/v1/workers/chunks/{id}/results",
+    tag = "workers",
+    params(("id" = u64, Path, description = "Chunk id")),
+    request_body = ChunkResultsRequest,
+    responses(
+        (status = 200, description = "Accepted, or dropped when the chunk is already done", body = ApiResponse<ChunkAccepted>),
+        (status = 400, description = "Invalid worker id", body = ErrorResponse),
+    )
+)]
+async fn api_worker_results(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<u64>,
+    Json(body): Json<ChunkResultsRequest>,
+) -> ApiResult<ChunkAccepted> {
+    validate_worker_id(&body.worker_id)?;
+    let accepted = state
+        .coordinator
+        .complete(id, &body.worker_id, body.results)
+        .await?;
+    Ok(ApiResponse::ok(ChunkAccepted { accepted }))
+}
+
+/// POST /api/v1/workers/chunks/{id}/fail — a worker could not run its chunk
+#[utoipa::path(
+    post,
+    path = "/api/v1/workers/chunks/{id}/fail",
+    tag = "workers",
+    params(("id" = u64, Path, description = "Chunk id")),
+    request_body = ChunkFailureRequest,
+    responses(
+        (status = 200, description = "Chunk queued again", body = ApiResponse<Ack>),
+        (status = 400, description = "Invalid worker id", body = ErrorResponse),
+    )
+)]
+async fn api_worker_fail(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<u64>,
+    Json(body): Json<ChunkFailureRequest>,
+) -> ApiResult<Ack> {
+    validate_worker_id(&body.worker_id)?;
+    state
+        .coordinator
+        .fail(id, &body.worker_id, &body.error)
+        .await?;
+    Ok(ApiResponse::ok(Ack::new(format!("Chunk {} released", id))))
+}
+
+/// GET /api/v1/workers — chunk queue of the running scan and the known workers
+#[utoipa::path(
+    get,
+    path = "/api/v1/workers",
+    tag = "workers",
+    responses(
+        (status = 200, description = "Coordinator status", body = ApiResponse<CoordinatorStatus>),
+        (status = 500, description = "Work queue unavailable", body = ErrorResponse),
+    )
+)]
+async fn api_workers_status(State(state): State<AppState>) -> ApiResult<CoordinatorStatus> {
+    Ok(ApiResponse::ok(state.coordinator.status().await?))
+}
+
+// ============================================================================
+// API Handlers — Knowledge Base
+// ============================================================================
+
+/// GET /api/v1/knowledge — paginated discovery backtest results with filters
+///
+/// Offset pages by default (`offset`); pass `cursor` (empty for the first page, then the
+/// `next_cursor` of the previous response) for keyset pages that stay fast at any depth.
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("limit" = Option<i64>, Query, description = "Page size (default 20)"),
+        ("offset" = Option<i64>, Query, description = "Offset pages (default 0), ignored with `cursor`"),
+        ("cursor" = Option<String>, Query, description = "Keyset paging: empty for the first page, then `next_cursor`"),
+        ("strategy_type" = Option<String>, Query, description = "Filter on the strategy type"),
+        ("symbol" = Option<String>, Query, description = "Filter on the symbol"),
+        ("min_win_rate" = Option<f64>, Query, description = "Minimum win rate (%)"),
+        ("min_recent_win_rate" = Option<f64>, Query, description = "Minimum win rate (%) over the last 30 days of the backtest"),
+        ("max_pnl_hhi" = Option<f64>, Query, description = "Largest concentration (0-1 HHI) of the PnL over weeks"),
+        ("sort_by" = Option<String>, Query, description = "score (default), win_rate, pnl, sharpe, confidence, annualized_return, sortino, decayed_score, score_30d, score_90d"),
+    ),
+    responses(
+        (status = 200, description = "`meta`: `limit` + `next_cursor`, plus `total` / `offset` on offset pages", body = ApiResponse<Vec<DiscoveryBacktestRecord>>),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_base(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult<Vec<DiscoveryBacktestRecord>> {
+    let limit: i64 = params
+        .get("limit")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(20);
+    let offset: i64 = params
+        .get("offset")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0);
+    let strategy_type = params.get("strategy_type").map(|s| s.as_str());
+    let symbol = params.get("symbol").map(|s| s.as_str());
+    let min_win_rate: Option<f64> = params.get("min_win_rate").and_then(|s| s.parse().ok());
+    let min_recent_win_rate: Option<f64> = params
+        .get("min_recent_win_rate")
+        .and_then(|s| s.parse().ok());
+    let max_pnl_hhi: Option<f64> = params.get("max_pnl_hhi").and_then(|s| s.parse().ok());
+    let sort_by = params.get("sort_by").map(|s| s.as_str());
+
+    let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+
+    if let Some(token) = params.get("cursor") {
+        let cursor = if token.is_empty() {
+            None
+        } else {
+            Some(
+                KnowledgeCursor::decode(token)
+                    .ok_or_else(|| ApiError::invalid_request("Invalid cursor"))?,
+            )
+        };
+
+        let (records, next) = repo
+            .get_page_after(
+                limit,
+                cursor.as_ref(),
+                strategy_type,
+                symbol,
+                min_win_rate,
+                min_recent_win_rate,
+                max_pnl_hhi,
+                sort_by,
+            )
+            .await
+            .map_err(|e| ApiError::database(format!("Failed to query knowledge base: {}", e)))?;
+        return Ok(ApiResponse::ok(records).with_meta(PageMeta {
+            limit: Some(limit),
+            next_cursor: next.map(|c| c.encode()),
+            ..Default::default()
+        }));
+    }
+
+    let (records, total) = repo
+        .get_all_paginated(
+            limit,
+            offset,
+            strategy_type,
+            symbol,
+            min_win_rate,
+            min_recent_win_rate,
+            max_pnl_hhi,
+            sort_by,
+        )
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to query knowledge base: {}", e)))?;
+
+    // Lets offset clients switch to keyset paging from any page
+    let next_cursor = if offset + (records.len() as i64) < total {
+        let order_col = persistence::repository::discovery::sort_column(sort_by);
+        records
+            .last()
+            .map(|r| KnowledgeCursor::after(r, order_col).encode())
     } else {
-        &best_so_far
+        None
+    };
+    Ok(ApiResponse::ok(records).with_meta(PageMeta {
+        total: Some(total),
+        limit: Some(limit),
+        offset: Some(offset),
+        next_cursor,
+        ..Default::default()
+    }))
+}
+
+/// GET /api/v1/knowledge/stats — aggregated knowledge base statistics (cached)
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/stats",
+    tag = "knowledge",
+    params(Workspace),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<KnowledgeStatsResponse>),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_stats(
+    State(state): State<AppState>,
+    workspace: Workspace,
+) -> ApiResult<KnowledgeStatsResponse> {
+    let generation = write_generation();
+    let key = format!("stats:{}", workspace.as_str());
+    if let Some(stats) = state
+        .query_cache
+        .get(&key, generation)
+        .and_then(|cached| serde_json::from_value(cached).ok())
+    {
+        return Ok(ApiResponse::ok(KnowledgeStatsResponse { stats }));
+    }
+
+    let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+    let stats = repo
+        .get_stats()
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to get knowledge base stats: {}", e)))?;
+    if let Ok(value) = serde_json::to_value(&stats) {
+        state.query_cache.insert(key, value, generation);
+    }
+    Ok(ApiResponse::ok(KnowledgeStatsResponse { stats }))
+}
+
+/// GET /api/v1/knowledge/top-strategies — top unique strategies (deduplicated by strategy_name, cached)
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/top-strategies",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("limit" = Option<i64>, Query, description = "Number of strategies (default 20)"),
+        ("sort_by" = Option<String>, Query, description = "Leading ranking key: win_rate (default), net_pnl or composite_score"),
+        ("rank_by" = Option<String>, Query, description = "Full ranking order, overrides sort_by (comma-separated among score, sharpe, trades, net_pnl, win_rate, profit_factor, drawdown)"),
+    ),
+    responses(
+        (status = 200, description = "Best result of each strategy_name", body = ApiResponse<Vec<DiscoveryBacktestRecord>>),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_top_strategies(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult<Vec<DiscoveryBacktestRecord>> {
+    let limit: i64 = params
+        .get("limit")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(20);
+
+    let order = match params.get("rank_by") {
+        Some(rank_by) => parse_rank_order(rank_by).map_err(ApiError::invalid_request)?,
+        None => top_strategies_order(params.get("sort_by").map(|s| s.as_str())),
+    };
+    let rank_by = format_rank_order(&order);
+
+    let generation = write_generation();
+    let key = format!("top:{}:{}:{}", workspace.as_str(), limit, rank_by);
+    let cached: Option<Vec<DiscoveryBacktestRecord>> = state
+        .query_cache
+        .get(&key, generation)
+        .and_then(|cached| serde_json::from_value(cached).ok());
+    let records = match cached {
+        Some(records) => records,
+        None => {
+            let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+            let records = repo
+                .get_top_unique_strategies(limit, &order)
+                .await
+                .map_err(|e| {
+                    ApiError::database(format!("Failed to query top strategies: {}", e))
+                })?;
+            if let Ok(value) = serde_json::to_value(&records) {
+                state.query_cache.insert(key, value, generation);
+            }
+            records
+        }
+    };
+    let total = records.len() as i64;
+    Ok(ApiResponse::ok(records).with_meta(PageMeta {
+        total: Some(total),
+        rank_by: Some(rank_by),
+        ..Default::default()
+    }))
+}
+
+/// Ranking of `/top-strategies` without `rank_by`: the legacy `sort_by` column first
+/// (win rate unless `net_pnl` or `composite_score`), then the default tie-breaks
+fn top_strategies_order(sort_by: Option<&str>) -> Vec<RankKey> {
+    let lead = match sort_by {
+        Some("net_pnl") => RankKey::NetPnl,
+        Some("composite_score") => RankKey::Score,
+        _ => RankKey::WinRate,
     };
+    let mut order = vec![lead];
+    order.extend(DEFAULT_RANK_ORDER.iter().filter(|key| **key != lead));
+    order
+}
 
-    Json(serde_json::json!({
-        "status": status,
-        "phase": phase,
-        "current_strategy": current_strategy,
-        "current_symbol": current_symbol,
-        "progress_pct": pct,
-        "completed": completed,
-        "skipped": skipped,
-        "total": total,
-        "best_so_far": best_so_far,
-        "results": results,
-        "error": error,
-        "started_at": started_at,
-        "current_cycle": current_cycle,
-        "total_tested_all_cycles": total_tested_all_cycles,
-        "total_new_this_cycle": total_new_this_cycle,
-        "is_continuous": is_continuous,
+/// Decay thresholds of the validation history endpoints (defaults: `DecayThresholds`)
+#[derive(Deserialize)]
+struct DriftParams {
+    win_rate_drop: Option<f64>,
+    score_drop_pct: Option<f64>,
+}
+
+impl DriftParams {
+    fn thresholds(&self) -> DecayThresholds {
+        let defaults = DecayThresholds::default();
+        DecayThresholds {
+            win_rate_drop: self.win_rate_drop.unwrap_or(defaults.win_rate_drop),
+            score_drop_pct: self.score_drop_pct.unwrap_or(defaults.score_drop_pct),
+        }
+    }
+}
+
+/// Knowledge base record `id` of the workspace, or a 404 (records of other workspaces
+/// included)
+async fn knowledge_record(
+    state: &AppState,
+    workspace: &Workspace,
+    id: i64,
+) -> Result<DiscoveryBacktestRecord, ApiError> {
+    DiscoveryRepository::new(state.db.pool())
+        .with_workspace(workspace.as_str())
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load record {}: {}", id, e)))?
+        .ok_or_else(|| ApiError::not_found(format!("No knowledge base record {}", id)))
+}
+
+async fn strategy_drift(
+    state: &AppState,
+    record: &DiscoveryBacktestRecord,
+    thresholds: DecayThresholds,
+) -> ApiResult<StrategyDrift> {
+    let id = record.id.unwrap_or_default();
+    let history = ValidationRepository::new(state.db.pool())
+        .get_history(id)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load validations of {}: {}", id, e)))?;
+    Ok(ApiResponse::ok(drift_report(record, &history, thresholds)))
+}
+
+/// POST /api/v1/knowledge/{id}/validate — re-run a stored strategy on fresh klines
+#[utoipa::path(
+    post,
+    path = "/api/v1/knowledge/{id}/validate",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+        ("win_rate_drop" = Option<f64>, Query, description = "Decay threshold in win rate points (default 10)"),
+        ("score_drop_pct" = Option<f64>, Query, description = "Decay threshold in % of the original score (default 30)"),
+    ),
+    responses(
+        (status = 200, description = "Validation stored; updated drift", body = ApiResponse<StrategyDrift>),
+        (status = 400, description = "Cross-sectional or undecodable strategy", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 502, description = "Binance klines could not be fetched", body = ErrorResponse),
+    )
+)]
+async fn api_validate_strategy(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Query(params): Query<DriftParams>,
+) -> ApiResult<StrategyDrift> {
+    let record = knowledge_record(&state, &workspace, id).await?;
+    validation_strategy(&record).map_err(ApiError::invalid_request)?;
+
+    let validation = revalidate(&record, &state.binance).await.map_err(|e| {
+        error!("Validation of record {} failed: {}", id, e);
+        ApiError::upstream(format!("Validation failed: {}", e))
+    })?;
+    ValidationRepository::new(state.db.pool())
+        .save(&validation)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to save validation: {}", e)))?;
+    info!(
+        id,
+        strategy = %record.strategy_name,
+        win_rate = validation.win_rate,
+        score = validation.composite_score,
+        "Strategy re-validated"
+    );
+
+    let drift = strategy_drift(&state, &record, params.thresholds()).await?;
+    match reject_if_decaying(state.db.pool(), &drift.data).await {
+        Ok(true) => info!(id, "Decaying strategy rejected from the pipeline"),
+        Ok(false) => {}
+        Err(e) => error!("Pipeline rejection of record {} failed: {}", id, e),
+    }
+    Ok(drift)
+}
+
+/// GET /api/v1/knowledge/{id} — one record and its trades by exit reason
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+    ),
+    responses(
+        (status = 200, description = "The record", body = ApiResponse<KnowledgeDetail>),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_detail(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> ApiResult<KnowledgeDetail> {
+    let record = knowledge_record(&state, &workspace, id).await?;
+    let exit_breakdown = record
+        .exit_breakdown
+        .as_deref()
+        .and_then(|b| serde_json::from_str(b).ok());
+    let cost_attribution = record
+        .cost_attribution
+        .as_deref()
+        .and_then(|c| serde_json::from_str(c).ok());
+    Ok(ApiResponse::ok(KnowledgeDetail {
+        record,
+        exit_breakdown,
+        cost_attribution,
+    }))
+}
+
+/// GET /api/v1/knowledge/{id}/costs — where a record's PnL went: signal PnL, timing
+/// cost of the fills, slippage and fees, down to its net PnL
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/costs",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+    ),
+    responses(
+        (status = 200, description = "Cost attribution of the record's backtest", body = ApiResponse<CostReport>),
+        (status = 404, description = "Unknown record, or backtested before costs were recorded", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_costs(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> ApiResult<CostReport> {
+    let record = knowledge_record(&state, &workspace, id).await?;
+    let costs = record
+        .cost_attribution
+        .as_deref()
+        .and_then(|c| serde_json::from_str(c).ok())
+        .ok_or_else(|| {
+            ApiError::not_found(format!(
+                "Record {} was backtested before costs were recorded",
+                id
+            ))
+        })?;
+    Ok(ApiResponse::ok(CostReport::new(&record, costs)))
+}
+
+/// GET /api/v1/knowledge/{id}/explain — the record's strategy as readable rules, with
+/// the sizing and fee assumptions of its backtest
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/explain",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+    ),
+    responses(
+        (status = 200, description = "Rules, sizing and fees", body = ApiResponse<StrategyExplanation>),
+        (status = 400, description = "Undecodable strategy params", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_explain(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> ApiResult<StrategyExplanation> {
+    let record = knowledge_record(&state, &workspace, id).await?;
+    explain_record(&record)
+        .map(ApiResponse::ok)
+        .map_err(ApiError::invalid_request)
+}
+
+/// Query params of `stub.py`
+#[derive(Deserialize)]
+struct StubParams {
+    /// `backtrader` (default) or `quantconnect`
+    target: Option<String>,
+}
+
+/// GET /api/v1/knowledge/{id}/stub.py — the record's strategy as a Python stub for
+/// Backtrader or QuantConnect, to re-verify it on another engine
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/stub.py",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+        ("target" = Option<String>, Query, description = "backtrader (default) or quantconnect"),
+    ),
+    responses(
+        (status = 200, description = "Python strategy", content_type = "text/x-python", body = String),
+        (status = 400, description = "Unknown target, or a strategy without indicators to export", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_stub(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Query(params): Query<StubParams>,
+) -> Result<Response, ApiError> {
+    let target = params
+        .target
+        .as_deref()
+        .map(StubTarget::parse)
+        .transpose()
+        .map_err(ApiError::invalid_request)?
+        .unwrap_or_default();
+    let record = knowledge_record(&state, &workspace, id).await?;
+    let stub = record_stub(&record, target).map_err(ApiError::invalid_request)?;
+    let disposition = format!(
+        "attachment; filename=\"strategy-{}-{}.py\"",
+        id,
+        target.name()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/x-python".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        stub,
+    )
+        .into_response())
+}
+
+/// Query params of the chart endpoints
+#[derive(Deserialize)]
+struct ChartParams {
+    /// Replay and draw again instead of serving the saved chart
+    #[serde(default)]
+    refresh: bool,
+}
+
+/// GET /api/v1/knowledge/{id}/chart.png — equity and drawdown chart of a record
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/chart.png",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+        ("refresh" = Option<bool>, Query, description = "Draw again instead of serving the saved chart"),
+    ),
+    responses(
+        (status = 200, description = "PNG image", content_type = "image/png", body = String),
+        (status = 400, description = "Gabagool, cross-sectional or undecodable strategy", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 502, description = "Binance klines could not be fetched", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_chart_png(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Query(params): Query<ChartParams>,
+) -> Result<Response, ApiError> {
+    knowledge_chart(&state, &workspace, id, ChartFormat::Png, params.refresh).await
+}
+
+/// GET /api/v1/knowledge/{id}/chart.svg — the chart of `chart.png` as SVG
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/chart.svg",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+        ("refresh" = Option<bool>, Query, description = "Draw again instead of serving the saved chart"),
+    ),
+    responses(
+        (status = 200, description = "SVG image", content_type = "image/svg+xml", body = String),
+        (status = 400, description = "Gabagool, cross-sectional or undecodable strategy", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 502, description = "Binance klines could not be fetched", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_chart_svg(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Query(params): Query<ChartParams>,
+) -> Result<Response, ApiError> {
+    knowledge_chart(&state, &workspace, id, ChartFormat::Svg, params.refresh).await
+}
+
+/// Chart of record `id`: replayed and drawn to `charts/` of the export dir on the first
+/// request (or `refresh`), served from that file after
+async fn knowledge_chart(
+    state: &AppState,
+    workspace: &Workspace,
+    id: i64,
+    format: ChartFormat,
+    refresh: bool,
+) -> Result<Response, ApiError> {
+    let record = knowledge_record(state, workspace, id).await?;
+    chart_strategy(&record).map_err(ApiError::invalid_request)?;
+
+    let charts = state.export_jobs.dir().join("charts");
+    let name = format!("{}-{}", workspace.as_str(), id);
+    let path = charts.join(format!("{}.{}", name, format.extension()));
+    if refresh || !path.exists() {
+        let curve = record_equity_curve(&record, &state.binance)
+            .await
+            .map_err(|e| {
+                error!("Chart replay of record {} failed: {}", id, e);
+                ApiError::upstream(format!("Chart replay failed: {}", e))
+            })?;
+        // Drawn next to the chart, then swapped in: a concurrent request never reads a
+        // half-written file
+        let partial = charts.join(format!("{}.part.{}", name, format.extension()));
+        let (out, target) = (partial.clone(), path.clone());
+        tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(out.parent().unwrap_or(Path::new(".")))?;
+            render_chart(&curve, format, &out)?;
+            std::fs::rename(&out, &target)?;
+            anyhow::Ok(())
+        })
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&partial);
+            ApiError::internal(format!("Chart rendering failed: {:#}", e))
+        })?;
+    }
+
+    let image = tokio::fs::read(&path)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read {}: {}", path.display(), e)))?;
+    Ok(([(header::CONTENT_TYPE, format.content_type())], image).into_response())
+}
+
+/// Query params of the daily PnL endpoint
+#[derive(Deserialize)]
+struct DailyPnlParams {
+    /// Replay again instead of serving the stored days
+    #[serde(default)]
+    refresh: bool,
+}
+
+/// GET /api/v1/knowledge/{id}/daily-pnl — PnL of a record's trades per UTC day of their
+/// exit, for calendar heatmaps
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/daily-pnl",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+        ("refresh" = Option<bool>, Query, description = "Replay again instead of serving the stored days"),
+    ),
+    responses(
+        (status = 200, description = "Days with a closed trade, oldest first", body = ApiResponse<Vec<DailyPnlRecord>>),
+        (status = 400, description = "Gabagool, cross-sectional or undecodable strategy", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 502, description = "Binance klines could not be fetched", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_daily_pnl(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Query(params): Query<DailyPnlParams>,
+) -> ApiResult<Vec<DailyPnlRecord>> {
+    let record = knowledge_record(&state, &workspace, id).await?;
+    daily_pnl_strategy(&record).map_err(ApiError::invalid_request)?;
+
+    // Replayed on the first request (or `refresh`), served from `daily_pnl` after
+    let repo = DailyPnlRepository::new(state.db.pool());
+    if !params.refresh {
+        let days = repo.get(id).await.map_err(|e| {
+            ApiError::database(format!("Failed to load daily PnL of {}: {}", id, e))
+        })?;
+        if !days.is_empty() {
+            return Ok(ApiResponse::ok(days));
+        }
+    }
+    let days = record_daily_pnl(&record, &state.binance)
+        .await
+        .map_err(|e| {
+            error!("Daily PnL replay of record {} failed: {}", id, e);
+            ApiError::upstream(format!("Daily PnL replay failed: {}", e))
+        })?;
+    // A read-only server replays on every request instead
+    if !state.read_only {
+        repo.replace(id, &days).await.map_err(|e| {
+            ApiError::database(format!("Failed to save daily PnL of {}: {}", id, e))
+        })?;
+    }
+    Ok(ApiResponse::ok(days))
+}
+
+/// GET /api/v1/knowledge/{id}/history — validation series, drift and decay flag
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/history",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+        ("win_rate_drop" = Option<f64>, Query, description = "Decay threshold in win rate points (default 10)"),
+        ("score_drop_pct" = Option<f64>, Query, description = "Decay threshold in % of the original score (default 30)"),
+    ),
+    responses(
+        (status = 200, description = "Validations, oldest first, against the original backtest", body = ApiResponse<StrategyDrift>),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_strategy_history(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Query(params): Query<DriftParams>,
+) -> ApiResult<StrategyDrift> {
+    let record = knowledge_record(&state, &workspace, id).await?;
+    strategy_drift(&state, &record, params.thresholds()).await
+}
+
+/// Query params for the similar-strategies endpoint
+#[derive(Deserialize)]
+struct SimilarParams {
+    /// Neighbors returned (default 20, max 100)
+    limit: Option<usize>,
+    /// Minimum similarity, from 0 to 1 (default 0.5)
+    min_similarity: Option<f64>,
+    /// Search every symbol instead of the record's only
+    #[serde(default)]
+    all_symbols: bool,
+}
+
+/// GET /api/v1/knowledge/{id}/similar — records with overlapping indicators and nearby params
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/similar",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id"),
+        ("limit" = Option<usize>, Query, description = "Neighbors returned (default 20, max 100)"),
+        ("min_similarity" = Option<f64>, Query, description = "Minimum similarity from 0 to 1 (default 0.5)"),
+        ("all_symbols" = Option<bool>, Query, description = "Search every symbol, not only the record's (default false)"),
+    ),
+    responses(
+        (status = 200, description = "Neighbors, most similar first, and their performance", body = ApiResponse<SimilarStrategies>),
+        (status = 400, description = "Undecodable strategy", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_knowledge_similar(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Query(params): Query<SimilarParams>,
+) -> ApiResult<SimilarStrategies> {
+    let record = knowledge_record(&state, &workspace, id).await?;
+    let strategy =
+        decode_strategy_params(&record.strategy_params, record.schema_version.unwrap_or(0))
+            .map_err(ApiError::invalid_request)?;
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SIMILAR_LIMIT)
+        .clamp(1, MAX_SIMILAR_LIMIT);
+    let min_similarity = params
+        .min_similarity
+        .unwrap_or(DEFAULT_MIN_SIMILARITY)
+        .clamp(0.0, 1.0);
+
+    let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+    let symbol = (!params.all_symbols).then_some(record.symbol.as_str());
+    let candidates = repo
+        .get_strategy_params_rows(symbol)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load strategies: {}", e)))?;
+    let ranked = rank_similar(&record, &candidates, min_similarity, limit)
+        .map_err(ApiError::invalid_request)?;
+
+    let mut neighbors = Vec::with_capacity(ranked.len());
+    for (neighbor_id, similarity) in ranked {
+        let neighbor = repo.get_by_id(neighbor_id).await.map_err(|e| {
+            ApiError::database(format!("Failed to load record {}: {}", neighbor_id, e))
+        })?;
+        if let Some(record) = neighbor {
+            neighbors.push(SimilarRecord { similarity, record });
+        }
+    }
+    let records: Vec<DiscoveryBacktestRecord> =
+        neighbors.iter().map(|n| n.record.clone()).collect();
+
+    Ok(ApiResponse::ok(SimilarStrategies {
+        id,
+        strategy_name: record.strategy_name.clone(),
+        indicators: StrategyProfile::of(&strategy)
+            .indicators
+            .into_iter()
+            .collect(),
+        neighborhood: NeighborhoodSummary::of(&record, &records),
+        neighbors,
+    }))
+}
+
+/// Query params for the resolution backtest endpoint
+#[derive(Deserialize)]
+struct ResolutionParams {
+    /// Days of resolved markets, ending now
+    #[serde(default = "default_resolution_days")]
+    days: i64,
+    /// USDC staked per bet
+    #[serde(default = "default_resolution_stake")]
+    stake: f64,
+}
+
+fn default_resolution_days() -> i64 {
+    3
+}
+
+fn default_resolution_stake() -> f64 {
+    100.0
+}
+
+/// POST /api/v1/knowledge/{id}/resolution-backtest — bet a stored strategy on resolved Polymarket up/down markets
+#[utoipa::path(
+    post,
+    path = "/api/v1/knowledge/{id}/resolution-backtest",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id (BTC, ETH, SOL or XRP)"),
+        ("days" = Option<i64>, Query, description = "Days of resolved markets ending now (default 3, max 10)"),
+        ("stake" = Option<f64>, Query, description = "USDC staked per bet (default 100)"),
+    ),
+    responses(
+        (status = 200, description = "Bets on realized YES/NO payoffs", body = ApiResponse<ResolutionBacktest>),
+        (status = 400, description = "Invalid params, strategy not tradable bar by bar, or no up/down markets for the symbol", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 502, description = "Polymarket markets or Binance klines could not be fetched", body = ErrorResponse),
+    )
+)]
+async fn api_resolution_backtest(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Query(params): Query<ResolutionParams>,
+) -> ApiResult<ResolutionBacktest> {
+    if !(1..=10).contains(&params.days) {
+        return Err(ApiError::invalid_request("days must be between 1 and 10"));
+    }
+    if !params.stake.is_finite() || params.stake <= 0.0 {
+        return Err(ApiError::invalid_request("stake must be positive"));
+    }
+    let record = knowledge_record(&state, &workspace, id).await?;
+    paper_strategy(&record).map_err(ApiError::invalid_request)?;
+    if market_asset(&record.symbol).is_none() {
+        return Err(ApiError::invalid_request(format!(
+            "No Polymarket up/down markets for {}",
+            record.symbol
+        )));
+    }
+
+    let report = run_resolution_backtest(
+        &record,
+        &state.binance,
+        &state.polymarket,
+        params.days,
+        params.stake,
+    )
+    .await
+    .map_err(|e| {
+        error!("Resolution backtest of record {} failed: {}", id, e);
+        ApiError::upstream(format!("Resolution backtest failed: {}", e))
+    })?;
+    Ok(ApiResponse::ok(report))
+}
+
+/// Body of the fee sensitivity endpoint
+#[derive(Deserialize, utoipa::ToSchema)]
+struct FeeSensitivityRequest {
+    /// Top unique strategies re-run (default 5, max 20)
+    #[serde(default = "default_fee_sensitivity_top_n")]
+    top_n: i64,
+    /// Presets compared with the zero-fee and Polymarket baselines
+    #[serde(default)]
+    presets: Vec<FeePreset>,
+}
+
+fn default_fee_sensitivity_top_n() -> i64 {
+    5
+}
+
+/// POST /api/v1/knowledge/fee-sensitivity — re-run the top strategies under each fee preset
+#[utoipa::path(
+    post,
+    path = "/api/v1/knowledge/fee-sensitivity",
+    tag = "knowledge",
+    params(Workspace),
+    request_body = FeeSensitivityRequest,
+    responses(
+        (status = 200, description = "Each strategy under every preset; `edge_vanishes` when only profitable before fees", body = ApiResponse<FeeSensitivityReport>),
+        (status = 400, description = "Invalid top_n or preset", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_fee_sensitivity(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Json(request): Json<FeeSensitivityRequest>,
+) -> ApiResult<FeeSensitivityReport> {
+    if !(1..=20).contains(&request.top_n) {
+        return Err(ApiError::invalid_request("top_n must be between 1 and 20"));
+    }
+    for preset in &request.presets {
+        preset.validate().map_err(ApiError::invalid_request)?;
+    }
+    let records = DiscoveryRepository::new(state.db.pool())
+        .with_workspace(workspace.as_str())
+        .get_top_unique_strategies(request.top_n, &top_strategies_order(None))
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to get top strategies: {}", e)))?;
+
+    let report = fee_sensitivity(&records, &state.binance, &request.presets).await;
+    info!(
+        strategies = report.strategies.len(),
+        vanished = report.vanished,
+        "Fee sensitivity computed"
+    );
+    Ok(ApiResponse::ok(report))
+}
+
+/// Body of the fill sensitivity endpoint
+#[derive(Deserialize, utoipa::ToSchema)]
+struct FillSensitivityRequest {
+    /// Top unique strategies re-run (default 5, max 20)
+    #[serde(default = "default_fee_sensitivity_top_n")]
+    top_n: i64,
+    /// Models compared with the close fills (every model when empty)
+    #[serde(default)]
+    fill_models: Vec<FillModel>,
+}
+
+/// POST /api/v1/knowledge/fill-sensitivity — re-run the top strategies under each fill model
+#[utoipa::path(
+    post,
+    path = "/api/v1/knowledge/fill-sensitivity",
+    tag = "knowledge",
+    params(Workspace),
+    request_body = FillSensitivityRequest,
+    responses(
+        (status = 200, description = "Each strategy under every fill model, with its PnL delta against close fills", body = ApiResponse<FillSensitivityReport>),
+        (status = 400, description = "Invalid top_n", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_fill_sensitivity(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Json(request): Json<FillSensitivityRequest>,
+) -> ApiResult<FillSensitivityReport> {
+    if !(1..=20).contains(&request.top_n) {
+        return Err(ApiError::invalid_request("top_n must be between 1 and 20"));
+    }
+    let records = DiscoveryRepository::new(state.db.pool())
+        .with_workspace(workspace.as_str())
+        .get_top_unique_strategies(request.top_n, &top_strategies_order(None))
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to get top strategies: {}", e)))?;
+
+    let report = fill_sensitivity(&records, &state.binance, &request.fill_models).await;
+    info!(
+        strategies = report.strategies.len(),
+        vanished = report.vanished,
+        "Fill sensitivity computed"
+    );
+    Ok(ApiResponse::ok(report))
+}
+
+/// Body of the ensemble builder endpoint
+#[derive(Deserialize, utoipa::ToSchema)]
+struct EnsembleBuildRequest {
+    /// Knowledge base records voting in the ensemble (2 to 8)
+    ids: Vec<i64>,
+    /// `majority` (one vote each, default) or `confidence_weighted`
+    #[serde(default)]
+    vote: EnsembleVote,
+    /// Symbol backtested (default: the first record's)
+    symbol: Option<String>,
+}
+
+/// POST /api/v1/ensemble/build — backtest a voting ensemble of stored strategies and
+/// store it in the knowledge base
+#[utoipa::path(
+    post,
+    path = "/api/v1/ensemble/build",
+    tag = "knowledge",
+    params(Workspace),
+    request_body = EnsembleBuildRequest,
+    responses(
+        (status = 200, description = "The ensemble's stored record and its members", body = ApiResponse<EnsembleBuild>),
+        (status = 400, description = "Invalid member count, or a member that cannot vote", body = ErrorResponse),
+        (status = 404, description = "Unknown record id", body = ErrorResponse),
+        (status = 502, description = "Klines could not be fetched", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_build_ensemble(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Json(request): Json<EnsembleBuildRequest>,
+) -> ApiResult<EnsembleBuild> {
+    if !(2..=MAX_MEMBERS).contains(&request.ids.len()) {
+        return Err(ApiError::invalid_request(format!(
+            "An ensemble needs 2 to {} record ids",
+            MAX_MEMBERS
+        )));
+    }
+    let mut records = Vec::with_capacity(request.ids.len());
+    for &id in &request.ids {
+        records.push(knowledge_record(&state, &workspace, id).await?);
+    }
+    ensemble_strategy(&records, request.vote).map_err(ApiError::invalid_request)?;
+
+    let store = SqliteResultStore::new(state.db.pool_clone()).with_workspace(workspace.as_str());
+    let build = build_ensemble(
+        &records,
+        request.vote,
+        request.symbol.as_deref(),
+        &state.binance,
+        &store,
+    )
+    .await
+    .map_err(|e| ApiError::upstream(format!("Failed to build ensemble: {}", e)))?;
+    info!(
+        strategy = %build.record.strategy_name,
+        members = build.members.len(),
+        created = build.created,
+        "Ensemble built"
+    );
+    Ok(ApiResponse::ok(build))
+}
+
+/// POST /api/v1/backtest — backtest a hand-written strategy once, optionally saving it
+#[utoipa::path(
+    post,
+    path = "/api/v1/backtest",
+    tag = "discovery",
+    params(Workspace),
+    request_body = ManualBacktestRequest,
+    responses(
+        (status = 200, description = "Scored result, its params hash and the knowledge base record of that hash", body = ApiResponse<ManualBacktest>),
+        (status = 400, description = "Invalid symbol, days or strategy", body = ErrorResponse),
+        (status = 502, description = "Klines could not be fetched", body = ErrorResponse),
+    )
+)]
+async fn api_manual_backtest(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Json(request): Json<ManualBacktestRequest>,
+) -> ApiResult<ManualBacktest> {
+    request.validate().map_err(ApiError::invalid_request)?;
+    let store = SqliteResultStore::new(state.db.pool_clone()).with_workspace(workspace.as_str());
+    let backtest = run_manual_backtest(&request, &state.binance, &store)
+        .await
+        .map_err(|e| ApiError::upstream(format!("Backtest failed: {}", e)))?;
+    info!(
+        strategy = %backtest.result.strategy_name,
+        symbol = %request.symbol,
+        saved = backtest.created,
+        "Manual backtest run"
+    );
+    Ok(ApiResponse::ok(backtest))
+}
+
+/// Query params for the run comparison endpoint
+#[derive(Deserialize)]
+struct CompareRunsParams {
+    run_a: Option<String>,
+    run_b: Option<String>,
+    #[serde(default = "default_compare_top_n")]
+    top_n: usize,
+}
+
+fn default_compare_top_n() -> usize {
+    10
+}
+
+/// GET /api/v1/runs/compare — diff the results of two discovery runs
+#[utoipa::path(
+    get,
+    path = "/api/v1/runs/compare",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("run_a" = Option<String>, Query, description = "Baseline discovery_run_id (default: the run before run_b)"),
+        ("run_b" = Option<String>, Query, description = "Compared discovery_run_id (default: latest run)"),
+        ("top_n" = Option<usize>, Query, description = "Families taken from each run's top list (default 10)"),
+    ),
+    responses(
+        (status = 200, description = "Top families, score distributions and new families of both runs", body = ApiResponse<RunComparison>),
+        (status = 400, description = "Fewer than two runs to compare", body = ErrorResponse),
+        (status = 404, description = "Unknown run", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_compare_runs(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Query(params): Query<CompareRunsParams>,
+) -> ApiResult<RunComparison> {
+    let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+    let db_error =
+        |e: persistence::DbError| ApiError::database(format!("Failed to compare runs: {}", e));
+
+    let (run_a, run_b) = match (params.run_a, params.run_b) {
+        (Some(a), Some(b)) => (a, b),
+        (run_a, run_b) => {
+            // Runs are listed newest first: default to the latest, compared with the one before
+            let runs: Vec<String> = repo
+                .list_runs(i64::MAX)
+                .await
+                .map_err(db_error)?
+                .into_iter()
+                .map(|r| r.run_id)
+                .collect();
+            let run_b = match run_b.or_else(|| runs.first().cloned()) {
+                Some(b) => b,
+                None => return Err(ApiError::invalid_request("No discovery run recorded yet")),
+            };
+            let run_a = match run_a {
+                Some(a) => a,
+                None => runs
+                    .iter()
+                    .skip_while(|id| **id != run_b)
+                    .nth(1)
+                    .cloned()
+                    .ok_or_else(|| {
+                        ApiError::invalid_request(format!(
+                            "No run before {} to compare with",
+                            run_b
+                        ))
+                    })?,
+            };
+            (run_a, run_b)
+        }
+    };
+
+    match repo.compare_runs(&run_a, &run_b, params.top_n).await {
+        Ok(Some(comparison)) => Ok(ApiResponse::ok(comparison)),
+        Ok(None) => Err(ApiError::not_found(format!(
+            "Unknown discovery run: {} or {}",
+            run_a, run_b
+        ))),
+        Err(e) => Err(db_error(e)),
+    }
+}
+
+/// GET /api/v1/runs/{run_id}/data-quality — kline quality of each symbol of a run
+#[utoipa::path(
+    get,
+    path = "/api/v1/runs/{run_id}/data-quality",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("run_id" = String, Path, description = "discovery_run_id"),
+    ),
+    responses(
+        (status = 200, description = "Quality score and defects of each symbol, worst first", body = ApiResponse<Vec<KlineQualityRecord>>),
+        (status = 404, description = "No quality check recorded for this run", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_run_data_quality(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(run_id): axum::extract::Path<String>,
+) -> ApiResult<Vec<KlineQualityRecord>> {
+    let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+    let records = repo
+        .get_data_quality(&run_id)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load kline quality: {}", e)))?;
+    if records.is_empty() {
+        return Err(ApiError::not_found(format!(
+            "No kline quality recorded for run {}",
+            run_id
+        )));
+    }
+    Ok(ApiResponse::list(records))
+}
+
+/// GET /api/v1/runs/{run_id}/cycles — summary of each finished cycle of a continuous run
+#[utoipa::path(
+    get,
+    path = "/api/v1/runs/{run_id}/cycles",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("run_id" = String, Path, description = "discovery_run_id of a continuous run"),
+    ),
+    responses(
+        (status = 200, description = "Grid size, new results, best score, duration and throughput of each cycle, oldest first", body = ApiResponse<Vec<CycleSummaryRecord>>),
+        (status = 404, description = "No cycle recorded for this run", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_run_cycles(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(run_id): axum::extract::Path<String>,
+) -> ApiResult<Vec<CycleSummaryRecord>> {
+    let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+    let records = repo
+        .get_cycle_summaries(&run_id)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load cycle summaries: {}", e)))?;
+    if records.is_empty() {
+        return Err(ApiError::not_found(format!(
+            "No cycle recorded for run {}",
+            run_id
+        )));
+    }
+    Ok(ApiResponse::list(records))
+}
+
+/// GET /api/v1/runs/{run_id}/crashes — panics of a supervised run's task
+#[utoipa::path(
+    get,
+    path = "/api/v1/runs/{run_id}/crashes",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("run_id" = String, Path, description = "discovery_run_id of the crashed run"),
+    ),
+    responses(
+        (status = 200, description = "Time, panic message and restart count of each crash, oldest first", body = ApiResponse<Vec<RunCrashRecord>>),
+        (status = 404, description = "No crash recorded for this run", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_run_crashes(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(run_id): axum::extract::Path<String>,
+) -> ApiResult<Vec<RunCrashRecord>> {
+    let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+    let records = repo
+        .get_run_crashes(&run_id)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load run crashes: {}", e)))?;
+    if records.is_empty() {
+        return Err(ApiError::not_found(format!(
+            "No crash recorded for run {}",
+            run_id
+        )));
+    }
+    Ok(ApiResponse::list(records))
+}
+
+/// GET /api/v1/runs/{run_id}/cycles/{cycle}/grid — strategies a continuous cycle ran
+#[utoipa::path(
+    get,
+    path = "/api/v1/runs/{run_id}/cycles/{cycle}/grid",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("run_id" = String, Path, description = "discovery_run_id of a continuous run"),
+        ("cycle" = i64, Path, description = "0-based cycle number"),
+    ),
+    responses(
+        (status = 200, description = "Shared, per-symbol and cross-sectional grids of the cycle", body = ApiResponse<CycleGridSnapshot>),
+        (status = 404, description = "Unknown cycle, or recorded without a grid snapshot", body = ErrorResponse),
+        (status = 500, description = "Database error or undecodable snapshot", body = ErrorResponse),
+    )
+)]
+async fn api_run_cycle_grid(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path((run_id, cycle)): axum::extract::Path<(String, i64)>,
+) -> ApiResult<CycleGridSnapshot> {
+    let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+    let summary = repo
+        .get_cycle_summaries(&run_id)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load cycle summaries: {}", e)))?
+        .into_iter()
+        .find(|s| s.cycle == cycle)
+        .ok_or_else(|| {
+            ApiError::not_found(format!("No cycle {} recorded for run {}", cycle, run_id))
+        })?;
+    let snapshot_id = summary.grid_snapshot_id.ok_or_else(|| {
+        ApiError::not_found(format!(
+            "Cycle {} of run {} was recorded without a grid snapshot",
+            cycle, run_id
+        ))
+    })?;
+    let snapshot = repo
+        .get_grid_snapshot(&snapshot_id)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load grid snapshot: {}", e)))?
+        .ok_or_else(|| ApiError::not_found(format!("No grid snapshot {}", snapshot_id)))?;
+    let grid = CycleGrid::from_record(&snapshot).map_err(ApiError::internal)?;
+    Ok(ApiResponse::ok(CycleGridSnapshot {
+        run_id,
+        cycle,
+        snapshot_id,
+        strategy_count: snapshot.strategy_count,
+        grid,
+        created_at: snapshot.created_at,
     }))
 }
 
 // ============================================================================
-// API Handlers — Knowledge Base
+// API Handlers — Pipeline
 // ============================================================================
 
-/// GET /api/knowledge — paginated discovery backtest results with filters
-async fn api_knowledge_base(
+async fn pipeline_status(state: &AppState, id: i64) -> ApiResult<PipelineStatusResponse> {
+    let db_error = |e: String| ApiError::database(format!("Failed to load pipeline: {}", e));
+    let stage = current_stage(state.db.pool(), id)
+        .await
+        .map_err(|e| db_error(e.to_string()))?;
+    let transitions = PipelineRepository::new(state.db.pool())
+        .get_transitions(id)
+        .await
+        .map_err(|e| db_error(e.to_string()))?;
+    Ok(ApiResponse::ok(PipelineStatusResponse {
+        backtest_id: id,
+        stage,
+        next_stages: stage.next_stages().to_vec(),
+        transitions,
+    }))
+}
+
+/// GET /api/v1/knowledge/{id}/pipeline — pipeline stage and transition history of a record
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/pipeline",
+    tag = "pipeline",
+    params(Workspace, ("id" = i64, Path, description = "Knowledge base record id")),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<PipelineStatusResponse>),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_pipeline_status(
     State(state): State<AppState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Json<serde_json::Value> {
-    let limit: i64 = params
-        .get("limit")
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(20);
-    let offset: i64 = params
-        .get("offset")
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(0);
-    let strategy_type = params.get("strategy_type").map(|s| s.as_str());
-    let symbol = params.get("symbol").map(|s| s.as_str());
-    let min_win_rate: Option<f64> = params.get("min_win_rate").and_then(|s| s.parse().ok());
-    let sort_by = params.get("sort_by").map(|s| s.as_str());
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> ApiResult<PipelineStatusResponse> {
+    knowledge_record(&state, &workspace, id).await?;
+    pipeline_status(&state, id).await
+}
 
-    let repo = DiscoveryRepository::new(state.db.pool());
-    match repo
-        .get_all_paginated(limit, offset, strategy_type, symbol, min_win_rate, sort_by)
+/// POST /api/v1/knowledge/{id}/pipeline — move a record to another stage
+#[utoipa::path(
+    post,
+    path = "/api/v1/knowledge/{id}/pipeline",
+    tag = "pipeline",
+    params(Workspace, ("id" = i64, Path, description = "Knowledge base record id")),
+    request_body = PipelineTransitionRequest,
+    responses(
+        (status = 200, description = "Moved", body = ApiResponse<PipelineStatusResponse>),
+        (status = 400, description = "Transition not allowed from the current stage", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+    )
+)]
+async fn api_pipeline_transition(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(request): Json<PipelineTransitionRequest>,
+) -> ApiResult<PipelineStatusResponse> {
+    knowledge_record(&state, &workspace, id).await?;
+    let current = current_stage(state.db.pool(), id)
         .await
-    {
-        Ok((records, total)) => Json(serde_json::json!({
-            "success": true,
-            "data": records,
-            "total": total,
-            "limit": limit,
-            "offset": offset,
-        })),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "error": format!("Failed to query knowledge base: {}", e),
-            "data": [],
-            "total": 0,
-        })),
-    }
-}
-
-/// GET /api/knowledge/stats — aggregated knowledge base statistics
-async fn api_knowledge_stats(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let repo = DiscoveryRepository::new(state.db.pool());
-    match repo.get_stats().await {
-        Ok(stats) => Json(serde_json::json!({
-            "success": true,
-            "stats": stats,
-        })),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "error": format!("Failed to get knowledge base stats: {}", e),
-        })),
-    }
-}
-
-/// GET /api/knowledge/top-strategies — top unique strategies (deduplicated by strategy_name)
-async fn api_top_strategies(
+        .map_err(|e| ApiError::database(e.to_string()))?;
+    if !current.can_move_to(request.stage) {
+        return Err(ApiError::invalid_request(format!(
+            "Cannot move record {} from {} to {} (allowed: {})",
+            id,
+            current.as_str(),
+            request.stage.as_str(),
+            current
+                .next_stages()
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    engine::pipeline::transition(
+        state.db.pool(),
+        id,
+        request.stage,
+        request.reason.as_deref(),
+        false,
+    )
+    .await
+    .map_err(|e| ApiError::database(format!("Failed to move record {}: {}", id, e)))?;
+    pipeline_status(&state, id).await
+}
+
+/// GET /api/v1/pipeline — record counts per stage and the best records of `stage`
+#[utoipa::path(
+    get,
+    path = "/api/v1/pipeline",
+    tag = "pipeline",
+    params(
+        Workspace,
+        ("stage" = Option<String>, Query, description = "discovered, validated, paper_trading, approved or rejected"),
+        ("limit" = Option<i64>, Query, description = "Records of the stage (default 50)"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<PipelineOverview>),
+        (status = 400, description = "Unknown stage", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_pipeline_overview(
     State(state): State<AppState>,
+    workspace: Workspace,
     Query(params): Query<HashMap<String, String>>,
-) -> Json<serde_json::Value> {
+) -> ApiResult<PipelineOverview> {
+    let stage = match params.get("stage") {
+        Some(s) => match PipelineStage::parse(s) {
+            Some(stage) => Some(stage),
+            None => return Err(ApiError::invalid_request(format!("Unknown stage: {}", s))),
+        },
+        None => None,
+    };
     let limit: i64 = params
         .get("limit")
         .and_then(|s| s.parse().ok())
-        .unwrap_or(20);
+        .unwrap_or(50);
 
-    let sort_by = params.get("sort_by").map(|s| s.as_str());
+    let repo = PipelineRepository::new(state.db.pool()).with_workspace(workspace.as_str());
+    let db_error = |e: persistence::DbError| ApiError::database(format!("Pipeline query: {}", e));
+    let counts = repo.stage_counts().await.map_err(db_error)?;
+    let records = match stage {
+        Some(stage) => repo
+            .list_stage(stage.as_str(), limit)
+            .await
+            .map_err(db_error)?,
+        None => Vec::new(),
+    };
+    Ok(ApiResponse::ok(PipelineOverview {
+        counts,
+        stage,
+        records,
+    }))
+}
+
+/// POST /api/v1/pipeline/apply-rules — run the automatic transitions now
+#[utoipa::path(
+    post,
+    path = "/api/v1/pipeline/apply-rules",
+    tag = "pipeline",
+    request_body(content = Option<PipelineRules>, description = "Rule thresholds (defaults when omitted)"),
+    responses(
+        (status = 200, description = "Ids promoted to validated and rejected for decay", body = ApiResponse<PipelineReport>),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_apply_pipeline_rules(
+    State(state): State<AppState>,
+    rules: Option<Json<PipelineRules>>,
+) -> ApiResult<PipelineReport> {
+    let rules = rules.map(|Json(r)| r).unwrap_or_default();
+    let report = apply_pipeline_rules(state.db.pool(), &rules)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to apply pipeline rules: {}", e)))?;
+    Ok(ApiResponse::ok(report))
+}
+
+// ============================================================================
+// API Handlers — Risk limits & paper trading
+// ============================================================================
+
+async fn risk_status(state: &AppState, id: i64) -> ApiResult<RiskStatusResponse> {
+    let repo = RiskRepository::new(state.db.pool());
+    let db_error = |e: persistence::DbError| ApiError::database(format!("Risk query: {}", e));
+    let limits = repo
+        .get_limits(id)
+        .await
+        .map_err(db_error)?
+        .unwrap_or(StrategyRiskLimits {
+            backtest_id: id,
+            ..Default::default()
+        });
+    let events = repo.get_events(id, 50).await.map_err(db_error)?;
+    Ok(ApiResponse::ok(RiskStatusResponse {
+        halted: limits.halted_at.is_some(),
+        limits,
+        events,
+    }))
+}
+
+/// GET /api/v1/knowledge/{id}/risk — paper-trading limits, halt state and breaches
+#[utoipa::path(
+    get,
+    path = "/api/v1/knowledge/{id}/risk",
+    tag = "risk",
+    params(Workspace, ("id" = i64, Path, description = "Knowledge base record id")),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<RiskStatusResponse>),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+    )
+)]
+async fn api_risk_status(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> ApiResult<RiskStatusResponse> {
+    knowledge_record(&state, &workspace, id).await?;
+    risk_status(&state, id).await
+}
+
+/// PUT /api/v1/knowledge/{id}/risk — replace the limits (a halt stays in place)
+#[utoipa::path(
+    put,
+    path = "/api/v1/knowledge/{id}/risk",
+    tag = "risk",
+    params(Workspace, ("id" = i64, Path, description = "Knowledge base record id")),
+    request_body = RiskLimits,
+    responses(
+        (status = 200, description = "Limits stored", body = ApiResponse<RiskStatusResponse>),
+        (status = 400, description = "Non-positive limit", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+    )
+)]
+async fn api_set_risk_limits(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(limits): Json<RiskLimits>,
+) -> ApiResult<RiskStatusResponse> {
+    knowledge_record(&state, &workspace, id).await?;
+    limits.validate().map_err(ApiError::invalid_request)?;
+    RiskRepository::new(state.db.pool())
+        .save_limits(&StrategyRiskLimits {
+            backtest_id: id,
+            max_daily_loss: limits.max_daily_loss,
+            max_open_exposure: limits.max_open_exposure,
+            max_consecutive_losses: limits.max_consecutive_losses.map(i64::from),
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to save risk limits: {}", e)))?;
+    risk_status(&state, id).await
+}
 
-    let repo = DiscoveryRepository::new(state.db.pool());
-    match repo.get_top_unique_strategies(limit, sort_by).await {
-        Ok(records) => Json(serde_json::json!({
-            "success": true,
-            "data": records,
-            "total": records.len(),
-        })),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "error": format!("Failed to query top strategies: {}", e),
-            "data": [],
-            "total": 0,
-        })),
+/// POST /api/v1/knowledge/{id}/risk/resume — clear the halt left by a breach
+#[utoipa::path(
+    post,
+    path = "/api/v1/knowledge/{id}/risk/resume",
+    tag = "risk",
+    params(Workspace, ("id" = i64, Path, description = "Knowledge base record id")),
+    responses(
+        (status = 200, description = "Trading again", body = ApiResponse<RiskStatusResponse>),
+        (status = 400, description = "The strategy is not halted", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+    )
+)]
+async fn api_resume_strategy(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> ApiResult<RiskStatusResponse> {
+    knowledge_record(&state, &workspace, id).await?;
+    let resumed = RiskRepository::new(state.db.pool())
+        .resume(id)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to resume record {}: {}", id, e)))?;
+    if !resumed {
+        return Err(ApiError::invalid_request(format!(
+            "Record {} is not halted",
+            id
+        )));
+    }
+    info!(id, "Risk halt cleared");
+    risk_status(&state, id).await
+}
+
+/// Query params for the paper-trading endpoint
+#[derive(Deserialize)]
+struct PaperTradeParams {
+    /// Days of 15m klines traded, ending now
+    #[serde(default = "default_paper_days")]
+    days: i64,
+}
+
+fn default_paper_days() -> i64 {
+    7
+}
+
+/// Record `id` if it can be paper traded: in the paper_trading stage, tradable bar by
+/// bar and not halted
+async fn paper_tradable_record(
+    state: &AppState,
+    workspace: &Workspace,
+    id: i64,
+) -> Result<DiscoveryBacktestRecord, ApiError> {
+    let record = knowledge_record(state, workspace, id).await?;
+    let stage = current_stage(state.db.pool(), id)
+        .await
+        .map_err(|e| ApiError::database(e.to_string()))?;
+    if stage != PipelineStage::PaperTrading {
+        return Err(ApiError::invalid_request(format!(
+            "Record {} is {}, move it to paper_trading first",
+            id,
+            stage.as_str()
+        )));
+    }
+    paper_strategy(&record).map_err(ApiError::invalid_request)?;
+    let limits = RiskRepository::new(state.db.pool())
+        .get_limits(id)
+        .await
+        .map_err(|e| ApiError::database(e.to_string()))?;
+    if let Some(halted) = limits.filter(|l| l.halted_at.is_some()) {
+        let reason = halted.halt_reason.unwrap_or_default();
+        return Err(ApiError::halted(format!(
+            "Record {} is halted: {}",
+            id, reason
+        )));
+    }
+    Ok(record)
+}
+
+/// POST /api/v1/knowledge/{id}/paper-trade — paper trade recent klines under the risk limits
+#[utoipa::path(
+    post,
+    path = "/api/v1/knowledge/{id}/paper-trade",
+    tag = "risk",
+    params(
+        Workspace,
+        ("id" = i64, Path, description = "Knowledge base record id (paper_trading stage)"),
+        ("days" = Option<i64>, Query, description = "Days of klines ending now (default 7, max 90)"),
+    ),
+    responses(
+        (status = 200, description = "Session result; `halted` holds the breach, if any", body = ApiResponse<PaperSession>),
+        (status = 400, description = "Not in the paper_trading stage, or strategy not tradable bar by bar", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 409, description = "Halted by an earlier breach", body = ErrorResponse),
+        (status = 502, description = "Binance klines could not be fetched", body = ErrorResponse),
+    )
+)]
+async fn api_paper_trade(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Query(params): Query<PaperTradeParams>,
+) -> ApiResult<PaperSession> {
+    if !(1..=90).contains(&params.days) {
+        return Err(ApiError::invalid_request("days must be between 1 and 90"));
+    }
+    let record = paper_tradable_record(&state, &workspace, id).await?;
+
+    let session = run_paper_session(state.db.pool(), &record, &state.binance, params.days)
+        .await
+        .map_err(|e| {
+            error!("Paper trading of record {} failed: {}", id, e);
+            ApiError::upstream(format!("Paper trading failed: {}", e))
+        })?;
+    info!(
+        id,
+        trades = session.trades,
+        net_pnl = session.net_pnl,
+        halted = session.halted.is_some(),
+        "Paper session finished"
+    );
+    Ok(ApiResponse::ok(session))
+}
+
+/// Body of the portfolio paper-trading endpoint
+#[derive(Deserialize, utoipa::ToSchema)]
+struct PortfolioPaperTradeRequest {
+    /// Knowledge base records traded together (paper_trading stage, 1 to 20)
+    record_ids: Vec<i64>,
+    /// Days of 15m klines traded, ending now (default 7, max 90)
+    #[serde(default = "default_paper_days")]
+    days: i64,
+    /// Shared capital pool (default 10 000 USDC, entries rejected when exhausted)
+    #[serde(default)]
+    capital: Option<CapitalConfig>,
+}
+
+/// Most records of one portfolio session
+const MAX_PORTFOLIO_RECORDS: usize = 20;
+
+/// POST /api/v1/paper-trade/portfolio — paper trade several records over one capital pool
+#[utoipa::path(
+    post,
+    path = "/api/v1/paper-trade/portfolio",
+    tag = "risk",
+    params(Workspace),
+    request_body = PortfolioPaperTradeRequest,
+    responses(
+        (status = 200, description = "Session result per record and of the pool; a breach halts its record only", body = ApiResponse<PortfolioPaperSession>),
+        (status = 400, description = "Invalid days, capital or record count, a record not in the paper_trading stage or not tradable bar by bar", body = ErrorResponse),
+        (status = 404, description = "Unknown record", body = ErrorResponse),
+        (status = 409, description = "A record is halted by an earlier breach", body = ErrorResponse),
+        (status = 502, description = "Binance klines could not be fetched", body = ErrorResponse),
+    )
+)]
+async fn api_paper_trade_portfolio(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Json(request): Json<PortfolioPaperTradeRequest>,
+) -> ApiResult<PortfolioPaperSession> {
+    if !(1..=90).contains(&request.days) {
+        return Err(ApiError::invalid_request("days must be between 1 and 90"));
+    }
+    if !(1..=MAX_PORTFOLIO_RECORDS).contains(&request.record_ids.len()) {
+        return Err(ApiError::invalid_request(format!(
+            "A portfolio needs 1 to {} record ids",
+            MAX_PORTFOLIO_RECORDS
+        )));
+    }
+    let capital = request.capital.unwrap_or_default();
+    capital.validate().map_err(ApiError::invalid_request)?;
+    let mut records: Vec<DiscoveryBacktestRecord> = Vec::with_capacity(request.record_ids.len());
+    for &id in &request.record_ids {
+        if records.iter().any(|r| r.id == Some(id)) {
+            return Err(ApiError::invalid_request(format!(
+                "Record {} is listed twice",
+                id
+            )));
+        }
+        records.push(paper_tradable_record(&state, &workspace, id).await?);
     }
+
+    let session = run_portfolio_paper_session(
+        state.db.pool(),
+        &records,
+        &state.binance,
+        request.days,
+        capital,
+    )
+    .await
+    .map_err(|e| {
+        error!("Portfolio paper trading failed: {}", e);
+        ApiError::upstream(format!("Paper trading failed: {}", e))
+    })?;
+    info!(
+        records = records.len(),
+        trades = session.trades,
+        net_pnl = session.net_pnl,
+        rejected = session.allocation.rejected,
+        queued = session.allocation.queued,
+        "Portfolio paper session finished"
+    );
+    Ok(ApiResponse::ok(session))
 }
 
 // ============================================================================
 // API Handlers — Optimizer
 // ============================================================================
 
-/// POST /api/optimize — Start parameter optimization in background
+/// POST /api/v1/optimize — Start parameter optimization in background
+#[utoipa::path(
+    post,
+    path = "/api/v1/optimize",
+    tag = "optimizer",
+    params(Workspace),
+    request_body = OptimizeRequest,
+    responses(
+        (status = 200, description = "Started", body = ApiResponse<OptimizationStarted>),
+        (status = 400, description = "Custom strategy the optimizer cannot run on one symbol", body = ErrorResponse),
+        (status = 404, description = "Unknown source_record_id", body = ErrorResponse),
+        (status = 409, description = "An optimization is already running", body = ErrorResponse),
+        (status = 422, description = "Too few klines over `days`", body = ErrorResponse),
+        (status = 502, description = "Binance klines could not be fetched", body = ErrorResponse),
+    )
+)]
 async fn api_start_optimization(
     State(state): State<AppState>,
+    workspace: Workspace,
     Json(request): Json<OptimizeRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> ApiResult<OptimizationStarted> {
     if state.optimize_progress.is_running() {
         let pct = state.optimize_progress.progress_pct();
-        return Ok(Json(serde_json::json!({
-            "success": false,
-            "message": format!("Optimization already in progress ({:.0}% complete)", pct),
-        })));
+        return Err(ApiError::already_running(format!(
+            "Optimization already in progress ({:.0}% complete)",
+            pct
+        )));
+    }
+    request.validate()?;
+    if let Some(id) = request.source_record_id {
+        knowledge_record(&state, &workspace, id).await?;
     }
 
     info!(
@@ -762,21 +3987,19 @@ async fn api_start_optimization(
     let start_time = end_time - (request.days as i64 * 24 * 60 * 60 * 1000);
 
     // Fetch klines from Binance
-    let klines = match state
+    let klines = state
         .binance
         .get_klines_paginated(&request.symbol, "15m", start_time, end_time)
         .await
-    {
+        .map_err(|e| EngineError::DataFetch(format!("Failed to fetch klines: {}", e)))
+        .and_then(|klines| check_optimization_klines(&klines).map(|()| klines));
+    let klines = match klines {
         Ok(k) => k,
         Err(e) => {
-            error!("Failed to fetch klines for optimization: {}", e);
+            error!("Optimization not started: {}", e);
             *state.optimize_progress.status.write().unwrap() = OptimizeStatus::Error;
-            *state.optimize_progress.error_message.write().unwrap() =
-                Some(format!("Failed to fetch klines: {}", e));
-            return Ok(Json(serde_json::json!({
-                "success": false,
-                "message": format!("Failed to fetch market data: {}", e),
-            })));
+            *state.optimize_progress.error_message.write().unwrap() = Some(e.to_string());
+            return Err(e.into());
         }
     };
 
@@ -786,8 +4009,24 @@ async fn api_start_optimization(
     );
 
     let progress = state.optimize_progress.clone();
+    let pool = state.db.pool_clone();
+    let run_id = Utc::now().timestamp_millis().to_string();
     tokio::spawn(async move {
-        run_optimization(request, klines, progress).await;
+        match run_optimization(request.clone(), klines, progress.clone()).await {
+            Ok(()) => {
+                // Kept in `optimizations` so the sweep survives a restart
+                let results = progress.results.read().unwrap().clone();
+                let records = optimization_records(&request, &run_id, workspace.as_str(), &results);
+                match OptimizationRepository::new(&pool).save_run(&records).await {
+                    Ok(()) => {
+                        info!(run_id = %run_id, results = records.len(), "Optimization saved")
+                    }
+                    Err(e) => error!("Failed to save optimization {}: {}", run_id, e),
+                }
+            }
+            Err(EngineError::Cancelled) => {}
+            Err(e) => error!("Optimization failed: {}", e),
+        }
     });
 
     let total = state
@@ -795,15 +4034,20 @@ async fn api_start_optimization(
         .total_combinations
         .load(std::sync::atomic::Ordering::Relaxed);
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": format!("Optimization started ({} combinations)", total),
-        "total_combinations": total,
-    })))
+    Ok(ApiResponse::ok(OptimizationStarted {
+        message: format!("Optimization started ({} combinations)", total),
+        total_combinations: total,
+    }))
 }
 
-/// GET /api/optimize/status — Poll optimization progress
-async fn api_optimize_status(State(state): State<AppState>) -> Json<serde_json::Value> {
+/// GET /api/v1/optimize/status — Poll optimization progress
+#[utoipa::path(
+    get,
+    path = "/api/v1/optimize/status",
+    tag = "optimizer",
+    responses((status = 200, description = "OK", body = ApiResponse<OptimizeStatusResponse>))
+)]
+async fn api_optimize_status(State(state): State<AppState>) -> ApiResponse<OptimizeStatusResponse> {
     let progress = &state.optimize_progress;
     let status = progress.status.read().unwrap().clone();
     let total = progress
@@ -817,26 +4061,79 @@ async fn api_optimize_status(State(state): State<AppState>) -> Json<serde_json::
     let error = progress.error_message.read().unwrap().clone();
     let strategy = progress.strategy.read().unwrap().clone();
 
-    Json(serde_json::json!({
-        "status": status,
-        "strategy": strategy,
-        "progress_pct": pct,
-        "completed": completed,
-        "total": total,
-        "results": results,
-        "error": error,
-    }))
+    ApiResponse::ok(OptimizeStatusResponse {
+        status,
+        strategy,
+        progress_pct: pct,
+        completed,
+        total,
+        results,
+        error,
+    })
+}
+
+/// Query params of the optimization history endpoint
+#[derive(Deserialize)]
+struct OptimizeHistoryParams {
+    /// Sweeps returned, newest first (default 10, max 100)
+    limit: Option<i64>,
+    /// Only the sweeps started from this knowledge base record
+    record_id: Option<i64>,
+}
+
+/// GET /api/v1/optimize/history — ranked results of past optimization sweeps
+#[utoipa::path(
+    get,
+    path = "/api/v1/optimize/history",
+    tag = "optimizer",
+    params(
+        Workspace,
+        ("limit" = Option<i64>, Query, description = "Sweeps returned, newest first (default 10, max 100)"),
+        ("record_id" = Option<i64>, Query, description = "Only the sweeps started from this knowledge base record"),
+    ),
+    responses(
+        (status = 200, description = "Results by sweep, newest first, then by rank", body = ApiResponse<Vec<OptimizationRecord>>),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_optimize_history(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Query(params): Query<OptimizeHistoryParams>,
+) -> ApiResult<Vec<OptimizationRecord>> {
+    let limit = params.limit.unwrap_or(10).clamp(1, 100);
+    let records = OptimizationRepository::new(state.db.pool())
+        .get_history(workspace.as_str(), params.record_id, limit)
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load optimization history: {}", e)))?;
+    Ok(ApiResponse::list(records))
 }
 
 // ============================================================================
 // API Handlers — Binance Proxy
 // ============================================================================
 
-/// GET /api/binance/klines — Proxy endpoint for Binance klines
+/// GET /api/v1/binance/klines — Proxy endpoint for Binance klines
+#[utoipa::path(
+    get,
+    path = "/api/v1/binance/klines",
+    tag = "market",
+    params(
+        ("symbol" = Option<String>, Query, description = "Default BTCUSDT"),
+        ("interval" = Option<String>, Query, description = "Default 15m"),
+        ("start_time" = Option<i64>, Query, description = "Unix ms; with end_time, pages through the whole range"),
+        ("end_time" = Option<i64>, Query, description = "Unix ms"),
+        ("limit" = Option<u32>, Query, description = "Bars of a single request"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<KlinesResponse>),
+        (status = 502, description = "Binance error", body = ErrorResponse),
+    )
+)]
 async fn api_binance_klines(
     State(state): State<AppState>,
     Query(params): Query<HashMap<String, String>>,
-) -> Json<serde_json::Value> {
+) -> ApiResult<KlinesResponse> {
     let symbol = params
         .get("symbol")
         .cloned()
@@ -861,22 +4158,136 @@ async fn api_binance_klines(
             .await
     };
 
-    match result {
-        Ok(klines) => Json(serde_json::json!({
-            "success": true,
-            "symbol": symbol,
-            "interval": interval,
-            "count": klines.len(),
-            "klines": klines,
-        })),
-        Err(e) => {
-            error!("Binance klines error: {}", e);
-            Json(serde_json::json!({
-                "success": false,
-                "message": format!("Failed to fetch klines: {}", e),
-            }))
-        }
+    let klines = result.map_err(|e| {
+        error!("Binance klines error: {}", e);
+        ApiError::upstream(format!("Failed to fetch klines: {}", e))
+    })?;
+    Ok(ApiResponse::ok(KlinesResponse {
+        symbol,
+        interval,
+        count: klines.len(),
+        klines,
+    }))
+}
+
+/// Query params for the kline diff endpoint
+#[derive(Deserialize)]
+struct KlineDiffParams {
+    symbol: String,
+    #[serde(default = "default_kline_interval")]
+    interval: String,
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+fn default_kline_interval() -> String {
+    KLINE_INTERVAL.to_string()
+}
+
+/// GET /api/v1/klines/diff — compare the klines discovery cached with a fresh fetch
+#[utoipa::path(
+    get,
+    path = "/api/v1/klines/diff",
+    tag = "market",
+    params(
+        ("symbol" = String, Query, description = "Symbol of the last discovery run"),
+        ("interval" = Option<String>, Query, description = "Default and only cached interval: 15m"),
+        ("from" = Option<i64>, Query, description = "First open time compared, Unix ms (default: the first cached bar)"),
+        ("to" = Option<i64>, Query, description = "Last open time compared, Unix ms (default: the last cached bar)"),
+    ),
+    responses(
+        (status = 200, description = "Matching, revised and missing bars on each side", body = ApiResponse<KlineDiffResponse>),
+        (status = 400, description = "Interval not cached, or `from` after `to`", body = ErrorResponse),
+        (status = 404, description = "No cached klines for the symbol", body = ErrorResponse),
+        (status = 502, description = "Binance error", body = ErrorResponse),
+    )
+)]
+async fn api_klines_diff(
+    State(state): State<AppState>,
+    Query(params): Query<KlineDiffParams>,
+) -> ApiResult<KlineDiffResponse> {
+    if params.interval != KLINE_INTERVAL {
+        return Err(ApiError::invalid_request(format!(
+            "Only {} klines are cached",
+            KLINE_INTERVAL
+        )));
+    }
+    let symbol = params.symbol.trim().to_uppercase();
+    let cached = state
+        .discovery_progress
+        .cached_klines(&symbol)
+        .filter(|klines| !klines.is_empty())
+        .ok_or_else(|| {
+            ApiError::not_found(format!(
+                "No cached klines for {}, run discovery on it first",
+                symbol
+            ))
+        })?;
+    let from = params.from.unwrap_or(cached[0].open_time);
+    let to = params.to.unwrap_or(cached[cached.len() - 1].open_time);
+    if from > to {
+        return Err(ApiError::invalid_request("from must not be after to"));
     }
+
+    let mut live = state
+        .binance
+        .get_klines_paginated(&symbol, KLINE_INTERVAL, from, to)
+        .await
+        .map_err(|e| {
+            error!("Binance klines error: {}", e);
+            ApiError::upstream(format!("Failed to fetch klines: {}", e))
+        })?;
+    dedupe_klines(&mut live);
+    live.retain(|k| (from..=to).contains(&k.open_time));
+    let in_window = |k: &&Kline| (from..=to).contains(&k.open_time);
+    let cached: Vec<Kline> = cached.iter().filter(in_window).cloned().collect();
+    let diff = diff_klines(&cached, &live, KLINE_INTERVAL_MS);
+    info!(
+        symbol = %symbol,
+        revised = diff.revised_bars,
+        missing_in_cache = diff.missing_in_cache.len(),
+        missing_live = diff.missing_live.len(),
+        "Kline diff"
+    );
+    Ok(ApiResponse::ok(KlineDiffResponse {
+        symbol,
+        interval: params.interval,
+        from,
+        to,
+        diff,
+    }))
+}
+
+/// GET /api/v1/symbols — exchange metadata and order filters of symbols
+#[utoipa::path(
+    get,
+    path = "/api/v1/symbols",
+    tag = "market",
+    params(
+        ("symbols" = Option<String>, Query, description = "Comma-separated, default BTCUSDT,ETHUSDT,SOLUSDT,XRPUSDT"),
+    ),
+    responses(
+        (status = 200, description = "Status, assets, tick size, lot step and minimums of each symbol", body = ApiResponse<Vec<SymbolMetadata>>),
+        (status = 502, description = "Binance error or unknown symbol", body = ErrorResponse),
+    )
+)]
+async fn api_symbols(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult<Vec<SymbolMetadata>> {
+    let symbols: Vec<String> = match params.get("symbols") {
+        Some(list) => list
+            .split(',')
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        None => DEFAULT_SYMBOLS.iter().map(|s| s.to_string()).collect(),
+    };
+    let metadata = state.binance.symbol_metadata(&symbols).await.map_err(|e| {
+        error!("Binance exchangeInfo error: {}", e);
+        ApiError::upstream(format!("Failed to fetch symbol metadata: {}", e))
+    })?;
+    Ok(ApiResponse::ok(metadata))
 }
 
 // ============================================================================
@@ -889,100 +4300,305 @@ struct ExportParams {
     #[serde(default = "default_top_n")]
     top_n: usize,
     min_win_rate: Option<f64>,
+    min_recent_win_rate: Option<f64>,
+    max_pnl_hhi: Option<f64>,
 }
 
 fn default_top_n() -> usize {
     20
 }
 
-/// GET /api/export — export top results as structured JSON
+/// Largest `top_n` of a buffered JSON export; bigger exports go through
+/// `/export/ndjson` or an export job
+const MAX_EXPORT_ROWS: usize = 5_000;
+
+/// GET /api/v1/export — export top results as structured JSON
+#[utoipa::path(
+    get,
+    path = "/api/v1/export",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("top_n" = Option<usize>, Query, description = "Number of results (default 20, at most 5000)"),
+        ("min_win_rate" = Option<f64>, Query, description = "Minimum win rate (%)"),
+        ("min_recent_win_rate" = Option<f64>, Query, description = "Minimum win rate (%) over the last 30 days of the backtest"),
+        ("max_pnl_hhi" = Option<f64>, Query, description = "Largest concentration (0-1 HHI) of the PnL over weeks"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<ExportResponse>),
+        (status = 400, description = "top_n above the buffered export limit", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
 async fn api_export(
     State(state): State<AppState>,
+    workspace: Workspace,
     Query(params): Query<ExportParams>,
-) -> Json<serde_json::Value> {
-    let repo = DiscoveryRepository::new(state.db.pool());
+) -> ApiResult<ExportResponse> {
+    if params.top_n > MAX_EXPORT_ROWS {
+        return Err(ApiError::invalid_request(format!(
+            "top_n is limited to {}; use /export/ndjson or POST /export/jobs for larger exports",
+            MAX_EXPORT_ROWS
+        )));
+    }
+    let repo = DiscoveryRepository::new(state.db.pool()).with_workspace(workspace.as_str());
 
     let min_wr = params.min_win_rate;
-    match repo
-        .get_all_paginated(params.top_n as i64, 0, None, None, min_wr, Some("score"))
+    let (records, total_in_db) = repo
+        .get_all_paginated(
+            params.top_n as i64,
+            0,
+            None,
+            None,
+            min_wr,
+            params.min_recent_win_rate,
+            params.max_pnl_hhi,
+            Some("score"),
+        )
         .await
-    {
-        Ok((records, total_in_db)) => {
-            let results: Vec<serde_json::Value> = records
-                .iter()
-                .enumerate()
-                .map(|(i, r)| {
-                    let params_json: serde_json::Value =
-                        serde_json::from_str(&r.strategy_params).unwrap_or_default();
-
-                    let wr: f64 = r.win_rate.parse().unwrap_or(0.0);
-                    let sr: f64 = r.sharpe_ratio.parse().unwrap_or(0.0);
-                    let recommendation = if wr > 70.0 && sr > 1.5 {
-                        "High confidence — strong risk-adjusted returns"
-                    } else if wr > 60.0 {
-                        "Moderate confidence — decent win rate"
-                    } else {
-                        "Low confidence — review parameters carefully"
-                    };
-
-                    serde_json::json!({
-                        "rank": i + 1,
-                        "strategy_name": r.strategy_name,
-                        "strategy_type": r.strategy_type,
-                        "params": params_json,
-                        "symbol": r.symbol,
-                        "metrics": {
-                            "composite_score": r.composite_score,
-                            "net_pnl": r.net_pnl,
-                            "win_rate": r.win_rate,
-                            "sharpe_ratio": r.sharpe_ratio,
-                            "max_drawdown_pct": r.max_drawdown_pct,
-                            "profit_factor": r.profit_factor,
-                            "total_trades": r.total_trades,
-                            "sortino_ratio": r.sortino_ratio,
-                            "max_consecutive_losses": r.max_consecutive_losses,
-                            "avg_win_pnl": r.avg_win_pnl,
-                            "avg_loss_pnl": r.avg_loss_pnl,
-                            "total_volume": r.total_volume,
-                            "annualized_return_pct": r.annualized_return_pct,
-                            "annualized_sharpe": r.annualized_sharpe,
-                            "strategy_confidence": r.strategy_confidence,
-                        },
-                        "recommendation": recommendation,
-                    })
-                })
-                .collect();
+        .map_err(|e| ApiError::database(format!("Export failed: {}", e)))?;
 
-            Json(serde_json::json!({
-                "generated_at": Utc::now().to_rfc3339(),
-                "total_backtests_in_db": total_in_db,
-                "export_filters": {
-                    "top_n": params.top_n,
-                    "min_win_rate": min_wr,
+    let results = records
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let wr = r.win_rate;
+            let sr = r.sharpe_ratio;
+            let concentrated = r
+                .top_week_pnl_share
+                .is_some_and(|share| share > engine::CONCENTRATED_WEEK_SHARE);
+            let recommendation = if concentrated {
+                "Low confidence — most of the PnL comes from a single week"
+            } else if wr > 70.0 && sr > 1.5 {
+                "High confidence — strong risk-adjusted returns"
+            } else if wr > 60.0 {
+                "Moderate confidence — decent win rate"
+            } else {
+                "Low confidence — review parameters carefully"
+            };
+
+            ExportedResult {
+                rank: i + 1,
+                strategy_name: r.strategy_name.clone(),
+                strategy_type: r.strategy_type.clone(),
+                params: serde_json::from_str(&r.strategy_params).unwrap_or_default(),
+                symbol: r.symbol.clone(),
+                metrics: ExportedMetrics {
+                    composite_score: r.composite_score,
+                    decayed_score: r.decayed_score,
+                    score_30d: r.score_30d,
+                    score_90d: r.score_90d,
+                    pnl_hhi: r.pnl_hhi,
+                    top_week_pnl_share: r.top_week_pnl_share,
+                    longest_drawdown_days: r.longest_drawdown_days,
+                    avg_recovery_days: r.avg_recovery_days,
+                    max_exposure_pct: r.max_exposure_pct,
+                    avg_exposure_pct: r.avg_exposure_pct,
+                    warmup_bars: r.warmup_bars,
+                    effective_days: r.effective_days,
+                    net_pnl: r.net_pnl,
+                    win_rate: r.win_rate,
+                    sharpe_ratio: r.sharpe_ratio,
+                    max_drawdown_pct: r.max_drawdown_pct,
+                    profit_factor: r.profit_factor,
+                    total_trades: r.total_trades,
+                    sortino_ratio: r.sortino_ratio,
+                    max_consecutive_losses: r.max_consecutive_losses,
+                    avg_win_pnl: r.avg_win_pnl,
+                    avg_loss_pnl: r.avg_loss_pnl,
+                    total_volume: r.total_volume,
+                    annualized_return_pct: r.annualized_return_pct,
+                    annualized_sharpe: r.annualized_sharpe,
+                    strategy_confidence: r.strategy_confidence,
                 },
-                "results": results,
-            }))
+                recommendation,
+                chart_url: chart_strategy(r)
+                    .ok()
+                    .and(r.id)
+                    .map(|id| format!("/api/v1/knowledge/{}/chart.png", id)),
+                explanation: explain_record(r).ok().map(|e| e.text),
+            }
+        })
+        .collect();
+
+    Ok(ApiResponse::ok(ExportResponse {
+        generated_at: Utc::now().to_rfc3339(),
+        total_backtests_in_db: total_in_db,
+        export_filters: ExportFilters {
+            top_n: params.top_n,
+            min_win_rate: min_wr,
+            min_recent_win_rate: params.min_recent_win_rate,
+            max_pnl_hhi: params.max_pnl_hhi,
+        },
+        results,
+    }))
+}
+
+/// Rows fetched per keyset page while streaming an NDJSON export
+const NDJSON_PAGE_SIZE: i64 = 500;
+
+/// GET /api/v1/export/ndjson — stream every matching record, one JSON object per line.
+///
+/// Filters: strategy_type, symbol, min_win_rate, min_recent_win_rate, max_pnl_hhi,
+/// sort_by; `max_rows` stops the stream
+/// early. Rows are read in keyset pages of `NDJSON_PAGE_SIZE`, so memory use stays flat
+/// whatever the table size.
+#[utoipa::path(
+    get,
+    path = "/api/v1/export/ndjson",
+    tag = "knowledge",
+    params(
+        Workspace,
+        ("strategy_type" = Option<String>, Query, description = "Filter on the strategy type"),
+        ("symbol" = Option<String>, Query, description = "Filter on the symbol"),
+        ("min_win_rate" = Option<f64>, Query, description = "Minimum win rate (%)"),
+        ("min_recent_win_rate" = Option<f64>, Query, description = "Minimum win rate (%) over the last 30 days of the backtest"),
+        ("max_pnl_hhi" = Option<f64>, Query, description = "Largest concentration (0-1 HHI) of the PnL over weeks"),
+        ("sort_by" = Option<String>, Query, description = "Same values as /api/v1/knowledge"),
+        ("max_rows" = Option<i64>, Query, description = "Stop after this many records"),
+    ),
+    responses((status = 200, description = "One DiscoveryBacktestRecord per line", content_type = "application/x-ndjson", body = String))
+)]
+async fn api_export_ndjson(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let pool = state.db.pool_clone();
+    let strategy_type = params.get("strategy_type").cloned();
+    let symbol = params.get("symbol").cloned();
+    let min_win_rate: Option<f64> = params.get("min_win_rate").and_then(|s| s.parse().ok());
+    let min_recent_win_rate: Option<f64> = params
+        .get("min_recent_win_rate")
+        .and_then(|s| s.parse().ok());
+    let max_pnl_hhi: Option<f64> = params.get("max_pnl_hhi").and_then(|s| s.parse().ok());
+    let sort_by = params.get("sort_by").cloned();
+    let max_rows: i64 = params
+        .get("max_rows")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(i64::MAX);
+
+    // State: None = finished, Some((cursor, rows left)) = fetch the page after cursor
+    let start = (max_rows > 0).then_some((None, max_rows));
+    let stream = futures_util::stream::unfold(start, move |next: Option<(Option<KnowledgeCursor>, i64)>| {
+        let pool = pool.clone();
+        let (strategy_type, symbol, sort_by) =
+            (strategy_type.clone(), symbol.clone(), sort_by.clone());
+        let workspace = workspace.clone();
+        async move {
+            let (cursor, left) = next?;
+            let page = DiscoveryRepository::new(&pool)
+                .with_workspace(workspace.as_str())
+                .get_page_after(
+                    NDJSON_PAGE_SIZE.min(left),
+                    cursor.as_ref(),
+                    strategy_type.as_deref(),
+                    symbol.as_deref(),
+                    min_win_rate,
+                    min_recent_win_rate,
+                    max_pnl_hhi,
+                    sort_by.as_deref(),
+                )
+                .await;
+
+            match page {
+                Ok((records, next_cursor)) => {
+                    let mut chunk = String::new();
+                    for r in &records {
+                        chunk.push_str(&serde_json::to_string(r).unwrap_or_default());
+                        chunk.push('\n');
+                    }
+                    let left = left - records.len() as i64;
+                    let next = next_cursor.filter(|_| left > 0).map(|c| (Some(c), left));
+                    Some((Ok::<_, std::io::Error>(chunk), next))
+                }
+                Err(e) => {
+                    error!("NDJSON export failed: {}", e);
+                    Some((Err(std::io::Error::other(e.to_string())), None))
+                }
+            }
         }
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "error": format!("Export failed: {}", e),
-        })),
-    }
+    });
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"knowledge.ndjson\"",
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+}
+
+/// POST /api/v1/export/jobs — write the matching records to a gzipped NDJSON file in
+/// POLY_DISCOVERY_EXPORT_DIR, in the background
+#[utoipa::path(
+    post,
+    path = "/api/v1/export/jobs",
+    tag = "knowledge",
+    params(Workspace),
+    request_body = ExportFilter,
+    responses((status = 200, description = "The running job", body = ApiResponse<ExportJob>))
+)]
+async fn api_start_export_job(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Json(filter): Json<ExportFilter>,
+) -> ApiResult<ExportJob> {
+    let job = state.export_jobs.start(workspace.as_str());
+    info!(job = job.id, file = %job.file_name, "Export job started");
+    let running = job.clone();
+    tokio::spawn(async move {
+        state.export_jobs.run(&running, &state.db, &filter).await;
+    });
+    Ok(ApiResponse::ok(job))
+}
+
+/// GET /api/v1/export/jobs/{id} — progress of an export job, with the download link of
+/// its file once completed
+#[utoipa::path(
+    get,
+    path = "/api/v1/export/jobs/{id}",
+    tag = "knowledge",
+    params(Workspace, ("id" = u64, Path, description = "Export job id")),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<ExportJob>),
+        (status = 404, description = "No such job in this workspace", body = ErrorResponse),
+    )
+)]
+async fn api_export_job_status(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    axum::extract::Path(id): axum::extract::Path<u64>,
+) -> ApiResult<ExportJob> {
+    let job = state
+        .export_jobs
+        .get(id, workspace.as_str())
+        .ok_or_else(|| ApiError::not_found(format!("Export job {} not found", id)))?;
+    Ok(ApiResponse::ok(job))
 }
 
 // ============================================================================
 // API Handlers — Leaderboard
 // ============================================================================
 
-/// POST /api/leaderboard — start leaderboard analysis
-async fn api_analyze_leaderboard(
-    State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+/// POST /api/v1/leaderboard — start leaderboard analysis
+#[utoipa::path(
+    post,
+    path = "/api/v1/leaderboard",
+    tag = "leaderboard",
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Ack>),
+        (status = 409, description = "An analysis is already running", body = ErrorResponse),
+    )
+)]
+async fn api_analyze_leaderboard(State(state): State<AppState>) -> ApiResult<Ack> {
     if state.leaderboard_progress.is_running() {
-        return Ok(Json(serde_json::json!({
-            "success": false,
-            "message": "Leaderboard analysis already running",
-        })));
+        return Err(ApiError::already_running(
+            "Leaderboard analysis already running",
+        ));
     }
 
     info!("Starting leaderboard analysis");
@@ -996,14 +4612,19 @@ async fn api_analyze_leaderboard(
         analyze_leaderboard(&client, &progress, 10, db_pool).await;
     });
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Leaderboard analysis started",
-    })))
+    Ok(ApiResponse::ok(Ack::new("Leaderboard analysis started")))
 }
 
-/// GET /api/leaderboard/status — poll leaderboard analysis progress
-async fn api_leaderboard_status(State(state): State<AppState>) -> Json<serde_json::Value> {
+/// GET /api/v1/leaderboard/status — poll leaderboard analysis progress
+#[utoipa::path(
+    get,
+    path = "/api/v1/leaderboard/status",
+    tag = "leaderboard",
+    responses((status = 200, description = "OK", body = ApiResponse<LeaderboardStatusResponse>))
+)]
+async fn api_leaderboard_status(
+    State(state): State<AppState>,
+) -> ApiResponse<LeaderboardStatusResponse> {
     let progress = &state.leaderboard_progress;
     let status = *progress.status.read().unwrap();
     let total = progress.total_traders.load(std::sync::atomic::Ordering::Relaxed);
@@ -1018,52 +4639,61 @@ async fn api_leaderboard_status(State(state): State<AppState>) -> Json<serde_jso
         0.0
     };
 
-    Json(serde_json::json!({
-        "status": status,
-        "total_traders": total,
-        "analyzed": analyzed,
-        "progress_pct": progress_pct,
-        "current_trader": current_trader,
-        "results": results,
-        "error": error,
-    }))
+    ApiResponse::ok(LeaderboardStatusResponse {
+        status,
+        total_traders: total,
+        analyzed,
+        progress_pct,
+        current_trader,
+        results,
+        error,
+    })
 }
 
 // ============================================================================
 // API Handlers — Leaderboard Traders (DB persistence)
 // ============================================================================
 
-/// GET /api/leaderboard/traders — get persisted traders from DB
-async fn api_leaderboard_traders(State(state): State<AppState>) -> Json<serde_json::Value> {
+/// GET /api/v1/leaderboard/traders — get persisted traders from DB
+#[utoipa::path(
+    get,
+    path = "/api/v1/leaderboard/traders",
+    tag = "leaderboard",
+    responses(
+        (status = 200, description = "Persisted trader analyses", body = ApiResponse<Vec<LeaderboardTraderRecord>>),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
+async fn api_leaderboard_traders(
+    State(state): State<AppState>,
+) -> ApiResult<Vec<LeaderboardTraderRecord>> {
     let repo = LeaderboardRepository::new(state.db.pool());
-    match repo.get_all_traders().await {
-        Ok(traders) => Json(serde_json::json!({
-            "success": true,
-            "data": traders,
-            "total": traders.len(),
-        })),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "error": format!("Failed to load traders: {}", e),
-            "data": [],
-            "total": 0,
-        })),
-    }
+    let traders = repo
+        .get_all_traders()
+        .await
+        .map_err(|e| ApiError::database(format!("Failed to load traders: {}", e)))?;
+    Ok(ApiResponse::list(traders))
 }
 
 // ============================================================================
 // API Handlers — Trade Watcher
 // ============================================================================
 
-/// POST /api/watcher/start — start the trade watcher
-async fn api_start_watcher(
-    State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+/// POST /api/v1/watcher/start — start the trade watcher
+#[utoipa::path(
+    post,
+    path = "/api/v1/watcher/start",
+    tag = "leaderboard",
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Ack>),
+        (status = 409, description = "The watcher is already running", body = ErrorResponse),
+    )
+)]
+async fn api_start_watcher(State(state): State<AppState>) -> ApiResult<Ack> {
     if state.watcher_progress.is_running() {
-        return Ok(Json(serde_json::json!({
-            "success": false,
-            "message": "Trade watcher is already running",
-        })));
+        return Err(ApiError::already_running(
+            "Trade watcher is already running",
+        ));
     }
 
     info!("Starting trade watcher");
@@ -1077,81 +4707,193 @@ async fn api_start_watcher(
         run_trade_watcher(&client, &progress, db_pool).await;
     });
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Trade watcher started",
-    })))
+    Ok(ApiResponse::ok(Ack::new("Trade watcher started")))
 }
 
-/// POST /api/watcher/stop — stop the trade watcher
-async fn api_stop_watcher(State(state): State<AppState>) -> Json<serde_json::Value> {
+/// POST /api/v1/watcher/stop — stop the trade watcher
+#[utoipa::path(
+    post,
+    path = "/api/v1/watcher/stop",
+    tag = "leaderboard",
+    responses((status = 200, description = "OK", body = ApiResponse<Ack>))
+)]
+async fn api_stop_watcher(State(state): State<AppState>) -> ApiResponse<Ack> {
     state
         .watcher_progress
         .cancelled
         .store(true, std::sync::atomic::Ordering::Relaxed);
     info!("Trade watcher stop requested via API");
-    Json(serde_json::json!({
-        "success": true,
-        "message": "Watcher stop requested",
-    }))
+    ApiResponse::ok(Ack::new("Watcher stop requested"))
 }
 
-/// GET /api/watcher/status — poll trade watcher status + alerts
-async fn api_watcher_status(State(state): State<AppState>) -> Json<serde_json::Value> {
+/// GET /api/v1/watcher/status — poll trade watcher status + alerts
+#[utoipa::path(
+    get,
+    path = "/api/v1/watcher/status",
+    tag = "leaderboard",
+    responses((status = 200, description = "OK", body = ApiResponse<WatcherStatusResponse>))
+)]
+async fn api_watcher_status(State(state): State<AppState>) -> ApiResponse<WatcherStatusResponse> {
     let progress = &state.watcher_progress;
     let status = *progress.status.read().unwrap();
     let alerts = progress.alerts.read().unwrap().clone();
     let watched_count = *progress.watched_count.read().unwrap();
     let error = progress.error_message.read().unwrap().clone();
 
-    Json(serde_json::json!({
-        "status": status,
-        "watched_count": watched_count,
-        "alerts": alerts,
-        "error": error,
-    }))
+    ApiResponse::ok(WatcherStatusResponse {
+        status,
+        watched_count,
+        alerts,
+        error,
+    })
+}
+
+// ============================================================================
+// API Handlers — Gabagool Scanner
+// ============================================================================
+
+/// POST /api/v1/gabagool/scanner/start — start scanning hourly crypto markets
+/// (optional JSON body: max_pair_cost, size_per_side, poll_interval_secs, fee_config)
+#[utoipa::path(
+    post,
+    path = "/api/v1/gabagool/scanner/start",
+    tag = "gabagool",
+    request_body = Option<GabagoolScannerConfig>,
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Ack>),
+        (status = 409, description = "The scanner is already running", body = ErrorResponse),
+    )
+)]
+async fn api_start_gabagool_scanner(
+    State(state): State<AppState>,
+    config: Option<Json<GabagoolScannerConfig>>,
+) -> ApiResult<Ack> {
+    if state.gabagool_scanner_progress.is_running() {
+        return Err(ApiError::already_running(
+            "Gabagool scanner is already running",
+        ));
+    }
+
+    let config = config.map(|Json(c)| c).unwrap_or_default();
+    info!(max_pair_cost = %config.max_pair_cost, "Starting Gabagool scanner");
+    state.gabagool_scanner_progress.reset(config);
+
+    let client = state.polymarket.clone();
+    let progress = state.gabagool_scanner_progress.clone();
+
+    tokio::spawn(async move {
+        run_gabagool_scanner(&client, &progress).await;
+    });
+
+    Ok(ApiResponse::ok(Ack::new("Gabagool scanner started")))
+}
+
+/// POST /api/v1/gabagool/scanner/stop — stop the Gabagool scanner
+#[utoipa::path(
+    post,
+    path = "/api/v1/gabagool/scanner/stop",
+    tag = "gabagool",
+    responses((status = 200, description = "OK", body = ApiResponse<Ack>))
+)]
+async fn api_stop_gabagool_scanner(State(state): State<AppState>) -> ApiResponse<Ack> {
+    state
+        .gabagool_scanner_progress
+        .cancelled
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+    info!("Gabagool scanner stop requested via API");
+    ApiResponse::ok(Ack::new("Scanner stop requested"))
+}
+
+/// GET /api/v1/gabagool/opportunities — current opportunities + recent opportunity events
+#[utoipa::path(
+    get,
+    path = "/api/v1/gabagool/opportunities",
+    tag = "gabagool",
+    responses((status = 200, description = "OK", body = ApiResponse<GabagoolOpportunitiesResponse>))
+)]
+async fn api_gabagool_opportunities(
+    State(state): State<AppState>,
+) -> ApiResponse<GabagoolOpportunitiesResponse> {
+    use std::sync::atomic::Ordering;
+
+    let progress = &state.gabagool_scanner_progress;
+    let status = *progress.status.read().unwrap();
+    let active = progress.active.read().unwrap().clone();
+    let events = progress.events.read().unwrap().clone();
+    let config = progress.config.read().unwrap().clone();
+
+    ApiResponse::ok(GabagoolOpportunitiesResponse {
+        status,
+        config,
+        scans: progress.scans.load(Ordering::Relaxed),
+        markets_scanned: progress.markets_scanned.load(Ordering::Relaxed),
+        last_scan_at: *progress.last_scan_at.read().unwrap(),
+        opportunities: active,
+        events,
+        error: progress.error_message.read().unwrap().clone(),
+    })
 }
 
 // ============================================================================
 // Strategies Catalog
 // ============================================================================
 
-/// GET /api/strategies/catalog — return the web-researched strategies catalog
-async fn api_strategies_catalog() -> Json<serde_json::Value> {
-    let catalog = engine::get_catalog();
-    Json(serde_json::json!({
-        "success": true,
-        "data": catalog,
-        "total": catalog.len(),
-    }))
+/// GET /api/v1/strategies/catalog — return the web-researched strategies catalog
+#[utoipa::path(
+    get,
+    path = "/api/v1/strategies/catalog",
+    tag = "knowledge",
+    responses((status = 200, description = "Web-researched strategies catalogue", body = ApiResponse<Vec<WebStrategyCatalogEntry>>))
+)]
+async fn api_strategies_catalog() -> ApiResponse<Vec<WebStrategyCatalogEntry>> {
+    ApiResponse::list(engine::get_catalog())
+}
+
+/// GET /api/v1/strategies/schema — JSON Schema of the strategy parameters
+#[utoipa::path(
+    get,
+    path = "/api/v1/strategies/schema",
+    tag = "knowledge",
+    responses((status = 200, description = "JSON Schemas of DiscoveryStrategyType and IndicatorParams", body = ApiResponse<StrategySchemas>))
+)]
+async fn api_strategies_schema() -> ApiResponse<StrategySchemas> {
+    ApiResponse::ok(strategy_schemas())
 }
 
 // ============================================================================
 // Profile Analysis endpoints
 // ============================================================================
 
-#[derive(Deserialize)]
+#[derive(Deserialize, utoipa::ToSchema)]
 struct ProfileAnalyzeRequest {
     username: String,
 }
 
+/// POST /api/v1/profile/analyze — start the analysis of a Polymarket username
+#[utoipa::path(
+    post,
+    path = "/api/v1/profile/analyze",
+    tag = "profile",
+    request_body = ProfileAnalyzeRequest,
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Ack>),
+        (status = 400, description = "Empty username", body = ErrorResponse),
+        (status = 409, description = "An analysis is already running", body = ErrorResponse),
+    )
+)]
 async fn api_start_profile_analysis(
     State(state): State<AppState>,
     Json(body): Json<ProfileAnalyzeRequest>,
-) -> (StatusCode, Json<serde_json::Value>) {
+) -> ApiResult<Ack> {
     let username = body.username.trim().to_string();
     if username.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({ "error": "Username is required" })),
-        );
+        return Err(ApiError::invalid_request("Username is required"));
     }
 
     if state.profile_progress.is_running() {
-        return (
-            StatusCode::CONFLICT,
-            Json(serde_json::json!({ "error": "Profile analysis already running" })),
-        );
+        return Err(ApiError::already_running(
+            "Profile analysis already running",
+        ));
     }
 
     state.profile_progress.reset(&username);
@@ -1170,15 +4912,17 @@ async fn api_start_profile_analysis(
         .await;
     });
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({ "success": true, "message": "Profile analysis started" })),
-    )
+    Ok(ApiResponse::ok(Ack::new("Profile analysis started")))
 }
 
-async fn api_profile_status(
-    State(state): State<AppState>,
-) -> Json<serde_json::Value> {
+/// GET /api/v1/profile/status — poll profile analysis progress (full result once complete)
+#[utoipa::path(
+    get,
+    path = "/api/v1/profile/status",
+    tag = "profile",
+    responses((status = 200, description = "OK", body = ApiResponse<ProfileStatusResponse>))
+)]
+async fn api_profile_status(State(state): State<AppState>) -> ApiResponse<ProfileStatusResponse> {
     let p = &state.profile_progress;
     let status = *p.status.read().unwrap();
     let completed = p.completed_steps.load(std::sync::atomic::Ordering::Relaxed);
@@ -1188,72 +4932,86 @@ async fn api_profile_status(
     let wallet = p.wallet.read().unwrap().clone();
     let error = p.error_message.read().unwrap().clone();
 
-    let mut response = serde_json::json!({
-        "status": format!("{:?}", status),
-        "completed_steps": completed,
-        "total_steps": total,
-        "current_step": current_step,
-        "username": username,
-        "wallet": wallet,
-        "running": p.is_running(),
-    });
-
-    if let Some(err) = error {
-        response["error"] = serde_json::json!(err);
-    }
-
-    if status == ProfileStatus::Complete {
-        if let Some(ref result) = *p.result.read().unwrap() {
-            response["result"] = serde_json::json!(result);
-        }
-    }
+    let result = if status == ProfileStatus::Complete {
+        p.result.read().unwrap().clone()
+    } else {
+        None
+    };
 
-    Json(response)
+    ApiResponse::ok(ProfileStatusResponse {
+        status: format!("{:?}", status),
+        completed_steps: completed,
+        total_steps: total,
+        current_step,
+        username,
+        wallet,
+        running: p.is_running(),
+        error,
+        result,
+    })
 }
 
-async fn api_cancel_profile_analysis(
-    State(state): State<AppState>,
-) -> Json<serde_json::Value> {
+/// POST /api/v1/profile/cancel — cancel profile analysis
+#[utoipa::path(
+    post,
+    path = "/api/v1/profile/cancel",
+    tag = "profile",
+    responses((status = 200, description = "OK", body = ApiResponse<Ack>))
+)]
+async fn api_cancel_profile_analysis(State(state): State<AppState>) -> ApiResponse<Ack> {
     state
         .profile_progress
         .cancelled
         .store(true, std::sync::atomic::Ordering::Relaxed);
-    Json(serde_json::json!({ "success": true, "message": "Cancellation requested" }))
+    ApiResponse::ok(Ack::new("Cancellation requested"))
 }
 
+/// GET /api/v1/profile/history — past profile analyses
+#[utoipa::path(
+    get,
+    path = "/api/v1/profile/history",
+    tag = "profile",
+    responses(
+        (status = 200, description = "Past profile analyses", body = ApiResponse<Vec<ProfileAnalysisRecord>>),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
 async fn api_profile_history(
     State(state): State<AppState>,
-) -> (StatusCode, Json<serde_json::Value>) {
+) -> ApiResult<Vec<ProfileAnalysisRecord>> {
     let repo = ProfileRepository::new(state.db.pool());
-    match repo.get_all_analyses().await {
-        Ok(analyses) => (
-            StatusCode::OK,
-            Json(serde_json::json!({
-                "success": true,
-                "data": analyses,
-                "total": analyses.len(),
-            })),
-        ),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": format!("DB error: {}", e) })),
-        ),
-    }
+    let analyses = repo
+        .get_all_analyses()
+        .await
+        .map_err(|e| ApiError::database(format!("DB error: {}", e)))?;
+    Ok(ApiResponse::list(analyses))
 }
 
 // ============================================================================
 // Orderbook Backtest Analysis
 // ============================================================================
 
+/// POST /api/v1/orderbook/analyze — start (or resume) the orderbook backtest
+#[utoipa::path(
+    post,
+    path = "/api/v1/orderbook/analyze",
+    tag = "orderbook",
+    params(
+        ("lookback_days" = Option<u32>, Query, description = "Days of markets to analyze (default 30)"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Ack>),
+        (status = 409, description = "A backtest is already running", body = ErrorResponse),
+    )
+)]
 async fn api_start_ob_backtest(
     State(state): State<AppState>,
     Query(params): Query<HashMap<String, String>>,
-) -> (StatusCode, Json<serde_json::Value>) {
+) -> ApiResult<Ack> {
     if state.ob_backtest_progress.is_running() {
-        return (
-            StatusCode::CONFLICT,
-            Json(serde_json::json!({ "error": "Orderbook backtest already running" })),
-        );
+        return Err(ApiError::already_running(
+            "Orderbook backtest already running",
+        ));
     }
 
     let lookback_days: u32 = params
@@ -1275,15 +5033,22 @@ async fn api_start_ob_backtest(
         run_orderbook_backtest(&progress, &client, db_pool, lookback_days).await;
     });
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({ "success": true, "message": format!("Orderbook backtest started ({} days lookback)", lookback_days) })),
-    )
+    Ok(ApiResponse::ok(Ack::new(format!(
+        "Orderbook backtest started ({} days lookback)",
+        lookback_days
+    ))))
 }
 
+/// GET /api/v1/orderbook/status — poll orderbook backtest progress
+#[utoipa::path(
+    get,
+    path = "/api/v1/orderbook/status",
+    tag = "orderbook",
+    responses((status = 200, description = "OK", body = ApiResponse<ObBacktestStatusResponse>))
+)]
 async fn api_ob_backtest_status(
     State(state): State<AppState>,
-) -> Json<serde_json::Value> {
+) -> ApiResponse<ObBacktestStatusResponse> {
     let p = &state.ob_backtest_progress;
     let status = *p.status.read().unwrap();
     let data_source = *p.data_source.read().unwrap();
@@ -1293,27 +5058,22 @@ async fn api_ob_backtest_status(
     let stats = p.stats.read().unwrap().clone();
     let logs = p.logs.read().unwrap().clone();
 
-    let mut response = serde_json::json!({
-        "status": format!("{:?}", status),
-        "data_source": format!("{}", data_source),
-        "running": p.is_running(),
-        "current_step": current_step,
-        "total_markets": p.total_markets.load(std::sync::atomic::Ordering::Relaxed),
-        "markets_discovered": p.markets_discovered.load(std::sync::atomic::Ordering::Relaxed),
-        "markets_fetched": p.markets_fetched.load(std::sync::atomic::Ordering::Relaxed),
-        "features_extracted": p.features_extracted.load(std::sync::atomic::Ordering::Relaxed),
-        "patterns_found": p.patterns_found.load(std::sync::atomic::Ordering::Relaxed),
-        "stats": stats,
-        "logs": logs,
-    });
-
-    if !patterns.is_empty() {
-        response["best_patterns"] = serde_json::json!(patterns);
-    }
-
-    if let Some(err) = error {
-        response["error"] = serde_json::json!(err);
-    }
+    let mut response = ObBacktestStatusResponse {
+        status: format!("{:?}", status),
+        data_source: format!("{}", data_source),
+        running: p.is_running(),
+        current_step,
+        total_markets: p.total_markets.load(std::sync::atomic::Ordering::Relaxed),
+        markets_discovered: p.markets_discovered.load(std::sync::atomic::Ordering::Relaxed),
+        markets_fetched: p.markets_fetched.load(std::sync::atomic::Ordering::Relaxed),
+        features_extracted: p.features_extracted.load(std::sync::atomic::Ordering::Relaxed),
+        patterns_found: p.patterns_found.load(std::sync::atomic::Ordering::Relaxed),
+        stats,
+        logs,
+        best_patterns: (!patterns.is_empty()).then_some(patterns),
+        error,
+        db_state: None,
+    };
 
     // When not running, include db_state for frontend resume display
     if !p.is_running() {
@@ -1330,36 +5090,55 @@ async fn api_ob_backtest_status(
                 .await
                 .ok()
                 .flatten();
-            response["db_state"] = serde_json::json!({
-                "total_markets": resume.total,
-                "unfetched": resume.unfetched,
-                "fetched": resume.fetched,
-                "extracted": resume.extracted,
-                "patterns": resume.patterns,
-                "last_step": last_step,
-                "data_source": data_source_state,
-                "last_run_timestamp": last_run,
+            response.db_state = Some(ObDbState {
+                total_markets: resume.total,
+                unfetched: resume.unfetched,
+                fetched: resume.fetched,
+                extracted: resume.extracted,
+                patterns: resume.patterns,
+                last_step,
+                data_source: data_source_state,
+                last_run_timestamp: last_run,
             });
         }
     }
 
-    Json(response)
+    ApiResponse::ok(response)
 }
 
-async fn api_cancel_ob_backtest(
-    State(state): State<AppState>,
-) -> Json<serde_json::Value> {
+/// POST /api/v1/orderbook/cancel — cancel orderbook backtest
+#[utoipa::path(
+    post,
+    path = "/api/v1/orderbook/cancel",
+    tag = "orderbook",
+    responses((status = 200, description = "OK", body = ApiResponse<Ack>))
+)]
+async fn api_cancel_ob_backtest(State(state): State<AppState>) -> ApiResponse<Ack> {
     state
         .ob_backtest_progress
         .cancelled
         .store(true, std::sync::atomic::Ordering::Relaxed);
-    Json(serde_json::json!({ "success": true, "message": "Cancellation requested" }))
+    ApiResponse::ok(Ack::new("Cancellation requested"))
 }
 
+/// GET /api/v1/orderbook/patterns — best detected patterns
+#[utoipa::path(
+    get,
+    path = "/api/v1/orderbook/patterns",
+    tag = "orderbook",
+    params(
+        ("limit" = Option<i64>, Query, description = "Number of patterns (default 50)"),
+        ("window" = Option<i64>, Query, description = "Only this time window (seconds)"),
+    ),
+    responses(
+        (status = 200, description = "Detected patterns", body = ApiResponse<Vec<ObPatternRecord>>),
+        (status = 500, description = "Database error", body = ErrorResponse),
+    )
+)]
 async fn api_ob_patterns(
     State(state): State<AppState>,
     Query(params): Query<HashMap<String, String>>,
-) -> (StatusCode, Json<serde_json::Value>) {
+) -> ApiResult<Vec<ObPatternRecord>> {
     let limit = params
         .get("limit")
         .and_then(|v| v.parse::<i64>().ok())
@@ -1374,25 +5153,18 @@ async fn api_ob_patterns(
         OrderbookRepository::get_top_patterns(state.db.pool(), limit).await
     };
 
-    match result {
-        Ok(patterns) => (
-            StatusCode::OK,
-            Json(serde_json::json!({
-                "success": true,
-                "data": patterns,
-                "total": patterns.len(),
-            })),
-        ),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": format!("DB error: {}", e) })),
-        ),
-    }
+    let patterns = result.map_err(|e| ApiError::database(format!("DB error: {}", e)))?;
+    Ok(ApiResponse::list(patterns))
 }
 
-async fn api_ob_stats(
-    State(state): State<AppState>,
-) -> (StatusCode, Json<serde_json::Value>) {
+/// GET /api/v1/orderbook/stats — market counts and orderbook table sizes
+#[utoipa::path(
+    get,
+    path = "/api/v1/orderbook/stats",
+    tag = "orderbook",
+    responses((status = 200, description = "OK", body = ApiResponse<ObStatsResponse>))
+)]
+async fn api_ob_stats(State(state): State<AppState>) -> ApiResponse<ObStatsResponse> {
     let market_stats = OrderbookRepository::get_market_stats(state.db.pool())
         .await
         .unwrap_or_default();
@@ -1400,28 +5172,29 @@ async fn api_ob_stats(
         .await
         .unwrap_or_default();
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "success": true,
-            "market_stats": market_stats,
-            "db_size": size_stats,
-        })),
-    )
+    ApiResponse::ok(ObStatsResponse {
+        market_stats,
+        db_size: size_stats,
+    })
 }
 
 // ============================================================================
 // Orderbook Collector (Live WebSocket)
 // ============================================================================
 
-async fn api_start_ob_collector(
-    State(state): State<AppState>,
-) -> (StatusCode, Json<serde_json::Value>) {
+/// POST /api/v1/orderbook/collector/start — start the live WebSocket collector
+#[utoipa::path(
+    post,
+    path = "/api/v1/orderbook/collector/start",
+    tag = "orderbook",
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Ack>),
+        (status = 409, description = "The collector is already running", body = ErrorResponse),
+    )
+)]
+async fn api_start_ob_collector(State(state): State<AppState>) -> ApiResult<Ack> {
     if state.ob_collector_progress.is_running() {
-        return (
-            StatusCode::CONFLICT,
-            Json(serde_json::json!({ "error": "Collector already running" })),
-        );
+        return Err(ApiError::already_running("Collector already running"));
     }
 
     state.ob_collector_progress.reset();
@@ -1434,79 +5207,87 @@ async fn api_start_ob_collector(
         run_orderbook_collector(&progress, &client, db_pool).await;
     });
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({ "success": true, "message": "Collector started" })),
-    )
+    Ok(ApiResponse::ok(Ack::new("Collector started")))
 }
 
-async fn api_stop_ob_collector(
-    State(state): State<AppState>,
-) -> Json<serde_json::Value> {
+/// POST /api/v1/orderbook/collector/stop — stop the live collector
+#[utoipa::path(
+    post,
+    path = "/api/v1/orderbook/collector/stop",
+    tag = "orderbook",
+    responses((status = 200, description = "OK", body = ApiResponse<Ack>))
+)]
+async fn api_stop_ob_collector(State(state): State<AppState>) -> ApiResponse<Ack> {
     state
         .ob_collector_progress
         .cancelled
         .store(true, std::sync::atomic::Ordering::Relaxed);
-    Json(serde_json::json!({ "success": true, "message": "Collector stop requested" }))
+    ApiResponse::ok(Ack::new("Collector stop requested"))
 }
 
+/// GET /api/v1/orderbook/collector/status — poll collector status
+#[utoipa::path(
+    get,
+    path = "/api/v1/orderbook/collector/status",
+    tag = "orderbook",
+    responses((status = 200, description = "OK", body = ApiResponse<CollectorStatusResponse>))
+)]
 async fn api_ob_collector_status(
     State(state): State<AppState>,
-) -> Json<serde_json::Value> {
+) -> ApiResponse<CollectorStatusResponse> {
     let p = &state.ob_collector_progress;
     let status = *p.status.read().unwrap();
     let current_market = p.current_market.read().unwrap().clone();
     let last_snapshot = p.last_snapshot_time.read().unwrap().clone();
     let error = p.error_message.read().unwrap().clone();
 
-    let mut response = serde_json::json!({
-        "status": format!("{:?}", status),
-        "running": p.is_running(),
-        "markets_watched": p.markets_watched.load(std::sync::atomic::Ordering::Relaxed),
-        "snapshots_recorded": p.snapshots_recorded.load(std::sync::atomic::Ordering::Relaxed),
-        "current_market": current_market,
-        "last_snapshot_time": last_snapshot,
-    });
-
-    if let Some(err) = error {
-        response["error"] = serde_json::json!(err);
-    }
-
-    Json(response)
+    ApiResponse::ok(CollectorStatusResponse {
+        status: format!("{:?}", status),
+        running: p.is_running(),
+        markets_watched: p.markets_watched.load(std::sync::atomic::Ordering::Relaxed),
+        snapshots_recorded: p.snapshots_recorded.load(std::sync::atomic::Ordering::Relaxed),
+        current_market,
+        last_snapshot_time: last_snapshot,
+        error,
+    })
 }
 
+/// POST /api/v1/orderbook/cleanup — purge orderbook data
+#[utoipa::path(
+    post,
+    path = "/api/v1/orderbook/cleanup",
+    tag = "orderbook",
+    params(
+        ("mode" = Option<String>, Query, description = "partial (default), refetch or full"),
+    ),
+    responses((status = 200, description = "OK", body = ApiResponse<CleanupResponse>))
+)]
 async fn api_ob_cleanup(
     State(state): State<AppState>,
     Query(params): Query<HashMap<String, String>>,
-) -> (StatusCode, Json<serde_json::Value>) {
+) -> ApiResponse<CleanupResponse> {
     let pool = state.db.pool();
     let mode = params.get("mode").map(|s| s.as_str()).unwrap_or("partial");
 
     if mode == "refetch" {
         // Reset fetch status: keep markets, re-fetch data
         let total = OrderbookRepository::reset_fetch_status(pool).await.unwrap_or(0);
-        return (
-            StatusCode::OK,
-            Json(serde_json::json!({
-                "success": true,
-                "mode": "refetch",
-                "total_reset": total,
-                "message": "Markets kept, fetch status reset. Re-run backtest to fetch data.",
-            })),
-        );
+        return ApiResponse::ok(CleanupResponse {
+            mode: "refetch",
+            total_reset: Some(total),
+            message: Some("Markets kept, fetch status reset. Re-run backtest to fetch data."),
+            ..Default::default()
+        });
     }
 
     if mode == "full" {
         // Full reset: delete ALL orderbook data
         let total = OrderbookRepository::full_reset(pool).await.unwrap_or(0);
-        return (
-            StatusCode::OK,
-            Json(serde_json::json!({
-                "success": true,
-                "mode": "full",
-                "total_deleted": total,
-            })),
-        );
+        return ApiResponse::ok(CleanupResponse {
+            mode: "full",
+            total_deleted: Some(total),
+            ..Default::default()
+        });
     }
 
     // Partial purge: prices for extracted markets + old snapshots
@@ -1517,21 +5298,206 @@ async fn api_ob_cleanup(
         .await
         .unwrap_or(0);
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "success": true,
-            "mode": "partial",
-            "prices_purged": prices_purged,
-            "snapshots_purged": snapshots_purged,
-        })),
+    ApiResponse::ok(CleanupResponse {
+        mode: "partial",
+        prices_purged: Some(prices_purged),
+        snapshots_purged: Some(snapshots_purged),
+        ..Default::default()
+    })
+}
+
+// ============================================================================
+// API Handlers — Admin / Maintenance
+// ============================================================================
+
+/// POST /api/v1/admin/maintenance?keep=3 — run knowledge base maintenance in background
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/maintenance",
+    tag = "admin",
+    params(
+        Workspace,
+        ("keep" = Option<i64>, Query, description = "Results kept per strategy (default 3)"),
+    ),
+    responses(
+        (status = 200, description = "Started", body = ApiResponse<MaintenanceStarted>),
+        (status = 400, description = "keep < 1", body = ErrorResponse),
+        (status = 409, description = "Maintenance is already running", body = ErrorResponse),
+    )
+)]
+async fn api_start_maintenance(
+    State(state): State<AppState>,
+    workspace: Workspace,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult<MaintenanceStarted> {
+    let keep: i64 = params
+        .get("keep")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3);
+    if keep < 1 {
+        return Err(ApiError::invalid_request("keep must be >= 1"));
+    }
+
+    if state
+        .maintenance_running
+        .swap(true, std::sync::atomic::Ordering::SeqCst)
+    {
+        return Err(ApiError::already_running("Maintenance already running"));
+    }
+
+    state.maintenance_progress.reset("manual");
+
+    let progress = Arc::clone(&state.maintenance_progress);
+    let running = Arc::clone(&state.maintenance_running);
+    let db_pool = state.db.pool_clone();
+
+    tokio::spawn(async move {
+        run_maintenance(&db_pool, keep, Some(workspace.as_str()), &progress).await;
+        running.store(false, std::sync::atomic::Ordering::SeqCst);
+    });
+
+    Ok(ApiResponse::ok(MaintenanceStarted {
+        message: "Maintenance started".to_string(),
+        keep,
+    }))
+}
+
+/// GET /api/v1/admin/maintenance/status — poll maintenance progress and last report
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maintenance/status",
+    tag = "admin",
+    responses((status = 200, description = "OK", body = ApiResponse<MaintenanceStatusResponse>))
+)]
+async fn api_maintenance_status(
+    State(state): State<AppState>,
+) -> ApiResponse<MaintenanceStatusResponse> {
+    let p = &state.maintenance_progress;
+    let status = *p.status.read().unwrap();
+    let completed = p.completed_steps.load(std::sync::atomic::Ordering::Relaxed);
+    let total = p.total_steps.load(std::sync::atomic::Ordering::Relaxed);
+    let current_step = p.current_step.read().unwrap().clone();
+    let trigger = p.trigger.read().unwrap().clone();
+    let error = p.error_message.read().unwrap().clone();
+
+    ApiResponse::ok(MaintenanceStatusResponse {
+        status: format!("{:?}", status),
+        completed_steps: completed,
+        total_steps: total,
+        current_step,
+        trigger,
+        running: p.is_running(),
+        error,
+        last_report: p.last_report.read().unwrap().clone(),
+    })
+}
+
+/// POST /api/v1/admin/backup — write a compressed online snapshot to POLY_DISCOVERY_BACKUP_DIR
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/backup",
+    tag = "admin",
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<BackupResponse>),
+        (status = 409, description = "A backup is already running", body = ErrorResponse),
+        (status = 500, description = "Backup failed", body = ErrorResponse),
     )
+)]
+async fn api_backup(State(state): State<AppState>) -> ApiResult<BackupResponse> {
+    if state
+        .backup_running
+        .swap(true, std::sync::atomic::Ordering::SeqCst)
+    {
+        return Err(ApiError::already_running("Backup already running"));
+    }
+
+    let out = state.backup_dir.join(backup_file_name(Utc::now()));
+    let result = create_backup(&state.db, &out).await;
+    state
+        .backup_running
+        .store(false, std::sync::atomic::Ordering::SeqCst);
+
+    let report = result.map_err(|e| {
+        error!("Backup failed: {:#}", e);
+        ApiError::internal(format!("Backup failed: {:#}", e))
+    })?;
+    Ok(ApiResponse::ok(BackupResponse { backup: report }))
+}
+
+/// GET /api/v1/admin/cache — hit/miss metrics of the stats / top-strategies cache
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/cache",
+    tag = "admin",
+    responses((status = 200, description = "OK", body = ApiResponse<CacheStatsResponse>))
+)]
+async fn api_cache_stats(State(state): State<AppState>) -> ApiResponse<CacheStatsResponse> {
+    ApiResponse::ok(CacheStatsResponse {
+        cache: state.query_cache.stats(),
+        write_generation: write_generation(),
+    })
+}
+
+/// Background loop: run maintenance every day at `hour`:00 UTC (skipped if already running)
+fn spawn_nightly_maintenance(state: AppState, hour: u32, keep: i64) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(duration_until_utc_hour(Utc::now(), hour)).await;
+
+            if state
+                .maintenance_running
+                .swap(true, std::sync::atomic::Ordering::SeqCst)
+            {
+                info!("Nightly maintenance skipped: a maintenance run is already in progress");
+                continue;
+            }
+
+            state.maintenance_progress.reset("scheduled");
+            run_maintenance(state.db.pool(), keep, None, &state.maintenance_progress).await;
+            state
+                .maintenance_running
+                .store(false, std::sync::atomic::Ordering::SeqCst);
+
+            if *state.maintenance_progress.status.read().unwrap() == MaintenanceStatus::Error {
+                error!("Nightly maintenance failed");
+            }
+        }
+    });
 }
 
 // ============================================================================
 // Helpers
 // ============================================================================
 
+/// Directory for API-triggered and default CLI backups
+fn backup_dir() -> PathBuf {
+    PathBuf::from(
+        std::env::var("POLY_DISCOVERY_BACKUP_DIR").unwrap_or_else(|_| "data/backups".to_string()),
+    )
+}
+
+/// Directory export jobs write to (served under /api/v1/export/files)
+fn export_dir() -> PathBuf {
+    PathBuf::from(
+        std::env::var("POLY_DISCOVERY_EXPORT_DIR").unwrap_or_else(|_| "data/exports".to_string()),
+    )
+}
+
+/// Knowledge base retention of discovery runs, from `POLY_DISCOVERY_RETENTION_KEEP`
+/// (top N per strategy per symbol), `POLY_DISCOVERY_RETENTION_MIN_SCORE` (score floor)
+/// and `POLY_DISCOVERY_RETENTION_NEGATIVE_DAYS` (age of negative-PnL rows to drop)
+fn retention_policy() -> RetentionPolicy {
+    fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+        std::env::var(name).ok().and_then(|s| s.trim().parse().ok())
+    }
+    RetentionPolicy {
+        keep_top_n: var::<i64>("POLY_DISCOVERY_RETENTION_KEEP").filter(|n| *n > 0),
+        min_score: var::<f64>("POLY_DISCOVERY_RETENTION_MIN_SCORE").filter(|s| s.is_finite()),
+        drop_negative_after_days: var::<i64>("POLY_DISCOVERY_RETENTION_NEGATIVE_DAYS")
+            .filter(|d| *d >= 0),
+    }
+}
+
 /// Build export JSON from in-memory results (used by CLI run command)
 fn build_export_json(
     results: &[DiscoveryResult],
@@ -1563,6 +5529,7 @@ fn build_export_json(
                 "symbol": r.symbol,
                 "metrics": {
                     "composite_score": r.composite_score,
+                    "decayed_score": r.decayed_score,
                     "net_pnl": r.net_pnl,
                     "win_rate": r.win_rate,
                     "sharpe_ratio": r.sharpe_ratio,
@@ -1594,7 +5561,8 @@ fn build_export_json(
 // Cleanup command — keep top N per strategy, delete the rest
 // ============================================================================
 
-async fn cmd_cleanup(keep: i64) -> anyhow::Result<()> {
+async fn cmd_cleanup(keep: i64, workspace: &str) -> anyhow::Result<()> {
+    let workspace = Workspace::parse(workspace).map_err(anyhow::Error::msg)?;
     info!("Poly-Discover DB cleanup — keeping top {} per strategy (positive PnL only)", keep);
 
     let db_path =
@@ -1605,7 +5573,7 @@ async fn cmd_cleanup(keep: i64) -> anyhow::Result<()> {
     })?;
     info!("Database opened: {}", db_path);
 
-    let repo = DiscoveryRepository::new(db.pool());
+    let repo = DiscoveryRepository::new(db.pool()).with_workspace(workspace.as_str());
     let (deleted, remaining) = repo.cleanup_keep_top_n(keep).await.map_err(|e| {
         anyhow::anyhow!("Cleanup failed: {}", e)
     })?;
@@ -1618,3 +5586,423 @@ async fn cmd_cleanup(keep: i64) -> anyhow::Result<()> {
     info!("Done! Deleted {} records, {} remaining.", deleted, remaining);
     Ok(())
 }
+
+// ============================================================================
+// Migrate-params command — upgrade or quarantine outdated strategy_params
+// ============================================================================
+
+async fn cmd_migrate_params(dry_run: bool) -> anyhow::Result<()> {
+    info!(
+        "Poly-Discover strategy params migration (target schema_version {}){}",
+        engine::STRATEGY_PARAMS_VERSION,
+        if dry_run { " — dry run" } else { "" }
+    );
+
+    let db_path =
+        std::env::var("POLY_DISCOVERY_DB_PATH").unwrap_or_else(|_| "data/discovery.db".to_string());
+    let db = persistence::Database::new(&db_path).await.map_err(|e| {
+        error!("Failed to initialize database: {}", e);
+        anyhow::anyhow!("Database initialization failed: {}", e)
+    })?;
+    info!("Database opened: {}", db_path);
+
+    let report = migrate_strategy_params(db.pool(), dry_run).await?;
+
+    let quarantined_total = DiscoveryRepository::new(db.pool())
+        .count_quarantined()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to count quarantined rows: {}", e))?;
+
+    info!(
+        "Done! Scanned {}, upgraded {}, duplicates removed {}, quarantined {} ({} rows in quarantine).",
+        report.scanned,
+        report.upgraded,
+        report.duplicates_removed,
+        report.quarantined,
+        quarantined_total
+    );
+    Ok(())
+}
+
+// ============================================================================
+// Backup / restore commands
+// ============================================================================
+
+async fn cmd_backup(out: Option<String>) -> anyhow::Result<()> {
+    let out = out
+        .map(PathBuf::from)
+        .unwrap_or_else(|| backup_dir().join(backup_file_name(Utc::now())));
+
+    let db_path =
+        std::env::var("POLY_DISCOVERY_DB_PATH").unwrap_or_else(|_| "data/discovery.db".to_string());
+    let db = persistence::Database::new(&db_path).await.map_err(|e| {
+        error!("Failed to initialize database: {}", e);
+        anyhow::anyhow!("Database initialization failed: {}", e)
+    })?;
+    info!("Database opened: {}", db_path);
+
+    let report = create_backup(&db, &out).await?;
+
+    info!(
+        "Done! {} ({} bytes, {} bytes uncompressed).",
+        report.path, report.compressed_bytes, report.db_bytes
+    );
+    Ok(())
+}
+
+async fn cmd_restore(from: &str) -> anyhow::Result<()> {
+    let db_path =
+        std::env::var("POLY_DISCOVERY_DB_PATH").unwrap_or_else(|_| "data/discovery.db".to_string());
+    info!("Restoring {} into {} — make sure no server is using it", from, db_path);
+
+    let report = restore_backup(Path::new(from), Path::new(&db_path)).await?;
+
+    match report.previous_db {
+        Some(prev) => info!("Done! Previous database kept as {}.", prev),
+        None => info!("Done! Database created from snapshot."),
+    }
+    Ok(())
+}
+
+// ============================================================================
+// ML feature export command
+// ============================================================================
+
+async fn cmd_features(
+    symbols: Vec<String>,
+    days: u32,
+    out: &str,
+    horizons: &[usize],
+) -> anyhow::Result<()> {
+    if horizons.is_empty() || horizons.contains(&0) {
+        anyhow::bail!("--horizons must be a list of positive bar counts");
+    }
+
+    let binance = BinanceClient::new();
+    let end_time = Utc::now().timestamp_millis();
+    let start_time = end_time - days as i64 * 24 * 60 * 60 * 1000;
+
+    let mut table = FeatureTable::default();
+    for symbol in &symbols {
+        let klines = binance
+            .get_klines_paginated(symbol, "15m", start_time, end_time)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch klines for {}: {}", symbol, e))?;
+        let features = compute_features(symbol, &klines, horizons);
+        info!(
+            symbol = %symbol,
+            bars = klines.len(),
+            rows = features.rows.len(),
+            "Computed features"
+        );
+        table.append(features);
+    }
+
+    let out_path = PathBuf::from(out);
+    let rows = tokio::task::spawn_blocking(move || write_features_parquet(&table, &out_path))
+        .await??;
+
+    info!("Done! {} rows written to {}.", rows, out);
+    Ok(())
+}
+
+// ============================================================================
+// Simulate command — discovery grid on synthetic klines
+// ============================================================================
+
+async fn cmd_simulate(
+    config: SyntheticConfig,
+    sizing_mode: SizingMode,
+    top_n: usize,
+    export: Option<String>,
+) -> anyhow::Result<()> {
+    println!("\n=== Poly-Discover v{} — simulation ===", APP_VERSION);
+    println!(
+        "Model: {} | Bars: {} | Volatility: {} | Drift: {} | Seed: {}",
+        config.model.as_str(),
+        config.bars,
+        config.volatility,
+        config.drift,
+        config.seed
+    );
+    println!();
+
+    let limits = BacktestLimits::default();
+    let report: SimulationReport = tokio::task::spawn_blocking(move || {
+        let on_progress = |done: usize, total: usize| {
+            if done.is_multiple_of(100) || done == total {
+                print!("\r  Backtesting grid {}/{}   ", done, total);
+                let _ = std::io::Write::flush(&mut std::io::stdout());
+            }
+        };
+        simulate(&config, sizing_mode, &limits, top_n, on_progress)
+    })
+    .await?;
+    println!();
+
+    let scores = &report.scores;
+    println!(
+        "\n{} backtests, {} scored (>= 5 trades), {} profitable",
+        report.tested, report.scored, report.profitable
+    );
+    println!(
+        "Composite score: min {:.1} | p25 {:.1} | median {:.1} | p75 {:.1} | p90 {:.1} | max {:.1}",
+        scores.min, scores.p25, scores.median, scores.p75, scores.p90, scores.max
+    );
+    if config.model == SyntheticModel::Gbm {
+        println!("The series is a random walk: every edge above is fitted to noise.");
+    }
+    print_results(&report.top, top_n);
+
+    if let Some(export_path) = export {
+        std::fs::write(&export_path, serde_json::to_string_pretty(&report)?)?;
+        println!("\nReport exported to {}", export_path);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Calibrate-probability command — logistic slope from resolved markets
+// ============================================================================
+
+async fn cmd_calibrate_probability(symbol: &str, days: i64) -> anyhow::Result<()> {
+    println!(
+        "\n=== Poly-Discover v{} — probability calibration ===",
+        APP_VERSION
+    );
+    println!(
+        "Symbol: {} | Resolved markets of the last {} days",
+        symbol, days
+    );
+    println!();
+
+    let calibration = calibrate_logistic(
+        &BinanceClient::new(),
+        &PolymarketDataClient::new(),
+        symbol,
+        days,
+    )
+    .await?;
+    println!(
+        "{} markets, {} price points",
+        calibration.markets, calibration.samples
+    );
+    println!(
+        "Logistic slope: {} (RMSE {:.4}, linear model {:.4})",
+        calibration.slope, calibration.rmse, calibration.linear_rmse
+    );
+    println!(
+        "Use it with: poly-discover run --probability-model logistic:{}",
+        calibration.slope
+    );
+    Ok(())
+}
+
+// ============================================================================
+// Bench command — timings of the discovery hot paths
+// ============================================================================
+
+fn format_ns(ns: f64) -> String {
+    if ns >= 1e9 {
+        format!("{:.2} s", ns / 1e9)
+    } else if ns >= 1e6 {
+        format!("{:.2} ms", ns / 1e6)
+    } else if ns >= 1e3 {
+        format!("{:.2} µs", ns / 1e3)
+    } else {
+        format!("{:.0} ns", ns)
+    }
+}
+
+async fn cmd_bench(
+    config: BenchConfig,
+    filter: Option<String>,
+    out: Option<String>,
+    baseline: Option<String>,
+    max_regression_pct: f64,
+) -> anyhow::Result<()> {
+    // Read the baseline first: a bad path should not cost a full run
+    let baseline: Option<BenchReport> = match baseline {
+        Some(path) => Some(serde_json::from_str(&std::fs::read_to_string(&path)?)?),
+        None => None,
+    };
+    println!("\n=== Poly-Discover v{} — benchmarks ===", APP_VERSION);
+    println!(
+        "Bars: {} | Iterations: {} | Warmup: {}",
+        config.bars, config.iterations, config.warmup
+    );
+    if cfg!(debug_assertions) {
+        println!("Debug build: timings are not representative, use --release");
+    }
+    println!();
+    println!(
+        "{:<36} {:>12} {:>12} {:>14}",
+        "Case", "Min", "Mean", "Bars/s"
+    );
+
+    let report = tokio::task::spawn_blocking(move || {
+        run_benchmarks(&config, filter.as_deref(), |timing| {
+            println!(
+                "{:<36} {:>12} {:>12} {:>14}",
+                timing.name,
+                format_ns(timing.min_ns),
+                format_ns(timing.mean_ns),
+                timing
+                    .bars_per_sec
+                    .map(|b| format!("{:.0}", b))
+                    .unwrap_or_else(|| "-".to_string())
+            );
+        })
+    })
+    .await?;
+    if report.timings.is_empty() {
+        anyhow::bail!("No benchmark case matches the filter");
+    }
+
+    if let Some(out) = out {
+        std::fs::write(&out, serde_json::to_string_pretty(&report)?)?;
+        println!("\nReport saved to {}", out);
+    }
+
+    if let Some(baseline) = baseline {
+        let regressions = find_regressions(&report, &baseline, max_regression_pct);
+        if regressions.is_empty() {
+            println!(
+                "\nNo regression over {}% against the baseline",
+                max_regression_pct
+            );
+        } else {
+            println!();
+            for r in &regressions {
+                println!(
+                    "REGRESSION {}: {} -> {} (+{:.1}%)",
+                    r.name,
+                    format_ns(r.baseline_ns),
+                    format_ns(r.current_ns),
+                    r.change_pct
+                );
+            }
+            anyhow::bail!(
+                "{} case(s) slower than the baseline by more than {}%",
+                regressions.len(),
+                max_regression_pct
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use persistence::repository::DiscoveryBacktestRecord;
+    use persistence::Database;
+
+    fn record(i: usize) -> DiscoveryBacktestRecord {
+        serde_json::from_value(serde_json::json!({
+            "params_hash": format!("hash-{i}"),
+            "strategy_type": "rsi",
+            "strategy_name": "RSI",
+            "strategy_params": "{}",
+            "symbol": "BTCUSDT",
+            "days": 90,
+            "sizing_mode": "fixed",
+            "composite_score": i as f64,
+            "net_pnl": 10.0,
+            "gross_pnl": 12.0,
+            "total_fees": 2.0,
+            "win_rate": 55.0,
+            "total_trades": 20,
+            "sharpe_ratio": 1.0,
+            "max_drawdown_pct": 5.0,
+            "profit_factor": 1.3,
+            "avg_trade_pnl": 0.5,
+        }))
+        .unwrap()
+    }
+
+    /// A read-only server serves the records of a file another instance writes without
+    /// migrating or writing it
+    #[tokio::test]
+    async fn test_read_only_server_leaves_the_database_unchanged() {
+        let dir = std::env::temp_dir().join(format!("poly-read-only-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("discovery.db");
+        let db_path = path.to_string_lossy().to_string();
+
+        // A file from an older version: one table the migrations would create is missing
+        let db = Database::new(dir.join("scanner.db")).await.unwrap();
+        let records: Vec<_> = (0..3).map(record).collect();
+        DiscoveryRepository::new(db.pool())
+            .save_batch(&records)
+            .await
+            .unwrap();
+        sqlx::query("DROP TABLE run_crashes")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        // Copied, so no connection of the writer is still closing on the compared file
+        db.snapshot_into(&path).await.unwrap();
+        db.close().await;
+        let before = std::fs::read(&path).unwrap();
+
+        let db = open_database(&db_path, true).await.unwrap();
+        let state = AppState {
+            read_only: true,
+            ..app_state(db, 0).unwrap()
+        };
+        let stats = api_knowledge_stats(State(state.clone()), Workspace::default())
+            .await
+            .unwrap();
+        assert_eq!(stats.data.stats.total_backtests, 3);
+        let id = DiscoveryRepository::new(state.db.pool())
+            .get_top_results(1, None, None, DEFAULT_RANK_ORDER)
+            .await
+            .unwrap()[0]
+            .id
+            .unwrap();
+        assert!(knowledge_record(&state, &Workspace::default(), id)
+            .await
+            .is_ok());
+        assert!(DiscoveryRepository::new(state.db.pool())
+            .save_batch(&[record(3)])
+            .await
+            .is_err());
+        let (tables,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM sqlite_master WHERE name = 'run_crashes'")
+                .fetch_one(state.db.pool())
+                .await
+                .unwrap();
+        assert_eq!(tables, 0);
+        state.db.close().await;
+
+        assert_eq!(std::fs::read(&path).unwrap(), before);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    /// A second maintenance request is refused until the running pass is over
+    #[tokio::test]
+    async fn test_maintenance_runs_one_pass_at_a_time() {
+        let state = app_state(Database::in_memory().await.unwrap(), 0).unwrap();
+        let start = || {
+            api_start_maintenance(
+                State(state.clone()),
+                Workspace::default(),
+                Query(HashMap::new()),
+            )
+        };
+
+        let (first, second) = tokio::join!(start(), start());
+        assert!(first.is_ok());
+        let err = second.unwrap_err();
+        assert_eq!(err.code, dto::ErrorCode::AlreadyRunning);
+
+        while state
+            .maintenance_running
+            .load(std::sync::atomic::Ordering::SeqCst)
+        {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(start().await.is_ok());
+    }
+}
@@ -0,0 +1,195 @@
+//! OpenAPI specification of the HTTP API
+//!
+//! `ApiDoc` gathers the `#[utoipa::path]` annotations of the handlers in main.rs and is
+//! served at `GET /api/v1/openapi.json`; `GET /api/v1/docs` renders it with Swagger UI.
+//!
+//! Response bodies are the typed envelopes of `dto`. Only the `/api/v1` paths are
+//! documented; the legacy `/api/...` aliases return the flattened pre-v1 shapes.
+
+use axum::response::{Html, Json};
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "poly-discover",
+        description = "Discovery agent, knowledge base and Polymarket analysis API"
+    ),
+    paths(
+        crate::api_health,
+        crate::api_start_discovery,
+        crate::api_discovery_status,
+        crate::api_cancel_discovery,
+        crate::api_pause_discovery,
+        crate::api_resume_discovery,
+        crate::api_set_discovery_pacing,
+        crate::api_estimate_discovery,
+        crate::api_workers_status,
+        crate::api_worker_lease,
+        crate::api_worker_results,
+        crate::api_worker_fail,
+        crate::api_manual_backtest,
+        crate::api_list_presets,
+        crate::api_get_preset,
+        crate::api_save_preset,
+        crate::api_delete_preset,
+        crate::api_knowledge_base,
+        crate::api_knowledge_detail,
+        crate::api_knowledge_explain,
+        crate::api_knowledge_stub,
+        crate::api_top_strategies,
+        crate::api_knowledge_stats,
+        crate::api_fee_sensitivity,
+        crate::api_fill_sensitivity,
+        crate::api_build_ensemble,
+        crate::api_validate_strategy,
+        crate::api_strategy_history,
+        crate::api_knowledge_similar,
+        crate::api_knowledge_chart_png,
+        crate::api_knowledge_chart_svg,
+        crate::api_knowledge_daily_pnl,
+        crate::api_knowledge_costs,
+        crate::api_resolution_backtest,
+        crate::api_export,
+        crate::api_export_ndjson,
+        crate::api_start_export_job,
+        crate::api_export_job_status,
+        crate::api_compare_runs,
+        crate::api_run_data_quality,
+        crate::api_run_cycles,
+        crate::api_run_crashes,
+        crate::api_run_cycle_grid,
+        crate::api_risk_status,
+        crate::api_set_risk_limits,
+        crate::api_resume_strategy,
+        crate::api_paper_trade,
+        crate::api_paper_trade_portfolio,
+        crate::api_pipeline_status,
+        crate::api_pipeline_transition,
+        crate::api_pipeline_overview,
+        crate::api_apply_pipeline_rules,
+        crate::api_start_optimization,
+        crate::api_optimize_status,
+        crate::api_optimize_history,
+        crate::api_binance_klines,
+        crate::api_klines_diff,
+        crate::api_symbols,
+        crate::api_analyze_leaderboard,
+        crate::api_leaderboard_status,
+        crate::api_leaderboard_traders,
+        crate::api_start_watcher,
+        crate::api_stop_watcher,
+        crate::api_watcher_status,
+        crate::api_start_gabagool_scanner,
+        crate::api_stop_gabagool_scanner,
+        crate::api_gabagool_opportunities,
+        crate::api_strategies_catalog,
+        crate::api_strategies_schema,
+        crate::api_start_profile_analysis,
+        crate::api_profile_status,
+        crate::api_cancel_profile_analysis,
+        crate::api_profile_history,
+        crate::api_start_ob_backtest,
+        crate::api_ob_backtest_status,
+        crate::api_cancel_ob_backtest,
+        crate::api_ob_patterns,
+        crate::api_ob_stats,
+        crate::api_start_ob_collector,
+        crate::api_stop_ob_collector,
+        crate::api_ob_collector_status,
+        crate::api_ob_cleanup,
+        crate::api_start_maintenance,
+        crate::api_maintenance_status,
+        crate::api_backup,
+        crate::api_cache_stats,
+        openapi_json,
+    ),
+    tags(
+        (name = "system", description = "Health, API description"),
+        (name = "discovery", description = "Discovery agent runs"),
+        (name = "workers", description = "Remote workers of the distributed Phase 1"),
+        (name = "knowledge", description = "Stored backtest results"),
+        (name = "pipeline", description = "Promotion funnel of knowledge base records"),
+        (name = "risk", description = "Paper-trading risk limits and sessions"),
+        (name = "optimizer", description = "Single-strategy grid search"),
+        (name = "market", description = "Binance market data proxy"),
+        (name = "leaderboard", description = "Top Polymarket traders and trade watcher"),
+        (name = "gabagool", description = "Live YES+NO pair scanner"),
+        (name = "profile", description = "Polymarket profile analysis"),
+        (name = "orderbook", description = "Orderbook backtest and live collector"),
+        (name = "admin", description = "Maintenance, backups, cache"),
+    )
+)]
+pub struct ApiDoc;
+
+/// GET /api/v1/openapi.json — this specification
+#[utoipa::path(
+    get,
+    path = "/api/v1/openapi.json",
+    tag = "system",
+    responses((status = 200, description = "OpenAPI 3.1 document", body = Object))
+)]
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// GET /api/v1/docs — Swagger UI (assets from the swagger-ui-dist CDN)
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI_HTML)
+}
+
+const SWAGGER_UI_HTML: &str = r##"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>poly-discover API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/api/v1/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_documents_every_route() {
+        let doc = ApiDoc::openapi();
+        let operations: usize = doc
+            .paths
+            .paths
+            .values()
+            .map(|item| {
+                [&item.get, &item.post, &item.put, &item.delete]
+                    .iter()
+                    .filter(|op| op.is_some())
+                    .count()
+            })
+            .sum();
+        // 54 API routes + openapi.json
+        assert_eq!(operations, 87);
+
+        let discover = doc.paths.paths["/api/v1/discover"].post.as_ref().unwrap();
+        assert!(discover.request_body.is_some());
+        // Legacy aliases are served but not documented
+        assert!(doc.paths.paths.keys().all(|p| p.starts_with("/api/v1/")));
+
+        let json = serde_json::to_value(&doc).unwrap();
+        let schemas = &json["components"]["schemas"];
+        for name in [
+            "DiscoveryRequest",
+            "DiscoveryResult",
+            "DiscoveryBacktestRecord",
+            "ErrorResponse",
+        ] {
+            assert!(schemas.get(name).is_some(), "missing schema {name}");
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! `worker` command — remote backtest worker of a coordinator server
+//!
+//! The worker leases Phase 1 chunks from `POST /api/v1/workers/lease` of the
+//! coordinator, backtests them with `ChunkRunner` (klines fetched by the worker
+//! itself) and posts the results to `/workers/chunks/{id}/results`, or reports the
+//! chunk as failed so another worker gets it. Idle, it polls every `poll`. A worker
+//! stopped mid-chunk needs no cleanup: its lease expires and the chunk is reassigned.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use engine::{BinanceClient, ChunkRunner, GridChunk};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::dto::ChunkAccepted;
+
+#[derive(Deserialize)]
+struct Envelope<T> {
+    data: Option<T>,
+    error: Option<EnvelopeError>,
+}
+
+#[derive(Deserialize)]
+struct EnvelopeError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct LeaseReply {
+    chunk: Option<GridChunk>,
+}
+
+struct CoordinatorClient {
+    http: reqwest::Client,
+    /// `<coordinator>/api/v1/workers`
+    base: String,
+    worker_id: String,
+}
+
+impl CoordinatorClient {
+    async fn post<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> anyhow::Result<T> {
+        let url = format!("{}{}", self.base, path);
+        let envelope: Envelope<T> = self
+            .http
+            .post(&url)
+            .json(body)
+            .send()
+            .await
+            .with_context(|| format!("POST {}", url))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid reply of {}", url))?;
+        match (envelope.data, envelope.error) {
+            (Some(data), _) => Ok(data),
+            (None, Some(error)) => bail!("{}: {}", url, error.message),
+            (None, None) => bail!("{}: empty reply", url),
+        }
+    }
+
+    async fn lease(&self) -> anyhow::Result<Option<GridChunk>> {
+        let body = serde_json::json!({ "worker_id": self.worker_id });
+        let reply: LeaseReply = self.post("/lease", &body).await?;
+        Ok(reply.chunk)
+    }
+}
+
+/// Default worker id: `<host>-<pid>`
+pub fn default_worker_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "worker".to_string());
+    format!("{}-{}", host, std::process::id())
+}
+
+/// Lease, run and post chunks of `coordinator` until the process is stopped
+pub async fn run_worker(
+    coordinator: &str,
+    worker_id: String,
+    poll: Duration,
+) -> anyhow::Result<()> {
+    let client = CoordinatorClient {
+        http: reqwest::Client::new(),
+        base: format!("{}/api/v1/workers", coordinator.trim_end_matches('/')),
+        worker_id,
+    };
+    let mut runner = ChunkRunner::new(Arc::new(BinanceClient::new()));
+    info!(coordinator, worker = %client.worker_id, "Worker started");
+
+    loop {
+        let chunk = match client.lease().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => {
+                tokio::time::sleep(poll).await;
+                continue;
+            }
+            Err(e) => {
+                warn!(error = %format!("{:#}", e), "Coordinator unreachable");
+                tokio::time::sleep(poll).await;
+                continue;
+            }
+        };
+        info!(
+            chunk = chunk.id,
+            run_id = %chunk.run_id,
+            symbol = %chunk.symbol,
+            strategies = chunk.strategies.len(),
+            "Running chunk"
+        );
+
+        let reply = match runner.run(&chunk).await {
+            Ok(results) => {
+                let body = serde_json::json!({
+                    "worker_id": client.worker_id,
+                    "results": results,
+                });
+                let path = format!("/chunks/{}/results", chunk.id);
+                client
+                    .post::<_, ChunkAccepted>(&path, &body)
+                    .await
+                    .map(|r| {
+                        if !r.accepted {
+                            info!(
+                                chunk = chunk.id,
+                                "Chunk already done elsewhere, results dropped"
+                            );
+                        }
+                    })
+            }
+            Err(e) => {
+                warn!(chunk = chunk.id, error = %e, "Chunk failed");
+                let body = serde_json::json!({
+                    "worker_id": client.worker_id,
+                    "error": e.to_string(),
+                });
+                let path = format!("/chunks/{}/fail", chunk.id);
+                client
+                    .post::<_, serde_json::Value>(&path, &body)
+                    .await
+                    .map(|_| ())
+            }
+        };
+        // The lease expires on its own if the coordinator missed the reply
+        if let Err(e) = reply {
+            warn!(chunk = chunk.id, error = %format!("{:#}", e), "Failed to report chunk");
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! `X-Workspace` request header — which researcher's knowledge base a request sees
+//!
+//! Several people can share one server: every record belongs to a workspace, and the
+//! knowledge, run, export, pipeline, risk and maintenance endpoints only read or
+//! modify the records of the request's workspace. Requests without the header use the
+//! `default` workspace, which also holds every record written before workspaces existed.
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use persistence::repository::DEFAULT_WORKSPACE;
+use utoipa::IntoParams;
+
+use crate::dto::ApiError;
+
+pub const WORKSPACE_HEADER: &str = "x-workspace";
+
+const MAX_WORKSPACE_LEN: usize = 64;
+
+/// Workspace of the request (`default` when the header is absent)
+#[derive(Debug, Clone, PartialEq, Eq, IntoParams)]
+#[into_params(names("X-Workspace"), parameter_in = Header)]
+pub struct Workspace(
+    /// Letters, digits, `-` or `_`, at most 64 characters (default: `default`)
+    String,
+);
+
+impl Workspace {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Validate a workspace name (from the header or the CLI)
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let valid_chars = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if name.is_empty() || name.len() > MAX_WORKSPACE_LEN || !valid_chars {
+            return Err(format!(
+                "Invalid workspace {:?}: use 1-{} letters, digits, '-' or '_'",
+                name, MAX_WORKSPACE_LEN
+            ));
+        }
+        Ok(Self(name.to_string()))
+    }
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self(DEFAULT_WORKSPACE.to_string())
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Workspace {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        match parts.headers.get(WORKSPACE_HEADER) {
+            None => Ok(Self::default()),
+            Some(value) => {
+                let name = value
+                    .to_str()
+                    .map_err(|_| ApiError::invalid_request("X-Workspace must be ASCII"))?;
+                Self::parse(name.trim()).map_err(ApiError::invalid_request)
+            }
+        }
+    }
+}
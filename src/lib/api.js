@@ -25,7 +25,9 @@ async function apiCall(endpoint, options = {}) {
     ...options,
   });
   if (!response.ok) {
-    throw new Error(`API error: ${response.status}`);
+    // Error bodies carry a message (`{ success: false, error, message }` on /api/...)
+    const body = await response.json().catch(() => null);
+    throw new Error(body?.message || `API error: ${response.status}`);
   }
   return response.json();
 }
@@ -37,9 +39,9 @@ async function apiCall(endpoint, options = {}) {
 export async function checkHealth() {
   try {
     const result = await apiCall('/api/health');
-    return { connected: true, version: result.version };
+    return { connected: true, version: result.version, readOnly: result.read_only || false };
   } catch (_e) {
-    return { connected: false, version: null };
+    return { connected: false, version: null, readOnly: false };
   }
 }
 
@@ -83,6 +85,8 @@ export async function getKnowledgeBase(params = {}) {
     const query = new URLSearchParams();
     if (params.limit) query.set('limit', params.limit);
     if (params.offset) query.set('offset', params.offset);
+    // Keyset paging: '' for the first page, then the previous response's next_cursor
+    if (params.cursor !== undefined && params.cursor !== null) query.set('cursor', params.cursor);
     if (params.strategy_type) query.set('strategy_type', params.strategy_type);
     if (params.symbol) query.set('symbol', params.symbol);
     if (params.min_win_rate) query.set('min_win_rate', params.min_win_rate);
@@ -122,6 +126,17 @@ export async function exportResults(params = {}) {
   }
 }
 
+// Streaming NDJSON export — returns a URL (for a download link) rather than parsed data
+export function knowledgeNdjsonUrl(params = {}) {
+  const query = new URLSearchParams();
+  if (params.strategy_type) query.set('strategy_type', params.strategy_type);
+  if (params.symbol) query.set('symbol', params.symbol);
+  if (params.min_win_rate) query.set('min_win_rate', params.min_win_rate);
+  if (params.sort_by) query.set('sort_by', params.sort_by);
+  const qs = query.toString();
+  return `${getApiBase()}/api/export/ndjson${qs ? '?' + qs : ''}`;
+}
+
 // ============================================================================
 // Optimizer
 // ============================================================================
@@ -228,6 +243,37 @@ export async function getWatcherStatus() {
   }
 }
 
+// ============================================================================
+// Gabagool Scanner
+// ============================================================================
+
+export async function startGabagoolScanner(config = {}) {
+  try {
+    return await apiCall('/api/gabagool/scanner/start', {
+      method: 'POST',
+      body: JSON.stringify(config),
+    });
+  } catch (e) {
+    return { success: false, message: String(e) };
+  }
+}
+
+export async function stopGabagoolScanner() {
+  try {
+    return await apiCall('/api/gabagool/scanner/stop', { method: 'POST' });
+  } catch (e) {
+    return { success: false, message: String(e) };
+  }
+}
+
+export async function getGabagoolOpportunities() {
+  try {
+    return await apiCall('/api/gabagool/opportunities');
+  } catch (e) {
+    return { status: 'Error', opportunities: [], events: [] };
+  }
+}
+
 // ============================================================================
 // Profile Analysis
 // ============================================================================
@@ -346,3 +392,35 @@ export async function obCleanup(mode = 'partial') {
     return { success: false, message: String(e) };
   }
 }
+
+export async function runMaintenance(keep = 3) {
+  try {
+    return await apiCall(`/api/admin/maintenance?keep=${keep}`, { method: 'POST' });
+  } catch (e) {
+    return { success: false, message: String(e) };
+  }
+}
+
+export async function getMaintenanceStatus() {
+  try {
+    return await apiCall('/api/admin/maintenance/status');
+  } catch (e) {
+    return { status: 'Error', running: false };
+  }
+}
+
+export async function createBackup() {
+  try {
+    return await apiCall('/api/admin/backup', { method: 'POST' });
+  } catch (e) {
+    return { success: false, message: String(e) };
+  }
+}
+
+export async function getCacheStats() {
+  try {
+    return await apiCall('/api/admin/cache');
+  } catch (e) {
+    return { success: false, cache: null, error: String(e) };
+  }
+}
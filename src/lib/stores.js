@@ -4,7 +4,7 @@ import { writable } from 'svelte/store';
 export const currentPage = writable('discovery');
 
 // Health status
-export const serverHealth = writable({ connected: false, version: null });
+export const serverHealth = writable({ connected: false, version: null, readOnly: false });
 
 // Discovery status — persists across page changes
 export const discoveryStatus = writable({
@@ -15,6 +15,9 @@ export const discoveryStatus = writable({
   completed: 0,
   total: 0,
   skipped: 0,
+  throughput_per_sec: 0,
+  eta_secs: null,
+  breakdown: { by_symbol: [], by_family: [] },
   current_cycle: 0,
   total_tested_all_cycles: 0,
   total_new_this_cycle: 0,